@@ -0,0 +1,684 @@
+//! Tolkning av tekst fra adresselinjen
+//!
+//! Avgjør om det brukeren skrev er en adresse, et bokmerke-nøkkelord eller
+//! søkeord, og bygger adressen som skal åpnes.
+
+use crate::bookmarks::percent_encode;
+use crate::settings::DefaultScheme;
+use serde::{Deserialize, Serialize};
+
+/// Standard søkemotor; `%s` erstattes med søkeordene
+pub const DEFAULT_SEARCH_ENGINE: &str = "https://lite.duckduckgo.com/lite/?q=%s";
+
+/// Protokoller som gjenkjennes foran `:` i adresselinjen
+const KNOWN_SCHEMES: [&str; 6] = ["http", "https", "gemini", "gopher", "file", "bare"];
+
+/// Kjente Gemini-verter; de og underdomenene deres åpnes alltid med gemini://
+const GEMINI_HOSTS: [&str; 4] = [
+    "geminiprotocol.net",
+    "circumlunar.space",
+    "flounder.online",
+    "smol.pub",
+];
+
+/// Standardporten for Gemini
+const GEMINI_PORT: &str = "1965";
+
+/// Standardporten for Gopher
+const GOPHER_PORT: &str = "70";
+
+/// Hva adresselinjen skal gjøre med teksten
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AddressAction {
+    /// Åpne en adresse eller lokal fil
+    Navigate,
+    /// Åpne adressen fra et bokmerke-nøkkelord
+    Keyword,
+    /// Søk med søkemotoren
+    Search,
+    /// Spør brukeren om adressen skal åpnes med https:// eller gemini://
+    Ask,
+}
+
+/// En endring som ble gjort på teksten før den ble til en adresse
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Normalization {
+    /// Mellomrom foran eller bak ble fjernet
+    Trimmed,
+    /// Komma i vertsnavnet ble rettet til punktum ("www,example,com")
+    FixedCommas,
+    /// Vertsnavnet ble gjort om til små bokstaver; stien beholdes som den er
+    LowercasedHost,
+    /// Port 1965 ga gemini://
+    GeminiFromPort,
+    /// Port 70 ga gopher://
+    GopherFromPort,
+    /// Protokollen ble lagt til etter innstillingene
+    AddedScheme,
+}
+
+impl Normalization {
+    /// Om endringen er så stor at brukeren bør spørres "Mente du …"
+    ///
+    /// De andre endringene gir samme side som brukeren skrev.
+    pub fn is_significant(self) -> bool {
+        matches!(self, Self::FixedCommas)
+    }
+}
+
+/// Resultatet av å tolke teksten fra adresselinjen
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressResolution {
+    pub action: AddressAction,
+    /// Adressen eller filstien som skal åpnes
+    pub url: String,
+    /// Adressene brukeren kan velge mellom når handlingen er `Ask`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub candidates: Vec<String>,
+    /// Endringene som ble gjort på teksten, i rekkefølge
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub normalizations: Vec<Normalization>,
+    /// Om frontend bør vise "Mente du …" for adressen
+    #[serde(default)]
+    pub did_you_mean: bool,
+}
+
+impl AddressResolution {
+    fn new(action: AddressAction, url: String) -> Self {
+        Self {
+            action,
+            url,
+            candidates: Vec::new(),
+            normalizations: Vec::new(),
+            did_you_mean: false,
+        }
+    }
+}
+
+/// Sjekk om en søkemotor-mal er gyldig (http(s)-adresse med `%s`)
+pub fn is_valid_search_engine(template: &str) -> bool {
+    template.contains("%s")
+        && url::Url::parse(&template.replace("%s", "test"))
+            .is_ok_and(|url| url.scheme() == "http" || url.scheme() == "https")
+}
+
+/// Bygg søkeadressen for søkeordene
+///
+/// # Arguments
+/// * `template` - Søkemotor-mal med `%s`
+/// * `terms` - Søkeordene, prosentkodes før de settes inn
+pub fn search_url(template: &str, terms: &str) -> String {
+    template.replace("%s", &percent_encode(terms.trim()))
+}
+
+/// Tolk teksten fra adresselinjen
+///
+/// Et nøkkelord fra bokmerkene går foran alt annet. Ellers gjelder, i
+/// rekkefølge: lokale stier åpnes, tekst med mellomrom søkes etter, tekst
+/// med kjent protokoll åpnes som den er, vertsnavn med komma i stedet for
+/// punktum rettes, tekst med punktum eller `localhost` åpnes, og resten
+/// søkes etter. Adresser uten protokoll normaliseres av
+/// `with_default_scheme`. Alle endringer rapporteres i `normalizations`.
+///
+/// # Arguments
+/// * `input` - Teksten brukeren skrev
+/// * `keyword_url` - Adressen fra et bokmerke-nøkkelord, hvis teksten er det
+/// * `search_engine` - Søkemotor-mal med `%s`
+/// * `scheme_for` - Protokollen som gjelder for et vertsnavn (med små bokstaver)
+pub fn resolve(
+    input: &str,
+    keyword_url: Option<String>,
+    search_engine: &str,
+    scheme_for: impl Fn(&str) -> DefaultScheme,
+) -> AddressResolution {
+    let trimmed = input.trim();
+    let mut resolution = match keyword_url {
+        Some(url) => AddressResolution::new(AddressAction::Keyword, url),
+        None => classify(trimmed, search_engine, scheme_for),
+    };
+    if trimmed != input {
+        resolution.normalizations.insert(0, Normalization::Trimmed);
+    }
+    resolution.did_you_mean = resolution.normalizations.iter().any(|n| n.is_significant());
+    resolution
+}
+
+/// Tolk teksten uten mellomrom foran og bak, når den ikke er et nøkkelord
+fn classify(
+    input: &str,
+    search_engine: &str,
+    scheme_for: impl Fn(&str) -> DefaultScheme,
+) -> AddressResolution {
+    let navigate = |url: String| AddressResolution::new(AddressAction::Navigate, url);
+    if is_local_path(input) {
+        return navigate(input.to_string());
+    }
+    if input.contains(char::is_whitespace) {
+        return search(input, search_engine);
+    }
+    if has_known_scheme(input) {
+        return navigate(input.to_string());
+    }
+    if let Some(fixed) = fix_commas(input) {
+        let mut resolution = with_default_scheme(&fixed, scheme_for);
+        resolution
+            .normalizations
+            .insert(0, Normalization::FixedCommas);
+        return resolution;
+    }
+    if input.contains('.') || is_localhost(input) {
+        return with_default_scheme(input, scheme_for);
+    }
+    search(input, search_engine)
+}
+
+/// Gi en adresse uten protokoll https://, gemini:// eller gopher://
+///
+/// Vertsnavnet gjøres om til små bokstaver, mens stien beholdes. Port 1965
+/// gir gemini:// og port 70 gir gopher://. Kjente Gemini-verter gir alltid
+/// gemini://. Ellers gjelder protokollen for verten, og `Ask` gir begge
+/// adressene å velge mellom.
+fn with_default_scheme(
+    input: &str,
+    scheme_for: impl Fn(&str) -> DefaultScheme,
+) -> AddressResolution {
+    let mut normalizations = Vec::new();
+    let (host, port) = host_and_port(input);
+    let lower = host.to_lowercase();
+    let address = if lower == host {
+        input.to_string()
+    } else {
+        normalizations.push(Normalization::LowercasedHost);
+        format!("{}{}", lower, &input[host.len()..])
+    };
+
+    let navigate = |scheme: &str, normalization: Normalization, mut normalizations: Vec<_>| {
+        normalizations.push(normalization);
+        AddressResolution {
+            normalizations,
+            ..AddressResolution::new(AddressAction::Navigate, format!("{}://{}", scheme, address))
+        }
+    };
+    if port == GEMINI_PORT {
+        return navigate("gemini", Normalization::GeminiFromPort, normalizations);
+    }
+    if port == GOPHER_PORT {
+        return navigate("gopher", Normalization::GopherFromPort, normalizations);
+    }
+
+    let scheme = if is_gemini_host(&lower) {
+        DefaultScheme::Gemini
+    } else {
+        scheme_for(&lower)
+    };
+    match scheme {
+        DefaultScheme::Https => navigate("https", Normalization::AddedScheme, normalizations),
+        DefaultScheme::Gemini => navigate("gemini", Normalization::AddedScheme, normalizations),
+        DefaultScheme::Ask => {
+            let https = format!("https://{}", address);
+            let gemini = format!("gemini://{}", address);
+            normalizations.push(Normalization::AddedScheme);
+            AddressResolution {
+                action: AddressAction::Ask,
+                url: https.clone(),
+                candidates: vec![https, gemini],
+                normalizations,
+                did_you_mean: false,
+            }
+        }
+    }
+}
+
+/// Rett komma til punktum i vertsnavnet ("www,example,com")
+///
+/// Bare når resultatet ser ut som et vertsnavn: minst to deler med
+/// bokstaver, tall og bindestrek, og et toppdomene på minst to bokstaver.
+/// Stien beholdes som den er.
+///
+/// # Returns
+/// Teksten med rettet vertsnavn, eller None hvis det ikke er en skrivefeil
+fn fix_commas(input: &str) -> Option<String> {
+    let (host, port) = host_and_port(input);
+    if !host.contains(',') || !port.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let fixed = host.replace(',', ".");
+    let labels: Vec<&str> = fixed.split('.').collect();
+    let is_host = labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+        && labels
+            .last()
+            .is_some_and(|tld| tld.len() >= 2 && tld.chars().all(|c| c.is_ascii_alphabetic()));
+    is_host.then(|| format!("{}{}", fixed, &input[host.len()..]))
+}
+
+/// Søk etter hele teksten med søkemotoren
+fn search(input: &str, search_engine: &str) -> AddressResolution {
+    AddressResolution::new(AddressAction::Search, search_url(search_engine, input))
+}
+
+/// Vertsnavn og port (tom hvis den mangler) fra en adresse uten protokoll
+fn host_and_port(input: &str) -> (&str, &str) {
+    let authority = input.split(['/', '?', '#']).next().unwrap_or(input);
+    authority.split_once(':').unwrap_or((authority, ""))
+}
+
+/// Verten er en kjent Gemini-vert eller et underdomene av en
+fn is_gemini_host(host: &str) -> bool {
+    let host = host.trim_end_matches('.');
+    GEMINI_HOSTS.iter().any(|known| {
+        host == *known
+            || host
+                .strip_suffix(known)
+                .is_some_and(|prefix| prefix.ends_with('.'))
+    })
+}
+
+/// Absolutt filsti (Unix eller Windows)
+pub fn is_local_path(input: &str) -> bool {
+    let bytes = input.as_bytes();
+    input.starts_with('/')
+        || (bytes.len() >= 3
+            && bytes[0].is_ascii_alphabetic()
+            && bytes[1] == b':'
+            && (bytes[2] == b'\\' || bytes[2] == b'/'))
+}
+
+/// Starter med en protokoll Bare kjenner, f.eks. `gemini://` eller `bare:`
+fn has_known_scheme(input: &str) -> bool {
+    input
+        .split_once(':')
+        .is_some_and(|(scheme, _)| KNOWN_SCHEMES.contains(&scheme.to_lowercase().as_str()))
+}
+
+/// `localhost`, eventuelt med port og sti
+fn is_localhost(input: &str) -> bool {
+    let (name, port) = host_and_port(input);
+    name.eq_ignore_ascii_case("localhost") && port.chars().all(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(input: &str) -> (AddressAction, String) {
+        let resolved = resolve(input, None, DEFAULT_SEARCH_ENGINE, |_| DefaultScheme::Https);
+        (resolved.action, resolved.url)
+    }
+
+    fn navigates_to(input: &str, url: &str) {
+        assert_eq!(
+            classify(input),
+            (AddressAction::Navigate, url.to_string()),
+            "{}",
+            input
+        );
+    }
+
+    fn searches_for(input: &str, encoded: &str) {
+        assert_eq!(
+            classify(input),
+            (
+                AddressAction::Search,
+                format!("https://lite.duckduckgo.com/lite/?q={}", encoded)
+            ),
+            "{}",
+            input
+        );
+    }
+
+    #[test]
+    fn test_urls_navigate() {
+        navigates_to("example.com", "https://example.com");
+        navigates_to("  example.com/sti?q=1  ", "https://example.com/sti?q=1");
+        navigates_to("https://example.com", "https://example.com");
+        navigates_to("HTTP://example.com", "HTTP://example.com");
+        navigates_to(
+            "gemini://geminiprotocol.net/",
+            "gemini://geminiprotocol.net/",
+        );
+        navigates_to(
+            "gopher://gopher.floodgap.com",
+            "gopher://gopher.floodgap.com",
+        );
+        navigates_to("bare://bookmarks", "bare://bookmarks");
+        navigates_to("192.168.1.1:8080", "https://192.168.1.1:8080");
+        navigates_to("README.md", "https://readme.md");
+    }
+
+    #[test]
+    fn test_localhost_navigates() {
+        navigates_to("localhost", "https://localhost");
+        navigates_to("localhost:8080", "https://localhost:8080");
+        navigates_to("LOCALHOST:3000/docs", "https://localhost:3000/docs");
+        // Ikke en port
+        searches_for("localhost:abc", "localhost%3Aabc");
+    }
+
+    #[test]
+    fn test_local_paths_navigate() {
+        navigates_to("/home/bruker/notater.md", "/home/bruker/notater.md");
+        navigates_to(
+            "/home/bruker/Mine notater.md",
+            "/home/bruker/Mine notater.md",
+        );
+        navigates_to("C:\\Users\\notat.md", "C:\\Users\\notat.md");
+        navigates_to("file:///tmp/a.md", "file:///tmp/a.md");
+    }
+
+    #[test]
+    fn test_words_search() {
+        searches_for("rust lang", "rust%20lang");
+        searches_for("rust", "rust");
+        searches_for("example.com is down", "example.com%20is%20down");
+        searches_for(
+            "https://example.com og mer",
+            "https%3A%2F%2Fexample.com%20og%20mer",
+        );
+        searches_for("c++", "c%2B%2B");
+        searches_for("hva er 2+2?", "hva%20er%202%2B2%3F");
+        searches_for("blåbær", "bl%C3%A5b%C3%A6r");
+        // Ukjent protokoll uten punktum
+        searches_for("foo:bar", "foo%3Abar");
+    }
+
+    #[test]
+    fn test_keyword_wins() {
+        let resolved = resolve(
+            "rs serde",
+            Some("https://docs.rs/serde".to_string()),
+            DEFAULT_SEARCH_ENGINE,
+            |_| DefaultScheme::Gemini,
+        );
+        assert_eq!(resolved.action, AddressAction::Keyword);
+        assert_eq!(resolved.url, "https://docs.rs/serde");
+    }
+
+    #[test]
+    fn test_custom_search_engine() {
+        let resolved = resolve(
+            "rust lang",
+            None,
+            "https://search.example/?s=%s&lang=no",
+            |_| DefaultScheme::Https,
+        );
+        assert_eq!(
+            resolved.url,
+            "https://search.example/?s=rust%20lang&lang=no"
+        );
+    }
+
+    fn with_scheme(input: &str, scheme: DefaultScheme) -> AddressResolution {
+        resolve(input, None, DEFAULT_SEARCH_ENGINE, |_| scheme)
+    }
+
+    #[test]
+    fn test_default_scheme_matrix() {
+        use DefaultScheme::*;
+        let cases = [
+            // (tekst, innstilling, handling, adresse)
+            (
+                "example.com",
+                Https,
+                AddressAction::Navigate,
+                "https://example.com",
+            ),
+            (
+                "example.com",
+                Gemini,
+                AddressAction::Navigate,
+                "gemini://example.com",
+            ),
+            (
+                "example.com/a",
+                Ask,
+                AddressAction::Ask,
+                "https://example.com/a",
+            ),
+            (
+                "localhost:8080",
+                Gemini,
+                AddressAction::Navigate,
+                "gemini://localhost:8080",
+            ),
+            // Port 1965 og kjente Gemini-verter går foran innstillingen
+            (
+                "example.com:1965",
+                Https,
+                AddressAction::Navigate,
+                "gemini://example.com:1965",
+            ),
+            (
+                "example.com:1965/",
+                Ask,
+                AddressAction::Navigate,
+                "gemini://example.com:1965/",
+            ),
+            (
+                "geminiprotocol.net",
+                Https,
+                AddressAction::Navigate,
+                "gemini://geminiprotocol.net",
+            ),
+            (
+                "Station.Flounder.Online/x",
+                Ask,
+                AddressAction::Navigate,
+                "gemini://station.flounder.online/x",
+            ),
+            // Ligner bare på en Gemini-vert
+            (
+                "notsmol.pub",
+                Https,
+                AddressAction::Navigate,
+                "https://notsmol.pub",
+            ),
+            // Protokoll som er skrevet inn, brukes som den er
+            (
+                "https://geminiprotocol.net",
+                Gemini,
+                AddressAction::Navigate,
+                "https://geminiprotocol.net",
+            ),
+            (
+                "http://example.com:1965",
+                Ask,
+                AddressAction::Navigate,
+                "http://example.com:1965",
+            ),
+        ];
+        for (input, scheme, action, url) in cases {
+            let resolved = with_scheme(input, scheme);
+            assert_eq!(
+                (resolved.action, resolved.url.as_str()),
+                (action, url),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_ask_returns_both_candidates() {
+        let resolved = with_scheme("example.com/sti", DefaultScheme::Ask);
+        assert_eq!(
+            resolved.candidates,
+            vec!["https://example.com/sti", "gemini://example.com/sti"]
+        );
+        // Andre handlinger har ingen kandidater
+        assert!(with_scheme("example.com", DefaultScheme::Https)
+            .candidates
+            .is_empty());
+        assert!(with_scheme("rust lang", DefaultScheme::Ask)
+            .candidates
+            .is_empty());
+
+        let json = serde_json::to_value(&resolved).unwrap();
+        assert_eq!(json["action"], "ask");
+        assert!(
+            serde_json::to_value(with_scheme("a.b", DefaultScheme::Https))
+                .unwrap()
+                .get("candidates")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_scheme_is_chosen_per_host() {
+        let scheme_for = |host: &str| {
+            if host == "kapsel.example" {
+                DefaultScheme::Gemini
+            } else {
+                DefaultScheme::Ask
+            }
+        };
+        let resolved = resolve(
+            "Kapsel.Example/logg",
+            None,
+            DEFAULT_SEARCH_ENGINE,
+            scheme_for,
+        );
+        assert_eq!(resolved.url, "gemini://kapsel.example/logg");
+        let resolved = resolve("annen.example", None, DEFAULT_SEARCH_ENGINE, scheme_for);
+        assert_eq!(resolved.action, AddressAction::Ask);
+    }
+
+    #[test]
+    fn test_normalization_table() {
+        use Normalization::*;
+        let cases: [(&str, &str, &[Normalization]); 18] = [
+            ("example.com", "https://example.com", &[AddedScheme]),
+            (
+                "EXAMPLE.COM/Path",
+                "https://example.com/Path",
+                &[LowercasedHost, AddedScheme],
+            ),
+            (
+                "Example.com/Sti?Q=A#Del",
+                "https://example.com/Sti?Q=A#Del",
+                &[LowercasedHost, AddedScheme],
+            ),
+            (
+                " gemini.circumlunar.space ",
+                "gemini://gemini.circumlunar.space",
+                &[Trimmed, AddedScheme],
+            ),
+            (
+                "example.com:1965",
+                "gemini://example.com:1965",
+                &[GeminiFromPort],
+            ),
+            (
+                "Kapsel.Example:1965/Logg",
+                "gemini://kapsel.example:1965/Logg",
+                &[LowercasedHost, GeminiFromPort],
+            ),
+            (
+                "gopher.floodgap.com:70",
+                "gopher://gopher.floodgap.com:70",
+                &[GopherFromPort],
+            ),
+            (
+                "example.com:70/1/Meny",
+                "gopher://example.com:70/1/Meny",
+                &[GopherFromPort],
+            ),
+            // Port 700 er ikke port 70
+            ("example.com:700", "https://example.com:700", &[AddedScheme]),
+            (
+                "www,example,com",
+                "https://www.example.com",
+                &[FixedCommas, AddedScheme],
+            ),
+            (
+                "  WWW,Example,com/Sti,med,komma ",
+                "https://www.example.com/Sti,med,komma",
+                &[Trimmed, FixedCommas, LowercasedHost, AddedScheme],
+            ),
+            (
+                "example,com:1965",
+                "gemini://example.com:1965",
+                &[FixedCommas, GeminiFromPort],
+            ),
+            (
+                "www.example,com",
+                "https://www.example.com",
+                &[FixedCommas, AddedScheme],
+            ),
+            (
+                "LOCALHOST",
+                "https://localhost",
+                &[LowercasedHost, AddedScheme],
+            ),
+            // Protokoll som er skrevet inn, brukes som den er
+            ("https://EXAMPLE.com/A", "https://EXAMPLE.com/A", &[]),
+            (
+                "  gopher://example.com:70  ",
+                "gopher://example.com:70",
+                &[Trimmed],
+            ),
+            ("/home/bruker/Notat.md", "/home/bruker/Notat.md", &[]),
+            ("bare://bookmarks", "bare://bookmarks", &[]),
+        ];
+
+        for (input, url, normalizations) in cases {
+            let resolved = with_scheme(input, DefaultScheme::Https);
+            assert_eq!(resolved.action, AddressAction::Navigate, "{:?}", input);
+            assert_eq!(
+                (resolved.url.as_str(), resolved.normalizations.as_slice()),
+                (url, normalizations),
+                "{:?}",
+                input
+            );
+            assert_eq!(
+                resolved.did_you_mean,
+                normalizations.contains(&FixedCommas),
+                "{:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_commas_that_are_not_typos_are_searched() {
+        searches_for("1,5", "1%2C5");
+        searches_for("ja,nei,kanskje,1", "ja%2Cnei%2Ckanskje%2C1");
+        searches_for(",example,com", "%2Cexample%2Ccom");
+        searches_for("a,,com", "a%2C%2Ccom");
+        searches_for("epler,pærer", "epler%2Cp%C3%A6rer");
+    }
+
+    #[test]
+    fn test_normalizations_are_serialized() {
+        let json =
+            serde_json::to_value(with_scheme("www,example,com", DefaultScheme::Ask)).unwrap();
+        assert_eq!(json["action"], "ask");
+        assert_eq!(
+            json["normalizations"],
+            serde_json::json!(["fixed-commas", "added-scheme"])
+        );
+        assert_eq!(json["did_you_mean"], true);
+        assert_eq!(
+            json["candidates"],
+            serde_json::json!(["https://www.example.com", "gemini://www.example.com"])
+        );
+
+        let json = serde_json::to_value(with_scheme("rust lang", DefaultScheme::Https)).unwrap();
+        assert!(json.get("normalizations").is_none());
+        assert_eq!(json["did_you_mean"], false);
+    }
+
+    #[test]
+    fn test_search_engine_validation() {
+        assert!(is_valid_search_engine(DEFAULT_SEARCH_ENGINE));
+        assert!(is_valid_search_engine("http://localhost:8888/search?q=%s"));
+        assert!(!is_valid_search_engine("https://example.com/search"));
+        assert!(!is_valid_search_engine("gemini://example.com/?%s"));
+        assert!(!is_valid_search_engine("%s"));
+    }
+}
@@ -0,0 +1,340 @@
+//! Tilstanden til appen
+//!
+//! Innstillinger, bokmerker, historikk og nettverksklientene samles i én
+//! `AppState` som registreres med `.manage()` og gis til commands som
+//! `tauri::State`. Alle filer den leser og skriver ligger under
+//! `ConfigPaths`, så flere tilstander kan leve side om side, f.eks. én per
+//! profil eller én per test i en midlertidig mappe.
+
+use crate::autocomplete::AutocompleteIndex;
+use crate::bookmarks::BookmarkStore;
+use crate::broadcast::Debouncer;
+use crate::commands::SettingsInfo;
+use crate::downloads::Downloads;
+use crate::fetcher::Fetcher;
+use crate::gemini::GeminiClient;
+use crate::gopher::GopherClient;
+use crate::history::History;
+use crate::prefetch::{self, PrefetchBudget, PrefetchCache, Prefetched};
+use crate::private_mode::PrivateMode;
+use crate::recent_files::RecentFiles;
+use crate::render_cache::RenderCache;
+use crate::settings::{NetworkSettings, PrivacySettings, Settings};
+use crate::snapshots::RecentPages;
+use crate::watcher::Watcher;
+use log::warn;
+use parking_lot::{Mutex, RwLock};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Hvor lenge zoom-endringer samles før de sendes til vinduene
+const ZOOM_BROADCAST_DELAY: Duration = Duration::from_millis(250);
+
+/// Filene til én profil
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigPaths {
+    dir: PathBuf,
+}
+
+impl ConfigPaths {
+    /// Filene i en bestemt mappe
+    pub fn in_dir(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Filene i `bare` under brukerens config-mappe
+    pub fn from_config_dir() -> Self {
+        let config_dir = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
+        Self::in_dir(config_dir.join("bare"))
+    }
+
+    /// Hent stien til innstillings-filen
+    pub fn settings(&self) -> PathBuf {
+        self.dir.join("settings.json")
+    }
+
+    /// Hent stien til brukerens CSS-fil
+    pub fn user_css(&self) -> PathBuf {
+        self.dir.join("user.css")
+    }
+
+    /// Hent stien til bokmerke-filen
+    pub fn bookmarks(&self) -> PathBuf {
+        self.dir.join("bookmarks.json")
+    }
+
+    /// Hent stien til historikkfilen
+    pub fn history(&self) -> PathBuf {
+        self.dir.join("history.jsonl")
+    }
+
+    /// Hent stien til listen over nylige filer
+    pub fn recent_files(&self) -> PathBuf {
+        self.dir.join("recent_files.json")
+    }
+
+    /// Hent mappen arkiverte kopier lagres i
+    pub fn snapshots(&self) -> PathBuf {
+        self.dir.join("snapshots")
+    }
+
+    /// Hent stien til loggfilen
+    pub fn log_file(&self) -> PathBuf {
+        self.dir.join("logs").join("bare.log")
+    }
+
+    /// Hent stien til TOFU-lagringsfilen
+    pub fn known_hosts(&self) -> PathBuf {
+        self.dir.join("known_hosts.json")
+    }
+
+    /// Hent stien til Gemini-identitetene (klientsertifikater)
+    pub fn gemini_identities(&self) -> PathBuf {
+        self.dir.join("gemini_identities.json")
+    }
+}
+
+/// Det som bare gjelder mens appen kjører og aldri skrives til disk
+pub struct Session {
+    /// Nedlastinger i denne økten
+    pub downloads: Mutex<Downloads>,
+    /// Markdown-kilden til de sist viste sidene (for arkiverte kopier)
+    pub recent_pages: Mutex<RecentPages>,
+    /// Sider som er forhåndshentet og venter på at brukeren klikker
+    pub prefetched: Mutex<PrefetchCache<Prefetched>>,
+    /// Grensen for samtidige forhåndshentinger
+    pub prefetch_budget: PrefetchBudget,
+    /// Indeksen for forslag i adresselinjen, bygges ved første bruk
+    ///
+    /// Settes til None når bokmerkene eller historikken endres. Låses alltid
+    /// sist, etter bokmerkene og historikken.
+    pub autocomplete: Mutex<Option<AutocompleteIndex>>,
+    /// Overvåking av den lokale filen hvert vindu viser, med etiketten som nøkkel
+    pub file_watchers: Mutex<HashMap<String, Watcher>>,
+    /// Overvåking av bokmerke- og innstillingsfilen
+    pub store_watchers: Mutex<Vec<Watcher>>,
+    /// Samler raske zoom-endringer slik at bare sluttresultatet sendes
+    pub zoom_broadcast: Arc<Debouncer<SettingsInfo>>,
+    /// Verter brukeren har valgt å åpne tross advarsler om adressen
+    pub trusted_hosts: Mutex<HashSet<String>>,
+    /// Rendret markdown, delt med renderingen gjennom `RenderOptions`
+    pub render_cache: Arc<Mutex<RenderCache>>,
+    /// Privat surfing; se `AppState::set_private`
+    pub private_mode: PrivateMode,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self {
+            downloads: Mutex::default(),
+            recent_pages: Mutex::default(),
+            prefetched: Mutex::default(),
+            prefetch_budget: PrefetchBudget::new(prefetch::MAX_CONCURRENT),
+            autocomplete: Mutex::default(),
+            file_watchers: Mutex::default(),
+            store_watchers: Mutex::default(),
+            zoom_broadcast: Arc::new(Debouncer::new(ZOOM_BROADCAST_DELAY)),
+            trusted_hosts: Mutex::default(),
+            render_cache: Arc::default(),
+            private_mode: PrivateMode::default(),
+        }
+    }
+}
+
+/// Tilstanden til appen, registrert med `.manage()`
+pub struct AppState {
+    pub paths: ConfigPaths,
+    /// Leses av hver henting, så lesere venter ikke på hverandre
+    pub settings: RwLock<Settings>,
+    pub bookmarks: Mutex<BookmarkStore>,
+    pub history: Mutex<History>,
+    pub recent_files: Mutex<RecentFiles>,
+    /// Klientene bygges på nytt når nettverksinnstillingene endres;
+    /// forespørsler som er i gang beholder sin `Arc`
+    fetcher: Mutex<Arc<Fetcher>>,
+    gemini: Mutex<Arc<GeminiClient>>,
+    gopher: Mutex<Arc<GopherClient>>,
+    pub session: Session,
+}
+
+impl AppState {
+    /// Les inn lagrene fra filene i `paths`
+    ///
+    /// Filer som mangler eller ikke kan leses gir tomme lagre, slik at appen
+    /// alltid starter.
+    pub fn load(paths: ConfigPaths) -> Self {
+        let settings = Settings::load(&paths.settings()).unwrap_or_default();
+        let bookmarks = BookmarkStore::load(&paths.bookmarks()).unwrap_or_default();
+        let history = History::load(&paths.history()).unwrap_or_else(|e| {
+            warn!("{}", e);
+            History::empty(&paths.history())
+        });
+        let recent_files = RecentFiles::load(&paths.recent_files());
+
+        let state = Self {
+            fetcher: Mutex::new(Arc::new(Fetcher::with_settings(
+                &settings.network,
+                &settings.privacy,
+            ))),
+            gemini: Mutex::new(Arc::new(
+                GeminiClient::with_tofu_path(&settings.network, paths.known_hosts())
+                    .with_identities(paths.gemini_identities()),
+            )),
+            gopher: Mutex::new(Arc::new(GopherClient::with_limits(&settings.network))),
+            settings: RwLock::new(settings),
+            bookmarks: Mutex::new(bookmarks),
+            history: Mutex::new(history),
+            recent_files: Mutex::new(recent_files),
+            session: Session::default(),
+            paths,
+        };
+        state.set_private(state.session.private_mode.is_active());
+        state
+    }
+
+    /// HTTP-klienten med gjeldende nettverksinnstillinger
+    pub fn fetcher(&self) -> Arc<Fetcher> {
+        self.fetcher.lock().clone()
+    }
+
+    /// Gemini-klienten med gjeldende nettverksinnstillinger
+    pub fn gemini(&self) -> Arc<GeminiClient> {
+        self.gemini.lock().clone()
+    }
+
+    /// Gopher-klienten med gjeldende nettverksinnstillinger
+    pub fn gopher(&self) -> Arc<GopherClient> {
+        self.gopher.lock().clone()
+    }
+
+    /// Bygg nettverksklientene på nytt med nye grenser
+    ///
+    /// Forespørsler som allerede er i gang fullføres med de gamle klientene.
+    pub fn rebuild_network_clients(&self, network: &NetworkSettings, privacy: &PrivacySettings) {
+        let private = self.session.private_mode.is_active();
+        let fetcher = Fetcher::with_settings(network, privacy);
+        fetcher.set_private(private);
+        *self.fetcher.lock() = Arc::new(fetcher);
+        let gemini = GeminiClient::with_tofu_path(network, self.paths.known_hosts())
+            .with_identities(self.paths.gemini_identities());
+        gemini.set_private(private);
+        *self.gemini.lock() = Arc::new(gemini);
+        let gopher = GopherClient::with_limits(network);
+        gopher.set_private(private);
+        *self.gopher.lock() = Arc::new(gopher);
+    }
+
+    /// Slå privat modus av eller på for økten
+    ///
+    /// Historikken og nettverksklientene får beskjed, så ingen av dem skriver
+    /// til disk eller logger adresser mens modusen er på.
+    pub fn set_private(&self, private: bool) {
+        self.session.private_mode.set_active(private);
+        self.history.lock().set_private(private);
+        self.fetcher().set_private(private);
+        self.gemini().set_private(private);
+        self.gopher().set_private(private);
+    }
+
+    /// Bygg forslagsindeksen på nytt ved neste oppslag
+    pub fn invalidate_autocomplete(&self) {
+        *self.session.autocomplete.lock() = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bookmarks::Bookmark;
+    use std::thread;
+
+    #[test]
+    fn test_paths_in_dir() {
+        let paths = ConfigPaths::in_dir("/profil");
+        assert_eq!(paths.settings(), PathBuf::from("/profil/settings.json"));
+        assert_eq!(paths.user_css(), PathBuf::from("/profil/user.css"));
+        assert_eq!(paths.snapshots(), PathBuf::from("/profil/snapshots"));
+        assert_eq!(paths.log_file(), PathBuf::from("/profil/logs/bare.log"));
+        assert!(ConfigPaths::from_config_dir()
+            .bookmarks()
+            .ends_with("bare/bookmarks.json"));
+    }
+
+    #[test]
+    fn test_isolated_states_side_by_side() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        let dirs = [tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap()];
+        let states: Vec<AppState> = dirs
+            .iter()
+            .map(|dir| AppState::load(ConfigPaths::in_dir(dir.path())))
+            .collect();
+
+        // Begge tilstandene endres samtidig fra hver sin tråd
+        thread::scope(|scope| {
+            for (i, state) in states.iter().enumerate() {
+                scope.spawn(move || {
+                    let mut store = state.bookmarks.lock();
+                    store
+                        .add(Bookmark {
+                            id: format!("profil-{}", i),
+                            title: format!("Profil {}", i),
+                            url: format!("https://profil{}.example/", i),
+                            created_at: 0,
+                            updated_at: 0,
+                            folder: None,
+                            tags: Vec::new(),
+                            keyword: None,
+                            snapshot_path: None,
+                            pinned: false,
+                        })
+                        .unwrap();
+                    store.save(&state.paths.bookmarks()).unwrap();
+
+                    let mut settings = state.settings.write();
+                    settings.network.timeout_seconds = 10 + i as u64;
+                    state.rebuild_network_clients(&settings.network, &settings.privacy);
+                    settings.save(&state.paths.settings()).unwrap();
+                });
+            }
+        });
+
+        for (i, dir) in dirs.iter().enumerate() {
+            let reloaded = AppState::load(ConfigPaths::in_dir(dir.path()));
+            let bookmarks = reloaded.bookmarks.lock();
+            assert_eq!(bookmarks.list().len(), 1);
+            assert!(bookmarks.is_bookmarked(&format!("https://profil{}.example/", i)));
+            assert_eq!(
+                reloaded.settings.read().network.timeout_seconds,
+                10 + i as u64
+            );
+        }
+    }
+
+    #[test]
+    fn test_private_mode_is_per_state() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        let dirs = [tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap()];
+        let states: Vec<AppState> = dirs
+            .iter()
+            .map(|dir| AppState::load(ConfigPaths::in_dir(dir.path())))
+            .collect();
+
+        states[0].set_private(true);
+        assert!(states[0].session.private_mode.is_active());
+        assert!(!states[1].session.private_mode.is_active());
+
+        // Klienter som bygges på nytt arver modusen fra økten
+        for state in &states {
+            let settings = Settings::default();
+            state.rebuild_network_clients(&settings.network, &settings.privacy);
+            state
+                .gemini()
+                .pin_certificate("example.org:1965", "abc")
+                .unwrap();
+        }
+        assert!(!states[0].paths.known_hosts().exists());
+        assert!(states[1].paths.known_hosts().exists());
+    }
+}
@@ -0,0 +1,497 @@
+//! Forslag i adresselinjen fra bokmerker og historikk
+//!
+//! Indeksen bygges én gang og holdes i minnet, slik at hvert tastetrykk bare
+//! sammenligner ferdig forberedt tekst. Den bygges på nytt når bokmerkene
+//! eller historikken endres. Bare sammenhengende treff gir forslag; uskarpe
+//! treff hører hjemme i søket, ikke i adresselinjen.
+
+use crate::bookmarks::{self, BookmarkStore};
+use crate::history::History;
+use crate::search::{self, MatchKind, MatchRange, TextMatch};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap};
+
+/// Hvor et forslag kommer fra
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SuggestionKind {
+    /// Nøkkelordet til et bokmerke
+    Keyword,
+    /// Et bokmerke, eventuelt også besøkt
+    Bookmark,
+    /// En side fra historikken
+    History,
+}
+
+/// Ett forslag i adresselinjen
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Suggestion {
+    pub url: String,
+    pub title: Option<String>,
+    pub kind: SuggestionKind,
+    /// Tegnposisjoner i tittelen som traff
+    pub title_ranges: Vec<MatchRange>,
+    /// Tegnposisjoner i adressen som traff
+    pub url_ranges: Vec<MatchRange>,
+}
+
+/// Rangering av et forslag, det beste først
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Tier {
+    /// Første ord er nøkkelordet til et bokmerke
+    Keyword,
+    /// Tittelen eller adressen til et bokmerke begynner med teksten
+    BookmarkPrefix,
+    /// Adressen eller tittelen til en besøkt side begynner med teksten
+    HistoryPrefix,
+    /// Teksten står et annet sted i tittelen eller adressen
+    Substring,
+}
+
+/// Et bokmerke med nøkkelord
+#[derive(Debug, Clone)]
+struct KeywordEntry {
+    keyword: String,
+    /// Adressen, eventuelt med `%s` for resten av teksten
+    url: String,
+    title: String,
+}
+
+/// Én side i indeksen, slått sammen fra bokmerker og historikk
+#[derive(Debug, Clone)]
+struct Entry {
+    url: String,
+    /// Normalisert adresse, brukt til å fjerne duplikater
+    key: String,
+    title: Option<String>,
+    bookmarked: bool,
+    /// Frecency fra historikken, 0 hvis siden ikke er besøkt
+    frecency: f64,
+    title_chars: Vec<char>,
+    url_chars: Vec<char>,
+    address_offset: usize,
+}
+
+impl Entry {
+    fn new(url: &str, title: Option<&str>) -> Self {
+        let mut entry = Self {
+            url: url.to_string(),
+            key: bookmarks::normalize_url(url),
+            title: None,
+            bookmarked: false,
+            frecency: 0.0,
+            title_chars: Vec::new(),
+            url_chars: search::folded(url),
+            address_offset: search::address_offset(url),
+        };
+        entry.set_title(title);
+        entry
+    }
+
+    fn set_title(&mut self, title: Option<&str>) {
+        let title = title.map(str::trim).filter(|t| !t.is_empty());
+        self.title_chars = title.map(search::folded).unwrap_or_default();
+        self.title = title.map(str::to_string);
+    }
+}
+
+/// Forberedt indeks over alt adresselinjen kan foreslå
+#[derive(Debug, Clone, Default)]
+pub struct AutocompleteIndex {
+    keywords: Vec<KeywordEntry>,
+    entries: Vec<Entry>,
+}
+
+impl AutocompleteIndex {
+    /// Bygg indeksen fra bokmerker og historikk
+    ///
+    /// Hver side får én oppføring, selv om den både er bokmerket og besøkt
+    /// flere ganger. Bokmerker med `%s` i adressen foreslås bare gjennom
+    /// nøkkelordet sitt.
+    ///
+    /// # Arguments
+    /// * `bookmarks` - Alle bokmerker
+    /// * `history` - Historikken, som brukes til frecency
+    /// * `now` - Nåværende tidspunkt (Unix timestamp)
+    pub fn build(bookmarks: &BookmarkStore, history: &History, now: u64) -> Self {
+        let mut index = Self::default();
+        let mut positions: HashMap<String, usize> = HashMap::new();
+
+        for bookmark in bookmarks.list() {
+            if let Some(keyword) = &bookmark.keyword {
+                index.keywords.push(KeywordEntry {
+                    keyword: keyword.to_lowercase(),
+                    url: bookmark.url.clone(),
+                    title: bookmark.title.clone(),
+                });
+            }
+            if bookmark.url.contains("%s") {
+                continue;
+            }
+
+            let mut entry = Entry::new(&bookmark.url, Some(&bookmark.title));
+            entry.bookmarked = true;
+            if !positions.contains_key(&entry.key) {
+                positions.insert(entry.key.clone(), index.entries.len());
+                index.entries.push(entry);
+            }
+        }
+
+        for site in history.top_sites(usize::MAX, now, &BTreeSet::new()) {
+            let key = bookmarks::normalize_url(&site.url);
+            match positions.get(&key) {
+                Some(&position) => {
+                    let entry = &mut index.entries[position];
+                    entry.frecency = site.score;
+                    if entry.title.is_none() {
+                        entry.set_title(site.title.as_deref());
+                    }
+                }
+                None => {
+                    let mut entry = Entry::new(&site.url, site.title.as_deref());
+                    entry.frecency = site.score;
+                    positions.insert(key, index.entries.len());
+                    index.entries.push(entry);
+                }
+            }
+        }
+
+        index
+    }
+
+    /// Finn forslag for teksten i adresselinjen
+    ///
+    /// Rekkefølgen er nøkkelord, prefiks-treff i bokmerker, prefiks-treff i
+    /// historikken og til slutt treff midt i teksten. Innenfor hvert nivå
+    /// går sider med høyest frecency først, og like poeng sorteres etter
+    /// adresse.
+    ///
+    /// # Arguments
+    /// * `input` - Teksten brukeren har skrevet
+    /// * `limit` - Maks antall forslag
+    /// * `include_history` - Om sider som bare finnes i historikken tas med
+    pub fn suggest(&self, input: &str, limit: usize, include_history: bool) -> Vec<Suggestion> {
+        let input = input.trim();
+        let query = search::folded(input);
+        if query.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+
+        let mut ranked: Vec<(Tier, f64, Suggestion)> = Vec::new();
+        let mut seen: BTreeSet<String> = BTreeSet::new();
+
+        if let Some(suggestion) = self.keyword_suggestion(input) {
+            seen.insert(bookmarks::normalize_url(&suggestion.url));
+            ranked.push((Tier::Keyword, f64::INFINITY, suggestion));
+        }
+
+        for entry in &self.entries {
+            if !entry.bookmarked && !include_history {
+                continue;
+            }
+            if seen.contains(&entry.key) {
+                continue;
+            }
+            let frecency = if include_history { entry.frecency } else { 0.0 };
+
+            let title = contiguous(search::find_folded(&entry.title_chars, &query));
+            let url = contiguous(search::find_in_url_folded(
+                &entry.url_chars,
+                entry.address_offset,
+                &query,
+            ));
+            let best = match (&title, &url) {
+                (None, None) => continue,
+                (Some(t), Some(u)) => t.kind.min(u.kind),
+                (Some(m), None) | (None, Some(m)) => m.kind,
+            };
+
+            let tier = match best {
+                MatchKind::Prefix if entry.bookmarked => Tier::BookmarkPrefix,
+                MatchKind::Prefix => Tier::HistoryPrefix,
+                _ => Tier::Substring,
+            };
+            let kind = if entry.bookmarked {
+                SuggestionKind::Bookmark
+            } else {
+                SuggestionKind::History
+            };
+            ranked.push((
+                tier,
+                frecency,
+                Suggestion {
+                    url: entry.url.clone(),
+                    title: entry.title.clone(),
+                    kind,
+                    title_ranges: title.map(|m| m.ranges).unwrap_or_default(),
+                    url_ranges: url.map(|m| m.ranges).unwrap_or_default(),
+                },
+            ));
+        }
+
+        ranked.sort_by(|a, b| {
+            a.0.cmp(&b.0)
+                .then_with(|| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal))
+                .then_with(|| a.2.url.cmp(&b.2.url))
+        });
+        ranked
+            .into_iter()
+            .take(limit)
+            .map(|(_, _, suggestion)| suggestion)
+            .collect()
+    }
+
+    /// Forslaget for et nøkkelord, hvis første ord er et nøkkelord
+    ///
+    /// Adressen fylles ut på samme måte som [`BookmarkStore::resolve_keyword`].
+    fn keyword_suggestion(&self, input: &str) -> Option<Suggestion> {
+        let (first, rest) = input
+            .split_once(char::is_whitespace)
+            .map_or((input, ""), |(first, rest)| (first, rest.trim()));
+        let first = first.to_lowercase();
+        let entry = self.keywords.iter().find(|k| k.keyword == first)?;
+
+        let url = if entry.url.contains("%s") {
+            entry.url.replace("%s", &bookmarks::percent_encode(rest))
+        } else {
+            entry.url.clone()
+        };
+        Some(Suggestion {
+            url,
+            title: Some(entry.title.clone()),
+            kind: SuggestionKind::Keyword,
+            title_ranges: Vec::new(),
+            url_ranges: Vec::new(),
+        })
+    }
+}
+
+/// Behold bare sammenhengende treff
+fn contiguous(found: Option<TextMatch>) -> Option<TextMatch> {
+    found.filter(|m| m.kind != MatchKind::Subsequence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bookmarks::Bookmark;
+    use crate::history::HistoryLimits;
+    use std::path::Path;
+    use std::time::{Duration, Instant};
+    use tempfile::tempdir;
+
+    const NOW: u64 = 1_700_000_000;
+
+    const LIMITS: HistoryLimits = HistoryLimits {
+        max_entries: 100_000,
+        max_age_days: 90,
+    };
+
+    fn bookmark(url: &str, title: &str, keyword: Option<&str>) -> Bookmark {
+        Bookmark {
+            id: bookmarks::generate_id(),
+            title: title.to_string(),
+            url: url.to_string(),
+            created_at: NOW,
+            updated_at: 0,
+            folder: None,
+            tags: Vec::new(),
+            keyword: keyword.map(str::to_string),
+            snapshot_path: None,
+            pinned: false,
+        }
+    }
+
+    fn store(bookmarks: Vec<Bookmark>) -> BookmarkStore {
+        let mut store = BookmarkStore::default();
+        for bookmark in bookmarks {
+            store.add(bookmark).unwrap();
+        }
+        store
+    }
+
+    /// Historikk der hver adresse er besøkt så mange ganger som oppgitt
+    fn history(dir: &Path, visits: &[(&str, &str, usize)]) -> History {
+        let mut history = History::empty(&dir.join("history.jsonl"));
+        for (url, title, count) in visits {
+            for i in 0..*count {
+                history
+                    .record(url, Some(title), NOW - i as u64, LIMITS)
+                    .unwrap();
+            }
+        }
+        history
+    }
+
+    fn urls(suggestions: &[Suggestion]) -> Vec<&str> {
+        suggestions.iter().map(|s| s.url.as_str()).collect()
+    }
+
+    #[test]
+    fn test_ranking_tiers() {
+        let dir = tempdir().unwrap();
+        let bookmarks = store(vec![
+            bookmark("https://rust-lang.org/", "Rust", Some("ru")),
+            bookmark("https://ruby-lang.org/", "Ruby", None),
+            bookmark("https://example.com/guru", "Eksempel", None),
+        ]);
+        let history = history(dir.path(), &[("https://rustup.rs/", "Installer", 5)]);
+        let index = AutocompleteIndex::build(&bookmarks, &history, NOW);
+
+        let suggestions = index.suggest("ru", 10, true);
+        assert_eq!(
+            urls(&suggestions),
+            [
+                // Nøkkelordet går foran alt
+                "https://rust-lang.org/",
+                "https://ruby-lang.org/",
+                "https://rustup.rs/",
+                "https://example.com/guru",
+            ]
+        );
+        assert_eq!(
+            suggestions.iter().map(|s| s.kind).collect::<Vec<_>>(),
+            [
+                SuggestionKind::Keyword,
+                SuggestionKind::Bookmark,
+                SuggestionKind::History,
+                SuggestionKind::Bookmark,
+            ]
+        );
+        assert_eq!(
+            suggestions[1].title_ranges,
+            [MatchRange { start: 0, end: 2 }]
+        );
+        assert_eq!(
+            suggestions[1].url_ranges,
+            [MatchRange { start: 8, end: 10 }]
+        );
+    }
+
+    #[test]
+    fn test_ties_use_frecency_then_url() {
+        let dir = tempdir().unwrap();
+        let bookmarks = store(vec![
+            bookmark("https://news.b.example/", "Nyheter B", None),
+            bookmark("https://news.a.example/", "Nyheter A", None),
+            bookmark("https://news.c.example/", "Nyheter C", None),
+        ]);
+        let history = history(dir.path(), &[("https://news.c.example/", "Nyheter C", 3)]);
+        let index = AutocompleteIndex::build(&bookmarks, &history, NOW);
+
+        assert_eq!(
+            urls(&index.suggest("nyheter", 10, true)),
+            [
+                "https://news.c.example/",
+                "https://news.a.example/",
+                "https://news.b.example/",
+            ]
+        );
+
+        // Uten historikk teller ikke besøkene
+        assert_eq!(
+            urls(&index.suggest("nyheter", 10, false)),
+            [
+                "https://news.a.example/",
+                "https://news.b.example/",
+                "https://news.c.example/",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dedupe_by_normalized_url() {
+        let dir = tempdir().unwrap();
+        let bookmarks = store(vec![
+            bookmark("https://example.com/", "Eksempel", Some("ex")),
+            bookmark("https://search.example/?q=%s", "Søk", Some("s")),
+        ]);
+        let history = history(
+            dir.path(),
+            &[
+                ("https://EXAMPLE.com", "Eksempel", 2),
+                ("https://example.com/#top", "Eksempel", 1),
+                ("https://search.example/?q=rust", "Søk", 1),
+            ],
+        );
+        let index = AutocompleteIndex::build(&bookmarks, &history, NOW);
+
+        let suggestions = index.suggest("example", 10, true);
+        assert_eq!(
+            urls(&suggestions),
+            ["https://example.com/", "https://search.example/?q=rust"]
+        );
+        assert_eq!(suggestions[0].kind, SuggestionKind::Bookmark);
+
+        // Nøkkelordet og bokmerket det peker på er samme forslag
+        let suggestions = index.suggest("ex", 10, true);
+        assert_eq!(
+            urls(&suggestions),
+            ["https://example.com/", "https://search.example/?q=rust"]
+        );
+        assert_eq!(suggestions[0].kind, SuggestionKind::Keyword);
+
+        // Søkemalen fylles ut og erstatter den besøkte søkesiden
+        assert_eq!(
+            urls(&index.suggest("s rust", 10, true)),
+            ["https://search.example/?q=rust"]
+        );
+    }
+
+    #[test]
+    fn test_history_only_pages_need_history() {
+        let dir = tempdir().unwrap();
+        let history = history(dir.path(), &[("https://example.org/", "Org", 1)]);
+        let index = AutocompleteIndex::build(&BookmarkStore::default(), &history, NOW);
+
+        assert_eq!(index.suggest("example", 10, true).len(), 1);
+        assert!(index.suggest("example", 10, false).is_empty());
+        assert!(index.suggest("  ", 10, true).is_empty());
+        // Uskarpe treff foreslås ikke
+        assert!(index.suggest("exorg", 10, true).is_empty());
+    }
+
+    #[test]
+    fn test_suggest_is_fast_for_thousands_of_entries() {
+        // Rett inn i listen; `add` sjekker duplikater og ville gjort
+        // oppbyggingen kvadratisk
+        let mut bookmarks = BookmarkStore::default();
+        bookmarks.bookmarks = (0..5_000)
+            .map(|i| {
+                bookmark(
+                    &format!("https://site{}.example/page", i),
+                    &format!("Side nummer {}", i),
+                    None,
+                )
+            })
+            .collect();
+        let dir = tempdir().unwrap();
+        let visits: Vec<(String, String)> = (0..5_000)
+            .map(|i| {
+                (
+                    format!("gemini://capsule{}.example/", i),
+                    format!("Kapsel {}", i),
+                )
+            })
+            .collect();
+        let visits: Vec<(&str, &str, usize)> = visits
+            .iter()
+            .map(|(url, title)| (url.as_str(), title.as_str(), 1))
+            .collect();
+        let history = history(dir.path(), &visits);
+        let index = AutocompleteIndex::build(&bookmarks, &history, NOW);
+
+        // Romslig grense, slik at testen holder også uten optimalisering
+        let started = Instant::now();
+        for query in ["s", "site42", "kapsel 4", "example/page", "ingen treff"] {
+            index.suggest(query, 10, true);
+        }
+        let per_query = started.elapsed() / 5;
+        assert!(
+            per_query < Duration::from_millis(50),
+            "{:?} per søk",
+            per_query
+        );
+    }
+}
@@ -0,0 +1,1577 @@
+//! Import og eksport av bokmerker
+//!
+//! Leser og skriver bokmerkefiler i Netscape-formatet (`<DL><DT><A HREF=...>`)
+//! som Firefox, Chrome og de fleste Gemini-klienter bruker, i tillegg til
+//! Bares eget JSON-format, en lesbar markdown-liste og gemtext-lenkesider.
+
+use crate::bookmarks::{self, Bookmark, BookmarkError, BookmarkStore};
+use crate::locale;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Protokoller Bare kan åpne
+const SUPPORTED_SCHEMES: &[&str] = &["http", "https", "gemini", "gopher", "file"];
+
+/// Filformat for import og eksport av bokmerker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BookmarkFormat {
+    /// Bares eget format (samme som bookmarks.json)
+    Json,
+    /// Markdown-liste gruppert etter mappe (kun eksport)
+    Markdown,
+    /// Netscape bookmark-HTML
+    #[serde(alias = "netscape")]
+    Html,
+    /// Gemtext-side med `=>`-lenker og én `##`-overskrift per mappe
+    #[serde(alias = "gmi")]
+    Gemtext,
+}
+
+/// Et bokmerke lest fra en importfil
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedBookmark {
+    /// Tittel (lenketekst)
+    pub title: String,
+    /// URL fra HREF
+    pub url: String,
+    /// Mappesti bygget fra overordnede `<H3>`-mapper
+    pub folder: Option<String>,
+    /// ADD_DATE som Unix timestamp, hvis oppgitt
+    pub created_at: Option<u64>,
+    /// Emneknagger fra TAGS (Firefox)
+    pub tags: Vec<String>,
+}
+
+/// Oppsummering av en import
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImportSummary {
+    /// Antall nye bokmerker
+    pub added: usize,
+    /// Antall bokmerker som allerede fantes
+    pub duplicates: usize,
+    /// URL-er som ble hoppet over fordi Bare ikke kan åpne dem
+    pub skipped: Vec<String>,
+}
+
+/// Importer bokmerker fra fil til en bokmerkesamling
+///
+/// # Arguments
+/// * `store` - Bokmerkesamlingen det importeres til
+/// * `path` - Sti til importfilen
+/// * `format` - Filformat
+///
+/// # Returns
+/// ImportSummary eller BookmarkError hvis filen ikke kan leses
+pub fn import_file(
+    store: &mut BookmarkStore,
+    path: &Path,
+    format: BookmarkFormat,
+) -> Result<ImportSummary, BookmarkError> {
+    let content = fs::read_to_string(path).map_err(|e| BookmarkError::Read(e.to_string()))?;
+    let entries = match format {
+        BookmarkFormat::Html => parse_netscape_html(&content),
+        BookmarkFormat::Json => {
+            let imported: BookmarkStore =
+                serde_json::from_str(&content).map_err(|e| BookmarkError::Read(e.to_string()))?;
+            imported
+                .bookmarks
+                .into_iter()
+                .map(|b| ImportedBookmark {
+                    title: b.title,
+                    url: b.url,
+                    folder: b.folder,
+                    created_at: Some(b.created_at),
+                    tags: b.tags,
+                })
+                .collect()
+        }
+        BookmarkFormat::Gemtext => parse_gemtext(&content),
+        BookmarkFormat::Markdown => {
+            return Err(BookmarkError::Read(
+                "Import fra markdown støttes ikke".to_string(),
+            ))
+        }
+    };
+    Ok(import_entries(store, entries))
+}
+
+/// Eksporter bokmerker til fil
+///
+/// Bokmerkene sorteres etter mappe, tittel og URL slik at to eksporter av
+/// samme samling blir identiske. Filen skrives atomisk via en midlertidig fil.
+///
+/// # Arguments
+/// * `store` - Bokmerkesamlingen som eksporteres
+/// * `path` - Målfil
+/// * `format` - Filformat
+///
+/// # Returns
+/// Antall eksporterte bokmerker, eller BookmarkError
+pub fn export_file(
+    store: &BookmarkStore,
+    path: &Path,
+    format: BookmarkFormat,
+) -> Result<usize, BookmarkError> {
+    let content = match format {
+        BookmarkFormat::Json => export_json(store)?,
+        BookmarkFormat::Markdown => export_markdown(store),
+        BookmarkFormat::Html => export_netscape_html(store),
+        BookmarkFormat::Gemtext => export_gemtext(store, chrono::Local::now().date_naive()),
+    };
+    storage::write_atomic(path, &content).map_err(|e| BookmarkError::Write(e.to_string()))?;
+    Ok(store.list().len())
+}
+
+/// Bokmerkene i fast rekkefølge: mappe, tittel, URL
+fn sorted_bookmarks(store: &BookmarkStore) -> Vec<&Bookmark> {
+    let mut sorted: Vec<&Bookmark> = store.list().iter().collect();
+    sorted.sort_by(|a, b| {
+        (a.folder.as_deref(), a.title.to_lowercase(), &a.url).cmp(&(
+            b.folder.as_deref(),
+            b.title.to_lowercase(),
+            &b.url,
+        ))
+    });
+    sorted
+}
+
+/// Bokmerkene som sortert JSON i Bares eget format
+pub fn export_json(store: &BookmarkStore) -> Result<String, BookmarkError> {
+    let mut sorted = store.clone();
+    sorted.bookmarks = sorted_bookmarks(store).into_iter().cloned().collect();
+    sorted.invalidate_sort_cache();
+    serde_json::to_string_pretty(&sorted).map_err(|e| BookmarkError::Write(e.to_string()))
+}
+
+/// Bokmerkene som markdown, med én overskrift per mappe
+pub fn export_markdown(store: &BookmarkStore) -> String {
+    let bookmarks = sorted_bookmarks(store);
+    let mut output = format!(
+        "# Bokmerker\n\n*Eksportert fra Bare — {} bokmerker*\n",
+        bookmarks.len()
+    );
+
+    let write_list = |output: &mut String, folder: Option<&str>| {
+        let mut wrote_any = false;
+        for bookmark in bookmarks.iter().filter(|b| b.folder.as_deref() == folder) {
+            if !wrote_any {
+                output.push('\n');
+                wrote_any = true;
+            }
+            output.push_str(&format!(
+                "- [{}]({})\n",
+                escape_markdown(&bookmark.title),
+                markdown_destination(&bookmark.url)
+            ));
+        }
+    };
+
+    write_list(&mut output, None);
+    for folder in store.folders() {
+        let depth = folder.matches('/').count();
+        let name = folder.rsplit('/').next().unwrap_or(&folder);
+        output.push_str(&format!("\n{} {}\n", "#".repeat((depth + 2).min(6)), name));
+        write_list(&mut output, Some(&folder));
+    }
+
+    output
+}
+
+/// Bokmerkene som en lesbar startside i markdown
+///
+/// Festede bokmerker kommer først, deretter bokmerkene i roten og én
+/// overskrift per mappe. Hvert bokmerke vises med vertsnavn, emneknagger og
+/// hvor lenge siden det ble lagt til.
+///
+/// # Arguments
+/// * `store` - Bokmerkesamlingen
+/// * `now` - Nåværende tidspunkt (Unix timestamp)
+pub fn bookmarks_page_markdown(store: &BookmarkStore, now: u64) -> String {
+    let bookmarks = sorted_bookmarks(store);
+    let folders = store.folders();
+    let mut output = format!(
+        "# {}\n\n{}\n",
+        locale::t("bookmarks_page.title"),
+        locale::tf(
+            "bookmarks_page.summary",
+            &[
+                &count_label(bookmarks.len(), "count.bookmark_one", "count.bookmark_many"),
+                &count_label(folders.len(), "count.folder_one", "count.folder_many"),
+            ]
+        )
+    );
+
+    if bookmarks.is_empty() {
+        output.push_str(&format!("\n{}\n", locale::t("bookmarks_page.empty")));
+        return output;
+    }
+
+    let pinned = store.pinned();
+    if !pinned.is_empty() {
+        output.push_str(&format!("\n## {}\n\n", locale::t("bookmarks_page.pinned")));
+        for bookmark in pinned {
+            output.push_str(&page_entry(bookmark, now));
+        }
+    }
+
+    let root: Vec<&&Bookmark> = bookmarks.iter().filter(|b| b.folder.is_none()).collect();
+    if !root.is_empty() {
+        output.push_str(&format!(
+            "\n## {}\n\n",
+            locale::t("bookmarks_page.no_folder")
+        ));
+        for bookmark in root {
+            output.push_str(&page_entry(bookmark, now));
+        }
+    }
+
+    for folder in &folders {
+        let depth = folder.matches('/').count();
+        let name = folder.rsplit('/').next().unwrap_or(folder);
+        output.push_str(&format!("\n{} {}\n", "#".repeat((depth + 2).min(6)), name));
+        let mut wrote_any = false;
+        for bookmark in bookmarks
+            .iter()
+            .filter(|b| b.folder.as_deref() == Some(folder))
+        {
+            if !wrote_any {
+                output.push('\n');
+                wrote_any = true;
+            }
+            output.push_str(&page_entry(bookmark, now));
+        }
+    }
+
+    output
+}
+
+/// Én linje på bokmerkesiden: lenke, vertsnavn, emneknagger og alder
+fn page_entry(bookmark: &Bookmark, now: u64) -> String {
+    let mut details = Vec::new();
+    if let Some(host) = url::Url::parse(&bookmark.url)
+        .ok()
+        .and_then(|u| u.host_str().map(String::from))
+    {
+        details.push(host);
+    }
+    if !bookmark.tags.is_empty() {
+        let tags: Vec<String> = bookmark.tags.iter().map(|t| format!("#{}", t)).collect();
+        details.push(escape_markdown(&tags.join(" ")));
+    }
+    if let Some(age) = relative_age(bookmark.created_at, now) {
+        details.push(locale::tf("bookmarks_page.added", &[&age]));
+    }
+
+    let mut line = format!(
+        "- [{}]({})",
+        escape_markdown(&bookmark.title),
+        markdown_destination(&bookmark.url)
+    );
+    if !details.is_empty() {
+        line.push_str(" — ");
+        line.push_str(&details.join(" · "));
+    }
+    line.push('\n');
+    line
+}
+
+/// "1 bokmerke" / "3 bokmerker"
+///
+/// # Arguments
+/// * `singular` - Nøkkel for entall
+/// * `plural` - Nøkkel for flertall
+fn count_label(count: usize, singular: &'static str, plural: &'static str) -> String {
+    locale::tf(if count == 1 { singular } else { plural }, &[&count])
+}
+
+/// Hvor lenge siden et tidspunkt var, på brukerens språk ("i dag", "for 3 uker siden")
+///
+/// Returnerer None for ukjent tidspunkt (0).
+fn relative_age(timestamp: u64, now: u64) -> Option<String> {
+    if timestamp == 0 {
+        return None;
+    }
+
+    let days = now.saturating_sub(timestamp) / 86_400;
+    let age = match days {
+        0 => locale::t("age.today").to_string(),
+        1 => locale::t("age.yesterday").to_string(),
+        2..=13 => locale::tf("age.days", &[&days]),
+        14..=59 => locale::tf("age.weeks", &[&(days / 7)]),
+        60..=364 => locale::tf("age.months", &[&(days / 30)]),
+        _ if days < 730 => locale::t("age.one_year").to_string(),
+        _ => locale::tf("age.years", &[&(days / 365)]),
+    };
+    Some(age)
+}
+
+/// Bokmerkene som en gemtext-side
+///
+/// Bokmerker i roten kommer først, deretter én `##`-overskrift per mappe
+/// (med hele stien, siden gemtext bare har tre overskriftsnivåer).
+///
+/// # Arguments
+/// * `store` - Bokmerkesamlingen som eksporteres
+/// * `date` - Datoen som står i toppteksten
+pub fn export_gemtext(store: &BookmarkStore, date: chrono::NaiveDate) -> String {
+    let bookmarks = sorted_bookmarks(store);
+    let mut output = format!(
+        "# Bokmerker\n\nEksportert fra Bare {} — {} bokmerker\n",
+        date.format("%Y-%m-%d"),
+        bookmarks.len()
+    );
+
+    let mut current: Option<Option<&str>> = None;
+    for bookmark in &bookmarks {
+        let folder = bookmark.folder.as_deref();
+        if current != Some(folder) {
+            output.push('\n');
+            if let Some(folder) = folder {
+                output.push_str(&format!("## {}\n\n", folder));
+            }
+            current = Some(folder);
+        }
+        output.push_str(&gemtext_link(bookmark));
+    }
+
+    output
+}
+
+/// Én `=>`-linje; URL-en kan ikke inneholde mellomrom og tittelen ikke linjeskift
+fn gemtext_link(bookmark: &Bookmark) -> String {
+    let url = bookmark.url.replace(' ', "%20");
+    let title = bookmark
+        .title
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if title.is_empty() {
+        format!("=> {}\n", url)
+    } else {
+        format!("=> {} {}\n", url, title)
+    }
+}
+
+/// Bokmerkene som Netscape bookmark-HTML
+pub fn export_netscape_html(store: &BookmarkStore) -> String {
+    let bookmarks = sorted_bookmarks(store);
+    let folders = store.folders();
+    let mut output = String::from(
+        "<!DOCTYPE NETSCAPE-Bookmark-file-1>\n\
+         <!-- This is an automatically generated file.\n     \
+         It will be read and overwritten.\n     \
+         DO NOT EDIT! -->\n\
+         <META HTTP-EQUIV=\"Content-Type\" CONTENT=\"text/html; charset=UTF-8\">\n\
+         <TITLE>Bookmarks</TITLE>\n\
+         <H1>Bookmarks</H1>\n",
+    );
+    write_netscape_folder(&mut output, &bookmarks, &folders, None, 0);
+    output
+}
+
+/// Skriv én `<DL>`-blokk med bokmerker og undermapper
+fn write_netscape_folder(
+    output: &mut String,
+    bookmarks: &[&Bookmark],
+    folders: &[String],
+    folder: Option<&str>,
+    depth: usize,
+) {
+    let indent = "    ".repeat(depth);
+    output.push_str(&format!("{}<DL><p>\n", indent));
+
+    for bookmark in bookmarks.iter().filter(|b| b.folder.as_deref() == folder) {
+        let tags = if bookmark.tags.is_empty() {
+            String::new()
+        } else {
+            format!(" TAGS=\"{}\"", escape_html(&bookmark.tags.join(",")))
+        };
+        output.push_str(&format!(
+            "{}    <DT><A HREF=\"{}\" ADD_DATE=\"{}\"{}>{}</A>\n",
+            indent,
+            escape_html(&bookmark.url),
+            bookmark.created_at,
+            tags,
+            escape_html(&bookmark.title)
+        ));
+    }
+
+    let children = folders.iter().filter(|f| match folder {
+        Some(parent) => f
+            .strip_prefix(parent)
+            .and_then(|rest| rest.strip_prefix('/'))
+            .is_some_and(|rest| !rest.contains('/')),
+        None => !f.contains('/'),
+    });
+    for child in children {
+        let name = child.rsplit('/').next().unwrap_or(child);
+        output.push_str(&format!(
+            "{}    <DT><H3>{}</H3>\n",
+            indent,
+            escape_html(name)
+        ));
+        write_netscape_folder(output, bookmarks, folders, Some(child), depth + 1);
+    }
+
+    output.push_str(&format!("{}</DL><p>\n", indent));
+}
+
+/// Escaper tegn med spesiell betydning i HTML
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Escaper tegn som ville brutt lenketeksten i markdown
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '[' | ']' | '\\' | '*' | '_' | '`') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Lenkemål for markdown; URL-er med mellomrom eller parenteser pakkes i `<>`
+fn markdown_destination(url: &str) -> String {
+    if url.contains([' ', '(', ')']) {
+        format!("<{}>", url)
+    } else {
+        url.to_string()
+    }
+}
+
+/// Legg importerte bokmerker inn i samlingen
+///
+/// Bokmerker med protokoller Bare ikke støtter hoppes over, og URL-er som
+/// allerede finnes (etter normalisering) telles som duplikater.
+pub fn import_entries(store: &mut BookmarkStore, entries: Vec<ImportedBookmark>) -> ImportSummary {
+    let mut summary = ImportSummary::default();
+    let mut known: HashSet<String> = store
+        .list()
+        .iter()
+        .map(|b| bookmarks::normalize_url(&b.url))
+        .collect();
+
+    for entry in entries {
+        let scheme_supported =
+            url::Url::parse(&entry.url).is_ok_and(|url| SUPPORTED_SCHEMES.contains(&url.scheme()));
+        if !scheme_supported {
+            summary.skipped.push(entry.url);
+            continue;
+        }
+        if !known.insert(bookmarks::normalize_url(&entry.url)) {
+            summary.duplicates += 1;
+            continue;
+        }
+
+        let title = if entry.title.is_empty() {
+            entry.url.clone()
+        } else {
+            entry.title
+        };
+        let created_at = entry
+            .created_at
+            .unwrap_or_else(bookmarks::current_timestamp);
+        let bookmark = Bookmark {
+            id: bookmarks::generate_id(),
+            title,
+            url: entry.url,
+            created_at,
+            updated_at: created_at,
+            folder: entry.folder,
+            tags: bookmarks::normalize_tags(&entry.tags),
+            keyword: None,
+            snapshot_path: None,
+            pinned: false,
+        };
+        match store.add(bookmark) {
+            Ok(()) => summary.added += 1,
+            Err(_) => summary.duplicates += 1,
+        }
+    }
+
+    summary
+}
+
+/// En tittelkonflikt funnet under sammenslåing
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MergeConflict {
+    /// URL-en til bokmerket (slik den står lokalt)
+    pub url: String,
+    /// Lokal tittel før sammenslåing
+    pub local_title: String,
+    /// Tittelen i den andre filen
+    pub remote_title: String,
+    /// Tittelen som ble beholdt
+    pub kept_title: String,
+}
+
+/// Oppsummering av en sammenslåing
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MergeSummary {
+    /// Antall bokmerker som bare fantes i den andre filen
+    pub added: usize,
+    /// Antall lokale bokmerker som ble endret
+    pub updated: usize,
+    /// Bokmerker med ulik tittel på de to maskinene
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// Slå sammen bokmerker fra en annen bokmerkefil
+///
+/// Leser Bares JSON-format (bookmarks.json eller en JSON-eksport), og faller
+/// tilbake til Netscape-HTML for filer eksportert fra andre nettlesere.
+///
+/// # Arguments
+/// * `store` - Den lokale bokmerkesamlingen
+/// * `path` - Sti til den andre filen
+///
+/// # Returns
+/// MergeSummary eller BookmarkError hvis filen ikke kan leses
+pub fn merge_file(store: &mut BookmarkStore, path: &Path) -> Result<MergeSummary, BookmarkError> {
+    let content = fs::read_to_string(path).map_err(|e| BookmarkError::Read(e.to_string()))?;
+    let remote = match serde_json::from_str::<BookmarkStore>(&content) {
+        Ok(remote) => remote,
+        Err(e) => {
+            let entries = parse_netscape_html(&content);
+            if entries.is_empty() {
+                return Err(BookmarkError::Read(e.to_string()));
+            }
+            let mut remote = BookmarkStore::default();
+            import_entries(&mut remote, entries);
+            remote
+        }
+    };
+    Ok(merge_stores(store, &remote))
+}
+
+/// Slå sammen to bokmerkesamlinger
+///
+/// Bokmerker matches på normalisert URL. For hvert par beholdes den tidligste
+/// `created_at` og unionen av emneknagger, mens tittel, mappe og nøkkelord
+/// hentes fra kopien som sist ble endret (`updated_at`, deretter tittel,
+/// mappe og nøkkelord som avgjørende). Nøkkelord må være unike: brukes det
+/// samme av flere bokmerker, får det sist endrede (deretter minste URL) det,
+/// og de andre faller tilbake på nøkkelordet fra den andre kopien eller
+/// mister det. Reglene er symmetriske og gir samme resultat ved gjentatt
+/// kjøring, slik at to maskiner som slår sammen hverandres filer ender likt.
+///
+/// # Returns
+/// Antall nye og endrede bokmerker, og tittelkonflikter
+pub fn merge_stores(local: &mut BookmarkStore, remote: &BookmarkStore) -> MergeSummary {
+    let mut summary = MergeSummary::default();
+    let original = local.bookmarks.clone();
+    let mut keys: Vec<String> = local
+        .bookmarks
+        .iter()
+        .map(|b| bookmarks::normalize_url(&b.url))
+        .collect();
+    let mut index: HashMap<String, usize> = keys
+        .iter()
+        .enumerate()
+        .map(|(i, key)| (key.clone(), i))
+        .collect();
+    // Nøkkelordene hvert bokmerke kan få, i prioritert rekkefølge
+    let mut claims: Vec<Vec<String>> = local
+        .bookmarks
+        .iter()
+        .map(|b| b.keyword.iter().cloned().collect())
+        .collect();
+    let mut ids: HashSet<String> = local.bookmarks.iter().map(|b| b.id.clone()).collect();
+
+    let mut incoming: Vec<&Bookmark> = remote.bookmarks.iter().collect();
+    incoming.sort_by(|a, b| (a.created_at, &a.url).cmp(&(b.created_at, &b.url)));
+
+    for theirs in incoming {
+        let key = bookmarks::normalize_url(&theirs.url);
+        let Some(&position) = index.get(&key) else {
+            let mut added = theirs.clone();
+            if !ids.insert(added.id.clone()) {
+                added.id = bookmarks::generate_id();
+                ids.insert(added.id.clone());
+            }
+            // Arkiverte kopier ligger bare på maskinen som lagret dem
+            added.snapshot_path = None;
+            index.insert(key.clone(), local.bookmarks.len());
+            keys.push(key);
+            claims.push(added.keyword.iter().cloned().collect());
+            local.bookmarks.push(added);
+            summary.added += 1;
+            continue;
+        };
+
+        let ours = &mut local.bookmarks[position];
+        let theirs_first = std::ptr::eq(merge_winner(ours, theirs), theirs);
+        let claim = &mut claims[position];
+        if let Some(keyword) = theirs.keyword.as_ref().filter(|k| !claim.contains(k)) {
+            if theirs_first {
+                claim.insert(0, keyword.clone());
+            } else {
+                claim.push(keyword.clone());
+            }
+        }
+        if ours.title != theirs.title {
+            let kept = merge_winner(ours, theirs).title.clone();
+            summary.conflicts.push(MergeConflict {
+                url: ours.url.clone(),
+                local_title: ours.title.clone(),
+                remote_title: theirs.title.clone(),
+                kept_title: kept,
+            });
+        }
+        merge_bookmark(ours, theirs);
+    }
+
+    let mut order: Vec<usize> = (0..local.bookmarks.len()).collect();
+    order.sort_by(|&a, &b| {
+        let updated = |i: usize| local.bookmarks[i].updated_at;
+        updated(b)
+            .cmp(&updated(a))
+            .then_with(|| keys[a].cmp(&keys[b]))
+    });
+    let mut taken: HashSet<&str> = HashSet::new();
+    for position in order {
+        let keyword = claims[position]
+            .iter()
+            .find(|k| !taken.contains(k.as_str()));
+        if let Some(keyword) = keyword {
+            taken.insert(keyword);
+        }
+        local.bookmarks[position].keyword = keyword.cloned();
+    }
+    summary.updated = original
+        .iter()
+        .zip(&local.bookmarks)
+        .filter(|(before, after)| before != after)
+        .count();
+
+    local.folders.extend(remote.folders.iter().cloned());
+    local.folders.sort();
+    local.folders.dedup();
+    local.invalidate_sort_cache();
+    summary
+}
+
+/// Kopien som bestemmer tittel, mappe, festing og nøkkelord: sist endret,
+/// deretter største tittel, mappe og nøkkelord slik at valget er likt uansett
+/// rekkefølge
+fn merge_winner<'a>(a: &'a Bookmark, b: &'a Bookmark) -> &'a Bookmark {
+    let rank = |x: &'a Bookmark| (x.updated_at, &x.title, &x.folder, x.pinned, &x.keyword);
+    if rank(b) > rank(a) {
+        b
+    } else {
+        a
+    }
+}
+
+/// Slå sammen ett bokmerke med en kopi fra den andre maskinen
+fn merge_bookmark(ours: &mut Bookmark, theirs: &Bookmark) {
+    let winner = merge_winner(ours, theirs);
+    let (title, folder, pinned, updated_at) = (
+        winner.title.clone(),
+        winner.folder.clone(),
+        winner.pinned,
+        winner.updated_at,
+    );
+
+    ours.title = title;
+    ours.folder = folder;
+    ours.pinned = pinned;
+    ours.updated_at = updated_at;
+    ours.created_at = ours.created_at.min(theirs.created_at);
+    for tag in &theirs.tags {
+        if !ours.tags.contains(tag) {
+            ours.tags.push(tag.clone());
+        }
+    }
+}
+
+/// Parser lenkene i en gemtext-side
+///
+/// `##`-overskrifter blir mapper og `#` går tilbake til roten; lenker i
+/// preformaterte blokker hoppes over.
+///
+/// # Arguments
+/// * `gemtext` - Innholdet i .gmi-filen
+pub fn parse_gemtext(gemtext: &str) -> Vec<ImportedBookmark> {
+    let mut entries = Vec::new();
+    let mut folder: Option<String> = None;
+    let mut preformatted = false;
+
+    for line in gemtext.lines() {
+        if line.starts_with("```") {
+            preformatted = !preformatted;
+            continue;
+        }
+        if preformatted {
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix("=>") {
+            let rest = rest.trim();
+            let (url, title) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            if !url.is_empty() {
+                entries.push(ImportedBookmark {
+                    title: title.trim().to_string(),
+                    url: url.to_string(),
+                    folder: folder.clone(),
+                    created_at: None,
+                    tags: Vec::new(),
+                });
+            }
+        } else if let Some(heading) = line.strip_prefix("## ") {
+            folder = bookmarks::normalize_folder(heading).ok().flatten();
+        } else if line.starts_with("# ") {
+            folder = None;
+        }
+    }
+
+    entries
+}
+
+/// Parser en Netscape bookmark-fil
+///
+/// Hver `<H3>` etterfulgt av `<DL>` blir en mappe, og mappene settes sammen
+/// til en sti ("Tech/Rust"). Formatet er ikke gyldig HTML, så parseren leser
+/// bare de taggene den trenger og ignorerer resten.
+///
+/// # Arguments
+/// * `html` - Innholdet i eksportfilen
+///
+/// # Returns
+/// Bokmerkene i filrekkefølge
+pub fn parse_netscape_html(html: &str) -> Vec<ImportedBookmark> {
+    let lower = html.to_ascii_lowercase();
+    let mut entries = Vec::new();
+    let mut folders: Vec<Option<String>> = Vec::new();
+    let mut pending_folder: Option<String> = None;
+    let mut pos = 0;
+
+    while let Some(offset) = html[pos..].find('<') {
+        let start = pos + offset;
+        if lower[start..].starts_with("<!--") {
+            pos = lower[start..]
+                .find("-->")
+                .map_or(html.len(), |end| start + end + 3);
+            continue;
+        }
+        let Some(end) = html[start..].find('>').map(|end| start + end) else {
+            break;
+        };
+        let tag = &html[start + 1..end];
+        let name = tag
+            .split(|c: char| c.is_whitespace())
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        pos = end + 1;
+
+        match name.as_str() {
+            "h3" => {
+                let (text, next) = element_text(html, &lower, pos, "</h3");
+                pending_folder = Some(text.replace('/', "-"));
+                pos = next;
+            }
+            "dl" => folders.push(pending_folder.take()),
+            "/dl" => {
+                folders.pop();
+            }
+            "a" => {
+                let (title, next) = element_text(html, &lower, pos, "</a");
+                pos = next;
+                let attributes = parse_attributes(&tag[1..]);
+                let Some(url) = attribute(&attributes, "href") else {
+                    continue;
+                };
+                let folder_path = folders.iter().flatten().cloned().collect::<Vec<_>>();
+                entries.push(ImportedBookmark {
+                    title,
+                    url: url.trim().to_string(),
+                    folder: bookmarks::normalize_folder(&folder_path.join("/"))
+                        .ok()
+                        .flatten(),
+                    created_at: attribute(&attributes, "add_date")
+                        .and_then(|d| d.trim().parse().ok())
+                        .map(unix_seconds),
+                    tags: attribute(&attributes, "tags")
+                        .map(|t| t.split(',').map(String::from).collect())
+                        .unwrap_or_default(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    entries
+}
+
+/// Teksten frem til avsluttende tag, dekodet og trimmet
+///
+/// # Returns
+/// Teksten og posisjonen etter den avsluttende taggen
+fn element_text(html: &str, lower: &str, pos: usize, closing: &str) -> (String, usize) {
+    let end = lower[pos..]
+        .find(closing)
+        .map_or(html.len(), |end| pos + end);
+    let after = html[end..].find('>').map_or(html.len(), |i| end + i + 1);
+    let text = decode_entities(&html[pos..end]);
+    (text.split_whitespace().collect::<Vec<_>>().join(" "), after)
+}
+
+/// Parser attributter i en tag (navn i små bokstaver, verdier dekodet)
+fn parse_attributes(tag: &str) -> Vec<(String, String)> {
+    let mut attributes = Vec::new();
+    let mut chars = tag.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let mut name_end = tag.len();
+        while let Some(&(i, c)) = chars.peek() {
+            if c == '=' || c.is_whitespace() {
+                name_end = i;
+                break;
+            }
+            chars.next();
+        }
+        let name = tag[start..name_end].to_ascii_lowercase();
+
+        while chars.peek().is_some_and(|&(_, c)| c.is_whitespace()) {
+            chars.next();
+        }
+        if chars.peek().map(|&(_, c)| c) != Some('=') {
+            attributes.push((name, String::new()));
+            continue;
+        }
+        chars.next();
+        while chars.peek().is_some_and(|&(_, c)| c.is_whitespace()) {
+            chars.next();
+        }
+
+        let quote = chars
+            .peek()
+            .map(|&(_, c)| c)
+            .filter(|c| *c == '"' || *c == '\'');
+        if quote.is_some() {
+            chars.next();
+        }
+        let value_start = chars.peek().map_or(tag.len(), |&(i, _)| i);
+        let mut value_end = tag.len();
+        while let Some(&(i, c)) = chars.peek() {
+            let done = match quote {
+                Some(q) => c == q,
+                None => c.is_whitespace(),
+            };
+            if done {
+                value_end = i;
+                chars.next();
+                break;
+            }
+            chars.next();
+        }
+        attributes.push((name, decode_entities(&tag[value_start..value_end])));
+    }
+
+    attributes
+}
+
+/// Slå opp et attributt etter navn
+fn attribute(attributes: &[(String, String)], name: &str) -> Option<String> {
+    attributes
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.clone())
+}
+
+/// Dekoder de vanligste HTML-entitetene
+fn decode_entities(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(index) = rest.find('&') {
+        output.push_str(&rest[..index]);
+        rest = &rest[index..];
+        let decoded = rest.find(';').filter(|end| *end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{a0}'),
+                _ => entity.strip_prefix('#').and_then(|number| {
+                    let code = match number.strip_prefix(['x', 'X']) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                        None => number.parse().ok(),
+                    };
+                    code.and_then(char::from_u32)
+                }),
+            };
+            c.map(|c| (c, end))
+        });
+
+        match decoded {
+            Some((c, end)) => {
+                output.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                output.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Tolker ADD_DATE i sekunder, millisekunder eller mikrosekunder
+fn unix_seconds(mut value: u64) -> u64 {
+    while value > 100_000_000_000 {
+        value /= 1000;
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIREFOX: &str = include_str!("../fixtures/bookmarks/firefox.html");
+    const CHROME: &str = include_str!("../fixtures/bookmarks/chrome.html");
+
+    /// Et bokmerke med standardverdier; testene overstyrer feltene de sjekker
+    fn bookmark(id: &str, url: &str) -> Bookmark {
+        Bookmark {
+            id: id.to_string(),
+            title: "Eksempel".to_string(),
+            url: url.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_firefox_export() {
+        let entries = parse_netscape_html(FIREFOX);
+        assert_eq!(entries.len(), 7);
+
+        assert_eq!(entries[0].title, "Get Help");
+        assert_eq!(entries[0].folder, None);
+        assert_eq!(entries[0].created_at, Some(1700000000));
+
+        let book = &entries[2];
+        assert_eq!(book.url, "https://doc.rust-lang.org/book/");
+        assert_eq!(book.folder.as_deref(), Some("Tech/Rust"));
+        assert_eq!(book.tags, ["rust", "docs"]);
+
+        assert_eq!(entries[4].folder.as_deref(), Some("Tech"));
+        assert_eq!(entries[5].title, "Bare & venner – GitHub");
+        assert_eq!(entries[5].folder.as_deref(), Some("Bookmarks Toolbar"));
+    }
+
+    #[test]
+    fn test_parse_chrome_export() {
+        let entries = parse_netscape_html(CHROME);
+        assert_eq!(entries.len(), 4);
+
+        assert_eq!(entries[0].folder.as_deref(), Some("Bookmarks bar"));
+        assert_eq!(entries[1].url, "https://example.com/artikkel?id=1&side=2");
+        assert_eq!(entries[1].title, "Artikkel <1>");
+        // Skråstrek i mappenavn lager ikke en ekstra nivå
+        assert_eq!(
+            entries[1].folder.as_deref(),
+            Some("Bookmarks bar/Lesing-Senere")
+        );
+        assert_eq!(entries[2].folder, None);
+    }
+
+    #[test]
+    fn test_import_skips_unsupported_schemes() {
+        let mut store = BookmarkStore::default();
+        let summary = import_entries(&mut store, parse_netscape_html(FIREFOX));
+
+        assert_eq!(summary.added, 5);
+        assert_eq!(summary.duplicates, 0);
+        assert_eq!(
+            summary.skipped,
+            [
+                "place:parent=menu________&queryType=1",
+                "javascript:alert(1)"
+            ]
+        );
+        let gemini = store
+            .list()
+            .iter()
+            .find(|b| b.url.starts_with("gemini://"))
+            .unwrap();
+        assert_eq!(gemini.created_at, 1700000007);
+        assert_eq!(gemini.folder.as_deref(), Some("Tech"));
+    }
+
+    #[test]
+    fn test_import_deduplicates_by_normalized_url() {
+        let mut store = BookmarkStore::default();
+        store
+            .add(bookmark("bm_1", "https://news.ycombinator.com/#top"))
+            .unwrap();
+
+        let mut entries = parse_netscape_html(CHROME);
+        entries.push(entries[3].clone());
+        let summary = import_entries(&mut store, entries);
+
+        assert_eq!(summary.added, 2);
+        assert_eq!(summary.duplicates, 2);
+        assert_eq!(summary.skipped, ["chrome://settings/"]);
+        assert_eq!(store.list().len(), 3);
+    }
+
+    #[test]
+    fn test_import_generates_unique_ids() {
+        let mut store = BookmarkStore::default();
+        import_entries(&mut store, parse_netscape_html(FIREFOX));
+
+        let ids: HashSet<_> = store.list().iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(ids.len(), store.list().len());
+    }
+
+    #[test]
+    fn test_decode_entities() {
+        assert_eq!(
+            decode_entities("a &amp; b &#228; &#xE5; &bogus; &"),
+            "a & b ä å &bogus; &"
+        );
+    }
+
+    #[test]
+    fn test_unix_seconds() {
+        assert_eq!(unix_seconds(1_700_000_000), 1_700_000_000);
+        assert_eq!(unix_seconds(1_700_000_000_000), 1_700_000_000);
+        assert_eq!(unix_seconds(1_700_000_000_000_000), 1_700_000_000);
+    }
+
+    fn sample_store() -> BookmarkStore {
+        let mut store = BookmarkStore::default();
+        let entries = vec![
+            ImportedBookmark {
+                title: "Zeta & co".to_string(),
+                url: "https://zeta.example.com/?a=1&b=2".to_string(),
+                folder: None,
+                created_at: Some(1_700_000_000),
+                tags: vec!["lesing".to_string()],
+            },
+            ImportedBookmark {
+                title: "Rust-boka [offisiell]".to_string(),
+                url: "https://doc.rust-lang.org/book/".to_string(),
+                folder: Some("Tech/Rust".to_string()),
+                created_at: Some(1_700_000_001),
+                tags: vec!["rust".to_string(), "docs".to_string()],
+            },
+            ImportedBookmark {
+                title: "Alfa".to_string(),
+                url: "gemini://alfa.example.org/".to_string(),
+                folder: None,
+                created_at: Some(1_700_000_002),
+                tags: Vec::new(),
+            },
+            ImportedBookmark {
+                title: "Go".to_string(),
+                url: "https://go.dev/".to_string(),
+                folder: Some("Tech".to_string()),
+                created_at: Some(1_700_000_003),
+                tags: Vec::new(),
+            },
+        ];
+        import_entries(&mut store, entries);
+        store
+    }
+
+    #[test]
+    fn test_export_html_round_trip() {
+        let store = sample_store();
+        let html = export_netscape_html(&store);
+        let reimported = parse_netscape_html(&html);
+
+        let key = |title: &str, url: &str, folder: Option<&str>, created: u64, tags: &[String]| {
+            (
+                title.to_string(),
+                url.to_string(),
+                folder.map(String::from),
+                created,
+                tags.to_vec(),
+            )
+        };
+        let mut original: Vec<_> = store
+            .list()
+            .iter()
+            .map(|b| key(&b.title, &b.url, b.folder.as_deref(), b.created_at, &b.tags))
+            .collect();
+        let mut roundtrip: Vec<_> = reimported
+            .iter()
+            .map(|e| {
+                key(
+                    &e.title,
+                    &e.url,
+                    e.folder.as_deref(),
+                    e.created_at.unwrap(),
+                    &e.tags,
+                )
+            })
+            .collect();
+        original.sort();
+        roundtrip.sort();
+        assert_eq!(original, roundtrip);
+    }
+
+    #[test]
+    fn test_export_is_deterministic() {
+        let store = sample_store();
+        let mut reversed = store.clone();
+        reversed.bookmarks.reverse();
+
+        assert_eq!(
+            export_netscape_html(&store),
+            export_netscape_html(&reversed)
+        );
+        assert_eq!(export_markdown(&store), export_markdown(&reversed));
+        assert_eq!(
+            export_json(&store).unwrap(),
+            export_json(&reversed).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_export_markdown_grouped_by_folder() {
+        let markdown = export_markdown(&sample_store());
+        let expected = "# Bokmerker\n\n\
+            *Eksportert fra Bare — 4 bokmerker*\n\n\
+            - [Alfa](gemini://alfa.example.org/)\n\
+            - [Zeta & co](https://zeta.example.com/?a=1&b=2)\n\n\
+            ## Tech\n\n\
+            - [Go](https://go.dev/)\n\n\
+            ### Rust\n\n\
+            - [Rust-boka \\[offisiell\\]](https://doc.rust-lang.org/book/)\n";
+        assert_eq!(markdown, expected);
+    }
+
+    #[test]
+    fn test_bookmarks_page_markdown() {
+        let mut store = sample_store();
+        let now = 1_700_000_000 + 3 * 86_400;
+        let go = store
+            .list()
+            .iter()
+            .find(|b| b.title == "Go")
+            .unwrap()
+            .id
+            .clone();
+        store.set_pinned(&go, true).unwrap();
+
+        let markdown = bookmarks_page_markdown(&store, now);
+        let expected = "# Bokmerker\n\n\
+            4 bokmerker i 2 mapper\n\n\
+            ## Festet\n\n\
+            - [Go](https://go.dev/) — go.dev · lagt til for 2 dager siden\n\n\
+            ## Uten mappe\n\n\
+            - [Alfa](gemini://alfa.example.org/) — alfa.example.org · lagt til for 2 dager siden\n\
+            - [Zeta & co](https://zeta.example.com/?a=1&b=2) — zeta.example.com · #lesing · lagt til for 3 dager siden\n\n\
+            ## Tech\n\n\
+            - [Go](https://go.dev/) — go.dev · lagt til for 2 dager siden\n\n\
+            ### Rust\n\n\
+            - [Rust-boka \\[offisiell\\]](https://doc.rust-lang.org/book/) — doc.rust-lang.org · #rust #docs · lagt til for 2 dager siden\n";
+        assert_eq!(markdown, expected);
+    }
+
+    #[test]
+    fn test_bookmarks_page_markdown_empty() {
+        let markdown = bookmarks_page_markdown(&BookmarkStore::default(), 0);
+        assert_eq!(
+            markdown,
+            "# Bokmerker\n\n0 bokmerker i 0 mapper\n\nDu har ingen bokmerker ennå.\n"
+        );
+    }
+
+    #[test]
+    fn test_relative_age() {
+        let day = 86_400;
+        let now = 1_000 * day;
+        assert_eq!(relative_age(0, now), None);
+        assert_eq!(relative_age(now - 60, now).as_deref(), Some("i dag"));
+        assert_eq!(relative_age(now - day, now).as_deref(), Some("i går"));
+        assert_eq!(
+            relative_age(now - 20 * day, now).as_deref(),
+            Some("for 2 uker siden")
+        );
+        assert_eq!(
+            relative_age(now - 400 * day, now).as_deref(),
+            Some("for ett år siden")
+        );
+        assert_eq!(relative_age(now + day, now).as_deref(), Some("i dag"));
+    }
+
+    #[test]
+    fn test_export_gemtext() {
+        let date = chrono::NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let gemtext = export_gemtext(&sample_store(), date);
+        let expected = "# Bokmerker\n\n\
+            Eksportert fra Bare 2026-10-16 — 4 bokmerker\n\n\
+            => gemini://alfa.example.org/ Alfa\n\
+            => https://zeta.example.com/?a=1&b=2 Zeta & co\n\n\
+            ## Tech\n\n\
+            => https://go.dev/ Go\n\n\
+            ## Tech/Rust\n\n\
+            => https://doc.rust-lang.org/book/ Rust-boka [offisiell]\n";
+        assert_eq!(gemtext, expected);
+    }
+
+    #[test]
+    fn test_export_gemtext_round_trip() {
+        let mut store = sample_store();
+        store.bookmarks.push(Bookmark {
+            title: "To\nlinjer".to_string(),
+            ..bookmark("mellomrom", "file:///home/bruker/mine notater.md")
+        });
+        let date = chrono::NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let gemtext = export_gemtext(&store, date);
+
+        let converted = crate::gemtext::gemtext_to_markdown(&gemtext);
+        assert_eq!(converted.title.as_deref(), Some("Bokmerker"));
+        let links: Vec<(String, Option<String>)> = converted
+            .links
+            .into_iter()
+            .map(|l| (l.url, l.text))
+            .collect();
+        assert_eq!(links.len(), 5);
+        assert!(links.contains(&(
+            "https://zeta.example.com/?a=1&b=2".to_string(),
+            Some("Zeta & co".to_string())
+        )));
+        assert!(links.contains(&(
+            "file:///home/bruker/mine%20notater.md".to_string(),
+            Some("To linjer".to_string())
+        )));
+
+        let parsed = parse_gemtext(&gemtext);
+        let mut imported = BookmarkStore::default();
+        let summary = import_entries(&mut imported, parsed);
+        assert_eq!(summary.added, 5);
+        let mut roundtrip: Vec<_> = imported
+            .list()
+            .iter()
+            .map(|b| (b.title.clone(), b.folder.clone()))
+            .collect();
+        roundtrip.sort();
+        assert!(roundtrip.contains(&(
+            "Rust-boka [offisiell]".to_string(),
+            Some("Tech/Rust".to_string())
+        )));
+        assert!(roundtrip.contains(&("Alfa".to_string(), None)));
+    }
+
+    #[test]
+    fn test_parse_gemtext_skips_preformatted_links() {
+        let entries = parse_gemtext(
+            "## Lesing\n=> gemini://a.example.org/\n```\n=> gemini://b.example.org/ B\n```\n# Rot\n=>gemini://c.example.org/ C\n",
+        );
+        let urls: Vec<(&str, Option<&str>)> = entries
+            .iter()
+            .map(|e| (e.url.as_str(), e.folder.as_deref()))
+            .collect();
+        assert_eq!(
+            urls,
+            vec![
+                ("gemini://a.example.org/", Some("Lesing")),
+                ("gemini://c.example.org/", None)
+            ]
+        );
+        assert_eq!(entries[0].title, "");
+        assert_eq!(entries[1].title, "C");
+    }
+
+    #[test]
+    fn test_export_file_json_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("eksport").join("bokmerker.json");
+        let store = sample_store();
+
+        assert_eq!(export_file(&store, &path, BookmarkFormat::Json).unwrap(), 4);
+        assert!(!dir
+            .path()
+            .join("eksport")
+            .join("bokmerker.json.tmp")
+            .exists());
+
+        let mut imported = BookmarkStore::default();
+        let summary = import_file(&mut imported, &path, BookmarkFormat::Json).unwrap();
+        assert_eq!(summary.added, 4);
+        assert!(matches!(
+            import_file(&mut imported, &path, BookmarkFormat::Markdown),
+            Err(BookmarkError::Read(_))
+        ));
+    }
+
+    /// Enkel deterministisk pseudotilfeldig generator for eksempelsamlinger
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self, max: u64) -> u64 {
+            self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1);
+            (self.0 >> 33) % max
+        }
+    }
+
+    fn random_store(rng: &mut Lcg, prefix: &str) -> BookmarkStore {
+        const URLS: [&str; 6] = [
+            "https://example.com/a",
+            "https://example.com/b/",
+            "https://EXAMPLE.com/c#del",
+            "gemini://example.org/",
+            "gopher://example.net:70/1/",
+            "https://example.com/d?q=1",
+        ];
+        const TITLES: [&str; 3] = ["Alfa", "Beta", "Gamma"];
+        const TAGS: [&str; 4] = ["rust", "lesing", "viktig", "gemini"];
+        const FOLDERS: [Option<&str>; 3] = [None, Some("Tech"), Some("Tech/Rust")];
+        const KEYWORDS: [Option<&str>; 4] = [None, Some("rs"), Some("gm"), Some("ex")];
+
+        let mut store = BookmarkStore::default();
+        for (i, url) in URLS.iter().enumerate() {
+            if rng.next(3) == 0 {
+                continue;
+            }
+            let tags = TAGS
+                .iter()
+                .filter(|_| rng.next(2) == 0)
+                .map(|t| t.to_string())
+                .collect();
+            store.bookmarks.push(Bookmark {
+                title: TITLES[rng.next(3) as usize].to_string(),
+                created_at: 100 + rng.next(5),
+                updated_at: 200 + rng.next(3),
+                folder: FOLDERS[rng.next(3) as usize].map(String::from),
+                tags,
+                keyword: KEYWORDS[rng.next(4) as usize].map(String::from),
+                ..bookmark(&format!("{}_{}", prefix, i), url)
+            });
+        }
+        // Nøkkelord er unike innenfor én samling
+        let mut seen = HashSet::new();
+        for bookmark in &mut store.bookmarks {
+            if bookmark
+                .keyword
+                .as_ref()
+                .is_some_and(|k| !seen.insert(k.clone()))
+            {
+                bookmark.keyword = None;
+            }
+        }
+        if rng.next(2) == 0 {
+            store.folders.push("Arkiv".to_string());
+        }
+        store
+    }
+
+    /// Innholdet i en samling uten ID-er og rekkefølge
+    fn canonical(store: &BookmarkStore) -> (Vec<String>, Vec<String>) {
+        let mut entries: Vec<String> = store
+            .list()
+            .iter()
+            .map(|b| {
+                let mut tags = b.tags.clone();
+                tags.sort();
+                format!(
+                    "{} {} {:?} {} {} {:?} {:?}",
+                    bookmarks::normalize_url(&b.url),
+                    b.title,
+                    b.folder,
+                    b.created_at,
+                    b.updated_at,
+                    tags,
+                    b.keyword
+                )
+            })
+            .collect();
+        entries.sort();
+        (entries, store.folders.clone())
+    }
+
+    fn merged(local: &BookmarkStore, remote: &BookmarkStore) -> BookmarkStore {
+        let mut result = local.clone();
+        merge_stores(&mut result, remote);
+        result
+    }
+
+    #[test]
+    fn test_merge_is_commutative_and_idempotent() {
+        let mut rng = Lcg(1147);
+        for _ in 0..50 {
+            let a = random_store(&mut rng, "a");
+            let b = random_store(&mut rng, "b");
+
+            let ab = merged(&a, &b);
+            assert_eq!(canonical(&ab), canonical(&merged(&b, &a)));
+
+            let mut again = ab.clone();
+            let summary = merge_stores(&mut again, &b);
+            assert_eq!(summary.added, 0);
+            assert_eq!(summary.updated, 0);
+            assert_eq!(canonical(&again), canonical(&ab));
+            assert_eq!(canonical(&merged(&ab, &ab)), canonical(&ab));
+        }
+    }
+
+    #[test]
+    fn test_merge_converges_between_machines() {
+        let mut rng = Lcg(42);
+        for _ in 0..50 {
+            let laptop = random_store(&mut rng, "laptop");
+            let desktop = random_store(&mut rng, "desktop");
+
+            // Laptopen henter fra desktop, deretter desktop fra laptopen
+            let laptop = merged(&laptop, &desktop);
+            let desktop = merged(&desktop, &laptop);
+            assert_eq!(canonical(&laptop), canonical(&desktop));
+        }
+    }
+
+    #[test]
+    fn test_merge_summary_and_field_rules() {
+        let mut local = BookmarkStore::default();
+        local.bookmarks.push(Bookmark {
+            title: "Gammel tittel".to_string(),
+            created_at: 300,
+            updated_at: 300,
+            folder: Some("Lesing".to_string()),
+            tags: vec!["rust".to_string()],
+            ..bookmark("bm_1", "https://example.com/side/")
+        });
+
+        let mut remote = BookmarkStore::default();
+        remote.bookmarks.push(Bookmark {
+            title: "Ny tittel".to_string(),
+            created_at: 100,
+            updated_at: 500,
+            tags: vec!["viktig".to_string(), "rust".to_string()],
+            ..bookmark("bm_9", "https://example.com/side#topp")
+        });
+        remote
+            .bookmarks
+            .push(bookmark("bm_1", "gemini://example.org/"));
+
+        let summary = merge_stores(&mut local, &remote);
+        assert_eq!(summary.added, 1);
+        assert_eq!(summary.updated, 1);
+        assert_eq!(
+            summary.conflicts,
+            [MergeConflict {
+                url: "https://example.com/side/".to_string(),
+                local_title: "Gammel tittel".to_string(),
+                remote_title: "Ny tittel".to_string(),
+                kept_title: "Ny tittel".to_string(),
+            }]
+        );
+
+        let merged = &local.list()[0];
+        assert_eq!(merged.id, "bm_1");
+        assert_eq!(merged.url, "https://example.com/side/");
+        assert_eq!(merged.title, "Ny tittel");
+        assert_eq!(merged.folder, None);
+        assert_eq!(merged.created_at, 100);
+        assert_eq!(merged.tags, ["rust", "viktig"]);
+
+        // Kolliderende ID får ny verdi
+        assert_ne!(local.list()[1].id, "bm_1");
+    }
+
+    #[test]
+    fn test_merge_keeps_distinct_gopher_selectors() {
+        let mut local = BookmarkStore::default();
+        local
+            .bookmarks
+            .push(bookmark("bm_1", "gopher://example.org/1/dir/"));
+        let mut remote = BookmarkStore::default();
+        remote
+            .bookmarks
+            .push(bookmark("bm_2", "gopher://example.org/1/dir"));
+
+        let summary = merge_stores(&mut local, &remote);
+        assert_eq!(summary.added, 1);
+        assert_eq!(local.list().len(), 2);
+    }
+
+    #[test]
+    fn test_merge_keywords_follow_latest_copy() {
+        let keyed = |id: &str, url: &str, updated_at: u64, keyword: &str| Bookmark {
+            updated_at,
+            keyword: Some(keyword.to_string()),
+            ..bookmark(id, url)
+        };
+        let docs = "https://docs.rs/";
+        let mut laptop = BookmarkStore::default();
+        laptop.bookmarks.push(keyed("bm_1", docs, 200, "docs"));
+        let mut desktop = BookmarkStore::default();
+        desktop.bookmarks.push(keyed("bm_2", docs, 300, "rs"));
+
+        // Sist endret vinner, uansett hvem som slår sammen
+        let keyword = |store: &BookmarkStore| store.list()[0].keyword.clone();
+        assert_eq!(keyword(&merged(&laptop, &desktop)).as_deref(), Some("rs"));
+        assert_eq!(keyword(&merged(&desktop, &laptop)).as_deref(), Some("rs"));
+
+        // Like gamle kopier: største nøkkelord
+        desktop.bookmarks[0].updated_at = 200;
+        assert_eq!(keyword(&merged(&laptop, &desktop)).as_deref(), Some("rs"));
+        assert_eq!(keyword(&merged(&desktop, &laptop)).as_deref(), Some("rs"));
+
+        // Et nøkkelord som er tatt av et nyere bokmerke, faller tilbake
+        desktop
+            .bookmarks
+            .push(keyed("bm_3", "https://crates.io/", 400, "rs"));
+        let result = merged(&laptop, &desktop);
+        assert_eq!(
+            result.with_keyword("rs").map(|b| b.url.as_str()),
+            Some("https://crates.io/")
+        );
+        assert_eq!(
+            result.with_keyword("docs").map(|b| b.url.as_str()),
+            Some(docs)
+        );
+        assert_eq!(canonical(&result), canonical(&merged(&desktop, &laptop)));
+    }
+
+    #[test]
+    fn test_merge_file_reads_json_and_html() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let json_path = dir.path().join("bookmarks.json");
+        let mut other = BookmarkStore::default();
+        other
+            .bookmarks
+            .push(bookmark("bm_1", "https://example.com/"));
+        other.save(&json_path).unwrap();
+
+        let html_path = dir.path().join("chrome.html");
+        fs::write(&html_path, CHROME).unwrap();
+
+        let mut store = BookmarkStore::default();
+        assert_eq!(merge_file(&mut store, &json_path).unwrap().added, 1);
+        let summary = merge_file(&mut store, &html_path).unwrap();
+        assert!(summary.added > 0);
+
+        let garbage = dir.path().join("tull.txt");
+        fs::write(&garbage, "ikke bokmerker").unwrap();
+        assert!(merge_file(&mut store, &garbage).is_err());
+    }
+}
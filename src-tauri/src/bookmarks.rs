@@ -2,9 +2,15 @@
 //!
 //! Lagrer og henter bokmerker fra JSON-fil.
 
+use crate::locale::{Locale, Localize};
+use crate::search::{self, MatchKind};
+use crate::storage::{self, file_stamp};
 use serde::{Deserialize, Serialize};
-use std::fs;
-use std::path::PathBuf;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
 use thiserror::Error;
 
 /// Feil som kan oppstå ved bokmerke-operasjoner
@@ -21,10 +27,44 @@ pub enum BookmarkError {
 
     #[error("Bokmerke ikke funnet: {0}")]
     NotFound(String),
+
+    #[error("Ugyldig mappenavn: {0}")]
+    InvalidFolder(String),
+
+    #[error("Mappe ikke funnet: {0}")]
+    FolderNotFound(String),
+
+    #[error("Mappe finnes allerede: {0}")]
+    FolderExists(String),
+
+    #[error("Ugyldig nøkkelord: {0} (kun små bokstaver, tall, - og _)")]
+    InvalidKeyword(String),
+
+    #[error("Nøkkelordet er allerede i bruk: {0}")]
+    KeywordInUse(String),
 }
 
+impl Localize for BookmarkError {
+    fn localize_in(&self, locale: Locale) -> String {
+        match self {
+            Self::Read(e) => locale.format("error.bookmarks_read", &[e]),
+            Self::Write(e) => locale.format("error.bookmarks_write", &[e]),
+            Self::AlreadyExists(url) => locale.format("error.bookmark_exists", &[url]),
+            Self::NotFound(id) => locale.format("error.bookmark_not_found", &[id]),
+            Self::InvalidFolder(name) => locale.format("error.invalid_folder", &[name]),
+            Self::FolderNotFound(name) => locale.format("error.folder_not_found", &[name]),
+            Self::FolderExists(name) => locale.format("error.folder_exists", &[name]),
+            Self::InvalidKeyword(keyword) => locale.format("error.invalid_keyword", &[keyword]),
+            Self::KeywordInUse(keyword) => locale.format("error.keyword_in_use", &[keyword]),
+        }
+    }
+}
+
+/// Nåværende versjon av bokmerkefilens format
+const STORE_VERSION: u32 = 2;
+
 /// Et enkelt bokmerke
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct Bookmark {
     /// Unik ID for bokmerket
     pub id: String,
@@ -34,47 +74,273 @@ pub struct Bookmark {
     pub url: String,
     /// Tidspunkt bokmerket ble opprettet (Unix timestamp)
     pub created_at: u64,
+    /// Tidspunkt bokmerket sist ble endret (Unix timestamp, 0 hvis ukjent)
+    #[serde(default)]
+    pub updated_at: u64,
+    /// Mappe bokmerket ligger i, som sti (f.eks. "Tech/Rust"); None er roten
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub folder: Option<String>,
+    /// Emneknagger, normalisert til små bokstaver
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Nøkkelord for adresselinjen (f.eks. "gp"), unikt blant bokmerkene
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keyword: Option<String>,
+    /// Sti til arkivert kopi av siden, hvis lagret
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot_path: Option<String>,
+    /// Festet til verktøylinjen og startsiden
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+}
+
+/// En emneknagg med antall bokmerker
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TagCount {
+    /// Normalisert navn
+    pub name: String,
+    /// Antall bokmerker med emneknaggen
+    pub count: usize,
 }
 
 /// Samling av alle bokmerker
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct BookmarkStore {
+    /// Filformatets versjon (0 for filer fra før versjonering)
+    #[serde(default)]
+    pub version: u32,
     pub bookmarks: Vec<Bookmark>,
+    /// Eksplisitt opprettede mapper, også tomme
+    #[serde(default)]
+    pub folders: Vec<String>,
+    /// Sist brukte sortering, bygges på nytt ved behov
+    #[serde(skip)]
+    sort_cache: RefCell<Option<(BookmarkSort, Vec<usize>)>>,
+    /// Hva som er lagret til fil
+    #[serde(skip)]
+    save_state: RefCell<SaveState>,
+}
+
+/// Forholdet mellom samlingen i minnet og filen
+#[derive(Debug, Clone, Default)]
+struct SaveState {
+    /// ID-er til bokmerker som er lagt til eller endret siden siste lagring
+    unsaved: HashSet<String>,
+    /// Endringstid og størrelse på filen slik vi sist leste eller skrev den
+    written: Option<(SystemTime, u64)>,
+}
+
+/// Sorteringsrekkefølge for bokmerkelister
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BookmarkSort {
+    /// Rekkefølgen bokmerkene ble lagt til i
+    #[default]
+    Manual,
+    /// Tittel alfabetisk (uavhengig av store/små bokstaver)
+    Title,
+    /// Nyeste først
+    Newest,
+    /// Eldste først
+    Oldest,
+    /// Sist endret først
+    Updated,
+    /// URL alfabetisk
+    Url,
+}
+
+/// Filter for bokmerkelister
+#[derive(Debug, Clone, Default)]
+pub struct BookmarkFilter {
+    /// Kun bokmerker i denne mappen (`Some(None)` er roten); alle hvis None
+    pub folder: Option<Option<String>>,
+    /// Ta med bokmerker i undermapper
+    pub recursive: bool,
+    /// Kun bokmerker med denne emneknaggen
+    pub tag: Option<String>,
+}
+
+/// Utfallet av å veksle et bokmerke av og på
+#[derive(Debug, Clone, PartialEq)]
+pub enum Toggle {
+    /// Bokmerket ble lagt til
+    Added(Bookmark),
+    /// Et eksisterende bokmerke for samme URL ble fjernet
+    Removed(Bookmark),
+}
+
+/// Et utsnitt av en bokmerkeliste
+#[derive(Debug)]
+pub struct BookmarkPage<'a> {
+    /// Bokmerkene i utsnittet
+    pub items: Vec<&'a Bookmark>,
+    /// Antall bokmerker som passer filteret, før utsnittet
+    pub total: usize,
 }
 
 impl BookmarkStore {
     /// Last bokmerker fra fil
-    pub fn load(path: &PathBuf) -> Result<Self, BookmarkError> {
-        if !path.exists() {
+    pub fn load(path: &Path) -> Result<Self, BookmarkError> {
+        let Some(mut store) =
+            storage::read_json::<Self>(path).map_err(|e| BookmarkError::Read(e.to_string()))?
+        else {
             return Ok(Self::default());
+        };
+        store.migrate();
+        store.save_state.get_mut().written = file_stamp(path);
+        Ok(store)
+    }
+
+    /// Oppgrader innlastede data til nåværende format
+    ///
+    /// Eldre versjoner lastes med `folder: None` og tomme `tags` via
+    /// serde-standarder; her normaliseres mappestier og emneknagger slik at
+    /// håndredigerte filer også fungerer.
+    fn migrate(&mut self) {
+        if self.version >= STORE_VERSION {
+            return;
         }
 
-        let content = fs::read_to_string(path).map_err(|e| BookmarkError::Read(e.to_string()))?;
+        for bookmark in &mut self.bookmarks {
+            bookmark.folder = bookmark
+                .folder
+                .as_deref()
+                .and_then(|f| normalize_folder(f).ok().flatten());
+            bookmark.tags = normalize_tags(&bookmark.tags);
+        }
+        self.folders = self
+            .folders
+            .iter()
+            .filter_map(|f| normalize_folder(f).ok().flatten())
+            .collect();
+        self.folders.sort();
+        self.folders.dedup();
+        self.version = STORE_VERSION;
+        self.invalidate_sort_cache();
+    }
 
-        serde_json::from_str(&content).map_err(|e| BookmarkError::Read(e.to_string()))
+    /// Glem cachet sortering etter endringer i bokmerkene
+    pub fn invalidate_sort_cache(&mut self) {
+        *self.sort_cache.get_mut() = None;
+    }
+
+    /// Finn et bokmerke for endring
+    fn find_mut(&mut self, id: &str) -> Result<&mut Bookmark, BookmarkError> {
+        self.invalidate_sort_cache();
+        self.save_state.get_mut().unsaved.insert(id.to_string());
+        self.bookmarks
+            .iter_mut()
+            .find(|b| b.id == id)
+            .ok_or_else(|| BookmarkError::NotFound(id.to_string()))
     }
 
     /// Lagre bokmerker til fil
-    pub fn save(&self, path: &PathBuf) -> Result<(), BookmarkError> {
-        // Opprett overordnede mapper hvis de ikke finnes
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent).map_err(|e| BookmarkError::Write(e.to_string()))?;
+    ///
+    /// Skrives atomisk, så en avbrutt lagring etterlater den forrige filen.
+    pub fn save(&self, path: &Path) -> Result<(), BookmarkError> {
+        let mut store = self.clone();
+        store.version = STORE_VERSION;
+        storage::write_json(path, &store).map_err(|e| BookmarkError::Write(e.to_string()))?;
+
+        let mut state = self.save_state.borrow_mut();
+        state.unsaved.clear();
+        state.written = file_stamp(path);
+        Ok(())
+    }
+
+    /// Sjekk om filen er slik vi sist leste eller skrev den
+    ///
+    /// Brukes av filovervåkingen for å se bort fra våre egne lagringer.
+    pub fn is_own_write(&self, path: &Path) -> bool {
+        let written = self.save_state.borrow().written;
+        written.is_some() && written == file_stamp(path)
+    }
+
+    /// Erstatt samlingen med en nyere versjon fra fil
+    ///
+    /// Bokmerker som er lagt til eller endret i minnet uten å være lagret,
+    /// beholdes: de erstatter bokmerket med samme normaliserte URL fra filen,
+    /// eller legges til.
+    ///
+    /// # Returns
+    /// `true` hvis ulagrede endringer ble tatt med, slik at samlingen bør
+    /// lagres igjen
+    pub fn reload(&mut self, disk: BookmarkStore, path: &Path) -> bool {
+        let unsaved = std::mem::take(&mut self.save_state.get_mut().unsaved);
+        let mut merged = disk;
+        let mut kept = HashSet::new();
+
+        for bookmark in self.bookmarks.iter().filter(|b| unsaved.contains(&b.id)) {
+            let key = normalize_url(&bookmark.url);
+            match merged
+                .bookmarks
+                .iter()
+                .position(|b| normalize_url(&b.url) == key)
+            {
+                Some(position) => merged.bookmarks[position] = bookmark.clone(),
+                None => merged.bookmarks.push(bookmark.clone()),
+            }
+            kept.insert(bookmark.id.clone());
         }
 
-        let content =
-            serde_json::to_string_pretty(self).map_err(|e| BookmarkError::Write(e.to_string()))?;
+        let has_unsaved = !kept.is_empty();
+        *merged.save_state.get_mut() = SaveState {
+            unsaved: kept,
+            written: file_stamp(path),
+        };
+        merged.invalidate_sort_cache();
+        *self = merged;
+        has_unsaved
+    }
 
-        fs::write(path, content).map_err(|e| BookmarkError::Write(e.to_string()))
+    /// Lagre bokmerker og behold den forrige filen som rotert sikkerhetskopi
+    ///
+    /// # Arguments
+    /// * `path` - Bokmerkefilen
+    /// * `generations` - Antall sikkerhetskopier som beholdes (0 slår av)
+    pub fn save_with_backups(&self, path: &Path, generations: u32) -> Result<(), BookmarkError> {
+        storage::rotate_backups(path, generations)
+            .map_err(|e| BookmarkError::Write(e.to_string()))?;
+        self.save(path)
+    }
+
+    /// Last en rotert sikkerhetskopi av bokmerkefilen
+    ///
+    /// # Arguments
+    /// * `path` - Bokmerkefilen
+    /// * `generation` - Hvilken kopi (1 er den nyeste)
+    pub fn load_backup(path: &Path, generation: u32) -> Result<Self, BookmarkError> {
+        let backup = storage::generation_path(path, generation);
+        if !backup.exists() {
+            return Err(BookmarkError::Read(format!(
+                "Fant ingen sikkerhetskopi nummer {}",
+                generation
+            )));
+        }
+        let mut store: Self =
+            storage::parse_file(&backup).map_err(|e| BookmarkError::Read(e.to_string()))?;
+        store.migrate();
+        Ok(store)
     }
 
     /// Legg til et nytt bokmerke
     pub fn add(&mut self, bookmark: Bookmark) -> Result<(), BookmarkError> {
-        // Sjekk om bokmerket allerede finnes (basert på URL)
-        if self.bookmarks.iter().any(|b| b.url == bookmark.url) {
+        // Sjekk om bokmerket allerede finnes (basert på normalisert URL)
+        if self.is_bookmarked(&bookmark.url) {
             return Err(BookmarkError::AlreadyExists(bookmark.url));
         }
+        if let Some(keyword) = &bookmark.keyword {
+            if self.with_keyword(keyword).is_some() {
+                return Err(BookmarkError::KeywordInUse(keyword.clone()));
+            }
+        }
 
+        self.save_state
+            .get_mut()
+            .unsaved
+            .insert(bookmark.id.clone());
         self.bookmarks.push(bookmark);
+        self.invalidate_sort_cache();
         Ok(())
     }
 
@@ -82,6 +348,7 @@ impl BookmarkStore {
     pub fn remove(&mut self, id: &str) -> Result<(), BookmarkError> {
         let original_len = self.bookmarks.len();
         self.bookmarks.retain(|b| b.id != id);
+        self.invalidate_sort_cache();
 
         if self.bookmarks.len() == original_len {
             return Err(BookmarkError::NotFound(id.to_string()));
@@ -90,32 +357,666 @@ impl BookmarkStore {
         Ok(())
     }
 
+    /// Fjern bokmerket for en URL (sammenlignet etter `normalize_url`)
+    pub fn remove_url(&mut self, url: &str) -> Option<Bookmark> {
+        let url = normalize_url(url);
+        let position = self
+            .bookmarks
+            .iter()
+            .position(|b| normalize_url(&b.url) == url)?;
+        self.invalidate_sort_cache();
+        Some(self.bookmarks.remove(position))
+    }
+
+    /// Legg til bokmerket, eller fjern det hvis URL-en allerede er bokmerket
+    ///
+    /// Begge deler skjer i samme kall, så to vekslinger etter hverandre
+    /// alltid går tilbake til utgangspunktet.
+    pub fn toggle(&mut self, bookmark: Bookmark) -> Result<Toggle, BookmarkError> {
+        if let Some(removed) = self.remove_url(&bookmark.url) {
+            return Ok(Toggle::Removed(removed));
+        }
+        self.add(bookmark.clone())?;
+        Ok(Toggle::Added(bookmark))
+    }
+
+    /// Hent bokmerket for en URL (sammenlignet etter `normalize_url`)
+    pub fn get_by_url(&self, url: &str) -> Option<&Bookmark> {
+        let url = normalize_url(url);
+        self.bookmarks.iter().find(|b| normalize_url(&b.url) == url)
+    }
+
     /// Hent alle bokmerker
     pub fn list(&self) -> &[Bookmark] {
         &self.bookmarks
     }
 
+    /// Hent et bokmerke basert på ID
+    pub fn get(&self, id: &str) -> Option<&Bookmark> {
+        self.bookmarks.iter().find(|b| b.id == id)
+    }
+
+    /// Registrer (eller fjern) stien til en arkivert kopi
+    pub fn set_snapshot(
+        &mut self,
+        id: &str,
+        path: Option<String>,
+    ) -> Result<&Bookmark, BookmarkError> {
+        let bookmark = self.find_mut(id)?;
+        bookmark.snapshot_path = path;
+        Ok(bookmark)
+    }
+
+    /// Fest eller løsne et bokmerke
+    pub fn set_pinned(&mut self, id: &str, pinned: bool) -> Result<&Bookmark, BookmarkError> {
+        let bookmark = self.find_mut(id)?;
+        if bookmark.pinned != pinned {
+            bookmark.pinned = pinned;
+            bookmark.updated_at = current_timestamp();
+        }
+        Ok(bookmark)
+    }
+
+    /// Hent festede bokmerker i manuell rekkefølge
+    pub fn pinned(&self) -> Vec<&Bookmark> {
+        self.bookmarks.iter().filter(|b| b.pinned).collect()
+    }
+
+    /// Endre rekkefølgen på de festede bokmerkene
+    ///
+    /// De festede bokmerkene bytter plass seg imellom; resten av listen
+    /// beholder rekkefølgen sin. Festede bokmerker som ikke er nevnt i `ids`
+    /// kommer etter de som er det, i samme innbyrdes rekkefølge som før.
+    ///
+    /// # Arguments
+    /// * `ids` - ID-ene til festede bokmerker i ønsket rekkefølge
+    pub fn reorder_pinned(&mut self, ids: &[String]) -> Result<(), BookmarkError> {
+        for id in ids {
+            if !self.get(id).is_some_and(|b| b.pinned) {
+                return Err(BookmarkError::NotFound(id.clone()));
+            }
+        }
+
+        let slots: Vec<usize> = (0..self.bookmarks.len())
+            .filter(|&i| self.bookmarks[i].pinned)
+            .collect();
+        let mut pinned: Vec<Bookmark> = slots.iter().map(|&i| self.bookmarks[i].clone()).collect();
+        pinned.sort_by_key(|b| ids.iter().position(|id| *id == b.id).unwrap_or(ids.len()));
+        for (slot, bookmark) in slots.into_iter().zip(pinned) {
+            self.bookmarks[slot] = bookmark;
+        }
+        self.invalidate_sort_cache();
+        Ok(())
+    }
+
     /// Sjekk om en URL er bokmerket
+    ///
+    /// URL-ene sammenlignes etter `normalize_url`, slik at f.eks. fragment og
+    /// avsluttende skråstrek ikke spiller inn.
     pub fn is_bookmarked(&self, url: &str) -> bool {
-        self.bookmarks.iter().any(|b| b.url == url)
+        self.get_by_url(url).is_some()
+    }
+
+    /// Hent bokmerker i en mappe
+    ///
+    /// # Arguments
+    /// * `folder` - Mappesti, eller None for roten
+    /// * `recursive` - Ta med bokmerker i undermapper
+    pub fn in_folder(&self, folder: Option<&str>, recursive: bool) -> Vec<&Bookmark> {
+        self.bookmarks
+            .iter()
+            .filter(|b| is_in_folder(b, folder, recursive))
+            .collect()
+    }
+
+    /// Hent et filtrert og sortert utsnitt av bokmerkene
+    ///
+    /// Sorteringen caches til bokmerkene endres, så bla gjennom sider i
+    /// en stor samling sorterer ikke på nytt for hvert kall.
+    ///
+    /// # Arguments
+    /// * `filter` - Mappe- og emneknaggfilter
+    /// * `sort` - Sorteringsrekkefølge
+    /// * `offset` - Antall treff som hoppes over
+    /// * `limit` - Maksimalt antall treff (alle hvis None)
+    pub fn page(
+        &self,
+        filter: &BookmarkFilter,
+        sort: BookmarkSort,
+        offset: usize,
+        limit: Option<usize>,
+    ) -> BookmarkPage<'_> {
+        let tag = filter.tag.as_deref().map(|t| t.trim().to_lowercase());
+        let matches = |b: &Bookmark| {
+            let in_folder = match &filter.folder {
+                Some(folder) => is_in_folder(b, folder.as_deref(), filter.recursive),
+                None => true,
+            };
+            let has_tag = match &tag {
+                Some(tag) => b.tags.iter().any(|t| t.to_lowercase() == *tag),
+                None => true,
+            };
+            in_folder && has_tag
+        };
+
+        let order = self.sorted(sort);
+        let mut total = 0;
+        let mut items = Vec::new();
+        for &index in order.iter() {
+            let bookmark = &self.bookmarks[index];
+            if !matches(bookmark) {
+                continue;
+            }
+            if total >= offset && limit.is_none_or(|limit| items.len() < limit) {
+                items.push(bookmark);
+            }
+            total += 1;
+        }
+
+        BookmarkPage { items, total }
+    }
+
+    /// Indeksene til bokmerkene i sortert rekkefølge
+    fn sorted(&self, sort: BookmarkSort) -> std::cell::Ref<'_, Vec<usize>> {
+        let stale = !matches!(
+            &*self.sort_cache.borrow(),
+            Some((cached, order)) if *cached == sort && order.len() == self.bookmarks.len()
+        );
+        if stale {
+            let mut order: Vec<usize> = (0..self.bookmarks.len()).collect();
+            let b = &self.bookmarks;
+            match sort {
+                BookmarkSort::Manual => {}
+                BookmarkSort::Title => {
+                    let titles: Vec<String> = b.iter().map(|b| b.title.to_lowercase()).collect();
+                    order.sort_by(|&x, &y| titles[x].cmp(&titles[y]));
+                }
+                BookmarkSort::Newest => order.sort_by_key(|&i| std::cmp::Reverse(b[i].created_at)),
+                BookmarkSort::Oldest => order.sort_by_key(|&i| b[i].created_at),
+                BookmarkSort::Updated => order.sort_by_key(|&i| std::cmp::Reverse(b[i].updated_at)),
+                BookmarkSort::Url => order.sort_by(|&x, &y| b[x].url.cmp(&b[y].url)),
+            }
+            *self.sort_cache.borrow_mut() = Some((sort, order));
+        }
+
+        std::cell::Ref::map(self.sort_cache.borrow(), |cache| {
+            &cache.as_ref().expect("sortering er nettopp bygget").1
+        })
+    }
+
+    /// Oppdater tittel og/eller emneknagger for et bokmerke
+    ///
+    /// # Arguments
+    /// * `id` - Bokmerke-ID
+    /// * `title` - Ny tittel (uendret hvis None)
+    /// * `tags` - Nye emneknagger (uendret hvis None)
+    pub fn update(
+        &mut self,
+        id: &str,
+        title: Option<String>,
+        tags: Option<&[String]>,
+    ) -> Result<&Bookmark, BookmarkError> {
+        let bookmark = self.find_mut(id)?;
+
+        if let Some(title) = title {
+            bookmark.title = title;
+        }
+        if let Some(tags) = tags {
+            bookmark.tags = normalize_tags(tags);
+        }
+        bookmark.updated_at = current_timestamp();
+        Ok(bookmark)
+    }
+
+    /// Sett eller fjern nøkkelordet for et bokmerke
+    ///
+    /// # Arguments
+    /// * `id` - Bokmerke-ID
+    /// * `keyword` - Nytt nøkkelord; tom streng fjerner det
+    pub fn set_keyword(&mut self, id: &str, keyword: &str) -> Result<&Bookmark, BookmarkError> {
+        let keyword = normalize_keyword(keyword)?;
+        if let Some(keyword) = &keyword {
+            if self.with_keyword(keyword).is_some_and(|b| b.id != id) {
+                return Err(BookmarkError::KeywordInUse(keyword.clone()));
+            }
+        }
+
+        let bookmark = self.find_mut(id)?;
+        bookmark.keyword = keyword;
+        bookmark.updated_at = current_timestamp();
+        Ok(bookmark)
+    }
+
+    /// Finn bokmerket med et gitt nøkkelord
+    pub fn with_keyword(&self, keyword: &str) -> Option<&Bookmark> {
+        self.bookmarks
+            .iter()
+            .find(|b| b.keyword.as_deref() == Some(keyword))
+    }
+
+    /// Gjør adresselinje-input med nøkkelord om til en URL
+    ///
+    /// Første ord slås opp som nøkkelord. Resten av teksten settes inn der
+    /// bokmerkets URL har `%s`, prosent-kodet. "rs docs" med nøkkelordet "rs"
+    /// og URL-en `https://docs.rs/%s` gir `https://docs.rs/docs`.
+    ///
+    /// # Returns
+    /// URL-en, eller None hvis første ord ikke er et nøkkelord
+    pub fn resolve_keyword(&self, input: &str) -> Option<String> {
+        let input = input.trim();
+        let (first, rest) = input
+            .split_once(char::is_whitespace)
+            .map_or((input, ""), |(first, rest)| (first, rest.trim()));
+        let bookmark = self.with_keyword(&first.to_lowercase())?;
+
+        if bookmark.url.contains("%s") {
+            Some(bookmark.url.replace("%s", &percent_encode(rest)))
+        } else {
+            Some(bookmark.url.clone())
+        }
+    }
+
+    /// Hent bokmerker med en gitt emneknagg (uavhengig av store/små bokstaver)
+    pub fn with_tag(&self, tag: &str) -> Vec<&Bookmark> {
+        let tag = tag.trim().to_lowercase();
+        self.bookmarks
+            .iter()
+            .filter(|b| b.tags.iter().any(|t| t.to_lowercase() == tag))
+            .collect()
+    }
+
+    /// Alle emneknagger med antall bokmerker, sortert etter navn
+    pub fn tags(&self) -> Vec<TagCount> {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for tag in self.bookmarks.iter().flat_map(|b| &b.tags) {
+            *counts.entry(tag).or_default() += 1;
+        }
+        counts
+            .into_iter()
+            .map(|(name, count)| TagCount {
+                name: name.to_string(),
+                count,
+            })
+            .collect()
+    }
+
+    /// Søk i bokmerker etter tittel, URL, emneknagger og mappe
+    ///
+    /// Søket skiller ikke mellom store og små bokstaver. Treff i tittelen
+    /// rangeres over treff i URL-en, og treff i starten av et felt over
+    /// treff midt i.
+    ///
+    /// # Arguments
+    /// * `query` - Søketekst; tomt søk gir ingen treff
+    /// * `limit` - Maksimalt antall treff
+    ///
+    /// # Returns
+    /// Treff sortert etter rangering, deretter tittel
+    pub fn search(&self, query: &str, limit: usize) -> Vec<&Bookmark> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let mut ranked: Vec<(SearchRank, String, &Bookmark)> = self
+            .bookmarks
+            .iter()
+            .filter_map(|b| {
+                let rank = search_rank(b, &query)?;
+                Some((rank, b.title.to_lowercase(), b))
+            })
+            .collect();
+        ranked.sort_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+        ranked.into_iter().take(limit).map(|(_, _, b)| b).collect()
+    }
+
+    /// Alle kjente mapper, inkludert overordnede mapper, sortert
+    pub fn folders(&self) -> Vec<String> {
+        let mut folders: Vec<String> = self
+            .folders
+            .iter()
+            .map(String::as_str)
+            .chain(self.bookmarks.iter().filter_map(|b| b.folder.as_deref()))
+            .flat_map(folder_with_ancestors)
+            .collect();
+        folders.sort();
+        folders.dedup();
+        folders
+    }
+
+    /// Opprett en (tom) mappe
+    ///
+    /// # Returns
+    /// Den normaliserte mappestien
+    pub fn create_folder(&mut self, path: &str) -> Result<String, BookmarkError> {
+        let folder = normalize_folder(path)?
+            .ok_or_else(|| BookmarkError::InvalidFolder(path.to_string()))?;
+        if self.folders().contains(&folder) {
+            return Err(BookmarkError::FolderExists(folder));
+        }
+
+        self.folders.push(folder.clone());
+        self.folders.sort();
+        Ok(folder)
+    }
+
+    /// Gi nytt navn til eller flytt en mappe, inkludert undermapper
+    ///
+    /// # Returns
+    /// Den normaliserte nye mappestien
+    pub fn rename_folder(&mut self, from: &str, to: &str) -> Result<String, BookmarkError> {
+        let from = normalize_folder(from)?
+            .ok_or_else(|| BookmarkError::InvalidFolder(from.to_string()))?;
+        let to =
+            normalize_folder(to)?.ok_or_else(|| BookmarkError::InvalidFolder(to.to_string()))?;
+
+        if !self.folders().contains(&from) {
+            return Err(BookmarkError::FolderNotFound(from));
+        }
+        if from == to {
+            return Ok(to);
+        }
+        if is_same_or_inside(&to, &from) {
+            return Err(BookmarkError::InvalidFolder(to));
+        }
+        if self.folders().contains(&to) {
+            return Err(BookmarkError::FolderExists(to));
+        }
+
+        for folder in &mut self.folders {
+            if let Some(renamed) = replace_folder_prefix(folder, &from, &to) {
+                *folder = renamed;
+            }
+        }
+        self.invalidate_sort_cache();
+        for bookmark in &mut self.bookmarks {
+            if let Some(renamed) = bookmark
+                .folder
+                .as_deref()
+                .and_then(|f| replace_folder_prefix(f, &from, &to))
+            {
+                bookmark.folder = Some(renamed);
+            }
+        }
+        self.folders.sort();
+        self.folders.dedup();
+        Ok(to)
+    }
+
+    /// Flytt et bokmerke til en annen mappe
+    ///
+    /// # Arguments
+    /// * `id` - Bokmerke-ID
+    /// * `folder` - Ny mappesti, eller None for roten
+    pub fn move_bookmark(&mut self, id: &str, folder: Option<&str>) -> Result<(), BookmarkError> {
+        let folder = match folder {
+            Some(path) => normalize_folder(path)?,
+            None => None,
+        };
+        let bookmark = self.find_mut(id)?;
+
+        bookmark.folder = folder;
+        bookmark.updated_at = current_timestamp();
+        Ok(())
+    }
+
+    /// Fjern en mappe og alle undermapper
+    ///
+    /// # Arguments
+    /// * `path` - Mappesti
+    /// * `cascade` - Slett bokmerkene i mappen; ellers flyttes de til roten
+    ///
+    /// # Returns
+    /// Antall bokmerker som ble slettet eller flyttet
+    pub fn remove_folder(&mut self, path: &str, cascade: bool) -> Result<usize, BookmarkError> {
+        let folder = normalize_folder(path)?
+            .ok_or_else(|| BookmarkError::InvalidFolder(path.to_string()))?;
+        if !self.folders().contains(&folder) {
+            return Err(BookmarkError::FolderNotFound(folder));
+        }
+
+        self.folders.retain(|f| !is_same_or_inside(f, &folder));
+
+        let inside = |b: &Bookmark| {
+            b.folder
+                .as_deref()
+                .is_some_and(|f| is_same_or_inside(f, &folder))
+        };
+        let affected = self.bookmarks.iter().filter(|b| inside(b)).count();
+        self.invalidate_sort_cache();
+        if cascade {
+            self.bookmarks.retain(|b| !inside(b));
+        } else {
+            for bookmark in self.bookmarks.iter_mut().filter(|b| inside(b)) {
+                bookmark.folder = None;
+            }
+        }
+
+        Ok(affected)
     }
 }
 
-/// Hent stien til bokmerke-filen
-pub fn get_bookmarks_path() -> PathBuf {
-    // Bruk brukerens config-mappe
-    let config_dir = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
-    config_dir.join("bare").join("bookmarks.json")
+/// Sjekk om et bokmerke ligger i en mappe
+///
+/// # Arguments
+/// * `folder` - Mappesti, eller None for roten
+/// * `recursive` - Regn med undermapper (med None: alle bokmerker)
+fn is_in_folder(bookmark: &Bookmark, folder: Option<&str>, recursive: bool) -> bool {
+    match (folder, bookmark.folder.as_deref()) {
+        (None, _) if recursive => true,
+        (None, current) => current.is_none(),
+        (Some(wanted), Some(current)) if recursive => is_same_or_inside(current, wanted),
+        (Some(wanted), Some(current)) => current == wanted,
+        (Some(_), None) => false,
+    }
+}
+
+/// Normaliser en mappesti
+///
+/// Segmentene trimmes og tomme segmenter fjernes, så " Tech//Rust/ " blir
+/// "Tech/Rust". En tom sti betyr roten og gir None.
+///
+/// # Returns
+/// Normalisert sti, None for roten, eller InvalidFolder ved ugyldige tegn
+pub fn normalize_folder(path: &str) -> Result<Option<String>, BookmarkError> {
+    let segments: Vec<&str> = path
+        .split('/')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    if segments
+        .iter()
+        .any(|s| *s == "." || *s == ".." || s.chars().any(char::is_control))
+    {
+        return Err(BookmarkError::InvalidFolder(path.to_string()));
+    }
+
+    if segments.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(segments.join("/")))
+    }
+}
+
+/// Normaliser emneknagger: trimmet, små bokstaver, uten tomme og duplikater
+pub fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+/// Maksimal lengde på et nøkkelord
+const MAX_KEYWORD_LENGTH: usize = 32;
+
+/// Normaliser et nøkkelord: trim og små bokstaver
+///
+/// # Returns
+/// None for tom streng, ellers nøkkelordet eller InvalidKeyword hvis det
+/// inneholder annet enn bokstaver, tall, `-` og `_`
+pub fn normalize_keyword(keyword: &str) -> Result<Option<String>, BookmarkError> {
+    let keyword = keyword.trim().to_lowercase();
+    if keyword.is_empty() {
+        return Ok(None);
+    }
+    let valid = keyword.chars().count() <= MAX_KEYWORD_LENGTH
+        && keyword
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(BookmarkError::InvalidKeyword(keyword));
+    }
+    Ok(Some(keyword))
 }
 
+/// Prosent-koder alt unntatt ureserverte tegn (RFC 3986)
+pub fn percent_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Sjekk om `folder` er lik `ancestor` eller ligger under den
+fn is_same_or_inside(folder: &str, ancestor: &str) -> bool {
+    folder == ancestor
+        || folder
+            .strip_prefix(ancestor)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Bytt ut prefikset `from` med `to` hvis mappen ligger under `from`
+fn replace_folder_prefix(folder: &str, from: &str, to: &str) -> Option<String> {
+    if is_same_or_inside(folder, from) {
+        Some(format!("{}{}", to, &folder[from.len()..]))
+    } else {
+        None
+    }
+}
+
+/// En mappe og alle dens overordnede mapper ("A/B" → ["A", "A/B"])
+fn folder_with_ancestors(folder: &str) -> Vec<String> {
+    folder
+        .match_indices('/')
+        .map(|(index, _)| folder[..index].to_string())
+        .chain(std::iter::once(folder.to_string()))
+        .collect()
+}
+
+/// Normaliserer en URL for sammenligning
+///
+/// Skjema og vertsnavn gjøres om til små bokstaver, standardporter og
+/// fragmenter fjernes, og en avsluttende skråstrek fjernes når URL-en ikke
+/// har spørring. Spørringen beholdes uendret. Gjelder også gemini:// og
+/// gopher://, som `url`-craten ellers lar være i fred. For gopher:// er
+/// selektoren ugjennomsiktig, så bare skråstreken etter verten fjernes der.
+///
+/// # Returns
+/// Normalisert URL, eller input uten omkringliggende mellomrom hvis den ikke
+/// kan parses
+pub fn normalize_url(url: &str) -> String {
+    let url = url.trim();
+    let Ok(mut parsed) = url::Url::parse(url) else {
+        return url.to_string();
+    };
+
+    parsed.set_fragment(None);
+
+    let default_port = match parsed.scheme() {
+        "gemini" => Some(1965),
+        "gopher" => Some(70),
+        _ => None,
+    };
+    if default_port.is_some() && parsed.port() == default_port {
+        let _ = parsed.set_port(None);
+    }
+    if let Some(host) = parsed.host_str() {
+        let lower = host.to_lowercase();
+        if lower != host {
+            let _ = parsed.set_host(Some(&lower));
+        }
+    }
+
+    let mut normalized = parsed.to_string();
+    let opaque_path = parsed.scheme() == "gopher" && parsed.path() != "/";
+    if parsed.query().is_none() && !opaque_path && normalized.ends_with('/') {
+        normalized.pop();
+    }
+    normalized
+}
+
+/// Hvor godt et bokmerke passer et søk; lavere er bedre
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SearchRank {
+    TitlePrefix,
+    TitleInfix,
+    TagPrefix,
+    UrlPrefix,
+    UrlInfix,
+    Other,
+    TitleFuzzy,
+}
+
+/// Rangerer et bokmerke mot et søk som allerede er i små bokstaver
+fn search_rank(bookmark: &Bookmark, query: &str) -> Option<SearchRank> {
+    let title = search::find(&bookmark.title, query).map(|m| m.kind);
+    match title {
+        Some(MatchKind::Prefix) => return Some(SearchRank::TitlePrefix),
+        Some(MatchKind::Substring) => return Some(SearchRank::TitleInfix),
+        _ => {}
+    }
+    if bookmark.tags.iter().any(|t| t.starts_with(query)) {
+        return Some(SearchRank::TagPrefix);
+    }
+
+    // "https://www.example.com" skal gi prefiks-treff på "example"
+    match search::find_in_url(&normalize_url(&bookmark.url), query).map(|m| m.kind) {
+        Some(MatchKind::Prefix) => return Some(SearchRank::UrlPrefix),
+        Some(MatchKind::Substring) => return Some(SearchRank::UrlInfix),
+        _ => {}
+    }
+
+    let folder_matches = bookmark
+        .folder
+        .as_deref()
+        .is_some_and(|f| f.to_lowercase().contains(query));
+    if folder_matches || bookmark.tags.iter().any(|t| t.contains(query)) {
+        return Some(SearchRank::Other);
+    }
+    // Uskarpe treff bare i tittelen; i adresser treffer de for lett
+    title.map(|_| SearchRank::TitleFuzzy)
+}
+
+/// Siste tidsstempel brukt i en bokmerke-ID
+static LAST_ID: AtomicU64 = AtomicU64::new(0);
+
 /// Generer en unik ID for et bokmerke
+///
+/// Bygger på millisekunder siden epoken, men teller opp ved flere kall i
+/// samme millisekund (f.eks. ved import) slik at ID-ene forblir unike.
 pub fn generate_id() -> String {
     use std::time::{SystemTime, UNIX_EPOCH};
-    let timestamp = SystemTime::now()
+    let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default()
-        .as_millis();
-    format!("bm_{}", timestamp)
+        .as_millis() as u64;
+    let previous = LAST_ID
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| {
+            Some(now.max(last + 1))
+        })
+        .unwrap_or(now);
+    format!("bm_{}", now.max(previous + 1))
 }
 
 /// Hent nåværende Unix timestamp
@@ -130,6 +1031,7 @@ pub fn current_timestamp() -> u64 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs;
     use tempfile::tempdir;
 
     fn create_test_bookmark(url: &str, title: &str) -> Bookmark {
@@ -138,6 +1040,27 @@ mod tests {
             title: title.to_string(),
             url: url.to_string(),
             created_at: current_timestamp(),
+            updated_at: 0,
+            folder: None,
+            tags: Vec::new(),
+            keyword: None,
+            snapshot_path: None,
+            pinned: false,
+        }
+    }
+
+    fn bookmark_in(id: &str, folder: Option<&str>) -> Bookmark {
+        Bookmark {
+            id: id.to_string(),
+            title: id.to_string(),
+            url: format!("https://example.com/{}", id),
+            created_at: 0,
+            updated_at: 0,
+            folder: folder.map(String::from),
+            tags: Vec::new(),
+            keyword: None,
+            snapshot_path: None,
+            pinned: false,
         }
     }
 
@@ -200,4 +1123,740 @@ mod tests {
         assert_eq!(loaded.bookmarks.len(), 1);
         assert_eq!(loaded.bookmarks[0].url, "https://example.com");
     }
+
+    #[test]
+    fn test_load_legacy_file_without_folders() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("bookmarks.json");
+        fs::write(
+            &path,
+            r#"{"bookmarks":[{"id":"bm_1","title":"Eksempel","url":"https://example.com","created_at":1700000000}]}"#,
+        )
+        .unwrap();
+
+        let store = BookmarkStore::load(&path).unwrap();
+        assert_eq!(store.version, STORE_VERSION);
+        assert_eq!(store.bookmarks[0].folder, None);
+        assert!(store.folders.is_empty());
+    }
+
+    #[test]
+    fn test_normalize_folder() {
+        assert_eq!(
+            normalize_folder(" Tech//Rust/ ").unwrap(),
+            Some("Tech/Rust".to_string())
+        );
+        assert_eq!(normalize_folder("/").unwrap(), None);
+        assert!(normalize_folder("Tech/../Hemmelig").is_err());
+    }
+
+    #[test]
+    fn test_nested_folders_and_filtering() {
+        let mut store = BookmarkStore::default();
+        store.add(bookmark_in("a", Some("Tech/Rust"))).unwrap();
+        store.add(bookmark_in("b", Some("Tech"))).unwrap();
+        store.add(bookmark_in("c", None)).unwrap();
+        store.add(bookmark_in("d", Some("Technology"))).unwrap();
+        store.create_folder("Lesing/Senere").unwrap();
+
+        assert_eq!(
+            store.folders(),
+            ["Lesing", "Lesing/Senere", "Tech", "Tech/Rust", "Technology"]
+        );
+
+        let ids = |list: Vec<&Bookmark>| list.iter().map(|b| b.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(store.in_folder(Some("Tech"), false)), ["b"]);
+        assert_eq!(ids(store.in_folder(Some("Tech"), true)), ["a", "b"]);
+        assert_eq!(ids(store.in_folder(None, false)), ["c"]);
+        assert_eq!(store.in_folder(None, true).len(), 4);
+
+        assert!(matches!(
+            store.create_folder("Tech/"),
+            Err(BookmarkError::FolderExists(_))
+        ));
+    }
+
+    #[test]
+    fn test_rename_folder_moves_subfolders() {
+        let mut store = BookmarkStore::default();
+        store.add(bookmark_in("a", Some("Tech/Rust"))).unwrap();
+        store.add(bookmark_in("b", Some("Technology"))).unwrap();
+        store.create_folder("Tech/Go").unwrap();
+
+        store.rename_folder("Tech", "Programmering").unwrap();
+        assert_eq!(
+            store.bookmarks[0].folder.as_deref(),
+            Some("Programmering/Rust")
+        );
+        assert_eq!(store.bookmarks[1].folder.as_deref(), Some("Technology"));
+        assert!(store.folders().contains(&"Programmering/Go".to_string()));
+        assert!(matches!(
+            store.rename_folder("Programmering", "Programmering/Under"),
+            Err(BookmarkError::InvalidFolder(_))
+        ));
+        assert!(matches!(
+            store.rename_folder("Finnes/Ikke", "X"),
+            Err(BookmarkError::FolderNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_move_bookmark() {
+        let mut store = BookmarkStore::default();
+        store.add(bookmark_in("a", None)).unwrap();
+
+        store.move_bookmark("a", Some("Nytt/Sted")).unwrap();
+        assert_eq!(store.bookmarks[0].folder.as_deref(), Some("Nytt/Sted"));
+        store.move_bookmark("a", None).unwrap();
+        assert_eq!(store.bookmarks[0].folder, None);
+        assert!(matches!(
+            store.move_bookmark("mangler", None),
+            Err(BookmarkError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_remove_folder_orphans_bookmarks() {
+        let mut store = BookmarkStore::default();
+        store.add(bookmark_in("a", Some("Tech/Rust"))).unwrap();
+        store.add(bookmark_in("b", Some("Tech"))).unwrap();
+        store.add(bookmark_in("c", Some("Annet"))).unwrap();
+
+        assert_eq!(store.remove_folder("Tech", false).unwrap(), 2);
+        assert_eq!(store.bookmarks.len(), 3);
+        assert_eq!(store.in_folder(None, false).len(), 2);
+        assert_eq!(store.folders(), ["Annet"]);
+    }
+
+    #[test]
+    fn test_remove_folder_cascades() {
+        let mut store = BookmarkStore::default();
+        store.add(bookmark_in("a", Some("Tech/Rust"))).unwrap();
+        store.add(bookmark_in("b", Some("Tech"))).unwrap();
+        store.add(bookmark_in("c", Some("Annet"))).unwrap();
+        store.create_folder("Tech/Tom").unwrap();
+
+        assert_eq!(store.remove_folder("Tech", true).unwrap(), 2);
+        assert_eq!(store.bookmarks.len(), 1);
+        assert_eq!(store.folders(), ["Annet"]);
+    }
+
+    fn tagged(id: &str, tags: &[&str]) -> Bookmark {
+        Bookmark {
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..bookmark_in(id, None)
+        }
+    }
+
+    #[test]
+    fn test_load_pre_tags_fixture() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("bookmarks.json");
+        fs::write(&path, include_str!("../fixtures/bookmarks/pre_tags.json")).unwrap();
+
+        let store = BookmarkStore::load(&path).unwrap();
+        assert_eq!(store.version, STORE_VERSION);
+        assert_eq!(store.bookmarks.len(), 2);
+        assert!(store.bookmarks.iter().all(|b| b.tags.is_empty()));
+        assert_eq!(store.bookmarks[1].folder.as_deref(), Some("Tech/Rust"));
+
+        // Tomme emneknagger skrives ikke til fil
+        store.save(&path).unwrap();
+        assert!(!fs::read_to_string(&path).unwrap().contains("tags"));
+    }
+
+    #[test]
+    fn test_normalize_tags() {
+        let tags = ["  Rust ", "rust", "", "Nettleser"].map(String::from);
+        assert_eq!(normalize_tags(&tags), ["rust", "nettleser"]);
+    }
+
+    #[test]
+    fn test_update_bookmark_tags() {
+        let mut store = BookmarkStore::default();
+        store.add(bookmark_in("a", None)).unwrap();
+
+        let tags = [" Lesing ".to_string(), "VIKTIG".to_string()];
+        let updated = store.update("a", None, Some(&tags)).unwrap();
+        assert_eq!(updated.tags, ["lesing", "viktig"]);
+        assert_eq!(updated.title, "a");
+
+        let updated = store.update("a", Some("Ny".to_string()), None).unwrap();
+        assert_eq!(updated.title, "Ny");
+        assert_eq!(updated.tags.len(), 2);
+    }
+
+    #[test]
+    fn test_tag_filtering_and_counts() {
+        let mut store = BookmarkStore::default();
+        store.add(tagged("a", &["rust", "lesing"])).unwrap();
+        store.add(tagged("b", &["rust"])).unwrap();
+        store.add(tagged("c", &[])).unwrap();
+
+        assert_eq!(store.with_tag("RUST").len(), 2);
+        assert_eq!(store.with_tag(" lesing ").len(), 1);
+        assert!(store.with_tag("ukjent").is_empty());
+        assert_eq!(
+            store.tags(),
+            [
+                TagCount {
+                    name: "lesing".to_string(),
+                    count: 1
+                },
+                TagCount {
+                    name: "rust".to_string(),
+                    count: 2
+                }
+            ]
+        );
+    }
+
+    fn titled(id: &str, title: &str, url: &str) -> Bookmark {
+        Bookmark {
+            title: title.to_string(),
+            url: url.to_string(),
+            ..bookmark_in(id, None)
+        }
+    }
+
+    #[test]
+    fn test_search_ranking() {
+        let mut store = BookmarkStore::default();
+        store
+            .add(titled("url", "Nyheter", "https://rust-lang.org/news"))
+            .unwrap();
+        store
+            .add(titled("infix", "Lær Rust", "https://example.com/a"))
+            .unwrap();
+        store
+            .add(titled("prefix", "Rust-boka", "https://example.com/b"))
+            .unwrap();
+        store
+            .add(titled("url-infix", "Blogg", "https://example.com/rust"))
+            .unwrap();
+        store
+            .add(Bookmark {
+                folder: Some("Rust/Verktøy".to_string()),
+                ..titled("folder", "Cargo", "https://doc.example.com/c")
+            })
+            .unwrap();
+        store.add(tagged("tag", &["rustacean"])).unwrap();
+        store
+            .add(titled("miss", "Annet", "https://example.org"))
+            .unwrap();
+
+        let ids: Vec<&str> = store
+            .search("rust", 10)
+            .iter()
+            .map(|b| b.id.as_str())
+            .collect();
+        assert_eq!(
+            ids,
+            ["prefix", "infix", "tag", "url", "url-infix", "folder"]
+        );
+        assert_eq!(store.search("rust", 2).len(), 2);
+
+        // Tegnene i riktig rekkefølge gir et svakere treff i tittelen
+        let ids: Vec<&str> = store
+            .search("rstbk", 10)
+            .iter()
+            .map(|b| b.id.as_str())
+            .collect();
+        assert_eq!(ids, ["prefix"]);
+    }
+
+    #[test]
+    fn test_search_unicode_case_folding() {
+        let mut store = BookmarkStore::default();
+        store
+            .add(titled("a", "ÆRLIG TALT", "https://example.com/a"))
+            .unwrap();
+        store
+            .add(titled("b", "Σοφία", "https://example.com/b"))
+            .unwrap();
+
+        assert_eq!(store.search("ærlig", 10)[0].id, "a");
+        assert_eq!(store.search("ΣΟΦΊΑ", 10)[0].id, "b");
+    }
+
+    #[test]
+    fn test_search_empty_query() {
+        let mut store = BookmarkStore::default();
+        store.add(bookmark_in("a", None)).unwrap();
+        assert!(store.search("", 10).is_empty());
+        assert!(store.search("   ", 10).is_empty());
+    }
+
+    #[test]
+    fn test_normalize_url() {
+        let cases = [
+            ("https://example.com/page/", "https://example.com/page"),
+            ("https://example.com/page#del-2", "https://example.com/page"),
+            ("HTTPS://Example.COM/Page", "https://example.com/Page"),
+            ("https://example.com:443/", "https://example.com"),
+            ("http://example.com:80/a", "http://example.com/a"),
+            ("http://example.com:8080/a/", "http://example.com:8080/a"),
+            ("https://example.com", "https://example.com"),
+            ("https://example.com/?q=1", "https://example.com/?q=1"),
+            ("https://example.com/a/?q=B#x", "https://example.com/a/?q=B"),
+            (
+                "gemini://Gemini.Circumlunar.Space:1965/",
+                "gemini://gemini.circumlunar.space",
+            ),
+            (
+                "gemini://example.org:1966/docs/",
+                "gemini://example.org:1966/docs",
+            ),
+            ("GOPHER://Example.org:70/1/", "gopher://example.org/1/"),
+            ("gopher://example.org:70/", "gopher://example.org"),
+            ("  https://example.com/  ", "https://example.com"),
+            ("ikke en url", "ikke en url"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(normalize_url(input), expected, "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn test_gopher_selectors_keep_trailing_slash() {
+        assert_ne!(
+            normalize_url("gopher://example.org/1/dir/"),
+            normalize_url("gopher://example.org/1/dir")
+        );
+
+        let mut store = BookmarkStore::default();
+        store
+            .add(create_test_bookmark("gopher://example.org/1/dir/", "Mappe"))
+            .unwrap();
+        assert!(!store.is_bookmarked("gopher://example.org/1/dir"));
+        store
+            .add(create_test_bookmark("gopher://example.org/1/dir", "Fil"))
+            .unwrap();
+        assert!(matches!(
+            store.add(create_test_bookmark(
+                "gopher://EXAMPLE.org:70/1/dir/",
+                "Kopi"
+            )),
+            Err(BookmarkError::AlreadyExists(_))
+        ));
+    }
+
+    #[test]
+    fn test_is_bookmarked_uses_normalized_url() {
+        let mut store = BookmarkStore::default();
+        store
+            .add(create_test_bookmark("https://example.com/page", "Side"))
+            .unwrap();
+
+        assert!(store.is_bookmarked("https://EXAMPLE.com:443/page/#topp"));
+        assert!(!store.is_bookmarked("https://example.com/page?versjon=2"));
+        assert!(matches!(
+            store.add(create_test_bookmark("https://example.com/page/", "Kopi")),
+            Err(BookmarkError::AlreadyExists(_))
+        ));
+    }
+
+    #[test]
+    fn test_stored_urls_still_match_after_normalization() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("bookmarks.json");
+        fs::write(&path, include_str!("../fixtures/bookmarks/pre_tags.json")).unwrap();
+
+        let store = BookmarkStore::load(&path).unwrap();
+        for bookmark in store.list() {
+            assert!(store.is_bookmarked(&bookmark.url), "{}", bookmark.url);
+        }
+    }
+
+    #[test]
+    fn test_load_recovers_from_backup_after_truncated_save() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("bookmarks.json");
+
+        let mut store = BookmarkStore::default();
+        store.add(bookmark_in("a", Some("Lesing"))).unwrap();
+        store.save(&path).unwrap();
+        BookmarkStore::load(&path).unwrap();
+
+        // Simuler en lagring som ble avbrutt midt i skrivingen
+        let content = fs::read_to_string(&path).unwrap();
+        fs::write(&path, &content[..content.len() / 2]).unwrap();
+
+        let recovered = BookmarkStore::load(&path).unwrap();
+        assert_eq!(recovered.list().len(), 1);
+        assert_eq!(recovered.list()[0].folder.as_deref(), Some("Lesing"));
+    }
+
+    fn with_keyword(id: &str, keyword: &str, url: &str) -> Bookmark {
+        Bookmark {
+            url: url.to_string(),
+            keyword: Some(keyword.to_string()),
+            ..bookmark_in(id, None)
+        }
+    }
+
+    #[test]
+    fn test_keyword_uniqueness_and_validation() {
+        let mut store = BookmarkStore::default();
+        store
+            .add(with_keyword("a", "gp", "gopher://gopher.floodgap.com/"))
+            .unwrap();
+        store.add(bookmark_in("b", None)).unwrap();
+
+        assert!(matches!(
+            store.set_keyword("b", " GP "),
+            Err(BookmarkError::KeywordInUse(_))
+        ));
+        assert!(matches!(
+            store.add(with_keyword("c", "gp", "https://example.com/c")),
+            Err(BookmarkError::KeywordInUse(_))
+        ));
+        assert!(matches!(
+            store.set_keyword("b", "to ord"),
+            Err(BookmarkError::InvalidKeyword(_))
+        ));
+
+        // Samme bokmerke kan sette sitt eget nøkkelord på nytt
+        assert!(store.set_keyword("a", "GP").is_ok());
+        assert_eq!(
+            store.set_keyword("b", "Wiki").unwrap().keyword.as_deref(),
+            Some("wiki")
+        );
+        assert_eq!(store.set_keyword("b", "").unwrap().keyword, None);
+    }
+
+    #[test]
+    fn test_resolve_keyword_substitutes_encoded_query() {
+        let mut store = BookmarkStore::default();
+        store
+            .add(with_keyword("a", "gp", "gopher://gopher.floodgap.com/"))
+            .unwrap();
+        store
+            .add(with_keyword(
+                "b",
+                "rs",
+                "https://docs.rs/releases/search?query=%s",
+            ))
+            .unwrap();
+
+        assert_eq!(
+            store.resolve_keyword("gp").as_deref(),
+            Some("gopher://gopher.floodgap.com/")
+        );
+        assert_eq!(
+            store.resolve_keyword("RS  serde json&co/æ ").as_deref(),
+            Some("https://docs.rs/releases/search?query=serde%20json%26co%2F%C3%A6")
+        );
+        assert_eq!(
+            store.resolve_keyword("rs").as_deref(),
+            Some("https://docs.rs/releases/search?query=")
+        );
+    }
+
+    #[test]
+    fn test_resolve_keyword_without_match() {
+        let mut store = BookmarkStore::default();
+        store
+            .add(with_keyword("a", "gp", "gopher://gopher.floodgap.com/"))
+            .unwrap();
+
+        assert_eq!(store.resolve_keyword("example.com"), None);
+        assert_eq!(store.resolve_keyword("gpx søk"), None);
+        assert_eq!(store.resolve_keyword(""), None);
+    }
+
+    #[test]
+    fn test_pinned_persist_and_default_to_unpinned() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("bookmarks.json");
+        fs::write(&path, include_str!("../fixtures/bookmarks/pre_tags.json")).unwrap();
+
+        let mut store = BookmarkStore::load(&path).unwrap();
+        assert!(store.pinned().is_empty());
+
+        let id = store.list()[0].id.clone();
+        assert!(store.set_pinned(&id, true).unwrap().pinned);
+        store.save(&path).unwrap();
+
+        let mut reloaded = BookmarkStore::load(&path).unwrap();
+        let pinned: Vec<&str> = reloaded.pinned().iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(pinned, vec![id.as_str()]);
+
+        reloaded.set_pinned(&id, false).unwrap();
+        reloaded.save(&path).unwrap();
+        assert!(BookmarkStore::load(&path).unwrap().pinned().is_empty());
+        assert!(!fs::read_to_string(&path).unwrap().contains("pinned"));
+
+        assert!(matches!(
+            reloaded.set_pinned("finnes_ikke", true),
+            Err(BookmarkError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_reorder_pinned() {
+        let mut store = BookmarkStore::default();
+        for id in ["a", "b", "c", "d", "e"] {
+            store.add(bookmark_in(id, None)).unwrap();
+        }
+        for id in ["b", "c", "e"] {
+            store.set_pinned(id, true).unwrap();
+        }
+
+        let ids = |items: Vec<&Bookmark>| -> Vec<String> {
+            items.into_iter().map(|b| b.id.clone()).collect()
+        };
+        assert_eq!(ids(store.pinned()), vec!["b", "c", "e"]);
+
+        store
+            .reorder_pinned(&["e".to_string(), "b".to_string()])
+            .unwrap();
+        assert_eq!(ids(store.pinned()), vec!["e", "b", "c"]);
+        // Ufestede bokmerker beholder plassen sin
+        let all: Vec<&str> = store.list().iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(all, vec!["a", "e", "b", "d", "c"]);
+
+        // Ukjente eller ufestede ID-er avvises uten å endre noe
+        assert!(store.reorder_pinned(&["a".to_string()]).is_err());
+        assert_eq!(ids(store.pinned()), vec!["e", "b", "c"]);
+    }
+
+    fn page_ids(page: BookmarkPage<'_>) -> (Vec<&str>, usize) {
+        (
+            page.items.iter().map(|b| b.id.as_str()).collect(),
+            page.total,
+        )
+    }
+
+    #[test]
+    fn test_page_boundaries() {
+        let mut store = BookmarkStore::default();
+        for id in ["a", "b", "c", "d", "e"] {
+            store.add(bookmark_in(id, None)).unwrap();
+        }
+        let all = BookmarkFilter::default();
+        let manual = BookmarkSort::Manual;
+
+        assert_eq!(
+            page_ids(store.page(&all, manual, 0, None)),
+            (vec!["a", "b", "c", "d", "e"], 5)
+        );
+        assert_eq!(
+            page_ids(store.page(&all, manual, 0, Some(2))),
+            (vec!["a", "b"], 5)
+        );
+        assert_eq!(
+            page_ids(store.page(&all, manual, 4, Some(2))),
+            (vec!["e"], 5)
+        );
+        assert_eq!(page_ids(store.page(&all, manual, 5, Some(2))), (vec![], 5));
+        assert_eq!(page_ids(store.page(&all, manual, 9, None)), (vec![], 5));
+        assert_eq!(page_ids(store.page(&all, manual, 0, Some(0))), (vec![], 5));
+    }
+
+    #[test]
+    fn test_page_filter_and_sort() {
+        let mut store = BookmarkStore::default();
+        let entries = [
+            ("a", "delta", Some("Tech"), &["rust"][..], 30),
+            ("b", "Alfa", Some("Tech/Rust"), &["rust"][..], 10),
+            ("c", "charlie", None, &["rust"][..], 20),
+            ("d", "Bravo", Some("Tech"), &[][..], 40),
+        ];
+        for (id, title, folder, tags, created_at) in entries {
+            store
+                .add(Bookmark {
+                    title: title.to_string(),
+                    created_at,
+                    ..tagged(id, tags)
+                })
+                .unwrap();
+            store.move_bookmark(id, folder).unwrap();
+        }
+
+        let tech = BookmarkFilter {
+            folder: Some(Some("Tech".to_string())),
+            ..Default::default()
+        };
+        assert_eq!(
+            page_ids(store.page(&tech, BookmarkSort::Title, 0, None)),
+            (vec!["d", "a"], 2)
+        );
+
+        let tech_rust = BookmarkFilter {
+            folder: Some(Some("Tech".to_string())),
+            recursive: true,
+            tag: Some("RUST".to_string()),
+        };
+        assert_eq!(
+            page_ids(store.page(&tech_rust, BookmarkSort::Title, 0, None)),
+            (vec!["b", "a"], 2)
+        );
+        assert_eq!(
+            page_ids(store.page(&tech_rust, BookmarkSort::Newest, 1, Some(1))),
+            (vec!["b"], 2)
+        );
+
+        let root = BookmarkFilter {
+            folder: Some(None),
+            ..Default::default()
+        };
+        assert_eq!(
+            page_ids(store.page(&root, BookmarkSort::Manual, 0, None)),
+            (vec!["c"], 1)
+        );
+
+        let all = BookmarkFilter::default();
+        assert_eq!(
+            page_ids(store.page(&all, BookmarkSort::Oldest, 0, None)),
+            (vec!["b", "c", "a", "d"], 4)
+        );
+
+        // Cachet sortering fornyes når bokmerkene endres
+        store.update("d", Some("Aaa".to_string()), None).unwrap();
+        store.remove("b").unwrap();
+        assert_eq!(
+            page_ids(store.page(&all, BookmarkSort::Title, 0, None)),
+            (vec!["d", "c", "a"], 3)
+        );
+    }
+
+    #[test]
+    fn test_toggle_adds_then_removes() {
+        let mut store = BookmarkStore::default();
+        let bookmark = create_test_bookmark("https://example.com/side", "Side");
+
+        assert_eq!(
+            store.toggle(bookmark.clone()).unwrap(),
+            Toggle::Added(bookmark.clone())
+        );
+        assert!(store.is_bookmarked("https://EXAMPLE.com/side/"));
+
+        let again = create_test_bookmark("https://example.com/side#topp", "Side");
+        assert_eq!(store.toggle(again).unwrap(), Toggle::Removed(bookmark));
+        assert!(store.list().is_empty());
+    }
+
+    #[test]
+    fn test_concurrent_toggles_never_double_add() {
+        use std::sync::{Arc, Mutex};
+        use std::thread;
+
+        const THREADS: usize = 8;
+        const TOGGLES: usize = 250;
+
+        let store = Arc::new(Mutex::new(BookmarkStore::default()));
+        let handles: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let store = Arc::clone(&store);
+                thread::spawn(move || {
+                    for _ in 0..TOGGLES {
+                        let bookmark = create_test_bookmark("https://example.com/", "Eksempel");
+                        let mut store = store.lock().unwrap();
+                        store.toggle(bookmark).unwrap();
+                        assert!(store.list().len() <= 1);
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        // Et partall vekslinger ender der det startet
+        assert!(store.lock().unwrap().list().is_empty());
+    }
+
+    #[test]
+    fn test_restore_backup_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("bookmarks.json");
+
+        let mut store = BookmarkStore::default();
+        store.add(bookmark_in("a", None)).unwrap();
+        store.save_with_backups(&path, 3).unwrap();
+        store.add(bookmark_in("b", None)).unwrap();
+        store.save_with_backups(&path, 3).unwrap();
+
+        // En dårlig import tømmer samlingen
+        BookmarkStore::default()
+            .save_with_backups(&path, 3)
+            .unwrap();
+
+        let restored = BookmarkStore::load_backup(&path, 1).unwrap();
+        let ids: Vec<&str> = restored.list().iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b"]);
+        assert_eq!(
+            BookmarkStore::load_backup(&path, 2).unwrap().list().len(),
+            1
+        );
+
+        // Gjenoppretting tar selv vare på den tomme tilstanden
+        restored.save_with_backups(&path, 3).unwrap();
+        assert_eq!(BookmarkStore::load(&path).unwrap().list().len(), 2);
+        assert!(BookmarkStore::load_backup(&path, 1)
+            .unwrap()
+            .list()
+            .is_empty());
+
+        assert!(BookmarkStore::load_backup(&path, 9).is_err());
+    }
+
+    #[test]
+    fn test_reload_keeps_unsaved_changes() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("bookmarks.json");
+
+        let mut memory = BookmarkStore::default();
+        memory.add(bookmark_in("lagret", None)).unwrap();
+        memory.add(bookmark_in("slettes", None)).unwrap();
+        memory.save(&path).unwrap();
+        assert!(memory.is_own_write(&path));
+
+        // Endringer i minnet som ikke rakk å bli lagret
+        memory.add(bookmark_in("ny", None)).unwrap();
+        memory
+            .update("lagret", Some("Lokal tittel".to_string()), None)
+            .unwrap();
+
+        // En annen maskin sletter ett bokmerke og endrer et annet
+        let mut other = BookmarkStore::load(&path).unwrap();
+        other.remove("slettes").unwrap();
+        other
+            .update("lagret", Some("Fjern tittel".to_string()), None)
+            .unwrap();
+        other.add(bookmark_in("fjern", None)).unwrap();
+        other.save(&path).unwrap();
+        let disk = BookmarkStore::load(&path).unwrap();
+
+        assert!(memory.reload(disk, &path));
+        let mut ids: Vec<&str> = memory.list().iter().map(|b| b.id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["fjern", "lagret", "ny"]);
+        assert_eq!(memory.get("lagret").unwrap().title, "Lokal tittel");
+        assert!(memory.is_own_write(&path));
+
+        // Etter lagring er det ingen ulagrede endringer igjen
+        memory.save(&path).unwrap();
+        let disk = BookmarkStore::load(&path).unwrap();
+        assert!(!memory.reload(disk, &path));
+        assert_eq!(memory.list().len(), 3);
+    }
+
+    #[test]
+    fn test_is_own_write_detects_external_changes() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("bookmarks.json");
+
+        let mut store = BookmarkStore::default();
+        assert!(!store.is_own_write(&path));
+        store.add(bookmark_in("a", None)).unwrap();
+        store.save(&path).unwrap();
+        assert!(store.is_own_write(&path));
+
+        let mut other = BookmarkStore::load(&path).unwrap();
+        other.add(bookmark_in("b-med-lengre-id", None)).unwrap();
+        other.save(&path).unwrap();
+        assert!(!store.is_own_write(&path));
+    }
 }
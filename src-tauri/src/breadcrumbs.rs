@@ -0,0 +1,263 @@
+//! Brødsmulesti for adressen som vises
+//!
+//! Stien deles opp i ledd, og hvert ledd får adressen til siden eller mappen
+//! det står for. Mapper får alltid `/` til slutt, slik at relative lenker på
+//! siden løses mot riktig mappe når brukeren går dit.
+
+use crate::gopher;
+use serde::{Deserialize, Serialize};
+
+/// Filendelser som fjernes fra teksten til et ledd
+const HIDDEN_EXTENSIONS: [&str; 2] = [".md", ".gmi"];
+
+/// Ett ledd i brødsmulestien
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Breadcrumb {
+    /// Teksten som vises
+    pub label: String,
+    /// Adressen leddet peker til
+    pub url: String,
+}
+
+impl Breadcrumb {
+    fn new(label: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            url: url.into(),
+        }
+    }
+}
+
+/// Bygg brødsmulestien for en adresse
+///
+/// Støtter http(s)://, gemini://, gopher:// og file:// samt absolutte
+/// filstier. Første ledd er verten (eller `/` for filer), siste ledd er
+/// siden selv. Spørring og fragment tas ikke med.
+///
+/// # Arguments
+/// * `url` - Adressen til siden som vises
+///
+/// # Returns
+/// Leddene fra roten og ned, eller en tom liste for andre adresser
+pub fn breadcrumbs(url: &str) -> Vec<Breadcrumb> {
+    let parsed = if url.starts_with('/') {
+        url::Url::from_file_path(url).ok()
+    } else {
+        url::Url::parse(url).ok()
+    };
+    let Some(parsed) = parsed else {
+        return Vec::new();
+    };
+
+    match parsed.scheme() {
+        "gopher" => gopher_breadcrumbs(parsed.as_str()),
+        "http" | "https" | "gemini" => {
+            let Some(host) = parsed.host_str() else {
+                return Vec::new();
+            };
+            let authority = match parsed.port() {
+                Some(port) => format!("{}:{}", host, port),
+                None => host.to_string(),
+            };
+            let origin = format!("{}://{}", parsed.scheme(), authority);
+            path_breadcrumbs(&authority, &origin, parsed.path())
+        }
+        "file" => path_breadcrumbs("/", "file://", parsed.path()),
+        _ => Vec::new(),
+    }
+}
+
+/// Ledd for en sti, med roten først
+///
+/// # Arguments
+/// * `root_label` - Teksten til roten
+/// * `origin` - Alt foran stien, f.eks. "https://example.com"
+/// * `path` - Stien, fortsatt prosentkodet
+fn path_breadcrumbs(root_label: &str, origin: &str, path: &str) -> Vec<Breadcrumb> {
+    let mut crumbs = vec![Breadcrumb::new(root_label, format!("{}/", origin))];
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let mut url = origin.to_string();
+    for (i, segment) in segments.iter().enumerate() {
+        url.push('/');
+        url.push_str(segment);
+        let is_last = i + 1 == segments.len();
+        let is_directory = !is_last || path.ends_with('/');
+        let target = if is_directory {
+            format!("{}/", url)
+        } else {
+            url.clone()
+        };
+        crumbs.push(Breadcrumb::new(prettify(segment), target));
+    }
+    crumbs
+}
+
+/// Ledd for en Gopher-adresse, bygget fra selektoren
+fn gopher_breadcrumbs(url: &str) -> Vec<Breadcrumb> {
+    let Ok(parsed) = gopher::parse_gopher_url(url) else {
+        return Vec::new();
+    };
+    gopher::selector_trail(&parsed)
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            // Vertsnavnet vises som det er
+            let label = if i == 0 {
+                item.display.clone()
+            } else {
+                prettify(&item.display)
+            };
+            Breadcrumb::new(label, gopher::build_gopher_url(item))
+        })
+        .collect()
+}
+
+/// Gjør et ledd i stien lesbart
+///
+/// Prosentkoding dekodes, `-` og `_` blir mellomrom, og `.md` og `.gmi`
+/// fjernes til slutt.
+fn prettify(segment: &str) -> String {
+    let decoded = percent_decode(segment);
+    let stem = HIDDEN_EXTENSIONS
+        .iter()
+        .find_map(|ext| {
+            let split = decoded.len().checked_sub(ext.len())?;
+            let (stem, tail) = (decoded.get(..split)?, decoded.get(split..)?);
+            (tail.eq_ignore_ascii_case(ext) && !stem.is_empty()).then_some(stem)
+        })
+        .unwrap_or(&decoded);
+    let label = stem.replace(['-', '_'], " ");
+    if label.trim().is_empty() {
+        decoded
+    } else {
+        label
+    }
+}
+
+/// Dekod prosentkoding; ugyldige sekvenser beholdes som de er
+pub fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trail(url: &str) -> Vec<(String, String)> {
+        breadcrumbs(url)
+            .into_iter()
+            .map(|c| (c.label, c.url))
+            .collect()
+    }
+
+    fn pairs(expected: &[(&str, &str)]) -> Vec<(String, String)> {
+        expected
+            .iter()
+            .map(|(label, url)| (label.to_string(), url.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_trailing_slash_marks_directories() {
+        assert_eq!(
+            trail("https://example.com/docs/guide/intro.md?v=2#del"),
+            pairs(&[
+                ("example.com", "https://example.com/"),
+                ("docs", "https://example.com/docs/"),
+                ("guide", "https://example.com/docs/guide/"),
+                ("intro", "https://example.com/docs/guide/intro.md"),
+            ])
+        );
+        assert_eq!(
+            trail("gemini://kapsel.example:1966/logg/"),
+            pairs(&[
+                ("kapsel.example:1966", "gemini://kapsel.example:1966/"),
+                ("logg", "gemini://kapsel.example:1966/logg/"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_encoded_and_prettified_segments() {
+        assert_eq!(
+            trail("https://example.com/Mine%20notater/blå_bær/getting-started.GMI"),
+            pairs(&[
+                ("example.com", "https://example.com/"),
+                ("Mine notater", "https://example.com/Mine%20notater/"),
+                (
+                    "blå bær",
+                    "https://example.com/Mine%20notater/bl%C3%A5_b%C3%A6r/"
+                ),
+                (
+                    "getting started",
+                    "https://example.com/Mine%20notater/bl%C3%A5_b%C3%A6r/getting-started.GMI"
+                ),
+            ])
+        );
+        assert_eq!(prettify("100%"), "100%");
+        assert_eq!(prettify(".md"), ".md");
+        assert_eq!(prettify("%E2%80%93"), "–");
+    }
+
+    #[test]
+    fn test_root_level_returns_host() {
+        assert_eq!(
+            trail("https://example.com"),
+            pairs(&[("example.com", "https://example.com/")])
+        );
+        assert_eq!(
+            trail("gemini://example.org/"),
+            pairs(&[("example.org", "gemini://example.org/")])
+        );
+    }
+
+    #[test]
+    fn test_files_and_gopher() {
+        assert_eq!(
+            trail("/home/bruker/notater.md"),
+            pairs(&[
+                ("/", "file:///"),
+                ("home", "file:///home/"),
+                ("bruker", "file:///home/bruker/"),
+                ("notater", "file:///home/bruker/notater.md"),
+            ])
+        );
+        assert_eq!(
+            trail("gopher://example.com/0/docs/rust_intro.txt"),
+            pairs(&[
+                ("example.com", "gopher://example.com/1"),
+                ("docs", "gopher://example.com/1/docs"),
+                (
+                    "rust intro.txt",
+                    "gopher://example.com/0/docs/rust_intro.txt"
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_unsupported_urls_are_empty() {
+        assert!(breadcrumbs("bare://bookmarks").is_empty());
+        assert!(breadcrumbs("mailto:a@example.com").is_empty());
+        assert!(breadcrumbs("ikke en adresse").is_empty());
+    }
+}
@@ -0,0 +1,142 @@
+//! Kringkasting av hendelser til alle vinduer
+//!
+//! Sender hendelser via en `EventSink` slik at logikken kan testes uten
+//! Tauri, og samler raske oppdateringer (f.eks. zoom) til én hendelse.
+
+use log::warn;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// Mottaker av hendelser som skal til alle vinduer
+pub trait EventSink<T>: Send + Sync {
+    /// Send en hendelse med nyttelast
+    fn send(&self, event: &str, payload: &T);
+}
+
+impl<T: Serialize + Clone> EventSink<T> for AppHandle {
+    fn send(&self, event: &str, payload: &T) {
+        if let Err(e) = self.emit(event, payload.clone()) {
+            warn!("Kunne ikke sende {}: {}", event, e);
+        }
+    }
+}
+
+/// Samler raske oppdateringer slik at bare den siste blir sendt
+///
+/// Hver `push` erstatter verdien som venter og starter ventetiden på nytt.
+pub struct Debouncer<T> {
+    delay: Duration,
+    pending: Mutex<Option<T>>,
+    generation: AtomicU64,
+}
+
+impl<T: Send + 'static> Debouncer<T> {
+    /// Opprett en debouncer med gitt ventetid
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            pending: Mutex::new(None),
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    /// Legg inn en ny verdi
+    ///
+    /// # Returns
+    /// Generasjonsnummeret til verdien, brukt av `take_if_latest`
+    pub fn push(&self, value: T) -> u64 {
+        let mut pending = self.pending.lock();
+        *pending = Some(value);
+        self.generation.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// Hent verdien som venter, men bare hvis ingen nyere er lagt inn
+    ///
+    /// # Arguments
+    /// * `generation` - Generasjonen fra `push`
+    pub fn take_if_latest(&self, generation: u64) -> Option<T> {
+        let mut pending = self.pending.lock();
+        if self.generation.load(Ordering::SeqCst) == generation {
+            pending.take()
+        } else {
+            None
+        }
+    }
+
+    /// Forkast verdien som venter, f.eks. fordi en nyere tilstand er sendt direkte
+    pub fn cancel(&self) {
+        let mut pending = self.pending.lock();
+        *pending = None;
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Legg inn en verdi og send den etter ventetiden hvis den fortsatt er den siste
+    ///
+    /// # Arguments
+    /// * `sink` - Mottakeren av hendelsen
+    /// * `event` - Navnet på hendelsen
+    /// * `value` - Nyttelasten
+    pub fn schedule(self: &Arc<Self>, sink: Arc<dyn EventSink<T>>, event: &'static str, value: T) {
+        let generation = self.push(value);
+        let debouncer = Arc::clone(self);
+        thread::spawn(move || {
+            thread::sleep(debouncer.delay);
+            if let Some(value) = debouncer.take_if_latest(generation) {
+                sink.send(event, &value);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Recorder {
+        sent: Mutex<Vec<(String, u32)>>,
+    }
+
+    impl EventSink<u32> for Recorder {
+        fn send(&self, event: &str, payload: &u32) {
+            self.sent.lock().push((event.to_string(), *payload));
+        }
+    }
+
+    #[test]
+    fn test_only_latest_generation_is_taken() {
+        let debouncer = Debouncer::new(Duration::from_millis(10));
+        let first = debouncer.push(110);
+        let second = debouncer.push(120);
+
+        assert_eq!(debouncer.take_if_latest(first), None);
+        assert_eq!(debouncer.take_if_latest(second), Some(120));
+        // Verdien sendes bare én gang
+        assert_eq!(debouncer.take_if_latest(second), None);
+
+        let third = debouncer.push(130);
+        debouncer.cancel();
+        assert_eq!(debouncer.take_if_latest(third), None);
+    }
+
+    #[test]
+    fn test_schedule_sends_final_value_once() {
+        let debouncer = Arc::new(Debouncer::new(Duration::from_millis(50)));
+        let recorder = Arc::new(Recorder::default());
+
+        for zoom in [110, 120, 130] {
+            debouncer.schedule(recorder.clone(), "settings-changed", zoom);
+        }
+        thread::sleep(Duration::from_millis(300));
+
+        assert_eq!(
+            *recorder.sent.lock(),
+            vec![("settings-changed".to_string(), 130)]
+        );
+    }
+}
@@ -0,0 +1,513 @@
+//! Sletting av alt Bare husker om surfingen
+//!
+//! Hver kategori tømmes gjennom modulen som eier lageret, ikke ved å slette
+//! filer direkte. Bokmerker og innstillinger er aldri med; overstyringer per
+//! nettsted er det eneste fra innstillingene som kan slettes her.
+//!
+//! Svar fra Gemini og Gopher lagres bare som forhåndshentede og rendrede
+//! sider, og slettes med resten av sidene i minnet. Bare husker ikke vinduer
+//! som er lukket, så det finnes ingen liste over nylig lukkede å slette.
+
+use crate::gemini::GeminiClient;
+use crate::history::{History, HistoryRange};
+use crate::locale::{self, Localize};
+use crate::prefetch::{PrefetchCache, Prefetched};
+use crate::recent_files::RecentFiles;
+use crate::render_cache::RenderCache;
+use crate::settings::{ResetScope, Settings};
+use crate::snapshots::RecentPages;
+use crate::window_state::WindowStates;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Hvilke kategorier som skal slettes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClearOptions {
+    /// Besøkte sider
+    pub history: bool,
+    /// Tilbake og frem i hvert vindu og verter godtatt tross advarsler
+    pub session_state: bool,
+    /// Sider i minnet: nylig viste, forhåndshentede og rendrede
+    pub page_cache: bool,
+    /// Kjente Gemini-verter (TOFU)
+    pub known_hosts: bool,
+    /// Nylig åpnede filer
+    pub recent_files: bool,
+    /// Innstillinger per nettsted
+    pub site_overrides: bool,
+}
+
+/// En kategori med lagrede data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ClearCategory {
+    History,
+    SessionState,
+    PageCache,
+    KnownHosts,
+    RecentFiles,
+    SiteOverrides,
+}
+
+impl ClearCategory {
+    /// Om kategorien er valgt
+    fn selected(self, options: &ClearOptions) -> bool {
+        match self {
+            Self::History => options.history,
+            Self::SessionState => options.session_state,
+            Self::PageCache => options.page_cache,
+            Self::KnownHosts => options.known_hosts,
+            Self::RecentFiles => options.recent_files,
+            Self::SiteOverrides => options.site_overrides,
+        }
+    }
+}
+
+/// Resultatet for én kategori
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClearResult {
+    pub category: ClearCategory,
+    /// Antall oppføringer som ble slettet
+    pub removed: usize,
+    /// Feilmelding hvis kategorien ikke kunne slettes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Et lager som kan tømmes
+pub trait Clearable {
+    /// Slett alt i lageret
+    ///
+    /// # Returns
+    /// Antall oppføringer som ble slettet, eller en lokalisert feilmelding
+    fn clear_data(&mut self) -> Result<usize, String>;
+}
+
+impl Clearable for History {
+    fn clear_data(&mut self) -> Result<usize, String> {
+        // Tidspunktet betyr ingenting når hele historikken slettes
+        self.clear(HistoryRange::All, 0).map_err(|e| e.localize())
+    }
+}
+
+/// Navigeringen i hvert vindu og vertene brukeren har godtatt
+pub struct SessionState<'a> {
+    pub windows: &'a mut WindowStates,
+    pub trusted_hosts: &'a mut HashSet<String>,
+}
+
+impl Clearable for SessionState<'_> {
+    fn clear_data(&mut self) -> Result<usize, String> {
+        let hosts = std::mem::take(self.trusted_hosts).len();
+        Ok(self.windows.clear_navigation() + self.windows.clear_pages() + hosts)
+    }
+}
+
+/// Sidene som holdes i minnet, fra nettet, Gemini og Gopher
+pub struct PageCaches<'a> {
+    /// Kilden til nylig viste sider (brukes til arkivering)
+    pub recent_pages: &'a mut RecentPages,
+    pub prefetched: &'a mut PrefetchCache<Prefetched>,
+    pub rendered: &'a mut RenderCache,
+}
+
+impl Clearable for PageCaches<'_> {
+    fn clear_data(&mut self) -> Result<usize, String> {
+        Ok(self.recent_pages.clear() + self.prefetched.clear() + self.rendered.clear())
+    }
+}
+
+/// Listen over nylig åpnede filer og filen den lagres i
+pub struct RecentFileList<'a> {
+    pub files: &'a mut RecentFiles,
+    pub path: &'a Path,
+}
+
+impl Clearable for RecentFileList<'_> {
+    fn clear_data(&mut self) -> Result<usize, String> {
+        let removed = self.files.clear();
+        self.files
+            .save(self.path)
+            .map_err(|e| locale::tf("error.recent_files_write", &[&e]))?;
+        Ok(removed)
+    }
+}
+
+/// TOFU-lageret til en Gemini-klient
+pub struct KnownHosts<'a>(pub &'a GeminiClient);
+
+impl Clearable for KnownHosts<'_> {
+    fn clear_data(&mut self) -> Result<usize, String> {
+        self.0.clear_known_hosts().map_err(|e| e.localize())
+    }
+}
+
+/// Overstyringene per nettsted i innstillingene
+///
+/// Tømmes bare i minnet; innstillingene lagres av den som kaller.
+pub struct SiteOverrides<'a>(pub &'a mut Settings);
+
+impl Clearable for SiteOverrides<'_> {
+    fn clear_data(&mut self) -> Result<usize, String> {
+        let count = self.0.site_overrides.len();
+        self.0.reset(ResetScope::SiteOverrides, false);
+        Ok(count)
+    }
+}
+
+/// Slett de valgte kategoriene
+///
+/// En feil i én kategori stopper ikke de andre.
+///
+/// # Arguments
+/// * `options` - Kategoriene som skal slettes
+/// * `stores` - Lageret for hver kategori
+///
+/// # Returns
+/// Ett resultat per valgt kategori, i samme rekkefølge som `stores`
+pub fn clear(
+    options: &ClearOptions,
+    stores: &mut [(ClearCategory, &mut dyn Clearable)],
+) -> Vec<ClearResult> {
+    stores
+        .iter_mut()
+        .filter(|(category, _)| category.selected(options))
+        .map(|(category, store)| match store.clear_data() {
+            Ok(removed) => ClearResult {
+                category: *category,
+                removed,
+                error: None,
+            },
+            Err(e) => ClearResult {
+                category: *category,
+                removed: 0,
+                error: Some(e),
+            },
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gopher::{GopherContentType, GopherResponse};
+    use crate::history::HistoryLimits;
+    use crate::markdown::RenderOutput;
+    use crate::settings::NetworkSettings;
+    use std::time::Instant;
+    use tempfile::tempdir;
+
+    const LIMITS: HistoryLimits = HistoryLimits {
+        max_entries: 100,
+        max_age_days: 36500,
+    };
+
+    const URL: &str = "https://example.com/";
+    const GOPHER_URL: &str = "gopher://example.org/0/a.txt";
+    const WINDOW: &str = "main";
+
+    /// Alle lagrene, fylt med innhold
+    struct Stores {
+        history: History,
+        windows: WindowStates,
+        trusted_hosts: HashSet<String>,
+        pages: RecentPages,
+        prefetched: PrefetchCache<Prefetched>,
+        rendered: RenderCache,
+        gemini: GeminiClient,
+        recent_files: RecentFiles,
+        settings: Settings,
+    }
+
+    impl Stores {
+        fn filled(dir: &Path) -> Self {
+            let mut history = History::load(&dir.join("history.jsonl")).unwrap();
+            history.record(URL, None, 1_700_000_000, LIMITS).unwrap();
+
+            // Ett steg tilbake, og siden det fører til i minnet
+            let mut windows = WindowStates::default();
+            for url in [URL, "https://example.com/b"] {
+                let ticket = windows.begin_fetch(WINDOW);
+                assert!(windows.finish_fetch(&ticket, Some(url)));
+            }
+            let page = serde_json::from_value(serde_json::json!({
+                "html": "<h1>Side</h1>",
+                "title": "Side",
+                "url": URL,
+            }))
+            .unwrap();
+            windows.remember_page(WINDOW, page);
+
+            let mut pages = RecentPages::default();
+            pages.remember(URL, "# Side");
+
+            let mut prefetched = PrefetchCache::default();
+            let response = GopherResponse {
+                content_type: GopherContentType::Text,
+                body: "tekst".into(),
+                raw: b"tekst".to_vec(),
+                items: Vec::new(),
+                final_url: GOPHER_URL.into(),
+                timing: Default::default(),
+            };
+            prefetched.insert(GOPHER_URL, Prefetched::Gopher(response), Instant::now());
+
+            let mut rendered = RenderCache::default();
+            rendered.insert(
+                [1; 32],
+                RenderOutput {
+                    html: "<h1>Side</h1>".into(),
+                    tasks: None,
+                    skipped_diagrams: 0,
+                    truncated: false,
+                },
+            );
+
+            let tofu_path = dir.join("known_hosts.json");
+            let mut tofu = crate::gemini::TofuStore::default();
+            tofu.verify("example.org:1965", "abc").unwrap();
+            tofu.save(&tofu_path).unwrap();
+            let gemini = GeminiClient::with_tofu_path(&NetworkSettings::default(), tofu_path);
+
+            let file = dir.join("a.md");
+            std::fs::write(&file, "# A").unwrap();
+            let mut recent_files = RecentFiles::default();
+            recent_files.add(&file);
+            recent_files.save(&dir.join("recent_files.json")).unwrap();
+
+            let mut settings = Settings::default();
+            settings.set_site_zoom("example.com", Some(120)).unwrap();
+
+            Self {
+                history,
+                windows,
+                trusted_hosts: HashSet::from(["evil.example".to_string()]),
+                pages,
+                prefetched,
+                rendered,
+                gemini,
+                recent_files,
+                settings,
+            }
+        }
+
+        fn clear(&mut self, dir: &Path, options: ClearOptions) -> Vec<ClearResult> {
+            let recent_path = dir.join("recent_files.json");
+            let mut session = SessionState {
+                windows: &mut self.windows,
+                trusted_hosts: &mut self.trusted_hosts,
+            };
+            let mut page_caches = PageCaches {
+                recent_pages: &mut self.pages,
+                prefetched: &mut self.prefetched,
+                rendered: &mut self.rendered,
+            };
+            let mut known_hosts = KnownHosts(&self.gemini);
+            let mut recent_files = RecentFileList {
+                files: &mut self.recent_files,
+                path: &recent_path,
+            };
+            let mut overrides = SiteOverrides(&mut self.settings);
+            clear(
+                &options,
+                &mut [
+                    (ClearCategory::History, &mut self.history),
+                    (ClearCategory::SessionState, &mut session),
+                    (ClearCategory::PageCache, &mut page_caches),
+                    (ClearCategory::KnownHosts, &mut known_hosts),
+                    (ClearCategory::RecentFiles, &mut recent_files),
+                    (ClearCategory::SiteOverrides, &mut overrides),
+                ],
+            )
+        }
+
+        /// Hvert lager, med kategorien det hører til og om det har innhold
+        fn contents(&self, dir: &Path) -> Vec<(ClearCategory, &'static str, bool)> {
+            let window = self.windows.get(WINDOW).unwrap();
+            let tofu = crate::gemini::TofuStore::load(&dir.join("known_hosts.json"));
+            let saved_files = RecentFiles::load(&dir.join("recent_files.json"));
+            vec![
+                (
+                    ClearCategory::History,
+                    "historikk",
+                    self.history.page(0, 1).total > 0,
+                ),
+                (
+                    ClearCategory::SessionState,
+                    "tilbake",
+                    !window.back.is_empty(),
+                ),
+                (
+                    ClearCategory::SessionState,
+                    "vinduets sider",
+                    window.cached_page(URL).is_some(),
+                ),
+                (
+                    ClearCategory::SessionState,
+                    "godtatte verter",
+                    !self.trusted_hosts.is_empty(),
+                ),
+                (
+                    ClearCategory::PageCache,
+                    "nylige sider",
+                    self.pages.get(URL).is_some(),
+                ),
+                (
+                    ClearCategory::PageCache,
+                    "forhåndshentet",
+                    self.prefetched.contains(GOPHER_URL),
+                ),
+                (
+                    ClearCategory::PageCache,
+                    "rendret",
+                    self.rendered.stats().entries > 0,
+                ),
+                (
+                    ClearCategory::KnownHosts,
+                    "kjente verter",
+                    serde_json::to_value(&tofu).unwrap()["hosts"]
+                        .as_object()
+                        .is_some_and(|hosts| !hosts.is_empty()),
+                ),
+                (
+                    ClearCategory::RecentFiles,
+                    "nylige filer",
+                    !self.recent_files.existing().is_empty(),
+                ),
+                (
+                    ClearCategory::RecentFiles,
+                    "lagrede nylige filer",
+                    !saved_files.existing().is_empty(),
+                ),
+                (
+                    ClearCategory::SiteOverrides,
+                    "overstyringer",
+                    !self.settings.site_overrides.is_empty(),
+                ),
+            ]
+        }
+    }
+
+    const ALL: ClearOptions = ClearOptions {
+        history: true,
+        session_state: true,
+        page_cache: true,
+        known_hosts: true,
+        recent_files: true,
+        site_overrides: true,
+    };
+
+    #[test]
+    fn test_each_flag_clears_only_its_store() {
+        let flags = [
+            (
+                ClearCategory::History,
+                1,
+                ClearOptions {
+                    history: true,
+                    ..Default::default()
+                },
+            ),
+            (
+                // Ett steg tilbake, én side i vinduet og én godtatt vert
+                ClearCategory::SessionState,
+                3,
+                ClearOptions {
+                    session_state: true,
+                    ..Default::default()
+                },
+            ),
+            (
+                // Én nylig, én forhåndshentet og én rendret side
+                ClearCategory::PageCache,
+                3,
+                ClearOptions {
+                    page_cache: true,
+                    ..Default::default()
+                },
+            ),
+            (
+                ClearCategory::KnownHosts,
+                1,
+                ClearOptions {
+                    known_hosts: true,
+                    ..Default::default()
+                },
+            ),
+            (
+                ClearCategory::RecentFiles,
+                1,
+                ClearOptions {
+                    recent_files: true,
+                    ..Default::default()
+                },
+            ),
+            (
+                ClearCategory::SiteOverrides,
+                1,
+                ClearOptions {
+                    site_overrides: true,
+                    ..Default::default()
+                },
+            ),
+        ];
+
+        for (cleared, removed, options) in flags {
+            let dir = tempdir().unwrap();
+            let mut stores = Stores::filled(dir.path());
+
+            let results = stores.clear(dir.path(), options);
+            assert_eq!(
+                results,
+                [ClearResult {
+                    category: cleared,
+                    removed,
+                    error: None,
+                }]
+            );
+
+            for (category, store, filled) in stores.contents(dir.path()) {
+                assert_eq!(filled, category != cleared, "{} etter {:?}", store, cleared);
+            }
+        }
+    }
+
+    #[test]
+    fn test_clear_nothing_and_everything() {
+        let dir = tempdir().unwrap();
+        let mut stores = Stores::filled(dir.path());
+
+        assert!(stores.clear(dir.path(), ClearOptions::default()).is_empty());
+        for (_, store, filled) in stores.contents(dir.path()) {
+            assert!(filled, "{}", store);
+        }
+
+        let categories: Vec<ClearCategory> = stores
+            .clear(dir.path(), ALL)
+            .iter()
+            .map(|r| r.category)
+            .collect();
+        assert_eq!(
+            categories,
+            [
+                ClearCategory::History,
+                ClearCategory::SessionState,
+                ClearCategory::PageCache,
+                ClearCategory::KnownHosts,
+                ClearCategory::RecentFiles,
+                ClearCategory::SiteOverrides,
+            ]
+        );
+        for (_, store, filled) in stores.contents(dir.path()) {
+            assert!(!filled, "{}", store);
+        }
+
+        // Historikkfilen er tømt gjennom modulen, ikke slettet
+        assert!(dir.path().join("history.jsonl").exists());
+        // Siden vinduet viser beholdes
+        let window = stores.windows.get(WINDOW).unwrap();
+        assert_eq!(window.current_url.as_deref(), Some("https://example.com/b"));
+    }
+}
@@ -0,0 +1,551 @@
+//! Feil fra commands til frontend
+//!
+//! Tauri sender feilen fra en command som JSON. Feil frontend skal handle på
+//! (spørsmål til brukeren, en henting som er forkastet) har egne varianter
+//! med feltene frontend trenger, slik at ingenting må leses ut av teksten.
+//! Alle varianter har `message` med en lesbar tekst, så feil frontend ikke
+//! kjenner kan vises som de er.
+//!
+//! ```json
+//! { "kind": "gemini_input_prompt", "message": "…", "prompt": "Søk", "sensitive": false, "url": "gemini://…" }
+//! ```
+
+use crate::commands::RenderedPage;
+use crate::fetcher::FetchError;
+use crate::gemini::GeminiError;
+use crate::gopher::GopherError;
+use crate::locale::{self, Localize};
+use crate::url_safety::UrlWarning;
+use serde::Serialize;
+use std::fmt;
+
+/// Hva slags nettverksfeil en henting stoppet på
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkErrorKind {
+    InvalidUrl,
+    UnsupportedScheme,
+    /// Vertsnavnet kunne ikke slås opp
+    Dns,
+    Connection,
+    Tls,
+    Timeout,
+    TooLarge,
+    NotFound,
+    Server,
+    InvalidResponse,
+    RedirectLoop,
+    ClientCertificate,
+}
+
+/// Feilen en command returnerer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CommandError {
+    /// Siden er ikke markdown; brukeren velger om den skal konverteres
+    ConversionPrompt { message: String, url: String },
+
+    /// Gemini-serveren ber om input (status 10 eller 11)
+    GeminiInputPrompt {
+        message: String,
+        prompt: String,
+        /// Status 11: input skal skjules og ikke huskes
+        sensitive: bool,
+        url: String,
+    },
+
+    /// Gopher-søket trenger et søkeord
+    GopherSearchPrompt { message: String, url: String },
+
+    /// Innholdet kan ikke vises, men kan lastes ned
+    DownloadPrompt { message: String, url: String },
+
+    /// En Gemini-side sender videre til en annen protokoll; brukeren
+    /// bestemmer om `url` skal åpnes
+    SchemeRedirect { message: String, url: String },
+
+    /// En Gemini-kapsel ber om klientsertifikat; brukeren kan lage en
+    /// identitet for `host` og åpne `url` på nytt
+    ClientCertPrompt {
+        message: String,
+        url: String,
+        host: String,
+    },
+
+    /// Adressen ser mistenkelig ut; brukeren må bekrefte navigeringen
+    ConfirmNavigation {
+        message: String,
+        url: String,
+        /// Verten som godtas hvis brukeren fortsetter
+        host: String,
+        warnings: Vec<UrlWarning>,
+    },
+
+    /// Gemini-serverens sertifikat er et annet enn det som ble husket
+    CertificateChanged {
+        message: String,
+        host: String,
+        old_fingerprint: String,
+        new_fingerprint: String,
+        /// Feilsiden, se `error_page`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        page: Option<Box<RenderedPage>>,
+    },
+
+    /// En nyere henting i samme vindu har tatt over; frontend ignorerer den
+    Superseded { message: String },
+
+    /// Hentingen feilet i nettverket eller hos serveren
+    Network {
+        message: String,
+        #[serde(rename = "network_kind")]
+        kind: NetworkErrorKind,
+        /// Feilsiden, se `error_page`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        page: Option<Box<RenderedPage>>,
+    },
+
+    /// En feil i Bare selv, f.eks. panikk under konvertering av siden
+    Internal { message: String },
+
+    /// Alle andre feil
+    Failed { message: String },
+}
+
+impl CommandError {
+    /// Lesbar tekst for feilen
+    pub fn message(&self) -> &str {
+        match self {
+            Self::ConversionPrompt { message, .. }
+            | Self::GeminiInputPrompt { message, .. }
+            | Self::GopherSearchPrompt { message, .. }
+            | Self::DownloadPrompt { message, .. }
+            | Self::SchemeRedirect { message, .. }
+            | Self::ClientCertPrompt { message, .. }
+            | Self::ConfirmNavigation { message, .. }
+            | Self::CertificateChanged { message, .. }
+            | Self::Superseded { message }
+            | Self::Network { message, .. }
+            | Self::Internal { message }
+            | Self::Failed { message } => message,
+        }
+    }
+
+    /// Spør brukeren om en side som ikke er markdown skal konverteres
+    pub fn conversion_prompt(url: &str) -> Self {
+        Self::ConversionPrompt {
+            message: locale::t("prompt.convert_html").to_string(),
+            url: url.to_string(),
+        }
+    }
+
+    /// Be om input til en Gemini-side
+    pub fn gemini_input(prompt: String, sensitive: bool, url: &str) -> Self {
+        let key = if sensitive {
+            "error.gemini_sensitive_input"
+        } else {
+            "error.gemini_input"
+        };
+        Self::GeminiInputPrompt {
+            message: locale::tf(key, &[&prompt]),
+            prompt,
+            sensitive,
+            url: url.to_string(),
+        }
+    }
+
+    /// Be om søkeord til et Gopher-søk
+    pub fn gopher_search(url: &str) -> Self {
+        Self::GopherSearchPrompt {
+            message: locale::t("error.gopher_search_input").to_string(),
+            url: url.to_string(),
+        }
+    }
+
+    /// Tilby å laste ned innhold som ikke kan vises
+    pub fn download_prompt(url: &str) -> Self {
+        Self::DownloadPrompt {
+            message: locale::tf("error.not_displayable", &[&url]),
+            url: url.to_string(),
+        }
+    }
+
+    /// Spør om en redirect til en annen protokoll skal følges
+    pub fn scheme_redirect(url: &str) -> Self {
+        Self::SchemeRedirect {
+            message: locale::tf("prompt.scheme_redirect", &[&url]),
+            url: url.to_string(),
+        }
+    }
+
+    /// Spør om en identitet skal lages for en kapsel som ber om klientsertifikat
+    ///
+    /// # Arguments
+    /// * `url` - Siden som ba om sertifikatet
+    /// * `host` - "host:port" identiteten knyttes til
+    /// * `meta` - Teksten fra serveren
+    pub fn client_cert_prompt(url: &str, host: &str, meta: &str) -> Self {
+        Self::ClientCertPrompt {
+            message: locale::tf("prompt.client_cert", &[&host, &meta]),
+            url: url.to_string(),
+            host: host.to_string(),
+        }
+    }
+
+    /// En adresse med en protokoll Bare ikke kan åpne
+    pub fn unknown_scheme(scheme: &str) -> Self {
+        Self::Network {
+            message: locale::tf("error.unknown_scheme", &[&scheme]),
+            kind: NetworkErrorKind::UnsupportedScheme,
+            page: None,
+        }
+    }
+
+    /// Be brukeren bekrefte en adresse som ser mistenkelig ut
+    pub fn confirm_navigation(url: &str, host: &str, warnings: Vec<UrlWarning>) -> Self {
+        let mut message = locale::tf("prompt.confirm_navigation", &[&host]);
+        for warning in &warnings {
+            message.push_str("\n\n• ");
+            message.push_str(&warning.describe());
+        }
+        message.push_str("\n\n");
+        message.push_str(locale::t("prompt.confirm_navigation_question"));
+        Self::ConfirmNavigation {
+            message,
+            url: url.to_string(),
+            host: host.to_string(),
+            warnings,
+        }
+    }
+
+    /// En henting som er forkastet fordi en nyere har tatt over
+    pub fn superseded() -> Self {
+        Self::Superseded {
+            message: locale::t("status.superseded").to_string(),
+        }
+    }
+
+    /// Arbeid som fikk panikk; detaljene står i loggen
+    pub fn internal() -> Self {
+        Self::Internal {
+            message: locale::t("error.internal").to_string(),
+        }
+    }
+
+    /// Legg ved en feilside frontend kan vise i stedet for meldingen
+    ///
+    /// Bare nettverksfeil og endrede sertifikater har plass til en side;
+    /// andre feil returneres uendret.
+    pub fn with_page(mut self, error_page: RenderedPage) -> Self {
+        if let Self::Network { page, .. } | Self::CertificateChanged { page, .. } = &mut self {
+            *page = Some(Box::new(error_page));
+        }
+        self
+    }
+
+    fn network(kind: NetworkErrorKind, error: &impl Localize) -> Self {
+        Self::Network {
+            message: error.localize(),
+            kind,
+            page: None,
+        }
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        Self::Failed { message }
+    }
+}
+
+impl From<&str> for CommandError {
+    fn from(message: &str) -> Self {
+        Self::Failed {
+            message: message.to_string(),
+        }
+    }
+}
+
+impl From<FetchError> for CommandError {
+    fn from(e: FetchError) -> Self {
+        let kind = match &e {
+            FetchError::InvalidUrl(_) => NetworkErrorKind::InvalidUrl,
+            FetchError::UnsupportedScheme(_) => NetworkErrorKind::UnsupportedScheme,
+            FetchError::Network(e) if is_dns_failure(e) => NetworkErrorKind::Dns,
+            FetchError::Network(_) => NetworkErrorKind::Connection,
+            FetchError::NotFound(_) => NetworkErrorKind::NotFound,
+            FetchError::ServerError(..) => NetworkErrorKind::Server,
+            FetchError::Timeout(_) => NetworkErrorKind::Timeout,
+            FetchError::TooLarge(_) => NetworkErrorKind::TooLarge,
+        };
+        Self::network(kind, &e)
+    }
+}
+
+impl From<GeminiError> for CommandError {
+    fn from(e: GeminiError) -> Self {
+        let kind = match e {
+            GeminiError::InvalidUrl(_) => NetworkErrorKind::InvalidUrl,
+            GeminiError::TlsError(_) => NetworkErrorKind::Tls,
+            GeminiError::ConnectionError(ref message) if looks_like_dns_failure(message) => {
+                NetworkErrorKind::Dns
+            }
+            GeminiError::ConnectionError(_) => NetworkErrorKind::Connection,
+            GeminiError::Timeout(_) => NetworkErrorKind::Timeout,
+            GeminiError::TooLarge(_) => NetworkErrorKind::TooLarge,
+            GeminiError::InvalidResponse(_) => NetworkErrorKind::InvalidResponse,
+            GeminiError::RedirectLoop(_) => NetworkErrorKind::RedirectLoop,
+            GeminiError::ClientCertRequired { .. } | GeminiError::ClientCertRejected { .. } => {
+                NetworkErrorKind::ClientCertificate
+            }
+            GeminiError::ServerError { .. } => NetworkErrorKind::Server,
+            // Uten adressen kan ikke frontend sende svaret; commands som
+            // henter sider lager spørsmålet selv med `gemini_input`
+            GeminiError::InputRequired(_) | GeminiError::SensitiveInputRequired(_) => {
+                return e.localize().into();
+            }
+            GeminiError::CrossSchemeRedirect(ref url) => return Self::scheme_redirect(url),
+            GeminiError::CertificateChanged {
+                ref host,
+                ref old_fp,
+                ref new_fp,
+            } => {
+                return Self::CertificateChanged {
+                    message: locale::tf("error.certificate_warning", &[host, old_fp, new_fp]),
+                    host: host.clone(),
+                    old_fingerprint: old_fp.clone(),
+                    new_fingerprint: new_fp.clone(),
+                    page: None,
+                };
+            }
+        };
+        Self::network(kind, &e)
+    }
+}
+
+impl From<GopherError> for CommandError {
+    fn from(e: GopherError) -> Self {
+        let kind = match e {
+            GopherError::InvalidUrl(_) => NetworkErrorKind::InvalidUrl,
+            GopherError::ConnectionError(ref message) if looks_like_dns_failure(message) => {
+                NetworkErrorKind::Dns
+            }
+            GopherError::ConnectionError(_) | GopherError::Io(_) => NetworkErrorKind::Connection,
+            GopherError::Timeout(_) => NetworkErrorKind::Timeout,
+            GopherError::TooLarge(_) => NetworkErrorKind::TooLarge,
+            GopherError::InvalidResponse(_) => NetworkErrorKind::InvalidResponse,
+            GopherError::SearchInputRequired => return e.localize().into(),
+        };
+        Self::network(kind, &e)
+    }
+}
+
+/// Om en feil fra reqwest skyldes at vertsnavnet ikke kunne slås opp
+fn is_dns_failure(error: &(dyn std::error::Error + 'static)) -> bool {
+    let mut source = Some(error);
+    while let Some(error) = source {
+        if looks_like_dns_failure(&error.to_string()) {
+            return true;
+        }
+        source = error.source();
+    }
+    false
+}
+
+/// Om en feilmelding kommer fra et navneoppslag som feilet
+///
+/// Meldingene kommer fra hyper og operativsystemet, så de er på engelsk.
+fn looks_like_dns_failure(message: &str) -> bool {
+    const PATTERNS: [&str; 5] = [
+        "dns error",
+        "failed to lookup address",
+        "name or service not known",
+        "nodename nor servname",
+        "no such host is known",
+    ];
+    let message = message.to_lowercase();
+    PATTERNS.iter().any(|pattern| message.contains(pattern))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn shape(error: CommandError) -> serde_json::Value {
+        serde_json::to_value(error).unwrap()
+    }
+
+    #[test]
+    fn test_prompt_shapes() {
+        assert_eq!(
+            shape(CommandError::ConversionPrompt {
+                message: "Konverter?".into(),
+                url: "https://a.example/a:b".into(),
+            }),
+            json!({
+                "kind": "conversion_prompt",
+                "message": "Konverter?",
+                "url": "https://a.example/a:b",
+            })
+        );
+        assert_eq!(
+            shape(CommandError::GeminiInputPrompt {
+                message: "Input".into(),
+                prompt: "Søk: hva?".into(),
+                sensitive: true,
+                url: "gemini://a.example/".into(),
+            }),
+            json!({
+                "kind": "gemini_input_prompt",
+                "message": "Input",
+                "prompt": "Søk: hva?",
+                "sensitive": true,
+                "url": "gemini://a.example/",
+            })
+        );
+        assert_eq!(
+            shape(CommandError::GopherSearchPrompt {
+                message: "Søk".into(),
+                url: "gopher://a.example/7/s".into(),
+            }),
+            json!({
+                "kind": "gopher_search_prompt",
+                "message": "Søk",
+                "url": "gopher://a.example/7/s",
+            })
+        );
+        assert_eq!(
+            shape(CommandError::SchemeRedirect {
+                message: "Videre?".into(),
+                url: "https://a.example/".into(),
+            }),
+            json!({
+                "kind": "scheme_redirect",
+                "message": "Videre?",
+                "url": "https://a.example/",
+            })
+        );
+        assert_eq!(
+            shape(CommandError::DownloadPrompt {
+                message: "Last ned".into(),
+                url: "https://a.example/a.zip".into(),
+            }),
+            json!({
+                "kind": "download_prompt",
+                "message": "Last ned",
+                "url": "https://a.example/a.zip",
+            })
+        );
+        assert_eq!(
+            shape(CommandError::ConfirmNavigation {
+                message: "Fortsette?".into(),
+                url: "https://xn--pple-43d.example/".into(),
+                host: "xn--pple-43d.example".into(),
+                warnings: vec![UrlWarning::MixedScript {
+                    host: "аpple.example".into(),
+                }],
+            }),
+            json!({
+                "kind": "confirm_navigation",
+                "message": "Fortsette?",
+                "url": "https://xn--pple-43d.example/",
+                "host": "xn--pple-43d.example",
+                "warnings": [{ "kind": "mixed_script", "host": "аpple.example" }],
+            })
+        );
+    }
+
+    #[test]
+    fn test_error_shapes() {
+        assert_eq!(
+            shape(CommandError::CertificateChanged {
+                message: "Endret".into(),
+                host: "a.example".into(),
+                old_fingerprint: "aa".into(),
+                new_fingerprint: "bb".into(),
+                page: None,
+            }),
+            json!({
+                "kind": "certificate_changed",
+                "message": "Endret",
+                "host": "a.example",
+                "old_fingerprint": "aa",
+                "new_fingerprint": "bb",
+            })
+        );
+        assert_eq!(
+            shape(CommandError::Superseded {
+                message: "Avbrutt".into()
+            }),
+            json!({ "kind": "superseded", "message": "Avbrutt" })
+        );
+        assert_eq!(
+            shape(CommandError::Network {
+                message: "Timeout".into(),
+                kind: NetworkErrorKind::Timeout,
+                page: None,
+            }),
+            json!({ "kind": "network", "message": "Timeout", "network_kind": "timeout" })
+        );
+        assert_eq!(
+            shape(CommandError::Internal {
+                message: "Intern feil".into()
+            }),
+            json!({ "kind": "internal", "message": "Intern feil" })
+        );
+        assert_eq!(
+            shape(CommandError::from("Noe gikk galt")),
+            json!({ "kind": "failed", "message": "Noe gikk galt" })
+        );
+    }
+
+    #[test]
+    fn test_from_protocol_errors() {
+        let error = CommandError::from(FetchError::Timeout(30));
+        assert!(matches!(
+            error,
+            CommandError::Network {
+                kind: NetworkErrorKind::Timeout,
+                ..
+            }
+        ));
+        assert_eq!(error.to_string(), FetchError::Timeout(30).localize());
+
+        let error = CommandError::from(GeminiError::CertificateChanged {
+            host: "a.example".into(),
+            old_fp: "aa".into(),
+            new_fp: "bb".into(),
+        });
+        assert!(matches!(
+            &error,
+            CommandError::CertificateChanged { host, .. } if host == "a.example"
+        ));
+
+        let error = CommandError::from(GeminiError::ConnectionError(
+            "failed to lookup address information: Name or service not known".into(),
+        ));
+        assert!(matches!(
+            error,
+            CommandError::Network {
+                kind: NetworkErrorKind::Dns,
+                ..
+            }
+        ));
+
+        let error = CommandError::from(GopherError::ConnectionError("nektet".into()));
+        assert!(matches!(
+            error,
+            CommandError::Network {
+                kind: NetworkErrorKind::Connection,
+                ..
+            }
+        ));
+    }
+}
@@ -2,20 +2,69 @@
 //!
 //! IPC-kommandoer som kan kalles fra frontend.
 
-use crate::bookmarks::{self, Bookmark, BookmarkStore};
+use crate::address::{self, AddressResolution};
+use crate::app_state::AppState;
+use crate::autocomplete::{AutocompleteIndex, Suggestion};
+use crate::bookmark_io::{self, BookmarkFormat, ImportSummary, MergeSummary};
+use crate::bookmarks::{
+    self, Bookmark, BookmarkFilter, BookmarkSort, BookmarkStore, TagCount, Toggle,
+};
+use crate::breadcrumbs::{self, Breadcrumb};
+use crate::broadcast::EventSink;
+use crate::browsing_data::{
+    self, ClearCategory, ClearOptions, ClearResult, KnownHosts, PageCaches, RecentFileList,
+    SessionState, SiteOverrides,
+};
+use crate::command_error::CommandError;
 use crate::converter;
-use crate::fetcher::{self, Fetcher};
-use crate::gemini::{self, GeminiClient, GeminiError};
+use crate::deep_link::{self, DeepLinks, MAIN_WINDOW, NAVIGATE_REQUEST_EVENT};
+use crate::downloads::{self, Download, DownloadBody, DownloadError, DownloadId, DownloadState};
+use crate::error_page;
+use crate::export::{self, ExportFormat, ExportStyle};
+use crate::fetcher;
+use crate::find::{self, FindMode, FindResult};
+use crate::gemini::{self, GeminiError};
+use crate::gemini_identity::IdentityInfo;
 use crate::gemtext;
 use crate::gopher;
 use crate::gophermap;
+use crate::history::{HistoryMatch, HistoryPage, HistoryRange, SiteVisits};
+use crate::keybindings::{self, KeyBindingInfo};
+use crate::linkcheck::{LinkCheckResult, LinkChecker};
+use crate::local_files::{self, LocalFormat};
+use crate::locale::{self, Localize};
+use crate::localsite::{self, LocalSite};
+use crate::logging;
 use crate::markdown;
-use crate::settings::{self, ConversionMode, FontFamily, Settings, Theme};
+use crate::page_security::{Protocol, SecurityInfo};
+use crate::page_source::{self, PageSource, SourceFormat};
+use crate::pages::{self, Page};
+use crate::prefetch::{self, PrefetchScheme, Prefetched};
+use crate::progress::{self, LoadProgress, LoadStage, ProgressSink};
+use crate::recent_files::RecentFile;
+use crate::render_cache::RenderCacheStats;
+use crate::settings::{
+    self, ConversionMode, Decorations, DefaultScheme, DomainRule, DownloadTarget, FontFamily,
+    HomePage, LogLevel, NetworkSettings, PlainText, PrivacySettings, ResetScope, Settings,
+    SiteOverride, SiteSettings, Theme, ThemeColors, Typography,
+};
+use crate::snapshots;
+use crate::startup::{StartupArgument, StartupTargets};
+use crate::storage::{self, Backup};
+use crate::timing::TimingInfo;
+use crate::url_safety::{self, UrlWarning};
+use crate::watcher;
+use crate::window_state::{FetchTicket, HistoryStep, WindowRegistry, WindowState};
+use log::{debug, info, warn};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::PathBuf;
-use std::sync::{LazyLock, Mutex};
-use tauri::Emitter;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_dialog::DialogExt;
 
 // Emoji-konstanter for protokollidentifikasjon
 const EMOJI_HTTPS: &str = "🔒";
@@ -24,30 +73,78 @@ const EMOJI_GEMINI: &str = "📡";
 const EMOJI_GOPHER: &str = "🐿️";
 const EMOJI_FILE: &str = "📁";
 
-/// Global HTTP-klient (gjenbrukes for alle forespørsler)
-static FETCHER: LazyLock<Fetcher> = LazyLock::new(Fetcher::new);
+/// Minste tid mellom to download-progress for samme nedlasting
+const DOWNLOAD_PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
 
-/// Global Gemini-klient (gjenbrukes for alle Gemini-forespørsler)
-static GEMINI_CLIENT: LazyLock<GeminiClient> = LazyLock::new(GeminiClient::new);
+/// Hent ut en forhåndshentet side for adressen, hvis den finnes
+fn take_prefetched(state: &AppState, url: &str) -> Option<Prefetched> {
+    let prefetched = state.session.prefetched.lock().take(url, Instant::now());
+    if prefetched.is_some() {
+        debug!(
+            "[forhåndshenting] Bruker hentet kopi av {}",
+            state.session.private_mode.log_url(url)
+        );
+    }
+    prefetched
+}
+
+/// Adressen som faktisk hentes ved navigering
+///
+/// Fjerner sporingsparametere hvis brukeren har slått det på.
+fn outgoing_url(state: &AppState, url: String) -> String {
+    if state.settings.read().privacy.strip_query_params {
+        fetcher::strip_tracking_params(&url)
+    } else {
+        url
+    }
+}
 
-/// Global bokmerke-lagring
-static BOOKMARKS: LazyLock<Mutex<BookmarkStore>> = LazyLock::new(|| {
-    let path = bookmarks::get_bookmarks_path();
-    Mutex::new(BookmarkStore::load(&path).unwrap_or_default())
-});
+/// Send en statusmelding til vinduet som startet hentingen
+///
+/// `emit` på et vindu når alle vinduer, så meldingen adresseres med etiketten.
+fn emit_status<S: Serialize + Clone>(window: &tauri::Window, status: S) {
+    let _ = window.emit_to(window.label(), progress::LOADING_STATUS_EVENT, status);
+}
 
-/// Global innstillingslagring
-static SETTINGS: LazyLock<Mutex<Settings>> = LazyLock::new(|| {
-    let path = settings::get_settings_path();
-    Mutex::new(Settings::load(&path).unwrap_or_default())
-});
+/// Start en henting i vinduet; eldre hentinger i samme vindu blir utdatert
+fn begin_fetch(window: &tauri::Window) -> FetchTicket {
+    window
+        .state::<WindowRegistry>()
+        .lock()
+        .begin_fetch(window.label())
+}
 
-/// Ekstraher vertsnavn fra en URL for visning i statusbar
-fn extract_host(url: &str) -> String {
-    url::Url::parse(url)
-        .ok()
-        .and_then(|u| u.host_str().map(|h| h.to_string()))
-        .unwrap_or_else(|| url.to_string())
+/// Avslutt en henting og registrer siden vinduet endte opp på
+///
+/// Har en nyere henting startet i samme vindu imens, forkastes resultatet
+/// med `CommandError::Superseded`. Frontend ignorerer den,
+/// slik at en treg side ikke skriver over den brukeren navigerte til etterpå.
+/// Hentede sider holdes i minnet, så tilbake og frem kan vise dem igjen.
+fn finish_fetch(
+    window: &tauri::Window,
+    ticket: &FetchTicket,
+    page: Result<RenderedPage, CommandError>,
+) -> Result<RenderedPage, CommandError> {
+    let registry = window.state::<WindowRegistry>();
+    let mut windows = registry.lock();
+    let url = page.as_ref().ok().and_then(|page| page.url.as_deref());
+    if !windows.finish_fetch(ticket, url) {
+        return Err(CommandError::superseded());
+    }
+    if let Some(page) = page.as_ref().ok().filter(|page| page.is_remote) {
+        windows.remember_page(window.label(), page.clone());
+    }
+    page
+}
+
+/// Registrer en side som lages av appen selv, f.eks. bokmerkesiden
+///
+/// Siden vises med en gang, så hentinger som pågår i vinduet blir utdatert.
+fn visit_location(window: &tauri::Window, location: &str) {
+    let registry = window.state::<WindowRegistry>();
+    let mut windows = registry.lock();
+    let ticket = windows.begin_fetch(window.label());
+    windows.finish_fetch(&ticket, Some(location));
 }
 
 /// Henter app-versjon fra Cargo.toml
@@ -56,8 +153,36 @@ pub fn get_app_version() -> String {
     env!("CARGO_PKG_VERSION").to_string()
 }
 
+/// De siste linjene i loggfilen, f.eks. til en feilrapport
+///
+/// # Arguments
+/// * `lines` - Maksimalt antall linjer (standard 200)
+///
+/// # Returns
+/// Linjene med eldste først, tom hvis ingenting er logget ennå
+#[tauri::command]
+pub fn get_recent_logs(
+    lines: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, CommandError> {
+    logging::recent_lines(&state.paths.log_file(), lines.unwrap_or(200))
+        .map_err(|e| locale::tf("error.log_read", &[&e]).into())
+}
+
+/// Stien til loggfilen, slik at brukeren kan legge den ved en feilrapport
+#[tauri::command]
+pub fn get_log_file_path(state: State<'_, AppState>) -> String {
+    state.paths.log_file().to_string_lossy().into_owned()
+}
+
+/// Treff og bom i mellomlageret for rendret markdown, for feilsøking
+#[tauri::command]
+pub fn get_render_cache_stats(state: State<'_, AppState>) -> RenderCacheStats {
+    state.session.render_cache.lock().stats()
+}
+
 /// Resultat fra markdown-rendering
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RenderedPage {
     /// HTML-innhold
     pub html: String,
@@ -72,513 +197,4076 @@ pub struct RenderedPage {
     /// Om innholdet ble konvertert fra HTML
     #[serde(default)]
     pub was_converted: bool,
+    /// Fremdrift i oppgavelister (hvis dokumentet har noen)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tasks: Option<markdown::TaskSummary>,
+    /// Antall diagramblokker vist som kilde i stedet for rendret
+    #[serde(default)]
+    pub skipped_diagrams: usize,
+    /// Om innholdet er en arkivert kopi i stedet for den levende siden
+    #[serde(default)]
+    pub is_archived: bool,
+    /// Om bare hovedinnholdet ble tatt med da HTML ble konvertert (lesemodus)
+    #[serde(default)]
+    pub used_readability: bool,
+    /// Om dokumentet var for stort og bare begynnelsen ble rendret
+    #[serde(default)]
+    pub truncated: bool,
+    /// Forbindelsen siden ble hentet over; None for lokale sider
+    #[serde(default)]
+    pub security: Option<SecurityInfo>,
+    /// Hvor lang tid hver fase av hentingen tok; None for lokale sider
+    #[serde(default)]
+    pub timing: Option<TimingInfo>,
 }
 
-/// Rendrer markdown-tekst til HTML
+/// Renderingsopsjoner basert på brukerens innstillinger
 ///
 /// # Arguments
-/// * `content` - Markdown-innhold som skal rendres
-///
-/// # Returns
-/// RenderedPage med HTML og eventuell tittel
-#[tauri::command]
-pub fn render_markdown(content: String) -> RenderedPage {
-    let html = markdown::render(&content);
-    let title = markdown::extract_title(&content);
+/// * `base_url` - Adressen dokumentet ble lastet fra, hvis kjent
+fn render_options(state: &AppState, base_url: Option<&str>) -> markdown::RenderOptions {
+    let mut options = base_url
+        .map(markdown::RenderOptions::with_base)
+        .unwrap_or_default();
+    let settings = state.settings.read();
+    options.diagram_languages = settings.diagram_languages.clone();
+    options.block_remote_images = !settings.privacy.load_remote_images;
+    options.max_input_bytes = Some(settings.max_render_bytes());
+    options.locale = locale::current();
+    options.cache = Some(state.session.render_cache.clone());
+    options
+}
 
-    RenderedPage {
-        html,
-        title,
-        url: None,
-        is_remote: false,
-        was_converted: false,
-    }
+/// Renderingsopsjoner for en side fra et nettsted med egne innstillinger
+fn site_render_options(
+    state: &AppState,
+    base_url: Option<&str>,
+    site: &SiteSettings,
+) -> markdown::RenderOptions {
+    let mut options = render_options(state, base_url);
+    options.strip_images = site.strip_images;
+    options
 }
 
-/// Åpner og leser en lokal markdown-fil
+/// Kjør CPU-tungt arbeid på en egen tråd
+///
+/// Konvertering og rendering av en stor side kan ta flere hundre
+/// millisekunder. På async-runtimen ville det holdt igjen alle andre
+/// commands imens, også den som avbryter hentingen. Tiden logges per steg.
 ///
 /// # Arguments
-/// * `path` - Sti til filen som skal åpnes
+/// * `phase` - Steget, for loggen
+/// * `work` - Arbeidet
 ///
 /// # Returns
-/// RenderedPage med HTML og tittel, eller feilmelding
-#[tauri::command]
-pub fn open_file(path: String, window: tauri::Window) -> Result<RenderedPage, String> {
-    let path = PathBuf::from(&path);
+/// Resultatet, eller `CommandError::Internal` hvis arbeidet fikk panikk
+async fn run_blocking<T, F>(phase: &str, work: F) -> Result<T, CommandError>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let started = Instant::now();
+    let result = tokio::task::spawn_blocking(work).await;
+    debug!("[{}] {} ms", phase, started.elapsed().as_millis());
+    result.map_err(|e| {
+        warn!("[{}] Feilet: {}", phase, e);
+        CommandError::internal()
+    })
+}
 
-    // Sjekk at filen eksisterer
-    if !path.exists() {
-        return Err(format!("Filen finnes ikke: {}", path.display()));
+/// Konverteringsvalgene for et nettsted
+///
+/// HTML utover grensen for rendring konverteres ikke.
+fn conversion_options(state: &AppState, site: &SiteSettings) -> converter::ConversionOptions {
+    converter::ConversionOptions {
+        readability: site.readability_enabled,
+        keep_images: !site.strip_images,
+        max_bytes: Some(state.settings.read().max_render_bytes()),
     }
+}
 
-    // Sjekk at det er en markdown-fil
-    match path.extension() {
-        Some(ext) if ext == "md" || ext == "markdown" => {}
-        _ => {
-            return Err("Bare støtter kun markdown-filer (.md, .markdown)".to_string());
+/// Konverter HTML til markdown på en egen tråd
+async fn convert_blocking(
+    html: String,
+    options: converter::ConversionOptions,
+) -> Result<converter::ConversionResult, CommandError> {
+    run_blocking("konvertering", move || {
+        converter::html_to_markdown_with(&html, &options)
+    })
+    .await
+}
+
+/// Rendre markdown på en egen tråd
+///
+/// # Returns
+/// Resultatet og markdown-kilden, som trengs videre for arkiverte kopier
+async fn render_blocking(
+    source: String,
+    options: markdown::RenderOptions,
+) -> Result<(markdown::RenderOutput, String), CommandError> {
+    run_blocking("rendering", move || {
+        let rendered = markdown::render_with_options(&source, &options);
+        (rendered, source)
+    })
+    .await
+}
+
+/// Ikonene foran lenker i generert innhold
+fn decorations(state: &AppState) -> Decorations {
+    state.settings.read().decorations
+}
+
+/// Hvordan ren tekst vises
+fn plain_text_style(state: &AppState) -> PlainText {
+    state.settings.read().plain_text
+}
+
+/// En lokal fil gjort om til markdown
+#[derive(Debug)]
+struct LocalDocument {
+    markdown: String,
+    title: Option<String>,
+    was_converted: bool,
+}
+
+/// Gjør innholdet i en lokal fil om til markdown
+///
+/// # Arguments
+/// * `content` - Filinnholdet
+/// * `format` - Formatet fra filendelsen
+/// * `plain_text` - Visningen av ren tekst fra innstillingene
+fn local_document(content: &str, format: LocalFormat, plain_text: PlainText) -> LocalDocument {
+    match format {
+        LocalFormat::Markdown => LocalDocument {
+            markdown: content.to_string(),
+            title: markdown::extract_title(content),
+            was_converted: false,
+        },
+        LocalFormat::Gemtext => {
+            let result = gemtext::gemtext_to_markdown(content);
+            let title = result
+                .title
+                .or_else(|| markdown::extract_title(&result.markdown));
+            LocalDocument {
+                markdown: result.markdown,
+                title,
+                was_converted: true,
+            }
         }
+        LocalFormat::Text => LocalDocument {
+            markdown: markdown::plain_text_to_markdown(content, plain_text),
+            title: None,
+            was_converted: true,
+        },
     }
+}
 
-    // Steg 1: Åpner fil
-    let filename = path.file_name().and_then(|n| n.to_str()).unwrap_or("fil");
-    let _ = window.emit(
-        "loading-status",
-        format!("{} Lokal fil: Åpner {}...", EMOJI_FILE, filename),
-    );
+/// Vertsnavnet i en URL, eller None for lokale filer og ugyldige adresser
+fn url_host(url: &str) -> Option<String> {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+}
 
-    // Les innholdet
-    let content = fs::read_to_string(&path).map_err(|e| format!("Kunne ikke lese fil: {}", e))?;
+/// Konverteringsmodusen for en side
+///
+/// Nettstedets overstyring går foran domeneregler, og domeneregler går
+/// foran standardmodusen for HTTP.
+fn effective_conversion_mode(url: &str, settings: &Settings) -> ConversionMode {
+    settings
+        .site_settings(url_host(url).as_deref())
+        .conversion_mode
+}
 
-    // Steg 2: Rendrer markdown
-    let _ = window.emit(
-        "loading-status",
-        format!("{} Lokal fil: Rendrer markdown...", EMOJI_FILE),
-    );
-    let html = markdown::render(&content);
-    let title = markdown::extract_title(&content);
+/// Hent innstillingene som gjelder for vertsnavnet i en URL
+fn site_settings_for(state: &AppState, url: &str) -> SiteSettings {
+    let host = url_host(url);
+    state.settings.read().site_settings(host.as_deref())
+}
 
-    let _ = window.emit("loading-status", "Dokument: Ferdig");
+/// Husk markdown-kilden til en side som vises, slik at den kan arkiveres
+fn remember_source(state: &AppState, url: &str, markdown: &str) {
+    state.session.recent_pages.lock().remember(url, markdown);
+}
 
-    Ok(RenderedPage {
-        html,
-        title,
-        url: Some(format!("file://{}", path.display())),
-        is_remote: false,
-        was_converted: false,
-    })
+/// Husk bytene serveren sendte for en side og forbindelsen de kom over,
+/// for `save_page_source` og `get_page_security`
+///
+/// # Arguments
+/// * `url` - Den endelige adressen
+/// * `bytes` - Innholdet før dekoding
+/// * `content_type` - Content-Type eller Gemini-meta, hvis kjent
+/// * `security` - Forbindelsen siden ble hentet over
+fn remember_raw(
+    state: &AppState,
+    url: &str,
+    bytes: &[u8],
+    content_type: Option<&str>,
+    security: &SecurityInfo,
+) {
+    let source = PageSource {
+        bytes: bytes.to_vec(),
+        format: SourceFormat::detect(content_type, url),
+    };
+    let mut recent_pages = state.session.recent_pages.lock();
+    recent_pages.remember_raw(url, source);
+    recent_pages.remember_security(url, security.clone());
 }
 
-/// Henter og rendrer markdown fra en URL
+/// Legg en vist side til i historikken, hvis brukeren har slått den på
+fn record_visit(state: &AppState, url: &str, title: Option<&str>) {
+    let limits = {
+        let settings = state.settings.read();
+        if !settings.history_enabled {
+            return;
+        }
+        settings.history_limits()
+    };
+    let now = bookmarks::current_timestamp();
+    match state.history.lock().record(url, title, now, limits) {
+        Ok(Some(_)) => state.invalidate_autocomplete(),
+        Ok(None) => {}
+        Err(e) => warn!("{}", e),
+    }
+}
+
+/// Rendrer markdown-tekst til HTML
 ///
 /// # Arguments
-/// * `url` - URL til markdown-filen som skal hentes
+/// * `content` - Markdown-innhold som skal rendres
 ///
 /// # Returns
-/// RenderedPage med HTML og tittel, eller feilmelding
+/// RenderedPage med HTML og eventuell tittel
 #[tauri::command]
-pub async fn fetch_url(url: String, window: tauri::Window) -> Result<RenderedPage, String> {
-    // Detekter protokoll
-    let parsed_url = url::Url::parse(&url).map_err(|e| e.to_string())?;
-    let scheme = parsed_url.scheme();
-    let host = extract_host(&url);
-    let protocol_emoji = if scheme == "https" {
-        EMOJI_HTTPS
-    } else {
-        EMOJI_HTTP
-    };
-    let protocol_name = if scheme == "https" { "HTTPS" } else { "HTTP" };
-
-    // Steg 1: Slår opp vert
-    let _ = window.emit(
-        "loading-status",
-        format!("{} {}: Slår opp {}...", protocol_emoji, protocol_name, host),
-    );
-
-    // Steg 2: Kobler til
-    let tls_info = if scheme == "https" { "/TLS" } else { "" };
-    let _ = window.emit(
-        "loading-status",
-        format!(
-            "{} {}{}: Kobler til {}...",
-            protocol_emoji, protocol_name, tls_info, host
-        ),
-    );
-
-    let result = FETCHER.fetch(&url).await.map_err(|e| {
-        let _ = window.emit("loading-status", "Feil under henting");
-        e.to_string()
-    })?;
+pub fn render_markdown(content: String, state: State<'_, AppState>) -> RenderedPage {
+    render_markdown_page(&state, &content)
+}
 
-    // Steg 3: Overfører data
-    let bytes = result.content.len();
-    let _ = window.emit(
-        "loading-status",
-        format!("Overfører data... ({} bytes)", bytes),
-    );
+/// Selve renderingen bak `render_markdown`
+fn render_markdown_page(state: &AppState, content: &str) -> RenderedPage {
+    let rendered = markdown::render_with_options(content, &render_options(state, None));
+    let title = markdown::extract_title(content);
 
-    // Hent konverteringsinnstillinger
-    let settings = SETTINGS.lock().unwrap();
-    let conversion_mode = settings.conversion_mode.clone();
-    let _readability_enabled = settings.readability_enabled;
-    drop(settings);
+    RenderedPage {
+        html: rendered.html,
+        title,
+        url: None,
+        is_remote: false,
+        was_converted: false,
+        tasks: rendered.tasks,
+        skipped_diagrams: rendered.skipped_diagrams,
+        truncated: rendered.truncated,
+        security: None,
+        timing: None,
+        is_archived: false,
+        used_readability: false,
+    }
+}
 
-    if result.is_markdown {
-        // Steg 4: Rendrer markdown
-        let _ = window.emit("loading-status", "Rendrer markdown...");
-        let html = markdown::render(&result.content);
-        let title = markdown::extract_title(&result.content);
+/// Formatet til innhold som rendres direkte, se `render_content`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentFormat {
+    Markdown,
+    Gemtext,
+    Html,
+}
 
-        let _ = window.emit("loading-status", "Dokument: Ferdig");
+/// Rendrer innhold som er sluppet eller limt inn, uten å gå via en fil
+///
+/// HTML konverteres som sider fra nettet, gemtext som Gemini-sider, og
+/// markdown rendres direkte. Relative lenker og bilder løses mot `base`.
+/// Størrelsesgrensen for lokale filer gjelder.
+///
+/// # Arguments
+/// * `content` - Innholdet
+/// * `format` - "markdown", "gemtext" eller "html"
+/// * `base` - Filsti eller adresse innholdet kom fra, hvis den er kjent
+///
+/// # Returns
+/// RenderedPage med `url` fra `base`, eller feilmelding hvis innholdet er
+/// for stort
+#[tauri::command]
+pub fn render_content(
+    content: String,
+    format: ContentFormat,
+    base: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<RenderedPage, CommandError> {
+    render_pasted(&state, content, format, base)
+}
 
-        return Ok(RenderedPage {
-            html,
-            title,
-            url: Some(result.final_url),
-            is_remote: true,
-            was_converted: false,
-        });
+/// Selve renderingen bak `render_content`
+fn render_pasted(
+    state: &AppState,
+    content: String,
+    format: ContentFormat,
+    base: Option<String>,
+) -> Result<RenderedPage, CommandError> {
+    let max = state.settings.read().open_policy().max_size;
+    let size = content.len() as u64;
+    if size > max {
+        return Err(local_files::LocalFileError::TooLarge { size, max }
+            .localize()
+            .into());
     }
 
-    // Ikke-markdown innhold - sjekk konverteringsmodus
-    match conversion_mode {
-        ConversionMode::MarkdownOnly => {
-            let _ = window.emit("loading-status", "Stoppet: Kun markdown");
-            Err(format!(
-                "Innholdet er ikke markdown (Content-Type: {:?}). Konvertering er deaktivert i innstillingene.",
-                result.content_type
-            ))
+    let url = base.as_deref().and_then(content_base_url);
+    let site = site_settings_for(state, url.as_deref().unwrap_or_default());
+    let mut conversion = None;
+    let document = match format {
+        ContentFormat::Markdown => {
+            local_document(&content, LocalFormat::Markdown, plain_text_style(state))
         }
-        ConversionMode::AskEverytime => {
-            let _ = window.emit("loading-status", "Venter på brukervalg...");
-            // Returner en spesiell respons som ber frontend spørre brukeren
-            Err(format!(
-                "CONVERSION_PROMPT:Innholdet er HTML. Vil du konvertere det til markdown?:{}",
-                result.final_url
-            ))
+        ContentFormat::Gemtext => {
+            local_document(&content, LocalFormat::Gemtext, plain_text_style(state))
         }
-        ConversionMode::ConvertAll => {
-            // Steg 4: Konverterer HTML
-            let _ = window.emit("loading-status", "Konverterer HTML til markdown...");
-            let conversion_result = converter::html_to_markdown(&result.content);
-
-            // Steg 5: Rendrer markdown
-            let _ = window.emit("loading-status", "Rendrer markdown...");
-            let html = markdown::render(&conversion_result.markdown);
-
-            let title = conversion_result
+        ContentFormat::Html => {
+            let result =
+                converter::html_to_markdown_with(&content, &conversion_options(state, &site));
+            conversion = Some((result.used_readability, result.truncated));
+            let title = result
                 .title
-                .or_else(|| markdown::extract_title(&conversion_result.markdown));
-
-            let _ = window.emit("loading-status", "Dokument: Ferdig");
-
-            Ok(RenderedPage {
-                html,
+                .or_else(|| markdown::extract_title(&result.markdown));
+            LocalDocument {
+                markdown: result.markdown,
                 title,
-                url: Some(result.final_url),
-                is_remote: true,
                 was_converted: true,
-            })
+            }
         }
+    };
+
+    let rendered = markdown::render_with_options(
+        &document.markdown,
+        &site_render_options(state, url.as_deref(), &site),
+    );
+    if let Some(url) = &url {
+        remember_source(state, url, &document.markdown);
     }
+    let is_remote = url
+        .as_deref()
+        .and_then(|u| url::Url::parse(u).ok())
+        .is_some_and(|u| u.scheme() != "file");
+
+    Ok(RenderedPage {
+        html: rendered.html,
+        title: document.title,
+        url,
+        is_remote,
+        was_converted: document.was_converted,
+        tasks: rendered.tasks,
+        skipped_diagrams: rendered.skipped_diagrams,
+        truncated: rendered.truncated || conversion.is_some_and(|(_, truncated)| truncated),
+        security: None,
+        timing: None,
+        is_archived: false,
+        used_readability: conversion.is_some_and(|(readability, _)| readability),
+    })
 }
 
-/// Konverter HTML til markdown manuelt (for "Spør hver gang"-modus)
+/// Adressen til en base for `render_content`
+///
+/// En adresse brukes som den er; en absolutt filsti blir en file://-adresse.
+fn content_base_url(base: &str) -> Option<String> {
+    let base = base.trim();
+    match url::Url::parse(base) {
+        // Windows-stier som "C:\docs" tolkes som en URL med ett-bokstavs skjema
+        Ok(url) if url.scheme().len() > 1 => Some(url.into()),
+        _ => url::Url::from_file_path(base).ok().map(String::from),
+    }
+}
+
+/// Ekstraherer ren tekst fra markdown-innhold
 ///
 /// # Arguments
-/// * `url` - URL til siden som skal konverteres
+/// * `content` - Markdown-innhold
+/// * `include_link_urls` - Legg til URL i parentes etter lenketekst
 ///
 /// # Returns
-/// RenderedPage med konvertert innhold
+/// Ren tekst uten markdown-syntaks, egnet for kopiering og opplesning
 #[tauri::command]
-pub async fn convert_url(url: String, window: tauri::Window) -> Result<RenderedPage, String> {
-    // Detekter protokoll
-    let parsed_url = url::Url::parse(&url).map_err(|e| e.to_string())?;
-    let scheme = parsed_url.scheme();
-    let host = extract_host(&url);
-    let protocol_emoji = if scheme == "https" {
-        EMOJI_HTTPS
-    } else {
-        EMOJI_HTTP
-    };
-    let protocol_name = if scheme == "https" { "HTTPS" } else { "HTTP" };
+pub fn get_plain_text(content: String, include_link_urls: Option<bool>) -> String {
+    markdown::to_plain_text(&content, include_link_urls.unwrap_or(false))
+}
 
-    let _ = window.emit(
-        "loading-status",
-        format!("{} {}: Slår opp {}...", protocol_emoji, protocol_name, host),
-    );
-    let tls_info = if scheme == "https" { "/TLS" } else { "" };
-    let _ = window.emit(
-        "loading-status",
-        format!(
-            "{} {}{}: Kobler til {}...",
-            protocol_emoji, protocol_name, tls_info, host
-        ),
-    );
+/// Søker etter tekst i den viste siden
+///
+/// # Arguments
+/// * `content` - Markdown-innhold for siden
+/// * `query` - Søketekst eller regulært uttrykk
+/// * `case_sensitive` - Skill mellom store og små bokstaver
+/// * `mode` - "literal" (standard), "word" eller "regex"
+///
+/// # Returns
+/// Treff med kontekst og nærmeste overskrift, eller feilmelding
+#[tauri::command]
+pub fn find_in_page(
+    content: String,
+    query: String,
+    case_sensitive: bool,
+    mode: Option<FindMode>,
+) -> Result<FindResult, CommandError> {
+    find::find_in_document(&content, &query, case_sensitive, mode.unwrap_or_default())
+        .map_err(|e| e.localize().into())
+}
 
-    let result = FETCHER.fetch(&url).await.map_err(|e| {
-        let _ = window.emit("loading-status", "Feil under henting");
-        e.to_string()
-    })?;
+/// Åpner og leser en lokal markdown-fil
+///
+/// # Arguments
+/// * `path` - Sti til filen som skal åpnes
+///
+/// # Returns
+/// RenderedPage med HTML og tittel, eller feilmelding
+#[tauri::command]
+pub fn open_file(
+    path: String,
+    window: tauri::Window,
+    state: State<'_, AppState>,
+) -> Result<RenderedPage, CommandError> {
+    let ticket = begin_fetch(&window);
+    let page = load_file(&state, path, window.clone());
+    finish_fetch(&window, &ticket, page)
+}
 
-    let bytes = result.content.len();
-    let _ = window.emit(
-        "loading-status",
-        format!("Overfører data... ({} bytes)", bytes),
+/// Selve lesingen bak `open_file`
+fn load_file(
+    state: &AppState,
+    path: String,
+    window: tauri::Window,
+) -> Result<RenderedPage, CommandError> {
+    // Sjekk at filen finnes og kan åpnes; symbolske lenker byttes med målet
+    let policy = state.settings.read().open_policy();
+    let (path, metadata) =
+        local_files::resolve(Path::new(&path), &policy).map_err(|e| e.localize())?;
+
+    if metadata.is_dir() {
+        return load_directory(state, &path, &window);
+    }
+
+    // Sjekk at det er et format Bare kan vise
+    let Some(format) = LocalFormat::from_path(&path) else {
+        return Err(locale::t("error.file_unsupported").into());
+    };
+
+    // Steg 1: Åpner fil
+    let filename = path.file_name().and_then(|n| n.to_str()).unwrap_or("fil");
+    emit_status(
+        &window,
+        locale::tf("status.file_opening", &[&EMOJI_FILE, &filename]),
     );
 
-    // Konverter HTML til markdown
-    let _ = window.emit("loading-status", "Konverterer HTML til markdown...");
-    let conversion_result = converter::html_to_markdown(&result.content);
+    // Steg 2: Rendrer markdown
+    emit_status(&window, locale::tf("status.file_rendering", &[&EMOJI_FILE]));
+    let page = render_local_file(state, &path, format)?;
+    if let Some(url) = &page.url {
+        record_visit(state, url, page.title.as_deref());
+    }
+    remember_file(state, &path);
 
-    // Render markdown til HTML for visning
-    let _ = window.emit("loading-status", "Rendrer markdown...");
-    let html = markdown::render(&conversion_result.markdown);
+    emit_status(&window, locale::t("status.done"));
 
-    // Bruk tittel fra konvertering eller markdown
-    let title = conversion_result
-        .title
-        .or_else(|| markdown::extract_title(&conversion_result.markdown));
+    Ok(page)
+}
 
-    let _ = window.emit("loading-status", "Dokument: Ferdig");
+/// Les og rendre en lokal fil; relative lenker løses mot filens mappe
+///
+/// Lenker og bilder må holde seg innenfor det lokale nettstedet filen hører
+/// til, eller mappen filen ligger i.
+fn render_local_file(
+    state: &AppState,
+    path: &Path,
+    format: LocalFormat,
+) -> Result<RenderedPage, String> {
+    let policy = state.settings.read().open_policy();
+    let content = local_files::read(path, &policy).map_err(|e| e.localize())?;
+    let document = local_document(&content, format, plain_text_style(state));
+    let site = LocalSite::find(path);
+    let mut options = render_options(state, Some(&path.to_string_lossy()));
+    options.local_root = site
+        .as_ref()
+        .map(|site| site.root.clone())
+        .or_else(|| path.parent().map(Path::to_path_buf));
+    let rendered = markdown::render_with_options(&document.markdown, &options);
+    // Sider i et lokalt nettsted får navigasjon over innholdet
+    let html = match site.and_then(|site| site.nav_markdown(path)) {
+        Some(nav) => format!(
+            "<nav class=\"local-site-nav\">{}</nav>\n{}",
+            markdown::render_with_options(&nav, &render_options(state, None)).html,
+            rendered.html
+        ),
+        None => rendered.html,
+    };
+    let url = url::Url::from_file_path(path)
+        .map(String::from)
+        .unwrap_or_else(|_| format!("file://{}", path.display()));
+    remember_source(state, &url, &document.markdown);
 
     Ok(RenderedPage {
         html,
-        title,
-        url: Some(result.final_url),
-        is_remote: true,
-        was_converted: true,
+        title: document.title,
+        url: Some(url),
+        is_remote: false,
+        was_converted: document.was_converted,
+        tasks: rendered.tasks,
+        skipped_diagrams: rendered.skipped_diagrams,
+        truncated: rendered.truncated,
+        security: None,
+        timing: None,
+        is_archived: false,
+        used_readability: false,
     })
 }
 
-/// Løser en relativ URL mot en base-URL
+/// Last siden på nytt når den lokale filen endres på disk
 ///
-/// # Arguments
-/// * `base_url` - Nåværende side sin URL
-/// * `relative_url` - Relativ URL som skal løses
+/// Vinduet får `file-changed` med den nye siden etter hver serie endringer.
+/// Mappen filen ligger i overvåkes, så filer som slettes og lages på nytt
+/// ved lagring fortsetter å bli fulgt. Et vindu overvåker høyst én fil; en ny
+/// overvåking erstatter den forrige.
 ///
-/// # Returns
+/// # Arguments
+/// * `path` - Filen vinduet viser
+#[tauri::command]
+pub fn watch_file(
+    path: String,
+    window: tauri::Window,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    // Samme sti som `open_file` viste, så adressen i file-changed stemmer
+    let path = fs::canonicalize(&path).unwrap_or_else(|_| PathBuf::from(path));
+    let Some(format) = LocalFormat::from_path(&path) else {
+        return Err(locale::t("error.file_unsupported").into());
+    };
+
+    let label = window.label().to_string();
+    let target = window.clone();
+    let watched = path.clone();
+    let file_watcher = watcher::watch_file(&path, move || {
+        // Filen kan være borte et øyeblikk midt i en lagring; da kommer en ny
+        // endring når den er tilbake
+        let Ok(page) = render_local_file(&target.state::<AppState>(), &watched, format) else {
+            return;
+        };
+        if let Err(e) = target.emit_to(target.label(), "file-changed", &page) {
+            warn!("Kunne ikke sende file-changed: {}", e);
+        }
+    })
+    .map_err(|e| locale::tf("error.watch_file", &[&e]))?;
+
+    state
+        .session
+        .file_watchers
+        .lock()
+        .insert(label, file_watcher);
+    Ok(())
+}
+
+/// Slutt å overvåke filen vinduet viste
+#[tauri::command]
+pub fn unwatch_file(window: tauri::Window, state: State<'_, AppState>) {
+    state.session.file_watchers.lock().remove(window.label());
+}
+
+/// Legg en fil først i listen over nylige filer, unntatt i privat modus
+fn remember_file(state: &AppState, path: &Path) {
+    if state.session.private_mode.is_active() {
+        return;
+    }
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let mut recent = state.recent_files.lock();
+    recent.add(&path);
+    if let Err(e) = recent.save(&state.paths.recent_files()) {
+        warn!("Kunne ikke lagre nylige filer: {}", e);
+    }
+}
+
+/// Hent nylig åpnede filer som fortsatt finnes, nyeste først
+#[tauri::command]
+pub fn get_recent_files(state: State<'_, AppState>) -> Vec<RecentFile> {
+    state.recent_files.lock().existing()
+}
+
+/// Tøm listen over nylige filer
+///
+/// # Returns
+/// Antall filer som ble fjernet
+#[tauri::command]
+pub fn clear_recent_files(state: State<'_, AppState>) -> Result<usize, CommandError> {
+    let mut recent = state.recent_files.lock();
+    let removed = recent.clear();
+    recent
+        .save(&state.paths.recent_files())
+        .map_err(|e| locale::tf("error.recent_files_write", &[&e]))?;
+    Ok(removed)
+}
+
+/// Viser en lokal mappe som en innholdsfortegnelse
+///
+/// index.md eller README.md i mappen vises under listen. I et lokalt
+/// nettsted kommer sidene i `nav` først, med titlene sine.
+fn load_directory(
+    state: &AppState,
+    dir: &Path,
+    window: &tauri::Window,
+) -> Result<RenderedPage, CommandError> {
+    let show_hidden = state.settings.read().show_hidden_files;
+    let mut entries = local_files::list_directory(dir, show_hidden)
+        .map_err(|e| locale::tf("error.directory_read", &[&e]))?;
+    let site = LocalSite::find(dir);
+    if let Some(site) = &site {
+        site.order_entries(dir, &mut entries);
+    }
+    let index_path = local_files::index_file(&entries);
+    let index = index_path
+        .as_ref()
+        .and_then(|path| fs::read_to_string(path).ok());
+    let content = local_files::directory_markdown(
+        dir,
+        &entries,
+        locale::t("directory_page.empty"),
+        index.as_deref().map(localsite::strip_front_matter),
+    );
+
+    emit_status(window, locale::tf("status.file_rendering", &[&EMOJI_FILE]));
+    // Relative lenker i index-filen løses mot mappen
+    let base = index_path.unwrap_or_else(|| dir.join("index.md"));
+    let mut options = render_options(state, Some(&base.to_string_lossy()));
+    options.local_root = Some(site.map_or_else(|| dir.to_path_buf(), |site| site.root));
+    let rendered = markdown::render_with_options(&content, &options);
+    let title = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .or_else(|| Some(dir.display().to_string()));
+    let url = url::Url::from_directory_path(dir)
+        .map(String::from)
+        .unwrap_or_else(|_| format!("file://{}", dir.display()));
+    remember_source(state, &url, &content);
+    record_visit(state, &url, title.as_deref());
+
+    emit_status(window, locale::t("status.done"));
+
+    Ok(RenderedPage {
+        html: rendered.html,
+        title,
+        url: Some(url),
+        is_remote: false,
+        was_converted: true,
+        tasks: rendered.tasks,
+        skipped_diagrams: rendered.skipped_diagrams,
+        truncated: rendered.truncated,
+        security: None,
+        timing: None,
+        is_archived: false,
+        used_readability: false,
+    })
+}
+
+/// Lagre kilden til en side slik serveren sendte den
+///
+/// Bytene fra da siden ble vist lagres uendret, uten ny dekoding. En side
+/// som ikke huskes lenger hentes på nytt; lokale filer kopieres som de er.
+///
+/// # Arguments
+/// * `url` - Siden
+/// * `destination` - Absolutt sti; får endelse etter formatet (.gmi, .html,
+///   .md eller .txt) hvis den mangler
+///
+/// # Returns
+/// Stien kilden ble lagret til
+#[tauri::command]
+pub async fn save_page_source(
+    url: String,
+    destination: String,
+    state: State<'_, AppState>,
+) -> Result<String, CommandError> {
+    let destination = PathBuf::from(destination);
+    if !destination.is_absolute() {
+        return Err(locale::t("error.download_destination").into());
+    }
+    let retained = state.session.recent_pages.lock().raw(&url).cloned();
+    let source = match retained {
+        Some(source) => source,
+        None => fetch_page_source(&state, &url).await?,
+    };
+    page_source::save(&destination, &source)
+        .map(|path| path.display().to_string())
+        .map_err(|e| locale::tf("error.save_source", &[&e]).into())
+}
+
+/// Teksten som kopieres for en side: markdown-kilden eller ren tekst
+fn clipboard_text(markdown: String, plain_text: bool) -> String {
+    if plain_text {
+        markdown::to_plain_text(&markdown, false)
+    } else {
+        markdown
+    }
+}
+
+/// Kopier en side til utklippstavlen
+///
+/// # Arguments
+/// * `url` - Adressen til siden; den må være vist nylig
+/// * `plain_text` - Kopier ren tekst i stedet for markdown
+#[tauri::command]
+pub fn copy_page_as_markdown(
+    app: AppHandle,
+    url: String,
+    plain_text: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let text = clipboard_text(page_source(&state, &url)?, plain_text.unwrap_or(false));
+    app.clipboard()
+        .write_text(text)
+        .map_err(|e| locale::tf("error.clipboard", &[&e]).into())
+}
+
+/// Kopier en lenke til utklippstavlen uten sporingsparametere
+///
+/// # Arguments
+/// * `url` - Lenken som skal kopieres
+///
+/// # Returns
+/// Lenken slik den ble kopiert
+#[tauri::command]
+pub fn copy_link(app: AppHandle, url: String) -> Result<String, CommandError> {
+    let cleaned = fetcher::strip_tracking_params(&url);
+    app.clipboard()
+        .write_text(cleaned.clone())
+        .map_err(|e| locale::tf("error.clipboard", &[&e]))?;
+    Ok(cleaned)
+}
+
+/// Hent kilden til en side som ikke huskes lenger
+async fn fetch_page_source(state: &AppState, url: &str) -> Result<PageSource, String> {
+    let parsed = url::Url::parse(url).map_err(|e| locale::tf("error.invalid_url", &[&e]))?;
+    match parsed.scheme() {
+        "http" | "https" => {
+            let result = state.fetcher().fetch(url).await.map_err(|e| e.localize())?;
+            Ok(PageSource {
+                format: SourceFormat::detect(result.content_type.as_deref(), &result.final_url),
+                bytes: result.raw,
+            })
+        }
+        "gemini" => {
+            let response = state.gemini().fetch(url).await.map_err(|e| e.localize())?;
+            Ok(PageSource {
+                format: SourceFormat::detect(Some(&response.meta), &response.final_url),
+                bytes: response.raw,
+            })
+        }
+        "gopher" => {
+            let response = state.gopher().fetch(url).await.map_err(|e| e.localize())?;
+            Ok(PageSource {
+                format: SourceFormat::detect(
+                    gopher_source_type(&response.content_type),
+                    &response.final_url,
+                ),
+                bytes: response.raw,
+            })
+        }
+        "file" => {
+            let path = parsed
+                .to_file_path()
+                .map_err(|_| locale::tf("error.invalid_url", &[&url]))?;
+            let policy = state.settings.read().open_policy();
+            let (path, _) = local_files::resolve(&path, &policy).map_err(|e| e.localize())?;
+            let Some(format) = LocalFormat::from_path(&path) else {
+                return Err(locale::t("error.file_unsupported").to_string());
+            };
+            let bytes = fs::read(&path).map_err(|e| locale::tf("error.file_read", &[&e]))?;
+            Ok(PageSource {
+                bytes,
+                format: match format {
+                    LocalFormat::Markdown => SourceFormat::Markdown,
+                    LocalFormat::Gemtext => SourceFormat::Gemtext,
+                    LocalFormat::Text => SourceFormat::Text,
+                },
+            })
+        }
+        scheme => Err(locale::tf("error.download_scheme", &[&scheme])),
+    }
+}
+
+/// MIME-typen til en gophersvar, for å velge filendelse til kilden
+fn gopher_source_type(content_type: &gopher::GopherContentType) -> Option<&'static str> {
+    match content_type {
+        gopher::GopherContentType::Html => Some("text/html"),
+        _ => None,
+    }
+}
+
+/// Start en nedlasting til en fil
+///
+/// Klienten velges ut fra protokollen. Nedlastingen fortsetter i bakgrunnen
+/// og rapporteres med `download-progress`, `download-complete` og
+/// `download-failed`, alle med hele `Download` som innhold.
+///
+/// # Arguments
+/// * `url` - Adressen som skal lastes ned (http, https, gemini eller gopher)
+/// * `destination` - Absolutt sti til filen innholdet skal lagres i
+///
+/// # Returns
+/// ID-en nedlastingen kan avbrytes med
+#[tauri::command]
+pub async fn start_download(
+    url: String,
+    destination: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<DownloadId, CommandError> {
+    let destination = PathBuf::from(destination);
+    if !destination.is_absolute() {
+        return Err(locale::t("error.download_destination").into());
+    }
+    let url = outgoing_url(&state, url);
+    let (body, _) = open_download(&state, &url).await?;
+    let id = state.session.downloads.lock().start(&url, &destination);
+    tauri::async_runtime::spawn(run_download(id, body, destination, app));
+    Ok(id)
+}
+
+/// Lagre en ressurs fra hvilken som helst protokoll til en fil
+///
+/// Filnavnet hentes fra forslaget, Content-Disposition eller adressen, se
+/// `downloads::resource_file_name`. Filen lagres i nedlastingsmappen med et
+/// nummer bak navnet hvis den finnes fra før, eller der brukeren velger i
+/// en lagringsdialog, etter innstillingene. Overføringen vises i
+/// nedlastingslisten som andre nedlastinger.
+///
+/// # Arguments
+/// * `url` - Adressen (http, https, gemini eller gopher)
+/// * `suggested_name` - Foreslått filnavn, f.eks. lenketeksten
+///
+/// # Returns
+/// Stien filen ble lagret til, eller None hvis brukeren avbrøt dialogen
+#[tauri::command]
+pub async fn save_resource(
+    url: String,
+    suggested_name: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, CommandError> {
+    let url = outgoing_url(&state, url);
+    let (body, content_type) = open_download(&state, &url).await?;
+    let file_name = downloads::resource_file_name(
+        suggested_name.as_deref(),
+        body.content_disposition(),
+        &url,
+        content_type.as_deref(),
+    );
+
+    let target = state.settings.read().download_target(&file_name);
+    let destination = match target {
+        DownloadTarget::Direct { path } => downloads::unique_path(&path),
+        DownloadTarget::Ask { default_dir } => {
+            match ask_save_path(&app, &file_name, default_dir).await {
+                Some(path) => path,
+                None => return Ok(None),
+            }
+        }
+    };
+
+    let id = state.session.downloads.lock().start(&url, &destination);
+    run_download(id, body, destination.clone(), app).await;
+    let download_state = state
+        .session
+        .downloads
+        .lock()
+        .get(id)
+        .map(|d| d.state.clone());
+    match download_state {
+        Some(DownloadState::Completed) => Ok(Some(destination.display().to_string())),
+        Some(DownloadState::Failed { error }) => Err(error.into()),
+        _ => Err(locale::t("error.download_cancelled").into()),
+    }
+}
+
+/// Vis en lagringsdialog og vent på svaret
+///
+/// # Returns
+/// Filen brukeren valgte, eller None hvis dialogen ble avbrutt
+async fn ask_save_path(
+    app: &AppHandle,
+    file_name: &str,
+    default_dir: Option<PathBuf>,
+) -> Option<PathBuf> {
+    let (sender, receiver) = futures::channel::oneshot::channel();
+    let mut dialog = app.dialog().file().set_file_name(file_name);
+    if let Some(dir) = default_dir {
+        dialog = dialog.set_directory(dir);
+    }
+    dialog.save_file(move |path| {
+        let _ = sender.send(path);
+    });
+    receiver.await.ok().flatten()?.into_path().ok()
+}
+
+/// Koble til med klienten for protokollen, uten å lese innholdet
+///
+/// # Returns
+/// Innholdet som skal leses, og MIME-typen hvis protokollen oppgir den
+async fn open_download(
+    state: &AppState,
+    url: &str,
+) -> Result<(DownloadBody, Option<String>), CommandError> {
+    let parsed = url::Url::parse(url).map_err(|e| locale::tf("error.invalid_url", &[&e]))?;
+    match parsed.scheme() {
+        "http" | "https" => state
+            .fetcher()
+            .open_download(url)
+            .await
+            .map(|response| {
+                let content_type = response
+                    .headers()
+                    .get(reqwest::header::CONTENT_TYPE)
+                    .and_then(|value| value.to_str().ok())
+                    .map(String::from);
+                (DownloadBody::Http(response), content_type)
+            })
+            .map_err(CommandError::from),
+        "gemini" => state
+            .gemini()
+            .open_download(url)
+            .await
+            .map(|(reader, meta)| (DownloadBody::stream(reader), Some(meta)))
+            .map_err(CommandError::from),
+        "gopher" => state
+            .gopher()
+            .open_download(url)
+            .await
+            .map(|reader| (DownloadBody::stream(reader), None))
+            .map_err(CommandError::from),
+        scheme => Err(locale::tf("error.download_scheme", &[&scheme]).into()),
+    }
+}
+
+/// Overfør en nedlasting og rapporter underveis
+async fn run_download(id: DownloadId, body: DownloadBody, destination: PathBuf, app: AppHandle) {
+    let state = app.state::<AppState>();
+    let state = state.inner();
+    let total = body.total();
+    let idle_timeout = Duration::from_secs(state.settings.read().network.timeout_seconds);
+    let mut last_report = Instant::now();
+    let result = downloads::transfer(body, &destination, idle_timeout, |received| {
+        if !state.session.downloads.lock().progress(id, received, total) {
+            return false;
+        }
+        if last_report.elapsed() >= DOWNLOAD_PROGRESS_INTERVAL {
+            last_report = Instant::now();
+            emit_download(state, &app, "download-progress", id);
+        }
+        true
+    })
+    .await;
+
+    let event = match result {
+        Ok(bytes) => {
+            info!(
+                "Nedlasting {} fullført ({} bytes): {}",
+                id,
+                bytes,
+                destination.display()
+            );
+            state.session.downloads.lock().complete(id);
+            "download-complete"
+        }
+        // cancel_download har allerede oppdatert listen
+        Err(DownloadError::Cancelled) => return,
+        Err(e) => {
+            warn!("Nedlasting {} feilet: {}", id, e);
+            state.session.downloads.lock().fail(id, e.localize());
+            "download-failed"
+        }
+    };
+    emit_download(state, &app, event, id);
+}
+
+/// Send en nedlastingshendelse med nedlastingens nåværende tilstand
+fn emit_download(state: &AppState, app: &AppHandle, event: &str, id: DownloadId) {
+    let Some(download) = state.session.downloads.lock().get(id).cloned() else {
+        return;
+    };
+    if let Err(e) = app.emit(event, &download) {
+        warn!("Kunne ikke sende {}: {}", event, e);
+    }
+}
+
+/// Avbryt en nedlasting; den halve filen slettes
+///
+/// # Returns
+/// false hvis nedlastingen ikke finnes eller allerede er ferdig
+#[tauri::command]
+pub fn cancel_download(id: DownloadId, state: State<'_, AppState>) -> bool {
+    state.session.downloads.lock().cancel(id)
+}
+
+/// Nedlastingene i denne økten, nyeste først
+#[tauri::command]
+pub fn list_downloads(state: State<'_, AppState>) -> Vec<Download> {
+    state.session.downloads.lock().list()
+}
+
+/// Henter detaljene om forbindelsen en nylig vist side ble hentet over
+///
+/// # Arguments
+/// * `url` - Adressen til siden
+///
+/// # Returns
+/// SecurityInfo, eller None for lokale sider og sider som ikke er vist nylig
+#[tauri::command]
+pub fn get_page_security(url: String, state: State<'_, AppState>) -> Option<SecurityInfo> {
+    state.session.recent_pages.lock().security(&url).cloned()
+}
+
+/// Sjekker om en adresse ser mistenkelig ut før den åpnes
+///
+/// # Arguments
+/// * `url` - Adressen lenken peker til
+/// * `link_text` - Teksten på lenken, hvis noen
+///
+/// # Returns
+/// Advarslene, tom hvis adressen ser trygg ut
+#[tauri::command]
+pub fn check_url_safety(url: String, link_text: Option<String>) -> Vec<UrlWarning> {
+    url_safety::check_url_safety(&url, link_text.as_deref())
+}
+
+/// Godtar verten i en adresse brukeren har bekreftet, for resten av økten
+///
+/// # Arguments
+/// * `url` - Adressen brukeren valgte å åpne
+#[tauri::command]
+pub fn trust_url_host(url: String, state: State<'_, AppState>) -> Result<(), CommandError> {
+    url_safety::trust_host(&state.session, &url)
+        .map(|host| info!("Godtok {} tross advarsler", host))
+        .ok_or_else(|| locale::tf("error.invalid_url", &[&url]).into())
+}
+
+/// Det `navigate` skal åpne, og klienten som henter det
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum NavigationTarget {
+    /// En lokal fil eller mappe
+    File(String),
+    Http(String),
+    Gemini(String),
+    Gopher(String),
+}
+
+impl NavigationTarget {
+    /// Adressen eller filstien
+    fn url(&self) -> &str {
+        match self {
+            Self::File(url) | Self::Http(url) | Self::Gemini(url) | Self::Gopher(url) => url,
+        }
+    }
+}
+
+/// Finn ut hva en adresse er og hvilken klient som skal hente den
+///
+/// Adresser uten protokoll tolkes som i adresselinjen, med protokollen fra
+/// innstillingene for verten. Skal brukeren velge protokoll, brukes https://.
+///
+/// # Returns
+/// Målet, eller en nettverksfeil for ugyldige adresser og ukjente protokoller
+fn navigation_target(input: &str, settings: &Settings) -> Result<NavigationTarget, CommandError> {
+    let input = input.trim();
+    if address::is_local_path(input) {
+        return Ok(NavigationTarget::File(input.to_string()));
+    }
+    let invalid = || CommandError::from(fetcher::FetchError::InvalidUrl(input.to_string()));
+
+    let url = if input.contains("://") {
+        input.to_string()
+    } else {
+        let resolution = address::resolve(input, None, settings.search_engine(), |host| {
+            settings.site_settings(Some(host)).default_scheme
+        });
+        match resolution.action {
+            address::AddressAction::Navigate | address::AddressAction::Ask => resolution.url,
+            // Søk og nøkkelord hører til adresselinjen
+            address::AddressAction::Search | address::AddressAction::Keyword => {
+                return Err(invalid())
+            }
+        }
+    };
+
+    let parsed = url::Url::parse(&url).map_err(|_| invalid())?;
+    match parsed.scheme() {
+        "http" | "https" => Ok(NavigationTarget::Http(url)),
+        "gemini" => Ok(NavigationTarget::Gemini(url)),
+        "gopher" => Ok(NavigationTarget::Gopher(url)),
+        "file" => parsed
+            .to_file_path()
+            .map(|path| NavigationTarget::File(path.to_string_lossy().into_owned()))
+            .map_err(|_| invalid()),
+        scheme => Err(CommandError::unknown_scheme(scheme)),
+    }
+}
+
+/// Åpner en adresse eller lokal fil med klienten for protokollen
+///
+/// Frontend trenger ikke vite om adressen er HTTP(S), Gemini, Gopher eller
+/// en fil. En Gemini-side som sender videre til en annen protokoll gir
+/// `CommandError::SchemeRedirect`, så brukeren kan velge om adressen skal
+/// åpnes.
+///
+/// # Arguments
+/// * `url` - Adressen eller filstien, med eller uten protokoll
+/// * `referrer` - Siden lenken ble fulgt fra; brukes bare for HTTP(S)
+/// * `bypass_cache` - Be om en fersk kopi; brukes bare for HTTP(S)
+/// * `link_text` - Teksten på lenken som ble klikket, se `url_safety`
+///
+/// # Returns
+/// RenderedPage, eller feilmelding; ukjente protokoller gir en nettverksfeil
+/// av typen `unsupported_scheme`
+#[tauri::command]
+pub async fn navigate(
+    url: String,
+    referrer: Option<String>,
+    bypass_cache: Option<bool>,
+    link_text: Option<String>,
+    window: tauri::Window,
+    state: State<'_, AppState>,
+) -> Result<RenderedPage, CommandError> {
+    let target = {
+        let settings = state.settings.read();
+        navigation_target(&url, &settings)?
+    };
+    if !matches!(target, NavigationTarget::File(_)) {
+        url_safety::confirm(&state.session, target.url(), link_text.as_deref())?;
+    }
+    let ticket = begin_fetch(&window);
+    let page = match &target {
+        NavigationTarget::File(path) => load_file(&state, path.clone(), window.clone()),
+        NavigationTarget::Http(url) => {
+            load_url(&state, url.clone(), referrer, bypass_cache, &window).await
+        }
+        NavigationTarget::Gemini(url) => load_gemini(&state, url.clone(), &window).await,
+        NavigationTarget::Gopher(url) => load_gopher(&state, url.clone(), &window).await,
+    }
+    .map_err(|e| error_page::attach(e, target.url()));
+    finish_fetch(&window, &ticket, page)
+}
+
+/// Henter og rendrer markdown fra en URL
+///
+/// # Arguments
+/// * `url` - URL til markdown-filen som skal hentes
+/// * `referrer` - Siden lenken ble fulgt fra, hvis noen
+/// * `bypass_cache` - Be om en fersk kopi forbi alle mellomlagre (Ctrl+Shift+R)
+/// * `link_text` - Teksten på lenken som ble klikket, se `url_safety`
+///
+/// # Returns
+/// RenderedPage med HTML og tittel, eller feilmelding
+#[tauri::command]
+pub async fn fetch_url(
+    url: String,
+    referrer: Option<String>,
+    bypass_cache: Option<bool>,
+    link_text: Option<String>,
+    window: tauri::Window,
+    state: State<'_, AppState>,
+) -> Result<RenderedPage, CommandError> {
+    url_safety::confirm(&state.session, &url, link_text.as_deref())?;
+    let ticket = begin_fetch(&window);
+    let page = load_url(&state, url.clone(), referrer, bypass_cache, &window)
+        .await
+        .map_err(|e| error_page::attach(e, &url));
+    finish_fetch(&window, &ticket, page)
+}
+
+/// Selve hentingen bak `fetch_url`
+async fn load_url<S: ProgressSink>(
+    state: &AppState,
+    url: String,
+    referrer: Option<String>,
+    bypass_cache: Option<bool>,
+    sink: &S,
+) -> Result<RenderedPage, CommandError> {
+    let url = outgoing_url(state, url);
+    // Detekter protokoll
+    let parsed_url = url::Url::parse(&url).map_err(|e| locale::tf("error.invalid_url", &[&e]))?;
+    let scheme = parsed_url.scheme();
+    let host = url_safety::display_host(&url);
+    let progress = LoadProgress::new(sink, &host, state.session.private_mode.is_active());
+    let protocol_emoji = if scheme == "https" {
+        EMOJI_HTTPS
+    } else {
+        EMOJI_HTTP
+    };
+    let protocol_name = if scheme == "https" { "HTTPS" } else { "HTTP" };
+
+    // Steg 1: Slår opp vert
+    progress.stage(
+        LoadStage::Resolving,
+        locale::tf(
+            "status.resolving",
+            &[&protocol_emoji, &protocol_name, &host],
+        ),
+    );
+
+    // Steg 2: Kobler til
+    let tls_info = if scheme == "https" { "/TLS" } else { "" };
+    progress.stage(
+        LoadStage::Connecting,
+        locale::tf(
+            "status.connecting",
+            &[
+                &protocol_emoji,
+                &format!("{}{}", protocol_name, tls_info),
+                &host,
+            ],
+        ),
+    );
+    if scheme == "https" {
+        progress.advance(LoadStage::TlsHandshake);
+    }
+
+    // En forhåndshentet kopi forkastes ved tvungen omlasting
+    let bypass_cache = bypass_cache.unwrap_or(false);
+    let result = match take_prefetched(state, &url) {
+        Some(Prefetched::Http(result)) if !bypass_cache => result,
+        _ => {
+            let result = state
+                .fetcher()
+                .fetch_with(&url, referrer.as_deref(), bypass_cache)
+                .await
+                .map_err(|e| {
+                    progress.stage(LoadStage::Error, locale::t("status.fetch_failed"));
+                    CommandError::from(e)
+                })?;
+            progress.network(result.timing);
+            result
+        }
+    };
+
+    // Binærfiler lastes ned i stedet for å vises
+    if result
+        .content_type
+        .as_deref()
+        .is_some_and(downloads::is_binary_content_type)
+    {
+        progress.status(locale::t("status.awaiting_choice"));
+        return Err(CommandError::download_prompt(&result.final_url));
+    }
+
+    // Steg 3: Overfører data
+    let bytes = result.content.len();
+    progress.transferring(
+        bytes as u64,
+        Some(bytes as u64),
+        locale::tf("status.transferring", &[&bytes]),
+    );
+    remember_raw(
+        state,
+        &result.final_url,
+        &result.raw,
+        result.content_type.as_deref(),
+        &result.security,
+    );
+
+    // Hent konverteringsinnstillinger for nettstedet siden endte opp på
+    let site = site_settings_for(state, &result.final_url);
+
+    if result.is_markdown {
+        // Steg 4: Rendrer markdown
+        progress.stage(LoadStage::Rendering, locale::t("status.rendering_markdown"));
+        let options = site_render_options(state, Some(&result.final_url), &site);
+        let (rendered, content) = render_blocking(result.content, options).await?;
+        let title = markdown::extract_title(&content);
+        remember_source(state, &result.final_url, &content);
+        record_visit(state, &result.final_url, title.as_deref());
+
+        let timing = progress.done(locale::t("status.done"));
+
+        return Ok(RenderedPage {
+            html: rendered.html,
+            title,
+            url: Some(result.final_url),
+            is_remote: true,
+            was_converted: false,
+            tasks: rendered.tasks,
+            skipped_diagrams: rendered.skipped_diagrams,
+            truncated: rendered.truncated,
+            security: Some(result.security),
+            timing: Some(timing),
+            is_archived: false,
+            used_readability: false,
+        });
+    }
+
+    // Ikke-markdown innhold - sjekk konverteringsmodus
+    let conversion_mode = effective_conversion_mode(&result.final_url, &state.settings.read());
+    match conversion_mode {
+        ConversionMode::MarkdownOnly => {
+            progress.stage(LoadStage::Error, locale::t("status.markdown_only"));
+            Err(locale::tf(
+                "error.not_markdown",
+                &[&format!("{:?}", result.content_type)],
+            )
+            .into())
+        }
+        ConversionMode::AskEverytime => {
+            progress.status(locale::t("status.awaiting_choice"));
+            // Frontend spør brukeren og kaller convert_url ved ja
+            Err(CommandError::conversion_prompt(&result.final_url))
+        }
+        ConversionMode::ConvertAll => {
+            // Steg 4: Konverterer HTML
+            progress.stage(LoadStage::Converting, locale::t("status.converting_html"));
+            let conversion_result =
+                convert_blocking(result.content, conversion_options(state, &site)).await?;
+
+            // Steg 5: Rendrer markdown
+            progress.stage(LoadStage::Rendering, locale::t("status.rendering_markdown"));
+            let options = site_render_options(state, Some(&result.final_url), &site);
+            let (rendered, markdown_content) =
+                render_blocking(conversion_result.markdown, options).await?;
+
+            let title = conversion_result
+                .title
+                .or_else(|| markdown::extract_title(&markdown_content));
+            remember_source(state, &result.final_url, &markdown_content);
+            record_visit(state, &result.final_url, title.as_deref());
+
+            let timing = progress.done(locale::t("status.done"));
+
+            Ok(RenderedPage {
+                html: rendered.html,
+                title,
+                url: Some(result.final_url),
+                is_remote: true,
+                was_converted: true,
+                tasks: rendered.tasks,
+                skipped_diagrams: rendered.skipped_diagrams,
+                truncated: rendered.truncated || conversion_result.truncated,
+                security: Some(result.security),
+                timing: Some(timing),
+                is_archived: false,
+                used_readability: conversion_result.used_readability,
+            })
+        }
+    }
+}
+
+/// Konverter HTML til markdown manuelt (for "Spør hver gang"-modus)
+///
+/// # Arguments
+/// * `url` - URL til siden som skal konverteres
+///
+/// # Returns
+/// RenderedPage med konvertert innhold
+#[tauri::command]
+pub async fn convert_url(
+    url: String,
+    window: tauri::Window,
+    state: State<'_, AppState>,
+) -> Result<RenderedPage, CommandError> {
+    let ticket = begin_fetch(&window);
+    let page = load_converted(&state, url.clone(), &window)
+        .await
+        .map_err(|e| error_page::attach(e, &url));
+    finish_fetch(&window, &ticket, page)
+}
+
+/// Selve hentingen bak `convert_url`
+async fn load_converted<S: ProgressSink>(
+    state: &AppState,
+    url: String,
+    sink: &S,
+) -> Result<RenderedPage, CommandError> {
+    let url = outgoing_url(state, url);
+    // Detekter protokoll
+    let parsed_url = url::Url::parse(&url).map_err(|e| locale::tf("error.invalid_url", &[&e]))?;
+    let scheme = parsed_url.scheme();
+    let host = url_safety::display_host(&url);
+    let progress = LoadProgress::new(sink, &host, state.session.private_mode.is_active());
+    let protocol_emoji = if scheme == "https" {
+        EMOJI_HTTPS
+    } else {
+        EMOJI_HTTP
+    };
+    let protocol_name = if scheme == "https" { "HTTPS" } else { "HTTP" };
+
+    progress.stage(
+        LoadStage::Resolving,
+        locale::tf(
+            "status.resolving",
+            &[&protocol_emoji, &protocol_name, &host],
+        ),
+    );
+    let tls_info = if scheme == "https" { "/TLS" } else { "" };
+    progress.stage(
+        LoadStage::Connecting,
+        locale::tf(
+            "status.connecting",
+            &[
+                &protocol_emoji,
+                &format!("{}{}", protocol_name, tls_info),
+                &host,
+            ],
+        ),
+    );
+    if scheme == "https" {
+        progress.advance(LoadStage::TlsHandshake);
+    }
+
+    let result = state.fetcher().fetch(&url).await.map_err(|e| {
+        progress.stage(LoadStage::Error, locale::t("status.fetch_failed"));
+        CommandError::from(e)
+    })?;
+    progress.network(result.timing);
+
+    let bytes = result.content.len();
+    progress.transferring(
+        bytes as u64,
+        Some(bytes as u64),
+        locale::tf("status.transferring", &[&bytes]),
+    );
+    remember_raw(
+        state,
+        &result.final_url,
+        &result.raw,
+        result.content_type.as_deref(),
+        &result.security,
+    );
+
+    // Konverter HTML til markdown
+    progress.stage(LoadStage::Converting, locale::t("status.converting_html"));
+    let site = site_settings_for(state, &result.final_url);
+    let conversion_result =
+        convert_blocking(result.content, conversion_options(state, &site)).await?;
+
+    // Render markdown til HTML for visning
+    progress.stage(LoadStage::Rendering, locale::t("status.rendering_markdown"));
+    let options = site_render_options(state, Some(&result.final_url), &site);
+    let (rendered, markdown_content) = render_blocking(conversion_result.markdown, options).await?;
+
+    // Bruk tittel fra konvertering eller markdown
+    let title = conversion_result
+        .title
+        .or_else(|| markdown::extract_title(&markdown_content));
+    remember_source(state, &result.final_url, &markdown_content);
+    record_visit(state, &result.final_url, title.as_deref());
+
+    let timing = progress.done(locale::t("status.done"));
+
+    Ok(RenderedPage {
+        html: rendered.html,
+        title,
+        url: Some(result.final_url),
+        is_remote: true,
+        was_converted: true,
+        tasks: rendered.tasks,
+        skipped_diagrams: rendered.skipped_diagrams,
+        truncated: rendered.truncated || conversion_result.truncated,
+        security: Some(result.security),
+        timing: Some(timing),
+        is_archived: false,
+        used_readability: conversion_result.used_readability,
+    })
+}
+
+/// Forhåndshent en lenke brukeren holder musen over
+///
+/// Siden hentes med samme klient som ved navigering, men rendres ikke. Blir
+/// lenken åpnet kort tid etter, brukes den hentede kopien. Gjør ingenting når
+/// forhåndshenting er av, privat surfing er på eller for mange
+/// forhåndshentinger pågår. Feil ignoreres.
+///
+/// # Arguments
+/// * `url` - Absolutt adresse (http(s)://, gemini:// eller gopher://)
+#[tauri::command]
+pub async fn prefetch(url: String, state: State<'_, AppState>) -> Result<(), CommandError> {
+    let enabled = state.settings.read().privacy.prefetch;
+    let scheme = match prefetch::check(&url, enabled, state.session.private_mode.is_active()) {
+        Ok(scheme) => scheme,
+        Err(reason) => {
+            debug!("[forhåndshenting] Hopper over ({:?})", reason);
+            return Ok(());
+        }
+    };
+    let url = match scheme {
+        PrefetchScheme::Http => outgoing_url(&state, url),
+        _ => url,
+    };
+    if state.session.prefetched.lock().contains(&url) {
+        return Ok(());
+    }
+    let Some(_permit) = state.session.prefetch_budget.try_acquire() else {
+        debug!("[forhåndshenting] For mange samtidige, hopper over");
+        return Ok(());
+    };
+
+    info!(
+        "[forhåndshenting] Henter {}",
+        state.session.private_mode.log_url(&url)
+    );
+    let result = match scheme {
+        PrefetchScheme::Http => state
+            .fetcher()
+            .fetch(&url)
+            .await
+            .map(Prefetched::Http)
+            .map_err(|e| e.to_string()),
+        PrefetchScheme::Gemini => state
+            .gemini()
+            .fetch(&url)
+            .await
+            .map(Prefetched::Gemini)
+            .map_err(|e| e.to_string()),
+        PrefetchScheme::Gopher => state
+            .gopher()
+            .fetch(&url)
+            .await
+            .map(Prefetched::Gopher)
+            .map_err(|e| e.to_string()),
+    };
+    match result {
+        Ok(prefetched) => state
+            .session
+            .prefetched
+            .lock()
+            .insert(&url, prefetched, Instant::now()),
+        Err(e) => debug!(
+            "[forhåndshenting] Feilet for {}: {}",
+            state.session.private_mode.log_url(&url),
+            e
+        ),
+    }
+    Ok(())
+}
+
+/// Løser en relativ URL mot en base-URL
+///
+/// # Arguments
+/// * `base_url` - Nåværende side sin URL
+/// * `relative_url` - Relativ URL som skal løses
+///
+/// # Returns
 /// Absolutt URL
 #[tauri::command]
-pub fn resolve_url(base_url: String, relative_url: String) -> Result<String, String> {
-    fetcher::resolve_url(&base_url, &relative_url).map_err(|e| e.to_string())
+pub fn resolve_url(base_url: String, relative_url: String) -> Result<String, CommandError> {
+    fetcher::resolve_url(&base_url, &relative_url).map_err(CommandError::from)
+}
+
+/// Brødsmulesti for siden som vises
+///
+/// # Arguments
+/// * `url` - Adressen eller filstien til siden
+///
+/// # Returns
+/// Leddene fra roten og ned; tom for adresser uten sti, f.eks. bare://
+#[tauri::command]
+pub fn get_breadcrumbs(url: String) -> Vec<Breadcrumb> {
+    breadcrumbs::breadcrumbs(&url)
+}
+
+/// Tolk teksten fra adresselinjen
+///
+/// # Arguments
+/// * `input` - Teksten brukeren skrev, f.eks. "example.com", "rs serde" eller "rust lang"
+///
+/// # Returns
+/// Om teksten er en adresse, et bokmerke-nøkkelord eller et søk, og adressen som skal åpnes
+#[tauri::command]
+pub fn resolve_address_input(input: String, state: State<'_, AppState>) -> AddressResolution {
+    let keyword_url = state.bookmarks.lock().resolve_keyword(&input);
+    let settings = state.settings.read();
+    address::resolve(&input, keyword_url, settings.search_engine(), |host| {
+        settings.site_settings(Some(host)).default_scheme
+    })
+}
+
+/// Standard antall forslag i adresselinjen
+const DEFAULT_AUTOCOMPLETE_LIMIT: usize = 8;
+
+/// Forslag til adresselinjen fra bokmerker og historikk
+///
+/// Sider som bare finnes i historikken tas med når historikken er slått på.
+///
+/// # Arguments
+/// * `input` - Teksten brukeren har skrevet så langt
+/// * `limit` - Maks antall forslag (standard 8, maks 200)
+///
+/// # Returns
+/// Forslagene, det beste først, med posisjonene som traff
+#[tauri::command]
+pub fn autocomplete(
+    input: String,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Vec<Suggestion> {
+    let limit = limit
+        .unwrap_or(DEFAULT_AUTOCOMPLETE_LIMIT)
+        .min(MAX_SEARCH_LIMIT);
+    let include_history = state.settings.read().history_enabled;
+
+    if let Some(index) = state.session.autocomplete.lock().as_ref() {
+        return index.suggest(&input, limit, include_history);
+    }
+
+    // Lagrene holdes låst til indeksen er lagret, slik at en endring ikke
+    // kan komme mellom byggingen og lagringen
+    let bookmarks = state.bookmarks.lock();
+    let history = state.history.lock();
+    let index = AutocompleteIndex::build(&bookmarks, &history, bookmarks::current_timestamp());
+    let suggestions = index.suggest(&input, limit, include_history);
+    *state.session.autocomplete.lock() = Some(index);
+    suggestions
+}
+
+/// Husk hvilken protokoll adresser til et nettsted skal åpnes med
+///
+/// Brukes når brukeren har valgt mellom https:// og gemini:// for en
+/// adresse uten protokoll.
+///
+/// # Arguments
+/// * `host` - Vertsnavnet
+/// * `scheme` - Protokollen, eller None for å spørre igjen
+///
+/// # Returns
+/// Oppdaterte innstillinger
+#[tauri::command]
+pub fn remember_address_scheme(
+    app: AppHandle,
+    host: String,
+    scheme: Option<DefaultScheme>,
+    state: State<'_, AppState>,
+) -> Result<SettingsInfo, CommandError> {
+    let mut settings = state.settings.write();
+    settings
+        .set_site_scheme(&host, scheme)
+        .map_err(|e| e.localize())?;
+
+    save_settings(&state, &mut settings)?;
+
+    let info = settings_info(&state, &settings);
+    broadcast_settings(&state, &app, &info);
+    Ok(info)
+}
+
+// ===== Bokmerke-commands =====
+
+/// Bokmerke-info for frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookmarkInfo {
+    pub id: String,
+    pub title: String,
+    pub url: String,
+    pub created_at: u64,
+    pub folder: Option<String>,
+    pub tags: Vec<String>,
+    pub keyword: Option<String>,
+    pub has_snapshot: bool,
+    pub pinned: bool,
+    /// Protokoll ("gemini", "https", "file", ...)
+    pub scheme: String,
+    /// Foreslått ikon for protokollen
+    pub icon: String,
+    /// Vertsnavn (tomt for lokale filer)
+    pub host: String,
+}
+
+impl From<&Bookmark> for BookmarkInfo {
+    fn from(b: &Bookmark) -> Self {
+        let scheme = bookmark_scheme(&b.url);
+        Self {
+            id: b.id.clone(),
+            title: b.title.clone(),
+            url: b.url.clone(),
+            created_at: b.created_at,
+            folder: b.folder.clone(),
+            tags: b.tags.clone(),
+            keyword: b.keyword.clone(),
+            has_snapshot: b.snapshot_path.is_some(),
+            pinned: b.pinned,
+            icon: scheme_icon(&scheme).to_string(),
+            host: bookmark_host(&scheme, &b.url),
+            scheme,
+        }
+    }
+}
+
+/// Protokollen til en bokmerke-URL
+///
+/// Eldre bokmerker kan være rene filstier uten `file://`, og Windows-stier
+/// som `C:\notater.md` ser ut som en URL med protokoll "c".
+fn bookmark_scheme(url: &str) -> String {
+    match url::Url::parse(url) {
+        Ok(parsed) if parsed.scheme().len() > 1 => parsed.scheme().to_string(),
+        _ => "file".to_string(),
+    }
+}
+
+/// Ikon for en protokoll
+fn scheme_icon(scheme: &str) -> &'static str {
+    match scheme {
+        "gemini" => "🔭",
+        "gopher" | "gophers" => "📜",
+        "http" | "https" => "🌐",
+        "file" => "📁",
+        _ => "🔗",
+    }
+}
+
+/// Vertsnavnet til en bokmerke-URL (tomt for lokale filer)
+fn bookmark_host(scheme: &str, url: &str) -> String {
+    if scheme == "file" {
+        return String::new();
+    }
+    url_safety::display_host(url)
+}
+
+/// Lagre bokmerker til standardfilen, med roterte sikkerhetskopier
+fn save_bookmarks(state: &AppState, store: &BookmarkStore) -> Result<(), String> {
+    let path = state.paths.bookmarks();
+    let generations = state.settings.read().backup_generations;
+    state.invalidate_autocomplete();
+    store
+        .save_with_backups(&path, generations)
+        .map_err(|e| e.localize())
+}
+
+/// Les bokmerkefilen på nytt etter en endring utenfra
+///
+/// Ulagrede endringer i minnet beholdes og skrives tilbake.
+///
+/// # Returns
+/// `true` hvis bokmerkene ble lest inn på nytt
+fn reload_bookmarks_from_disk(state: &AppState) -> Result<bool, String> {
+    let path = state.paths.bookmarks();
+    let mut store = state.bookmarks.lock();
+    if !path.exists() || store.is_own_write(&path) {
+        return Ok(false);
+    }
+
+    let disk = BookmarkStore::load(&path).map_err(|e| e.localize())?;
+    if store.reload(disk, &path) {
+        save_bookmarks(state, &store)?;
+    }
+    state.invalidate_autocomplete();
+    Ok(true)
+}
+
+/// Start overvåking av bokmerkefilen
+///
+/// Når filen endres av en annen prosess leses den inn på nytt, og alle
+/// vinduer får eventet `bookmarks-changed`.
+pub fn start_bookmarks_watcher(app: AppHandle) {
+    let state = app.state::<AppState>();
+    let path = state.paths.bookmarks();
+    let handle = app.clone();
+    let result = watcher::watch_file(&path, move || {
+        match reload_bookmarks_from_disk(&handle.state::<AppState>()) {
+            Ok(true) => {
+                info!("Bokmerker lest inn på nytt etter endring på disk");
+                if let Err(e) = handle.emit("bookmarks-changed", ()) {
+                    warn!("Kunne ikke sende bookmarks-changed: {}", e);
+                }
+            }
+            Ok(false) => {}
+            Err(e) => warn!("Kunne ikke lese bokmerker på nytt: {}", e),
+        }
+    });
+
+    match result {
+        Ok(watcher) => state.session.store_watchers.lock().push(watcher),
+        Err(e) => warn!("Kunne ikke overvåke {}: {}", path.display(), e),
+    }
+}
+
+/// Hent tilgjengelige sikkerhetskopier av bokmerkefilen, nyeste først
+#[tauri::command]
+pub fn list_bookmark_backups(state: State<'_, AppState>) -> Vec<Backup> {
+    let generations = state.settings.read().backup_generations;
+    storage::list_backups(&state.paths.bookmarks(), generations)
+}
+
+/// Gjenopprett bokmerker fra en sikkerhetskopi
+///
+/// Den nåværende samlingen blir selv den nyeste sikkerhetskopien, så
+/// gjenopprettingen kan angres.
+///
+/// # Arguments
+/// * `generation` - Hvilken kopi (1 er den nyeste)
+///
+/// # Returns
+/// Antall bokmerker etter gjenoppretting
+#[tauri::command]
+pub fn restore_bookmarks_backup(
+    generation: u32,
+    state: State<'_, AppState>,
+) -> Result<usize, CommandError> {
+    let mut store = state.bookmarks.lock();
+    let restored = BookmarkStore::load_backup(&state.paths.bookmarks(), generation)
+        .map_err(|e| e.localize())?;
+    *store = restored;
+    save_bookmarks(&state, &store)?;
+    Ok(store.list().len())
+}
+
+/// En side med bokmerker for frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookmarkList {
+    pub items: Vec<BookmarkInfo>,
+    /// Antall bokmerker som passer filteret, før `offset` og `limit`
+    pub total: usize,
+}
+
+/// Hent bokmerker
+///
+/// Uten argumenter returneres alle bokmerker i rekkefølgen de ble lagt til.
+///
+/// # Arguments
+/// * `folder` - Kun bokmerker i denne mappen ("" er roten); alle hvis utelatt
+/// * `recursive` - Ta med undermapper (standard: false)
+/// * `tag` - Kun bokmerker med denne emneknaggen
+/// * `sort` - Sorteringsrekkefølge (standard: manuell)
+/// * `offset` - Antall bokmerker som hoppes over (standard: 0)
+/// * `limit` - Maksimalt antall bokmerker (standard: alle)
+#[tauri::command]
+pub fn get_bookmarks(
+    folder: Option<String>,
+    recursive: Option<bool>,
+    tag: Option<String>,
+    sort: Option<BookmarkSort>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> BookmarkList {
+    let folder = match folder {
+        Some(folder) => match bookmarks::normalize_folder(&folder) {
+            Ok(folder) => Some(folder),
+            Err(_) => {
+                return BookmarkList {
+                    items: Vec::new(),
+                    total: 0,
+                }
+            }
+        },
+        None => None,
+    };
+    let filter = BookmarkFilter {
+        folder,
+        recursive: recursive.unwrap_or(false),
+        tag,
+    };
+
+    let store = state.bookmarks.lock();
+    let page = store.page(
+        &filter,
+        sort.unwrap_or_default(),
+        offset.unwrap_or(0),
+        limit,
+    );
+    BookmarkList {
+        items: page.items.into_iter().map(BookmarkInfo::from).collect(),
+        total: page.total,
+    }
+}
+
+/// Legg til et nytt bokmerke
+///
+/// Er tittelen tom, hentes sidens egen tittel (med vertsnavnet som reserve).
+///
+/// # Arguments
+/// * `save_snapshot` - Lagre en arkivert kopi av siden (må være vist nylig)
+#[tauri::command]
+pub async fn add_bookmark(
+    title: String,
+    url: String,
+    folder: Option<String>,
+    tags: Option<Vec<String>>,
+    save_snapshot: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<BookmarkInfo, CommandError> {
+    let title = if title.trim().is_empty() {
+        if state.bookmarks.lock().is_bookmarked(&url) {
+            return Err(bookmarks::BookmarkError::AlreadyExists(url)
+                .localize()
+                .into());
+        }
+        fetch_bookmark_title(&state, &url).await
+    } else {
+        title
+    };
+
+    insert_bookmark(
+        &state,
+        title,
+        url,
+        folder,
+        tags,
+        save_snapshot.unwrap_or(false),
+    )
+}
+
+/// Legg et nytt bokmerke inn i samlingen og lagre
+fn insert_bookmark(
+    state: &AppState,
+    title: String,
+    url: String,
+    folder: Option<String>,
+    tags: Option<Vec<String>>,
+    save_snapshot: bool,
+) -> Result<BookmarkInfo, CommandError> {
+    let mut store = state.bookmarks.lock();
+
+    let folder = match folder {
+        Some(folder) => bookmarks::normalize_folder(&folder).map_err(|e| e.localize())?,
+        None => None,
+    };
+    let source = if save_snapshot {
+        Some(page_source(state, &url)?)
+    } else {
+        None
+    };
+    let mut bookmark = Bookmark {
+        folder,
+        tags: bookmarks::normalize_tags(&tags.unwrap_or_default()),
+        ..new_bookmark(title, url)
+    };
+
+    store.add(bookmark.clone()).map_err(|e| e.localize())?;
+
+    if let Some(source) = source {
+        bookmark = write_snapshot(state, &mut store, &bookmark.id, &source)?;
+    }
+
+    // Lagre til fil
+    save_bookmarks(state, &store)?;
+
+    Ok(BookmarkInfo::from(&bookmark))
+}
+
+/// Maks tid for å hente tittelen til en side som bokmerkes
+const TITLE_FETCH_TIMEOUT_SECS: u64 = 5;
+
+/// Hvor mye av en nettside som leses for å finne tittelen
+const TITLE_FETCH_MAX_BYTES: usize = 64 * 1024;
+
+/// Tittel for et bokmerke uten tittel
+///
+/// Henter sidens egen tittel; ved feil eller tidsavbrudd brukes vertsnavnet.
+async fn fetch_bookmark_title(state: &AppState, url: &str) -> String {
+    let timeout = std::time::Duration::from_secs(TITLE_FETCH_TIMEOUT_SECS);
+    let title = tokio::time::timeout(timeout, fetch_page_title(state, url))
+        .await
+        .ok()
+        .flatten()
+        .map(|t| t.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|t| !t.is_empty());
+    title.unwrap_or_else(|| url_safety::display_host(url))
+}
+
+/// Hent tittelen til en side med samme konvertering som når den vises
+async fn fetch_page_title(state: &AppState, url: &str) -> Option<String> {
+    let scheme = url::Url::parse(url).ok()?.scheme().to_string();
+    match scheme.as_str() {
+        "http" | "https" => {
+            let result = state
+                .fetcher()
+                .fetch_prefix(url, TITLE_FETCH_MAX_BYTES)
+                .await
+                .ok()?;
+            if result.is_markdown {
+                markdown::extract_title(&result.content)
+            } else {
+                converter::extract_title(&result.content)
+            }
+        }
+        "gemini" => {
+            let response = state.gemini().fetch(url).await.ok()?;
+            let body = response.body?;
+            if response.meta.is_empty() || response.meta.starts_with("text/gemini") {
+                gemtext::gemtext_to_markdown(&body).title
+            } else {
+                markdown::extract_title(&body)
+            }
+        }
+        "gopher" => {
+            let response = state.gopher().fetch(url).await.ok()?;
+            match response.content_type {
+                gopher::GopherContentType::Menu => {
+                    gophermap::to_markdown(&response.items, &response.final_url, Decorations::None)
+                        .title
+                }
+                gopher::GopherContentType::Html => converter::extract_title(&response.body),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Resultat av `toggle_bookmark`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToggleResult {
+    /// Om URL-en er bokmerket etter kallet
+    pub bookmarked: bool,
+    /// Det nye bokmerket, hvis det ble lagt til
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bookmark: Option<BookmarkInfo>,
+}
+
+/// Legg til eller fjern bokmerket for en URL
+///
+/// Sjekk og endring skjer under samme lås, så to raske klikk kan ikke legge
+/// til samme side to ganger. Er tittelen tom, hentes sidens egen tittel før
+/// bokmerket legges til.
+#[tauri::command]
+pub async fn toggle_bookmark(
+    title: String,
+    url: String,
+    state: State<'_, AppState>,
+) -> Result<ToggleResult, CommandError> {
+    if !title.trim().is_empty() {
+        let mut store = state.bookmarks.lock();
+        let toggled = store
+            .toggle(new_bookmark(title, url))
+            .map_err(|e| e.localize())?;
+        return finish_toggle(&state, &store, toggled);
+    }
+
+    {
+        let mut store = state.bookmarks.lock();
+        if let Some(removed) = store.remove_url(&url) {
+            return finish_toggle(&state, &store, Toggle::Removed(removed));
+        }
+    }
+    let title = fetch_bookmark_title(&state, &url).await;
+
+    // Mens tittelen ble hentet kan siden ha blitt bokmerket fra et annet
+    // vindu; da er ønsket tilstand allerede nådd
+    let mut store = state.bookmarks.lock();
+    if let Some(existing) = store.get_by_url(&url) {
+        return Ok(ToggleResult {
+            bookmarked: true,
+            bookmark: Some(BookmarkInfo::from(existing)),
+        });
+    }
+    let bookmark = new_bookmark(title, url);
+    store.add(bookmark.clone()).map_err(|e| e.localize())?;
+    finish_toggle(&state, &store, Toggle::Added(bookmark))
+}
+
+/// Et nytt bokmerke i roten, uten emneknagger
+fn new_bookmark(title: String, url: String) -> Bookmark {
+    let now = bookmarks::current_timestamp();
+    Bookmark {
+        id: bookmarks::generate_id(),
+        title,
+        url,
+        created_at: now,
+        updated_at: now,
+        folder: None,
+        tags: Vec::new(),
+        keyword: None,
+        snapshot_path: None,
+        pinned: false,
+    }
+}
+
+/// Lagre etter en veksling og rydd opp en eventuell arkivert kopi
+fn finish_toggle(
+    state: &AppState,
+    store: &BookmarkStore,
+    toggled: Toggle,
+) -> Result<ToggleResult, CommandError> {
+    save_bookmarks(state, store)?;
+    match toggled {
+        Toggle::Added(bookmark) => Ok(ToggleResult {
+            bookmarked: true,
+            bookmark: Some(BookmarkInfo::from(&bookmark)),
+        }),
+        Toggle::Removed(bookmark) => {
+            if let Some(path) = &bookmark.snapshot_path {
+                delete_snapshot(path);
+            }
+            Ok(ToggleResult {
+                bookmarked: false,
+                bookmark: None,
+            })
+        }
+    }
+}
+
+/// Markdown-kilden til en nylig vist side
+fn page_source(state: &AppState, url: &str) -> Result<String, String> {
+    state
+        .session
+        .recent_pages
+        .lock()
+        .get(url)
+        .map(String::from)
+        .ok_or_else(|| snapshots::SnapshotError::NotLoaded(url.to_string()).localize())
+}
+
+/// Skriv en arkivert kopi og registrer den på bokmerket
+fn write_snapshot(
+    state: &AppState,
+    store: &mut BookmarkStore,
+    id: &str,
+    source: &str,
+) -> Result<Bookmark, String> {
+    let path = snapshots::save(&state.paths.snapshots(), id, source).map_err(|e| e.localize())?;
+    store
+        .set_snapshot(id, Some(path.to_string_lossy().to_string()))
+        .cloned()
+        .map_err(|e| e.localize())
+}
+
+/// Slett en arkivert kopi, og logg hvis det ikke går
+fn delete_snapshot(path: &str) {
+    if let Err(e) = snapshots::delete(Path::new(path)) {
+        warn!("Kunne ikke slette arkivert kopi {}: {}", path, e);
+    }
+}
+
+/// Fjern et bokmerke
+///
+/// En eventuell arkivert kopi slettes også.
+#[tauri::command]
+pub fn remove_bookmark(id: String, state: State<'_, AppState>) -> Result<(), CommandError> {
+    let mut store = state.bookmarks.lock();
+    let snapshot = store.get(&id).and_then(|b| b.snapshot_path.clone());
+    store.remove(&id).map_err(|e| e.localize())?;
+
+    // Lagre til fil
+    save_bookmarks(&state, &store)?;
+
+    if let Some(path) = snapshot {
+        delete_snapshot(&path);
+    }
+    Ok(())
+}
+
+/// Fest eller løsne et bokmerke
+#[tauri::command]
+pub fn set_bookmark_pinned(
+    id: String,
+    pinned: bool,
+    state: State<'_, AppState>,
+) -> Result<BookmarkInfo, CommandError> {
+    let mut store = state.bookmarks.lock();
+    let bookmark = store
+        .set_pinned(&id, pinned)
+        .map(BookmarkInfo::from)
+        .map_err(|e| e.localize())?;
+    save_bookmarks(&state, &store)?;
+    Ok(bookmark)
+}
+
+/// Hent festede bokmerker i manuell rekkefølge
+#[tauri::command]
+pub fn get_pinned_bookmarks(state: State<'_, AppState>) -> Vec<BookmarkInfo> {
+    let store = state.bookmarks.lock();
+    store.pinned().into_iter().map(BookmarkInfo::from).collect()
+}
+
+/// Endre rekkefølgen på de festede bokmerkene
+///
+/// # Arguments
+/// * `ids` - ID-ene til festede bokmerker i ønsket rekkefølge
+#[tauri::command]
+pub fn reorder_pinned_bookmarks(
+    ids: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<BookmarkInfo>, CommandError> {
+    let mut store = state.bookmarks.lock();
+    store.reorder_pinned(&ids).map_err(|e| e.localize())?;
+    save_bookmarks(&state, &store)?;
+    Ok(store.pinned().into_iter().map(BookmarkInfo::from).collect())
+}
+
+/// Lagre en arkivert kopi av siden et bokmerke peker til
+///
+/// Siden må være vist nylig; kopien erstatter en eventuell eldre kopi.
+#[tauri::command]
+pub fn snapshot_bookmark(
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<BookmarkInfo, CommandError> {
+    let mut store = state.bookmarks.lock();
+    let url = store
+        .get(&id)
+        .map(|b| b.url.clone())
+        .ok_or_else(|| bookmarks::BookmarkError::NotFound(id.clone()).localize())?;
+    let source = page_source(&state, &url)?;
+    let bookmark = write_snapshot(&state, &mut store, &id, &source)?;
+    save_bookmarks(&state, &store)?;
+    Ok(BookmarkInfo::from(&bookmark))
+}
+
+/// Åpne den arkiverte kopien av et bokmerke
+///
+/// # Returns
+/// RenderedPage med `is_archived` satt, eller feilmelding
+#[tauri::command]
+pub fn open_bookmark_snapshot(
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<RenderedPage, CommandError> {
+    let (url, path) = {
+        let store = state.bookmarks.lock();
+        let bookmark = store
+            .get(&id)
+            .ok_or_else(|| bookmarks::BookmarkError::NotFound(id.clone()).localize())?;
+        let path = bookmark
+            .snapshot_path
+            .clone()
+            .ok_or_else(|| locale::tf("error.no_snapshot", &[&bookmark.title]))?;
+        (bookmark.url.clone(), path)
+    };
+
+    let content = snapshots::load(Path::new(&path)).map_err(|e| e.localize())?;
+    let rendered = markdown::render_with_options(&content, &render_options(&state, Some(&url)));
+
+    Ok(RenderedPage {
+        html: rendered.html,
+        title: markdown::extract_title(&content),
+        url: Some(url),
+        is_remote: false,
+        was_converted: false,
+        tasks: rendered.tasks,
+        skipped_diagrams: rendered.skipped_diagrams,
+        truncated: rendered.truncated,
+        security: None,
+        timing: None,
+        is_archived: true,
+        used_readability: false,
+    })
+}
+
+/// Sjekk om en URL er bokmerket
+#[tauri::command]
+pub fn is_bookmarked(url: String, state: State<'_, AppState>) -> bool {
+    let store = state.bookmarks.lock();
+    store.is_bookmarked(&url)
+}
+
+/// Oppdater tittel, emneknagger og/eller nøkkelord for et bokmerke
+///
+/// # Arguments
+/// * `id` - Bokmerke-ID
+/// * `title` - Ny tittel (uendret hvis utelatt)
+/// * `tags` - Nye emneknagger (uendret hvis utelatt)
+/// * `keyword` - Nytt nøkkelord; tom streng fjerner det (uendret hvis utelatt)
+#[tauri::command]
+pub fn update_bookmark(
+    id: String,
+    title: Option<String>,
+    tags: Option<Vec<String>>,
+    keyword: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<BookmarkInfo, CommandError> {
+    let mut store = state.bookmarks.lock();
+    if let Some(keyword) = keyword {
+        store.set_keyword(&id, &keyword).map_err(|e| e.localize())?;
+    }
+    let info = store
+        .update(&id, title, tags.as_deref())
+        .map(BookmarkInfo::from)
+        .map_err(|e| e.localize())?;
+    save_bookmarks(&state, &store)?;
+    Ok(info)
+}
+
+/// Gjør adresselinje-input med bokmerke-nøkkelord om til en URL
+///
+/// # Arguments
+/// * `input` - Teksten fra adresselinjen, f.eks. "rs serde"
+///
+/// # Returns
+/// URL-en fra bokmerket med søketeksten satt inn for `%s`, eller input
+/// uendret hvis første ord ikke er et nøkkelord
+#[tauri::command]
+pub fn resolve_keyword(input: String, state: State<'_, AppState>) -> String {
+    let store = state.bookmarks.lock();
+    store.resolve_keyword(&input).unwrap_or(input)
+}
+
+/// Hent bokmerker med en emneknagg (uavhengig av store/små bokstaver)
+#[tauri::command]
+pub fn get_bookmarks_by_tag(tag: String, state: State<'_, AppState>) -> Vec<BookmarkInfo> {
+    let store = state.bookmarks.lock();
+    store
+        .with_tag(&tag)
+        .into_iter()
+        .map(BookmarkInfo::from)
+        .collect()
+}
+
+/// Standard antall treff fra bokmerkesøk
+const DEFAULT_SEARCH_LIMIT: usize = 20;
+
+/// Maksimalt antall treff fra bokmerkesøk
+const MAX_SEARCH_LIMIT: usize = 200;
+
+/// Søk i bokmerker etter tittel, URL, emneknagger og mappe
+///
+/// # Arguments
+/// * `query` - Søketekst (uavhengig av store/små bokstaver)
+/// * `limit` - Maksimalt antall treff (standard: 20)
+///
+/// # Returns
+/// Treff sortert med best treff først
+#[tauri::command]
+pub fn search_bookmarks(
+    query: String,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Vec<BookmarkInfo> {
+    let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT).min(MAX_SEARCH_LIMIT);
+    let store = state.bookmarks.lock();
+    store
+        .search(&query, limit)
+        .into_iter()
+        .map(BookmarkInfo::from)
+        .collect()
+}
+
+/// Hent alle emneknagger med antall bokmerker
+#[tauri::command]
+pub fn list_tags(state: State<'_, AppState>) -> Vec<TagCount> {
+    let store = state.bookmarks.lock();
+    store.tags()
+}
+
+/// Fremdrift fra sjekk av bokmerker
+#[derive(Debug, Clone, Serialize)]
+struct BookmarkCheckProgress<'a> {
+    /// Antall ferdige sjekker
+    done: usize,
+    /// Totalt antall bokmerker som sjekkes
+    total: usize,
+    /// Resultatet som nettopp ble ferdig
+    result: &'a LinkCheckResult,
+}
+
+/// Sjekk om bokmerker fortsatt virker
+///
+/// Sender `bookmark-check-progress` for hvert ferdige bokmerke. Permanente
+/// redirects har `suggested_url`, som frontend kan lagre med `update_bookmark`.
+///
+/// # Arguments
+/// * `ids` - Bokmerkene som skal sjekkes; alle hvis utelatt
+///
+/// # Returns
+/// Status per bokmerke, i samme rekkefølge som bokmerkene
+#[tauri::command]
+pub async fn check_bookmarks(
+    ids: Option<Vec<String>>,
+    window: tauri::Window,
+    state: State<'_, AppState>,
+) -> Result<Vec<LinkCheckResult>, CommandError> {
+    let targets: Vec<(String, String)> = {
+        let store = state.bookmarks.lock();
+        store
+            .list()
+            .iter()
+            .filter(|b| ids.as_ref().is_none_or(|ids| ids.contains(&b.id)))
+            .map(|b| (b.id.clone(), b.url.clone()))
+            .collect()
+    };
+
+    let total = targets.len();
+    let mut done = 0;
+    let results = LinkChecker::new()
+        .check_all(&state.gemini(), targets, |result| {
+            done += 1;
+            let _ = window.emit_to(
+                window.label(),
+                "bookmark-check-progress",
+                BookmarkCheckProgress {
+                    done,
+                    total,
+                    result,
+                },
+            );
+        })
+        .await;
+    Ok(results)
+}
+
+/// Importer bokmerker fra en eksportfil fra en annen nettleser
+///
+/// # Arguments
+/// * `path` - Sti til filen
+/// * `format` - Filformat ("html" for Netscape bookmark-filer, "gemtext" for .gmi)
+///
+/// # Returns
+/// Antall nye, duplikater og URL-er som ble hoppet over
+#[tauri::command]
+pub fn import_bookmarks(
+    path: String,
+    format: BookmarkFormat,
+    state: State<'_, AppState>,
+) -> Result<ImportSummary, CommandError> {
+    let mut store = state.bookmarks.lock();
+    let summary = bookmark_io::import_file(&mut store, &PathBuf::from(path), format)
+        .map_err(|e| e.localize())?;
+    if summary.added > 0 {
+        save_bookmarks(&state, &store)?;
+    }
+    Ok(summary)
+}
+
+/// Slå sammen bokmerker fra en annen maskin
+///
+/// # Arguments
+/// * `path` - Sti til en annen bookmarks.json, en JSON-eksport eller en
+///   Netscape bookmark-fil
+///
+/// # Returns
+/// Antall nye og endrede bokmerker og eventuelle tittelkonflikter
+#[tauri::command]
+pub fn merge_bookmarks(
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<MergeSummary, CommandError> {
+    let mut store = state.bookmarks.lock();
+    let summary =
+        bookmark_io::merge_file(&mut store, &PathBuf::from(path)).map_err(|e| e.localize())?;
+    if summary.added > 0 || summary.updated > 0 {
+        save_bookmarks(&state, &store)?;
+    }
+    Ok(summary)
+}
+
+/// Eksporter alle bokmerker til fil
+///
+/// # Arguments
+/// * `path` - Målfil
+/// * `format` - "json", "markdown", "html" (Netscape) eller "gemtext"
+///
+/// # Returns
+/// Antall eksporterte bokmerker
+#[tauri::command]
+pub fn export_bookmarks(
+    path: String,
+    format: BookmarkFormat,
+    state: State<'_, AppState>,
+) -> Result<usize, CommandError> {
+    let store = state.bookmarks.lock();
+    bookmark_io::export_file(&store, &PathBuf::from(path), format).map_err(|e| e.localize().into())
+}
+
+/// Hent alle bokmerkemapper (inkludert overordnede), sortert
+#[tauri::command]
+pub fn get_bookmark_folders(state: State<'_, AppState>) -> Vec<String> {
+    let store = state.bookmarks.lock();
+    store.folders()
+}
+
+/// Opprett en bokmerkemappe
+///
+/// # Arguments
+/// * `path` - Mappesti, f.eks. "Tech/Rust"
+///
+/// # Returns
+/// Normalisert mappesti
+#[tauri::command]
+pub fn create_bookmark_folder(
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<String, CommandError> {
+    let mut store = state.bookmarks.lock();
+    let folder = store.create_folder(&path).map_err(|e| e.localize())?;
+    save_bookmarks(&state, &store)?;
+    Ok(folder)
+}
+
+/// Gi nytt navn til en bokmerkemappe (undermapper følger med)
+#[tauri::command]
+pub fn rename_bookmark_folder(
+    from: String,
+    to: String,
+    state: State<'_, AppState>,
+) -> Result<String, CommandError> {
+    let mut store = state.bookmarks.lock();
+    let folder = store.rename_folder(&from, &to).map_err(|e| e.localize())?;
+    save_bookmarks(&state, &store)?;
+    Ok(folder)
+}
+
+/// Flytt et bokmerke til en mappe (None for roten)
+#[tauri::command]
+pub fn move_bookmark(
+    id: String,
+    folder: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let mut store = state.bookmarks.lock();
+    store
+        .move_bookmark(&id, folder.as_deref())
+        .map_err(|e| e.localize())?;
+    Ok(save_bookmarks(&state, &store)?)
+}
+
+/// Fjern en bokmerkemappe
+///
+/// # Arguments
+/// * `path` - Mappesti
+/// * `cascade` - Slett bokmerkene i mappen (standard: flytt dem til roten)
+///
+/// # Returns
+/// Antall bokmerker som ble slettet eller flyttet
+#[tauri::command]
+pub fn remove_bookmark_folder(
+    path: String,
+    cascade: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<usize, CommandError> {
+    let mut store = state.bookmarks.lock();
+    let snapshots: Vec<(String, String)> = match bookmarks::normalize_folder(&path) {
+        Ok(Some(folder)) => store
+            .in_folder(Some(&folder), true)
+            .into_iter()
+            .filter_map(|b| Some((b.id.clone(), b.snapshot_path.clone()?)))
+            .collect(),
+        _ => Vec::new(),
+    };
+    let affected = store
+        .remove_folder(&path, cascade.unwrap_or(false))
+        .map_err(|e| e.localize())?;
+    save_bookmarks(&state, &store)?;
+
+    // Slett arkiverte kopier for bokmerker som ble slettet sammen med mappen
+    for (id, snapshot) in snapshots {
+        if store.get(&id).is_none() {
+            delete_snapshot(&snapshot);
+        }
+    }
+    Ok(affected)
+}
+
+// ===== Innstillinger-commands =====
+
+/// Innstillinger for frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsInfo {
+    pub theme: String,
+    pub font_size: u32,
+    pub zoom: u32,
+    pub zoom_step: u32,
+    pub font_family: String,
+    pub custom_font_name: Option<String>,
+    pub custom_colors: Option<ThemeColors>,
+    pub content_width: u32,
+    pub show_line_numbers: bool,
+    pub show_hidden_files: bool,
+    pub max_file_mb: u32,
+    pub max_render_mb: u32,
+    pub follow_symlinks: bool,
+    pub conversion_mode: String,
+    pub conversion_rules: Vec<DomainRule>,
+    pub readability_enabled: bool,
+    pub decorations: Decorations,
+    pub plain_text: PlainText,
+    pub onboarding_completed: bool,
+    pub language: String,
+    pub log_level: LogLevel,
+    pub diagram_languages: Vec<String>,
+    pub backup_generations: u32,
+    pub homepage: String,
+    pub search_engine: String,
+    pub default_scheme: DefaultScheme,
+    /// Nedlastingsmappen som gjelder, også når systemets mappe brukes
+    pub downloads_dir: Option<String>,
+    pub use_downloads_dir_without_asking: bool,
+    pub custom_css: Option<String>,
+    pub typography: Typography,
+    pub network: NetworkSettings,
+    pub privacy: PrivacySettings,
+    pub history_enabled: bool,
+    pub history_max_entries: u32,
+    pub history_max_age_days: u32,
+    /// Privat surfing; lagres ikke, gjelder til appen lukkes
+    pub private_mode: bool,
+    pub site_overrides: BTreeMap<String, SiteOverride>,
+}
+
+impl SettingsInfo {
+    /// # Arguments
+    /// * `s` - Innstillingene
+    /// * `private_mode` - Om privat modus er på i økten
+    pub fn new(s: &Settings, private_mode: bool) -> Self {
+        Self {
+            theme: match s.theme {
+                Theme::Light => "light".to_string(),
+                Theme::Dark => "dark".to_string(),
+                Theme::System => "system".to_string(),
+                Theme::Sepia => "sepia".to_string(),
+            },
+            font_size: s.font_size,
+            zoom: s.zoom,
+            zoom_step: s.zoom_step(),
+            font_family: match s.font_family {
+                FontFamily::System => "system".to_string(),
+                FontFamily::Serif => "serif".to_string(),
+                FontFamily::SansSerif => "sans-serif".to_string(),
+                FontFamily::Mono => "mono".to_string(),
+                FontFamily::Custom(_) => "custom".to_string(),
+            },
+            custom_font_name: s.font_family.custom_name().map(str::to_string),
+            custom_colors: s.custom_colors.clone(),
+            content_width: s.content_width,
+            show_line_numbers: s.show_line_numbers,
+            show_hidden_files: s.show_hidden_files,
+            max_file_mb: s.max_file_mb,
+            max_render_mb: s.max_render_mb,
+            follow_symlinks: s.follow_symlinks,
+            conversion_mode: match s.conversion.http {
+                ConversionMode::MarkdownOnly => "markdown-only".to_string(),
+                ConversionMode::ConvertAll => "convert-all".to_string(),
+                ConversionMode::AskEverytime => "ask-everytime".to_string(),
+            },
+            conversion_rules: s.conversion.rules.clone(),
+            readability_enabled: s.readability_enabled,
+            decorations: s.decorations,
+            plain_text: s.plain_text,
+            onboarding_completed: s.onboarding_completed,
+            language: s.language.clone(),
+            log_level: s.log_level,
+            diagram_languages: s.diagram_languages.clone(),
+            backup_generations: s.backup_generations,
+            homepage: s.homepage.clone().unwrap_or_else(|| "welcome".to_string()),
+            search_engine: s.search_engine().to_string(),
+            default_scheme: s.default_scheme,
+            downloads_dir: s
+                .effective_downloads_dir()
+                .map(|dir| dir.display().to_string()),
+            use_downloads_dir_without_asking: s.use_downloads_dir_without_asking,
+            custom_css: s.custom_css.clone(),
+            typography: s.typography.clone(),
+            network: s.network.clone(),
+            privacy: s.privacy.clone(),
+            history_enabled: s.history_enabled,
+            history_max_entries: s.history_max_entries,
+            history_max_age_days: s.history_max_age_days,
+            private_mode,
+            site_overrides: s.site_overrides.clone(),
+        }
+    }
+}
+
+/// Innstillingene til frontend, med privat modus fra økten
+fn settings_info(state: &AppState, settings: &Settings) -> SettingsInfo {
+    SettingsInfo::new(settings, state.session.private_mode.is_active())
+}
+
+/// Velg språk for tekster til frontend ut fra språkinnstillingen
+pub fn init_locale(state: &AppState) {
+    let language = state.settings.read().language.clone();
+    locale::set_current(locale::Locale::from_language(&language));
+}
+
+/// Hendelsen som forteller alle vinduer at innstillingene er endret
+const SETTINGS_CHANGED: &str = "settings-changed";
+
+/// Send de lagrede innstillingene til alle vinduer
+fn broadcast_settings(state: &AppState, app: &AppHandle, info: &SettingsInfo) {
+    // En eldre zoom-tilstand som venter må ikke overskrive denne
+    state.session.zoom_broadcast.cancel();
+    EventSink::send(app, SETTINGS_CHANGED, info);
+}
+
+/// Send innstillingene etter en zoom-endring når brukeren har zoomet ferdig
+fn broadcast_zoom(state: &AppState, app: &AppHandle, info: &SettingsInfo) {
+    state
+        .session
+        .zoom_broadcast
+        .schedule(Arc::new(app.clone()), SETTINGS_CHANGED, info.clone());
+}
+
+/// Lagre innstillingene uten å overskrive endringer gjort i filen utenfra
+///
+/// Hvis filen er endret siden vi sist leste eller skrev den, legges
+/// endringene i minnet oppå den nye versjonen før lagring. Endrer det
+/// nettverks- eller personverninnstillingene, bygges klientene på nytt.
+fn save_settings(state: &AppState, settings: &mut Settings) -> Result<(), String> {
+    let path = state.paths.settings();
+    if path.exists() && !settings.is_own_write(&path) {
+        match Settings::load_external(&path) {
+            Ok(disk) => {
+                let old_network = settings.network.clone();
+                let old_privacy = settings.privacy.clone();
+                settings.rebase(disk, &path);
+                locale::set_current(locale::Locale::from_language(&settings.language));
+                logging::set_level(settings.log_level);
+                if settings.network != old_network
+                    || settings.privacy.needs_new_client(&old_privacy)
+                {
+                    state.rebuild_network_clients(&settings.network, &settings.privacy);
+                }
+            }
+            Err(e) => warn!("Overskriver ugyldig innstillingsfil: {}", e),
+        }
+    }
+    settings.save(&path).map_err(|e| e.localize())
+}
+
+/// Les innstillingsfilen på nytt etter en endring utenfra
+///
+/// Ulagrede endringer i minnet beholdes og skrives tilbake. En ugyldig fil
+/// gir en feil, og innstillingene i minnet beholdes uendret.
+///
+/// # Returns
+/// De nye innstillingene, eller None hvis endringen var vår egen
+fn reload_settings_from_disk(state: &AppState) -> Result<Option<SettingsInfo>, String> {
+    let path = state.paths.settings();
+    let mut settings = state.settings.write();
+    if !path.exists() || settings.is_own_write(&path) {
+        return Ok(None);
+    }
+
+    let disk = Settings::load_external(&path).map_err(|e| e.localize())?;
+    let old_network = settings.network.clone();
+    let old_privacy = settings.privacy.clone();
+    if settings.rebase(disk, &path) {
+        settings.save(&path).map_err(|e| e.localize())?;
+    }
+    locale::set_current(locale::Locale::from_language(&settings.language));
+    logging::set_level(settings.log_level);
+
+    let info = settings_info(state, &settings);
+    drop(settings);
+
+    if info.network != old_network || info.privacy.needs_new_client(&old_privacy) {
+        state.rebuild_network_clients(&info.network, &info.privacy);
+    }
+    Ok(Some(info))
+}
+
+/// Start overvåking av innstillingsfilen
+///
+/// Når filen endres utenfra, f.eks. for hånd i en teksteditor, leses den
+/// inn på nytt og alle vinduer får eventet `settings-changed`.
+pub fn start_settings_watcher(app: AppHandle) {
+    let state = app.state::<AppState>();
+    let path = state.paths.settings();
+    let handle = app.clone();
+    let result = watcher::watch_file(&path, move || {
+        let state = handle.state::<AppState>();
+        match reload_settings_from_disk(&state) {
+            Ok(Some(info)) => {
+                info!("Innstillinger lest inn på nytt etter endring på disk");
+                broadcast_settings(&state, &handle, &info);
+            }
+            Ok(None) => {}
+            Err(e) => warn!("Kunne ikke lese innstillinger på nytt: {}", e),
+        }
+    });
+
+    match result {
+        Ok(watcher) => state.session.store_watchers.lock().push(watcher),
+        Err(e) => warn!("Kunne ikke overvåke {}: {}", path.display(), e),
+    }
+}
+
+/// Hent gjeldende innstillinger
+#[tauri::command]
+pub fn get_settings(state: State<'_, AppState>) -> SettingsInfo {
+    let settings = state.settings.read();
+    settings_info(&state, &settings)
+}
+
+/// Maksimalt antall roterte sikkerhetskopier
+const MAX_BACKUP_GENERATIONS: u32 = 20;
+
+/// Parametere for oppdatering av innstillinger
+#[derive(serde::Deserialize)]
+pub struct UpdateSettingsParams {
+    pub theme: Option<String>,
+    pub font_size: Option<u32>,
+    pub zoom: Option<u32>,
+    pub zoom_step: Option<u32>,
+    pub font_family: Option<String>,
+    pub custom_font_name: Option<String>,
+    pub custom_colors: Option<ThemeColors>,
+    pub content_width: Option<u32>,
+    pub show_line_numbers: Option<bool>,
+    pub show_hidden_files: Option<bool>,
+    pub max_file_mb: Option<u32>,
+    pub max_render_mb: Option<u32>,
+    pub follow_symlinks: Option<bool>,
+    pub conversion_mode: Option<String>,
+    pub readability_enabled: Option<bool>,
+    pub decorations: Option<String>,
+    pub plain_text: Option<String>,
+    pub onboarding_completed: Option<bool>,
+    pub language: Option<String>,
+    pub log_level: Option<String>,
+    pub diagram_languages: Option<Vec<String>>,
+    pub backup_generations: Option<u32>,
+    pub homepage: Option<String>,
+    pub search_engine: Option<String>,
+    pub default_scheme: Option<String>,
+    pub downloads_dir: Option<String>,
+    pub use_downloads_dir_without_asking: Option<bool>,
+    pub custom_css: Option<String>,
+    pub typography: Option<TypographyParams>,
+    pub network: Option<NetworkParams>,
+    pub privacy: Option<PrivacyParams>,
+    pub history_enabled: Option<bool>,
+    pub history_max_entries: Option<u32>,
+    pub history_max_age_days: Option<u32>,
+}
+
+impl UpdateSettingsParams {
+    /// Feltene update_settings kjenner; andre felter ignoreres med en advarsel
+    const FIELDS: [&'static str; 34] = [
+        "theme",
+        "font_size",
+        "zoom",
+        "zoom_step",
+        "font_family",
+        "custom_font_name",
+        "custom_colors",
+        "content_width",
+        "show_line_numbers",
+        "show_hidden_files",
+        "max_file_mb",
+        "max_render_mb",
+        "follow_symlinks",
+        "conversion_mode",
+        "readability_enabled",
+        "decorations",
+        "plain_text",
+        "onboarding_completed",
+        "language",
+        "log_level",
+        "diagram_languages",
+        "backup_generations",
+        "homepage",
+        "search_engine",
+        "default_scheme",
+        "downloads_dir",
+        "use_downloads_dir_without_asking",
+        "custom_css",
+        "typography",
+        "network",
+        "privacy",
+        "history_enabled",
+        "history_max_entries",
+        "history_max_age_days",
+    ];
+
+    /// Les parametrene fra JSON
+    ///
+    /// Ukjente felter gir en advarsel. Hvert kjent felt leses for seg, slik
+    /// at en verdi av feil type gir en feil som nevner feltet.
+    ///
+    /// # Arguments
+    /// * `raw` - JSON-objektet fra frontend
+    /// * `warnings` - Advarslene legges til her
+    fn parse(
+        raw: serde_json::Value,
+        warnings: &mut Vec<SettingsWarning>,
+    ) -> Result<Self, UpdateSettingsError> {
+        let serde_json::Value::Object(fields) = raw else {
+            return Err(UpdateSettingsError::new(
+                locale::t("error.settings_not_object").to_string(),
+            ));
+        };
+
+        let mut known = serde_json::Map::new();
+        for (field, value) in fields {
+            if !Self::FIELDS.contains(&field.as_str()) {
+                warnings.push(SettingsWarning::new(&field, value, WarningAction::Ignored));
+                continue;
+            }
+            let single = serde_json::json!({ field.as_str(): value });
+            if let Err(e) = serde_json::from_value::<Self>(single) {
+                return Err(UpdateSettingsError::for_field(&field, e.to_string()));
+            }
+            known.insert(field, value);
+        }
+        serde_json::from_value(serde_json::Value::Object(known))
+            .map_err(|e| UpdateSettingsError::new(e.to_string()))
+    }
+}
+
+/// Endringer i typografien; felter som mangler beholdes
+#[derive(serde::Deserialize)]
+pub struct TypographyParams {
+    pub line_height: Option<u32>,
+    pub paragraph_spacing: Option<u32>,
+    pub justify_text: Option<bool>,
+}
+
+/// Endringer i nettverksinnstillingene; felter som mangler beholdes
+#[derive(serde::Deserialize)]
+pub struct NetworkParams {
+    pub timeout_seconds: Option<u64>,
+    pub max_response_mb: Option<u32>,
+    pub max_redirects: Option<u8>,
+}
+
+/// Endringer i personverninnstillingene; felter som mangler beholdes
+#[derive(serde::Deserialize)]
+pub struct PrivacyParams {
+    pub load_remote_images: Option<bool>,
+    pub strip_query_params: Option<bool>,
+    pub send_referrer: Option<bool>,
+    pub send_gpc: Option<bool>,
+    pub prefetch: Option<bool>,
+}
+
+/// Hva update_settings gjorde med en verdi den ikke kunne bruke som den var
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WarningAction {
+    /// Verdien var utenfor gyldig område og ble justert
+    Clamped,
+    /// Feltet er ukjent og ble ikke brukt
+    Ignored,
+    /// Verdien er ugyldig; innstillingen er uendret
+    Invalid,
+}
+
+/// En verdi som ikke ble brukt slik den ble sendt
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SettingsWarning {
+    /// Feltet, f.eks. "font_size" eller "typography.line_height"
+    pub field: String,
+    /// Verdien slik den ble sendt
+    pub value: serde_json::Value,
+    pub action: WarningAction,
+}
+
+impl SettingsWarning {
+    fn new(field: &str, value: impl Serialize, action: WarningAction) -> Self {
+        Self {
+            field: field.to_string(),
+            value: serde_json::to_value(value).unwrap_or_default(),
+            action,
+        }
+    }
+}
+
+/// Resultatet av update_settings
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsUpdate {
+    pub settings: SettingsInfo,
+    /// Verdier som ble justert eller ikke brukt
+    pub warnings: Vec<SettingsWarning>,
+}
+
+/// Feil fra update_settings
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UpdateSettingsError {
+    /// Feltet som er ugyldig, hvis feilen gjelder ett felt
+    pub field: Option<String>,
+    pub message: String,
+}
+
+impl UpdateSettingsError {
+    fn new(message: String) -> Self {
+        Self {
+            field: None,
+            message,
+        }
+    }
+
+    fn for_field(field: &str, reason: String) -> Self {
+        Self {
+            field: Some(field.to_string()),
+            message: locale::tf("error.settings_field", &[&field, &reason]),
+        }
+    }
+}
+
+impl From<String> for UpdateSettingsError {
+    fn from(message: String) -> Self {
+        Self::new(message)
+    }
+}
+
+/// Juster en verdi til området og gi en advarsel hvis den var utenfor
+fn clamp_field<T: Ord + Copy + Serialize>(
+    field: &str,
+    value: T,
+    min: T,
+    max: T,
+    warnings: &mut Vec<SettingsWarning>,
+) -> T {
+    let clamped = value.clamp(min, max);
+    if clamped != value {
+        warnings.push(SettingsWarning::new(field, value, WarningAction::Clamped));
+    }
+    clamped
+}
+
+/// Gi en advarsel for hvert felt som ble endret da en gruppe ble justert
+///
+/// # Arguments
+/// * `section` - Gruppen, f.eks. "typography"
+/// * `requested` - Verdiene slik de ble sendt
+/// * `clamped` - Verdiene etter justering
+fn warn_clamped_section<T: Serialize>(
+    section: &str,
+    requested: &T,
+    clamped: &T,
+    warnings: &mut Vec<SettingsWarning>,
+) {
+    let (Ok(serde_json::Value::Object(requested)), Ok(clamped)) = (
+        serde_json::to_value(requested),
+        serde_json::to_value(clamped),
+    ) else {
+        return;
+    };
+    for (field, value) in requested {
+        if clamped.get(&field) != Some(&value) {
+            warnings.push(SettingsWarning::new(
+                &format!("{}.{}", section, field),
+                value,
+                WarningAction::Clamped,
+            ));
+        }
+    }
+}
+
+/// Legg endringene over innstillingene
+///
+/// # Returns
+/// Advarsler for verdier som ble justert eller ikke brukt, eller en feil
+/// som nevner feltet som ikke kunne brukes. Ved feil kan innstillingene
+/// være delvis endret og skal ikke lagres.
+fn apply_settings_params(
+    settings: &mut Settings,
+    raw: serde_json::Value,
+) -> Result<Vec<SettingsWarning>, UpdateSettingsError> {
+    let mut warnings = Vec::new();
+    let params = UpdateSettingsParams::parse(raw, &mut warnings)?;
+    let invalid =
+        |field: &str, value: &str| SettingsWarning::new(field, value, WarningAction::Invalid);
+
+    if let Some(t) = params.theme {
+        match Theme::parse(&t) {
+            Some(theme) => settings.theme = theme,
+            None => warnings.push(invalid("theme", &t)),
+        }
+    }
+
+    if let Some(size) = params.font_size {
+        settings.font_size = clamp_field("font_size", size, 70, 150, &mut warnings);
+    }
+
+    if let Some(colors) = params.custom_colors {
+        settings
+            .set_custom_colors(Some(colors))
+            .map_err(|e| UpdateSettingsError::for_field("custom_colors", e.localize()))?;
+    }
+
+    if let Some(z) = params.zoom {
+        settings.zoom = clamp_field("zoom", z, 50, 200, &mut warnings);
+    }
+
+    if let Some(step) = params.zoom_step {
+        settings.set_zoom_step(clamp_field("zoom_step", step, 5, 50, &mut warnings));
+    }
+
+    if let Some(ff) = params.font_family {
+        let font_family = match ff.as_str() {
+            "system" => Some(FontFamily::System),
+            "serif" => Some(FontFamily::Serif),
+            "sans-serif" => Some(FontFamily::SansSerif),
+            "mono" => Some(FontFamily::Mono),
+            "custom" => {
+                // Uten nytt navn beholdes skriften som allerede er valgt
+                let name = params
+                    .custom_font_name
+                    .as_deref()
+                    .or(settings.font_family.custom_name())
+                    .unwrap_or_default();
+                Some(FontFamily::custom(name).map_err(|e| {
+                    UpdateSettingsError::for_field("custom_font_name", e.localize())
+                })?)
+            }
+            _ => None,
+        };
+        match font_family {
+            Some(font_family) => settings.font_family = font_family,
+            None => warnings.push(invalid("font_family", &ff)),
+        }
+    } else if let Some(name) = params.custom_font_name {
+        settings.font_family = FontFamily::custom(&name)
+            .map_err(|e| UpdateSettingsError::for_field("custom_font_name", e.localize()))?;
+    }
+
+    if let Some(width) = params.content_width {
+        settings.content_width = clamp_field("content_width", width, 400, 1200, &mut warnings);
+    }
+
+    if let Some(ln) = params.show_line_numbers {
+        settings.show_line_numbers = ln;
+    }
+
+    if let Some(hidden) = params.show_hidden_files {
+        settings.show_hidden_files = hidden;
+    }
+
+    if let Some(max) = params.max_file_mb {
+        settings.max_file_mb = clamp_field("max_file_mb", max, 1, 100, &mut warnings);
+    }
+
+    if let Some(max) = params.max_render_mb {
+        settings.max_render_mb = clamp_field("max_render_mb", max, 1, 100, &mut warnings);
+    }
+
+    if let Some(follow) = params.follow_symlinks {
+        settings.follow_symlinks = follow;
+    }
+
+    if let Some(cm) = params.conversion_mode {
+        match cm.as_str() {
+            "markdown-only" => settings.conversion.http = ConversionMode::MarkdownOnly,
+            "convert-all" => settings.conversion.http = ConversionMode::ConvertAll,
+            "ask-everytime" => settings.conversion.http = ConversionMode::AskEverytime,
+            _ => warnings.push(invalid("conversion_mode", &cm)),
+        }
+    }
+
+    if let Some(re) = params.readability_enabled {
+        settings.readability_enabled = re;
+    }
+
+    if let Some(decorations) = params.decorations {
+        match decorations.as_str() {
+            "emoji" => settings.decorations = Decorations::Emoji,
+            "text" => settings.decorations = Decorations::Text,
+            "none" => settings.decorations = Decorations::None,
+            _ => warnings.push(invalid("decorations", &decorations)),
+        }
+    }
+
+    if let Some(plain_text) = params.plain_text {
+        match plain_text.as_str() {
+            "preformatted" => settings.plain_text = PlainText::Preformatted,
+            "paragraphs" => settings.plain_text = PlainText::Paragraphs,
+            _ => warnings.push(invalid("plain_text", &plain_text)),
+        }
+    }
+
+    if let Some(oc) = params.onboarding_completed {
+        settings.onboarding_completed = oc;
+    }
+
+    if let Some(lang) = params.language {
+        settings.language = lang;
+    }
+
+    if let Some(level) = params.log_level {
+        match LogLevel::parse(&level) {
+            Some(level) => settings.log_level = level,
+            None => warnings.push(invalid("log_level", &level)),
+        }
+    }
+
+    if let Some(languages) = params.diagram_languages {
+        let mut valid = Vec::new();
+        for language in &languages {
+            let normalized = language.trim().to_lowercase();
+            if !normalized.is_empty()
+                && normalized
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            {
+                valid.push(normalized);
+            } else {
+                warnings.push(invalid("diagram_languages", language));
+            }
+        }
+        settings.diagram_languages = valid;
+    }
+
+    if let Some(generations) = params.backup_generations {
+        settings.backup_generations = clamp_field(
+            "backup_generations",
+            generations,
+            0,
+            MAX_BACKUP_GENERATIONS,
+            &mut warnings,
+        );
+    }
+
+    if let Some(homepage) = params.homepage {
+        settings
+            .set_homepage(&homepage)
+            .map_err(|e| UpdateSettingsError::for_field("homepage", e.localize()))?;
+    }
+
+    if let Some(search_engine) = params.search_engine {
+        settings
+            .set_search_engine(&search_engine)
+            .map_err(|e| UpdateSettingsError::for_field("search_engine", e.localize()))?;
+    }
+
+    if let Some(scheme) = params.default_scheme {
+        match scheme.as_str() {
+            "https" => settings.default_scheme = DefaultScheme::Https,
+            "gemini" => settings.default_scheme = DefaultScheme::Gemini,
+            "ask" => settings.default_scheme = DefaultScheme::Ask,
+            _ => warnings.push(invalid("default_scheme", &scheme)),
+        }
+    }
+
+    if let Some(dir) = params.downloads_dir {
+        settings
+            .set_downloads_dir(&dir)
+            .map_err(|e| UpdateSettingsError::for_field("downloads_dir", e.localize()))?;
+    }
+
+    if let Some(without_asking) = params.use_downloads_dir_without_asking {
+        settings.use_downloads_dir_without_asking = without_asking;
+    }
+
+    if let Some(css) = params.custom_css {
+        settings
+            .set_custom_css(&css)
+            .map_err(|e| UpdateSettingsError::for_field("custom_css", e.localize()))?;
+    }
+
+    if let Some(typography) = params.typography {
+        let old = &settings.typography;
+        let requested = Typography {
+            line_height: typography.line_height.unwrap_or(old.line_height),
+            paragraph_spacing: typography
+                .paragraph_spacing
+                .unwrap_or(old.paragraph_spacing),
+            justify_text: typography.justify_text.unwrap_or(old.justify_text),
+        };
+        settings.typography = requested.clone().clamped();
+        warn_clamped_section(
+            "typography",
+            &requested,
+            &settings.typography,
+            &mut warnings,
+        );
+    }
+
+    if let Some(network) = params.network {
+        let old = &settings.network;
+        let requested = NetworkSettings {
+            timeout_seconds: network.timeout_seconds.unwrap_or(old.timeout_seconds),
+            max_response_mb: network.max_response_mb.unwrap_or(old.max_response_mb),
+            max_redirects: network.max_redirects.unwrap_or(old.max_redirects),
+        };
+        settings.network = requested.clone().clamped();
+        warn_clamped_section("network", &requested, &settings.network, &mut warnings);
+    }
+
+    if let Some(privacy) = params.privacy {
+        let old = &settings.privacy;
+        settings.privacy = PrivacySettings {
+            load_remote_images: privacy.load_remote_images.unwrap_or(old.load_remote_images),
+            strip_query_params: privacy.strip_query_params.unwrap_or(old.strip_query_params),
+            send_referrer: privacy.send_referrer.unwrap_or(old.send_referrer),
+            send_gpc: privacy.send_gpc.unwrap_or(old.send_gpc),
+            prefetch: privacy.prefetch.unwrap_or(old.prefetch),
+        };
+    }
+
+    if let Some(enabled) = params.history_enabled {
+        settings.history_enabled = enabled;
+    }
+
+    if let Some(max) = params.history_max_entries {
+        settings.history_max_entries =
+            clamp_field("history_max_entries", max, 100, 100_000, &mut warnings);
+    }
+
+    if let Some(days) = params.history_max_age_days {
+        settings.history_max_age_days =
+            clamp_field("history_max_age_days", days, 1, 3650, &mut warnings);
+    }
+
+    Ok(warnings)
+}
+
+/// Oppdater innstillinger
+///
+/// Verdier utenfor gyldig område justeres, og ukjente felter og ugyldige
+/// valg hoppes over. Alt dette rapporteres som advarsler.
+///
+/// # Arguments
+/// * `params` - Feltene som skal endres
+///
+/// # Returns
+/// De lagrede innstillingene med advarsler, eller en feil som nevner feltet
+/// som ikke kunne brukes. Ved feil lagres ingenting.
+#[tauri::command]
+pub fn update_settings(
+    app: AppHandle,
+    params: serde_json::Value,
+    state: State<'_, AppState>,
+) -> Result<SettingsUpdate, UpdateSettingsError> {
+    let mut settings = state.settings.write();
+    let old_network = settings.network.clone();
+    let old_privacy = settings.privacy.clone();
+    let old_language = settings.language.clone();
+
+    let mut updated = settings.clone();
+    let warnings = apply_settings_params(&mut updated, params)?;
+    *settings = updated;
+
+    if settings.language != old_language {
+        locale::set_current(locale::Locale::from_language(&settings.language));
+    }
+    logging::set_level(settings.log_level);
+
+    // Lagre til fil
+    save_settings(&state, &mut settings)?;
+
+    let info = settings_info(&state, &settings);
+    let network = settings.network.clone();
+    let privacy = settings.privacy.clone();
+    drop(settings);
+
+    if network != old_network || privacy.needs_new_client(&old_privacy) {
+        state.rebuild_network_clients(&network, &privacy);
+    }
+    broadcast_settings(&state, &app, &info);
+    Ok(SettingsUpdate {
+        settings: info,
+        warnings,
+    })
+}
+
+/// Fjern egne farger og gå tilbake til temaets farger
+///
+/// # Returns
+/// Oppdaterte innstillinger
+#[tauri::command]
+pub fn reset_colors(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<SettingsInfo, CommandError> {
+    let mut settings = state.settings.write();
+    settings.custom_colors = None;
+    save_settings(&state, &mut settings)?;
+
+    let info = settings_info(&state, &settings);
+    drop(settings);
+    broadcast_settings(&state, &app, &info);
+    Ok(info)
 }
 
-// ===== Bokmerke-commands =====
+/// Slå privat surfing av eller på
+///
+/// Mens privat modus er på, skrives verken historikk eller nye
+/// Gemini-sertifikater til disk, og adresser holdes utenfor loggen.
+///
+/// # Returns
+/// Oppdaterte innstillinger
+#[tauri::command]
+pub fn set_private_mode(app: AppHandle, enabled: bool, state: State<'_, AppState>) -> SettingsInfo {
+    state.set_private(enabled);
+    // Sider hentet før privat surfing skal ikke vises under den
+    state.session.prefetched.lock().clear();
+
+    let info = settings_info(&state, &state.settings.read());
+    broadcast_settings(&state, &app, &info);
+    info
+}
 
-/// Bokmerke-info for frontend
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BookmarkInfo {
-    pub id: String,
-    pub title: String,
-    pub url: String,
-    pub created_at: u64,
+/// Tilbakestill en gruppe innstillinger til standardverdiene
+///
+/// # Arguments
+/// * `scope` - "all", "appearance", "network", "privacy" eller "site-overrides"
+/// * `keep_onboarding` - Ved "all": ikke vis onboarding på nytt
+///
+/// # Returns
+/// Oppdaterte innstillinger
+#[tauri::command]
+pub fn reset_settings(
+    app: AppHandle,
+    scope: ResetScope,
+    keep_onboarding: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<SettingsInfo, CommandError> {
+    let mut settings = state.settings.write();
+    let old_network = settings.network.clone();
+    let old_privacy = settings.privacy.clone();
+    settings.reset(scope, keep_onboarding.unwrap_or(true));
+    locale::set_current(locale::Locale::from_language(&settings.language));
+    logging::set_level(settings.log_level);
+
+    save_settings(&state, &mut settings)?;
+
+    let info = settings_info(&state, &settings);
+    drop(settings);
+
+    if info.network != old_network || info.privacy.needs_new_client(&old_privacy) {
+        state.rebuild_network_clients(&info.network, &info.privacy);
+    }
+    broadcast_settings(&state, &app, &info);
+    Ok(info)
 }
 
-impl From<&Bookmark> for BookmarkInfo {
-    fn from(b: &Bookmark) -> Self {
-        Self {
-            id: b.id.clone(),
-            title: b.title.clone(),
-            url: b.url.clone(),
-            created_at: b.created_at,
-        }
+/// Hent konverteringsreglene for domener
+#[tauri::command]
+pub fn list_domain_conversion_rules(state: State<'_, AppState>) -> Vec<DomainRule> {
+    state.settings.read().conversion.rules.clone()
+}
+
+/// Velg konverteringsmodus for et domene, f.eks. "konverter alltid"
+///
+/// # Arguments
+/// * `host_pattern` - Vertsnavn eller `*.vert` for alle underdomener
+/// * `mode` - "markdown-only", "convert-all" eller "ask-everytime"
+///
+/// # Returns
+/// Alle regler etter endringen
+#[tauri::command]
+pub fn set_domain_conversion_rule(
+    app: AppHandle,
+    host_pattern: String,
+    mode: ConversionMode,
+    state: State<'_, AppState>,
+) -> Result<Vec<DomainRule>, CommandError> {
+    let mut settings = state.settings.write();
+    settings
+        .conversion
+        .set_rule(&host_pattern, mode)
+        .map_err(|e| e.localize())?;
+
+    save_settings(&state, &mut settings)?;
+
+    let info = settings_info(&state, &settings);
+    drop(settings);
+    broadcast_settings(&state, &app, &info);
+    Ok(info.conversion_rules)
+}
+
+/// Fjern konverteringsregelen for et domene
+///
+/// # Returns
+/// Reglene som er igjen
+#[tauri::command]
+pub fn remove_domain_conversion_rule(
+    app: AppHandle,
+    pattern: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<DomainRule>, CommandError> {
+    let mut settings = state.settings.write();
+    if !settings.conversion.remove_rule(&pattern) {
+        return Ok(settings.conversion.rules.clone());
     }
+
+    save_settings(&state, &mut settings)?;
+
+    let info = settings_info(&state, &settings);
+    drop(settings);
+    broadcast_settings(&state, &app, &info);
+    Ok(info.conversion_rules)
 }
 
-/// Hent alle bokmerker
+/// Finn ut hvor en nedlasting skal lagres
+///
+/// # Arguments
+/// * `file_name` - Foreslått filnavn
+///
+/// # Returns
+/// En fil å lagre direkte til, eller at frontend skal vise en lagringsdialog
 #[tauri::command]
-pub fn get_bookmarks() -> Vec<BookmarkInfo> {
-    let store = BOOKMARKS.lock().unwrap();
-    store.list().iter().map(BookmarkInfo::from).collect()
+pub fn get_download_target(file_name: String, state: State<'_, AppState>) -> DownloadTarget {
+    state.settings.read().download_target(&file_name)
 }
 
-/// Legg til et nytt bokmerke
+/// Hent innstillingene som gjelder for en side
+///
+/// # Arguments
+/// * `url` - Adressen til siden
+///
+/// # Returns
+/// Globale innstillinger med nettstedets overstyringer lagt over
 #[tauri::command]
-pub fn add_bookmark(title: String, url: String) -> Result<BookmarkInfo, String> {
-    let mut store = BOOKMARKS.lock().unwrap();
+pub fn get_site_settings(url: String, state: State<'_, AppState>) -> SiteSettings {
+    site_settings_for(&state, &url)
+}
 
-    let bookmark = Bookmark {
-        id: bookmarks::generate_id(),
-        title,
-        url,
-        created_at: bookmarks::current_timestamp(),
+/// Lagre innstillinger for et nettsted
+///
+/// # Arguments
+/// * `host` - Vertsnavn, eller `*.vert` for alle underdomener
+/// * `params` - Innstillingene som skal overstyres; tomme felter arves
+///
+/// # Returns
+/// Oppdaterte innstillinger
+#[tauri::command]
+pub fn set_site_override(
+    app: AppHandle,
+    host: String,
+    params: SiteOverride,
+    state: State<'_, AppState>,
+) -> Result<SettingsInfo, CommandError> {
+    let mut settings = state.settings.write();
+    settings
+        .set_site_override(&host, params)
+        .map_err(|e| e.localize())?;
+
+    save_settings(&state, &mut settings)?;
+
+    let info = settings_info(&state, &settings);
+    broadcast_settings(&state, &app, &info);
+    Ok(info)
+}
+
+/// Fjern innstillingene for et nettsted
+#[tauri::command]
+pub fn clear_site_override(
+    app: AppHandle,
+    host: String,
+    state: State<'_, AppState>,
+) -> Result<SettingsInfo, CommandError> {
+    let mut settings = state.settings.write();
+    let info = if settings.clear_site_override(&host) {
+        save_settings(&state, &mut settings)?;
+
+        let info = settings_info(&state, &settings);
+        broadcast_settings(&state, &app, &info);
+        info
+    } else {
+        settings_info(&state, &settings)
     };
 
-    store.add(bookmark.clone()).map_err(|e| e.to_string())?;
+    Ok(info)
+}
 
-    // Lagre til fil
-    let path = bookmarks::get_bookmarks_path();
-    store.save(&path).map_err(|e| e.to_string())?;
+/// Hent gjeldende tastatursnarveier
+#[tauri::command]
+pub fn get_key_bindings(state: State<'_, AppState>) -> Vec<KeyBindingInfo> {
+    keybindings::list(&state.settings.read().key_bindings)
+}
 
-    Ok(BookmarkInfo::from(&bookmark))
+/// Gi en handling en ny tastatursnarvei
+///
+/// # Arguments
+/// * `action` - Handlingen, f.eks. "zoom-in"
+/// * `accelerator` - Snarveien, f.eks. "Ctrl+Shift+Plus"
+///
+/// # Returns
+/// Alle snarveier etter endringen, eller feil hvis snarveien er ugyldig
+/// eller allerede i bruk
+#[tauri::command]
+pub fn set_key_binding(
+    action: String,
+    accelerator: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<KeyBindingInfo>, CommandError> {
+    let mut settings = state.settings.write();
+    keybindings::set(&mut settings.key_bindings, &action, &accelerator)
+        .map_err(|e| e.localize())?;
+
+    save_settings(&state, &mut settings)?;
+
+    Ok(keybindings::list(&settings.key_bindings))
 }
 
-/// Fjern et bokmerke
+/// Tilbakestill en handling til standardsnarveien
 #[tauri::command]
-pub fn remove_bookmark(id: String) -> Result<(), String> {
-    let mut store = BOOKMARKS.lock().unwrap();
-    store.remove(&id).map_err(|e| e.to_string())?;
+pub fn reset_key_binding(
+    action: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<KeyBindingInfo>, CommandError> {
+    let mut settings = state.settings.write();
+    keybindings::reset(&mut settings.key_bindings, &action).map_err(|e| e.localize())?;
 
-    // Lagre til fil
-    let path = bookmarks::get_bookmarks_path();
-    store.save(&path).map_err(|e| e.to_string())
+    save_settings(&state, &mut settings)?;
+
+    Ok(keybindings::list(&settings.key_bindings))
 }
 
-/// Sjekk om en URL er bokmerket
+/// Hent skriftfamiliene som er installert på maskinen
+///
+/// Brukes til å foreslå navn for en egendefinert skrift.
 #[tauri::command]
-pub fn is_bookmarked(url: String) -> bool {
-    let store = BOOKMARKS.lock().unwrap();
-    store.is_bookmarked(&url)
+pub async fn list_system_fonts() -> Vec<String> {
+    tokio::task::spawn_blocking(settings::system_font_families)
+        .await
+        .unwrap_or_default()
 }
 
-// ===== Innstillinger-commands =====
+/// Hent CSS-en som skal legges over sidens stiler
+///
+/// `user.css` ved siden av settings.json går foran innstillingen.
+#[tauri::command]
+pub fn get_custom_css(state: State<'_, AppState>) -> Option<String> {
+    let settings = state.settings.read();
+    settings.effective_css(&state.paths.user_css())
+}
 
-/// Innstillinger for frontend
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SettingsInfo {
-    pub theme: String,
-    pub font_size: u32,
-    pub zoom: u32,
-    pub font_family: String,
-    pub content_width: u32,
-    pub show_line_numbers: bool,
-    pub conversion_mode: String,
-    pub readability_enabled: bool,
-    pub onboarding_completed: bool,
-    pub language: String,
+/// Zoom inn
+///
+/// # Arguments
+/// * `url` - Siden som vises; zoomen huskes da for verten
+#[tauri::command]
+pub fn zoom_in(
+    app: AppHandle,
+    url: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<SettingsInfo, CommandError> {
+    let host = url.as_deref().and_then(url_host);
+    let mut settings = state.settings.write();
+    settings.zoom_in(host.as_deref());
+
+    save_settings(&state, &mut settings)?;
+
+    let info = settings_info(&state, &settings);
+    broadcast_zoom(&state, &app, &info);
+    Ok(info)
 }
 
-impl From<&Settings> for SettingsInfo {
-    fn from(s: &Settings) -> Self {
-        Self {
-            theme: match s.theme {
-                Theme::Light => "light".to_string(),
-                Theme::Dark => "dark".to_string(),
-                Theme::System => "system".to_string(),
-            },
-            font_size: s.font_size,
-            zoom: s.zoom,
-            font_family: match s.font_family {
-                FontFamily::System => "system".to_string(),
-                FontFamily::Serif => "serif".to_string(),
-                FontFamily::SansSerif => "sans-serif".to_string(),
-                FontFamily::Mono => "mono".to_string(),
-            },
-            content_width: s.content_width,
-            show_line_numbers: s.show_line_numbers,
-            conversion_mode: match s.conversion_mode {
-                ConversionMode::MarkdownOnly => "markdown-only".to_string(),
-                ConversionMode::ConvertAll => "convert-all".to_string(),
-                ConversionMode::AskEverytime => "ask-everytime".to_string(),
-            },
-            readability_enabled: s.readability_enabled,
-            onboarding_completed: s.onboarding_completed,
-            language: s.language.clone(),
-        }
-    }
+/// Zoom ut
+///
+/// # Arguments
+/// * `url` - Siden som vises; zoomen huskes da for verten
+#[tauri::command]
+pub fn zoom_out(
+    app: AppHandle,
+    url: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<SettingsInfo, CommandError> {
+    let host = url.as_deref().and_then(url_host);
+    let mut settings = state.settings.write();
+    settings.zoom_out(host.as_deref());
+
+    save_settings(&state, &mut settings)?;
+
+    let info = settings_info(&state, &settings);
+    broadcast_zoom(&state, &app, &info);
+    Ok(info)
 }
 
-/// Hent gjeldende innstillinger
+/// Tilbakestill zoom
+///
+/// # Arguments
+/// * `url` - Siden som vises; vertens zoom fjernes før den globale nullstilles
 #[tauri::command]
-pub fn get_settings() -> SettingsInfo {
-    let settings = SETTINGS.lock().unwrap();
-    SettingsInfo::from(&*settings)
+pub fn zoom_reset(
+    app: AppHandle,
+    url: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<SettingsInfo, CommandError> {
+    let host = url.as_deref().and_then(url_host);
+    let mut settings = state.settings.write();
+    settings.zoom_reset(host.as_deref());
+
+    save_settings(&state, &mut settings)?;
+
+    let info = settings_info(&state, &settings);
+    broadcast_zoom(&state, &app, &info);
+    Ok(info)
 }
 
-/// Parametere for oppdatering av innstillinger
-#[derive(serde::Deserialize)]
-pub struct UpdateSettingsParams {
-    pub theme: Option<String>,
-    pub font_size: Option<u32>,
-    pub zoom: Option<u32>,
-    pub font_family: Option<String>,
-    pub content_width: Option<u32>,
-    pub show_line_numbers: Option<bool>,
-    pub conversion_mode: Option<String>,
-    pub readability_enabled: Option<bool>,
-    pub onboarding_completed: Option<bool>,
-    pub language: Option<String>,
+/// Husk zoom-nivået for et nettsted
+///
+/// # Arguments
+/// * `host` - Vertsnavn, eller `*.vert` for alle underdomener
+/// * `zoom` - Zoom i prosent, eller None for å bruke den globale zoomen
+///
+/// # Returns
+/// Oppdaterte innstillinger
+#[tauri::command]
+pub fn set_site_zoom(
+    app: AppHandle,
+    host: String,
+    zoom: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<SettingsInfo, CommandError> {
+    let mut settings = state.settings.write();
+    settings
+        .set_site_zoom(&host, zoom)
+        .map_err(|e| e.localize())?;
+
+    save_settings(&state, &mut settings)?;
+
+    let info = settings_info(&state, &settings);
+    broadcast_zoom(&state, &app, &info);
+    Ok(info)
 }
 
-/// Oppdater innstillinger
+/// Hent zoom-nivået som gjelder for en side
+///
+/// # Returns
+/// Nettstedets egen zoom, ellers den globale
 #[tauri::command]
-pub fn update_settings(params: UpdateSettingsParams) -> Result<SettingsInfo, String> {
-    let mut settings = SETTINGS.lock().unwrap();
+pub fn get_effective_zoom(url: String, state: State<'_, AppState>) -> u32 {
+    site_settings_for(&state, &url).zoom
+}
 
-    if let Some(t) = params.theme {
-        settings.theme = match t.as_str() {
-            "dark" => Theme::Dark,
-            "system" => Theme::System,
-            _ => Theme::Light,
-        };
-    }
+// ===== Eksport =====
+
+/// Resultatet av en eksport
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum ExportResult {
+    /// HTML-filen er skrevet
+    Saved {
+        path: String,
+        /// Bilder som ble bygget inn som data-URI
+        embedded_images: usize,
+        /// Bilder som ikke kunne hentes og ble utelatt
+        dropped_images: usize,
+    },
+    /// Frontend skal åpne utskriftsdialogen, der PDF velges som skriver
+    Print,
+}
 
-    if let Some(size) = params.font_size {
-        settings.font_size = size.clamp(70, 150);
+/// Eksporterer siden som vises til en fil som kan deles
+///
+/// HTML-eksporten er én fil med temaets stiler og bildene bygget inn, og
+/// refererer ikke til noe på nettet. PDF lages gjennom webviewens
+/// utskriftsdialog, så da ber kommandoen bare frontend om å åpne den.
+///
+/// # Arguments
+/// * `url` - Adressen til siden; den må være vist nylig
+/// * `format` - "html" eller "pdf"
+/// * `destination` - Filen HTML-en skrives til; brukes ikke for PDF
+///
+/// # Returns
+/// Hvor filen ble lagret, eller at utskriftsdialogen skal åpnes
+#[tauri::command]
+pub async fn export_page(
+    url: String,
+    format: ExportFormat,
+    destination: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<ExportResult, CommandError> {
+    if format == ExportFormat::Pdf {
+        return Ok(ExportResult::Print);
     }
+    let destination = destination.ok_or_else(|| locale::t("error.export_no_destination"))?;
+    let source = page_source(&state, &url)?;
+
+    let site = site_settings_for(&state, &url);
+    let rendered =
+        markdown::render_with_options(&source, &site_render_options(&state, Some(&url), &site));
+    let title = markdown::extract_title(&source).unwrap_or_else(|| url.clone());
+    let style = {
+        let settings = state.settings.read();
+        let css = settings.effective_css(&state.paths.user_css());
+        ExportStyle::from_settings(&settings, site.font_size, css)
+    };
 
-    if let Some(z) = params.zoom {
-        settings.zoom = z.clamp(50, 200);
+    // Bilder som er blokkert i innstillingene er allerede fjernet av rendreren
+    let sources = export::image_sources(&rendered.html);
+    let mut images = HashMap::new();
+    for src in &sources {
+        if let Some(uri) = embedded_image(&state, src).await {
+            images.insert(src.clone(), uri);
+        }
     }
 
-    if let Some(ff) = params.font_family {
-        settings.font_family = match ff.as_str() {
-            "serif" => FontFamily::Serif,
-            "sans-serif" => FontFamily::SansSerif,
-            "mono" => FontFamily::Mono,
-            _ => FontFamily::System,
-        };
-    }
+    let html = export::standalone_html(&title, &rendered.html, &style, &images);
+    storage::write_atomic(Path::new(&destination), &html)
+        .map_err(|e| locale::tf("error.export_write", &[&e]))?;
+    info!(
+        "Eksporterte {} til {}",
+        state.session.private_mode.log_url(&url),
+        destination
+    );
 
-    if let Some(width) = params.content_width {
-        settings.content_width = width.clamp(400, 1200);
-    }
+    Ok(ExportResult::Saved {
+        path: destination,
+        embedded_images: images.len(),
+        dropped_images: sources.len() - images.len(),
+    })
+}
 
-    if let Some(ln) = params.show_line_numbers {
-        settings.show_line_numbers = ln;
-    }
+/// Hent et bilde og gjør det om til en data-URI
+///
+/// # Returns
+/// None hvis bildet ikke kan hentes eller ikke er et bilde
+async fn embedded_image(state: &AppState, src: &str) -> Option<String> {
+    let parsed = url::Url::parse(src).ok()?;
+    let (bytes, content_type) = match parsed.scheme() {
+        "http" | "https" => match state.fetcher().fetch_bytes(src, "image/*").await {
+            Ok(image) => image,
+            Err(e) => {
+                debug!(
+                    "Utelater bilde {}: {}",
+                    state.session.private_mode.log_url(src),
+                    e
+                );
+                return None;
+            }
+        },
+        "file" => (fs::read(parsed.to_file_path().ok()?).ok()?, None),
+        _ => return None,
+    };
+    export::image_data_uri(&bytes, content_type.as_deref())
+}
 
-    if let Some(cm) = params.conversion_mode {
-        settings.conversion_mode = match cm.as_str() {
-            "markdown-only" => ConversionMode::MarkdownOnly,
-            "ask-everytime" => ConversionMode::AskEverytime,
-            _ => ConversionMode::ConvertAll,
-        };
-    }
+// ===== Vinduer =====
 
-    if let Some(re) = params.readability_enabled {
-        settings.readability_enabled = re;
-    }
+/// Navigeringstilstanden til vinduet som spør
+///
+/// # Returns
+/// Adressen som vises og navigeringsstablene, tomme hvis vinduet ikke har navigert
+#[tauri::command]
+pub fn get_window_state(
+    window: tauri::Window,
+    windows: tauri::State<'_, WindowRegistry>,
+) -> WindowState {
+    windows
+        .lock()
+        .get(window.label())
+        .cloned()
+        .unwrap_or_default()
+}
 
-    if let Some(oc) = params.onboarding_completed {
-        settings.onboarding_completed = oc;
-    }
+/// Gå ett steg tilbake i vinduet som spør
+///
+/// # Returns
+/// Adressen og siden fra minnet; er siden ikke der, må frontend hente
+/// adressen på nytt. None hvis vinduet ikke kan gå tilbake.
+#[tauri::command]
+pub fn navigate_back(
+    window: tauri::Window,
+    windows: tauri::State<'_, WindowRegistry>,
+) -> Option<HistoryStep> {
+    windows.lock().go_back(window.label())
+}
 
-    if let Some(lang) = params.language {
-        settings.language = lang;
-    }
+/// Gå ett steg frem i vinduet som spør; se `navigate_back`
+#[tauri::command]
+pub fn navigate_forward(
+    window: tauri::Window,
+    windows: tauri::State<'_, WindowRegistry>,
+) -> Option<HistoryStep> {
+    windows.lock().go_forward(window.label())
+}
 
-    // Lagre til fil
-    let path = settings::get_settings_path();
-    settings.save(&path).map_err(|e| e.to_string())?;
+/// Om vinduet som spør har en side å gå tilbake til
+#[tauri::command]
+pub fn can_go_back(window: tauri::Window, windows: tauri::State<'_, WindowRegistry>) -> bool {
+    windows
+        .lock()
+        .get(window.label())
+        .is_some_and(WindowState::can_go_back)
+}
 
-    Ok(SettingsInfo::from(&*settings))
+/// Om vinduet som spør har en side å gå frem til
+#[tauri::command]
+pub fn can_go_forward(window: tauri::Window, windows: tauri::State<'_, WindowRegistry>) -> bool {
+    windows
+        .lock()
+        .get(window.label())
+        .is_some_and(WindowState::can_go_forward)
 }
 
-/// Zoom inn
+/// Adressen et nytt vindu skal åpne ved oppstart
+///
+/// # Returns
+/// Adressen fra `open_url_in_new_window`, eller None for hovedvinduet og
+/// når den allerede er hentet
 #[tauri::command]
-pub fn zoom_in() -> Result<SettingsInfo, String> {
-    let mut settings = SETTINGS.lock().unwrap();
-    settings.zoom_in();
+pub fn take_initial_url(
+    window: tauri::Window,
+    windows: tauri::State<'_, WindowRegistry>,
+) -> Option<String> {
+    windows.lock().take_initial_url(window.label())
+}
 
-    let path = settings::get_settings_path();
-    settings.save(&path).map_err(|e| e.to_string())?;
+/// Åpner en adresse i et nytt vindu
+///
+/// Vinduet får sin egen navigeringstilstand og henter adressen med
+/// `take_initial_url` når det har startet.
+///
+/// # Arguments
+/// * `url` - Adressen som skal åpnes
+///
+/// # Returns
+/// Etiketten til det nye vinduet
+#[tauri::command]
+pub async fn open_url_in_new_window(
+    url: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+    windows: tauri::State<'_, WindowRegistry>,
+) -> Result<String, CommandError> {
+    let label = windows.lock().open(&url);
+    build_window(&app, &label).map_err(|e| {
+        windows.lock().remove(&label);
+        locale::tf("error.open_window", &[&e])
+    })?;
+    info!(
+        "Åpnet {} i vindu {}",
+        state.session.private_mode.log_url(&url),
+        label
+    );
+    Ok(label)
+}
 
-    Ok(SettingsInfo::from(&*settings))
+/// Lag et nettleservindu med samme størrelse som hovedvinduet i tauri.conf.json
+fn build_window(app: &AppHandle, label: &str) -> tauri::Result<tauri::WebviewWindow> {
+    tauri::WebviewWindowBuilder::new(app, label, tauri::WebviewUrl::default())
+        .title("Bare")
+        .inner_size(1024.0, 768.0)
+        .min_inner_size(400.0, 300.0)
+        .build()
 }
 
-/// Zoom ut
+/// Adressene andre programmer ba om å åpne før hovedvinduet var klart
+///
+/// Frontend i hovedvinduet kaller denne når den lytter på
+/// `navigate-request`; senere adresser sendes som hendelser.
+///
+/// # Returns
+/// Adressene, eldste først; tom for andre vinduer enn hovedvinduet
+#[tauri::command]
+pub fn take_deep_links(
+    window: tauri::Window,
+    deep_links: tauri::State<'_, DeepLinks>,
+) -> Vec<String> {
+    if window.label() != MAIN_WINDOW {
+        return Vec::new();
+    }
+    deep_links.lock().take_pending()
+}
+
+/// En fil eller adresse fra kommandolinjen, se `startup`
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum StartupTarget {
+    /// Åpnes som om den ble skrevet i adresselinjen
+    Open { url: String },
+    /// Kan ikke åpnes; feilsiden vises i stedet
+    Invalid {
+        argument: String,
+        page: Box<RenderedPage>,
+    },
+}
+
+impl From<StartupArgument> for StartupTarget {
+    fn from(argument: StartupArgument) -> Self {
+        match argument {
+            StartupArgument::Open(url) => Self::Open { url },
+            StartupArgument::Invalid(argument) => {
+                let page = error_page::error_page(
+                    error_page::ErrorPageKind::InvalidArgument,
+                    &argument,
+                    "",
+                );
+                Self::Invalid {
+                    argument,
+                    page: Box::new(page),
+                }
+            }
+        }
+    }
+}
+
+/// Filene og adressene Bare ble startet med
+///
+/// Frontend i hovedvinduet kaller denne ved oppstart og åpner målene i
+/// rekkefølge. Filer som åpnes fra Finder senere, sendes som
+/// `navigate-request`.
+///
+/// # Returns
+/// Målene i rekkefølge; tom for andre vinduer og etter første kall
 #[tauri::command]
-pub fn zoom_out() -> Result<SettingsInfo, String> {
-    let mut settings = SETTINGS.lock().unwrap();
-    settings.zoom_out();
-
-    let path = settings::get_settings_path();
-    settings.save(&path).map_err(|e| e.to_string())?;
+pub fn get_startup_targets(
+    window: tauri::Window,
+    targets: tauri::State<'_, StartupTargets>,
+) -> Vec<StartupTarget> {
+    if window.label() != MAIN_WINDOW {
+        return Vec::new();
+    }
+    targets.lock().take().into_iter().map(Into::into).collect()
+}
 
-    Ok(SettingsInfo::from(&*settings))
+/// Åpne filer fra operativsystemet, f.eks. fra Finder på macOS
+///
+/// Før frontend har startet legges filene i køen til `get_startup_targets`,
+/// senere sendes de til hovedvinduet.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub fn open_startup_files(app: &AppHandle, paths: impl IntoIterator<Item = PathBuf>) {
+    for path in paths {
+        info!(
+            "Fil fra systemet: {}",
+            app.state::<AppState>()
+                .session
+                .private_mode
+                .log_url(&path.to_string_lossy())
+        );
+        let target = StartupArgument::Open(path.to_string_lossy().into_owned());
+        if let Some(StartupArgument::Open(path)) = app.state::<StartupTargets>().lock().push(target)
+        {
+            let _ = app.emit_to(MAIN_WINDOW, NAVIGATE_REQUEST_EVENT, path);
+        }
+    }
 }
 
-/// Tilbakestill zoom
-#[tauri::command]
-pub fn zoom_reset() -> Result<SettingsInfo, String> {
-    let mut settings = SETTINGS.lock().unwrap();
-    settings.zoom_reset();
+/// Ta imot adresser fra et annet program, se `deep_link`
+///
+/// Adresser med andre protokoller enn gemini og gopher forkastes.
+///
+/// # Returns
+/// Adressene som kan sendes til hovedvinduet med en gang; resten venter i
+/// køen til frontend henter dem
+pub fn accept_deep_links(app: &AppHandle, urls: impl IntoIterator<Item = String>) -> Vec<String> {
+    let deep_links = app.state::<DeepLinks>();
+    let state = app.state::<AppState>();
+    let mut ready = Vec::new();
+    for url in urls {
+        let Some(url) = deep_link::validate(&url) else {
+            warn!(
+                "Avviste adresse fra et annet program: {}",
+                state.session.private_mode.log_url(&url)
+            );
+            continue;
+        };
+        info!(
+            "Adresse fra et annet program: {}",
+            state.session.private_mode.log_url(&url)
+        );
+        ready.extend(deep_links.lock().push(url));
+    }
+    ready
+}
 
-    let path = settings::get_settings_path();
-    settings.save(&path).map_err(|e| e.to_string())?;
+/// Åpne adresser fra et annet program i hovedvinduet
+///
+/// Hovedvinduet opprettes hvis det er lukket, og hentes frem uansett, også
+/// når Bare startes på nytt uten adresse.
+pub fn open_deep_links(app: &AppHandle, urls: impl IntoIterator<Item = String>) {
+    let window = match app.get_webview_window(MAIN_WINDOW) {
+        Some(window) => window,
+        None => {
+            app.state::<DeepLinks>().lock().window_closed();
+            match build_window(app, MAIN_WINDOW) {
+                Ok(window) => window,
+                Err(e) => {
+                    warn!("Kunne ikke åpne hovedvinduet: {}", e);
+                    return;
+                }
+            }
+        }
+    };
+    for url in accept_deep_links(app, urls) {
+        let _ = app.emit_to(MAIN_WINDOW, NAVIGATE_REQUEST_EVENT, url);
+    }
+    let _ = window.unminimize();
+    let _ = window.set_focus();
+}
 
-    Ok(SettingsInfo::from(&*settings))
+/// Glem tilstanden til et vindu som er lukket
+pub fn forget_window(window: &tauri::Window) {
+    let state = window.state::<AppState>();
+    state.session.file_watchers.lock().remove(window.label());
+    if window.label() == MAIN_WINDOW {
+        window.state::<DeepLinks>().lock().window_closed();
+    }
+    window
+        .state::<WindowRegistry>()
+        .lock()
+        .remove(window.label());
 }
 
 // ===== Gemini-commands =====
@@ -587,33 +4275,68 @@ pub fn zoom_reset() -> Result<SettingsInfo, String> {
 ///
 /// # Arguments
 /// * `url` - Gemini-URL å hente (gemini://...)
+/// * `link_text` - Teksten på lenken som ble klikket, se `url_safety`
 ///
 /// # Returns
 /// RenderedPage med konvertert gemtext→markdown→HTML, eller feilmelding
 #[tauri::command]
-pub async fn fetch_gemini(url: String, window: tauri::Window) -> Result<RenderedPage, String> {
-    let host = extract_host(&url);
+pub async fn fetch_gemini(
+    url: String,
+    link_text: Option<String>,
+    window: tauri::Window,
+    state: State<'_, AppState>,
+) -> Result<RenderedPage, CommandError> {
+    url_safety::confirm(&state.session, &url, link_text.as_deref())?;
+    let ticket = begin_fetch(&window);
+    let page = load_gemini(&state, url.clone(), &window)
+        .await
+        .map_err(|e| error_page::attach(e, &url));
+    finish_fetch(&window, &ticket, page)
+}
+
+/// Selve hentingen bak `fetch_gemini`
+async fn load_gemini<S: ProgressSink>(
+    state: &AppState,
+    url: String,
+    sink: &S,
+) -> Result<RenderedPage, CommandError> {
+    let host = url_safety::display_host(&url);
+    let progress = LoadProgress::new(sink, &host, state.session.private_mode.is_active());
 
     // Steg 1: Gemini TLS-handshake
-    let _ = window.emit(
-        "loading-status",
-        format!(
-            "{} Gemini TLS-handshake (port 1965) med {}...",
-            EMOJI_GEMINI, host
-        ),
+    progress.stage(
+        LoadStage::Connecting,
+        locale::tf("status.gemini_handshake", &[&EMOJI_GEMINI, &host]),
     );
-
-    let result = GEMINI_CLIENT.fetch(&url).await;
+    progress.advance(LoadStage::TlsHandshake);
+
+    let result = match take_prefetched(state, &url) {
+        Some(Prefetched::Gemini(response)) => Ok(response),
+        _ => state
+            .gemini()
+            .fetch(&url)
+            .await
+            .inspect(|response| progress.network(response.timing)),
+    };
 
     match result {
         Ok(response) => {
             let body = response.body.unwrap_or_default();
+            let site = site_settings_for(state, &response.final_url);
             let bytes = body.len();
 
             // Steg 2: Overfører data
-            let _ = window.emit(
-                "loading-status",
-                format!("Overfører data... ({} bytes)", bytes),
+            progress.transferring(
+                bytes as u64,
+                Some(bytes as u64),
+                locale::tf("status.transferring", &[&bytes]),
+            );
+            remember_raw(
+                state,
+                &response.final_url,
+                &response.raw,
+                Some(&response.meta),
+                &response.security,
             );
 
             // Sjekk om innholdet er gemtext
@@ -623,80 +4346,99 @@ pub async fn fetch_gemini(url: String, window: tauri::Window) -> Result<Rendered
 
             if is_gemtext {
                 // Steg 3: Konverterer gemtext
-                let _ = window.emit("loading-status", "Konverterer gemtext...");
-                let gemtext_result = gemtext::gemtext_to_markdown(&body);
+                progress.stage(
+                    LoadStage::Converting,
+                    locale::t("status.converting_gemtext"),
+                );
+                let gemtext_result =
+                    run_blocking("gemtext", move || gemtext::gemtext_to_markdown(&body)).await?;
 
                 // Steg 4: Rendrer markdown
-                let _ = window.emit("loading-status", "Rendrer markdown...");
-                let html = markdown::render(&gemtext_result.markdown);
+                progress.stage(LoadStage::Rendering, locale::t("status.rendering_markdown"));
+                let options = site_render_options(state, None, &site);
+                let (rendered, markdown_content) =
+                    render_blocking(gemtext_result.markdown, options).await?;
 
                 let title = gemtext_result
                     .title
-                    .or_else(|| markdown::extract_title(&gemtext_result.markdown));
+                    .or_else(|| markdown::extract_title(&markdown_content));
+                remember_source(state, &response.final_url, &markdown_content);
+                record_visit(state, &response.final_url, title.as_deref());
 
-                let _ = window.emit("loading-status", "Dokument: Ferdig");
+                let timing = progress.done(locale::t("status.done"));
 
                 Ok(RenderedPage {
-                    html,
+                    html: rendered.html,
                     title,
                     url: Some(response.final_url),
                     is_remote: true,
                     was_converted: true,
+                    tasks: rendered.tasks,
+                    skipped_diagrams: rendered.skipped_diagrams,
+                    truncated: rendered.truncated,
+                    security: Some(response.security),
+                    timing: Some(timing),
+                    is_archived: false,
+                    used_readability: false,
                 })
             } else if response.meta.starts_with("text/") {
-                // Ren tekst — vis som markdown-kodeblokk
-                let _ = window.emit("loading-status", "Rendrer tekst...");
-                let markdown_content = format!("```\n{}\n```", body);
-                let html = markdown::render(&markdown_content);
-
-                let _ = window.emit("loading-status", "Dokument: Ferdig");
+                // Ren tekst — vis etter innstillingen for ren tekst
+                progress.stage(LoadStage::Rendering, locale::t("status.rendering_text"));
+                let markdown_content =
+                    markdown::plain_text_to_markdown(&body, plain_text_style(state));
+                let options = site_render_options(state, None, &site);
+                let (rendered, markdown_content) =
+                    render_blocking(markdown_content, options).await?;
+                remember_source(state, &response.final_url, &markdown_content);
+                record_visit(state, &response.final_url, None);
+
+                let timing = progress.done(locale::t("status.done"));
 
                 Ok(RenderedPage {
-                    html,
+                    html: rendered.html,
                     title: None,
                     url: Some(response.final_url),
                     is_remote: true,
                     was_converted: true,
+                    tasks: rendered.tasks,
+                    skipped_diagrams: rendered.skipped_diagrams,
+                    truncated: rendered.truncated,
+                    security: Some(response.security),
+                    timing: Some(timing),
+                    is_archived: false,
+                    used_readability: false,
                 })
             } else {
-                // Ikke-tekstinnhold
-                Err(format!(
-                    "Innholdstypen '{}' støttes ikke. Bare kan kun vise tekst-basert innhold.",
-                    response.meta
-                ))
+                // Ikke-tekstinnhold lastes ned i stedet for å vises
+                progress.status(locale::t("status.awaiting_choice"));
+                Err(CommandError::download_prompt(&response.final_url))
             }
         }
         Err(GeminiError::InputRequired(prompt)) => {
-            let _ = window.emit("loading-status", "Venter på brukerinput...");
-            Err(format!("GEMINI_INPUT_PROMPT:{}", prompt))
+            progress.status(locale::t("status.awaiting_input"));
+            Err(CommandError::gemini_input(prompt, false, &url))
         }
         Err(GeminiError::SensitiveInputRequired(prompt)) => {
-            let _ = window.emit("loading-status", "Venter på brukerinput...");
-            Err(format!("GEMINI_SENSITIVE_INPUT_PROMPT:{}", prompt))
-        }
-        Err(GeminiError::CertificateChanged {
-            host,
-            old_fp,
-            new_fp,
-        }) => {
-            let _ = window.emit("loading-status", "Sertifikat-feil");
-            Err(format!(
-                "⚠️ Sertifikatadvarsel for {}!\n\n\
-                 Sertifikatet har endret seg siden forrige besøk.\n\
-                 Dette kan indikere et sikkerhetsbrudd.\n\n\
-                 Gammelt fingerprint: {}\nNytt fingerprint: {}",
-                host, old_fp, new_fp
-            ))
-        }
-        Err(GeminiError::ClientCertRequired) => {
-            let _ = window.emit("loading-status", "Klientsertifikat påkrevd");
-            Err("Denne Gemini-kapselen krever klientsertifikat.\n\
-                 Denne funksjonaliteten er ikke støttet ennå."
-                .to_string())
+            // Svaret sendes som spørring og skal aldri havne i historikken
+            state.history.lock().mark_sensitive(&url);
+            progress.status(locale::t("status.awaiting_input"));
+            Err(CommandError::gemini_input(prompt, true, &url))
+        }
+        Err(GeminiError::CrossSchemeRedirect(target)) => {
+            progress.status(locale::t("status.awaiting_choice"));
+            Err(CommandError::scheme_redirect(&target))
+        }
+        Err(e @ GeminiError::CertificateChanged { .. }) => {
+            progress.stage(LoadStage::Error, locale::t("status.certificate_error"));
+            Err(e.into())
+        }
+        Err(GeminiError::ClientCertRequired { host, meta }) => {
+            progress.status(locale::t("status.client_cert_required"));
+            Err(CommandError::client_cert_prompt(&url, &host, &meta))
         }
         Err(e) => {
-            let _ = window.emit("loading-status", "Feil under henting");
-            Err(e.to_string())
+            progress.stage(LoadStage::Error, locale::t("status.fetch_failed"));
+            Err(e.into())
         }
     }
 }
@@ -716,13 +4458,14 @@ pub async fn submit_gemini_input(
     url: String,
     input: String,
     window: tauri::Window,
-) -> Result<RenderedPage, String> {
+    state: State<'_, AppState>,
+) -> Result<RenderedPage, CommandError> {
     // Konstruer URL med input som query-parameter
-    let mut parsed = url::Url::parse(&url).map_err(|e| format!("Ugyldig URL: {}", e))?;
+    let mut parsed = url::Url::parse(&url).map_err(|e| locale::tf("error.invalid_url", &[&e]))?;
     parsed.set_query(Some(&input));
 
     let input_url = parsed.to_string();
-    fetch_gemini(input_url, window).await
+    fetch_gemini(input_url, None, window, state).await
 }
 
 /// Løser en relativ URL mot en Gemini base-URL
@@ -734,8 +4477,66 @@ pub async fn submit_gemini_input(
 /// # Returns
 /// Absolutt URL
 #[tauri::command]
-pub fn resolve_gemini_url(base_url: String, relative_url: String) -> Result<String, String> {
-    gemini::resolve_gemini_url(&base_url, &relative_url).map_err(|e| e.to_string())
+pub fn resolve_gemini_url(base_url: String, relative_url: String) -> Result<String, CommandError> {
+    gemini::resolve_gemini_url(&base_url, &relative_url).map_err(CommandError::from)
+}
+
+/// Alle Gemini-identiteter (klientsertifikater), uten nøklene
+#[tauri::command]
+pub fn list_gemini_identities(state: State<'_, AppState>) -> Vec<IdentityInfo> {
+    state.gemini().identities()
+}
+
+/// Lager en ny Gemini-identitet med et selvsignert sertifikat
+///
+/// # Arguments
+/// * `name` - Navnet på identiteten
+/// * `host` - Verten identiteten knyttes til med en gang, hvis noen
+///
+/// # Returns
+/// Den nye identiteten
+#[tauri::command]
+pub fn create_gemini_identity(
+    name: String,
+    host: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<IdentityInfo, CommandError> {
+    Ok(state
+        .gemini()
+        .create_identity(&name, host.as_deref())
+        .map_err(|e| e.localize())?)
+}
+
+/// Knytter en Gemini-identitet til en vert
+///
+/// # Arguments
+/// * `fingerprint` - Identiteten, eller None for å fjerne vertens identitet
+/// * `host` - Vertsnavn, "vert:port" eller en gemini://-adresse
+#[tauri::command]
+pub fn assign_identity_to_host(
+    fingerprint: Option<String>,
+    host: String,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    Ok(state
+        .gemini()
+        .assign_identity(fingerprint.as_deref(), &host)
+        .map_err(|e| e.localize())?)
+}
+
+/// Sletter en Gemini-identitet
+///
+/// # Arguments
+/// * `fingerprint` - Identiteten som skal slettes
+#[tauri::command]
+pub fn delete_gemini_identity(
+    fingerprint: String,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    Ok(state
+        .gemini()
+        .delete_identity(&fingerprint)
+        .map_err(|e| e.localize())?)
 }
 
 // ===== Gopher-commands =====
@@ -744,306 +4545,1658 @@ pub fn resolve_gemini_url(base_url: String, relative_url: String) -> Result<Stri
 ///
 /// # Arguments
 /// * `url` - Gopher-URL å hente (gopher://...)
+/// * `link_text` - Teksten på lenken som ble klikket, se `url_safety`
 ///
 /// # Returns
 /// RenderedPage med konvertert gophermap→markdown→HTML, eller feilmelding
 #[tauri::command]
-pub async fn fetch_gopher(url: String, window: tauri::Window) -> Result<RenderedPage, String> {
-    let host = extract_host(&url);
+pub async fn fetch_gopher(
+    url: String,
+    link_text: Option<String>,
+    window: tauri::Window,
+    state: State<'_, AppState>,
+) -> Result<RenderedPage, CommandError> {
+    url_safety::confirm(&state.session, &url, link_text.as_deref())?;
+    let ticket = begin_fetch(&window);
+    let page = load_gopher(&state, url.clone(), &window)
+        .await
+        .map_err(|e| error_page::attach(e, &url));
+    finish_fetch(&window, &ticket, page)
+}
+
+/// Selve hentingen bak `fetch_gopher`
+async fn load_gopher<S: ProgressSink>(
+    state: &AppState,
+    url: String,
+    sink: &S,
+) -> Result<RenderedPage, CommandError> {
+    let host = url_safety::display_host(&url);
+    let progress = LoadProgress::new(sink, &host, state.session.private_mode.is_active());
 
     // Steg 1: Kobler til
-    let _ = window.emit(
-        "loading-status",
-        format!("{} Gopher: Kobler til {} (port 70)...", EMOJI_GOPHER, host),
+    progress.stage(
+        LoadStage::Connecting,
+        locale::tf("status.gopher_connecting", &[&EMOJI_GOPHER, &host]),
     );
 
-    let result = gopher::fetch(&url).await;
+    // Binærfiler og bilder lastes ned i stedet for å vises
+    if gopher::parse_gopher_url(&url).is_ok_and(|parsed| parsed.item_type.is_download()) {
+        progress.status(locale::t("status.awaiting_choice"));
+        return Err(CommandError::download_prompt(&url));
+    }
+
+    let result = match take_prefetched(state, &url) {
+        Some(Prefetched::Gopher(response)) => Ok(response),
+        _ => state
+            .gopher()
+            .fetch(&url)
+            .await
+            .inspect(|response| progress.network(response.timing)),
+    };
 
     match result {
         Ok(response) => {
             let bytes = response.body.len();
 
             // Steg 2: Overfører data
-            let _ = window.emit(
-                "loading-status",
-                format!("Overfører data... ({} bytes)", bytes),
+            progress.transferring(
+                bytes as u64,
+                Some(bytes as u64),
+                locale::tf("status.transferring", &[&bytes]),
+            );
+            remember_raw(
+                state,
+                &response.final_url,
+                &response.raw,
+                gopher_source_type(&response.content_type),
+                &SecurityInfo::plain(Protocol::Gopher),
             );
 
             match response.content_type {
                 gopher::GopherContentType::Menu => {
                     // Steg 3: Konverterer gophermap
-                    let _ = window.emit("loading-status", "Konverterer gophermap...");
-                    let gophermap_result =
-                        gophermap::to_markdown(&response.items, &response.final_url);
+                    progress.stage(
+                        LoadStage::Converting,
+                        locale::t("status.converting_gophermap"),
+                    );
+                    let gophermap_result = gophermap::to_markdown(
+                        &response.items,
+                        &response.final_url,
+                        decorations(state),
+                    );
 
                     // Steg 4: Rendrer markdown
-                    let _ = window.emit("loading-status", "Rendrer markdown...");
-                    let html = markdown::render(&gophermap_result.markdown);
+                    progress.stage(LoadStage::Rendering, locale::t("status.rendering_markdown"));
+                    let rendered = markdown::render_with_options(
+                        &gophermap_result.markdown,
+                        &render_options(state, None),
+                    );
 
                     let title = gophermap_result
                         .title
                         .or_else(|| markdown::extract_title(&gophermap_result.markdown));
+                    remember_source(state, &response.final_url, &gophermap_result.markdown);
+                    record_visit(state, &response.final_url, title.as_deref());
+
+                    let timing = progress.done(locale::t("status.done"));
+
+                    Ok(RenderedPage {
+                        html: rendered.html,
+                        title,
+                        url: Some(response.final_url),
+                        is_remote: true,
+                        was_converted: true,
+                        tasks: rendered.tasks,
+                        skipped_diagrams: rendered.skipped_diagrams,
+                        truncated: rendered.truncated,
+                        security: Some(SecurityInfo::plain(Protocol::Gopher)),
+                        timing: Some(timing),
+                        is_archived: false,
+                        used_readability: false,
+                    })
+                }
+                gopher::GopherContentType::Text => {
+                    // Steg 3: Ren tekst — vis etter innstillingen for ren tekst,
+                    // så ASCII-kunst og kolonner beholdes
+                    progress.stage(LoadStage::Rendering, locale::t("status.rendering_text"));
+                    let markdown_content =
+                        markdown::plain_text_to_markdown(&response.body, plain_text_style(state));
+                    let rendered = markdown::render_with_options(
+                        &markdown_content,
+                        &render_options(state, None),
+                    );
+                    remember_source(state, &response.final_url, &markdown_content);
+                    record_visit(state, &response.final_url, None);
+
+                    let timing = progress.done(locale::t("status.done"));
+
+                    Ok(RenderedPage {
+                        html: rendered.html,
+                        title: None,
+                        url: Some(response.final_url),
+                        is_remote: true,
+                        was_converted: true,
+                        tasks: rendered.tasks,
+                        skipped_diagrams: rendered.skipped_diagrams,
+                        truncated: rendered.truncated,
+                        security: Some(SecurityInfo::plain(Protocol::Gopher)),
+                        timing: Some(timing),
+                        is_archived: false,
+                        used_readability: false,
+                    })
+                }
+                gopher::GopherContentType::Html => {
+                    // Konverter HTML til markdown
+                    progress.stage(LoadStage::Converting, locale::t("status.converting_html"));
+                    let site = site_settings_for(state, &response.final_url);
+                    let conversion_result = converter::html_to_markdown_with(
+                        &response.body,
+                        &conversion_options(state, &site),
+                    );
+
+                    progress.stage(LoadStage::Rendering, locale::t("status.rendering_markdown"));
+                    let rendered = markdown::render_with_options(
+                        &conversion_result.markdown,
+                        &site_render_options(state, None, &site),
+                    );
+                    let title = conversion_result
+                        .title
+                        .or_else(|| markdown::extract_title(&conversion_result.markdown));
+                    remember_source(state, &response.final_url, &conversion_result.markdown);
+                    record_visit(state, &response.final_url, title.as_deref());
+
+                    let timing = progress.done(locale::t("status.done"));
+
+                    Ok(RenderedPage {
+                        html: rendered.html,
+                        title,
+                        url: Some(response.final_url),
+                        is_remote: true,
+                        was_converted: true,
+                        tasks: rendered.tasks,
+                        skipped_diagrams: rendered.skipped_diagrams,
+                        truncated: rendered.truncated || conversion_result.truncated,
+                        security: Some(SecurityInfo::plain(Protocol::Gopher)),
+                        timing: Some(timing),
+                        is_archived: false,
+                        used_readability: conversion_result.used_readability,
+                    })
+                }
+                gopher::GopherContentType::Error => {
+                    // Vis feilmeny som markdown
+                    progress.stage(LoadStage::Converting, locale::t("status.converting_error"));
+                    let gophermap_result = gophermap::to_markdown(
+                        &response.items,
+                        &response.final_url,
+                        decorations(state),
+                    );
+                    let rendered = markdown::render_with_options(
+                        &gophermap_result.markdown,
+                        &render_options(state, None),
+                    );
+
+                    let timing = progress.done(locale::t("status.done"));
+
+                    Ok(RenderedPage {
+                        html: rendered.html,
+                        title: Some(locale::t("gopher.error_title").to_string()),
+                        url: Some(response.final_url),
+                        is_remote: true,
+                        was_converted: true,
+                        tasks: rendered.tasks,
+                        skipped_diagrams: rendered.skipped_diagrams,
+                        truncated: rendered.truncated,
+                        security: Some(SecurityInfo::plain(Protocol::Gopher)),
+                        timing: Some(timing),
+                        is_archived: false,
+                        used_readability: false,
+                    })
+                }
+                gopher::GopherContentType::Search => {
+                    // Bør ikke skje — search håndteres via SearchInputRequired error
+                    Err(CommandError::gopher_search(&url))
+                }
+            }
+        }
+        Err(gopher::GopherError::SearchInputRequired) => {
+            progress.status(locale::t("status.awaiting_search"));
+            Err(CommandError::gopher_search(&url))
+        }
+        Err(e) => {
+            progress.stage(LoadStage::Error, locale::t("status.fetch_failed"));
+            Err(e.into())
+        }
+    }
+}
+
+/// Utfører et Gopher-søk (type 7)
+///
+/// # Arguments
+/// * `url` - Gopher-søke-URL
+/// * `query` - Brukerens søkestreng
+///
+/// # Returns
+/// RenderedPage med søkeresultater
+#[tauri::command]
+pub async fn gopher_search(
+    url: String,
+    query: String,
+    window: tauri::Window,
+    state: State<'_, AppState>,
+) -> Result<RenderedPage, CommandError> {
+    let ticket = begin_fetch(&window);
+    let page = load_gopher_search(&state, url.clone(), query, &window)
+        .await
+        .map_err(|e| error_page::attach(e, &url));
+    finish_fetch(&window, &ticket, page)
+}
+
+/// Selve søket bak `gopher_search`
+async fn load_gopher_search<S: ProgressSink>(
+    state: &AppState,
+    url: String,
+    query: String,
+    sink: &S,
+) -> Result<RenderedPage, CommandError> {
+    let host = url_safety::display_host(&url);
+    let progress = LoadProgress::new(sink, &host, state.session.private_mode.is_active());
+
+    progress.stage(
+        LoadStage::Connecting,
+        locale::tf("status.gopher_searching", &[&EMOJI_GOPHER, &host]),
+    );
+
+    let result = state.gopher().search(&url, &query).await?;
+    progress.network(result.timing);
+
+    let bytes = result.body.len();
+    progress.transferring(
+        bytes as u64,
+        Some(bytes as u64),
+        locale::tf("status.transferring", &[&bytes]),
+    );
+    remember_raw(
+        state,
+        &result.final_url,
+        &result.raw,
+        gopher_source_type(&result.content_type),
+        &SecurityInfo::plain(Protocol::Gopher),
+    );
+
+    progress.stage(
+        LoadStage::Converting,
+        locale::t("status.converting_search_results"),
+    );
+    let gophermap_result =
+        gophermap::to_markdown(&result.items, &result.final_url, decorations(state));
+
+    progress.stage(LoadStage::Rendering, locale::t("status.rendering_markdown"));
+    let rendered =
+        markdown::render_with_options(&gophermap_result.markdown, &render_options(state, None));
+
+    let title = gophermap_result
+        .title
+        .or_else(|| Some(locale::tf("gopher.search_results", &[&query])));
+    remember_source(state, &result.final_url, &gophermap_result.markdown);
+    record_visit(state, &result.final_url, title.as_deref());
+
+    let timing = progress.done(locale::t("status.done"));
+
+    Ok(RenderedPage {
+        html: rendered.html,
+        title,
+        url: Some(result.final_url),
+        is_remote: true,
+        was_converted: true,
+        tasks: rendered.tasks,
+        skipped_diagrams: rendered.skipped_diagrams,
+        truncated: rendered.truncated,
+        security: Some(SecurityInfo::plain(Protocol::Gopher)),
+        timing: Some(timing),
+        is_archived: false,
+        used_readability: false,
+    })
+}
+
+/// Løser en relativ URL mot en Gopher base-URL
+///
+/// # Arguments
+/// * `base_url` - Nåværende Gopher-side sin URL
+/// * `relative_url` - Relativ URL som skal løses
+///
+/// # Returns
+/// Absolutt URL
+#[tauri::command]
+pub fn resolve_gopher_url(base_url: String, relative_url: String) -> Result<String, CommandError> {
+    gopher::resolve_gopher_url(&base_url, &relative_url).map_err(CommandError::from)
+}
+
+// ===== Historikk-commands =====
+
+/// Standard antall oppføringer per side i historikken
+const DEFAULT_HISTORY_LIMIT: usize = 50;
+
+/// Maks antall oppføringer per side i historikken
+const MAX_HISTORY_LIMIT: usize = 500;
+
+/// Legg til et besøk i historikken
+///
+/// Sider som hentes med `navigate` huskes av seg selv; denne er for sider
+/// frontend viser på andre måter. Er adressen allerede den siste i
+/// historikken, er besøket regnet med, og ingenting skjer. Ingenting lagres
+/// når historikken er slått av eller i privat modus.
+///
+/// # Arguments
+/// * `url` - Adressen som ble vist
+/// * `title` - Sidens tittel, hvis den har en
+#[tauri::command]
+pub fn add_history_entry(url: String, title: Option<String>, state: State<'_, AppState>) {
+    add_visit(&state, &url, title.as_deref());
+}
+
+/// Selve arbeidet bak `add_history_entry`
+fn add_visit(state: &AppState, url: &str, title: Option<&str>) {
+    if state.history.lock().is_latest(url) {
+        return;
+    }
+    record_visit(state, url, title);
+}
+
+/// Hent en side med historikk, nyeste først
+///
+/// Historikk som finnes kan leses selv om den er slått av.
+///
+/// # Arguments
+/// * `offset` - Antall oppføringer som hoppes over (standard 0)
+/// * `limit` - Maks antall oppføringer (standard 50, maks 500)
+#[tauri::command]
+pub fn get_history(
+    offset: Option<usize>,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> HistoryPage {
+    let limit = limit
+        .unwrap_or(DEFAULT_HISTORY_LIMIT)
+        .min(MAX_HISTORY_LIMIT);
+    state.history.lock().page(offset.unwrap_or(0), limit)
+}
 
-                    let _ = window.emit("loading-status", "Dokument: Ferdig");
+/// Søk i historikken etter tittel og adresse
+///
+/// Treffene har posisjonene som traff, slik at de kan utheves.
+///
+/// # Arguments
+/// * `query` - Søketeksten
+/// * `limit` - Maks antall treff (standard 20, maks 200)
+#[tauri::command]
+pub fn search_history(
+    query: String,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Vec<HistoryMatch> {
+    let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT).min(MAX_SEARCH_LIMIT);
+    state
+        .history
+        .lock()
+        .search(&query, limit, bookmarks::current_timestamp())
+}
+
+/// Slett én oppføring fra historikken
+///
+/// # Returns
+/// true hvis oppføringen fantes
+#[tauri::command]
+pub fn delete_history_entry(id: u64, state: State<'_, AppState>) -> Result<bool, CommandError> {
+    let deleted = state.history.lock().delete(id).map_err(|e| e.localize())?;
+    state.invalidate_autocomplete();
+    Ok(deleted)
+}
+
+/// Slett historikk fra et tidsrom
+///
+/// # Arguments
+/// * `range` - "last-hour", "last-day", "last-week", "last-month" eller "all"
+///
+/// # Returns
+/// Antall oppføringer som ble slettet
+#[tauri::command]
+pub fn clear_history(
+    range: HistoryRange,
+    state: State<'_, AppState>,
+) -> Result<usize, CommandError> {
+    let now = bookmarks::current_timestamp();
+    let removed = state
+        .history
+        .lock()
+        .clear(range, now)
+        .map_err(|e| e.localize())?;
+    state.invalidate_autocomplete();
+    Ok(removed)
+}
+
+/// Slett det Bare husker om surfingen
+///
+/// Bokmerker og innstillinger slettes aldri, bortsett fra overstyringer per
+/// nettsted når de er valgt.
+///
+/// # Arguments
+/// * `options` - Kategoriene som skal slettes
+///
+/// # Returns
+/// Ett resultat per valgt kategori, med eventuell feil
+#[tauri::command]
+pub fn clear_browsing_data(
+    app: AppHandle,
+    options: ClearOptions,
+    state: State<'_, AppState>,
+    windows: State<'_, WindowRegistry>,
+) -> Vec<ClearResult> {
+    let gemini = state.gemini();
+    let mut history = state.history.lock();
+    let mut windows = windows.lock();
+    let mut trusted_hosts = state.session.trusted_hosts.lock();
+    let mut recent_pages = state.session.recent_pages.lock();
+    let mut prefetched = state.session.prefetched.lock();
+    let mut rendered = state.session.render_cache.lock();
+    let mut recent_files = state.recent_files.lock();
+    let recent_files_path = state.paths.recent_files();
+    let mut settings = state.settings.write();
+
+    let mut results = {
+        let mut session = SessionState {
+            windows: &mut windows,
+            trusted_hosts: &mut trusted_hosts,
+        };
+        let mut page_caches = PageCaches {
+            recent_pages: &mut recent_pages,
+            prefetched: &mut prefetched,
+            rendered: &mut rendered,
+        };
+        let mut known_hosts = KnownHosts(&gemini);
+        let mut recent_files = RecentFileList {
+            files: &mut recent_files,
+            path: &recent_files_path,
+        };
+        let mut site_overrides = SiteOverrides(&mut settings);
+        browsing_data::clear(
+            &options,
+            &mut [
+                (ClearCategory::History, &mut *history),
+                (ClearCategory::SessionState, &mut session),
+                (ClearCategory::PageCache, &mut page_caches),
+                (ClearCategory::KnownHosts, &mut known_hosts),
+                (ClearCategory::RecentFiles, &mut recent_files),
+                (ClearCategory::SiteOverrides, &mut site_overrides),
+            ],
+        )
+    };
+    drop((
+        history,
+        windows,
+        trusted_hosts,
+        recent_pages,
+        prefetched,
+        rendered,
+        recent_files,
+    ));
+    state.invalidate_autocomplete();
+
+    let overrides_removed = results
+        .iter_mut()
+        .find(|r| r.category == ClearCategory::SiteOverrides && r.removed > 0);
+    if let Some(result) = overrides_removed {
+        match save_settings(&state, &mut settings) {
+            Ok(()) => {
+                let info = settings_info(&state, &settings);
+                drop(settings);
+                broadcast_settings(&state, &app, &info);
+            }
+            Err(e) => result.error = Some(e),
+        }
+    }
+    results
+}
+
+/// Antall sider som vises under "ofte besøkt" og "nylig besøkt" som standard
+const DEFAULT_TOP_SITES: usize = 8;
+
+/// Hent sidene med høyest frecency fra historikken
+///
+/// Tom når historikken er slått av. Sider brukeren har skjult tas ikke med.
+///
+/// # Arguments
+/// * `limit` - Maks antall sider (standard 8)
+#[tauri::command]
+pub fn get_top_sites(limit: Option<usize>, state: State<'_, AppState>) -> Vec<SiteVisits> {
+    let dismissed = {
+        let settings = state.settings.read();
+        if !settings.history_enabled {
+            return Vec::new();
+        }
+        settings.dismissed_top_sites.clone()
+    };
+    let limit = limit.unwrap_or(DEFAULT_TOP_SITES).min(MAX_HISTORY_LIMIT);
+    state
+        .history
+        .lock()
+        .top_sites(limit, bookmarks::current_timestamp(), &dismissed)
+}
+
+/// Skjul en side fra "ofte besøkt" og "nylig besøkt"
+///
+/// Valget lagres i innstillingene og overlever at historikken slettes.
+#[tauri::command]
+pub fn dismiss_top_site(
+    app: AppHandle,
+    url: String,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let mut settings = state.settings.write();
+    if !settings.dismiss_top_site(&url) {
+        return Ok(());
+    }
+    save_settings(&state, &mut settings)?;
+
+    let info = settings_info(&state, &settings);
+    drop(settings);
+    broadcast_settings(&state, &app, &info);
+    Ok(())
+}
+
+/// Seksjonene "ofte besøkt" og "nylig besøkt" for start- og bokmerkesiden
+///
+/// Tom når historikken er slått av eller ingen sider er besøkt.
+fn visited_sites_markdown(state: &AppState) -> String {
+    let dismissed = {
+        let settings = state.settings.read();
+        if !settings.history_enabled {
+            return String::new();
+        }
+        settings.dismissed_top_sites.clone()
+    };
+    let now = bookmarks::current_timestamp();
+    let history = state.history.lock();
+    let top = history.top_sites(DEFAULT_TOP_SITES, now, &dismissed);
+    // Nylig besøkte sider som allerede står under "ofte besøkt" gjentas ikke
+    let recent: Vec<SiteVisits> = history
+        .recent_sites(DEFAULT_TOP_SITES * 2, &dismissed)
+        .into_iter()
+        .filter(|site| !top.iter().any(|t| t.url == site.url))
+        .take(DEFAULT_TOP_SITES)
+        .collect();
+    drop(history);
+
+    let mut md = String::new();
+    for (key, sites) in [
+        ("top_sites.most_visited", &top),
+        ("top_sites.recently_visited", &recent),
+    ] {
+        if sites.is_empty() {
+            continue;
+        }
+        md.push_str(&format!(
+            "## {}
+
+",
+            locale::t(key)
+        ));
+        for site in sites.iter() {
+            let title = site.title.as_deref().unwrap_or(&site.url);
+            let title = title.replace('[', "\\[").replace(']', "\\]");
+            md.push_str(&format!("- [{}](<{}>)\n", title, site.url));
+        }
+        md.push('\n');
+    }
+    md
+}
+
+/// Adressen til bokmerkesiden
+const BOOKMARKS_PAGE_URL: &str = "bare://bookmarks";
+
+/// Adressen velkomstsiden har på navigeringsstabelen
+const HOME_PAGE_URL: &str = "bare://home";
+
+/// Returnerer alle bokmerker som en side
+///
+/// Festede bokmerker vises først, deretter én seksjon per mappe.
+#[tauri::command]
+pub fn get_bookmarks_page(window: tauri::Window, state: State<'_, AppState>) -> RenderedPage {
+    visit_location(&window, BOOKMARKS_PAGE_URL);
+    bookmarks_page(&state)
+}
 
-                    Ok(RenderedPage {
-                        html,
-                        title,
-                        url: Some(response.final_url),
-                        is_remote: true,
-                        was_converted: true,
-                    })
-                }
-                gopher::GopherContentType::Text => {
-                    // Steg 3: Rendrer tekst som markdown
-                    let _ = window.emit("loading-status", "Rendrer markdown...");
-                    let html = markdown::render(&response.body);
-                    let title = markdown::extract_title(&response.body);
+/// Selve bokmerkesiden bak `get_bookmarks_page`
+fn bookmarks_page(state: &AppState) -> RenderedPage {
+    let page_md = {
+        let store = state.bookmarks.lock();
+        bookmark_io::bookmarks_page_markdown(&store, bookmarks::current_timestamp())
+    };
+    // Besøkte sider vises rett under oppsummeringen øverst
+    let visited = visited_sites_markdown(state);
+    let page_md = match page_md.match_indices('\n').nth(2) {
+        Some((end, _)) if !visited.is_empty() => {
+            format!("{}\n{}{}", &page_md[..=end], visited, &page_md[end + 1..])
+        }
+        _ => page_md,
+    };
 
-                    let _ = window.emit("loading-status", "Dokument: Ferdig");
+    let rendered = markdown::render_with_options(&page_md, &render_options(state, None));
 
-                    Ok(RenderedPage {
-                        html,
-                        title,
-                        url: Some(response.final_url),
-                        is_remote: true,
-                        was_converted: false,
-                    })
-                }
-                gopher::GopherContentType::Html => {
-                    // Konverter HTML til markdown
-                    let _ = window.emit("loading-status", "Konverterer HTML til markdown...");
-                    let conversion_result = converter::html_to_markdown(&response.body);
+    RenderedPage {
+        html: rendered.html,
+        title: markdown::extract_title(&page_md),
+        url: Some(BOOKMARKS_PAGE_URL.to_string()),
+        is_remote: false,
+        was_converted: false,
+        tasks: rendered.tasks,
+        skipped_diagrams: rendered.skipped_diagrams,
+        truncated: rendered.truncated,
+        security: None,
+        timing: None,
+        is_archived: false,
+        used_readability: false,
+    }
+}
 
-                    let _ = window.emit("loading-status", "Rendrer markdown...");
-                    let html = markdown::render(&conversion_result.markdown);
-                    let title = conversion_result
-                        .title
-                        .or_else(|| markdown::extract_title(&conversion_result.markdown));
+/// Returnerer velkomst-innhold for når appen starter
+///
+/// Festede bokmerker vises øverst.
+#[tauri::command]
+pub fn get_welcome_content(state: State<'_, AppState>) -> RenderedPage {
+    welcome_page(&state)
+}
 
-                    let _ = window.emit("loading-status", "Dokument: Ferdig");
+/// Selve velkomstsiden bak `get_welcome_content`
+fn welcome_page(state: &AppState) -> RenderedPage {
+    let pinned = pinned_markdown(&state.bookmarks.lock().pinned()) + &visited_sites_markdown(state);
+    let welcome_md = welcome_markdown().replacen("\n\n", &format!("\n\n{}", pinned), 1);
 
-                    Ok(RenderedPage {
-                        html,
-                        title,
-                        url: Some(response.final_url),
-                        is_remote: true,
-                        was_converted: true,
-                    })
-                }
-                gopher::GopherContentType::Error => {
-                    // Vis feilmeny som markdown
-                    let _ = window.emit("loading-status", "Konverterer feilmelding...");
-                    let gophermap_result =
-                        gophermap::to_markdown(&response.items, &response.final_url);
-                    let html = markdown::render(&gophermap_result.markdown);
+    let rendered = markdown::render_with_options(&welcome_md, &render_options(state, None));
+    let title = markdown::extract_title(&welcome_md);
 
-                    let _ = window.emit("loading-status", "Dokument: Ferdig");
+    RenderedPage {
+        html: rendered.html,
+        title,
+        url: None,
+        is_remote: false,
+        was_converted: false,
+        tasks: rendered.tasks,
+        skipped_diagrams: rendered.skipped_diagrams,
+        truncated: rendered.truncated,
+        security: None,
+        timing: None,
+        is_archived: false,
+        used_readability: false,
+    }
+}
 
-                    Ok(RenderedPage {
-                        html,
-                        title: Some("Gopher-feil".to_string()),
-                        url: Some(response.final_url),
-                        is_remote: true,
-                        was_converted: true,
-                    })
+/// Returnerer startsiden fra innstillingene
+///
+/// Startsiden kan være velkomstsiden, bokmerkesiden, en URL eller en lokal
+/// fil. Kan den ikke lastes, vises velkomstsiden i stedet.
+#[tauri::command]
+pub async fn get_home_page(
+    window: tauri::Window,
+    state: State<'_, AppState>,
+) -> Result<RenderedPage, CommandError> {
+    let home = state.settings.read().home_page();
+    let result = match &home {
+        HomePage::Welcome => {
+            visit_location(&window, HOME_PAGE_URL);
+            return Ok(welcome_page(&state));
+        }
+        HomePage::Bookmarks => {
+            visit_location(&window, BOOKMARKS_PAGE_URL);
+            return Ok(bookmarks_page(&state));
+        }
+        HomePage::File(path) => open_file(
+            path.to_string_lossy().into_owned(),
+            window.clone(),
+            state.clone(),
+        ),
+        HomePage::Url(url) => {
+            // Brukeren har valgt startsiden selv, så adressen trenger ingen bekreftelse
+            url_safety::trust_host(&state.session, url);
+            match url.split_once("://").map(|(scheme, _)| scheme) {
+                Some("gemini") => {
+                    fetch_gemini(url.clone(), None, window.clone(), state.clone()).await
                 }
-                gopher::GopherContentType::Search => {
-                    // Bør ikke skje — search håndteres via SearchInputRequired error
-                    Err("GOPHER_SEARCH_PROMPT:".to_string() + &url)
+                Some("gopher") => {
+                    fetch_gopher(url.clone(), None, window.clone(), state.clone()).await
                 }
+                _ => fetch_url(url.clone(), None, None, None, window.clone(), state.clone()).await,
             }
         }
-        Err(gopher::GopherError::SearchInputRequired) => {
-            let _ = window.emit("loading-status", "Venter på søkeinput...");
-            Err(format!("GOPHER_SEARCH_PROMPT:{}", url))
-        }
-        Err(e) => {
-            let _ = window.emit("loading-status", "Feil under henting");
-            Err(e.to_string())
-        }
+    };
+    if matches!(result, Err(CommandError::Superseded { .. })) {
+        return result;
     }
+    if result.is_err() {
+        visit_location(&window, HOME_PAGE_URL);
+    }
+    Ok(welcome_on_error(&state, result, &home))
 }
 
-/// Utfører et Gopher-søk (type 7)
+/// Vis velkomstsiden hvis startsiden ikke kunne lastes
+fn welcome_on_error(
+    state: &AppState,
+    result: Result<RenderedPage, CommandError>,
+    home: &HomePage,
+) -> RenderedPage {
+    result.unwrap_or_else(|e| {
+        warn!("Kunne ikke laste startsiden {:?}: {}", home, e);
+        welcome_page(state)
+    })
+}
+
+/// Markdown-liste over festede bokmerker (tom hvis ingen er festet)
+fn pinned_markdown(pinned: &[&Bookmark]) -> String {
+    if pinned.is_empty() {
+        return String::new();
+    }
+
+    let mut md = format!("## {}\n\n", locale::t("welcome.pinned"));
+    for bookmark in pinned {
+        let title = bookmark.title.replace('[', "\\[").replace(']', "\\]");
+        md.push_str(&format!("- [{}](<{}>)\n", title, bookmark.url));
+    }
+    md.push('\n');
+    md
+}
+
+/// Markdown-kilden for velkomstsiden på brukerens språk
+fn welcome_markdown() -> String {
+    pages::source(Page::Welcome, locale::current()).unwrap_or_default()
+}
+
+/// Returnerer et steg i onboarding
 ///
 /// # Arguments
-/// * `url` - Gopher-søke-URL
-/// * `query` - Brukerens søkestreng
+/// * `step` - Steget, fra 1 til `pages::ONBOARDING_STEPS`
 ///
 /// # Returns
-/// RenderedPage med søkeresultater
+/// RenderedPage med steget på brukerens språk, eller feilmelding hvis
+/// steget ikke finnes
 #[tauri::command]
-pub async fn gopher_search(
-    url: String,
-    query: String,
-    window: tauri::Window,
-) -> Result<RenderedPage, String> {
-    let host = extract_host(&url);
+pub fn get_onboarding_page(
+    step: usize,
+    state: State<'_, AppState>,
+) -> Result<RenderedPage, CommandError> {
+    onboarding_page(&state, step)
+}
 
-    let _ = window.emit(
-        "loading-status",
-        format!("{} Gopher: Søker på {}...", EMOJI_GOPHER, host),
-    );
+/// Selve siden bak `get_onboarding_page`
+fn onboarding_page(state: &AppState, step: usize) -> Result<RenderedPage, CommandError> {
+    let source = pages::source(Page::Onboarding(step), locale::current())
+        .ok_or_else(|| locale::tf("error.onboarding_step", &[&step, &pages::ONBOARDING_STEPS]))?;
+    Ok(render_markdown_page(state, &source))
+}
 
-    let result = gopher::search(&url, &query)
-        .await
-        .map_err(|e| e.to_string())?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::ConfigPaths;
+    use crate::command_error::NetworkErrorKind;
+    use crate::test_support::http_server;
+
+    /// En tilstand med egne filer i en midlertidig mappe
+    fn test_state() -> (tempfile::TempDir, AppState) {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        let dir = tempfile::tempdir().unwrap();
+        let state = AppState::load(ConfigPaths::in_dir(dir.path()));
+        (dir, state)
+    }
 
-    let bytes = result.body.len();
-    let _ = window.emit(
-        "loading-status",
-        format!("Overfører data... ({} bytes)", bytes),
-    );
+    #[test]
+    fn test_local_file_links_and_images_stay_in_root() {
+        let (_dir, state) = test_state();
+        let files = tempfile::tempdir().unwrap();
+        let root = files.path().canonicalize().unwrap();
+        let notes = root.join("notater");
+        fs::create_dir_all(notes.join("img")).unwrap();
+        fs::write(notes.join("img").join("inne.png"), b"inne").unwrap();
+        fs::write(root.join("ute.png"), b"ute").unwrap();
+        fs::write(root.join("hemmelig.md"), "# Hemmelig").unwrap();
+        let readme = notes.join("readme.md");
+        fs::write(
+            &readme,
+            "[Design](design.md) [Utenfor](../hemmelig.md)\n\n\
+             ![Inne](img/inne.png) ![Ute](../ute.png)",
+        )
+        .unwrap();
+
+        // Uten nettsted er mappen filen ligger i grensen
+        let html = render_local_file(&state, &readme, LocalFormat::Markdown)
+            .unwrap()
+            .html;
+        assert!(html.contains("design.md\">Design</a>"));
+        assert!(html.contains("<a class=\"outside-root\">Utenfor</a>"));
+        assert!(!html.contains("hemmelig.md"));
+        assert!(html.contains("src=\"data:image/png;base64,aW5uZQ==\""));
+        assert!(html.contains("src=\"../ute.png\""));
+
+        // I et lokalt nettsted er roten til nettstedet grensen
+        fs::write(root.join(localsite::MANIFEST_FILE), "title = \"Notater\"\n").unwrap();
+        let html = render_local_file(&state, &readme, LocalFormat::Markdown)
+            .unwrap()
+            .html;
+        assert!(html.contains("hemmelig.md\">Utenfor</a>"));
+        assert!(html.contains("src=\"data:image/png;base64,dXRl\""));
+    }
 
-    let _ = window.emit("loading-status", "Konverterer søkeresultater...");
-    let gophermap_result = gophermap::to_markdown(&result.items, &result.final_url);
+    #[test]
+    fn test_local_document_per_format() {
+        let (_dir, state) = test_state();
+        let markdown = local_document(
+            "# Notater\n",
+            LocalFormat::Markdown,
+            plain_text_style(&state),
+        );
+        assert_eq!(markdown.markdown, "# Notater\n");
+        assert_eq!(markdown.title.as_deref(), Some("Notater"));
+        assert!(!markdown.was_converted);
+
+        let gemtext = local_document(
+            "# Kapsel\n=> annen.gmi Neste side\n",
+            LocalFormat::Gemtext,
+            PlainText::default(),
+        );
+        assert_eq!(gemtext.title.as_deref(), Some("Kapsel"));
+        assert!(gemtext.markdown.contains("](annen.gmi)"));
+        assert!(gemtext.was_converted);
+
+        let text = local_document(
+            "# ikke en tittel",
+            LocalFormat::Text,
+            PlainText::Preformatted,
+        );
+        assert_eq!(text.markdown, "```\n# ikke en tittel\n```\n");
+        assert_eq!(text.title, None);
 
-    let _ = window.emit("loading-status", "Rendrer markdown...");
-    let html = markdown::render(&gophermap_result.markdown);
+        let text = local_document("*a*\n\nb", LocalFormat::Text, PlainText::Paragraphs);
+        assert_eq!(text.markdown, "\\*a\\*\n\nb");
+    }
 
-    let title = gophermap_result
-        .title
-        .or_else(|| Some(format!("Søkeresultater: {}", query)));
+    #[test]
+    fn test_save_settings_rebuilds_clients_after_external_edit() {
+        let (_dir, state) = test_state();
+        let path = state.paths.settings();
+        save_settings(&state, &mut state.settings.write()).unwrap();
+        let fetcher = state.fetcher();
+
+        // En lagring uten endringer utenfra beholder klientene
+        save_settings(&state, &mut state.settings.write()).unwrap();
+        assert!(Arc::ptr_eq(&fetcher, &state.fetcher()));
+
+        let mut disk: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        disk["network"]["timeout_seconds"] = 5.into();
+        fs::write(&path, disk.to_string()).unwrap();
+
+        let mut settings = state.settings.write();
+        settings.zoom = 120;
+        save_settings(&state, &mut settings).unwrap();
+        assert_eq!(settings.network.timeout_seconds, 5);
+        assert_eq!(settings.zoom, 120);
+        drop(settings);
+        assert!(!Arc::ptr_eq(&fetcher, &state.fetcher()));
+    }
 
-    let _ = window.emit("loading-status", "Dokument: Ferdig");
+    #[test]
+    fn test_settings_changed_payload() {
+        let settings =
+            Settings::from_json(serde_json::json!({ "zoom": 120, "theme": "dark" })).unwrap();
+
+        let payload = serde_json::to_value(SettingsInfo::new(&settings, false)).unwrap();
+        assert_eq!(payload["theme"], "dark");
+        assert_eq!(payload["zoom"], 120);
+        assert_eq!(payload["homepage"], "welcome");
+        assert!(payload["network"]["timeout_seconds"].is_u64());
+
+        // Frontend må kunne lese nyttelasten tilbake uten tap
+        let back: SettingsInfo = serde_json::from_value(payload).unwrap();
+        assert_eq!(back.zoom, 120);
+    }
 
-    Ok(RenderedPage {
-        html,
-        title,
-        url: Some(result.final_url),
-        is_remote: true,
-        was_converted: true,
-    })
-}
+    fn warnings_for(params: serde_json::Value) -> (Settings, Vec<SettingsWarning>) {
+        let mut settings = Settings::default();
+        let warnings = apply_settings_params(&mut settings, params).unwrap();
+        (settings, warnings)
+    }
 
-/// Løser en relativ URL mot en Gopher base-URL
-///
-/// # Arguments
-/// * `base_url` - Nåværende Gopher-side sin URL
-/// * `relative_url` - Relativ URL som skal løses
-///
-/// # Returns
-/// Absolutt URL
-#[tauri::command]
-pub fn resolve_gopher_url(base_url: String, relative_url: String) -> Result<String, String> {
-    gopher::resolve_gopher_url(&base_url, &relative_url).map_err(|e| e.to_string())
-}
+    #[test]
+    fn test_update_settings_clamped_warning() {
+        let (settings, warnings) = warnings_for(serde_json::json!({
+            "font_size": 500,
+            "zoom": 120,
+            "typography": { "line_height": 999, "justify_text": true },
+        }));
+        assert_eq!(settings.font_size, 150);
+        assert_eq!(settings.zoom, 120);
+        assert_eq!(settings.typography.line_height, 220);
+        assert!(settings.typography.justify_text);
+        assert_eq!(
+            warnings,
+            vec![
+                SettingsWarning::new("font_size", 500, WarningAction::Clamped),
+                SettingsWarning::new("typography.line_height", 999, WarningAction::Clamped),
+            ]
+        );
+    }
 
-/// Returnerer velkomst-innhold for når appen starter
-#[tauri::command]
-pub fn get_welcome_content() -> RenderedPage {
-    let welcome_md = r#"# Velkommen til Bare
+    #[test]
+    fn test_update_settings_ignored_warning() {
+        let (settings, warnings) =
+            warnings_for(serde_json::json!({ "them": "drak", "font_size": 120 }));
+        assert_eq!(settings.font_size, 120);
+        assert_eq!(
+            warnings,
+            vec![SettingsWarning::new("them", "drak", WarningAction::Ignored)]
+        );
+    }
 
-> "The internet doesn't have to be heavy."
+    #[test]
+    fn test_update_settings_invalid_warning() {
+        let (settings, warnings) = warnings_for(serde_json::json!({
+            "theme": "drak",
+            "conversion_mode": "alt",
+            "diagram_languages": ["mermaid", "c++"],
+        }));
+        // Ugyldige valg lar innstillingen være som den var
+        assert_eq!(settings.theme, Theme::Light);
+        assert_eq!(settings.conversion.http, ConversionMode::default());
+        assert_eq!(settings.diagram_languages, vec!["mermaid".to_string()]);
+        assert_eq!(
+            warnings,
+            vec![
+                SettingsWarning::new("theme", "drak", WarningAction::Invalid),
+                SettingsWarning::new("conversion_mode", "alt", WarningAction::Invalid),
+                SettingsWarning::new("diagram_languages", "c++", WarningAction::Invalid),
+            ]
+        );
+    }
 
-**Bare** er en eksperimentell markdown-nettleser med fokus på:
+    #[test]
+    fn test_update_settings_type_error_names_field() {
+        let mut settings = Settings::default();
+        let error =
+            apply_settings_params(&mut settings, serde_json::json!({ "font_size": "stor" }))
+                .unwrap_err();
+        assert_eq!(error.field.as_deref(), Some("font_size"));
+        assert!(error.message.contains("font_size"));
+
+        let error = apply_settings_params(
+            &mut settings,
+            serde_json::json!({ "network": { "max_redirects": -1 } }),
+        )
+        .unwrap_err();
+        assert_eq!(error.field.as_deref(), Some("network"));
+
+        let error = apply_settings_params(&mut settings, serde_json::json!([1, 2])).unwrap_err();
+        assert_eq!(error.field, None);
+    }
 
-- **Personvern** — Ingen cookies, ingen JavaScript, ingen sporing
-- **Hastighet** — Lynrask lasting av rent innhold
-- **Fokus** — Innholdet er i sentrum, ikke designet
+    #[test]
+    fn test_settings_update_envelope() {
+        let (settings, warnings) = warnings_for(serde_json::json!({ "zoom": 10 }));
+        let update = SettingsUpdate {
+            settings: SettingsInfo::new(&settings, false),
+            warnings,
+        };
+        let payload = serde_json::to_value(&update).unwrap();
+        assert_eq!(payload["settings"]["zoom"], 50);
+        assert_eq!(
+            payload["warnings"],
+            serde_json::json!([{ "field": "zoom", "value": 10, "action": "clamped" }])
+        );
 
-## Kom i gang
+        let error = serde_json::to_value(UpdateSettingsError::for_field("zoom", "x".into()));
+        assert_eq!(error.unwrap()["field"], "zoom");
+    }
 
-### Åpne en lokal fil
+    #[test]
+    fn test_effective_conversion_mode_precedence() {
+        let mut settings = Settings::from_json(serde_json::json!({
+            "conversion": {
+                "http": "ask-everytime",
+                "rules": [
+                    { "pattern": "*.intra.example", "mode": "convert-all" },
+                    { "pattern": "wiki.intra.example", "mode": "markdown-only" },
+                ],
+            },
+        }))
+        .unwrap();
 
-Klikk på **Åpne fil** i verktøylinjen for å velge en `.md`-fil fra datamaskinen din.
+        let mode = |url: &str, settings: &Settings| effective_conversion_mode(url, settings);
+        assert_eq!(
+            mode("https://wiki.intra.example/side", &settings),
+            ConversionMode::MarkdownOnly
+        );
+        assert_eq!(
+            mode("https://mail.intra.example/", &settings),
+            ConversionMode::ConvertAll
+        );
+        assert_eq!(
+            mode("https://example.org/", &settings),
+            ConversionMode::AskEverytime
+        );
+        assert_eq!(mode("ikke en url", &settings), ConversionMode::AskEverytime);
+
+        // En overstyring for nettstedet går foran regelen
+        settings
+            .set_site_override(
+                "wiki.intra.example",
+                SiteOverride {
+                    conversion_mode: Some(ConversionMode::ConvertAll),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            mode("https://wiki.intra.example/side", &settings),
+            ConversionMode::ConvertAll
+        );
+    }
 
-### Skriv inn en URL
+    #[test]
+    fn test_render_markdown_command() {
+        let (_dir, state) = test_state();
+        let result = render_markdown_page(&state, "# Test");
+        assert!(result.html.contains("<h1 id=\"test\">"));
+        assert_eq!(result.title, Some("Test".to_string()));
+        assert!(!result.is_remote);
+        assert!(result.tasks.is_none());
+    }
 
-Skriv inn en URL til en markdown-fil i adressefeltet og trykk Enter.
+    #[test]
+    fn test_render_content_per_format() {
+        let (_dir, state) = test_state();
+        let page = render_pasted(
+            &state,
+            "# Notat\n\nTekst".into(),
+            ContentFormat::Markdown,
+            None,
+        )
+        .unwrap();
+        assert_eq!(page.title.as_deref(), Some("Notat"));
+        assert!(page.html.contains("<p>Tekst</p>"));
+        assert!(page.url.is_none());
+        assert!(!page.was_converted);
+
+        let page = render_pasted(
+            &state,
+            "# Kapsel\n=> gemini://a.example/ Lenke\n".into(),
+            ContentFormat::Gemtext,
+            None,
+        )
+        .unwrap();
+        assert_eq!(page.title.as_deref(), Some("Kapsel"));
+        assert!(page.html.contains("href=\"gemini://a.example/\""));
+        assert!(page.was_converted);
+
+        let page = render_pasted(&state,
+            "<html><head><title>Side</title></head><body><h1>Side</h1><p>Avsnitt med <em>vekt</em>.</p></body></html>".into(),
+            ContentFormat::Html,
+            None,
+        )
+        .unwrap();
+        assert_eq!(page.title.as_deref(), Some("Side"));
+        assert!(page.html.contains("<em>vekt</em>"));
+        assert!(page.was_converted);
+    }
 
-### Gemini-protokollen
+    #[test]
+    fn test_add_history_entry_ignores_repeat_of_latest() {
+        let (_dir, state) = test_state();
+        state.settings.write().history_enabled = true;
+
+        // Siden er allerede husket av lastingen
+        record_visit(&state, "https://example.com/", Some("Eksempel"));
+        add_visit(&state, "https://example.com/", Some("Eksempel"));
+        let entries = state.history.lock().page(0, 10).entries;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].visits, 1);
+
+        add_visit(&state, "https://example.org/", None);
+        add_visit(&state, "https://example.com/", None);
+        let entries = state.history.lock().page(0, 10).entries;
+        assert_eq!(entries.len(), 3);
+        assert!(entries.iter().all(|entry| entry.visits == 1));
+    }
 
-Bare støtter **Gemini-protokollen** — et enkelt og personvernvennlig alternativ til HTTP.
+    #[test]
+    fn test_render_cache_belongs_to_state() {
+        let (_dir, state) = test_state();
+        let (_other_dir, other) = test_state();
+        let content = "# Side\n\nAvsnitt.";
+
+        render_markdown_page(&state, content);
+        render_markdown_page(&state, content);
+        render_markdown_page(&other, content);
+
+        let stats = state.session.render_cache.lock().stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 1));
+        let stats = other.session.render_cache.lock().stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (0, 1, 1));
+    }
 
-Prøv en av disse adressene:
+    #[test]
+    fn test_html_conversion_honors_readability_setting() {
+        let (_dir, state) = test_state();
+        let html = "<html><body><nav>Meny</nav><article><h1>Artikkel</h1>\
+            <p>Det viktige innholdet står her, med nok tekst til at leservisningen finner artikkelen.</p></article><footer>Bunntekst</footer></body></html>";
+        let page = render_pasted(&state, html.into(), ContentFormat::Html, None).unwrap();
+        assert!(page.used_readability);
+        assert!(page.html.contains("Det viktige innholdet"));
+        assert!(!page.html.contains("Meny"));
+
+        state.settings.write().readability_enabled = false;
+        let page = render_pasted(&state, html.into(), ContentFormat::Html, None).unwrap();
+        assert!(!page.used_readability);
+        assert!(page.html.contains("Det viktige innholdet"));
+        assert!(page.html.contains("Meny"));
+        assert!(page.html.contains("Bunntekst"));
+    }
 
-- [gemini://geminiprotocol.net/](gemini://geminiprotocol.net/)
-- [gemini://gemini.circumlunar.space/](gemini://gemini.circumlunar.space/)
-- [gemini://geminiquickst.art/](gemini://geminiquickst.art/)
+    #[test]
+    fn test_render_content_resolves_against_base() {
+        let (_dir, state) = test_state();
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("notater").join("a.md");
+        let page = render_pasted(
+            &state,
+            "[Neste](b.md)".into(),
+            ContentFormat::Markdown,
+            Some(base.display().to_string()),
+        )
+        .unwrap();
+        let expected = url::Url::from_file_path(dir.path().join("notater/b.md")).unwrap();
+        assert!(page.html.contains(&format!("href=\"{}\"", expected)));
+        assert_eq!(
+            page.url,
+            Some(url::Url::from_file_path(&base).unwrap().to_string())
+        );
+        assert!(!page.is_remote);
+
+        let page = render_pasted(
+            &state,
+            "<p><img src=\"bilder/kart.png\" alt=\"Kart\"></p>".into(),
+            ContentFormat::Html,
+            Some("https://a.example/guide/side.html".into()),
+        )
+        .unwrap();
+        assert!(page
+            .html
+            .contains("src=\"https://a.example/guide/bilder/kart.png\""));
+        assert_eq!(
+            page.url.as_deref(),
+            Some("https://a.example/guide/side.html")
+        );
+        assert!(page.is_remote);
+    }
 
-Gemini-sider bruker et enkelt format kalt gemtext, som automatisk konverteres til markdown.
+    #[test]
+    fn test_render_content_size_cap() {
+        let (_dir, state) = test_state();
+        let max = state.settings.read().open_policy().max_size as usize;
+        let result = render_pasted(&state, "a".repeat(max + 1), ContentFormat::Markdown, None);
+        assert!(result.is_err());
+    }
 
-## Eksempel på markdown
+    #[test]
+    fn test_render_markdown_task_summary() {
+        let (_dir, state) = test_state();
+        let result = render_markdown_page(&state, "- [x] En\n- [ ] To\n- [x] Tre");
+        assert_eq!(
+            result.tasks,
+            Some(markdown::TaskSummary { total: 3, done: 2 })
+        );
+    }
 
-Her er noen eksempler på hva Bare kan vise:
+    #[tokio::test]
+    async fn test_page_load_progress_stages_in_order() {
+        let (_dir, state) = test_state();
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        let addr = http_server(
+            "<html><head><title>Fremdrift</title></head><body><p>Hei</p></body></html>",
+        )
+        .await;
+        let recorder = crate::progress::tests::Recorder::default();
+
+        let page = load_converted(&state, format!("http://{}/side.html", addr), &recorder)
+            .await
+            .unwrap();
+        assert_eq!(page.title.as_deref(), Some("Fremdrift"));
+
+        assert_eq!(
+            recorder.stages(),
+            vec![
+                LoadStage::Resolving,
+                LoadStage::Connecting,
+                LoadStage::Transferring,
+                LoadStage::Converting,
+                LoadStage::Rendering,
+                LoadStage::Done,
+            ]
+        );
+        let progress = recorder.progress.lock().unwrap();
+        assert!(progress.iter().all(|p| p.host == addr.to_string()));
+        let transferred = &progress[2];
+        assert!(transferred.bytes.is_some_and(|bytes| bytes > 0));
+        assert_eq!(transferred.bytes, transferred.total);
+    }
 
-### Tekst-formatering
+    /// Sjekk at fasene til sammen blir omtrent hele tiden
+    fn assert_phases_sum_to_total(timing: &TimingInfo) {
+        let phases = [
+            timing.connect_ms,
+            timing.tls_ms,
+            timing.transfer_ms,
+            timing.convert_ms,
+            timing.render_ms,
+        ];
+        let sum: u64 = phases.iter().flatten().sum();
+        assert!(
+            sum <= timing.total_ms && timing.total_ms - sum < 25,
+            "{:?}",
+            timing
+        );
+    }
 
-- **Fet tekst** for viktige ting
-- *Kursiv tekst* for vektlegging
-- ~~Gjennomstreket~~ for ting som ikke gjelder lenger
-- `Kode` for tekniske termer
+    #[tokio::test]
+    async fn test_gopher_timing_has_no_tls() {
+        use crate::test_support::{GopherServer, Reply};
+
+        let (_dir, state) = test_state();
+        let server = GopherServer::start(|_| {
+            // Serveren bruker litt tid, så overføringen blir målbar
+            std::thread::sleep(Duration::from_millis(50));
+            Reply::text("iVelkommen\tfake\t(NULL)\t0\r\n.\r\n")
+        })
+        .await;
+        let recorder = crate::progress::tests::Recorder::default();
+
+        let page = load_gopher(&state, server.url("/"), &recorder)
+            .await
+            .unwrap();
+        let timing = page.timing.unwrap();
+        assert_eq!(timing.tls_ms, None);
+        assert!(timing.connect_ms.is_some());
+        assert!(timing.transfer_ms.is_some_and(|ms| ms >= 50));
+        assert!(timing.render_ms.is_some());
+        assert_phases_sum_to_total(&timing);
+
+        // Done-steget har med de samme tidene
+        let progress = recorder.progress.lock().unwrap();
+        let done = progress.last().unwrap();
+        assert_eq!(done.stage, LoadStage::Done);
+        assert_eq!(done.timing, Some(timing));
+        assert!(progress[..progress.len() - 1]
+            .iter()
+            .all(|p| p.timing.is_none()));
+    }
 
-### Lister
+    #[test]
+    fn test_navigation_target() {
+        let settings = Settings::default();
+        let target = |input: &str| navigation_target(input, &settings);
 
-1. Nummererte lister
-2. Fungerer også
-3. Automatisk nummerering
+        assert_eq!(
+            target("https://a.example/side.md").unwrap(),
+            NavigationTarget::Http("https://a.example/side.md".into())
+        );
+        assert_eq!(
+            target("gemini://a.example/").unwrap(),
+            NavigationTarget::Gemini("gemini://a.example/".into())
+        );
+        assert_eq!(
+            target("gopher://a.example/1/").unwrap(),
+            NavigationTarget::Gopher("gopher://a.example/1/".into())
+        );
+        assert_eq!(
+            target("/home/a/notater.md").unwrap(),
+            NavigationTarget::File("/home/a/notater.md".into())
+        );
+        #[cfg(unix)]
+        assert_eq!(
+            target("file:///home/a/mine%20notater.md").unwrap(),
+            NavigationTarget::File("/home/a/mine notater.md".into())
+        );
 
-### Oppgavelister
+        // Uten protokoll: fra innstillingene, eller fra porten
+        assert_eq!(
+            target(" a.example/side ").unwrap(),
+            NavigationTarget::Http("https://a.example/side".into())
+        );
+        assert_eq!(
+            target("a.example:1965").unwrap(),
+            NavigationTarget::Gemini("gemini://a.example:1965".into())
+        );
+    }
 
-- [x] Sett opp Tauri-prosjekt
-- [x] Implementer markdown-rendering
-- [ ] Legg til nettverksstøtte
-- [ ] Lag HTML-til-markdown konvertering
+    #[test]
+    fn test_navigation_target_errors() {
+        let settings = Settings::default();
+        let error = navigation_target("ftp://a.example/fil", &settings).unwrap_err();
+        assert!(matches!(
+            error,
+            CommandError::Network {
+                kind: NetworkErrorKind::UnsupportedScheme,
+                ..
+            }
+        ));
+        assert!(error.message().contains("ftp"));
+
+        for input in ["bare ord", "https://", ""] {
+            assert!(
+                matches!(
+                    navigation_target(input, &settings),
+                    Err(CommandError::Network {
+                        kind: NetworkErrorKind::InvalidUrl,
+                        ..
+                    })
+                ),
+                "{:?}",
+                input
+            );
+        }
+    }
 
-### Tabeller
+    #[tokio::test]
+    async fn test_gemini_redirect_to_other_scheme_is_surfaced() {
+        use crate::test_support::{GeminiServer, Reply};
+
+        let (_dir, state) = test_state();
+        let server = GeminiServer::start(|_| Reply::text("30 https://a.example/ny\r\n")).await;
+        let recorder = crate::progress::tests::Recorder::default();
+
+        let error = load_gemini(&state, server.url("/gammel"), &recorder)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            &error,
+            CommandError::SchemeRedirect { url, .. } if url == "https://a.example/ny"
+        ));
+        // Et spørsmål til brukeren er ingen feilside
+        assert_eq!(error_page::ErrorPageKind::of(&error), None);
+    }
 
-| Funksjon | Status |
-|----------|--------|
-| Markdown-rendering | ✅ Ferdig |
-| Lokale filer | ✅ Ferdig |
-| Nettverksforespørsler | 🚧 Kommer |
+    #[tokio::test]
+    async fn test_gopher_text_is_preformatted() {
+        use crate::test_support::{GopherServer, Reply};
+
+        let (_dir, state) = test_state();
+        let server = GopherServer::start(|_| {
+            Reply::text("# ikke en tittel\r\n  /\\_/\\\r\n *katt*\r\n.\r\n")
+        })
+        .await;
+        let recorder = crate::progress::tests::Recorder::default();
+
+        let page = load_gopher(&state, server.url("/0/katt.txt"), &recorder)
+            .await
+            .unwrap();
+        assert_eq!(page.title, None);
+        assert!(page.was_converted);
+        assert!(page.html.contains("<pre"), "{}", page.html);
+        assert!(page.html.contains("*katt*"), "{}", page.html);
+        assert!(!page.html.contains("<h1"), "{}", page.html);
+    }
 
-### Kodeblokker
+    #[tokio::test]
+    async fn test_gemini_timing_includes_tls_and_conversion() {
+        use crate::test_support::{GeminiServer, Reply};
+
+        let (_dir, state) = test_state();
+        let server = GeminiServer::start(|_| {
+            std::thread::sleep(Duration::from_millis(50));
+            Reply::text("20 text/gemini\r\n# Kapsel\n=> /a Lenke\n")
+        })
+        .await;
+        let recorder = crate::progress::tests::Recorder::default();
+
+        let page = load_gemini(&state, server.url("/"), &recorder)
+            .await
+            .unwrap();
+        let timing = page.timing.unwrap();
+        assert!(timing.connect_ms.is_some());
+        assert!(timing.tls_ms.is_some());
+        assert!(timing.transfer_ms.is_some_and(|ms| ms >= 50));
+        assert!(timing.convert_ms.is_some());
+        assert!(timing.render_ms.is_some());
+        assert_phases_sum_to_total(&timing);
+    }
 
-```rust
-fn main() {
-    println!("Hello, Bare!");
-}
-```
+    #[tokio::test]
+    async fn test_blocking_work_does_not_block_other_commands() {
+        let (_dir, state) = test_state();
+        let (release, released) = std::sync::mpsc::channel::<()>();
 
----
+        // Testruntimen har én tråd. Arbeidet venter til innstillingene er
+        // hentet; gikk det på runtimetråden, ville det aldri skjedd
+        let work = run_blocking("test", move || {
+            released.recv_timeout(Duration::from_secs(10)).is_ok()
+        });
+        let settings = async {
+            // Det get_settings gjør
+            let info = settings_info(&state, &state.settings.read());
+            release.send(()).unwrap();
+            info
+        };
+        let (finished, _) = tokio::join!(work, settings);
 
-*Bare v{} — Laget med ❤️ for et enklere internett*
-"#;
+        assert!(finished.unwrap(), "arbeidet holdt igjen runtimen");
+    }
 
-    let welcome_md = welcome_md.replace("{}", env!("CARGO_PKG_VERSION"));
+    #[tokio::test]
+    async fn test_panic_in_blocking_work_is_internal_error() {
+        let result: Result<(), CommandError> =
+            run_blocking("test", || panic!("feil under konvertering")).await;
+        assert!(matches!(result, Err(CommandError::Internal { .. })));
+    }
 
-    let html = markdown::render(&welcome_md);
-    let title = markdown::extract_title(&welcome_md);
+    #[tokio::test]
+    async fn test_bookmark_title_falls_back_to_host() {
+        let (_dir, state) = test_state();
+        let _ = rustls::crypto::ring::default_provider().install_default();
 
-    RenderedPage {
-        html,
-        title,
-        url: None,
-        is_remote: false,
-        was_converted: false,
+        // Port 1 er lukket, så tilkoblingen avvises med en gang
+        assert_eq!(
+            fetch_bookmark_title(&state, "http://127.0.0.1:1/artikkel").await,
+            "127.0.0.1:1"
+        );
+        assert_eq!(
+            fetch_bookmark_title(&state, "gemini://127.0.0.1:1/").await,
+            "127.0.0.1:1"
+        );
+        assert_eq!(
+            fetch_bookmark_title(&state, "finger://example.com/bruker").await,
+            "example.com"
+        );
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_panic_while_locked_leaves_state_usable() {
+        let (_dir, state) = test_state();
+
+        // En command som får panikk midt i en endring, med låsene holdt
+        let panicked = std::thread::scope(|scope| {
+            scope
+                .spawn(|| {
+                    let _settings = state.settings.write();
+                    let _bookmarks = state.bookmarks.lock();
+                    let _history = state.history.lock();
+                    panic!("feil i en command");
+                })
+                .join()
+                .is_err()
+        });
+        assert!(panicked);
+
+        // Neste command virker som før, både ved lesing og skriving
+        assert!(bookmarks_page(&state).html.contains("Bokmerker"));
+        let page = render_pasted(&state, "# Etterpå".into(), ContentFormat::Markdown, None);
+        assert_eq!(page.unwrap().title.as_deref(), Some("Etterpå"));
+        let mut settings = state.settings.write();
+        settings.zoom = 120;
+        save_settings(&state, &mut settings).unwrap();
+    }
 
     #[test]
-    fn test_render_markdown_command() {
-        let result = render_markdown("# Test".to_string());
-        assert!(result.html.contains("<h1>"));
-        assert_eq!(result.title, Some("Test".to_string()));
+    fn test_get_bookmarks_page() {
+        let (_dir, state) = test_state();
+        let result = bookmarks_page(&state);
+        assert!(result.html.contains("Bokmerker"));
+        assert_eq!(result.url.as_deref(), Some(BOOKMARKS_PAGE_URL));
         assert!(!result.is_remote);
     }
 
     #[test]
     fn test_get_welcome_content() {
-        let result = get_welcome_content();
+        let (_dir, state) = test_state();
+        let result = welcome_page(&state);
         assert!(result.html.contains("Velkommen til Bare"));
         assert!(result.title.is_some());
         assert!(!result.is_remote);
     }
 
+    #[test]
+    fn test_onboarding_page() {
+        let (_dir, state) = test_state();
+        for step in 1..=pages::ONBOARDING_STEPS {
+            let page = onboarding_page(&state, step).unwrap();
+            assert!(page.title.is_some());
+            assert!(page.html.contains(env!("CARGO_PKG_VERSION")));
+        }
+        assert!(onboarding_page(&state, 0).is_err());
+        assert!(onboarding_page(&state, pages::ONBOARDING_STEPS + 1).is_err());
+    }
+
+    #[test]
+    fn test_home_page_falls_back_to_welcome() {
+        let (_dir, state) = test_state();
+        let home = HomePage::Url("gemini://nede.example/".to_string());
+        let page = welcome_on_error(&state, Err("Tilkoblingsfeil".into()), &home);
+        assert!(page.html.contains("Velkommen til Bare"));
+        assert_eq!(page.url, None);
+
+        let loaded = bookmarks_page(&state);
+        let page = welcome_on_error(&state, Ok(loaded), &HomePage::Bookmarks);
+        assert_eq!(page.url.as_deref(), Some(BOOKMARKS_PAGE_URL));
+    }
+
+    #[test]
+    fn test_bookmark_info_protocol_metadata() {
+        let info = |url: &str| {
+            let bookmark = Bookmark {
+                id: "bm_1".to_string(),
+                title: "Test".to_string(),
+                url: url.to_string(),
+                created_at: 0,
+                updated_at: 0,
+                folder: None,
+                tags: Vec::new(),
+                keyword: None,
+                snapshot_path: None,
+                pinned: false,
+            };
+            let info = BookmarkInfo::from(&bookmark);
+            (info.scheme, info.icon, info.host)
+        };
+        let expect = |scheme: &str, icon: &str, host: &str| {
+            (scheme.to_string(), icon.to_string(), host.to_string())
+        };
+
+        assert_eq!(
+            info("gemini://geminiprotocol.net/"),
+            expect("gemini", "🔭", "geminiprotocol.net")
+        );
+        assert_eq!(
+            info("gopher://gopher.floodgap.com/1/"),
+            expect("gopher", "📜", "gopher.floodgap.com")
+        );
+        assert_eq!(
+            info("gophers://gopher.example.org/"),
+            expect("gophers", "📜", "gopher.example.org")
+        );
+        assert_eq!(
+            info("http://example.com/side"),
+            expect("http", "🌐", "example.com")
+        );
+        assert_eq!(
+            info("https://example.com/side"),
+            expect("https", "🌐", "example.com")
+        );
+        assert_eq!(
+            info("file:///home/bruker/notater.md"),
+            expect("file", "📁", "")
+        );
+        assert_eq!(info("/home/bruker/notater.md"), expect("file", "📁", ""));
+        assert_eq!(info("C:\\notater.md"), expect("file", "📁", ""));
+        assert_eq!(
+            info("finger://example.com/bruker"),
+            expect("finger", "🔗", "example.com")
+        );
+    }
+
+    #[test]
+    fn test_pinned_markdown() {
+        let (_dir, state) = test_state();
+        assert_eq!(pinned_markdown(&[]), "");
+
+        let bookmark = Bookmark {
+            id: "bm_1".to_string(),
+            title: "Rust [bok]".to_string(),
+            url: "https://doc.rust-lang.org/book/".to_string(),
+            created_at: 0,
+            updated_at: 0,
+            folder: None,
+            tags: Vec::new(),
+            keyword: None,
+            snapshot_path: None,
+            pinned: true,
+        };
+        let md = pinned_markdown(&[&bookmark]);
+        assert!(md.starts_with("## Festede bokmerker\n\n"));
+        assert!(md.contains("- [Rust \\[bok\\]](<https://doc.rust-lang.org/book/>)\n"));
+
+        let rendered = markdown::render_with_options(&md, &render_options(&state, None));
+        assert!(rendered
+            .html
+            .contains("href=\"https://doc.rust-lang.org/book/\""));
+        assert!(rendered.html.contains("Rust [bok]"));
+    }
+
+    #[test]
+    fn test_get_plain_text_welcome_content() {
+        let text = get_plain_text(welcome_markdown(), None);
+        assert!(text.starts_with("Velkommen til Bare\n\n"));
+        assert!(text.contains("\n• Personvern — Ingen cookies, ingen JavaScript, ingen sporing\n"));
+        assert!(text.contains("\n1. Nummererte lister\n2. Fungerer også\n"));
+        assert!(text.contains("\n• [x] Sett opp Tauri-prosjekt\n"));
+        assert!(text.contains("\nFunksjon — Status\nMarkdown-rendering — ✅ Ferdig\n"));
+        assert!(text.contains("\nfn main() {\n    println!(\"Hello, Bare!\");\n}\n"));
+        assert!(text.contains("\n• gemini://geminiprotocol.net/\n"));
+        assert!(!text.contains("**"));
+        assert!(!text.contains("```"));
+        assert!(!text.contains('|'));
+    }
+
+    #[test]
+    fn test_get_plain_text_with_link_urls() {
+        let text = get_plain_text("[Bare](https://example.com)".to_string(), Some(true));
+        assert_eq!(text, "Bare (https://example.com)");
+    }
+
+    #[test]
+    fn test_clipboard_text_markdown_or_plain() {
+        let markdown = "# Tittel\n\nLes [mer](https://example.com) **her**.".to_string();
+        assert_eq!(clipboard_text(markdown.clone(), false), markdown);
+        assert_eq!(
+            clipboard_text(markdown.clone(), true),
+            "Tittel\n\nLes mer her."
+        );
+    }
+
     #[test]
     fn test_resolve_url_command() {
         let result = resolve_url(
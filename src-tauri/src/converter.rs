@@ -31,29 +31,86 @@ pub struct ConversionResult {
     pub markdown: String,
     /// Ekstrahert tittel (fra <title> eller <h1>)
     pub title: Option<String>,
-    /// Om readability-modus ble brukt (for fremtidig statistikk/logging)
-    #[allow(dead_code)]
+    /// Om bare hovedinnholdet ble tatt med (readability-modus)
     pub used_readability: bool,
+    /// Om dokumentet var større enn `max_bytes` og ble kuttet
+    pub truncated: bool,
 }
 
-/// Konverter HTML til markdown
+/// Valg for konverteringen, vanligvis fra innstillingene for nettstedet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConversionOptions {
+    /// Ekstraher hovedinnholdet i stedet for hele dokumentet
+    pub readability: bool,
+    /// Ta med bildeadressene; ellers beholdes bare alt-teksten
+    pub keep_images: bool,
+    /// Største HTML-dokument som konverteres, i bytes; resten kuttes
+    pub max_bytes: Option<usize>,
+}
+
+impl Default for ConversionOptions {
+    fn default() -> Self {
+        Self {
+            readability: true,
+            keep_images: true,
+            max_bytes: None,
+        }
+    }
+}
+
+/// Konverter HTML til markdown med standardvalgene
 ///
 /// # Arguments
 /// * `html` - HTML-innhold som skal konverteres
 ///
 /// # Returns
 /// Konvertert markdown-innhold
+#[cfg(test)]
 pub fn html_to_markdown(html: &str) -> ConversionResult {
+    html_to_markdown_with(html, &ConversionOptions::default())
+}
+
+/// Konverter HTML til markdown
+///
+/// Uten readability-modus konverteres hele det rensede dokumentet.
+///
+/// # Arguments
+/// * `html` - HTML-innhold som skal konverteres
+/// * `options` - Readability, bilder og størrelsesgrense
+///
+/// # Returns
+/// Konvertert markdown-innhold
+pub fn html_to_markdown_with(html: &str, options: &ConversionOptions) -> ConversionResult {
     info!("Konverterer HTML til markdown ({} bytes)", html.len());
 
+    let (html, truncated) = match options.max_bytes {
+        Some(max) if html.len() > max => {
+            warn!(
+                "HTML er for stor ({} bytes), kutter ved {} bytes",
+                html.len(),
+                max
+            );
+            let end = (0..=max)
+                .rev()
+                .find(|&i| html.is_char_boundary(i))
+                .unwrap_or(0);
+            (&html[..end], true)
+        }
+        _ => (html, false),
+    };
+
     // Ekstraher tittel før sanitizing
     let title = extract_title(html);
 
     // Sanitize HTML for å fjerne scripts, styles, etc.
-    let clean_html = sanitize_html(html);
+    let clean_html = sanitize_html(html, options.keep_images);
 
     // Ekstraher hovedinnhold hvis mulig (readability-modus)
-    let (content_html, used_readability) = extract_main_content(&clean_html);
+    let (content_html, used_readability) = if options.readability {
+        extract_main_content(&clean_html)
+    } else {
+        (clean_html, false)
+    };
 
     // Konverter til markdown
     let markdown = html2md::parse_html(&content_html);
@@ -73,6 +130,7 @@ pub fn html_to_markdown(html: &str) -> ConversionResult {
         markdown: cleaned_markdown,
         title,
         used_readability,
+        truncated,
     }
 }
 
@@ -141,7 +199,7 @@ fn detect_charset_from_html(bytes: &[u8]) -> Option<String> {
 }
 
 /// Sanitize HTML ved å fjerne potensielt farlige eller unødvendige elementer
-fn sanitize_html(html: &str) -> String {
+fn sanitize_html(html: &str, keep_images: bool) -> String {
     // Definer hvilke tags vi vil beholde
     let mut allowed_tags: HashSet<&str> = HashSet::new();
     for tag in &[
@@ -215,6 +273,14 @@ fn sanitize_html(html: &str) -> String {
         .add_tag_attributes("a", &["href", "target"])
         // Tillat src og alt for bilder
         .add_tag_attributes("img", &["src", "alt", "width", "height"])
+        // Uten bilder kommer adressene aldri med i markdown; alt-teksten blir
+        .attribute_filter(move |element, attribute, value| {
+            if !keep_images && element == "img" && attribute == "src" {
+                None
+            } else {
+                Some(value.into())
+            }
+        })
         .link_rel(Some("noopener noreferrer"))
         .clean(html)
         .to_string()
@@ -296,8 +362,8 @@ fn remove_boilerplate(html: &str) -> String {
     result
 }
 
-/// Ekstraher tittel fra HTML
-fn extract_title(html: &str) -> Option<String> {
+/// Ekstraher tittel fra HTML (`<title>`, ellers første `<h1>`)
+pub fn extract_title(html: &str) -> Option<String> {
     let html_lower = html.to_lowercase();
 
     // Prøv <title> først
@@ -603,7 +669,67 @@ mod tests {
         "#;
         let result = html_to_markdown(html);
         assert!(result.markdown.contains("Hovedinnhold"));
+        assert!(!result.markdown.contains("Menu"));
+        assert!(!result.markdown.contains("Footer"));
         assert!(result.used_readability);
+
+        // Uten readability kommer hele dokumentet med
+        let result = html_to_markdown_with(
+            html,
+            &ConversionOptions {
+                readability: false,
+                ..Default::default()
+            },
+        );
+        assert!(result.markdown.contains("Hovedinnhold"));
+        assert!(result.markdown.contains("Menu"));
+        assert!(result.markdown.contains("Footer"));
+        assert!(!result.used_readability);
+    }
+
+    #[test]
+    fn test_conversion_without_images_keeps_alt_text() {
+        let html = r#"<p>Se her: <img src="https://bilder.example/katt.png" alt="En katt"></p>"#;
+        let result = html_to_markdown(html);
+        assert!(result.markdown.contains("https://bilder.example/katt.png"));
+
+        let result = html_to_markdown_with(
+            html,
+            &ConversionOptions {
+                keep_images: false,
+                ..Default::default()
+            },
+        );
+        assert!(result.markdown.contains("En katt"));
+        assert!(!result.markdown.contains("bilder.example"));
+    }
+
+    #[test]
+    fn test_conversion_truncates_large_documents() {
+        let html = "<p>Første avsnitt</p><p>Ærlig talt</p><p>Siste avsnitt</p>";
+        let end = html.find("Siste").unwrap();
+        let result = html_to_markdown_with(
+            html,
+            &ConversionOptions {
+                // Midt i "Æ"
+                max_bytes: Some(html.find('Æ').unwrap() + 1),
+                ..Default::default()
+            },
+        );
+        assert!(result.truncated);
+        assert!(result.markdown.contains("Første avsnitt"));
+        assert!(!result.markdown.contains("Ærlig"));
+
+        let result = html_to_markdown_with(
+            html,
+            &ConversionOptions {
+                max_bytes: Some(end),
+                ..Default::default()
+            },
+        );
+        assert!(result.markdown.contains("Ærlig talt"));
+        assert!(!result.markdown.contains("Siste"));
+        assert!(!html_to_markdown(html).truncated);
     }
 
     #[test]
@@ -631,7 +757,7 @@ mod tests {
     fn test_sanitize_removes_script() {
         // ammonia fjerner scripts automatisk
         let html = "<div><p>Trygt</p></div>";
-        let sanitized = sanitize_html(html);
+        let sanitized = sanitize_html(html, true);
         assert!(sanitized.contains("Trygt"));
     }
 
@@ -0,0 +1,167 @@
+//! Adresser fra andre programmer (deep links)
+//!
+//! Bare er registrert som program for `gemini://`- og `gopher://`-lenker.
+//! Klikker brukeren på en slik lenke et annet sted, får Bare adressen fra
+//! deep-link-pluginen, eller fra single-instance-pluginen når Bare allerede
+//! kjører. Adressen sendes til hovedvinduet som `navigate-request`.
+//!
+//! Adresser som kommer før frontend i hovedvinduet har startet, ville gått
+//! tapt, så de legges i kø til frontend henter dem med `take_deep_links`.
+
+use parking_lot::Mutex;
+use url::Url;
+
+/// Hendelsen som ber hovedvinduet navigere til en adresse
+pub const NAVIGATE_REQUEST_EVENT: &str = "navigate-request";
+
+/// Etiketten til hovedvinduet
+pub const MAIN_WINDOW: &str = "main";
+
+/// Protokollene Bare tar imot fra andre programmer
+const SCHEMES: [&str; 2] = ["gemini", "gopher"];
+
+/// Lengste adresse som godtas; Gemini tillater 1024 bytes
+const MAX_URL_LENGTH: usize = 1024;
+
+/// Flest adresser som holdes i kø før frontend er klar
+const MAX_PENDING: usize = 16;
+
+/// Sjekk en adresse fra et annet program
+///
+/// Bare adresser med en av protokollene Bare er registrert for, og med en
+/// vert, godtas. Andre programmer skal ikke kunne åpne lokale filer eller
+/// interne sider.
+///
+/// # Returns
+/// Den normaliserte adressen, eller None hvis den avvises
+pub fn validate(url: &str) -> Option<String> {
+    let url = url.trim();
+    if url.len() > MAX_URL_LENGTH {
+        return None;
+    }
+    let parsed = Url::parse(url).ok()?;
+    if !SCHEMES.contains(&parsed.scheme()) || parsed.host_str().is_none_or(str::is_empty) {
+        return None;
+    }
+    Some(parsed.to_string())
+}
+
+/// Køen, registrert med `.manage()`
+pub type DeepLinks = Mutex<DeepLinkQueue>;
+
+/// Adresser som venter på at hovedvinduet skal bli klart
+#[derive(Debug, Default)]
+pub struct DeepLinkQueue {
+    /// Om frontend i hovedvinduet har startet og lytter på `navigate-request`
+    ready: bool,
+    pending: Vec<String>,
+}
+
+impl DeepLinkQueue {
+    /// Ta imot en godkjent adresse
+    ///
+    /// # Returns
+    /// Adressen hvis den kan sendes til hovedvinduet med en gang, None hvis
+    /// den ble lagt i kø
+    pub fn push(&mut self, url: String) -> Option<String> {
+        if self.ready {
+            return Some(url);
+        }
+        if self.pending.len() == MAX_PENDING {
+            self.pending.remove(0);
+        }
+        self.pending.push(url);
+        None
+    }
+
+    /// Frontend i hovedvinduet har startet
+    ///
+    /// # Returns
+    /// Adressene som kom før, eldste først
+    pub fn take_pending(&mut self) -> Vec<String> {
+        self.ready = true;
+        std::mem::take(&mut self.pending)
+    }
+
+    /// Hovedvinduet er lukket eller lastes på nytt; adresser legges i kø
+    /// til det nye vinduet er klart
+    pub fn window_closed(&mut self) {
+        self.ready = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_accepts_registered_schemes() {
+        assert_eq!(
+            validate("gemini://geminiprotocol.net/").as_deref(),
+            Some("gemini://geminiprotocol.net/")
+        );
+        assert_eq!(
+            validate(" gopher://gopher.floodgap.com/1/world\n").as_deref(),
+            Some("gopher://gopher.floodgap.com/1/world")
+        );
+        assert_eq!(
+            validate("GEMINI://Example.org:1966").as_deref(),
+            Some("gemini://Example.org:1966")
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_other_requests() {
+        for url in [
+            "file:///etc/passwd",
+            "bare://settings",
+            "https://example.com/",
+            "javascript:alert(1)",
+            "gemini:///sti",
+            "gemini:",
+            "ikke en adresse",
+            "",
+        ] {
+            assert_eq!(validate(url), None, "{:?}", url);
+        }
+        let long = format!("gemini://example.org/{}", "a".repeat(MAX_URL_LENGTH));
+        assert_eq!(validate(&long), None);
+    }
+
+    #[test]
+    fn test_queue_until_frontend_is_ready() {
+        let mut queue = DeepLinkQueue::default();
+        assert_eq!(queue.push("gemini://a.example/".into()), None);
+        assert_eq!(queue.push("gemini://b.example/".into()), None);
+
+        assert_eq!(
+            queue.take_pending(),
+            ["gemini://a.example/", "gemini://b.example/"]
+        );
+        assert!(queue.take_pending().is_empty());
+        assert_eq!(
+            queue.push("gopher://c.example/".into()).as_deref(),
+            Some("gopher://c.example/")
+        );
+
+        // Et nytt hovedvindu må hente adressene på nytt
+        queue.window_closed();
+        assert_eq!(queue.push("gopher://d.example/".into()), None);
+        assert_eq!(queue.take_pending(), ["gopher://d.example/"]);
+    }
+
+    #[test]
+    fn test_queue_keeps_the_newest() {
+        let mut queue = DeepLinkQueue::default();
+        for i in 0..MAX_PENDING + 2 {
+            queue.push(format!("gemini://{}.example/", i));
+        }
+        let pending = queue.take_pending();
+        assert_eq!(pending.len(), MAX_PENDING);
+        assert_eq!(pending[0], "gemini://2.example/");
+        assert_eq!(
+            pending.last().unwrap(),
+            &format!("gemini://{}.example/", MAX_PENDING + 1)
+        );
+    }
+}
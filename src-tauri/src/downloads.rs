@@ -0,0 +1,718 @@
+//! Nedlastinger til disk
+//!
+//! Filer Bare ikke kan vise (binærfiler over HTTP, Gemini-innhold som ikke er
+//! tekst, Gopher-binærfiler) lastes ned gjennom samme løype uansett protokoll.
+//! Innholdet strømmes til en `.part`-fil ved siden av målet, som døpes om når
+//! alt er mottatt. Avbrytes eller feiler nedlastingen, slettes `.part`-filen.
+//! Listen over nedlastinger lever bare så lenge appen kjører.
+
+use crate::breadcrumbs;
+use crate::locale::{Locale, Localize};
+use serde::Serialize;
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Størrelsen på hver bit som leses fra nettverket
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Filnavnet når verken brukeren, serveren eller adressen gir et
+const FALLBACK_FILE_NAME: &str = "nedlasting";
+
+/// Lengste filnavn som brukes, i tegn
+const MAX_FILE_NAME_CHARS: usize = 200;
+
+/// Filendelser for innholdstyper, når adressen ikke har en
+const MIME_EXTENSIONS: [(&str, &str); 20] = [
+    ("application/pdf", "pdf"),
+    ("application/zip", "zip"),
+    ("application/gzip", "gz"),
+    ("application/x-tar", "tar"),
+    ("application/epub+zip", "epub"),
+    ("application/json", "json"),
+    ("application/xml", "xml"),
+    ("image/png", "png"),
+    ("image/jpeg", "jpg"),
+    ("image/gif", "gif"),
+    ("image/webp", "webp"),
+    ("image/svg+xml", "svg"),
+    ("audio/mpeg", "mp3"),
+    ("audio/ogg", "ogg"),
+    ("video/mp4", "mp4"),
+    ("video/webm", "webm"),
+    ("text/plain", "txt"),
+    ("text/html", "html"),
+    ("text/markdown", "md"),
+    ("text/gemini", "gmi"),
+];
+
+/// Identifiserer en nedlasting i hendelser og commands
+pub type DownloadId = u64;
+
+/// Feil som kan avslutte en nedlasting
+#[derive(Debug, Error)]
+pub enum DownloadError {
+    #[error("{0}")]
+    Io(#[from] io::Error),
+
+    #[error("Timeout: Serveren sendte ikke noe på {0} sekunder")]
+    Timeout(u64),
+
+    #[error("Nedlastingen ble avbrutt")]
+    Cancelled,
+}
+
+impl Localize for DownloadError {
+    fn localize_in(&self, locale: Locale) -> String {
+        match self {
+            Self::Io(e) => locale.format("error.download_write", &[e]),
+            Self::Timeout(seconds) => locale.format("error.timeout", &[seconds]),
+            Self::Cancelled => locale.text("error.download_cancelled").to_string(),
+        }
+    }
+}
+
+/// Hvor langt en nedlasting har kommet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", rename_all = "lowercase")]
+pub enum DownloadState {
+    /// Overføringen pågår
+    Active,
+    /// Filen ligger på målet
+    Completed,
+    /// Overføringen stoppet med en feil
+    Failed { error: String },
+    /// Brukeren avbrøt
+    Cancelled,
+}
+
+/// Én nedlasting i listen
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Download {
+    pub id: DownloadId,
+    pub url: String,
+    /// Filen innholdet lagres til
+    pub destination: PathBuf,
+    /// Antall bytes mottatt så langt
+    pub received: u64,
+    /// Forventet størrelse, hvis serveren oppga den
+    pub total: Option<u64>,
+    #[serde(flatten)]
+    pub state: DownloadState,
+}
+
+/// Alle nedlastinger i denne økten, eldste først
+#[derive(Debug, Default)]
+pub struct Downloads {
+    entries: Vec<Download>,
+    next_id: DownloadId,
+}
+
+impl Downloads {
+    /// Registrer en ny nedlasting
+    ///
+    /// # Returns
+    /// ID-en nedlastingen rapporteres og avbrytes med
+    pub fn start(&mut self, url: &str, destination: &Path) -> DownloadId {
+        self.next_id += 1;
+        self.entries.push(Download {
+            id: self.next_id,
+            url: url.to_string(),
+            destination: destination.to_path_buf(),
+            received: 0,
+            total: None,
+            state: DownloadState::Active,
+        });
+        self.next_id
+    }
+
+    /// En nedlasting, hvis den finnes
+    pub fn get(&self, id: DownloadId) -> Option<&Download> {
+        self.entries.iter().find(|d| d.id == id)
+    }
+
+    /// En nedlasting som fortsatt pågår
+    fn active(&mut self, id: DownloadId) -> Option<&mut Download> {
+        self.entries
+            .iter_mut()
+            .find(|d| d.id == id && d.state == DownloadState::Active)
+    }
+
+    /// Registrer mottatte bytes
+    ///
+    /// # Returns
+    /// false hvis nedlastingen ikke lenger pågår, f.eks. fordi den er avbrutt
+    pub fn progress(&mut self, id: DownloadId, received: u64, total: Option<u64>) -> bool {
+        let Some(download) = self.active(id) else {
+            return false;
+        };
+        download.received = received;
+        download.total = total;
+        true
+    }
+
+    /// Flytt en pågående nedlasting til en slutt-tilstand
+    ///
+    /// # Returns
+    /// false hvis nedlastingen allerede var avsluttet; da endres ingenting
+    fn finish(&mut self, id: DownloadId, state: DownloadState) -> bool {
+        let Some(download) = self.active(id) else {
+            return false;
+        };
+        download.state = state;
+        true
+    }
+
+    /// Marker en nedlasting som fullført
+    pub fn complete(&mut self, id: DownloadId) -> bool {
+        self.finish(id, DownloadState::Completed)
+    }
+
+    /// Marker en nedlasting som feilet
+    pub fn fail(&mut self, id: DownloadId, error: String) -> bool {
+        self.finish(id, DownloadState::Failed { error })
+    }
+
+    /// Avbryt en nedlasting; overføringen stopper ved neste bit
+    pub fn cancel(&mut self, id: DownloadId) -> bool {
+        self.finish(id, DownloadState::Cancelled)
+    }
+
+    /// Alle nedlastinger, nyeste først
+    pub fn list(&self) -> Vec<Download> {
+        self.entries.iter().rev().cloned().collect()
+    }
+}
+
+/// Stien til den ufullstendige filen, f.eks. "bilde.png.part"
+pub fn part_path(destination: &Path) -> PathBuf {
+    let mut name = destination
+        .file_name()
+        .map(OsString::from)
+        .unwrap_or_default();
+    name.push(".part");
+    destination.with_file_name(name)
+}
+
+/// En `.part`-fil som skrives til under nedlasting
+///
+/// Filen slettes når den droppes uten at `finish` er kalt, slik at avbrutte
+/// og feilede nedlastinger ikke etterlater halve filer.
+#[derive(Debug)]
+pub struct PartFile {
+    file: Option<File>,
+    part: PathBuf,
+    destination: PathBuf,
+}
+
+impl PartFile {
+    /// Opprett `.part`-filen for et mål
+    pub fn create(destination: &Path) -> io::Result<Self> {
+        let part = part_path(destination);
+        let file = File::create(&part)?;
+        Ok(Self {
+            file: Some(file),
+            part,
+            destination: destination.to_path_buf(),
+        })
+    }
+
+    /// Skriv en bit av innholdet
+    pub fn write(&mut self, chunk: &[u8]) -> io::Result<()> {
+        match &mut self.file {
+            Some(file) => file.write_all(chunk),
+            None => Err(io::Error::other("filen er allerede lukket")),
+        }
+    }
+
+    /// Skriv ferdig og døp filen om til målet
+    pub fn finish(mut self) -> io::Result<PathBuf> {
+        let result = match self.file.take() {
+            Some(file) => file.sync_all(),
+            None => Ok(()),
+        }
+        .and_then(|()| fs::rename(&self.part, &self.destination));
+        if let Err(e) = result {
+            let _ = fs::remove_file(&self.part);
+            return Err(e);
+        }
+        Ok(self.destination.clone())
+    }
+}
+
+impl Drop for PartFile {
+    fn drop(&mut self) {
+        if self.file.take().is_some() {
+            let _ = fs::remove_file(&self.part);
+        }
+    }
+}
+
+/// Innholdet som lastes ned, uavhengig av protokoll
+pub enum DownloadBody {
+    /// HTTP-respons, lest bit for bit
+    Http(reqwest::Response),
+    /// Gemini- eller Gopher-tilkobling, lest til serveren lukker
+    Stream(Pin<Box<dyn AsyncRead + Send>>),
+}
+
+impl DownloadBody {
+    /// Pakk inn en tilkobling som leses til slutten
+    pub fn stream(reader: impl AsyncRead + Send + 'static) -> Self {
+        Self::Stream(Box::pin(reader))
+    }
+
+    /// Forventet størrelse, hvis protokollen oppgir den
+    pub fn total(&self) -> Option<u64> {
+        match self {
+            Self::Http(response) => response.content_length(),
+            Self::Stream(_) => None,
+        }
+    }
+
+    /// Content-Disposition-headeren, hvis protokollen har en
+    pub fn content_disposition(&self) -> Option<&str> {
+        match self {
+            Self::Http(response) => response
+                .headers()
+                .get(reqwest::header::CONTENT_DISPOSITION)
+                .and_then(|value| value.to_str().ok()),
+            Self::Stream(_) => None,
+        }
+    }
+
+    /// Les neste bit
+    ///
+    /// # Returns
+    /// None når alt er lest
+    async fn chunk(&mut self) -> io::Result<Option<Vec<u8>>> {
+        match self {
+            Self::Http(response) => response
+                .chunk()
+                .await
+                .map(|chunk| chunk.map(|bytes| bytes.to_vec()))
+                .map_err(io::Error::other),
+            Self::Stream(reader) => {
+                let mut buffer = vec![0; CHUNK_SIZE];
+                let read = reader.read(&mut buffer).await?;
+                buffer.truncate(read);
+                Ok((read > 0).then_some(buffer))
+            }
+        }
+    }
+}
+
+/// Overfør innholdet til målet
+///
+/// # Arguments
+/// * `body` - Fra protokollens klient
+/// * `destination` - Filen innholdet skal ende opp i
+/// * `idle_timeout` - Hvor lenge serveren kan være stille før vi gir opp
+/// * `on_progress` - Kalles med antall mottatte bytes etter hver bit;
+///   returnerer false for å avbryte
+///
+/// # Returns
+/// Antall bytes skrevet. Ved feil og avbrudd finnes verken målet eller
+/// `.part`-filen etterpå.
+pub async fn transfer<F>(
+    mut body: DownloadBody,
+    destination: &Path,
+    idle_timeout: Duration,
+    mut on_progress: F,
+) -> Result<u64, DownloadError>
+where
+    F: FnMut(u64) -> bool,
+{
+    let mut part = PartFile::create(destination)?;
+    let mut received = 0u64;
+    loop {
+        let chunk = tokio::time::timeout(idle_timeout, body.chunk())
+            .await
+            .map_err(|_| DownloadError::Timeout(idle_timeout.as_secs()))??;
+        let Some(chunk) = chunk else {
+            break;
+        };
+        part.write(&chunk)?;
+        received += chunk.len() as u64;
+        if !on_progress(received) {
+            return Err(DownloadError::Cancelled);
+        }
+    }
+    part.finish()?;
+    Ok(received)
+}
+
+/// Filnavnet en ressurs lagres med
+///
+/// Første navn som er brukbart etter rensing vinner: navnet brukeren
+/// foreslo, navnet i Content-Disposition, siste ledd i adressens sti og til
+/// slutt vertsnavnet. Mangler navnet endelse, legges endelsen for
+/// innholdstypen til.
+///
+/// # Arguments
+/// * `suggested` - Navn fra frontend, f.eks. lenketeksten
+/// * `content_disposition` - Headeren fra en HTTP-respons
+/// * `url` - Adressen ressursen lastes ned fra
+/// * `content_type` - MIME-typen serveren oppga
+pub fn resource_file_name(
+    suggested: Option<&str>,
+    content_disposition: Option<&str>,
+    url: &str,
+    content_type: Option<&str>,
+) -> String {
+    let parsed = url::Url::parse(url).ok();
+    let from_path = parsed
+        .as_ref()
+        .and_then(|u| u.path_segments())
+        .and_then(|mut segments| segments.next_back())
+        .map(breadcrumbs::percent_decode);
+    let from_host = parsed.as_ref().and_then(|u| u.host_str()).map(String::from);
+
+    let name = [
+        suggested.map(String::from),
+        content_disposition.and_then(disposition_file_name),
+        from_path,
+        from_host,
+    ]
+    .into_iter()
+    .flatten()
+    .map(|name| sanitize_file_name(&name))
+    .find(|name| !name.is_empty())
+    .unwrap_or_else(|| FALLBACK_FILE_NAME.to_string());
+
+    match content_type.and_then(mime_extension) {
+        Some(extension) if Path::new(&name).extension().is_none() => {
+            format!("{}.{}", name, extension)
+        }
+        _ => name,
+    }
+}
+
+/// Filnavnet i en Content-Disposition-header
+///
+/// `filename*` (RFC 5987) foretrekkes fremfor `filename`, siden den kan
+/// inneholde tegn utenfor ASCII.
+fn disposition_file_name(header: &str) -> Option<String> {
+    let mut plain = None;
+    for param in header.split(';').skip(1) {
+        let Some((key, value)) = param.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim().to_ascii_lowercase().as_str() {
+            "filename*" => {
+                // charset'språk'verdi
+                let encoded = value.splitn(3, '\'').nth(2)?;
+                return Some(breadcrumbs::percent_decode(encoded));
+            }
+            "filename" => plain = Some(value.trim_matches('"').to_string()),
+            _ => {}
+        }
+    }
+    plain
+}
+
+/// Gjør et navn trygt som filnavn på alle plattformer
+///
+/// Mappeskilletegn og tegn Windows ikke tillater blir `_`, kontrolltegn
+/// fjernes, og punktum og mellomrom i endene fjernes, slik at navnet verken
+/// kan peke ut av mappen eller bli en skjult fil.
+fn sanitize_file_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .filter(|c| !c.is_control())
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c => c,
+        })
+        .take(MAX_FILE_NAME_CHARS)
+        .collect();
+    cleaned
+        .trim_matches(|c: char| c == '.' || c.is_whitespace())
+        .to_string()
+}
+
+/// Filendelsen for en MIME-type, hvis den er kjent
+fn mime_extension(content_type: &str) -> Option<&'static str> {
+    let mime = content_type.split(';').next()?.trim().to_ascii_lowercase();
+    MIME_EXTENSIONS
+        .iter()
+        .find(|(known, _)| *known == mime)
+        .map(|(_, extension)| *extension)
+}
+
+/// En sti som ikke finnes fra før
+///
+/// Finnes filen, prøves `navn (1).endelse`, `navn (2).endelse` og så videre.
+pub fn unique_path(path: &Path) -> PathBuf {
+    if !path.exists() {
+        return path.to_path_buf();
+    }
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    (1..)
+        .map(|n| path.with_file_name(format!("{} ({}){}", stem, n, extension)))
+        .find(|candidate| !candidate.exists())
+        .expect("uendelig mange kandidater")
+}
+
+/// Om en HTTP Content-Type er noe Bare ikke kan vise og bør lastes ned
+///
+/// Tekst, HTML, XML og JSON vises; alt annet regnes som binært.
+pub fn is_binary_content_type(content_type: &str) -> bool {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    if mime.is_empty() || mime.starts_with("text/") {
+        return false;
+    }
+    !["html", "xml", "json"]
+        .iter()
+        .any(|kind| mime.contains(kind))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_state_transitions() {
+        let mut downloads = Downloads::default();
+        let first = downloads.start("https://example.com/a.zip", Path::new("/tmp/a.zip"));
+        let second = downloads.start("gemini://example.org/b.png", Path::new("/tmp/b.png"));
+        assert_ne!(first, second);
+
+        assert!(downloads.progress(first, 10, Some(100)));
+        assert!(downloads.complete(first));
+        // Slutt-tilstander kan ikke endres
+        assert!(!downloads.progress(first, 20, Some(100)));
+        assert!(!downloads.cancel(first));
+        assert!(!downloads.fail(first, "for sent".into()));
+        assert_eq!(
+            downloads.get(first).unwrap().state,
+            DownloadState::Completed
+        );
+        assert_eq!(downloads.get(first).unwrap().received, 10);
+
+        assert!(downloads.cancel(second));
+        assert!(!downloads.complete(second));
+        assert_eq!(
+            downloads.get(second).unwrap().state,
+            DownloadState::Cancelled
+        );
+
+        let third = downloads.start("gopher://example.net/9/c.bin", Path::new("/tmp/c.bin"));
+        assert!(downloads.fail(third, "Tilkoblingsfeil".into()));
+        assert_eq!(
+            downloads.get(third).unwrap().state,
+            DownloadState::Failed {
+                error: "Tilkoblingsfeil".into()
+            }
+        );
+
+        let ids: Vec<_> = downloads.list().iter().map(|d| d.id).collect();
+        assert_eq!(ids, vec![third, second, first]);
+        assert!(!downloads.cancel(999));
+    }
+
+    #[test]
+    fn test_download_serializes_state_inline() {
+        let mut downloads = Downloads::default();
+        let id = downloads.start("https://example.com/a.zip", Path::new("/tmp/a.zip"));
+        downloads.fail(id, "Nettverksfeil".into());
+        let json = serde_json::to_value(downloads.get(id).unwrap()).unwrap();
+        assert_eq!(json["state"], "failed");
+        assert_eq!(json["error"], "Nettverksfeil");
+        assert_eq!(json["id"], id);
+    }
+
+    #[test]
+    fn test_part_file_is_renamed_or_removed() {
+        let dir = tempdir().unwrap();
+        let destination = dir.path().join("fil.bin");
+        assert_eq!(part_path(&destination), dir.path().join("fil.bin.part"));
+
+        let mut part = PartFile::create(&destination).unwrap();
+        part.write(b"halv").unwrap();
+        assert!(part_path(&destination).exists());
+        drop(part);
+        assert!(!part_path(&destination).exists());
+        assert!(!destination.exists());
+
+        let mut part = PartFile::create(&destination).unwrap();
+        part.write(b"hel").unwrap();
+        assert_eq!(part.finish().unwrap(), destination);
+        assert_eq!(fs::read(&destination).unwrap(), b"hel");
+        assert!(!part_path(&destination).exists());
+    }
+
+    #[tokio::test]
+    async fn test_transfer_completes_and_cancels() {
+        let dir = tempdir().unwrap();
+        let content = vec![7u8; CHUNK_SIZE * 3];
+
+        let destination = dir.path().join("hel.bin");
+        let body = DownloadBody::stream(io::Cursor::new(content.clone()));
+        let mut reports = Vec::new();
+        let written = transfer(body, &destination, Duration::from_secs(5), |received| {
+            reports.push(received);
+            true
+        })
+        .await
+        .unwrap();
+        assert_eq!(written, content.len() as u64);
+        assert_eq!(reports.last(), Some(&written));
+        assert_eq!(fs::read(&destination).unwrap(), content);
+
+        // Avbryt etter første bit
+        let destination = dir.path().join("avbrutt.bin");
+        let body = DownloadBody::stream(io::Cursor::new(content));
+        let result = transfer(body, &destination, Duration::from_secs(5), |_| false).await;
+        assert!(matches!(result, Err(DownloadError::Cancelled)));
+        assert!(!destination.exists());
+        assert!(!part_path(&destination).exists());
+    }
+
+    #[test]
+    fn test_resource_file_name_precedence() {
+        let url = "https://a.example/filer/rapport%202024.pdf?versjon=2";
+        let disposition = Some("attachment; filename=\"fra-server.pdf\"");
+        assert_eq!(
+            resource_file_name(Some("valgt.pdf"), disposition, url, None),
+            "valgt.pdf"
+        );
+        assert_eq!(
+            resource_file_name(Some("  "), disposition, url, None),
+            "fra-server.pdf"
+        );
+        assert_eq!(
+            resource_file_name(None, None, url, None),
+            "rapport 2024.pdf"
+        );
+        assert_eq!(
+            resource_file_name(None, None, "https://a.example/", None),
+            "a.example"
+        );
+        assert_eq!(
+            resource_file_name(None, None, "data:,tekst", None),
+            FALLBACK_FILE_NAME
+        );
+
+        // filename* vinner over filename
+        assert_eq!(
+            resource_file_name(
+                None,
+                Some("attachment; filename=\"bla.txt\"; filename*=UTF-8''bl%C3%A5b%C3%A6r.txt"),
+                url,
+                None,
+            ),
+            "blåbær.txt"
+        );
+    }
+
+    #[test]
+    fn test_resource_file_name_is_sanitized() {
+        assert_eq!(
+            resource_file_name(Some("../../.bashrc"), None, "https://a.example/", None),
+            "_.._.bashrc"
+        );
+        assert_eq!(
+            resource_file_name(
+                None,
+                Some("attachment; filename=\"..\""),
+                "gemini://a.example/bilde",
+                None
+            ),
+            "bilde"
+        );
+        assert_eq!(
+            resource_file_name(Some("a\u{0}b:c?.txt"), None, "https://a.example/", None),
+            "ab_c_.txt"
+        );
+        let long = "x".repeat(500);
+        assert_eq!(
+            resource_file_name(Some(&long), None, "https://a.example/", None)
+                .chars()
+                .count(),
+            MAX_FILE_NAME_CHARS
+        );
+    }
+
+    #[test]
+    fn test_resource_file_name_adds_extension_from_mime() {
+        assert_eq!(
+            resource_file_name(None, None, "gemini://a.example/bilde", Some("image/png")),
+            "bilde.png"
+        );
+        assert_eq!(
+            resource_file_name(
+                None,
+                None,
+                "https://a.example/hent?id=3",
+                Some("application/pdf; qs=0.9")
+            ),
+            "hent.pdf"
+        );
+        // En endelse i adressen beholdes, og ukjente typer gir ingen endelse
+        assert_eq!(
+            resource_file_name(None, None, "https://a.example/a.zip", Some("image/png")),
+            "a.zip"
+        );
+        assert_eq!(
+            resource_file_name(
+                None,
+                None,
+                "gopher://a.example/9/fil",
+                Some("x-ukjent/type")
+            ),
+            "fil"
+        );
+    }
+
+    #[test]
+    fn test_unique_path_adds_counter() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("rapport.pdf");
+        assert_eq!(unique_path(&path), path);
+
+        fs::write(&path, "").unwrap();
+        assert_eq!(unique_path(&path), dir.path().join("rapport (1).pdf"));
+        fs::write(dir.path().join("rapport (1).pdf"), "").unwrap();
+        assert_eq!(unique_path(&path), dir.path().join("rapport (2).pdf"));
+
+        let bare = dir.path().join("LESMEG");
+        fs::write(&bare, "").unwrap();
+        assert_eq!(unique_path(&bare), dir.path().join("LESMEG (1)"));
+    }
+
+    #[test]
+    fn test_is_binary_content_type() {
+        for binary in ["application/zip", "image/png", "application/octet-stream"] {
+            assert!(is_binary_content_type(binary), "{}", binary);
+        }
+        for text in [
+            "text/plain; charset=utf-8",
+            "text/html",
+            "application/xhtml+xml",
+            "application/json",
+            "",
+        ] {
+            assert!(!is_binary_content_type(text), "{}", text);
+        }
+    }
+}
@@ -0,0 +1,395 @@
+//! Feilsider for hentinger som mislykkes
+//!
+//! En feilet navigering gir en vanlig side med en overskrift, en forklaring
+//! som passer feilen, adressen som ble forsøkt og lenker for å prøve igjen,
+//! åpne adressen i en annen nettleser eller gå tilbake. Siden sendes med
+//! i `CommandError`, så frontend kan velge mellom siden og feilmeldingen.
+//!
+//! Lenkene til handlingene bruker `bare://`-adresser som frontend kjenner
+//! igjen, se `BACK_URL` og `OPEN_EXTERNALLY_URL`.
+
+use crate::command_error::{CommandError, NetworkErrorKind};
+use crate::commands::RenderedPage;
+use crate::locale::{self, Locale};
+use crate::markdown;
+
+/// Lenken som går tilbake til forrige side
+pub const BACK_URL: &str = "bare://back";
+
+/// Lenken som åpner `?url=` i systemets nettleser
+pub const OPEN_EXTERNALLY_URL: &str = "bare://open-externally";
+
+/// Hva slags feil siden forklarer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorPageKind {
+    /// Vertsnavnet kunne ikke slås opp
+    Dns,
+    /// Serveren tok ikke imot tilkoblingen
+    Connection,
+    Timeout,
+    NotFound,
+    Server,
+    /// Gemini-serverens sertifikat er et annet enn det som ble husket
+    CertificateChanged,
+    TooLarge,
+    /// Protokollen eller innholdet kan ikke vises
+    Unsupported,
+    /// Et argument fra kommandolinjen er verken en fil eller en adresse
+    InvalidArgument,
+    Other,
+}
+
+impl ErrorPageKind {
+    /// Feilsiden for en feil fra en henting
+    ///
+    /// # Returns
+    /// None for spørsmål til brukeren og hentinger som er forkastet, som
+    /// ikke skal vises som feilside
+    pub fn of(error: &CommandError) -> Option<Self> {
+        match error {
+            CommandError::Network { kind, .. } => Some(match kind {
+                NetworkErrorKind::Dns => Self::Dns,
+                NetworkErrorKind::Connection => Self::Connection,
+                NetworkErrorKind::Timeout => Self::Timeout,
+                NetworkErrorKind::NotFound => Self::NotFound,
+                NetworkErrorKind::Server => Self::Server,
+                NetworkErrorKind::TooLarge => Self::TooLarge,
+                NetworkErrorKind::UnsupportedScheme | NetworkErrorKind::ClientCertificate => {
+                    Self::Unsupported
+                }
+                NetworkErrorKind::InvalidUrl
+                | NetworkErrorKind::Tls
+                | NetworkErrorKind::InvalidResponse
+                | NetworkErrorKind::RedirectLoop => Self::Other,
+            }),
+            CommandError::CertificateChanged { .. } => Some(Self::CertificateChanged),
+            _ => None,
+        }
+    }
+}
+
+/// Lag en feilside
+///
+/// # Arguments
+/// * `kind` - Hva slags feil
+/// * `url` - Adressen som ble forsøkt
+/// * `details` - Feilmeldingen, vises under forklaringen
+///
+/// # Returns
+/// RenderedPage med feilsiden på brukerens språk
+pub fn error_page(kind: ErrorPageKind, url: &str, details: &str) -> RenderedPage {
+    let locale = locale::current();
+    let source = error_markdown(locale, kind, url, details);
+    let options = markdown::RenderOptions {
+        locale,
+        ..Default::default()
+    };
+    let rendered = markdown::render_with_options(&source, &options);
+    RenderedPage {
+        html: rendered.html,
+        title: Some(title(locale, kind).to_string()),
+        url: Some(url.to_string()),
+        is_remote: false,
+        was_converted: false,
+        tasks: None,
+        skipped_diagrams: 0,
+        is_archived: false,
+        used_readability: false,
+        truncated: false,
+        security: None,
+        timing: None,
+    }
+}
+
+/// Legg ved en feilside hvis feilen skal ha en
+///
+/// # Arguments
+/// * `error` - Feilen fra hentingen
+/// * `url` - Adressen som ble forsøkt
+pub fn attach(error: CommandError, url: &str) -> CommandError {
+    match ErrorPageKind::of(&error) {
+        Some(kind) => {
+            let page = error_page(kind, url, error.message());
+            error.with_page(page)
+        }
+        None => error,
+    }
+}
+
+fn title(locale: Locale, kind: ErrorPageKind) -> &'static str {
+    locale.text(match kind {
+        ErrorPageKind::Dns => "error_page.dns.title",
+        ErrorPageKind::Connection => "error_page.connection.title",
+        ErrorPageKind::Timeout => "error_page.timeout.title",
+        ErrorPageKind::NotFound => "error_page.not_found.title",
+        ErrorPageKind::Server => "error_page.server.title",
+        ErrorPageKind::CertificateChanged => "error_page.certificate_changed.title",
+        ErrorPageKind::TooLarge => "error_page.too_large.title",
+        ErrorPageKind::Unsupported => "error_page.unsupported.title",
+        ErrorPageKind::InvalidArgument => "error_page.invalid_argument.title",
+        ErrorPageKind::Other => "error_page.other.title",
+    })
+}
+
+fn explanation(locale: Locale, kind: ErrorPageKind, host: &str) -> String {
+    let key = match kind {
+        ErrorPageKind::Dns => "error_page.dns.body",
+        ErrorPageKind::Connection => "error_page.connection.body",
+        ErrorPageKind::Timeout => "error_page.timeout.body",
+        ErrorPageKind::NotFound => "error_page.not_found.body",
+        ErrorPageKind::Server => "error_page.server.body",
+        ErrorPageKind::CertificateChanged => "error_page.certificate_changed.body",
+        ErrorPageKind::TooLarge => "error_page.too_large.body",
+        ErrorPageKind::Unsupported => "error_page.unsupported.body",
+        ErrorPageKind::InvalidArgument => "error_page.invalid_argument.body",
+        ErrorPageKind::Other => "error_page.other.body",
+    };
+    locale.format(key, &[&host])
+}
+
+/// Markdown-kilden til feilsiden
+fn error_markdown(locale: Locale, kind: ErrorPageKind, url: &str, details: &str) -> String {
+    let parsed = url::Url::parse(url).ok();
+    let host = parsed
+        .as_ref()
+        .and_then(|u| u.host_str())
+        .unwrap_or(url)
+        .to_string();
+
+    let mut output = format!(
+        "# {}\n\n{}\n\n{}: `{}`\n",
+        title(locale, kind),
+        explanation(locale, kind, &host),
+        locale.text("error_page.address"),
+        url.replace('`', "%60")
+    );
+
+    let details = details.trim();
+    if !details.is_empty() {
+        output.push('\n');
+        for line in details.lines() {
+            if line.is_empty() {
+                output.push_str(">\n");
+            } else {
+                output.push_str(&format!("> {}\n", line));
+            }
+        }
+    }
+
+    output.push('\n');
+    // Samme adresse gir samme feil når den er ugyldig eller sertifikatet er endret
+    if parsed.is_some() && kind != ErrorPageKind::CertificateChanged {
+        output.push_str(&format!(
+            "- [{}](<{}>)\n",
+            locale.text("error_page.retry"),
+            link_destination(url)
+        ));
+    }
+    if parsed
+        .as_ref()
+        .is_some_and(|u| u.scheme() == "http" || u.scheme() == "https")
+    {
+        let encoded: String = url::form_urlencoded::byte_serialize(url.as_bytes()).collect();
+        output.push_str(&format!(
+            "- [{}]({}?url={})\n",
+            locale.text("error_page.open_externally"),
+            OPEN_EXTERNALLY_URL,
+            encoded
+        ));
+    }
+    output.push_str(&format!(
+        "- [{}]({})\n",
+        locale.text("error_page.back"),
+        BACK_URL
+    ));
+    output
+}
+
+/// Adressen som lenkemål innenfor `<…>`
+fn link_destination(url: &str) -> String {
+    url.replace('<', "%3C").replace('>', "%3E")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dns_page() {
+        assert_eq!(
+            error_markdown(
+                Locale::Nb,
+                ErrorPageKind::Dns,
+                "https://finnes.ikke.example/side?a=1",
+                "Nettverksfeil: dns error"
+            ),
+            "# Fant ikke serveren\n\
+             \n\
+             Navnet finnes.ikke.example kunne ikke slås opp. Sjekk at adressen er skrevet riktig og at du er koblet til nettet.\n\
+             \n\
+             Adresse: `https://finnes.ikke.example/side?a=1`\n\
+             \n\
+             > Nettverksfeil: dns error\n\
+             \n\
+             - [Prøv igjen](<https://finnes.ikke.example/side?a=1>)\n\
+             - [Åpne i standardnettleseren](bare://open-externally?url=https%3A%2F%2Ffinnes.ikke.example%2Fside%3Fa%3D1)\n\
+             - [Gå tilbake](bare://back)\n"
+        );
+    }
+
+    #[test]
+    fn test_timeout_page() {
+        assert_eq!(
+            error_markdown(
+                Locale::Nb,
+                ErrorPageKind::Timeout,
+                "gemini://treg.example/",
+                "Timeout etter 30 sekunder"
+            ),
+            "# Serveren brukte for lang tid\n\
+             \n\
+             treg.example svarte ikke i tide. Prøv igjen om litt, eller øk tidsavbruddet i innstillingene.\n\
+             \n\
+             Adresse: `gemini://treg.example/`\n\
+             \n\
+             > Timeout etter 30 sekunder\n\
+             \n\
+             - [Prøv igjen](<gemini://treg.example/>)\n\
+             - [Gå tilbake](bare://back)\n"
+        );
+    }
+
+    #[test]
+    fn test_not_found_page() {
+        assert_eq!(
+            error_markdown(
+                Locale::En,
+                ErrorPageKind::NotFound,
+                "https://a.example/borte.md",
+                ""
+            ),
+            "# Page not found\n\
+             \n\
+             The server has no page at this address. It may have been moved or deleted.\n\
+             \n\
+             Address: `https://a.example/borte.md`\n\
+             \n\
+             - [Try again](<https://a.example/borte.md>)\n\
+             - [Open in your default browser](bare://open-externally?url=https%3A%2F%2Fa.example%2Fborte.md)\n\
+             - [Go back](bare://back)\n"
+        );
+    }
+
+    #[test]
+    fn test_certificate_changed_page() {
+        assert_eq!(
+            error_markdown(
+                Locale::Nb,
+                ErrorPageKind::CertificateChanged,
+                "gemini://a.example/",
+                "Gammelt fingerprint: aa\n\nNytt fingerprint: bb"
+            ),
+            "# Sertifikatet er endret\n\
+             \n\
+             Sertifikatet til a.example er et annet enn sist. Det kan være fornyet, men det kan også være noen som utgir seg for å være serveren.\n\
+             \n\
+             Adresse: `gemini://a.example/`\n\
+             \n\
+             > Gammelt fingerprint: aa\n\
+             >\n\
+             > Nytt fingerprint: bb\n\
+             \n\
+             - [Gå tilbake](bare://back)\n"
+        );
+    }
+
+    #[test]
+    fn test_too_large_page() {
+        assert_eq!(
+            error_markdown(
+                Locale::Nb,
+                ErrorPageKind::TooLarge,
+                "gopher://a.example/9/stor.bin",
+                "Responsen er for stor"
+            ),
+            "# Siden er for stor\n\
+             \n\
+             Siden er større enn grensen i innstillingene og ble ikke lastet ned.\n\
+             \n\
+             Adresse: `gopher://a.example/9/stor.bin`\n\
+             \n\
+             > Responsen er for stor\n\
+             \n\
+             - [Prøv igjen](<gopher://a.example/9/stor.bin>)\n\
+             - [Gå tilbake](bare://back)\n"
+        );
+    }
+
+    #[test]
+    fn test_invalid_argument_page() {
+        // Argumentet er ingen adresse, så det er ingenting å prøve igjen
+        assert_eq!(
+            error_markdown(
+                Locale::En,
+                ErrorPageKind::InvalidArgument,
+                "notater.md",
+                ""
+            ),
+            "# File or address not found\n\
+             \n\
+             “notater.md” from the command line is neither an existing file nor an address Bare can open.\n\
+             \n\
+             Address: `notater.md`\n\
+             \n\
+             - [Go back](bare://back)\n"
+        );
+    }
+
+    #[test]
+    fn test_unsupported_page() {
+        assert_eq!(
+            error_markdown(
+                Locale::Nb,
+                ErrorPageKind::Unsupported,
+                "ftp://a.example/fil`navn",
+                "Ustøttet protokoll: ftp"
+            ),
+            "# Kan ikke vises\n\
+             \n\
+             Bare kan ikke vise innhold fra a.example.\n\
+             \n\
+             Adresse: `ftp://a.example/fil%60navn`\n\
+             \n\
+             > Ustøttet protokoll: ftp\n\
+             \n\
+             - [Prøv igjen](<ftp://a.example/fil`navn>)\n\
+             - [Gå tilbake](bare://back)\n"
+        );
+    }
+
+    #[test]
+    fn test_attach_only_to_failures() {
+        let error = attach(
+            CommandError::Network {
+                message: "Timeout".into(),
+                kind: NetworkErrorKind::Timeout,
+                page: None,
+            },
+            "https://a.example/",
+        );
+        let CommandError::Network {
+            page: Some(page), ..
+        } = &error
+        else {
+            panic!("feilside mangler: {:?}", error);
+        };
+        assert_eq!(page.url.as_deref(), Some("https://a.example/"));
+        assert!(page.html.contains("<h1"));
+        assert!(page.html.contains("href=\"bare://back\""));
+
+        let prompt = CommandError::conversion_prompt("https://a.example/");
+        assert_eq!(attach(prompt.clone(), "https://a.example/"), prompt);
+        let superseded = CommandError::superseded();
+        assert_eq!(attach(superseded.clone(), "https://a.example/"), superseded);
+    }
+}
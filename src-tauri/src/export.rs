@@ -0,0 +1,481 @@
+//! Eksport av sider til frittstående filer
+//!
+//! En eksportert HTML-fil inneholder alt den trenger: den rendrede siden,
+//! temaets stiler og bildene som data-URI-er. Den henter aldri noe fra nettet
+//! når den åpnes, så mottakeren avslører ingenting ved å lese den. Vanlige
+//! lenker beholdes, men følges bare hvis leseren klikker på dem.
+
+use crate::settings::{self, FontFamily, Settings, Theme, ThemeColors, Typography};
+use base64::Engine;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Maks størrelse på ett bilde som bygges inn (5 MB)
+pub const MAX_IMAGE_SIZE: usize = 5 * 1024 * 1024;
+
+/// Innholdssikkerhet for den eksporterte filen: bare innebygde stiler og bilder
+const EXPORT_CSP: &str = "default-src 'none'; img-src data:; style-src 'unsafe-inline'";
+
+/// Et HTML-element med attributter, f.eks. `<img src="..." alt="...">`
+static TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<([a-zA-Z][a-zA-Z0-9-]*)(\s[^<>]*)?>").unwrap());
+
+/// Ett attributt med verdi i et element
+static ATTRIBUTE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\s([a-zA-Z][a-zA-Z0-9:-]*)\s*=\s*("[^"]*"|'[^']*'|[^\s"'>]+)"#).unwrap()
+});
+
+/// Attributter som får nettleseren til å hente noe når siden åpnes
+const FETCHING_ATTRIBUTES: [&str; 6] = ["src", "srcset", "poster", "data", "background", "action"];
+
+/// Elementer som alltid fjernes fra en eksport
+const DROPPED_ELEMENTS: [&str; 4] = ["link", "meta", "base", "script"];
+
+/// Formatet en side eksporteres til
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// Én HTML-fil med stiler og bilder bygget inn
+    Html,
+    /// PDF via webviewens utskriftsdialog
+    Pdf,
+}
+
+/// Utseendet til en eksportert side, hentet fra innstillingene
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportStyle {
+    pub theme: Theme,
+    pub colors: Option<ThemeColors>,
+    pub font_family: FontFamily,
+    /// Skriftstørrelse i prosent
+    pub font_size: u32,
+    /// Maks innholdsbredde i piksler
+    pub content_width: u32,
+    pub typography: Typography,
+    /// Brukerens egen CSS, allerede renset for eksterne adresser
+    pub custom_css: Option<String>,
+}
+
+impl ExportStyle {
+    /// Utseendet slik brukeren ser siden
+    ///
+    /// # Arguments
+    /// * `settings` - Gjeldende innstillinger
+    /// * `font_size` - Skriftstørrelsen for nettstedet, med overstyringer
+    /// * `custom_css` - CSS fra `Settings::effective_css`
+    pub fn from_settings(settings: &Settings, font_size: u32, custom_css: Option<String>) -> Self {
+        Self {
+            theme: settings.theme.clone(),
+            colors: settings.custom_colors.clone(),
+            font_family: settings.font_family.clone(),
+            font_size,
+            content_width: settings.content_width,
+            typography: settings.typography.clone(),
+            custom_css,
+        }
+    }
+}
+
+/// Fargene i et tema
+struct Palette {
+    background: &'static str,
+    text: &'static str,
+    muted: &'static str,
+    link: &'static str,
+    code_background: &'static str,
+    border: &'static str,
+}
+
+const LIGHT: Palette = Palette {
+    background: "#fafaf8",
+    text: "#111",
+    muted: "#888",
+    link: "#0000ee",
+    code_background: "#f0efe8",
+    border: "#111",
+};
+
+const DARK: Palette = Palette {
+    background: "#0c0c0c",
+    text: "#d4d4d4",
+    muted: "#555",
+    link: "#33ff99",
+    code_background: "#161616",
+    border: "#33ff99",
+};
+
+const SEPIA: Palette = Palette {
+    background: "#f4ecd8",
+    text: "#3b2f22",
+    muted: "#8a7659",
+    link: "#8b4513",
+    code_background: "#ebe0c6",
+    border: "#8b4513",
+};
+
+impl Palette {
+    /// CSS-variablene for paletten, med brukerens egne farger over
+    fn variables(&self, colors: Option<&ThemeColors>) -> String {
+        let pick = |own: Option<&String>, theme: &str| own.map_or(theme.to_string(), String::clone);
+        let colors = colors.cloned().unwrap_or_default();
+        format!(
+            "--bg: {}; --text: {}; --muted: {}; --link: {}; --code-bg: {}; --border: {};",
+            pick(colors.background.as_ref(), self.background),
+            pick(colors.text.as_ref(), self.text),
+            self.muted,
+            pick(colors.link.as_ref(), self.link),
+            self.code_background,
+            pick(colors.accent.as_ref(), self.border),
+        )
+    }
+}
+
+/// Skriftfamilien som CSS-verdi
+fn font_stack(family: &FontFamily) -> String {
+    const BODY: &str =
+        "\"IBM Plex Sans\", -apple-system, BlinkMacSystemFont, \"Segoe UI\", system-ui, sans-serif";
+    match family {
+        FontFamily::System | FontFamily::SansSerif => BODY.to_string(),
+        FontFamily::Serif => "Georgia, \"Times New Roman\", serif".to_string(),
+        FontFamily::Mono => "\"IBM Plex Mono\", Consolas, Monaco, monospace".to_string(),
+        // Navnet er allerede validert, se `FontFamily::custom`
+        FontFamily::Custom(name) => format!("\"{}\", {}", name, BODY),
+    }
+}
+
+/// Stilarket som bygges inn i den eksporterte filen
+pub fn stylesheet(style: &ExportStyle) -> String {
+    let colors = style.colors.as_ref();
+    let root = match style.theme {
+        Theme::Light => format!(":root {{ {} }}", LIGHT.variables(colors)),
+        Theme::Dark => format!(":root {{ {} }}", DARK.variables(colors)),
+        Theme::Sepia => format!(":root {{ {} }}", SEPIA.variables(colors)),
+        // Mottakerens eget valg av lys eller mørk bestemmer
+        Theme::System => format!(
+            ":root {{ {} }}\n@media (prefers-color-scheme: dark) {{ :root {{ {} }} }}",
+            LIGHT.variables(colors),
+            DARK.variables(colors)
+        ),
+    };
+    let typography = &style.typography;
+    let mut css = format!(
+        "{root}
+body {{ margin: 0; padding: 24px; background: var(--bg); color: var(--text); font-family: {font}; font-size: {size}%; }}
+.markdown-body {{ max-width: {width}px; margin: 0 auto; line-height: {line_height}; text-align: {align}; }}
+.markdown-body p {{ margin: 0 0 {spacing}em; }}
+.markdown-body a {{ color: var(--link); }}
+.markdown-body h1, .markdown-body h2 {{ border-bottom: 2px solid var(--border); padding-bottom: 4px; line-height: 1.2; }}
+.markdown-body pre, .markdown-body code {{ background: var(--code-bg); font-family: \"IBM Plex Mono\", Consolas, Monaco, monospace; }}
+.markdown-body pre {{ padding: 12px; overflow-x: auto; }}
+.markdown-body blockquote {{ margin: 0; padding-left: 16px; border-left: 3px solid var(--border); color: var(--muted); }}
+.markdown-body img {{ max-width: 100%; }}
+.markdown-body table {{ border-collapse: collapse; }}
+.markdown-body th, .markdown-body td {{ border: 1px solid var(--muted); padding: 4px 8px; }}
+",
+        font = font_stack(&style.font_family),
+        size = style.font_size,
+        width = style.content_width,
+        line_height = f64::from(typography.line_height) / 100.0,
+        spacing = f64::from(typography.paragraph_spacing) / 100.0,
+        align = if typography.justify_text { "justify" } else { "start" },
+    );
+    if let Some(custom) = &style.custom_css {
+        css.push_str(&settings::sanitize_css(custom));
+        css.push('\n');
+    }
+    css
+}
+
+/// Bildeadressene i en rendret side som kan bygges inn
+///
+/// # Returns
+/// Hver adresse én gang, i rekkefølgen de står i; data-URI-er tas ikke med
+pub fn image_sources(html: &str) -> Vec<String> {
+    let mut sources = Vec::new();
+    for tag in TAG.captures_iter(html) {
+        if !tag[1].eq_ignore_ascii_case("img") {
+            continue;
+        }
+        let Some(attributes) = tag.get(2) else {
+            continue;
+        };
+        for attribute in ATTRIBUTE.captures_iter(attributes.as_str()) {
+            if !attribute[1].eq_ignore_ascii_case("src") {
+                continue;
+            }
+            let src = unescape(unquote(&attribute[2]));
+            if !is_data_uri(&src) && !sources.contains(&src) {
+                sources.push(src);
+            }
+        }
+    }
+    sources
+}
+
+/// Lag en data-URI av et bilde
+///
+/// # Arguments
+/// * `bytes` - Bildet
+/// * `content_type` - Content-Type fra serveren, hvis kjent
+///
+/// # Returns
+/// URI-en, eller None hvis innholdet ikke er et bilde eller er for stort
+pub fn image_data_uri(bytes: &[u8], content_type: Option<&str>) -> Option<String> {
+    if bytes.len() > MAX_IMAGE_SIZE {
+        return None;
+    }
+    let declared = content_type
+        .and_then(|ct| ct.split(';').next())
+        .map(|ct| ct.trim().to_ascii_lowercase())
+        .filter(|ct| ct.starts_with("image/"));
+    let mime = declared.or_else(|| sniff_image(bytes).map(str::to_string))?;
+    Some(format!(
+        "data:{};base64,{}",
+        mime,
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    ))
+}
+
+/// Gjenkjenn vanlige bildeformater på de første bytene
+fn sniff_image(bytes: &[u8]) -> Option<&'static str> {
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(256)]);
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if bytes.starts_with(&[0xff, 0xd8, 0xff]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WEBP") {
+        Some("image/webp")
+    } else if head.trim_start().starts_with("<svg") || head.contains("<svg") {
+        Some("image/svg+xml")
+    } else {
+        None
+    }
+}
+
+/// Bygg en frittstående HTML-fil av en rendret side
+///
+/// Bilder som finnes i `images` erstattes med data-URI-en sin. Alle andre
+/// attributter som ville hentet noe (`src`, `srcset`, `poster`, ...) fjernes,
+/// det samme gjør `<link>`, `<script>` og lignende. `<a href>` beholdes.
+///
+/// # Arguments
+/// * `title` - Sidetittelen
+/// * `body` - HTML-en fra markdown-rendreren
+/// * `style` - Utseendet
+/// * `images` - Bildeadresse -> data-URI, fra `image_sources`
+pub fn standalone_html(
+    title: &str,
+    body: &str,
+    style: &ExportStyle,
+    images: &HashMap<String, String>,
+) -> String {
+    format!(
+        "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<meta http-equiv=\"Content-Security-Policy\" content=\"{csp}\">
+<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">
+<title>{title}</title>
+<style>
+{css}</style>
+</head>
+<body>
+<article class=\"markdown-body\">
+{body}</article>
+</body>
+</html>
+",
+        csp = EXPORT_CSP,
+        title = escape(title),
+        css = stylesheet(style),
+        body = self_contained(body, images),
+    )
+}
+
+/// Skriv om alle elementer slik at ingenting hentes fra utsiden
+fn self_contained(html: &str, images: &HashMap<String, String>) -> String {
+    TAG.replace_all(html, |tag: &regex::Captures| {
+        let name = &tag[1];
+        if DROPPED_ELEMENTS
+            .iter()
+            .any(|d| name.eq_ignore_ascii_case(d))
+        {
+            return String::new();
+        }
+        let Some(attributes) = tag.get(2) else {
+            return tag[0].to_string();
+        };
+        let is_image = name.eq_ignore_ascii_case("img");
+        let attributes =
+            ATTRIBUTE.replace_all(attributes.as_str(), |attribute: &regex::Captures| {
+                let key = attribute[1].to_ascii_lowercase();
+                let value = unescape(unquote(&attribute[2]));
+                if key == "style" {
+                    return format!(" style=\"{}\"", escape(&settings::sanitize_css(&value)));
+                }
+                if key.starts_with("on") || (key == "href" && !name.eq_ignore_ascii_case("a")) {
+                    return String::new();
+                }
+                if !FETCHING_ATTRIBUTES.contains(&key.as_str()) {
+                    return attribute[0].to_string();
+                }
+                if is_data_uri(&value) {
+                    return attribute[0].to_string();
+                }
+                match images.get(&value) {
+                    Some(uri) if is_image && key == "src" => format!(" src=\"{}\"", uri),
+                    _ => String::new(),
+                }
+            });
+        format!("<{}{}>", name, attributes)
+    })
+    .into_owned()
+}
+
+/// Fjern anførselstegn rundt en attributtverdi
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+        .unwrap_or(value)
+}
+
+fn is_data_uri(value: &str) -> bool {
+    value.trim_start().to_ascii_lowercase().starts_with("data:")
+}
+
+/// Dekod HTML-entitetene rendreren bruker i attributter
+fn unescape(value: &str) -> String {
+    value
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// Kod tekst for bruk i HTML
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markdown;
+
+    const FIXTURE: &str = r#"# Reisebrev
+
+Se [kilden](https://example.com/kilde) og ![kart](https://example.com/kart.png "Kart").
+
+![mangler](https://example.com/borte.png)
+
+<img src="//cdn.example.com/sporing.gif" srcset="https://cdn.example.com/a.png 2x" onerror="x()">
+<video poster="http://example.com/p.jpg" src="https://example.com/v.mp4"></video>
+<link rel="stylesheet" href="https://example.com/stil.css">
+<p style="background: url(https://example.com/bg.png)">Bakgrunn</p>
+<iframe src="https://example.com/ramme"></iframe>
+"#;
+
+    fn style() -> ExportStyle {
+        ExportStyle::from_settings(&Settings::default(), 100, None)
+    }
+
+    /// Verdiene til alle attributter med et gitt navn
+    fn attribute_values(html: &str, names: &[&str]) -> Vec<String> {
+        TAG.captures_iter(html)
+            .filter_map(|tag| tag.get(2).map(|a| a.as_str().to_string()))
+            .flat_map(|attributes| {
+                ATTRIBUTE
+                    .captures_iter(&attributes)
+                    .filter(|a| names.contains(&a[1].to_ascii_lowercase().as_str()))
+                    .map(|a| unquote(&a[2]).to_string())
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_export_has_no_remote_references() {
+        let rendered = markdown::render_with_options(FIXTURE, &markdown::RenderOptions::default());
+        let sources = image_sources(&rendered.html);
+        assert!(sources.contains(&"https://example.com/kart.png".to_string()));
+
+        let png = b"\x89PNG\r\n\x1a\nrest".to_vec();
+        let images = HashMap::from([(
+            "https://example.com/kart.png".to_string(),
+            image_data_uri(&png, Some("image/png")).unwrap(),
+        )]);
+        let mut style = style();
+        style.custom_css = Some("body { background: url(https://example.com/x.png) }".into());
+        let html = standalone_html("Reisebrev", &rendered.html, &style, &images);
+
+        for value in attribute_values(&html, &["src", "srcset", "poster", "data", "href"]) {
+            let is_link = html.contains(&format!("<a href=\"{}\"", value));
+            assert!(
+                !value.contains("http") && !value.starts_with("//") || is_link,
+                "ekstern referanse: {}",
+                value
+            );
+        }
+        // Den eneste gjenværende adressen er den vanlige lenken
+        assert_eq!(html.matches("https://").count(), 1);
+        assert!(html.contains("<a href=\"https://example.com/kilde\">"));
+        assert!(html.contains("src=\"data:image/png;base64,"));
+        assert!(!html.contains("<link"));
+        assert!(!html.contains("onerror"));
+    }
+
+    #[test]
+    fn test_image_sources_are_unique_and_decoded() {
+        let html = r#"<p><img src="https://a.example/x.png?a=1&amp;b=2" alt=""><img src="https://a.example/x.png?a=1&amp;b=2"><img src="data:image/gif;base64,R0lG"></p>"#;
+        assert_eq!(image_sources(html), vec!["https://a.example/x.png?a=1&b=2"]);
+    }
+
+    #[test]
+    fn test_image_data_uri_requires_an_image() {
+        assert_eq!(
+            image_data_uri(b"GIF89a...", None).as_deref(),
+            Some("data:image/gif;base64,R0lGODlhLi4u")
+        );
+        assert!(image_data_uri(b"<html>", Some("text/html")).is_none());
+        assert!(image_data_uri(b"<svg xmlns=\"\"/>", Some("text/plain"))
+            .unwrap()
+            .starts_with("data:image/svg+xml;"));
+        assert!(image_data_uri(&vec![0; MAX_IMAGE_SIZE + 1], Some("image/png")).is_none());
+    }
+
+    #[test]
+    fn test_stylesheet_follows_settings() {
+        let mut style = style();
+        style.theme = Theme::Sepia;
+        style.content_width = 640;
+        style.colors = Some(ThemeColors {
+            link: Some("#123456".into()),
+            ..Default::default()
+        });
+        let css = stylesheet(&style);
+        assert!(css.contains("--bg: #f4ecd8"));
+        assert!(css.contains("--link: #123456"));
+        assert!(css.contains("max-width: 640px"));
+
+        style.theme = Theme::System;
+        assert!(stylesheet(&style).contains("prefers-color-scheme: dark"));
+    }
+
+    #[test]
+    fn test_title_is_escaped() {
+        let html = standalone_html("<Tom & Jerry>", "", &style(), &HashMap::new());
+        assert!(html.contains("<title>&lt;Tom &amp; Jerry&gt;</title>"));
+    }
+}
@@ -2,12 +2,23 @@
 //!
 //! Håndterer nettverksforespørsler for å hente markdown-filer fra internett.
 
+use crate::locale::{Locale, Localize};
+use crate::page_security::SecurityInfo;
+use crate::private_mode::PrivateMode;
+use crate::settings::{NetworkSettings, PrivacySettings};
+use crate::timing::NetworkTiming;
 use log::{debug, info, warn};
-use reqwest::header::{HeaderMap, ACCEPT, CONTENT_TYPE, USER_AGENT};
-use std::time::Duration;
+use reqwest::header::{
+    HeaderMap, HeaderName, HeaderValue, ACCEPT, CACHE_CONTROL, CONTENT_TYPE, DNT, PRAGMA, REFERER,
+    USER_AGENT,
+};
+use std::time::{Duration, Instant};
 use thiserror::Error;
 use url::Url;
 
+/// Accept-headeren ved henting av sider; markdown foretrekkes
+const PAGE_ACCEPT: &str = "text/markdown, text/plain;q=0.9, text/html;q=0.5";
+
 /// Feil som kan oppstå under henting av innhold
 #[derive(Debug, Error)]
 pub enum FetchError {
@@ -28,6 +39,23 @@ pub enum FetchError {
 
     #[error("Timeout: Serveren svarte ikke innen {0} sekunder")]
     Timeout(u64),
+
+    #[error("Responsen er for stor (maks {0} bytes)")]
+    TooLarge(usize),
+}
+
+impl Localize for FetchError {
+    fn localize_in(&self, locale: Locale) -> String {
+        match self {
+            Self::InvalidUrl(url) => locale.format("error.invalid_url", &[url]),
+            Self::UnsupportedScheme(scheme) => locale.format("error.unsupported_scheme", &[scheme]),
+            Self::Network(e) => locale.format("error.network", &[e]),
+            Self::NotFound(url) => locale.format("error.not_found", &[url]),
+            Self::ServerError(status, message) => locale.format("error.server", &[status, message]),
+            Self::Timeout(seconds) => locale.format("error.timeout", &[seconds]),
+            Self::TooLarge(max) => locale.format("error.too_large", &[max]),
+        }
+    }
 }
 
 /// Resultat fra en vellykket fetch-operasjon
@@ -35,18 +63,32 @@ pub enum FetchError {
 pub struct FetchResult {
     /// Innholdet som ble hentet
     pub content: String,
+    /// Bytene slik serveren sendte dem, før dekoding
+    pub raw: Vec<u8>,
     /// Content-Type header fra responsen
     pub content_type: Option<String>,
     /// Den endelige URL-en (etter eventuelle redirects)
     pub final_url: String,
     /// Om innholdet er markdown
     pub is_markdown: bool,
+    /// Forbindelsen til serveren som sendte svaret
+    pub security: SecurityInfo,
+    /// Tiden frem til headerne kom (`connect`) og til body var lest
+    pub timing: NetworkTiming,
 }
 
+/// Global Privacy Control; reqwest har ingen konstant for denne
+const SEC_GPC: HeaderName = HeaderName::from_static("sec-gpc");
+
 /// HTTP-klient for Bare
 pub struct Fetcher {
     client: reqwest::Client,
     timeout_seconds: u64,
+    max_response_size: usize,
+    /// Om Referer sendes når en lenke følges fra en annen side
+    send_referrer: bool,
+    /// Privat modus: adresser holdes utenfor loggen
+    private: PrivateMode,
 }
 
 impl Default for Fetcher {
@@ -58,20 +100,36 @@ impl Default for Fetcher {
 impl Fetcher {
     /// Opprett en ny Fetcher med standard innstillinger
     pub fn new() -> Self {
-        Self::with_timeout(30)
+        Self::with_limits(&NetworkSettings::default())
     }
 
-    /// Opprett en Fetcher med egendefinert timeout
-    pub fn with_timeout(timeout_seconds: u64) -> Self {
+    /// Opprett en Fetcher med grensene fra nettverksinnstillingene
+    pub fn with_limits(network: &NetworkSettings) -> Self {
+        Self::with_settings(network, &PrivacySettings::default())
+    }
+
+    /// Opprett en Fetcher med nettverks- og personverninnstillingene
+    ///
+    /// GPC- og DNT-headerne ligger i klientens standardheadere, så en endring
+    /// i personverninnstillingene krever en ny Fetcher.
+    pub fn with_settings(network: &NetworkSettings, privacy: &PrivacySettings) -> Self {
+        let timeout_seconds = network.timeout_seconds;
+        let redirects = || reqwest::redirect::Policy::limited(network.max_redirects as usize);
         // Prøv først å bygge klient med custom user agent
         let user_agent = format!("Bare/{} (Markdown Browser)", env!("CARGO_PKG_VERSION"));
 
         let client = if let Ok(value) = user_agent.parse() {
             let mut headers = HeaderMap::new();
             headers.insert(USER_AGENT, value);
+            if privacy.send_gpc {
+                headers.insert(SEC_GPC, HeaderValue::from_static("1"));
+                headers.insert(DNT, HeaderValue::from_static("1"));
+            }
 
             reqwest::Client::builder()
                 .timeout(Duration::from_secs(timeout_seconds))
+                .redirect(redirects())
+                .tls_info(true)
                 .default_headers(headers)
                 .build()
                 .ok()
@@ -84,6 +142,8 @@ impl Fetcher {
             warn!("Kunne ikke opprette HTTP-klient med headers. Prøver uten.");
             reqwest::Client::builder()
                 .timeout(Duration::from_secs(timeout_seconds))
+                .redirect(redirects())
+                .tls_info(true)
                 .build()
                 .ok()
         });
@@ -97,9 +157,17 @@ impl Fetcher {
         Self {
             client,
             timeout_seconds,
+            max_response_size: network.max_response_bytes(),
+            send_referrer: privacy.send_referrer,
+            private: PrivateMode::default(),
         }
     }
 
+    /// Slå privat modus av eller på
+    pub fn set_private(&self, private: bool) {
+        self.private.set_active(private);
+    }
+
     /// Valider og parse en URL
     pub fn validate_url(url_str: &str) -> Result<Url, FetchError> {
         let parsed = Url::parse(url_str)
@@ -126,35 +194,208 @@ impl Fetcher {
     }
 
     /// Hent innhold fra en URL
+    ///
+    /// Avbryter med FetchError::TooLarge hvis responsen er større enn
+    /// grensen i nettverksinnstillingene.
     pub async fn fetch(&self, url_str: &str) -> Result<FetchResult, FetchError> {
+        self.fetch_from(url_str, None).await
+    }
+
+    /// Hent innhold fra en URL som ble åpnet fra en annen side
+    ///
+    /// # Arguments
+    /// * `url_str` - URL som skal hentes
+    /// * `referrer` - Siden lenken ble fulgt fra; sendes bare hvis
+    ///   personverninnstillingene tillater det
+    pub async fn fetch_from(
+        &self,
+        url_str: &str,
+        referrer: Option<&str>,
+    ) -> Result<FetchResult, FetchError> {
+        self.fetch_with(url_str, referrer, false).await
+    }
+
+    /// Hent innhold fra en URL, eventuelt forbi alle mellomlagre
+    ///
+    /// # Arguments
+    /// * `url_str` - URL som skal hentes
+    /// * `referrer` - Siden lenken ble fulgt fra, se `fetch_from`
+    /// * `bypass_cache` - Be serveren og mellomtjenere om en fersk kopi
+    ///   (`Cache-Control: no-cache`), som ved Ctrl+Shift+R
+    pub async fn fetch_with(
+        &self,
+        url_str: &str,
+        referrer: Option<&str>,
+        bypass_cache: bool,
+    ) -> Result<FetchResult, FetchError> {
+        let (response, mut result) = self
+            .send(url_str, referrer, bypass_cache, PAGE_ACCEPT)
+            .await?;
+        let started = Instant::now();
+        let bytes = self.read_body(response).await?;
+        result.timing.transfer = Some(started.elapsed());
+        result.content = decode_text(&bytes, result.content_type.as_deref());
+        result.raw = bytes;
+        Ok(result)
+    }
+
+    /// Hent en binær ressurs, f.eks. et bilde som skal bygges inn i en eksport
+    ///
+    /// # Arguments
+    /// * `url_str` - URL som skal hentes
+    /// * `accept` - Accept-headeren, f.eks. "image/*"
+    ///
+    /// # Returns
+    /// Innholdet og Content-Type fra serveren
+    pub async fn fetch_bytes(
+        &self,
+        url_str: &str,
+        accept: &str,
+    ) -> Result<(Vec<u8>, Option<String>), FetchError> {
+        let (response, result) = self.send(url_str, None, false, accept).await?;
+        let bytes = self.read_body(response).await?;
+        Ok((bytes, result.content_type))
+    }
+
+    /// Start en nedlasting uten å lese innholdet
+    ///
+    /// Størrelsesgrensen for sider gjelder ikke, siden innholdet strømmes
+    /// rett til disk.
+    ///
+    /// # Returns
+    /// Responsen, klar til å leses bit for bit
+    pub async fn open_download(&self, url_str: &str) -> Result<reqwest::Response, FetchError> {
+        let (response, _) = self.send(url_str, None, false, "*/*").await?;
+        Ok(response)
+    }
+
+    /// Les hele body-en, men ikke mer enn grensen i nettverksinnstillingene
+    async fn read_body(&self, mut response: reqwest::Response) -> Result<Vec<u8>, FetchError> {
+        if response
+            .content_length()
+            .is_some_and(|length| length > self.max_response_size as u64)
+        {
+            return Err(FetchError::TooLarge(self.max_response_size));
+        }
+
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(|e| self.map_error(e))? {
+            if bytes.len() + chunk.len() > self.max_response_size {
+                return Err(FetchError::TooLarge(self.max_response_size));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        debug!("Fetched {} bytes", bytes.len());
+        Ok(bytes)
+    }
+
+    /// Hent bare starten av en side
+    ///
+    /// Leser til minst `max_bytes` er mottatt og avbryter resten, f.eks. for
+    /// å finne `<title>` uten å laste ned hele siden.
+    ///
+    /// # Arguments
+    /// * `url_str` - URL som skal hentes
+    /// * `max_bytes` - Omtrentlig maksimal mengde som leses
+    pub async fn fetch_prefix(
+        &self,
+        url_str: &str,
+        max_bytes: usize,
+    ) -> Result<FetchResult, FetchError> {
+        let (mut response, mut result) = self.send(url_str, None, false, PAGE_ACCEPT).await?;
+
+        let mut bytes = Vec::new();
+        while bytes.len() < max_bytes {
+            match response.chunk().await.map_err(|e| self.map_error(e))? {
+                Some(chunk) => bytes.extend_from_slice(&chunk),
+                None => break,
+            }
+        }
+        debug!("Fetched {} bytes (prefix)", bytes.len());
+
+        result.content = String::from_utf8_lossy(&bytes).to_string();
+        Ok(result)
+    }
+
+    /// Skill tidsavbrudd fra andre nettverksfeil
+    fn map_error(&self, error: reqwest::Error) -> FetchError {
+        if error.is_timeout() {
+            FetchError::Timeout(self.timeout_seconds)
+        } else {
+            FetchError::Network(error)
+        }
+    }
+
+    /// Referer-verdien for en forespørsel, hvis den skal sendes
+    ///
+    /// Sender bare opprinnelsen (skjema, vert og port), og aldri fra https
+    /// til http, som `strict-origin` i nettlesere.
+    fn referer_for(&self, referrer: Option<&str>, target: &Url) -> Option<String> {
+        if !self.send_referrer {
+            return None;
+        }
+        let referrer = Url::parse(referrer?).ok()?;
+        if !matches!(referrer.scheme(), "http" | "https")
+            || (referrer.scheme() == "https" && target.scheme() == "http")
+        {
+            return None;
+        }
+        Some(format!("{}/", referrer.origin().ascii_serialization()))
+    }
+
+    /// Send en GET-forespørsel og sjekk statuskoden
+    ///
+    /// # Returns
+    /// Responsen med body uhentet, og et FetchResult med tomt innhold
+    async fn send(
+        &self,
+        url_str: &str,
+        referrer: Option<&str>,
+        bypass_cache: bool,
+        accept: &str,
+    ) -> Result<(reqwest::Response, FetchResult), FetchError> {
         let url = Self::validate_url(url_str)?;
-        info!("Fetching content from: {}", url);
+        info!(
+            "Fetching content from: {}",
+            self.private.log_url(url.as_str())
+        );
 
-        let response = self
-            .client
-            .get(url.as_str())
-            .header(ACCEPT, "text/markdown, text/plain;q=0.9, text/html;q=0.5")
-            .send()
-            .await
-            .map_err(|e| {
-                if e.is_timeout() {
-                    FetchError::Timeout(self.timeout_seconds)
-                } else {
-                    FetchError::Network(e)
-                }
-            })?;
+        let mut request = self.client.get(url.as_str()).header(ACCEPT, accept);
+        if let Some(referer) = self.referer_for(referrer, &url) {
+            request = request.header(REFERER, referer);
+        }
+        if bypass_cache {
+            request = request
+                .header(CACHE_CONTROL, "no-cache")
+                .header(PRAGMA, "no-cache");
+        }
+        let started = Instant::now();
+        let response = request.send().await.map_err(|e| self.map_error(e))?;
+        let timing = NetworkTiming {
+            connect: Some(started.elapsed()),
+            ..Default::default()
+        };
 
         let status = response.status();
         let final_url = response.url().to_string();
+        let security = SecurityInfo::from_http(&url, &response);
 
-        debug!("Response status: {} for {}", status, final_url);
+        debug!(
+            "Response status: {} for {}",
+            status,
+            self.private.log_url(&final_url)
+        );
 
         if status.as_u16() == 404 {
             return Err(FetchError::NotFound(final_url));
         }
 
         if !status.is_success() {
-            warn!("Non-success status: {} for {}", status, final_url);
+            warn!(
+                "Non-success status: {} for {}",
+                status,
+                self.private.log_url(&final_url)
+            );
             return Err(FetchError::ServerError(status.as_u16(), final_url.clone()));
         }
 
@@ -177,16 +418,38 @@ impl Fetcher {
             content_type, is_markdown
         );
 
-        let content = response.text().await.map_err(FetchError::Network)?;
-        debug!("Fetched {} bytes", content.len());
+        Ok((
+            response,
+            FetchResult {
+                content: String::new(),
+                raw: Vec::new(),
+                content_type,
+                final_url,
+                is_markdown,
+                security,
+                timing,
+            },
+        ))
+    }
+}
 
-        Ok(FetchResult {
-            content,
-            content_type,
-            final_url,
-            is_markdown,
+/// Dekod en respons-body som tekst
+///
+/// Bruker charset fra Content-Type hvis den finnes, ellers UTF-8, slik
+/// `reqwest::Response::text` gjør.
+fn decode_text(bytes: &[u8], content_type: Option<&str>) -> String {
+    let encoding = content_type
+        .and_then(|ct| {
+            ct.split(';').find_map(|part| {
+                let (key, value) = part.trim().split_once('=')?;
+                key.eq_ignore_ascii_case("charset")
+                    .then(|| value.trim_matches('"').trim())
+            })
         })
-    }
+        .and_then(|label| encoding_rs::Encoding::for_label(label.as_bytes()))
+        .unwrap_or(encoding_rs::UTF_8);
+    let (text, _, _) = encoding.decode(bytes);
+    text.into_owned()
 }
 
 /// Sjekk om en URL bruker Gemini-protokollen
@@ -195,6 +458,49 @@ pub fn is_gemini_url(url_str: &str) -> bool {
     url_str.starts_with("gemini://")
 }
 
+/// Spørreparametere som bare brukes til sporing
+const TRACKING_PARAMS: [&str; 12] = [
+    "fbclid", "gclid", "dclid", "gbraid", "wbraid", "msclkid", "yclid", "igshid", "mc_cid",
+    "mc_eid", "_ga", "_gl",
+];
+
+/// Fjern sporingsparametere fra en http(s)-adresse
+///
+/// Fjerner `utm_*` og parameterne i `TRACKING_PARAMS`; andre parametere
+/// beholdes i samme rekkefølge. Andre protokoller og ugyldige adresser
+/// returneres uendret.
+///
+/// # Arguments
+/// * `url_str` - Adressen som skal renses
+pub fn strip_tracking_params(url_str: &str) -> String {
+    let Ok(mut url) = Url::parse(url_str) else {
+        return url_str.to_string();
+    };
+    if !matches!(url.scheme(), "http" | "https") || url.query().is_none() {
+        return url_str.to_string();
+    }
+
+    let is_tracking = |key: &str| {
+        let key = key.to_ascii_lowercase();
+        key.starts_with("utm_") || TRACKING_PARAMS.contains(&key.as_str())
+    };
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(key, _)| !is_tracking(key))
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    if pairs.len() == url.query_pairs().count() {
+        return url_str.to_string();
+    }
+
+    if pairs.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    url.to_string()
+}
+
 /// Løs en relativ URL mot en base-URL
 pub fn resolve_url(base: &str, relative: &str) -> Result<String, FetchError> {
     // Hvis det allerede er en absolutt URL, returner den
@@ -226,6 +532,21 @@ pub fn resolve_url(base: &str, relative: &str) -> Result<String, FetchError> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::page_security::Protocol;
+
+    #[test]
+    fn test_error_localized() {
+        let error = FetchError::ServerError(503, "Service Unavailable".to_string());
+        assert_eq!(error.localize_in(Locale::Nb), error.to_string());
+        assert_eq!(
+            error.localize_in(Locale::En),
+            "Server error (503): Service Unavailable"
+        );
+        assert_eq!(
+            FetchError::Timeout(30).localize_in(Locale::En),
+            "Timeout: The server did not respond within 30 seconds"
+        );
+    }
 
     #[test]
     fn test_validate_url_https() {
@@ -316,4 +637,198 @@ mod tests {
         assert!(!is_gemini_url("https://example.com"));
         assert!(!is_gemini_url("http://example.com"));
     }
+
+    /// Lytter som godtar tilkoblinger men aldri svarer
+    async fn silent_server() -> std::net::SocketAddr {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut open = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                open.push(socket);
+            }
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_fetch_honors_timeout_from_settings() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        let addr = silent_server().await;
+        let fetcher = Fetcher::with_limits(&NetworkSettings {
+            timeout_seconds: 1,
+            ..Default::default()
+        });
+
+        let started = std::time::Instant::now();
+        let result = fetcher.fetch(&format!("http://{}/side.md", addr)).await;
+        assert!(matches!(result, Err(FetchError::Timeout(1))));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_strip_tracking_params() {
+        assert_eq!(
+            strip_tracking_params("https://example.com/a?utm_source=x&id=7&fbclid=abc#top"),
+            "https://example.com/a?id=7#top"
+        );
+        assert_eq!(
+            strip_tracking_params("https://example.com/a?UTM_Medium=x"),
+            "https://example.com/a"
+        );
+        // Uendret når det ikke er noe å fjerne, og for andre protokoller
+        assert_eq!(
+            strip_tracking_params("https://example.com/a?q=a+b&page=2"),
+            "https://example.com/a?q=a+b&page=2"
+        );
+        assert_eq!(
+            strip_tracking_params("gemini://example.com/?utm_source=x"),
+            "gemini://example.com/?utm_source=x"
+        );
+    }
+
+    /// Server som svarer på én forespørsel og returnerer headerne den fikk
+    async fn recording_server() -> (std::net::SocketAddr, tokio::task::JoinHandle<String>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let handle = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let n = socket.read(&mut buf).await.unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: text/markdown\r\nContent-Length: 2\r\nConnection: close\r\n\r\n# ")
+                .await
+                .unwrap();
+            String::from_utf8_lossy(&request).to_lowercase()
+        });
+        (addr, handle)
+    }
+
+    #[tokio::test]
+    async fn test_referrer_only_sent_when_enabled() {
+        let referrer = Some("http://forrige.example/sti/side.md?hemmelig=1");
+
+        let (addr, request) = recording_server().await;
+        let fetcher = Fetcher::new();
+        let url = format!("http://{}/side.md", addr);
+        fetcher.fetch_from(&url, referrer).await.unwrap();
+        assert!(!request.await.unwrap().contains("referer:"));
+
+        let (addr, request) = recording_server().await;
+        let fetcher = Fetcher::with_settings(
+            &NetworkSettings::default(),
+            &PrivacySettings {
+                send_referrer: true,
+                ..Default::default()
+            },
+        );
+        let url = format!("http://{}/side.md", addr);
+        fetcher.fetch_from(&url, referrer).await.unwrap();
+        let request = request.await.unwrap();
+        assert!(request.contains("referer: http://forrige.example/\r\n"));
+        assert!(!request.contains("hemmelig"));
+
+        // Aldri fra https til http
+        let target = Url::parse("http://example.com/").unwrap();
+        assert_eq!(
+            fetcher.referer_for(Some("https://sikker.example/side"), &target),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bypass_cache_sends_no_cache() {
+        let (addr, request) = recording_server().await;
+        let url = format!("http://{}/side.md", addr);
+        Fetcher::new().fetch_from(&url, None).await.unwrap();
+        assert!(!request.await.unwrap().contains("no-cache"));
+
+        let (addr, request) = recording_server().await;
+        let url = format!("http://{}/side.md", addr);
+        let result = Fetcher::new().fetch_with(&url, None, true).await.unwrap();
+        assert_eq!(result.content, "# ");
+        let request = request.await.unwrap();
+        assert!(request.contains("cache-control: no-cache\r\n"));
+        assert!(request.contains("pragma: no-cache\r\n"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_bytes_sends_accept_and_returns_content_type() {
+        let (addr, request) = recording_server().await;
+        let url = format!("http://{}/bilde.png", addr);
+        let (bytes, content_type) = Fetcher::new().fetch_bytes(&url, "image/*").await.unwrap();
+        assert_eq!(bytes, b"# ");
+        assert_eq!(content_type.as_deref(), Some("text/markdown"));
+        assert!(request.await.unwrap().contains("accept: image/*\r\n"));
+    }
+
+    #[tokio::test]
+    async fn test_gpc_headers_only_sent_when_enabled() {
+        let (addr, request) = recording_server().await;
+        let url = format!("http://{}/side.md", addr);
+        Fetcher::new().fetch(&url).await.unwrap();
+        let request = request.await.unwrap();
+        assert!(!request.contains("sec-gpc:"));
+        assert!(!request.contains("dnt:"));
+
+        let (addr, request) = recording_server().await;
+        let fetcher = Fetcher::with_settings(
+            &NetworkSettings::default(),
+            &PrivacySettings {
+                send_gpc: true,
+                ..Default::default()
+            },
+        );
+        let url = format!("http://{}/side.md", addr);
+        fetcher.fetch(&url).await.unwrap();
+        let request = request.await.unwrap();
+        assert!(request.contains("sec-gpc: 1\r\n"));
+        assert!(request.contains("dnt: 1\r\n"));
+    }
+
+    #[test]
+    fn test_decode_text_uses_charset() {
+        assert_eq!(decode_text("blåbær".as_bytes(), None), "blåbær");
+        assert_eq!(
+            decode_text(b"bl\xe5b\xe6r", Some("text/plain; charset=ISO-8859-1")),
+            "blåbær"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_keeps_raw_bytes() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        const BODY: &[u8] = b"<p>Bl\xe5b\xe6rsyltet\xf8y</p>\r\n";
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = socket.read(&mut buf).await;
+            let head = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=iso-8859-1\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                BODY.len()
+            );
+            socket.write_all(head.as_bytes()).await.unwrap();
+            socket.write_all(BODY).await.unwrap();
+        });
+
+        let result = Fetcher::new()
+            .fetch(&format!("http://{}/", addr))
+            .await
+            .unwrap();
+        assert_eq!(result.raw, BODY);
+        assert_eq!(result.content, "<p>Blåbærsyltetøy</p>\r\n");
+        assert_eq!(result.security, SecurityInfo::plain(Protocol::Http));
+    }
 }
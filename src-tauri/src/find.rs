@@ -0,0 +1,312 @@
+//! Søk i side
+//!
+//! Finner treff i den rene teksten til et markdown-dokument og knytter hvert
+//! treff til nærmeste foregående overskrift, slik at frontend kan rulle til
+//! riktig seksjon.
+
+use crate::locale::{Locale, Localize};
+use crate::markdown;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Maksimal lengde på søket (i tegn)
+const MAX_QUERY_LENGTH: usize = 256;
+
+/// Maksimalt antall treff som returneres
+const MAX_MATCHES: usize = 1000;
+
+/// Maksimal størrelse på kompilert regex (i bytes)
+const MAX_REGEX_SIZE: usize = 1 << 20;
+
+/// Antall tegn med kontekst før og etter et treff
+const CONTEXT_CHARS: usize = 40;
+
+/// Feil som kan oppstå ved søk i side
+#[derive(Debug, Error)]
+pub enum FindError {
+    #[error("Søket er tomt")]
+    EmptyQuery,
+
+    #[error("Søket er for langt ({0} tegn, maks {MAX_QUERY_LENGTH})")]
+    QueryTooLong(usize),
+
+    #[error("Ugyldig søkemønster: {0}")]
+    InvalidPattern(String),
+}
+
+impl Localize for FindError {
+    fn localize_in(&self, locale: Locale) -> String {
+        match self {
+            Self::EmptyQuery => locale.text("error.find_empty").to_string(),
+            Self::QueryTooLong(length) => {
+                locale.format("error.find_too_long", &[length, &MAX_QUERY_LENGTH])
+            }
+            Self::InvalidPattern(e) => locale.format("error.find_invalid_pattern", &[e]),
+        }
+    }
+}
+
+/// Hvordan søketeksten tolkes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FindMode {
+    /// Vanlig tekst, treff hvor som helst
+    #[default]
+    Literal,
+    /// Vanlig tekst, kun hele ord
+    Word,
+    /// Regulært uttrykk
+    Regex,
+}
+
+/// Ett treff med kontekst
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FindMatch {
+    /// Indeks i dokumentets overskriftsliste for nærmeste foregående overskrift
+    pub heading_index: Option<usize>,
+    /// Fragment-id for den samme overskriften
+    pub heading_id: Option<String>,
+    /// Tekst rett før treffet
+    pub before: String,
+    /// Selve treffet
+    pub matched: String,
+    /// Tekst rett etter treffet
+    pub after: String,
+}
+
+/// Resultat fra søk i side
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FindResult {
+    /// Treff i dokumentrekkefølge (maks `MAX_MATCHES`)
+    pub matches: Vec<FindMatch>,
+    /// Totalt antall treff i dokumentet
+    pub total: usize,
+    /// Om `matches` ble avkortet
+    pub truncated: bool,
+}
+
+/// Søker etter tekst i et markdown-dokument
+///
+/// Dokumentet deles i seksjoner ved overskriftene, og hver seksjon søkes i
+/// som ren tekst. Treff overlapper aldri: "aa" i "aaaa" gir to treff.
+///
+/// # Arguments
+/// * `content` - Markdown-tekst
+/// * `query` - Søketekst eller regulært uttrykk
+/// * `case_sensitive` - Skill mellom store og små bokstaver
+/// * `mode` - Hvordan søket tolkes
+///
+/// # Returns
+/// Treff med kontekst og totalt antall, eller FindError
+pub fn find_in_document(
+    content: &str,
+    query: &str,
+    case_sensitive: bool,
+    mode: FindMode,
+) -> Result<FindResult, FindError> {
+    let pattern = build_pattern(query, case_sensitive, mode)?;
+    let headings = markdown::extract_headings(content);
+
+    let mut matches = Vec::new();
+    let mut total = 0;
+
+    for section in markdown::split_sections(content) {
+        let text = markdown::to_plain_text(section.source, false);
+        for found in pattern.find_iter(&text).filter(|m| !m.is_empty()) {
+            total += 1;
+            if matches.len() >= MAX_MATCHES {
+                continue;
+            }
+            matches.push(FindMatch {
+                heading_index: section.heading_index,
+                heading_id: section
+                    .heading_index
+                    .and_then(|index| headings.get(index))
+                    .map(|heading| heading.id.clone()),
+                before: context_before(&text[..found.start()]),
+                matched: found.as_str().to_string(),
+                after: context_after(&text[found.end()..]),
+            });
+        }
+    }
+
+    Ok(FindResult {
+        truncated: total > matches.len(),
+        matches,
+        total,
+    })
+}
+
+/// Bygger et regulært uttrykk for søket
+///
+/// Alle moduser går via regex-motoren slik at Unicode-bokstaver sammenlignes
+/// uten hensyn til store/små bokstaver og posisjonene i teksten stemmer.
+fn build_pattern(query: &str, case_sensitive: bool, mode: FindMode) -> Result<Regex, FindError> {
+    if query.is_empty() {
+        return Err(FindError::EmptyQuery);
+    }
+    let length = query.chars().count();
+    if length > MAX_QUERY_LENGTH {
+        return Err(FindError::QueryTooLong(length));
+    }
+
+    let pattern = match mode {
+        FindMode::Literal => regex::escape(query),
+        FindMode::Word => {
+            let is_word = |c: char| c.is_alphanumeric() || c == '_';
+            let start = if query.starts_with(is_word) {
+                r"\b"
+            } else {
+                ""
+            };
+            let end = if query.ends_with(is_word) { r"\b" } else { "" };
+            format!("{}{}{}", start, regex::escape(query), end)
+        }
+        FindMode::Regex => query.to_string(),
+    };
+
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!case_sensitive)
+        .size_limit(MAX_REGEX_SIZE)
+        .dfa_size_limit(MAX_REGEX_SIZE)
+        .build()
+        .map_err(|e| FindError::InvalidPattern(e.to_string()))
+}
+
+/// De siste tegnene før et treff, på én linje
+fn context_before(text: &str) -> String {
+    let start = text
+        .char_indices()
+        .rev()
+        .nth(CONTEXT_CHARS - 1)
+        .map_or(0, |(index, _)| index);
+    text[start..].replace('\n', " ")
+}
+
+/// De første tegnene etter et treff, på én linje
+fn context_after(text: &str) -> String {
+    text.chars()
+        .take(CONTEXT_CHARS)
+        .collect::<String>()
+        .replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find(content: &str, query: &str) -> FindResult {
+        find_in_document(content, query, false, FindMode::Literal).unwrap()
+    }
+
+    #[test]
+    fn test_overlapping_matches_are_not_counted_twice() {
+        let result = find("aaaa", "aa");
+        assert_eq!(result.total, 2);
+        assert_eq!(result.matches[0].after, "aa");
+        assert_eq!(result.matches[1].before, "aa");
+    }
+
+    #[test]
+    fn test_unicode_case_folding() {
+        let result = find("Ærlig talt, ÆRLIG TALT og ærlig talt.", "ærlig");
+        assert_eq!(result.total, 3);
+        assert_eq!(result.matches[1].matched, "ÆRLIG");
+
+        let result = find("ΣΟΦΊΑ", "σοφία");
+        assert_eq!(result.total, 1);
+    }
+
+    #[test]
+    fn test_case_sensitive() {
+        let result = find_in_document("Bare bare BARE", "bare", true, FindMode::Literal).unwrap();
+        assert_eq!(result.total, 1);
+        assert_eq!(result.matches[0].before, "Bare ");
+    }
+
+    #[test]
+    fn test_heading_association() {
+        let input = "treff i innledningen\n\n# Første\n\nEt treff her.\n\n## Andre del {#andre}\n\nOg et treff til.";
+        let result = find(input, "treff");
+        assert_eq!(result.total, 3);
+        assert_eq!(result.matches[0].heading_index, None);
+        assert_eq!(result.matches[0].heading_id, None);
+        assert_eq!(result.matches[1].heading_index, Some(0));
+        assert_eq!(result.matches[1].heading_id.as_deref(), Some("første"));
+        assert_eq!(result.matches[2].heading_index, Some(1));
+        assert_eq!(result.matches[2].heading_id.as_deref(), Some("andre"));
+    }
+
+    #[test]
+    fn test_searches_rendered_text_not_syntax() {
+        let result = find("Dette er **fet** tekst", "fet tekst");
+        assert_eq!(result.total, 1);
+        assert_eq!(find("[lenke](https://example.com)", "example").total, 0);
+    }
+
+    #[test]
+    fn test_whole_word_mode() {
+        let content = "kode, kodeblokk og kildekode";
+        let result = find_in_document(content, "kode", false, FindMode::Word).unwrap();
+        assert_eq!(result.total, 1);
+        assert_eq!(result.matches[0].after, ", kodeblokk og kildekode");
+    }
+
+    #[test]
+    fn test_regex_mode() {
+        let result =
+            find_in_document("v1.2 og v10.4", r"v\d+\.\d", false, FindMode::Regex).unwrap();
+        assert_eq!(result.total, 2);
+        assert_eq!(result.matches[1].matched, "v10.4");
+    }
+
+    #[test]
+    fn test_literal_mode_escapes_regex_syntax() {
+        assert_eq!(find("a.b axb", "a.b").total, 1);
+    }
+
+    #[test]
+    fn test_invalid_and_oversized_patterns() {
+        assert!(matches!(
+            find_in_document("tekst", "(", false, FindMode::Regex),
+            Err(FindError::InvalidPattern(_))
+        ));
+        assert!(matches!(
+            find_in_document("tekst", r"\w{1000}{1000}", false, FindMode::Regex),
+            Err(FindError::InvalidPattern(_))
+        ));
+        let long = "a".repeat(MAX_QUERY_LENGTH + 1);
+        assert!(matches!(
+            find_in_document("tekst", &long, false, FindMode::Literal),
+            Err(FindError::QueryTooLong(_))
+        ));
+        assert!(matches!(
+            find_in_document("tekst", "", false, FindMode::Literal),
+            Err(FindError::EmptyQuery)
+        ));
+    }
+
+    #[test]
+    fn test_empty_regex_matches_are_skipped() {
+        let result = find_in_document("abc", "x*", false, FindMode::Regex).unwrap();
+        assert_eq!(result.total, 0);
+    }
+
+    #[test]
+    fn test_match_cap() {
+        let content = "x ".repeat(MAX_MATCHES + 5);
+        let result = find(&content, "x");
+        assert_eq!(result.total, MAX_MATCHES + 5);
+        assert_eq!(result.matches.len(), MAX_MATCHES);
+        assert!(result.truncated);
+    }
+
+    #[test]
+    fn test_context_is_trimmed_to_window() {
+        let content = format!("{}mål{}", "ø".repeat(100), "å".repeat(100));
+        let result = find(&content, "mål");
+        assert_eq!(result.matches[0].before.chars().count(), CONTEXT_CHARS);
+        assert_eq!(result.matches[0].after.chars().count(), CONTEXT_CHARS);
+    }
+}
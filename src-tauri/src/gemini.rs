@@ -3,39 +3,59 @@
 //! Implementerer Gemini-protokollen (gemini://) med TOFU (Trust On First Use)
 //! sertifikathåndtering. Bruker TLS over TCP på port 1965.
 
+use crate::gemini_identity::{IdentityError, IdentityInfo, IdentityStore};
+use crate::locale::{Locale, Localize};
+use crate::page_security::{self, SecurityInfo};
+use crate::private_mode;
+use crate::settings::NetworkSettings;
+use crate::storage;
+use crate::timing::NetworkTiming;
 use log::{debug, info, warn};
+use parking_lot::Mutex;
 use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
 use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
 use rustls::{ClientConfig, DigitallySignedStruct, Error as TlsError, SignatureScheme};
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
 use std::collections::HashMap;
-use std::fs;
-use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use thiserror::Error;
-use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader, ReadHalf};
 use tokio::net::TcpStream;
+use tokio_rustls::client::TlsStream;
 use tokio_rustls::TlsConnector;
 use url::Url;
 
 /// Standard Gemini-port
 const DEFAULT_PORT: u16 = 1965;
 
-/// Maksimal respons-størrelse (5 MB)
-const MAX_RESPONSE_SIZE: usize = 5 * 1024 * 1024;
-
 /// Maksimal URL-lengde i bytes
 const MAX_URL_LENGTH: usize = 1024;
 
-/// Maksimalt antall redirects å følge
-const MAX_REDIRECTS: u8 = 5;
+/// Leser for resten av en Gemini-respons etter headeren
+pub type ResponseReader = BufReader<ReadHalf<TlsStream<TcpStream>>>;
+
+/// Svaret fra serveren frem til og med headeren
+struct ResponseHead {
+    /// Leser posisjonert rett etter headeren
+    reader: ResponseReader,
+    status: u8,
+    meta: String,
+    security: SecurityInfo,
+    /// Tilkobling, handshake og tiden frem til headeren kom
+    timing: NetworkTiming,
+    /// "host:port" det ble koblet til
+    host_port: String,
+    /// Om klienten hadde en identitet for verten
+    identified: bool,
+}
 
 /// Internt resultat fra en enkelt fetch-operasjon
 enum FetchOutcome {
     /// Ferdig resultat
-    Success(GeminiResponse),
+    Success(Box<GeminiResponse>),
     /// Redirect til ny URL
     Redirect(String),
 }
@@ -70,6 +90,11 @@ pub enum GeminiError {
     #[error("For mange redirects (maks {0})")]
     RedirectLoop(u8),
 
+    /// Gemini-klienter skal ikke følge redirects til andre protokoller
+    /// uten at brukeren godtar det
+    #[error("Serveren sender videre til en annen protokoll: {0}")]
+    CrossSchemeRedirect(String),
+
     #[error("Sertifikatet for {host} har endret seg!\nGammelt fingerprint: {old_fp}\nNytt fingerprint: {new_fp}\nDette kan indikere et man-in-the-middle angrep.")]
     CertificateChanged {
         host: String,
@@ -77,13 +102,53 @@ pub enum GeminiError {
         new_fp: String,
     },
 
-    #[error("Serveren krever klientsertifikat. Dette er ikke støttet ennå.")]
-    ClientCertRequired,
+    /// Status 60 uten at verten har en identitet
+    #[error("Serveren krever klientsertifikat: {meta}")]
+    ClientCertRequired { host: String, meta: String },
+
+    /// Status 6x selv om klienten sendte identiteten for verten
+    #[error("Serveren godtok ikke klientsertifikatet ({status}): {meta}")]
+    ClientCertRejected { status: u8, meta: String },
 
     #[error("Gemini-feil ({status}): {meta}")]
     ServerError { status: u8, meta: String },
 }
 
+impl Localize for GeminiError {
+    fn localize_in(&self, locale: Locale) -> String {
+        match self {
+            Self::InvalidUrl(url) => locale.format("error.invalid_url", &[url]),
+            Self::TlsError(e) => locale.format("error.tls", &[e]),
+            Self::ConnectionError(e) => locale.format("error.connection", &[e]),
+            Self::Timeout(seconds) => locale.format("error.timeout", &[seconds]),
+            Self::TooLarge(max) => locale.format("error.too_large", &[max]),
+            Self::InvalidResponse(e) => locale.format("error.invalid_response", &[e]),
+            Self::InputRequired(prompt) => locale.format("error.gemini_input", &[prompt]),
+            Self::SensitiveInputRequired(prompt) => {
+                locale.format("error.gemini_sensitive_input", &[prompt])
+            }
+            Self::RedirectLoop(max) => locale.format("error.redirect_loop", &[max]),
+            Self::CrossSchemeRedirect(url) => {
+                locale.format("error.gemini_cross_scheme_redirect", &[url])
+            }
+            Self::CertificateChanged {
+                host,
+                old_fp,
+                new_fp,
+            } => locale.format("error.gemini_certificate_changed", &[host, old_fp, new_fp]),
+            Self::ClientCertRequired { meta, .. } => {
+                locale.format("error.gemini_client_cert", &[meta])
+            }
+            Self::ClientCertRejected { status, meta } => {
+                locale.format("error.gemini_client_cert_rejected", &[status, meta])
+            }
+            Self::ServerError { status, meta } => {
+                locale.format("error.gemini_server", &[status, meta])
+            }
+        }
+    }
+}
+
 /// Respons fra en Gemini-server
 #[derive(Debug)]
 #[allow(dead_code)]
@@ -94,8 +159,14 @@ pub struct GeminiResponse {
     pub meta: String,
     /// Respons-body (kun for 2x-statuskoder)
     pub body: Option<String>,
+    /// Bytene i body slik serveren sendte dem
+    pub raw: Vec<u8>,
     /// Den endelige URL-en (etter eventuelle redirects)
     pub final_url: String,
+    /// Forbindelsen til serveren som sendte svaret
+    pub security: SecurityInfo,
+    /// Tiden nettverket brukte, summert over alle redirects
+    pub timing: NetworkTiming,
 }
 
 /// Lagret sertifikat for TOFU
@@ -118,31 +189,19 @@ pub struct TofuStore {
 
 impl TofuStore {
     /// Last TOFU-lageret fra fil
-    pub fn load(path: &PathBuf) -> Self {
-        if !path.exists() {
-            return Self::default();
-        }
-
-        fs::read_to_string(path)
-            .ok()
-            .and_then(|content| serde_json::from_str(&content).ok())
+    pub fn load(path: &Path) -> Self {
+        storage::read_json(path)
+            .unwrap_or_else(|e| {
+                warn!("Kunne ikke lese TOFU-lager: {}", e);
+                None
+            })
             .unwrap_or_default()
     }
 
     /// Lagre TOFU-lageret til fil
-    pub fn save(&self, path: &PathBuf) -> Result<(), GeminiError> {
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)
-                .map_err(|e| GeminiError::TlsError(format!("Kunne ikke opprette mappe: {}", e)))?;
-        }
-
-        let content = serde_json::to_string_pretty(self)
-            .map_err(|e| GeminiError::TlsError(format!("Serialiseringsfeil: {}", e)))?;
-
-        fs::write(path, content)
-            .map_err(|e| GeminiError::TlsError(format!("Kunne ikke skrive fil: {}", e)))?;
-
-        Ok(())
+    pub fn save(&self, path: &Path) -> Result<(), GeminiError> {
+        storage::write_json(path, self)
+            .map_err(|e| GeminiError::TlsError(format!("Kunne ikke skrive fil: {}", e)))
     }
 
     /// Sjekk et sertifikat mot TOFU-lageret
@@ -167,7 +226,6 @@ impl TofuStore {
             }
         } else {
             // Ukjent vert — lagre sertifikatet
-            info!("TOFU: Lagrer nytt sertifikat for {}", host_port);
             self.hosts.insert(
                 host_port.to_string(),
                 StoredCert {
@@ -179,13 +237,21 @@ impl TofuStore {
             Ok(true)
         }
     }
+
+    /// Glem alle kjente verter
+    ///
+    /// # Returns
+    /// Antall verter som ble glemt
+    pub fn clear(&mut self) -> usize {
+        let count = self.hosts.len();
+        self.hosts.clear();
+        count
+    }
 }
 
 /// Beregn SHA-256 fingerprint av et sertifikat
 fn cert_fingerprint(cert: &CertificateDer) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(cert.as_ref());
-    hex::encode(hasher.finalize())
+    page_security::fingerprint(cert.as_ref())
 }
 
 /// Custom ServerCertVerifier som aksepterer alle sertifikater.
@@ -249,32 +315,158 @@ pub struct GeminiClient {
     tofu_store: Mutex<TofuStore>,
     /// Sti til TOFU-lagringsfil
     tofu_path: PathBuf,
+    /// Klientsertifikatene og vertene de sendes til
+    identities: Mutex<IdentityStore>,
+    /// Sti til identitetene; None lagrer dem bare i minnet
+    identities_path: Option<PathBuf>,
+    /// Privat modus: nye sertifikater festes bare i minnet
+    private: AtomicBool,
     /// Timeout i sekunder
     timeout_seconds: u64,
+    /// Maksimal respons-størrelse i bytes
+    max_response_size: usize,
+    /// Maksimalt antall redirects å følge
+    max_redirects: u8,
 }
 
 impl GeminiClient {
-    /// Opprett en ny GeminiClient
-    pub fn new() -> Self {
-        Self::with_timeout(30)
-    }
-
-    /// Opprett en ny GeminiClient med egendefinert timeout
-    pub fn with_timeout(timeout_seconds: u64) -> Self {
+    /// Opprett en ny GeminiClient med TOFU-lageret i en bestemt fil
+    pub fn with_tofu_path(network: &NetworkSettings, tofu_path: PathBuf) -> Self {
         let tls_config = ClientConfig::builder()
             .dangerous()
             .with_custom_certificate_verifier(Arc::new(TofuVerifier))
             .with_no_client_auth();
 
-        let tofu_path = get_tofu_path();
         let tofu_store = TofuStore::load(&tofu_path);
 
         Self {
             tls_config: Arc::new(tls_config),
             tofu_store: Mutex::new(tofu_store),
             tofu_path,
-            timeout_seconds,
+            identities: Mutex::default(),
+            identities_path: None,
+            private: AtomicBool::new(false),
+            timeout_seconds: network.timeout_seconds,
+            max_response_size: network.max_response_bytes(),
+            max_redirects: network.max_redirects,
+        }
+    }
+
+    /// Les identitetene fra og lagre dem i en bestemt fil
+    pub fn with_identities(mut self, path: PathBuf) -> Self {
+        self.identities = Mutex::new(IdentityStore::load(&path));
+        self.identities_path = Some(path);
+        self
+    }
+
+    /// Alle identiteter, uten nøklene
+    pub fn identities(&self) -> Vec<IdentityInfo> {
+        self.identities.lock().list()
+    }
+
+    /// Lag en ny identitet og lagre den
+    ///
+    /// # Arguments
+    /// * `name` - Navnet på identiteten
+    /// * `host` - Verten identiteten knyttes til med en gang, hvis noen
+    pub fn create_identity(
+        &self,
+        name: &str,
+        host: Option<&str>,
+    ) -> Result<IdentityInfo, IdentityError> {
+        self.update_identities(|store| store.create(name, host))
+    }
+
+    /// Knytt en identitet til en vert, eller fjern vertens identitet (None)
+    pub fn assign_identity(
+        &self,
+        fingerprint: Option<&str>,
+        host: &str,
+    ) -> Result<(), IdentityError> {
+        self.update_identities(|store| store.assign(fingerprint, host))
+    }
+
+    /// Slett en identitet
+    pub fn delete_identity(&self, fingerprint: &str) -> Result<(), IdentityError> {
+        self.update_identities(|store| store.delete(fingerprint))
+    }
+
+    /// Endre identitetene og lagre dem hvis endringen lyktes
+    fn update_identities<T>(
+        &self,
+        change: impl FnOnce(&mut IdentityStore) -> Result<T, IdentityError>,
+    ) -> Result<T, IdentityError> {
+        let mut store = self.identities.lock();
+        let result = change(&mut store)?;
+        if let Some(path) = &self.identities_path {
+            store.save(path)?;
+        }
+        Ok(result)
+    }
+
+    /// TLS-konfigurasjonen for en vert, med identiteten hvis den har en
+    ///
+    /// # Returns
+    /// Konfigurasjonen, og om den har et klientsertifikat
+    fn tls_config_for(&self, host_port: &str) -> Result<(Arc<ClientConfig>, bool), GeminiError> {
+        let Some(identity) = self.identities.lock().for_host(host_port) else {
+            return Ok((self.tls_config.clone(), false));
+        };
+        let config = ClientConfig::builder()
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(TofuVerifier))
+            .with_client_auth_cert(vec![identity.certificate], identity.key)
+            .map_err(|e| GeminiError::TlsError(e.to_string()))?;
+        Ok((Arc::new(config), true))
+    }
+
+    /// Slå privat modus av eller på
+    ///
+    /// Når privat modus slås av, leses TOFU-lageret inn på nytt, slik at
+    /// sertifikater som bare ble festet i minnet glemmes.
+    pub fn set_private(&self, private: bool) {
+        let was_private = self.private.swap(private, Ordering::Relaxed);
+        if was_private && !private {
+            *self.tofu_store.lock() = TofuStore::load(&self.tofu_path);
+        }
+    }
+
+    /// Adressen slik den skal stå i loggen; se `private_mode::log_url`
+    fn log_url<'a>(&self, url: &'a str) -> &'a str {
+        private_mode::log_url(self.private.load(Ordering::Relaxed), url)
+    }
+
+    /// Sjekk et sertifikat mot TOFU-lageret og fest det
+    ///
+    /// Lageret lagres etter hver sjekk, bortsett fra i privat modus.
+    pub fn pin_certificate(&self, host_port: &str, fingerprint: &str) -> Result<(), GeminiError> {
+        let mut store = self.tofu_store.lock();
+        let known = store.hosts.contains_key(host_port);
+        store.verify(host_port, fingerprint)?;
+        if !known {
+            info!(
+                "TOFU: Lagrer nytt sertifikat for {}",
+                self.log_url(host_port)
+            );
+        }
+
+        if !self.private.load(Ordering::Relaxed) {
+            if let Err(e) = store.save(&self.tofu_path) {
+                warn!("Kunne ikke lagre TOFU-lager: {}", e);
+            }
         }
+        Ok(())
+    }
+
+    /// Glem alle kjente verter og lagre det tomme TOFU-lageret
+    ///
+    /// # Returns
+    /// Antall verter som ble glemt
+    pub fn clear_known_hosts(&self) -> Result<usize, GeminiError> {
+        let mut store = self.tofu_store.lock();
+        let count = store.clear();
+        store.save(&self.tofu_path)?;
+        Ok(count)
     }
 
     /// Valider og parse en Gemini-URL
@@ -309,21 +501,25 @@ impl GeminiClient {
     pub async fn fetch(&self, url_str: &str) -> Result<GeminiResponse, GeminiError> {
         let mut current_url = url_str.to_string();
         let mut redirect_count: u8 = 0;
+        let mut timing = NetworkTiming::default();
 
         loop {
-            if redirect_count > MAX_REDIRECTS {
-                return Err(GeminiError::RedirectLoop(MAX_REDIRECTS));
+            if redirect_count > self.max_redirects {
+                return Err(GeminiError::RedirectLoop(self.max_redirects));
             }
 
-            match self.fetch_single(&current_url).await? {
-                FetchOutcome::Success(response) => return Ok(response),
+            match self.fetch_single(&current_url, &mut timing).await? {
+                FetchOutcome::Success(response) => return Ok(*response),
                 FetchOutcome::Redirect(new_url) => {
+                    if Url::parse(&new_url).is_ok_and(|url| url.scheme() != "gemini") {
+                        return Err(GeminiError::CrossSchemeRedirect(new_url));
+                    }
                     info!(
                         "Gemini: Redirect {} -> {} (#{}/{})",
-                        current_url,
-                        new_url,
+                        self.log_url(current_url.as_str()),
+                        self.log_url(new_url.as_str()),
                         redirect_count + 1,
-                        MAX_REDIRECTS
+                        self.max_redirects
                     );
                     current_url = new_url;
                     redirect_count += 1;
@@ -332,9 +528,55 @@ impl GeminiClient {
         }
     }
 
-    /// Intern fetch for én enkelt forespørsel (uten redirect-følging)
-    async fn fetch_single(&self, url_str: &str) -> Result<FetchOutcome, GeminiError> {
+    /// Start en nedlasting fra en Gemini-URL
+    ///
+    /// Følger redirects som `fetch`, men leser ikke body. Størrelsesgrensen
+    /// for sider gjelder ikke, siden innholdet strømmes rett til disk.
+    ///
+    /// # Returns
+    /// Leseren posisjonert rett etter headeren, og meta-feltet (MIME-typen)
+    pub async fn open_download(
+        &self,
+        url_str: &str,
+    ) -> Result<(ResponseReader, String), GeminiError> {
+        let mut current_url = url_str.to_string();
+        for _ in 0..=self.max_redirects {
+            let url = Self::validate_url(&current_url)?;
+            let head = self.send_request(&url).await?;
+            match head.status / 10 {
+                2 => return Ok((head.reader, head.meta)),
+                3 => current_url = redirect_target(&url, &head.meta),
+                _ => {
+                    return Err(status_error(
+                        head.status,
+                        head.meta,
+                        &head.host_port,
+                        head.identified,
+                    ))
+                }
+            }
+        }
+        Err(GeminiError::RedirectLoop(self.max_redirects))
+    }
+
+    /// Hent kun respons-headeren for en Gemini-URL
+    ///
+    /// Følger ikke redirects og leser ikke body. Brukes til å sjekke om en
+    /// side fortsatt finnes uten å laste den ned.
+    ///
+    /// # Returns
+    /// Statuskode og meta-felt, eller GeminiError (f.eks. endret sertifikat)
+    pub async fn fetch_header(&self, url_str: &str) -> Result<(u8, String), GeminiError> {
         let url = Self::validate_url(url_str)?;
+        let head = self.send_request(&url).await?;
+        Ok((head.status, head.meta))
+    }
+
+    /// Kobler til, verifiserer sertifikatet og sender forespørselen
+    ///
+    /// # Returns
+    /// Headeren og leseren posisjonert rett etter den
+    async fn send_request(&self, url: &Url) -> Result<ResponseHead, GeminiError> {
         let host = url
             .host_str()
             .ok_or_else(|| GeminiError::InvalidUrl("Mangler vertsnavn".to_string()))?
@@ -342,9 +584,10 @@ impl GeminiClient {
         let port = url.port().unwrap_or(DEFAULT_PORT);
         let host_port = format!("{}:{}", host, port);
 
-        info!("Gemini: Kobler til {}", host_port);
+        info!("Gemini: Kobler til {}", self.log_url(&host_port));
 
         // TCP-tilkobling med timeout
+        let started = Instant::now();
         let tcp_stream = tokio::time::timeout(
             Duration::from_secs(self.timeout_seconds),
             TcpStream::connect(&host_port),
@@ -353,14 +596,20 @@ impl GeminiClient {
         .map_err(|_| GeminiError::Timeout(self.timeout_seconds))?
         .map_err(|e| GeminiError::ConnectionError(e.to_string()))?;
 
-        debug!("Gemini: TCP-tilkobling etablert til {}", host_port);
+        debug!(
+            "Gemini: TCP-tilkobling etablert til {}",
+            self.log_url(&host_port)
+        );
 
         // TLS-handshake
         let server_name = ServerName::try_from(host.clone())
             .map_err(|e| GeminiError::TlsError(format!("Ugyldig vertsnavn: {}", e)))?;
 
-        let connector = TlsConnector::from(self.tls_config.clone());
+        // Klientsertifikatet sendes bare hvis serveren ber om det
+        let (tls_config, identified) = self.tls_config_for(&host_port)?;
+        let connector = TlsConnector::from(tls_config);
 
+        let connected = Instant::now();
         let tls_stream = tokio::time::timeout(
             Duration::from_secs(self.timeout_seconds),
             connector.connect(server_name, tcp_stream),
@@ -370,29 +619,28 @@ impl GeminiClient {
         .map_err(|e| GeminiError::TlsError(e.to_string()))?;
 
         debug!("Gemini: TLS-handshake fullført");
+        let handshaken = Instant::now();
 
         // TOFU-sjekk: Verifiser serversertifikatet
         let (io, session) = tls_stream.get_ref();
         let _ = io; // Vi trenger kun session for sertifikater
 
-        if let Some(certs) = session.peer_certificates() {
-            if let Some(cert) = certs.first() {
-                let fingerprint = cert_fingerprint(cert);
-                debug!("Gemini: Sertifikat-fingerprint mottatt for {}", host_port);
-
-                let mut store = self.tofu_store.lock().unwrap();
-                store.verify(&host_port, &fingerprint)?;
-
-                // Lagre oppdatert TOFU-lager
-                if let Err(e) = store.save(&self.tofu_path) {
-                    warn!("Kunne ikke lagre TOFU-lager: {}", e);
-                }
-            }
+        let fingerprint = session
+            .peer_certificates()
+            .and_then(|certs| certs.first())
+            .map(cert_fingerprint);
+        if let Some(fingerprint) = &fingerprint {
+            debug!(
+                "Gemini: Sertifikat-fingerprint mottatt for {}",
+                self.log_url(&host_port)
+            );
+            self.pin_certificate(&host_port, fingerprint)?;
         }
+        let security = SecurityInfo::from_gemini(session, fingerprint);
 
         // Send forespørsel
         let request = format!("{}\r\n", url.as_str());
-        let (mut read_half, mut write_half) = tokio::io::split(tls_stream);
+        let (read_half, mut write_half) = tokio::io::split(tls_stream);
 
         write_half
             .write_all(request.as_bytes())
@@ -401,10 +649,10 @@ impl GeminiClient {
                 GeminiError::ConnectionError(format!("Kunne ikke sende forespørsel: {}", e))
             })?;
 
-        debug!("Gemini: Forespørsel sendt: {}", url.as_str());
+        debug!("Gemini: Forespørsel sendt: {}", self.log_url(url.as_str()));
 
         // Les respons-header
-        let mut reader = BufReader::new(&mut read_half);
+        let mut reader = BufReader::new(read_half);
         let mut header_line = String::new();
 
         tokio::time::timeout(
@@ -417,25 +665,56 @@ impl GeminiClient {
 
         debug!("Gemini: Respons-header: {:?}", header_line.trim());
 
-        // Parse status og meta
         let (status, meta) = parse_response_header(&header_line)?;
+        Ok(ResponseHead {
+            reader,
+            status,
+            meta,
+            security,
+            timing: NetworkTiming {
+                connect: Some(connected - started),
+                tls: Some(handshaken - connected),
+                transfer: Some(handshaken.elapsed()),
+            },
+            host_port,
+            identified,
+        })
+    }
+
+    /// Intern fetch for én enkelt forespørsel (uten redirect-følging)
+    ///
+    /// # Arguments
+    /// * `url_str` - Adressen
+    /// * `timing` - Tiden så langt i kjeden av redirects; denne forespørselen
+    ///   legges til
+    async fn fetch_single(
+        &self,
+        url_str: &str,
+        timing: &mut NetworkTiming,
+    ) -> Result<FetchOutcome, GeminiError> {
+        let url = Self::validate_url(url_str)?;
+        let ResponseHead {
+            reader,
+            status,
+            meta,
+            security,
+            timing: head_timing,
+            host_port,
+            identified,
+        } = self.send_request(&url).await?;
+        *timing += head_timing;
 
         // Håndter statuskoder
         match status / 10 {
-            1 => {
-                // Input påkrevd
-                if status == 11 {
-                    Err(GeminiError::SensitiveInputRequired(meta))
-                } else {
-                    Err(GeminiError::InputRequired(meta))
-                }
-            }
             2 => {
                 // Suksess — les body
+                let started = Instant::now();
                 let mut body = Vec::new();
                 let bytes_read = tokio::time::timeout(
                     Duration::from_secs(self.timeout_seconds),
-                    reader.take(MAX_RESPONSE_SIZE as u64).read_to_end(&mut body),
+                    reader
+                        .take(self.max_response_size as u64)
+                        .read_to_end(&mut body),
                 )
                 .await
                 .map_err(|_| GeminiError::Timeout(self.timeout_seconds))?
@@ -445,48 +724,66 @@ impl GeminiClient {
 
                 debug!("Gemini: Mottatt {} bytes body", bytes_read);
 
-                if bytes_read >= MAX_RESPONSE_SIZE {
-                    return Err(GeminiError::TooLarge(MAX_RESPONSE_SIZE));
+                if bytes_read >= self.max_response_size {
+                    return Err(GeminiError::TooLarge(self.max_response_size));
                 }
 
                 let body_str = String::from_utf8_lossy(&body).to_string();
+                *timing += NetworkTiming {
+                    transfer: Some(started.elapsed()),
+                    ..Default::default()
+                };
 
-                Ok(FetchOutcome::Success(GeminiResponse {
+                Ok(FetchOutcome::Success(Box::new(GeminiResponse {
                     status,
                     meta,
                     body: Some(body_str),
+                    raw: body,
                     final_url: url_str.to_string(),
-                }))
-            }
-            3 => {
-                // Redirect
-                let redirect_url = if meta.starts_with("gemini://") || meta.starts_with("//") {
-                    meta.clone()
-                } else {
-                    // Relativ URL — løs mot nåværende
-                    url.join(&meta)
-                        .map(|u| u.to_string())
-                        .unwrap_or(meta.clone())
-                };
-
-                Ok(FetchOutcome::Redirect(redirect_url))
-            }
-            4 | 5 => {
-                // Midlertidig/permanent feil
-                Err(GeminiError::ServerError { status, meta })
+                    security,
+                    timing: *timing,
+                })))
             }
-            6 => {
-                // Klientsertifikat påkrevd
-                Err(GeminiError::ClientCertRequired)
-            }
-            _ => Err(GeminiError::InvalidResponse(format!(
-                "Ukjent statuskode: {}",
-                status
-            ))),
+            3 => Ok(FetchOutcome::Redirect(redirect_target(&url, &meta))),
+            _ => Err(status_error(status, meta, &host_port, identified)),
         }
     }
 }
 
+/// Adressen en redirect (3x) peker til
+fn redirect_target(url: &Url, meta: &str) -> String {
+    if meta.starts_with("gemini://") || meta.starts_with("//") {
+        meta.to_string()
+    } else {
+        // Relativ URL — løs mot nåværende
+        url.join(meta)
+            .map(|u| u.to_string())
+            .unwrap_or(meta.to_string())
+    }
+}
+
+/// Feilen for en statuskode som verken er suksess (2x) eller redirect (3x)
+///
+/// # Arguments
+/// * `host_port` - Verten, som en identitet kan knyttes til
+/// * `identified` - Om klienten hadde en identitet for verten
+fn status_error(status: u8, meta: String, host_port: &str, identified: bool) -> GeminiError {
+    match status / 10 {
+        // Input påkrevd
+        1 if status == 11 => GeminiError::SensitiveInputRequired(meta),
+        1 => GeminiError::InputRequired(meta),
+        // Midlertidig/permanent feil
+        4 | 5 => GeminiError::ServerError { status, meta },
+        // Klientsertifikat påkrevd, eller ikke godtatt
+        6 if identified => GeminiError::ClientCertRejected { status, meta },
+        6 => GeminiError::ClientCertRequired {
+            host: host_port.to_string(),
+            meta,
+        },
+        _ => GeminiError::InvalidResponse(format!("Ukjent statuskode: {}", status)),
+    }
+}
+
 /// Parse Gemini respons-header
 ///
 /// Format: <STATUS><SPACE><META>\r\n
@@ -541,15 +838,11 @@ pub fn resolve_gemini_url(base: &str, relative: &str) -> Result<String, GeminiEr
     Ok(resolved.to_string())
 }
 
-/// Hent stien til TOFU-lagringsfilen
-pub fn get_tofu_path() -> PathBuf {
-    let config_dir = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
-    config_dir.join("bare").join("known_hosts.json")
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::page_security::{CertificateTrust, Protocol};
+    use crate::test_support::{GeminiServer, Identity, Reply};
 
     #[test]
     fn test_validate_url_valid() {
@@ -697,6 +990,40 @@ mod tests {
         assert!(!fp1.is_empty());
     }
 
+    #[test]
+    fn test_private_pins_stay_in_memory() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("known_hosts.json");
+        let client = GeminiClient::with_tofu_path(&NetworkSettings::default(), path.clone());
+
+        client.pin_certificate("kjent.example:1965", "aaa").unwrap();
+        let before = (std::fs::read(&path).unwrap(), storage::file_stamp(&path));
+
+        client.set_private(true);
+        client
+            .pin_certificate("privat.example:1965", "bbb")
+            .unwrap();
+        client.pin_certificate("kjent.example:1965", "aaa").unwrap();
+        // Festet i minnet for resten av den private økten
+        assert!(matches!(
+            client.pin_certificate("privat.example:1965", "ccc"),
+            Err(GeminiError::CertificateChanged { .. })
+        ));
+        assert_eq!(
+            (std::fs::read(&path).unwrap(), storage::file_stamp(&path)),
+            before
+        );
+
+        // Etter privat modus er den private festingen glemt
+        client.set_private(false);
+        client
+            .pin_certificate("privat.example:1965", "ccc")
+            .unwrap();
+        assert!(TofuStore::load(&path)
+            .hosts
+            .contains_key("privat.example:1965"));
+    }
+
     #[test]
     fn test_tofu_store_save_load() {
         let dir = tempfile::tempdir().unwrap();
@@ -710,4 +1037,203 @@ mod tests {
         assert!(loaded.hosts.contains_key("example.com:1965"));
         assert_eq!(loaded.hosts["example.com:1965"].fingerprint, "abc123");
     }
+
+    /// Klient med TOFU-lageret i en midlertidig mappe
+    fn test_client(dir: &tempfile::TempDir, network: &NetworkSettings) -> GeminiClient {
+        GeminiClient::with_tofu_path(network, dir.path().join("known_hosts.json"))
+    }
+
+    #[tokio::test]
+    async fn test_fetch_success() {
+        let server = GeminiServer::start(|_| Reply::text("20 text/gemini\r\n# Hei\n")).await;
+        let dir = tempfile::tempdir().unwrap();
+        let client = test_client(&dir, &NetworkSettings::default());
+
+        let url = server.url("/side.gmi");
+        let response = client.fetch(&url).await.unwrap();
+        assert_eq!(response.status, 20);
+        assert_eq!(response.meta, "text/gemini");
+        assert_eq!(response.body.as_deref(), Some("# Hei\n"));
+        assert_eq!(response.final_url, url);
+        assert_eq!(server.requests(), [url]);
+        // Sertifikatet er festet på disk
+        assert!(dir.path().join("known_hosts.json").exists());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_follows_redirect_chain() {
+        let server = GeminiServer::start(|request| {
+            let path = Url::parse(request).unwrap().path().to_string();
+            match path.as_str() {
+                "/a" => Reply::text("31 /b\r\n"),
+                "/b" => Reply::text(format!("30 {}c\r\n", request.trim_end_matches('b'))),
+                "/c" => Reply::text("20 text/gemini\r\nFremme\n"),
+                _ => Reply::text("51 Ikke funnet\r\n"),
+            }
+        })
+        .await;
+        let dir = tempfile::tempdir().unwrap();
+        let client = test_client(&dir, &NetworkSettings::default());
+
+        let response = client.fetch(&server.url("/a")).await.unwrap();
+        assert_eq!(response.body.as_deref(), Some("Fremme\n"));
+        assert_eq!(response.final_url, server.url("/c"));
+        assert_eq!(
+            server.requests(),
+            [server.url("/a"), server.url("/b"), server.url("/c")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_stops_at_cross_scheme_redirect() {
+        let server = GeminiServer::start(|_| Reply::text("31 https://a.example/side\r\n")).await;
+        let dir = tempfile::tempdir().unwrap();
+        let client = test_client(&dir, &NetworkSettings::default());
+
+        let result = client.fetch(&server.url("/")).await;
+        assert!(
+            matches!(&result, Err(GeminiError::CrossSchemeRedirect(url)) if url == "https://a.example/side")
+        );
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_sends_identity_assigned_to_host() {
+        let server = GeminiServer::start_identified(|request, fingerprint| {
+            match (request.ends_with("/avvist"), fingerprint) {
+                (true, _) => Reply::text("61 Ikke for deg\r\n"),
+                (false, Some(fingerprint)) => {
+                    Reply::text(format!("20 text/gemini\r\n# Hei {}\n", fingerprint))
+                }
+                (false, None) => Reply::text("60 Hvem er du?\r\n"),
+            }
+        })
+        .await;
+        let dir = tempfile::tempdir().unwrap();
+        let client = test_client(&dir, &NetworkSettings::default())
+            .with_identities(dir.path().join("gemini_identities.json"));
+        let host = server.addr.to_string();
+
+        let result = client.fetch(&server.url("/")).await;
+        assert!(
+            matches!(&result, Err(GeminiError::ClientCertRequired { host: h, meta }) if *h == host && meta == "Hvem er du?")
+        );
+
+        // Etter at brukeren har laget en identitet, sendes den
+        let identity = client.create_identity("plante", Some(&host)).unwrap();
+        let response = client.fetch(&server.url("/")).await.unwrap();
+        assert_eq!(
+            response.body.unwrap(),
+            format!("# Hei {}\n", identity.fingerprint)
+        );
+        assert!(matches!(
+            client.fetch(&server.url("/avvist")).await,
+            Err(GeminiError::ClientCertRejected { status: 61, .. })
+        ));
+
+        // Identiteten er lagret, og en ny klient sender den også
+        let reloaded = test_client(&dir, &NetworkSettings::default())
+            .with_identities(dir.path().join("gemini_identities.json"));
+        assert!(reloaded.fetch(&server.url("/")).await.is_ok());
+
+        client.delete_identity(&identity.fingerprint).unwrap();
+        assert!(client.identities().is_empty());
+        assert!(matches!(
+            client.fetch(&server.url("/")).await,
+            Err(GeminiError::ClientCertRequired { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_stops_redirect_loop() {
+        let server = GeminiServer::start(|_| Reply::text("31 /igjen\r\n")).await;
+        let dir = tempfile::tempdir().unwrap();
+        let network = NetworkSettings {
+            max_redirects: 2,
+            ..Default::default()
+        };
+        let client = test_client(&dir, &network);
+
+        let result = client.fetch(&server.url("/")).await;
+        assert!(matches!(result, Err(GeminiError::RedirectLoop(2))));
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_detects_changed_certificate() {
+        let server = GeminiServer::start(|_| Reply::text("20 text/plain\r\nHei\n")).await;
+        let dir = tempfile::tempdir().unwrap();
+        let client = test_client(&dir, &NetworkSettings::default());
+        client.fetch(&server.url("/")).await.unwrap();
+
+        server.set_identity(Identity::Second);
+        let Err(GeminiError::CertificateChanged {
+            host,
+            old_fp,
+            new_fp,
+        }) = client.fetch(&server.url("/")).await
+        else {
+            panic!("endret sertifikat ble ikke oppdaget");
+        };
+        assert_eq!(host, server.addr.to_string());
+        assert_ne!(old_fp, new_fp);
+        // Forespørselen ble aldri sendt til den nye serveren
+        assert_eq!(server.requests().len(), 1);
+
+        // Det gamle sertifikatet huskes også av en ny klient
+        let client = test_client(&dir, &NetworkSettings::default());
+        let result = client.fetch(&server.url("/")).await;
+        assert!(matches!(
+            result,
+            Err(GeminiError::CertificateChanged { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_records_connection_security() {
+        let server = GeminiServer::start(|_| Reply::text("20 text/gemini\r\n# Hei\n")).await;
+        let dir = tempfile::tempdir().unwrap();
+        let client = test_client(&dir, &NetworkSettings::default());
+
+        let first = client.fetch(&server.url("/")).await.unwrap().security;
+        assert_eq!(first.protocol, Protocol::Gemini);
+        assert!(first.encrypted);
+        assert!(!first.downgraded);
+        assert_eq!(first.tls_version.as_deref(), Some("TLS 1.3"));
+        assert!(first.cipher_suite.unwrap().starts_with("TLS13_"));
+        assert_eq!(first.certificate, CertificateTrust::Pinned);
+
+        // Samme festede sertifikat ved neste besøk
+        let second = client.fetch(&server.url("/")).await.unwrap().security;
+        assert!(first.fingerprint.is_some());
+        assert_eq!(second.fingerprint, first.fingerprint);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_slow_down() {
+        let server = GeminiServer::start(|_| Reply::text("44 60\r\n")).await;
+        let dir = tempfile::tempdir().unwrap();
+        let client = test_client(&dir, &NetworkSettings::default());
+
+        let result = client.fetch(&server.url("/")).await;
+        let Err(GeminiError::ServerError { status, meta }) = result else {
+            panic!("forventet serverfeil: {:?}", result);
+        };
+        assert_eq!(status, 44);
+        assert_eq!(meta, "60");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_timeout_waiting_for_header() {
+        let server = GeminiServer::start(|_| Reply::Stall(Vec::new())).await;
+        let dir = tempfile::tempdir().unwrap();
+        let network = NetworkSettings {
+            timeout_seconds: 1,
+            ..Default::default()
+        };
+        let client = test_client(&dir, &network);
+
+        let result = client.fetch(&server.url("/")).await;
+        assert!(matches!(result, Err(GeminiError::Timeout(1))));
+    }
 }
@@ -0,0 +1,410 @@
+//! Klientsertifikater (identiteter) for Gemini
+//!
+//! Noen Gemini-kapsler, f.eks. Astrobotany og Station, kjenner igjen brukeren
+//! på klientsertifikatet. En identitet er et selvsignert sertifikat med
+//! nøkkel, laget lokalt. Den kan knyttes til verter, og sendes da til dem når
+//! serveren ber om et sertifikat. Identiteter kan lages for én vert eller med
+//! et eget navn og knyttes til flere.
+//!
+//! Identitetene lagres i `gemini_identities.json` i config-mappen. Filen
+//! inneholder private nøkler, så bare eieren kan lese den, og den får ingen
+//! `.bak`-kopi. Nøklene forlater aldri maskinen.
+
+use crate::locale::{Locale, Localize};
+use crate::page_security;
+use crate::storage;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::Datelike;
+use log::{info, warn};
+use rcgen::{CertificateParams, DnType, KeyPair, PKCS_ECDSA_P256_SHA256};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use thiserror::Error;
+use url::Url;
+
+/// Standard Gemini-port, for verter som er gitt uten port
+const DEFAULT_PORT: u16 = 1965;
+
+/// Feil som kan oppstå ved håndtering av identiteter
+#[derive(Debug, Error)]
+pub enum IdentityError {
+    #[error("Identiteten må ha et navn")]
+    EmptyName,
+
+    #[error("Fant ingen identitet med fingerprint {0}")]
+    NotFound(String),
+
+    #[error("Ugyldig vert: {0}")]
+    InvalidHost(String),
+
+    #[error("Kunne ikke lage sertifikat: {0}")]
+    Generate(String),
+
+    #[error("Kunne ikke lagre identitetene: {0}")]
+    Save(String),
+}
+
+impl Localize for IdentityError {
+    fn localize_in(&self, locale: Locale) -> String {
+        match self {
+            Self::EmptyName => locale.text("error.identity_empty_name").to_string(),
+            Self::NotFound(fingerprint) => {
+                locale.format("error.identity_not_found", &[fingerprint])
+            }
+            Self::InvalidHost(host) => locale.format("error.identity_invalid_host", &[host]),
+            Self::Generate(e) => locale.format("error.identity_generate", &[e]),
+            Self::Save(e) => locale.format("error.identity_save", &[e]),
+        }
+    }
+}
+
+/// En identitet slik den lagres
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredIdentity {
+    name: String,
+    /// SHA-256 fingerprint av sertifikatet, brukes som nøkkel
+    fingerprint: String,
+    /// Når identiteten ble laget
+    created: String,
+    /// Verter identiteten sendes til ("host:port")
+    hosts: Vec<String>,
+    /// Sertifikatet som DER, base64-kodet
+    certificate: String,
+    /// Den private nøkkelen som PKCS#8, base64-kodet
+    key: String,
+}
+
+/// En identitet slik frontend ser den, uten nøkkelen
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IdentityInfo {
+    pub name: String,
+    pub fingerprint: String,
+    pub created: String,
+    pub hosts: Vec<String>,
+}
+
+impl From<&StoredIdentity> for IdentityInfo {
+    fn from(identity: &StoredIdentity) -> Self {
+        Self {
+            name: identity.name.clone(),
+            fingerprint: identity.fingerprint.clone(),
+            created: identity.created.clone(),
+            hosts: identity.hosts.clone(),
+        }
+    }
+}
+
+/// Sertifikatet og nøkkelen klienten sender
+pub struct ClientCertificate {
+    pub certificate: CertificateDer<'static>,
+    pub key: PrivateKeyDer<'static>,
+}
+
+/// Lageret for identiteter
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct IdentityStore {
+    identities: Vec<StoredIdentity>,
+}
+
+impl IdentityStore {
+    /// Last identitetene fra fil
+    ///
+    /// Uten sikkerhetskopi: en `.bak` ville vært en kopi av nøklene til.
+    /// En kopi fra før dette fjernes.
+    pub fn load(path: &Path) -> Self {
+        let _ = std::fs::remove_file(storage::backup_path(path));
+        if !path.exists() {
+            return Self::default();
+        }
+        storage::parse_file(path).unwrap_or_else(|e| {
+            warn!("Kunne ikke lese Gemini-identiteter: {}", e);
+            Self::default()
+        })
+    }
+
+    /// Lagre identitetene til fil som bare eieren kan lese
+    pub fn save(&self, path: &Path) -> Result<(), IdentityError> {
+        storage::write_private_json(path, self).map_err(|e| IdentityError::Save(e.to_string()))
+    }
+
+    /// Alle identiteter, i den rekkefølgen de ble laget
+    pub fn list(&self) -> Vec<IdentityInfo> {
+        self.identities.iter().map(IdentityInfo::from).collect()
+    }
+
+    /// Lag en ny identitet med et selvsignert sertifikat
+    ///
+    /// # Arguments
+    /// * `name` - Navnet, brukes også som CN i sertifikatet
+    /// * `host` - Verten identiteten knyttes til med en gang, hvis noen
+    pub fn create(
+        &mut self,
+        name: &str,
+        host: Option<&str>,
+    ) -> Result<IdentityInfo, IdentityError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(IdentityError::EmptyName);
+        }
+        let host = host.map(host_key).transpose()?;
+        let (certificate, key) = generate_certificate(name)?;
+        let fingerprint = page_security::fingerprint(&certificate);
+
+        let identity = StoredIdentity {
+            name: name.to_string(),
+            fingerprint: fingerprint.clone(),
+            created: chrono::Utc::now().to_rfc3339(),
+            hosts: Vec::new(),
+            certificate: BASE64.encode(certificate),
+            key: BASE64.encode(key),
+        };
+        info!("Gemini: Laget identiteten {}", name);
+        self.identities.push(identity);
+        if let Some(host) = host {
+            self.assign(Some(&fingerprint), &host)?;
+        }
+        Ok(self.find(&fingerprint).map(IdentityInfo::from).unwrap())
+    }
+
+    /// Knytt en identitet til en vert, eller fjern verten fra alle identiteter
+    ///
+    /// En vert har høyst én identitet; den forrige slippes.
+    ///
+    /// # Arguments
+    /// * `fingerprint` - Identiteten, eller None for ingen
+    /// * `host` - Vertsnavn, "vert:port" eller en gemini://-adresse
+    pub fn assign(&mut self, fingerprint: Option<&str>, host: &str) -> Result<(), IdentityError> {
+        let host = host_key(host)?;
+        if let Some(fingerprint) = fingerprint {
+            if self.find(fingerprint).is_none() {
+                return Err(IdentityError::NotFound(fingerprint.to_string()));
+            }
+        }
+        for identity in &mut self.identities {
+            identity.hosts.retain(|assigned| *assigned != host);
+            if Some(identity.fingerprint.as_str()) == fingerprint {
+                identity.hosts.push(host.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// Slett en identitet; vertene den var knyttet til får ingen
+    pub fn delete(&mut self, fingerprint: &str) -> Result<(), IdentityError> {
+        let before = self.identities.len();
+        self.identities
+            .retain(|identity| identity.fingerprint != fingerprint);
+        if self.identities.len() == before {
+            return Err(IdentityError::NotFound(fingerprint.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Sertifikatet som skal sendes til en vert
+    ///
+    /// # Arguments
+    /// * `host_port` - "host:port" slik klienten kobler til
+    pub fn for_host(&self, host_port: &str) -> Option<ClientCertificate> {
+        let identity = self
+            .identities
+            .iter()
+            .find(|identity| identity.hosts.iter().any(|host| host == host_port))?;
+        let certificate = BASE64.decode(&identity.certificate).ok()?;
+        let key = BASE64.decode(&identity.key).ok()?;
+        Some(ClientCertificate {
+            certificate: CertificateDer::from(certificate),
+            key: PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key)),
+        })
+    }
+
+    fn find(&self, fingerprint: &str) -> Option<&StoredIdentity> {
+        self.identities
+            .iter()
+            .find(|identity| identity.fingerprint == fingerprint)
+    }
+}
+
+/// Nøkkelen en vert lagres med: "host:port" med små bokstaver
+///
+/// # Arguments
+/// * `host` - Vertsnavn, "vert:port" eller en gemini://-adresse
+pub fn host_key(host: &str) -> Result<String, IdentityError> {
+    let trimmed = host.trim();
+    let address = if trimmed.contains("://") {
+        trimmed.to_string()
+    } else {
+        format!("gemini://{}/", trimmed)
+    };
+    let url = Url::parse(&address).map_err(|_| IdentityError::InvalidHost(host.to_string()))?;
+    match url.host_str() {
+        Some(name) if url.scheme() == "gemini" => Ok(format!(
+            "{}:{}",
+            name.to_lowercase(),
+            url.port().unwrap_or(DEFAULT_PORT)
+        )),
+        _ => Err(IdentityError::InvalidHost(host.to_string())),
+    }
+}
+
+// ===== Sertifikater =====
+
+/// Lag et selvsignert sertifikat og en ny nøkkel
+///
+/// ECDSA P-256 med SHA-256. Gemini-servere bruker bare fingerprint og
+/// eventuelt CN, så utsteder og emne er det samme navnet, og sertifikatet
+/// utløper i praksis aldri.
+///
+/// # Returns
+/// Sertifikatet som DER og nøkkelen som PKCS#8
+fn generate_certificate(common_name: &str) -> Result<(Vec<u8>, Vec<u8>), IdentityError> {
+    let failed = |e: rcgen::Error| IdentityError::Generate(e.to_string());
+    let key_pair = KeyPair::generate_for(&PKCS_ECDSA_P256_SHA256).map_err(failed)?;
+
+    let mut params = CertificateParams::new(Vec::new()).map_err(failed)?;
+    params
+        .distinguished_name
+        .push(DnType::CommonName, common_name);
+    let today = chrono::Utc::now().date_naive();
+    params.not_before = rcgen::date_time_ymd(today.year(), today.month() as u8, today.day() as u8);
+    // "Ingen utløpsdato" etter RFC 5280
+    params.not_after = rcgen::date_time_ymd(9999, 12, 31);
+
+    let certificate = params.self_signed(&key_pair).map_err(failed)?;
+    Ok((certificate.der().to_vec(), key_pair.serialize_der()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_host_key() {
+        assert_eq!(
+            host_key("Station.Martinrue.com").unwrap(),
+            "station.martinrue.com:1965"
+        );
+        assert_eq!(host_key("example.org:1966").unwrap(), "example.org:1966");
+        assert_eq!(
+            host_key("gemini://astrobotany.mozz.us/app").unwrap(),
+            "astrobotany.mozz.us:1965"
+        );
+        assert!(host_key("https://example.org/").is_err());
+        assert!(host_key("").is_err());
+    }
+
+    #[test]
+    fn test_generated_certificate_parses() {
+        use x509_parser::prelude::{FromDer, X509Certificate};
+
+        let (der, key) = generate_certificate("plante").unwrap();
+        let (rest, certificate) = X509Certificate::from_der(&der).unwrap();
+        assert!(rest.is_empty());
+
+        let common_name = |name: &x509_parser::x509::X509Name| {
+            name.iter_common_name()
+                .next()
+                .and_then(|cn| cn.as_str().ok())
+                .map(str::to_string)
+        };
+        assert_eq!(
+            common_name(certificate.subject()).as_deref(),
+            Some("plante")
+        );
+        assert_eq!(certificate.subject(), certificate.issuer());
+        assert!(certificate.validity().is_valid());
+        assert_eq!(certificate.validity().not_after.to_datetime().year(), 9999);
+        assert_eq!(
+            certificate.public_key().algorithm.algorithm.to_id_string(),
+            "1.2.840.10045.2.1"
+        );
+        assert!(certificate.verify_signature(None).is_ok());
+
+        // Nøkkelen hører til sertifikatet
+        let key_pair = KeyPair::try_from(key.as_slice()).unwrap();
+        assert_eq!(
+            key_pair.public_key_raw(),
+            certificate.public_key().subject_public_key.data.as_ref()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_store_file_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gemini_identities.json");
+        let mut store = IdentityStore::default();
+        store.create("plante", None).unwrap();
+        store.save(&path).unwrap();
+        std::fs::write(storage::backup_path(&path), "{}").unwrap();
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(IdentityStore::load(&path).list().len(), 1);
+        assert!(!storage::backup_path(&path).exists());
+    }
+
+    #[test]
+    fn test_create_assign_and_delete() {
+        let mut store = IdentityStore::default();
+        let plant = store.create("plante", Some("astrobotany.mozz.us")).unwrap();
+        let named = store.create(" Meg ", None).unwrap();
+        assert_eq!(named.name, "Meg");
+        assert_eq!(plant.hosts, vec!["astrobotany.mozz.us:1965"]);
+        assert_ne!(plant.fingerprint, named.fingerprint);
+        assert!(matches!(
+            store.create("  ", None),
+            Err(IdentityError::EmptyName)
+        ));
+
+        // En vert har én identitet; den nye tar over
+        store
+            .assign(Some(&named.fingerprint), "gemini://astrobotany.mozz.us/")
+            .unwrap();
+        store
+            .assign(Some(&named.fingerprint), "station.martinrue.com")
+            .unwrap();
+        let list = store.list();
+        assert!(list[0].hosts.is_empty());
+        assert_eq!(
+            list[1].hosts,
+            vec!["astrobotany.mozz.us:1965", "station.martinrue.com:1965"]
+        );
+
+        let sent = store.for_host("station.martinrue.com:1965").unwrap();
+        assert_eq!(
+            page_security::fingerprint(sent.certificate.as_ref()),
+            named.fingerprint
+        );
+        assert!(store.for_host("example.org:1965").is_none());
+
+        store.assign(None, "station.martinrue.com").unwrap();
+        assert!(store.for_host("station.martinrue.com:1965").is_none());
+        assert!(matches!(
+            store.assign(Some("ukjent"), "example.org"),
+            Err(IdentityError::NotFound(_))
+        ));
+
+        store.delete(&named.fingerprint).unwrap();
+        assert!(store.for_host("astrobotany.mozz.us:1965").is_none());
+        assert!(matches!(
+            store.delete(&named.fingerprint),
+            Err(IdentityError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_store_save_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gemini_identities.json");
+        let mut store = IdentityStore::default();
+        let identity = store.create("plante", Some("astrobotany.mozz.us")).unwrap();
+        store.save(&path).unwrap();
+
+        let loaded = IdentityStore::load(&path);
+        assert_eq!(loaded.list(), vec![identity]);
+        assert!(loaded.for_host("astrobotany.mozz.us:1965").is_some());
+    }
+}
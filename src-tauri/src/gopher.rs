@@ -3,24 +3,26 @@
 //! Implementerer Gopher-protokollen (RFC 1436) med TCP-tilkobling.
 //! Støtter menyer, tekstfiler, søk og HTML-lenker.
 
+use crate::locale::{self, Locale, Localize};
+use crate::private_mode::PrivateMode;
+use crate::settings::NetworkSettings;
+use crate::timing::NetworkTiming;
 use log::{debug, info, warn};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::tcp::OwnedReadHalf;
 use tokio::net::TcpStream;
 use url::Url;
 
 /// Standard Gopher-port
 const DEFAULT_PORT: u16 = 70;
 
-/// Maksimal respons-størrelse (5 MB)
-const MAX_RESPONSE_SIZE: usize = 5 * 1024 * 1024;
-
 /// Maksimal URL-lengde
 const MAX_URL_LENGTH: usize = 1024;
 
-/// Timeout i sekunder
-const TIMEOUT_SECONDS: u64 = 10;
+/// Antall bytes som leses ved sjekk av en ressurs
+const PROBE_SIZE: usize = 512;
 
 /// Feil som kan oppstå under Gopher-forespørsler
 #[derive(Debug, Error)]
@@ -48,6 +50,20 @@ pub enum GopherError {
     SearchInputRequired,
 }
 
+impl Localize for GopherError {
+    fn localize_in(&self, locale: Locale) -> String {
+        match self {
+            Self::InvalidUrl(url) => locale.format("error.invalid_url", &[url]),
+            Self::ConnectionError(e) => locale.format("error.connection", &[e]),
+            Self::Timeout(seconds) => locale.format("error.timeout", &[seconds]),
+            Self::TooLarge(max) => locale.format("error.too_large", &[max]),
+            Self::Io(e) => locale.format("error.io", &[e]),
+            Self::InvalidResponse(e) => locale.format("error.invalid_response", &[e]),
+            Self::SearchInputRequired => locale.text("error.gopher_search_input").to_string(),
+        }
+    }
+}
+
 /// Gopher-elementtype hentet fra meny-respons
 #[derive(Debug, Clone, PartialEq)]
 pub enum GopherItemType {
@@ -130,25 +146,38 @@ impl GopherItemType {
         }
     }
 
-    /// Beskrivelse av elementtypen
-    pub fn description(&self) -> &str {
+    /// Om elementet er en fil som lastes ned i stedet for å vises
+    pub fn is_download(&self) -> bool {
+        matches!(
+            self,
+            GopherItemType::BinHex
+                | GopherItemType::DosBinary
+                | GopherItemType::UuEncoded
+                | GopherItemType::Binary
+                | GopherItemType::Gif
+                | GopherItemType::Image
+        )
+    }
+
+    /// Beskrivelse av elementtypen på brukerens språk
+    pub fn description(&self) -> &'static str {
         match self {
-            GopherItemType::TextFile => "Tekstfil",
-            GopherItemType::Directory => "Mappe",
-            GopherItemType::CsoPhonebook => "CSO-telefonbok",
-            GopherItemType::Error => "Feil",
-            GopherItemType::BinHex => "BinHex-fil",
-            GopherItemType::DosBinary => "DOS-binærfil",
-            GopherItemType::UuEncoded => "UUencodet fil",
-            GopherItemType::Search => "Søk",
+            GopherItemType::TextFile => locale::t("gopher.type.text"),
+            GopherItemType::Directory => locale::t("gopher.type.directory"),
+            GopherItemType::CsoPhonebook => locale::t("gopher.cso_phonebook"),
+            GopherItemType::Error => locale::t("gopher.type.error"),
+            GopherItemType::BinHex => locale::t("gopher.type.binhex"),
+            GopherItemType::DosBinary => locale::t("gopher.type.dos_binary"),
+            GopherItemType::UuEncoded => locale::t("gopher.type.uuencoded"),
+            GopherItemType::Search => locale::t("gopher.type.search"),
             GopherItemType::Telnet => "Telnet",
-            GopherItemType::Binary => "Binærfil",
-            GopherItemType::Gif => "GIF-bilde",
-            GopherItemType::Image => "Bilde",
+            GopherItemType::Binary => locale::t("gopher.type.binary"),
+            GopherItemType::Gif => locale::t("gopher.type.gif"),
+            GopherItemType::Image => locale::t("gopher.type.image"),
             GopherItemType::Html => "HTML",
             GopherItemType::Info => "Info",
             GopherItemType::Telnet3270 => "Telnet 3270",
-            GopherItemType::Unknown(_) => "Ukjent",
+            GopherItemType::Unknown(_) => locale::t("gopher.type.unknown"),
         }
     }
 }
@@ -204,10 +233,14 @@ pub struct GopherResponse {
     pub content_type: GopherContentType,
     /// Rå respons-body
     pub body: String,
+    /// Bytene slik serveren sendte dem, før dekoding
+    pub raw: Vec<u8>,
     /// Parsed meny-elementer (kun for menyer)
     pub items: Vec<GopherItem>,
     /// Den endelige URL-en
     pub final_url: String,
+    /// Tilkobling og overføring; Gopher har ingen TLS
+    pub timing: NetworkTiming,
 }
 
 /// Parser en Gopher-URL til komponentene
@@ -338,248 +371,390 @@ pub fn parse_menu(response: &str) -> Vec<GopherItem> {
     items
 }
 
-/// Henter en Gopher-ressurs via TCP
-///
-/// # Arguments
-/// * `url` - Gopher-URL (gopher://host[:port]/[type][selector])
-///
-/// # Returns
-/// * `Ok(GopherResponse)` - Parsed respons
-/// * `Err(GopherError)` - Ved feil
-pub async fn fetch(url: &str) -> Result<GopherResponse, GopherError> {
-    let parsed = parse_gopher_url(url)?;
-
-    info!("Gopher: Kobler til {}:{}", parsed.host, parsed.port);
+/// Gopher-klient med grenser fra nettverksinnstillingene
+#[derive(Debug)]
+pub struct GopherClient {
+    /// Timeout i sekunder
+    timeout_seconds: u64,
+    /// Maksimal respons-størrelse i bytes
+    max_response_size: usize,
+    /// Privat modus: adresser og selektorer holdes utenfor loggen
+    private: PrivateMode,
+}
 
-    // Sjekk om dette er et søk som krever input
-    if parsed.item_type == GopherItemType::Search && parsed.selector.find('\t').is_none() {
-        // Søk uten query - be om input
-        return Err(GopherError::SearchInputRequired);
+impl Default for GopherClient {
+    fn default() -> Self {
+        Self::with_limits(&NetworkSettings::default())
     }
+}
 
-    // TCP-tilkobling med timeout
-    let addr = format!("{}:{}", parsed.host, parsed.port);
-    let stream = tokio::time::timeout(
-        Duration::from_secs(TIMEOUT_SECONDS),
-        TcpStream::connect(&addr),
-    )
-    .await
-    .map_err(|_| GopherError::Timeout(TIMEOUT_SECONDS))?
-    .map_err(|e| GopherError::ConnectionError(format!("Kunne ikke koble til {}: {}", addr, e)))?;
+impl GopherClient {
+    /// Opprett en GopherClient med grensene fra nettverksinnstillingene
+    ///
+    /// Gopher har ingen redirects, så `max_redirects` brukes ikke.
+    pub fn with_limits(network: &NetworkSettings) -> Self {
+        Self {
+            timeout_seconds: network.timeout_seconds,
+            max_response_size: network.max_response_bytes(),
+            private: PrivateMode::default(),
+        }
+    }
 
-    info!("Gopher: Tilkoblet til {}", addr);
+    /// Slå privat modus av eller på
+    pub fn set_private(&self, private: bool) {
+        self.private.set_active(private);
+    }
 
-    // Send selektor + CRLF
-    let selector_str = format!("{}\r\n", parsed.selector);
-    debug!("Gopher: Sender selektor: {:?}", selector_str.trim());
+    /// Kobler til serveren og sender selektoren
+    ///
+    /// # Returns
+    /// Lesehalvdelen av tilkoblingen, klar for respons, og tiden det tok å
+    /// koble til
+    async fn send_selector(
+        &self,
+        parsed: &GopherUrl,
+    ) -> Result<(OwnedReadHalf, Duration), GopherError> {
+        // TCP-tilkobling med timeout
+        let addr = format!("{}:{}", parsed.host, parsed.port);
+        let started = Instant::now();
+        let stream = tokio::time::timeout(
+            Duration::from_secs(self.timeout_seconds),
+            TcpStream::connect(&addr),
+        )
+        .await
+        .map_err(|_| GopherError::Timeout(self.timeout_seconds))?
+        .map_err(|e| {
+            GopherError::ConnectionError(format!("Kunne ikke koble til {}: {}", addr, e))
+        })?;
+
+        let connect = started.elapsed();
+        info!("Gopher: Tilkoblet til {}", self.private.log_url(&addr));
+
+        // Send selektor + CRLF
+        let selector_str = format!("{}\r\n", parsed.selector);
+        debug!(
+            "Gopher: Sender selektor: {:?}",
+            self.private.log_url(selector_str.trim())
+        );
+
+        let (reader, mut writer) = stream.into_split();
+
+        writer
+            .write_all(selector_str.as_bytes())
+            .await
+            .map_err(|e| {
+                GopherError::ConnectionError(format!("Kunne ikke sende selektor: {}", e))
+            })?;
+
+        Ok((reader, connect))
+    }
 
-    let (mut reader, mut writer) = stream.into_split();
+    /// Sjekker at en Gopher-ressurs svarer, uten å laste ned hele responsen
+    ///
+    /// Søk (type 7) uten query sjekkes mot serverens rotmeny, siden selve søket
+    /// krever input.
+    ///
+    /// # Arguments
+    /// * `url` - Gopher-URL
+    ///
+    /// # Returns
+    /// * `Ok(String)` - De første bytene av responsen (tom hvis serveren ikke svarte)
+    /// * `Err(GopherError)` - Ved feil
+    pub async fn probe(&self, url: &str) -> Result<String, GopherError> {
+        let mut parsed = parse_gopher_url(url)?;
+        if parsed.item_type == GopherItemType::Search && !parsed.selector.contains('\t') {
+            parsed.selector.clear();
+        }
 
-    writer
-        .write_all(selector_str.as_bytes())
+        let (mut reader, _) = self.send_selector(&parsed).await?;
+        let mut buffer = [0u8; PROBE_SIZE];
+        let read = tokio::time::timeout(
+            Duration::from_secs(self.timeout_seconds),
+            reader.read(&mut buffer),
+        )
         .await
-        .map_err(|e| GopherError::ConnectionError(format!("Kunne ikke sende selektor: {}", e)))?;
+        .map_err(|_| GopherError::Timeout(self.timeout_seconds))??;
 
-    // Les respons med timeout og størrelsesbegrensning
-    let mut buffer = Vec::new();
-    let mut total_read = 0;
-    let mut temp_buf = [0u8; 8192];
+        Ok(String::from_utf8_lossy(&buffer[..read]).to_string())
+    }
 
-    loop {
-        let read_result = tokio::time::timeout(
-            Duration::from_secs(TIMEOUT_SECONDS),
-            reader.read(&mut temp_buf),
-        )
-        .await;
+    /// Start en nedlasting av en Gopher-ressurs
+    ///
+    /// Størrelsesgrensen for sider gjelder ikke, siden innholdet strømmes
+    /// rett til disk.
+    ///
+    /// # Returns
+    /// Tilkoblingen, som leses til serveren lukker den
+    pub async fn open_download(&self, url: &str) -> Result<OwnedReadHalf, GopherError> {
+        let parsed = parse_gopher_url(url)?;
+        if parsed.item_type == GopherItemType::Search && !parsed.selector.contains('\t') {
+            return Err(GopherError::SearchInputRequired);
+        }
+        let (reader, _) = self.send_selector(&parsed).await?;
+        Ok(reader)
+    }
+
+    /// Henter en Gopher-ressurs via TCP
+    ///
+    /// # Arguments
+    /// * `url` - Gopher-URL (gopher://host[:port]/[type][selector])
+    ///
+    /// # Returns
+    /// * `Ok(GopherResponse)` - Parsed respons
+    /// * `Err(GopherError)` - Ved feil
+    pub async fn fetch(&self, url: &str) -> Result<GopherResponse, GopherError> {
+        let parsed = parse_gopher_url(url)?;
+
+        info!(
+            "Gopher: Kobler til {}",
+            self.private
+                .log_url(&format!("{}:{}", parsed.host, parsed.port))
+        );
+
+        // Sjekk om dette er et søk som krever input
+        if parsed.item_type == GopherItemType::Search && parsed.selector.find('\t').is_none() {
+            // Søk uten query - be om input
+            return Err(GopherError::SearchInputRequired);
+        }
 
-        match read_result {
-            Ok(Ok(0)) => break, // Tilkobling lukket
-            Ok(Ok(n)) => {
-                total_read += n;
-                if total_read > MAX_RESPONSE_SIZE {
-                    return Err(GopherError::TooLarge(MAX_RESPONSE_SIZE));
+        let (mut reader, connect) = self.send_selector(&parsed).await?;
+        let started = Instant::now();
+
+        // Les respons med timeout og størrelsesbegrensning
+        let mut buffer = Vec::new();
+        let mut total_read = 0;
+        let mut temp_buf = [0u8; 8192];
+
+        loop {
+            let read_result = tokio::time::timeout(
+                Duration::from_secs(self.timeout_seconds),
+                reader.read(&mut temp_buf),
+            )
+            .await;
+
+            match read_result {
+                Ok(Ok(0)) => break, // Tilkobling lukket
+                Ok(Ok(n)) => {
+                    total_read += n;
+                    if total_read > self.max_response_size {
+                        return Err(GopherError::TooLarge(self.max_response_size));
+                    }
+                    buffer.extend_from_slice(&temp_buf[..n]);
                 }
-                buffer.extend_from_slice(&temp_buf[..n]);
-            }
-            Ok(Err(e)) => {
-                warn!("Gopher: Lesefeil: {}", e);
-                if buffer.is_empty() {
-                    return Err(GopherError::Io(e));
+                Ok(Err(e)) => {
+                    warn!("Gopher: Lesefeil: {}", e);
+                    if buffer.is_empty() {
+                        return Err(GopherError::Io(e));
+                    }
+                    // Bruk det vi har lest så langt
+                    break;
                 }
-                // Bruk det vi har lest så langt
-                break;
-            }
-            Err(_) => {
-                if buffer.is_empty() {
-                    return Err(GopherError::Timeout(TIMEOUT_SECONDS));
+                Err(_) => {
+                    if buffer.is_empty() {
+                        return Err(GopherError::Timeout(self.timeout_seconds));
+                    }
+                    // Bruk det vi har lest så langt
+                    break;
                 }
-                // Bruk det vi har lest så langt
-                break;
             }
         }
-    }
 
-    debug!("Gopher: Mottok {} bytes", buffer.len());
+        debug!("Gopher: Mottok {} bytes", buffer.len());
+        let timing = NetworkTiming {
+            connect: Some(connect),
+            tls: None,
+            transfer: Some(started.elapsed()),
+        };
 
-    // Konverter til streng, forsøk UTF-8 først, deretter Latin-1
-    let body = match String::from_utf8(buffer.clone()) {
-        Ok(s) => s,
-        Err(_) => {
-            // Fallback til Latin-1 (ISO-8859-1)
-            info!("Gopher: UTF-8-dekoding feilet, bruker Latin-1 fallback");
-            buffer.iter().map(|&b| b as char).collect()
-        }
-    };
+        // Konverter til streng, forsøk UTF-8 først, deretter Latin-1
+        let body = match String::from_utf8(buffer.clone()) {
+            Ok(s) => s,
+            Err(_) => {
+                // Fallback til Latin-1 (ISO-8859-1)
+                info!("Gopher: UTF-8-dekoding feilet, bruker Latin-1 fallback");
+                buffer.iter().map(|&b| b as char).collect()
+            }
+        };
 
-    // Bygg respons basert på URL-type
-    match parsed.item_type {
-        GopherItemType::Directory | GopherItemType::Search => {
-            let items = parse_menu(&body);
-            Ok(GopherResponse {
-                content_type: GopherContentType::Menu,
-                body,
-                items,
-                final_url: url.to_string(),
-            })
-        }
-        GopherItemType::TextFile => {
-            // Fjern terminering (. på egen linje)
-            let text = strip_termination(&body);
-            Ok(GopherResponse {
-                content_type: GopherContentType::Text,
-                body: text,
-                items: Vec::new(),
-                final_url: url.to_string(),
-            })
-        }
-        GopherItemType::Html => {
-            let text = strip_termination(&body);
-            Ok(GopherResponse {
-                content_type: GopherContentType::Html,
-                body: text,
-                items: Vec::new(),
-                final_url: url.to_string(),
-            })
-        }
-        GopherItemType::Error => Ok(GopherResponse {
-            content_type: GopherContentType::Error,
-            body: body.clone(),
-            items: parse_menu(&body),
-            final_url: url.to_string(),
-        }),
-        GopherItemType::Info => {
-            // Info-type i URL → behandle som tekstfil
-            let text = strip_termination(&body);
-            Ok(GopherResponse {
-                content_type: GopherContentType::Text,
-                body: text,
-                items: Vec::new(),
-                final_url: url.to_string(),
-            })
-        }
-        _ => {
-            // Andre typer (binær, bilder, etc.) — prøv å tolke som meny eller tekst
-            warn!(
-                "Gopher: Ukjent/usupported type '{}', forsøker tekstvisning",
-                parsed.item_type.to_char()
-            );
-            let text = strip_termination(&body);
-            Ok(GopherResponse {
-                content_type: GopherContentType::Text,
-                body: text,
-                items: Vec::new(),
+        // Bygg respons basert på URL-type
+        match parsed.item_type {
+            GopherItemType::Directory | GopherItemType::Search => {
+                let items = parse_menu(&body);
+                Ok(GopherResponse {
+                    content_type: GopherContentType::Menu,
+                    body,
+                    items,
+                    raw: buffer,
+                    final_url: url.to_string(),
+                    timing,
+                })
+            }
+            GopherItemType::TextFile => {
+                // Fjern terminering (. på egen linje)
+                let text = strip_termination(&body);
+                Ok(GopherResponse {
+                    content_type: GopherContentType::Text,
+                    body: text,
+                    items: Vec::new(),
+                    raw: buffer,
+                    final_url: url.to_string(),
+                    timing,
+                })
+            }
+            GopherItemType::Html => {
+                let text = strip_termination(&body);
+                Ok(GopherResponse {
+                    content_type: GopherContentType::Html,
+                    body: text,
+                    items: Vec::new(),
+                    raw: buffer,
+                    final_url: url.to_string(),
+                    timing,
+                })
+            }
+            GopherItemType::Error => Ok(GopherResponse {
+                content_type: GopherContentType::Error,
+                body: body.clone(),
+                items: parse_menu(&body),
+                raw: buffer,
                 final_url: url.to_string(),
-            })
+                timing,
+            }),
+            GopherItemType::Info => {
+                // Info-type i URL → behandle som tekstfil
+                let text = strip_termination(&body);
+                Ok(GopherResponse {
+                    content_type: GopherContentType::Text,
+                    body: text,
+                    items: Vec::new(),
+                    raw: buffer,
+                    final_url: url.to_string(),
+                    timing,
+                })
+            }
+            _ => {
+                // Andre typer (binær, bilder, etc.) — prøv å tolke som meny eller tekst
+                warn!(
+                    "Gopher: Ukjent/usupported type '{}', forsøker tekstvisning",
+                    parsed.item_type.to_char()
+                );
+                let text = strip_termination(&body);
+                Ok(GopherResponse {
+                    content_type: GopherContentType::Text,
+                    body: text,
+                    items: Vec::new(),
+                    raw: buffer,
+                    final_url: url.to_string(),
+                    timing,
+                })
+            }
         }
     }
-}
 
-/// Utfører et Gopher-søk (type 7)
-///
-/// # Arguments
-/// * `url` - Gopher-søke-URL (gopher://host/7/selector)
-/// * `query` - Søkestreng fra brukeren
-///
-/// # Returns
-/// * `Ok(GopherResponse)` - Søkeresultater som meny
-/// * `Err(GopherError)` - Ved feil
-pub async fn search(url: &str, query: &str) -> Result<GopherResponse, GopherError> {
-    let parsed = parse_gopher_url(url)?;
-
-    info!(
-        "Gopher: Søker på {}:{} med query: {}",
-        parsed.host, parsed.port, query
-    );
-
-    // TCP-tilkobling med timeout
-    let addr = format!("{}:{}", parsed.host, parsed.port);
-    let stream = tokio::time::timeout(
-        Duration::from_secs(TIMEOUT_SECONDS),
-        TcpStream::connect(&addr),
-    )
-    .await
-    .map_err(|_| GopherError::Timeout(TIMEOUT_SECONDS))?
-    .map_err(|e| GopherError::ConnectionError(format!("Kunne ikke koble til {}: {}", addr, e)))?;
-
-    // Send selektor\tsøkestreng\r\n
-    let search_str = format!("{}\t{}\r\n", parsed.selector, query);
-    debug!("Gopher: Sender søk: {:?}", search_str.trim());
-
-    let (mut reader, mut writer) = stream.into_split();
-
-    writer.write_all(search_str.as_bytes()).await.map_err(|e| {
-        GopherError::ConnectionError(format!("Kunne ikke sende søkeforespørsel: {}", e))
-    })?;
-
-    // Les respons
-    let mut buffer = Vec::new();
-    let mut total_read = 0;
-    let mut temp_buf = [0u8; 8192];
-
-    loop {
-        let read_result = tokio::time::timeout(
-            Duration::from_secs(TIMEOUT_SECONDS),
-            reader.read(&mut temp_buf),
+    /// Utfører et Gopher-søk (type 7)
+    ///
+    /// # Arguments
+    /// * `url` - Gopher-søke-URL (gopher://host/7/selector)
+    /// * `query` - Søkestreng fra brukeren
+    ///
+    /// # Returns
+    /// * `Ok(GopherResponse)` - Søkeresultater som meny
+    /// * `Err(GopherError)` - Ved feil
+    pub async fn search(&self, url: &str, query: &str) -> Result<GopherResponse, GopherError> {
+        let parsed = parse_gopher_url(url)?;
+
+        info!(
+            "Gopher: Søker på {} med query: {}",
+            self.private
+                .log_url(&format!("{}:{}", parsed.host, parsed.port)),
+            self.private.log_url(query)
+        );
+
+        // TCP-tilkobling med timeout
+        let addr = format!("{}:{}", parsed.host, parsed.port);
+        let started = Instant::now();
+        let stream = tokio::time::timeout(
+            Duration::from_secs(self.timeout_seconds),
+            TcpStream::connect(&addr),
         )
-        .await;
-
-        match read_result {
-            Ok(Ok(0)) => break,
-            Ok(Ok(n)) => {
-                total_read += n;
-                if total_read > MAX_RESPONSE_SIZE {
-                    return Err(GopherError::TooLarge(MAX_RESPONSE_SIZE));
+        .await
+        .map_err(|_| GopherError::Timeout(self.timeout_seconds))?
+        .map_err(|e| {
+            GopherError::ConnectionError(format!("Kunne ikke koble til {}: {}", addr, e))
+        })?;
+        let connected = Instant::now();
+
+        // Send selektor\tsøkestreng\r\n
+        let search_str = format!("{}\t{}\r\n", parsed.selector, query);
+        debug!(
+            "Gopher: Sender søk: {:?}",
+            self.private.log_url(search_str.trim())
+        );
+
+        let (mut reader, mut writer) = stream.into_split();
+
+        writer.write_all(search_str.as_bytes()).await.map_err(|e| {
+            GopherError::ConnectionError(format!("Kunne ikke sende søkeforespørsel: {}", e))
+        })?;
+
+        // Les respons
+        let mut buffer = Vec::new();
+        let mut total_read = 0;
+        let mut temp_buf = [0u8; 8192];
+
+        loop {
+            let read_result = tokio::time::timeout(
+                Duration::from_secs(self.timeout_seconds),
+                reader.read(&mut temp_buf),
+            )
+            .await;
+
+            match read_result {
+                Ok(Ok(0)) => break,
+                Ok(Ok(n)) => {
+                    total_read += n;
+                    if total_read > self.max_response_size {
+                        return Err(GopherError::TooLarge(self.max_response_size));
+                    }
+                    buffer.extend_from_slice(&temp_buf[..n]);
                 }
-                buffer.extend_from_slice(&temp_buf[..n]);
-            }
-            Ok(Err(e)) => {
-                if buffer.is_empty() {
-                    return Err(GopherError::Io(e));
+                Ok(Err(e)) => {
+                    if buffer.is_empty() {
+                        return Err(GopherError::Io(e));
+                    }
+                    break;
                 }
-                break;
-            }
-            Err(_) => {
-                if buffer.is_empty() {
-                    return Err(GopherError::Timeout(TIMEOUT_SECONDS));
+                Err(_) => {
+                    if buffer.is_empty() {
+                        return Err(GopherError::Timeout(self.timeout_seconds));
+                    }
+                    break;
                 }
-                break;
             }
         }
-    }
 
-    // Konverter til streng
-    let body = match String::from_utf8(buffer.clone()) {
-        Ok(s) => s,
-        Err(_) => buffer.iter().map(|&b| b as char).collect(),
-    };
+        let timing = NetworkTiming {
+            connect: Some(connected - started),
+            tls: None,
+            transfer: Some(connected.elapsed()),
+        };
 
-    let items = parse_menu(&body);
-    Ok(GopherResponse {
-        content_type: GopherContentType::Menu,
-        body,
-        items,
-        final_url: url.to_string(),
-    })
+        // Konverter til streng
+        let body = match String::from_utf8(buffer.clone()) {
+            Ok(s) => s,
+            Err(_) => buffer.iter().map(|&b| b as char).collect(),
+        };
+
+        let items = parse_menu(&body);
+        Ok(GopherResponse {
+            content_type: GopherContentType::Menu,
+            body,
+            items,
+            raw: buffer,
+            final_url: url.to_string(),
+            timing,
+        })
+    }
 }
 
 /// Fjerner Gopher-terminering (. på egen linje i slutten)
@@ -619,6 +794,42 @@ pub fn build_gopher_url(item: &GopherItem) -> String {
     }
 }
 
+/// Menyene over et element, ut fra selektoren
+///
+/// Gopher har ingen mapper, men de fleste servere bruker selektorer som
+/// ligner stier. Hver del av selektoren regnes derfor som en meny. Rotmenyen
+/// kommer først, med vertsnavnet som tekst, og elementet selv kommer sist.
+pub fn selector_trail(url: &GopherUrl) -> Vec<GopherItem> {
+    let item = |item_type, display: &str, selector: String| GopherItem {
+        item_type,
+        display: display.to_string(),
+        selector,
+        host: url.host.clone(),
+        port: url.port,
+    };
+
+    let mut trail = vec![item(GopherItemType::Directory, &url.host, String::new())];
+    let segments: Vec<&str> = url.selector.split('/').filter(|s| !s.is_empty()).collect();
+    let leading_slash = if url.selector.starts_with('/') {
+        "/"
+    } else {
+        ""
+    };
+    for (i, segment) in segments.iter().enumerate() {
+        let is_last = i + 1 == segments.len();
+        let (item_type, selector) = if is_last {
+            (url.item_type.clone(), url.selector.clone())
+        } else {
+            (
+                GopherItemType::Directory,
+                format!("{}{}", leading_slash, segments[..=i].join("/")),
+            )
+        };
+        trail.push(item(item_type, segment, selector));
+    }
+    trail
+}
+
 /// Løser en relativ Gopher-URL mot en base-URL
 pub fn resolve_gopher_url(base_url: &str, relative_url: &str) -> Result<String, GopherError> {
     // Hvis det allerede er en absolutt URL, returner den
@@ -645,6 +856,7 @@ pub fn resolve_gopher_url(base_url: &str, relative_url: &str) -> Result<String,
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::{GopherServer, Reply};
 
     #[test]
     fn test_parse_gopher_url_basic() {
@@ -655,6 +867,31 @@ mod tests {
         assert_eq!(url.selector, "");
     }
 
+    #[test]
+    fn test_selector_trail() {
+        let url = parse_gopher_url("gopher://example.com:7070/0/docs/rust/intro.txt").unwrap();
+        let trail: Vec<(String, String)> = selector_trail(&url)
+            .iter()
+            .map(|item| (item.display.clone(), build_gopher_url(item)))
+            .collect();
+        assert_eq!(
+            trail,
+            [
+                ("example.com", "gopher://example.com:7070/1"),
+                ("docs", "gopher://example.com:7070/1/docs"),
+                ("rust", "gopher://example.com:7070/1/docs/rust"),
+                (
+                    "intro.txt",
+                    "gopher://example.com:7070/0/docs/rust/intro.txt"
+                ),
+            ]
+            .map(|(display, url)| (display.to_string(), url.to_string()))
+        );
+
+        let root = parse_gopher_url("gopher://example.com/").unwrap();
+        assert_eq!(selector_trail(&root).len(), 1);
+    }
+
     #[test]
     fn test_parse_gopher_url_with_type() {
         let url = parse_gopher_url("gopher://example.com/0/readme.txt").unwrap();
@@ -805,4 +1042,117 @@ mod tests {
         let result = resolve_gopher_url("gopher://example.com/1/docs", "gopher://other.com/0/file");
         assert_eq!(result.unwrap(), "gopher://other.com/0/file");
     }
+
+    #[tokio::test]
+    async fn test_fetch_honors_timeout_from_settings() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            // Godta tilkoblingen, men svar aldri
+            let (_socket, _) = listener.accept().await.unwrap();
+            tokio::time::sleep(Duration::from_secs(10)).await;
+        });
+
+        let client = GopherClient::with_limits(&NetworkSettings {
+            timeout_seconds: 1,
+            ..Default::default()
+        });
+        let result = client.fetch(&format!("gopher://{}/0/tekst", addr)).await;
+        assert!(matches!(result, Err(GopherError::Timeout(1))));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_menu() {
+        let server = GopherServer::start(|_| {
+            Reply::text(
+                "iVelkommen\t\terror.host\t1\r\n\
+                 0Om serveren\t/om.txt\tlocalhost\t70\r\n\
+                 1Arkiv\t/arkiv\tlocalhost\t70\r\n\
+                 .\r\n",
+            )
+        })
+        .await;
+        let client = GopherClient::default();
+
+        let response = client.fetch(&server.url("/")).await.unwrap();
+        assert!(matches!(response.content_type, GopherContentType::Menu));
+        assert_eq!(response.items.len(), 3);
+        assert_eq!(response.items[1].item_type, GopherItemType::TextFile);
+        assert_eq!(response.items[1].selector, "/om.txt");
+        assert_eq!(response.items[2].display, "Arkiv");
+        assert_eq!(server.requests(), [""]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_text() {
+        let server =
+            GopherServer::start(|_| Reply::text("Første linje\r\nAndre linje\r\n.\r\n")).await;
+        let client = GopherClient::default();
+
+        let response = client.fetch(&server.url("/0/om.txt")).await.unwrap();
+        assert!(matches!(response.content_type, GopherContentType::Text));
+        assert_eq!(response.body, "Første linje\r\nAndre linje");
+        assert_eq!(server.requests(), ["/om.txt"]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_too_large() {
+        let server = GopherServer::start(|_| Reply::Close(vec![b'x'; 1024 * 1024 + 1])).await;
+        let client = GopherClient::with_limits(&NetworkSettings {
+            max_response_mb: 1,
+            ..Default::default()
+        });
+
+        let result = client.fetch(&server.url("/0/stor.txt")).await;
+        assert!(matches!(result, Err(GopherError::TooLarge(1048576))));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_timeout_keeps_partial_data() {
+        let server = GopherServer::start(|_| Reply::Stall(b"Begynnelsen\r\n".to_vec())).await;
+        let client = GopherClient::with_limits(&NetworkSettings {
+            timeout_seconds: 1,
+            ..Default::default()
+        });
+
+        let response = client.fetch(&server.url("/0/treg.txt")).await.unwrap();
+        assert_eq!(response.body, "Begynnelsen\r\n");
+    }
+
+    #[tokio::test]
+    async fn test_search_sends_query() {
+        let server = GopherServer::start(|request| {
+            let (_, query) = request.split_once('\t').unwrap();
+            Reply::text(format!(
+                "0Treff på {}\t/treff\tlocalhost\t70\r\n.\r\n",
+                query
+            ))
+        })
+        .await;
+        let client = GopherClient::default();
+
+        let response = client
+            .search(&server.url("/7/sok"), "mose og lav")
+            .await
+            .unwrap();
+        assert!(matches!(response.content_type, GopherContentType::Menu));
+        assert_eq!(response.items.len(), 1);
+        assert_eq!(response.items[0].display, "Treff på mose og lav");
+        assert_eq!(server.requests(), ["/sok\tmose og lav"]);
+    }
+
+    #[tokio::test]
+    async fn test_search_timeout_keeps_partial_data() {
+        let server =
+            GopherServer::start(|_| Reply::Stall(b"0Delvis\t/delvis\tlocalhost\t70\r\n".to_vec()))
+                .await;
+        let client = GopherClient::with_limits(&NetworkSettings {
+            timeout_seconds: 1,
+            ..Default::default()
+        });
+
+        let response = client.search(&server.url("/7/sok"), "ord").await.unwrap();
+        assert_eq!(response.items.len(), 1);
+        assert_eq!(response.items[0].selector, "/delvis");
+    }
 }
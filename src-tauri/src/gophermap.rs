@@ -1,9 +1,12 @@
 //! Gopher-meny til Markdown konvertering
 //!
 //! Konverterer Gopher-menyer (gophermap) til Markdown-format.
-//! Hver elementtype konverteres til passende markdown med emoji-ikoner.
+//! Hver elementtype konverteres til passende markdown, med emoji, tekst
+//! eller ingen ikoner foran lenkene etter brukerens innstilling.
 
 use crate::gopher::{build_gopher_url, GopherItem, GopherItemType};
+use crate::locale;
+use crate::settings::Decorations;
 
 /// Resultat fra gophermap-konvertering
 #[derive(Debug, Clone)]
@@ -19,10 +22,15 @@ pub struct GophermapResult {
 /// # Arguments
 /// * `items` - Gopher-meny-elementer
 /// * `_base_url` - Base-URL for å løse relative lenker
+/// * `decorations` - Ikonene foran lenkene
 ///
 /// # Returns
 /// GophermapResult med markdown og eventuell tittel
-pub fn to_markdown(items: &[GopherItem], _base_url: &str) -> GophermapResult {
+pub fn to_markdown(
+    items: &[GopherItem],
+    _base_url: &str,
+    decorations: Decorations,
+) -> GophermapResult {
     let mut output = String::new();
     let mut title: Option<String> = None;
     let mut prev_was_info = false;
@@ -42,43 +50,47 @@ pub fn to_markdown(items: &[GopherItem], _base_url: &str) -> GophermapResult {
                     output.push('\n');
                 }
                 prev_was_info = false;
-                convert_directory(item)
+                convert_directory(item, decorations)
             }
             GopherItemType::TextFile => {
                 if prev_was_info {
                     output.push('\n');
                 }
                 prev_was_info = false;
-                convert_text_file(item)
+                convert_text_file(item, decorations)
             }
             GopherItemType::Search => {
                 if prev_was_info {
                     output.push('\n');
                 }
                 prev_was_info = false;
-                convert_search(item)
+                convert_search(item, decorations)
             }
             GopherItemType::Html => {
                 if prev_was_info {
                     output.push('\n');
                 }
                 prev_was_info = false;
-                convert_html_link(item)
+                convert_html_link(item, decorations)
             }
             GopherItemType::Error => {
                 prev_was_info = false;
-                format!("⚠️ {}", item.display)
+                format!("{}{}", prefix(decorations, "⚠️", "ERROR"), item.display)
             }
             GopherItemType::Gif | GopherItemType::Image => {
                 if prev_was_info {
                     output.push('\n');
                 }
                 prev_was_info = false;
-                convert_image(item)
+                convert_image(item, decorations)
             }
             GopherItemType::Telnet | GopherItemType::Telnet3270 => {
                 prev_was_info = false;
-                format!("  {} *(Telnet — ikke støttet)*", item.display)
+                format!(
+                    "  {} *({})*",
+                    item.display,
+                    locale::t("gopher.telnet_unsupported")
+                )
             }
             GopherItemType::Binary
             | GopherItemType::BinHex
@@ -92,7 +104,12 @@ pub fn to_markdown(items: &[GopherItem], _base_url: &str) -> GophermapResult {
             }
             GopherItemType::CsoPhonebook => {
                 prev_was_info = false;
-                format!("📖 {} *(CSO-telefonbok)*", item.display)
+                format!(
+                    "{}{} *({})*",
+                    prefix(decorations, "📖", "CSO"),
+                    item.display,
+                    locale::t("gopher.cso_phonebook")
+                )
             }
             GopherItemType::Unknown(_) => {
                 prev_was_info = false;
@@ -110,43 +127,82 @@ pub fn to_markdown(items: &[GopherItem], _base_url: &str) -> GophermapResult {
     }
 }
 
+/// Ikonet foran et element, med mellomrom etter
+///
+/// # Arguments
+/// * `decorations` - Valgt visning
+/// * `emoji` - Ikonet i emoji-modus
+/// * `label` - Teksten i tekstmodus, vises som `[DIR]`
+fn prefix(decorations: Decorations, emoji: &str, label: &str) -> String {
+    match decorations {
+        Decorations::Emoji => format!("{} ", emoji),
+        Decorations::Text => format!("\\[{}\\] ", label),
+        Decorations::None => String::new(),
+    }
+}
+
 /// Konverterer en informasjonslinje
 fn convert_info_line(item: &GopherItem) -> String {
     item.display.clone()
 }
 
 /// Konverterer en mappe/meny-lenke
-fn convert_directory(item: &GopherItem) -> String {
+fn convert_directory(item: &GopherItem, decorations: Decorations) -> String {
     let url = build_gopher_url(item);
-    format!("📁 [{}]({})", item.display, url)
+    format!(
+        "{}[{}]({})",
+        prefix(decorations, "📁", "DIR"),
+        item.display,
+        url
+    )
 }
 
 /// Konverterer en tekstfil-lenke
-fn convert_text_file(item: &GopherItem) -> String {
+fn convert_text_file(item: &GopherItem, decorations: Decorations) -> String {
     let url = build_gopher_url(item);
-    format!("📄 [{}]({})", item.display, url)
+    format!(
+        "{}[{}]({})",
+        prefix(decorations, "📄", "TXT"),
+        item.display,
+        url
+    )
 }
 
 /// Konverterer en søke-lenke
-fn convert_search(item: &GopherItem) -> String {
+fn convert_search(item: &GopherItem, decorations: Decorations) -> String {
     let url = build_gopher_url(item);
-    format!("🔍 [{}]({})", item.display, url)
+    format!(
+        "{}[{}]({})",
+        prefix(decorations, "🔍", "SEARCH"),
+        item.display,
+        url
+    )
 }
 
 /// Konverterer en HTML-lenke (type h)
-fn convert_html_link(item: &GopherItem) -> String {
+fn convert_html_link(item: &GopherItem, decorations: Decorations) -> String {
     let url = if let Some(stripped) = item.selector.strip_prefix("URL:") {
         stripped.to_string()
     } else {
         build_gopher_url(item)
     };
-    format!("🌐 [{}]({})", item.display, url)
+    format!(
+        "{}[{}]({})",
+        prefix(decorations, "🌐", "HTML"),
+        item.display,
+        url
+    )
 }
 
 /// Konverterer en bilde-lenke
-fn convert_image(item: &GopherItem) -> String {
+fn convert_image(item: &GopherItem, decorations: Decorations) -> String {
     let url = build_gopher_url(item);
-    format!("🖼️ [{}]({})", item.display, url)
+    format!(
+        "{}[{}]({})",
+        prefix(decorations, "🖼️", "IMG"),
+        item.display,
+        url
+    )
 }
 
 // ===== Tester =====
@@ -164,7 +220,7 @@ mod tests {
             host: "(NULL)".to_string(),
             port: 0,
         };
-        let result = to_markdown(&[item], "gopher://example.com/");
+        let result = to_markdown(&[item], "gopher://example.com/", Decorations::Emoji);
         assert!(result.markdown.contains("Welcome to Gopher"));
         assert!(!result.markdown.contains("[")); // Ingen lenke
     }
@@ -178,7 +234,7 @@ mod tests {
             host: "example.com".to_string(),
             port: 70,
         };
-        let result = to_markdown(&[item], "gopher://example.com/");
+        let result = to_markdown(&[item], "gopher://example.com/", Decorations::Emoji);
         assert!(result.markdown.contains("📁"));
         assert!(result.markdown.contains("[Documents]"));
         assert!(result.markdown.contains("gopher://example.com/1/docs"));
@@ -193,7 +249,7 @@ mod tests {
             host: "example.com".to_string(),
             port: 70,
         };
-        let result = to_markdown(&[item], "gopher://example.com/");
+        let result = to_markdown(&[item], "gopher://example.com/", Decorations::Emoji);
         assert!(result.markdown.contains("📄"));
         assert!(result.markdown.contains("[README]"));
     }
@@ -207,7 +263,7 @@ mod tests {
             host: "example.com".to_string(),
             port: 70,
         };
-        let result = to_markdown(&[item], "gopher://example.com/");
+        let result = to_markdown(&[item], "gopher://example.com/", Decorations::Emoji);
         assert!(result.markdown.contains("🔍"));
         assert!(result.markdown.contains("[Search]"));
     }
@@ -221,7 +277,7 @@ mod tests {
             host: "example.com".to_string(),
             port: 70,
         };
-        let result = to_markdown(&[item], "gopher://example.com/");
+        let result = to_markdown(&[item], "gopher://example.com/", Decorations::Emoji);
         assert!(result.markdown.contains("🌐"));
         assert!(result.markdown.contains("[Google]"));
         assert!(result.markdown.contains("https://google.com"));
@@ -236,7 +292,7 @@ mod tests {
             host: "".to_string(),
             port: 0,
         };
-        let result = to_markdown(&[item], "gopher://example.com/");
+        let result = to_markdown(&[item], "gopher://example.com/", Decorations::Emoji);
         assert!(result.markdown.contains("⚠️"));
         assert!(result.markdown.contains("Not found"));
     }
@@ -259,7 +315,7 @@ mod tests {
                 port: 70,
             },
         ];
-        let result = to_markdown(&items, "gopher://example.com/");
+        let result = to_markdown(&items, "gopher://example.com/", Decorations::Emoji);
         assert!(result.markdown.contains("Welcome"));
         assert!(result.markdown.contains("📁 [Docs]"));
     }
@@ -282,7 +338,7 @@ mod tests {
                 port: 0,
             },
         ];
-        let result = to_markdown(&items, "gopher://example.com/");
+        let result = to_markdown(&items, "gopher://example.com/", Decorations::Emoji);
         assert_eq!(result.title, Some("Welcome to My Server".to_string()));
     }
 
@@ -295,7 +351,7 @@ mod tests {
             host: "example.com".to_string(),
             port: 70,
         };
-        let result = to_markdown(&[item], "gopher://example.com/");
+        let result = to_markdown(&[item], "gopher://example.com/", Decorations::Emoji);
         assert!(result.markdown.contains("🖼️"));
         assert!(result.markdown.contains("[Photo]"));
     }
@@ -309,14 +365,94 @@ mod tests {
             host: "example.com".to_string(),
             port: 23,
         };
-        let result = to_markdown(&[item], "gopher://example.com/");
+        let result = to_markdown(&[item], "gopher://example.com/", Decorations::Emoji);
         assert!(result.markdown.contains("ikke støttet"));
         assert!(!result.markdown.contains("[")); // Ingen klikkbar lenke
     }
 
+    fn item(item_type: GopherItemType, display: &str, selector: &str) -> GopherItem {
+        GopherItem {
+            item_type,
+            display: display.to_string(),
+            selector: selector.to_string(),
+            host: "example.com".to_string(),
+            port: 70,
+        }
+    }
+
+    #[test]
+    fn test_decorations_per_mode() {
+        let cases = [
+            (
+                item(GopherItemType::Directory, "Docs", "/docs"),
+                "📁 [Docs](gopher://example.com/1/docs)",
+                "\\[DIR\\] [Docs](gopher://example.com/1/docs)",
+                "[Docs](gopher://example.com/1/docs)",
+            ),
+            (
+                item(GopherItemType::TextFile, "README", "/readme.txt"),
+                "📄 [README](gopher://example.com/0/readme.txt)",
+                "\\[TXT\\] [README](gopher://example.com/0/readme.txt)",
+                "[README](gopher://example.com/0/readme.txt)",
+            ),
+            (
+                item(GopherItemType::Search, "Search", "/search"),
+                "🔍 [Search](gopher://example.com/7/search)",
+                "\\[SEARCH\\] [Search](gopher://example.com/7/search)",
+                "[Search](gopher://example.com/7/search)",
+            ),
+            (
+                item(GopherItemType::Html, "Web", "URL:https://example.org"),
+                "🌐 [Web](https://example.org)",
+                "\\[HTML\\] [Web](https://example.org)",
+                "[Web](https://example.org)",
+            ),
+            (
+                item(GopherItemType::Image, "Photo", "/photo.jpg"),
+                "🖼️ [Photo](gopher://example.com/I/photo.jpg)",
+                "\\[IMG\\] [Photo](gopher://example.com/I/photo.jpg)",
+                "[Photo](gopher://example.com/I/photo.jpg)",
+            ),
+            (
+                item(GopherItemType::Error, "Not found", ""),
+                "⚠️ Not found",
+                "\\[ERROR\\] Not found",
+                "Not found",
+            ),
+        ];
+
+        for (item, emoji, text, none) in cases {
+            for (decorations, expected) in [
+                (Decorations::Emoji, emoji),
+                (Decorations::Text, text),
+                (Decorations::None, none),
+            ] {
+                let result = to_markdown(
+                    std::slice::from_ref(&item),
+                    "gopher://example.com/",
+                    decorations,
+                );
+                assert_eq!(
+                    result.markdown,
+                    format!("{}\n", expected),
+                    "{:?}",
+                    decorations
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_text_decorations_render_as_brackets() {
+        let items = [item(GopherItemType::Directory, "Docs", "/docs")];
+        let result = to_markdown(&items, "gopher://example.com/", Decorations::Text);
+        let html = crate::markdown::render_with_options(&result.markdown, &Default::default()).html;
+        assert!(html.contains("[DIR] <a href=\"gopher://example.com/1/docs\">Docs</a>"));
+    }
+
     #[test]
     fn test_empty_menu() {
-        let result = to_markdown(&[], "gopher://example.com/");
+        let result = to_markdown(&[], "gopher://example.com/", Decorations::Emoji);
         assert!(result.markdown.is_empty());
         assert!(result.title.is_none());
     }
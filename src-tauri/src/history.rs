@@ -0,0 +1,920 @@
+//! Historikk over besøkte sider
+//!
+//! Historikken er valgfri og skrives bare når `history_enabled` er på.
+//! Hvert besøk legges til som én JSON-linje i `history.jsonl` under
+//! config-mappen, slik at en ny oppføring ikke krever at hele filen skrives
+//! på nytt. Sletting og opprydding skriver filen atomisk i sin helhet.
+//!
+//! Besøk på samme adresse rett etter hverandre, f.eks. ved omlasting, slås
+//! sammen til én oppføring med flere `visits`. Den oppdaterte oppføringen
+//! legges til som en ny linje med samme id, og den siste linjen gjelder.
+
+use crate::bookmarks;
+use crate::locale::{Locale, Localize};
+use crate::search::{self, MatchRange};
+use crate::storage;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Sekunder i ett døgn
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+/// Feil som kan oppstå ved lesing og skriving av historikken
+#[derive(Debug, Error)]
+pub enum HistoryError {
+    #[error("Kunne ikke lese historikken: {0}")]
+    Read(String),
+
+    #[error("Kunne ikke lagre historikken: {0}")]
+    Write(String),
+}
+
+impl Localize for HistoryError {
+    fn localize_in(&self, locale: Locale) -> String {
+        match self {
+            Self::Read(e) => locale.format("error.history_read", &[e]),
+            Self::Write(e) => locale.format("error.history_write", &[e]),
+        }
+    }
+}
+
+/// Et besøk i historikken
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Løpenummer, unikt innenfor filen
+    pub id: u64,
+    /// Adressen som ble vist (etter eventuelle redirects)
+    pub url: String,
+    /// Sidens tittel, hvis den har en
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Når siden ble besøkt (Unix timestamp)
+    pub timestamp: u64,
+    /// Protokollen, f.eks. "https" eller "gemini"
+    pub scheme: String,
+    /// Antall besøk på rad; `timestamp` er det siste
+    #[serde(default = "one_visit")]
+    pub visits: u32,
+}
+
+fn one_visit() -> u32 {
+    1
+}
+
+/// En side med historikk, nyeste først
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryPage {
+    pub entries: Vec<HistoryEntry>,
+    /// Antall oppføringer totalt
+    pub total: usize,
+}
+
+/// Et treff i historikksøket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryMatch {
+    #[serde(flatten)]
+    pub entry: HistoryEntry,
+    /// Delene av tittelen som traff søket
+    pub title_ranges: Vec<MatchRange>,
+    /// Delene av adressen som traff søket
+    pub url_ranges: Vec<MatchRange>,
+    pub score: f64,
+}
+
+/// Tidsrommet som skal slettes fra historikken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HistoryRange {
+    LastHour,
+    LastDay,
+    LastWeek,
+    LastMonth,
+    All,
+}
+
+impl HistoryRange {
+    /// Eldste tidspunkt som omfattes, eller None for hele historikken
+    fn since(self, now: u64) -> Option<u64> {
+        let secs = match self {
+            Self::LastHour => 60 * 60,
+            Self::LastDay => DAY_SECS,
+            Self::LastWeek => 7 * DAY_SECS,
+            Self::LastMonth => 30 * DAY_SECS,
+            Self::All => return None,
+        };
+        Some(now.saturating_sub(secs))
+    }
+}
+
+/// En side fra historikken med samlet besøkstall
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SiteVisits {
+    pub url: String,
+    /// Tittelen fra siste besøk
+    pub title: Option<String>,
+    /// Antall besøk
+    pub visits: usize,
+    /// Siste besøk (Unix timestamp)
+    pub last_visit: u64,
+    /// Frecency-poeng (besøk vektet etter alder)
+    pub score: f64,
+}
+
+/// Vekten til ett besøk ut fra hvor gammelt det er
+///
+/// Ferske besøk teller mest, men gamle besøk teller fortsatt litt, slik at
+/// sider man bruker ofte holder seg oppe selv etter en pause.
+fn visit_weight(age_secs: u64) -> f64 {
+    match age_secs / DAY_SECS {
+        0..=3 => 100.0,
+        4..=13 => 70.0,
+        14..=30 => 50.0,
+        31..=90 => 30.0,
+        _ => 10.0,
+    }
+}
+
+/// Frecency for en side: antall besøk vektet etter alder
+///
+/// # Arguments
+/// * `visits` - Tidspunktene for besøkene (Unix timestamp)
+/// * `now` - Nåværende tidspunkt
+pub fn frecency(visits: &[u64], now: u64) -> f64 {
+    visits
+        .iter()
+        .map(|&timestamp| visit_weight(now.saturating_sub(timestamp)))
+        .sum()
+}
+
+/// Grenser for hvor mye historikk som beholdes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistoryLimits {
+    /// Maks antall oppføringer
+    pub max_entries: usize,
+    /// Maks alder i døgn
+    pub max_age_days: u32,
+}
+
+/// Historikken i minnet, speilet av filen på disk
+#[derive(Debug)]
+pub struct History {
+    path: PathBuf,
+    entries: Vec<HistoryEntry>,
+    next_id: u64,
+    /// Antall linjer i filen; gjentatte besøk legger til linjer uten nye
+    /// oppføringer, så filen skrives sammen når den blir for lang
+    lines: usize,
+    /// Gemini-adresser som har bedt om sensitiv input (status 11)
+    ///
+    /// Holdes bare i minnet; svar på slike spørsmål skal aldri skrives.
+    sensitive: HashSet<String>,
+    /// Privat modus: ingen nye besøk tas imot
+    private: bool,
+}
+
+impl History {
+    /// Last historikken fra fil
+    ///
+    /// Linjer som ikke kan tolkes (f.eks. etter et krasj midt i en
+    /// skriving) hoppes over med en advarsel.
+    ///
+    /// # Arguments
+    /// * `path` - Historikkfilen; en fil som mangler gir tom historikk
+    pub fn load(path: &Path) -> Result<Self, HistoryError> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::empty(path)),
+            Err(e) => return Err(HistoryError::Read(e.to_string())),
+        };
+
+        let mut entries: Vec<HistoryEntry> = Vec::new();
+        let mut lines = 0;
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            lines += 1;
+            match serde_json::from_str::<HistoryEntry>(line) {
+                Ok(entry) => match entries.last_mut() {
+                    // Et gjentatt besøk erstatter linjen før
+                    Some(last) if last.id == entry.id => *last = entry,
+                    _ => entries.push(entry),
+                },
+                Err(e) => warn!("Hopper over ugyldig linje i {}: {}", path.display(), e),
+            }
+        }
+        let next_id = entries.iter().map(|e| e.id + 1).max().unwrap_or(1);
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            entries,
+            next_id,
+            lines,
+            sensitive: HashSet::new(),
+            private: false,
+        })
+    }
+
+    /// Tom historikk som lagres til `path`
+    pub fn empty(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            entries: Vec::new(),
+            next_id: 1,
+            lines: 0,
+            sensitive: HashSet::new(),
+            private: false,
+        }
+    }
+
+    /// Slå privat modus av eller på
+    ///
+    /// Mens privat modus er på, blir besøk verken lagret eller husket.
+    pub fn set_private(&mut self, private: bool) {
+        self.private = private;
+    }
+
+    /// Husk at en Gemini-adresse ba om sensitiv input
+    ///
+    /// Svar sendes som spørring til samme adresse, så alle varianter av
+    /// adressen med spørring holdes utenfor historikken.
+    pub fn mark_sensitive(&mut self, url: &str) {
+        self.sensitive.insert(without_query(url));
+    }
+
+    /// Om en adresse er et svar på et sensitivt spørsmål
+    pub fn is_sensitive(&self, url: &str) -> bool {
+        self.sensitive.contains(&without_query(url))
+    }
+
+    /// Om adressen er den siste som ble husket
+    pub fn is_latest(&self, url: &str) -> bool {
+        self.entries.last().is_some_and(|last| last.url == url)
+    }
+
+    /// Legg til et besøk
+    ///
+    /// Er adressen den samme som i forrige oppføring, telles besøket der i
+    /// stedet for i en ny oppføring.
+    ///
+    /// # Arguments
+    /// * `url` - Adressen som ble vist
+    /// * `title` - Sidens tittel
+    /// * `now` - Tidspunktet for besøket
+    /// * `limits` - Grensene som gjelder; eldre oppføringer ryddes bort
+    ///
+    /// # Returns
+    /// Den nye oppføringen, eller None hvis adressen ikke skal huskes
+    pub fn record(
+        &mut self,
+        url: &str,
+        title: Option<&str>,
+        now: u64,
+        limits: HistoryLimits,
+    ) -> Result<Option<HistoryEntry>, HistoryError> {
+        let Ok(parsed) = url::Url::parse(url) else {
+            return Ok(None);
+        };
+        if self.private || self.is_sensitive(url) {
+            return Ok(None);
+        }
+        let title = title.map(str::to_string).filter(|t| !t.trim().is_empty());
+
+        if let Some(last) = self.entries.last_mut().filter(|last| last.url == url) {
+            last.visits += 1;
+            last.timestamp = now;
+            if title.is_some() {
+                last.title = title;
+            }
+            let entry = last.clone();
+            self.append(&entry)?;
+            self.prune(now, limits)?;
+            return Ok(Some(entry));
+        }
+
+        let entry = HistoryEntry {
+            id: self.next_id,
+            url: url.to_string(),
+            title,
+            timestamp: now,
+            scheme: parsed.scheme().to_string(),
+            visits: 1,
+        };
+        self.append(&entry)?;
+        self.next_id += 1;
+        self.entries.push(entry.clone());
+
+        self.prune(now, limits)?;
+        Ok(Some(entry))
+    }
+
+    /// Hent en side med historikk, nyeste først
+    ///
+    /// # Arguments
+    /// * `offset` - Antall oppføringer som hoppes over
+    /// * `limit` - Maks antall oppføringer
+    pub fn page(&self, offset: usize, limit: usize) -> HistoryPage {
+        HistoryPage {
+            entries: self
+                .entries
+                .iter()
+                .rev()
+                .skip(offset)
+                .take(limit)
+                .cloned()
+                .collect(),
+            total: self.entries.len(),
+        }
+    }
+
+    /// Sidene med høyest frecency
+    ///
+    /// Besøk samles per normalisert adresse.
+    ///
+    /// # Arguments
+    /// * `limit` - Maks antall sider
+    /// * `now` - Nåværende tidspunkt
+    /// * `dismissed` - Normaliserte adresser brukeren har skjult
+    pub fn top_sites(
+        &self,
+        limit: usize,
+        now: u64,
+        dismissed: &BTreeSet<String>,
+    ) -> Vec<SiteVisits> {
+        let mut sites = self.sites(dismissed, |timestamps| frecency(timestamps, now));
+        sites.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then(b.last_visit.cmp(&a.last_visit))
+        });
+        sites.truncate(limit);
+        sites
+    }
+
+    /// De sist besøkte sidene, uten gjentakelser
+    ///
+    /// # Arguments
+    /// * `limit` - Maks antall sider
+    /// * `dismissed` - Normaliserte adresser brukeren har skjult
+    pub fn recent_sites(&self, limit: usize, dismissed: &BTreeSet<String>) -> Vec<SiteVisits> {
+        let mut sites = self.sites(dismissed, |_| 0.0);
+        sites.sort_by_key(|site| std::cmp::Reverse(site.last_visit));
+        sites.truncate(limit);
+        sites
+    }
+
+    /// Besøk samlet per normalisert adresse, med poeng fra `score`
+    fn sites(
+        &self,
+        dismissed: &BTreeSet<String>,
+        score: impl Fn(&[u64]) -> f64,
+    ) -> Vec<SiteVisits> {
+        let mut grouped: HashMap<String, (&HistoryEntry, Vec<u64>)> = HashMap::new();
+        for entry in &self.entries {
+            let key = bookmarks::normalize_url(&entry.url);
+            if dismissed.contains(&key) {
+                continue;
+            }
+            let (latest, timestamps) = grouped.entry(key).or_insert((entry, Vec::new()));
+            if entry.timestamp >= latest.timestamp {
+                *latest = entry;
+            }
+            timestamps.extend(std::iter::repeat_n(entry.timestamp, entry.visits as usize));
+        }
+
+        grouped
+            .into_values()
+            .map(|(latest, timestamps)| SiteVisits {
+                url: latest.url.clone(),
+                title: latest.title.clone(),
+                visits: timestamps.len(),
+                last_visit: latest.timestamp,
+                score: score(&timestamps),
+            })
+            .collect()
+    }
+
+    /// Søk i titler og adresser
+    ///
+    /// Hver adresse tas med én gang, med det siste besøket. Et treff i
+    /// tittelen teller litt mer enn i adressen, og nylige besøk løftes.
+    ///
+    /// # Arguments
+    /// * `query` - Søketeksten
+    /// * `limit` - Maks antall treff
+    /// * `now` - Nåværende tidspunkt
+    pub fn search(&self, query: &str, limit: usize, now: u64) -> Vec<HistoryMatch> {
+        let mut seen = HashSet::new();
+        let mut matches: Vec<HistoryMatch> = self
+            .entries
+            .iter()
+            .rev()
+            .filter(|entry| seen.insert(bookmarks::normalize_url(&entry.url)))
+            .filter_map(|entry| {
+                let title = entry
+                    .title
+                    .as_deref()
+                    .and_then(|title| search::find(title, query));
+                let url = search::find_in_url(&entry.url, query);
+                let title_score = title.as_ref().map(|m| m.kind.score() + 0.25);
+                let url_score = url.as_ref().map(|m| m.kind.score());
+                let best = title_score.into_iter().chain(url_score).reduce(f64::max)?;
+
+                Some(HistoryMatch {
+                    entry: entry.clone(),
+                    title_ranges: title.map(|m| m.ranges).unwrap_or_default(),
+                    url_ranges: url.map(|m| m.ranges).unwrap_or_default(),
+                    score: best + search::recency_boost(now.saturating_sub(entry.timestamp)),
+                })
+            })
+            .collect();
+
+        matches.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then(b.entry.timestamp.cmp(&a.entry.timestamp))
+        });
+        matches.truncate(limit);
+        matches
+    }
+
+    /// Slett én oppføring
+    ///
+    /// # Returns
+    /// true hvis oppføringen fantes
+    pub fn delete(&mut self, id: u64) -> Result<bool, HistoryError> {
+        let before = self.entries.len();
+        self.entries.retain(|e| e.id != id);
+        if self.entries.len() == before {
+            return Ok(false);
+        }
+        self.rewrite()?;
+        Ok(true)
+    }
+
+    /// Slett historikk fra et tidsrom
+    ///
+    /// # Returns
+    /// Antall oppføringer som ble slettet
+    pub fn clear(&mut self, range: HistoryRange, now: u64) -> Result<usize, HistoryError> {
+        let before = self.entries.len();
+        match range.since(now) {
+            Some(since) => self.entries.retain(|e| e.timestamp < since),
+            None => self.entries.clear(),
+        }
+        let removed = before - self.entries.len();
+        if removed > 0 || range == HistoryRange::All {
+            self.rewrite()?;
+        }
+        Ok(removed)
+    }
+
+    /// Fjern oppføringer som er for gamle eller over grensen for antall
+    ///
+    /// Filen skrives på nytt bare når noe faktisk fjernes, eller når
+    /// gjentatte besøk har gjort den mer enn dobbelt så lang som historikken.
+    ///
+    /// # Returns
+    /// Antall oppføringer som ble fjernet
+    pub fn prune(&mut self, now: u64, limits: HistoryLimits) -> Result<usize, HistoryError> {
+        let before = self.entries.len();
+        let oldest = now.saturating_sub(u64::from(limits.max_age_days) * DAY_SECS);
+        self.entries.retain(|e| e.timestamp >= oldest);
+        if self.entries.len() > limits.max_entries {
+            let excess = self.entries.len() - limits.max_entries;
+            self.entries.drain(..excess);
+        }
+
+        let removed = before - self.entries.len();
+        if removed > 0 || self.lines > 2 * self.entries.len() {
+            self.rewrite()?;
+        }
+        Ok(removed)
+    }
+
+    /// Legg én oppføring til på slutten av filen
+    fn append(&mut self, entry: &HistoryEntry) -> Result<(), HistoryError> {
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|e| HistoryError::Write(e.to_string()))?;
+        }
+        let line = serde_json::to_string(entry).map_err(|e| HistoryError::Write(e.to_string()))?;
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| writeln!(file, "{}", line))
+            .map_err(|e| HistoryError::Write(e.to_string()))?;
+        self.lines += 1;
+        Ok(())
+    }
+
+    /// Skriv hele filen på nytt ut fra oppføringene i minnet
+    fn rewrite(&mut self) -> Result<(), HistoryError> {
+        let mut content = String::new();
+        for entry in &self.entries {
+            let line =
+                serde_json::to_string(entry).map_err(|e| HistoryError::Write(e.to_string()))?;
+            content.push_str(&line);
+            content.push('\n');
+        }
+        storage::write_atomic(&self.path, &content)
+            .map_err(|e| HistoryError::Write(e.to_string()))?;
+        self.lines = self.entries.len();
+        Ok(())
+    }
+}
+
+/// Adressen uten spørring og fragment
+fn without_query(url: &str) -> String {
+    match url::Url::parse(url) {
+        Ok(mut parsed) => {
+            parsed.set_query(None);
+            parsed.set_fragment(None);
+            parsed.to_string()
+        }
+        Err(_) => url.split(['?', '#']).next().unwrap_or(url).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const NOW: u64 = 1_700_000_000;
+
+    const LIMITS: HistoryLimits = HistoryLimits {
+        max_entries: 1000,
+        max_age_days: 90,
+    };
+
+    fn urls(history: &History) -> Vec<String> {
+        history
+            .page(0, usize::MAX)
+            .entries
+            .into_iter()
+            .map(|e| e.url)
+            .collect()
+    }
+
+    #[test]
+    fn test_history_persists_across_reloads() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+
+        let mut history = History::load(&path).unwrap();
+        let first = history
+            .record("https://example.com/", Some("Eksempel"), NOW, LIMITS)
+            .unwrap()
+            .unwrap();
+        history
+            .record("gemini://example.org/", None, NOW + 1, LIMITS)
+            .unwrap();
+        assert_eq!(first.scheme, "https");
+
+        let mut reloaded = History::load(&path).unwrap();
+        assert_eq!(
+            urls(&reloaded),
+            vec!["gemini://example.org/", "https://example.com/"]
+        );
+        assert_eq!(
+            reloaded.page(1, 1).entries[0].title.as_deref(),
+            Some("Eksempel")
+        );
+        assert_eq!(reloaded.page(0, 1).total, 2);
+
+        // Nye ID-er fortsetter etter de lagrede
+        let next = reloaded
+            .record("https://example.net/", None, NOW + 2, LIMITS)
+            .unwrap()
+            .unwrap();
+        assert!(next.id > first.id + 1);
+
+        assert!(reloaded.delete(first.id).unwrap());
+        assert!(!reloaded.delete(first.id).unwrap());
+        assert_eq!(History::load(&path).unwrap().page(0, 0).total, 2);
+    }
+
+    #[test]
+    fn test_repeated_visits_are_merged() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let mut history = History::load(&path).unwrap();
+
+        let first = history
+            .record("https://example.com/", Some("Eksempel"), NOW, LIMITS)
+            .unwrap()
+            .unwrap();
+        let again = history
+            .record("https://example.com/", None, NOW + 5, LIMITS)
+            .unwrap()
+            .unwrap();
+        assert_eq!(again.id, first.id);
+        assert_eq!(again.visits, 2);
+        assert_eq!(again.timestamp, NOW + 5);
+        assert_eq!(again.title.as_deref(), Some("Eksempel"));
+
+        // Et besøk et annet sted imellom gir en ny oppføring
+        history
+            .record("https://example.org/", None, NOW + 6, LIMITS)
+            .unwrap();
+        history
+            .record("https://example.com/", None, NOW + 7, LIMITS)
+            .unwrap();
+        assert_eq!(
+            urls(&history),
+            vec![
+                "https://example.com/",
+                "https://example.org/",
+                "https://example.com/"
+            ]
+        );
+
+        let reloaded = History::load(&path).unwrap();
+        assert_eq!(
+            reloaded.page(0, usize::MAX).entries,
+            history.page(0, usize::MAX).entries
+        );
+        let top = reloaded.top_sites(1, NOW + 8, &BTreeSet::new());
+        assert_eq!(top[0].url, "https://example.com/");
+        assert_eq!(top[0].visits, 3);
+    }
+
+    #[test]
+    fn test_repeated_visits_keep_file_bounded() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let mut history = History::load(&path).unwrap();
+        history
+            .record("https://example.org/", None, NOW, LIMITS)
+            .unwrap();
+        for i in 1..=100 {
+            history
+                .record("https://example.com/", None, NOW + i, LIMITS)
+                .unwrap();
+        }
+        assert!(history.is_latest("https://example.com/"));
+        assert!(!history.is_latest("https://example.org/"));
+
+        let lines = fs::read_to_string(&path).unwrap().lines().count();
+        assert!(lines <= 4, "{} linjer", lines);
+        let reloaded = History::load(&path).unwrap();
+        assert_eq!(
+            urls(&reloaded),
+            vec!["https://example.com/", "https://example.org/"]
+        );
+        assert_eq!(reloaded.page(0, 1).entries[0].visits, 100);
+    }
+
+    #[test]
+    fn test_entries_without_visits_count_once() {
+        let entry: HistoryEntry = serde_json::from_str(
+            r#"{"id":1,"url":"https://example.com/","timestamp":1,"scheme":"https"}"#,
+        )
+        .unwrap();
+        assert_eq!(entry.visits, 1);
+    }
+
+    #[test]
+    fn test_private_session_writes_nothing() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let mut history = History::load(&path).unwrap();
+        history
+            .record("https://example.com/", None, NOW, LIMITS)
+            .unwrap();
+        let before = (
+            fs::read_to_string(&path).unwrap(),
+            storage::file_stamp(&path),
+        );
+
+        history.set_private(true);
+        for i in 0..3 {
+            let recorded = history
+                .record(
+                    &format!("https://privat.example/{}", i),
+                    None,
+                    NOW + i,
+                    LIMITS,
+                )
+                .unwrap();
+            assert!(recorded.is_none());
+        }
+        assert_eq!(
+            (
+                fs::read_to_string(&path).unwrap(),
+                storage::file_stamp(&path)
+            ),
+            before
+        );
+        assert_eq!(urls(&history), vec!["https://example.com/"]);
+
+        // Vanlig oppførsel igjen uten å laste på nytt
+        history.set_private(false);
+        history
+            .record("https://example.org/", None, NOW + 10, LIMITS)
+            .unwrap();
+        assert_eq!(History::load(&path).unwrap().page(0, 0).total, 2);
+    }
+
+    #[test]
+    fn test_corrupt_line_is_skipped() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let mut history = History::load(&path).unwrap();
+        history
+            .record("https://example.com/", None, NOW, LIMITS)
+            .unwrap();
+
+        let mut content = fs::read_to_string(&path).unwrap();
+        content.push_str("{\"id\": 9, \"url\": ");
+        fs::write(&path, content).unwrap();
+
+        assert_eq!(
+            urls(&History::load(&path).unwrap()),
+            vec!["https://example.com/"]
+        );
+    }
+
+    #[test]
+    fn test_prune_by_count_and_age() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let mut history = History::load(&path).unwrap();
+
+        let old = NOW - 100 * DAY_SECS;
+        history
+            .record("https://example.com/gammel", None, old, LIMITS)
+            .unwrap();
+        history
+            .record("https://example.com/ny", None, NOW, LIMITS)
+            .unwrap();
+        assert_eq!(urls(&history), vec!["https://example.com/ny"]);
+
+        let small = HistoryLimits {
+            max_entries: 2,
+            max_age_days: 90,
+        };
+        for i in 0..3 {
+            history
+                .record(&format!("https://example.com/{}", i), None, NOW + i, small)
+                .unwrap();
+        }
+        assert_eq!(
+            urls(&history),
+            vec!["https://example.com/2", "https://example.com/1"]
+        );
+        assert_eq!(History::load(&path).unwrap().page(0, 0).total, 2);
+    }
+
+    #[test]
+    fn test_frecency_prefers_recent_and_frequent() {
+        let day = |n: u64| NOW - n * DAY_SECS;
+
+        // Ingen besøk gir ingen poeng
+        assert_eq!(frecency(&[], NOW), 0.0);
+        // Ett ferskt besøk slår ett gammelt
+        assert!(frecency(&[day(1)], NOW) > frecency(&[day(200)], NOW));
+        // Flere besøk slår ett, innenfor samme alder
+        assert!(frecency(&[day(20), day(21)], NOW) > frecency(&[day(20)], NOW));
+        // Mange gamle besøk kan slå ett ferskt
+        let old: Vec<u64> = (0..20).map(|i| day(120 + i)).collect();
+        assert!(frecency(&old, NOW) > frecency(&[day(0)], NOW));
+        // Et besøk "i fremtiden" (klokken justert) teller som ferskt
+        assert_eq!(frecency(&[NOW + 60], NOW), 100.0);
+    }
+
+    #[test]
+    fn test_top_and_recent_sites() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let mut history = History::load(&path).unwrap();
+        let visit = |history: &mut History, url: &str, title: &str, at: u64| {
+            history.record(url, Some(title), at, LIMITS).unwrap();
+        };
+
+        for i in 0..3 {
+            visit(
+                &mut history,
+                "https://example.com/",
+                "Gammel tittel",
+                NOW - 10 - i,
+            );
+        }
+        visit(
+            &mut history,
+            "https://EXAMPLE.com/#del",
+            "Eksempel",
+            NOW - 5,
+        );
+        visit(&mut history, "gemini://example.org/", "Gemini", NOW - 1);
+        visit(&mut history, "https://skjult.example/", "Skjult", NOW);
+
+        let dismissed = BTreeSet::from(["https://skjult.example".to_string()]);
+        let top = history.top_sites(10, NOW, &dismissed);
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0].visits, 4);
+        assert_eq!(top[0].title.as_deref(), Some("Eksempel"));
+        assert_eq!(top[1].url, "gemini://example.org/");
+
+        let recent = history.recent_sites(1, &dismissed);
+        assert_eq!(recent[0].url, "gemini://example.org/");
+        assert_eq!(history.top_sites(10, NOW, &BTreeSet::new()).len(), 3);
+    }
+
+    #[test]
+    fn test_search_ranks_and_highlights() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let mut history = History::load(&path).unwrap();
+        let visit = |history: &mut History, url: &str, title: &str, at: u64| {
+            history.record(url, Some(title), at, LIMITS).unwrap();
+        };
+
+        visit(
+            &mut history,
+            "https://example.com/rust",
+            "Rust-boka",
+            NOW - 50 * DAY_SECS,
+        );
+        visit(&mut history, "https://example.com/b", "Ruby og Rust", NOW);
+        visit(
+            &mut history,
+            "https://example.com/c",
+            "Rask ustabil test",
+            NOW,
+        );
+        visit(
+            &mut history,
+            "https://example.com/rust/",
+            "Rust-boka",
+            NOW - 40 * DAY_SECS,
+        );
+        visit(&mut history, "https://example.com/d", "Annet", NOW);
+
+        let results = history.search("RUST", 10, NOW);
+        let urls: Vec<&str> = results.iter().map(|m| m.entry.url.as_str()).collect();
+        // Prefiks foran delstreng foran uskarpt treff, uansett hvor ferskt
+        assert_eq!(
+            urls,
+            [
+                "https://example.com/rust/",
+                "https://example.com/b",
+                "https://example.com/c",
+            ]
+        );
+        assert_eq!(results[0].title_ranges, [MatchRange { start: 0, end: 4 }]);
+        assert_eq!(results[0].url_ranges, [MatchRange { start: 20, end: 24 }]);
+        assert_eq!(results[1].title_ranges, [MatchRange { start: 8, end: 12 }]);
+        assert!(results[1].url_ranges.is_empty());
+
+        assert_eq!(history.search("rust", 1, NOW).len(), 1);
+        assert!(history.search("", 10, NOW).is_empty());
+    }
+
+    #[test]
+    fn test_clear_range() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let mut history = History::load(&path).unwrap();
+
+        history
+            .record(
+                "https://example.com/i-går",
+                None,
+                NOW - 2 * DAY_SECS,
+                LIMITS,
+            )
+            .unwrap();
+        history
+            .record("https://example.com/nå", None, NOW - 60, LIMITS)
+            .unwrap();
+
+        assert_eq!(history.clear(HistoryRange::LastHour, NOW).unwrap(), 1);
+        assert_eq!(urls(&History::load(&path).unwrap()).len(), 1);
+        assert_eq!(history.clear(HistoryRange::All, NOW).unwrap(), 1);
+        assert_eq!(History::load(&path).unwrap().page(0, 0).total, 0);
+    }
+
+    #[test]
+    fn test_sensitive_input_is_never_recorded() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let mut history = History::load(&path).unwrap();
+
+        history.mark_sensitive("gemini://example.org/login");
+        let recorded = history
+            .record("gemini://example.org/login?hemmelig", None, NOW, LIMITS)
+            .unwrap();
+        assert!(recorded.is_none());
+        history
+            .record("gemini://example.org/login/annet", None, NOW, LIMITS)
+            .unwrap();
+
+        assert_eq!(urls(&history), vec!["gemini://example.org/login/annet"]);
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("hemmelig"));
+    }
+}
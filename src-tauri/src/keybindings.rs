@@ -0,0 +1,417 @@
+//! Tastatursnarveier
+//!
+//! Handlingene som kan få egne snarveier, standardsnarveiene deres og
+//! validering av snarveier brukeren velger selv. Innstillingene lagrer bare
+//! brukerens endringer, så nye handlinger får standardsnarveien selv i eldre
+//! innstillingsfiler.
+
+use crate::locale::{Locale, Localize};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+/// Feil som kan oppstå ved endring av snarveier
+#[derive(Debug, Error, PartialEq)]
+pub enum KeyBindingError {
+    #[error("Ukjent handling: {0}")]
+    UnknownAction(String),
+
+    #[error("Ugyldig snarvei: {0}")]
+    InvalidAccelerator(String),
+
+    #[error("Snarveien {accelerator} brukes allerede av {action}")]
+    Duplicate {
+        accelerator: String,
+        action: &'static str,
+    },
+}
+
+impl Localize for KeyBindingError {
+    fn localize_in(&self, locale: Locale) -> String {
+        match self {
+            Self::UnknownAction(action) => locale.format("error.unknown_action", &[action]),
+            Self::InvalidAccelerator(accelerator) => {
+                locale.format("error.invalid_accelerator", &[accelerator])
+            }
+            Self::Duplicate {
+                accelerator,
+                action,
+            } => locale.format("error.duplicate_accelerator", &[accelerator, action]),
+        }
+    }
+}
+
+/// Handlinger som kan ha tastatursnarvei
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Action {
+    Reload,
+    HardReload,
+    OpenFile,
+    Find,
+    ToggleBookmark,
+    ShowBookmarks,
+    ZoomIn,
+    ZoomOut,
+    ZoomReset,
+    Back,
+    Forward,
+    FocusAddress,
+}
+
+impl Action {
+    /// Alle handlinger, i den rekkefølgen de vises i innstillingene
+    pub const ALL: [Action; 12] = [
+        Action::Reload,
+        Action::HardReload,
+        Action::OpenFile,
+        Action::Find,
+        Action::ToggleBookmark,
+        Action::ShowBookmarks,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::ZoomReset,
+        Action::Back,
+        Action::Forward,
+        Action::FocusAddress,
+    ];
+
+    /// Navnet som brukes i innstillingene og av frontend
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Action::Reload => "reload",
+            Action::HardReload => "hard-reload",
+            Action::OpenFile => "open-file",
+            Action::Find => "find",
+            Action::ToggleBookmark => "toggle-bookmark",
+            Action::ShowBookmarks => "show-bookmarks",
+            Action::ZoomIn => "zoom-in",
+            Action::ZoomOut => "zoom-out",
+            Action::ZoomReset => "zoom-reset",
+            Action::Back => "back",
+            Action::Forward => "forward",
+            Action::FocusAddress => "focus-address",
+        }
+    }
+
+    /// Finn en handling fra navnet
+    pub fn parse(name: &str) -> Result<Self, KeyBindingError> {
+        Self::ALL
+            .into_iter()
+            .find(|action| action.as_str() == name)
+            .ok_or_else(|| KeyBindingError::UnknownAction(name.to_string()))
+    }
+
+    /// Standardsnarveien for handlingen
+    pub fn default_accelerator(self) -> &'static str {
+        match self {
+            Action::Reload => "Ctrl+R",
+            Action::HardReload => "Ctrl+Shift+R",
+            Action::OpenFile => "Ctrl+O",
+            Action::Find => "Ctrl+F",
+            Action::ToggleBookmark => "Ctrl+D",
+            Action::ShowBookmarks => "Ctrl+B",
+            Action::ZoomIn => "Ctrl+Plus",
+            Action::ZoomOut => "Ctrl+Minus",
+            Action::ZoomReset => "Ctrl+0",
+            Action::Back => "Alt+ArrowLeft",
+            Action::Forward => "Alt+ArrowRight",
+            Action::FocusAddress => "Ctrl+L",
+        }
+    }
+}
+
+/// Navngitte taster, med alternative skrivemåter
+const NAMED_KEYS: &[(&str, &[&str])] = &[
+    ("Plus", &["+"]),
+    ("Minus", &["-"]),
+    ("Space", &[" "]),
+    ("ArrowLeft", &["Left"]),
+    ("ArrowRight", &["Right"]),
+    ("ArrowUp", &["Up"]),
+    ("ArrowDown", &["Down"]),
+    ("Home", &[]),
+    ("End", &[]),
+    ("PageUp", &[]),
+    ("PageDown", &[]),
+    ("Enter", &["Return"]),
+    ("Tab", &[]),
+    ("Backspace", &[]),
+    ("Delete", &["Del"]),
+    ("Insert", &["Ins"]),
+];
+
+/// Tegn som kan brukes som tast sammen med en modifikator
+const PUNCTUATION_KEYS: &str = ",./;'[]\\=`";
+
+/// Normaliser en snarvei til formen `Ctrl+Alt+Shift+Meta+Tast`
+///
+/// Modifikatorer kan skrives i vilkårlig rekkefølge og med store eller små
+/// bokstaver (`Control`, `Cmd`, `Super` og `CmdOrCtrl` godtas). Vanlige tegn
+/// krever Ctrl, Alt eller Meta, så snarveien ikke stjeler vanlig skriving.
+///
+/// # Returns
+/// Snarveien på normal form, eller KeyBindingError::InvalidAccelerator
+pub fn parse_accelerator(accelerator: &str) -> Result<String, KeyBindingError> {
+    let invalid = || KeyBindingError::InvalidAccelerator(accelerator.to_string());
+    let trimmed = accelerator.trim();
+    // "Ctrl++" betyr Ctrl og plusstasten
+    let (modifiers, key) = match trimmed.strip_suffix("++") {
+        Some(rest) => (rest, "+"),
+        None => trimmed.rsplit_once('+').unwrap_or(("", trimmed)),
+    };
+
+    let (mut ctrl, mut alt, mut shift, mut meta) = (false, false, false, false);
+    let modifiers = modifiers.split('+').filter(|_| !modifiers.is_empty());
+    for modifier in modifiers {
+        let flag = match modifier.trim().to_ascii_lowercase().as_str() {
+            "ctrl" | "control" | "cmdorctrl" | "commandorcontrol" => &mut ctrl,
+            "alt" | "option" => &mut alt,
+            "shift" => &mut shift,
+            "meta" | "super" | "cmd" | "command" => &mut meta,
+            _ => return Err(invalid()),
+        };
+        if *flag {
+            return Err(invalid());
+        }
+        *flag = true;
+    }
+
+    let key = key.trim();
+    let named = NAMED_KEYS.iter().find(|(name, aliases)| {
+        name.eq_ignore_ascii_case(key) || aliases.iter().any(|a| a.eq_ignore_ascii_case(key))
+    });
+    let function_key = key
+        .strip_prefix(['F', 'f'])
+        .and_then(|n| n.parse::<u8>().ok())
+        .filter(|n| (1..=24).contains(n));
+
+    let key = match (named, function_key) {
+        (Some((name, _)), _) => name.to_string(),
+        (None, Some(n)) => format!("F{}", n),
+        (None, None) => {
+            let mut chars = key.chars();
+            let (Some(c), None) = (chars.next(), chars.next()) else {
+                return Err(invalid());
+            };
+            if !(c.is_ascii_alphanumeric() || PUNCTUATION_KEYS.contains(c)) {
+                return Err(invalid());
+            }
+            if !(ctrl || alt || meta) {
+                return Err(invalid());
+            }
+            c.to_ascii_uppercase().to_string()
+        }
+    };
+
+    let mut parts: Vec<&str> = Vec::new();
+    for (active, name) in [
+        (ctrl, "Ctrl"),
+        (alt, "Alt"),
+        (shift, "Shift"),
+        (meta, "Meta"),
+    ] {
+        if active {
+            parts.push(name);
+        }
+    }
+    parts.push(&key);
+    Ok(parts.join("+"))
+}
+
+/// Gjeldende snarvei for hver handling
+///
+/// Standardsnarveiene med brukerens endringer lagt over. Ugyldige eller
+/// ukjente oppføringer i innstillingsfilen hoppes over.
+pub fn effective(overrides: &BTreeMap<String, String>) -> BTreeMap<Action, String> {
+    let mut bindings: BTreeMap<Action, String> = Action::ALL
+        .into_iter()
+        .map(|action| (action, action.default_accelerator().to_string()))
+        .collect();
+
+    for (name, accelerator) in overrides {
+        match (Action::parse(name), parse_accelerator(accelerator)) {
+            (Ok(action), Ok(accelerator)) => {
+                bindings.insert(action, accelerator);
+            }
+            _ => warn!("Ignorerer ugyldig snarvei {} = {}", name, accelerator),
+        }
+    }
+    bindings
+}
+
+/// Gi en handling en ny snarvei
+///
+/// En snarvei som er lik standarden fjernes fra `overrides`.
+///
+/// # Arguments
+/// * `overrides` - Brukerens endringer fra innstillingene
+/// * `action` - Navnet på handlingen
+/// * `accelerator` - Den nye snarveien
+///
+/// # Returns
+/// KeyBindingError hvis handlingen er ukjent, snarveien er ugyldig eller
+/// snarveien allerede brukes av en annen handling
+pub fn set(
+    overrides: &mut BTreeMap<String, String>,
+    action: &str,
+    accelerator: &str,
+) -> Result<(), KeyBindingError> {
+    let action = Action::parse(action)?;
+    let accelerator = parse_accelerator(accelerator)?;
+
+    let conflict = effective(overrides)
+        .into_iter()
+        .find(|(other, bound)| *other != action && *bound == accelerator);
+    if let Some((other, _)) = conflict {
+        return Err(KeyBindingError::Duplicate {
+            accelerator,
+            action: other.as_str(),
+        });
+    }
+
+    if accelerator == action.default_accelerator() {
+        overrides.remove(action.as_str());
+    } else {
+        overrides.insert(action.as_str().to_string(), accelerator);
+    }
+    Ok(())
+}
+
+/// Tilbakestill en handling til standardsnarveien
+pub fn reset(
+    overrides: &mut BTreeMap<String, String>,
+    action: &str,
+) -> Result<(), KeyBindingError> {
+    let action = Action::parse(action)?;
+    set(overrides, action.as_str(), action.default_accelerator())
+}
+
+/// En snarvei slik frontend ser den
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyBindingInfo {
+    pub action: String,
+    pub accelerator: String,
+    pub is_default: bool,
+}
+
+/// Gjeldende snarveier for frontend, i fast rekkefølge
+pub fn list(overrides: &BTreeMap<String, String>) -> Vec<KeyBindingInfo> {
+    effective(overrides)
+        .into_iter()
+        .map(|(action, accelerator)| KeyBindingInfo {
+            action: action.as_str().to_string(),
+            is_default: accelerator == action.default_accelerator(),
+            accelerator,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_accelerator_normalizes() {
+        assert_eq!(parse_accelerator("ctrl+d").unwrap(), "Ctrl+D");
+        assert_eq!(
+            parse_accelerator("Shift+Control+d").unwrap(),
+            "Ctrl+Shift+D"
+        );
+        assert_eq!(parse_accelerator("Ctrl++").unwrap(), "Ctrl+Plus");
+        assert_eq!(parse_accelerator("CmdOrCtrl+-").unwrap(), "Ctrl+Minus");
+        assert_eq!(parse_accelerator("alt+left").unwrap(), "Alt+ArrowLeft");
+        assert_eq!(parse_accelerator("f5").unwrap(), "F5");
+        assert_eq!(parse_accelerator("Super+,").unwrap(), "Meta+,");
+    }
+
+    #[test]
+    fn test_parse_accelerator_rejects_malformed() {
+        for invalid in [
+            "",
+            "Ctrl+",
+            "Ctrl+Ctrl+D",
+            "Hyper+D",
+            "Ctrl+DD",
+            "Ctrl+F25",
+            "d",
+            "Shift+d",
+            "Ctrl+æ",
+            "Ctrl+D+E",
+        ] {
+            assert!(
+                matches!(
+                    parse_accelerator(invalid),
+                    Err(KeyBindingError::InvalidAccelerator(_))
+                ),
+                "{}",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_defaults_are_valid_and_unique() {
+        let bindings = effective(&BTreeMap::new());
+        let mut seen = std::collections::HashSet::new();
+        for (action, accelerator) in &bindings {
+            assert_eq!(
+                parse_accelerator(accelerator).unwrap(),
+                *accelerator,
+                "{:?}",
+                action
+            );
+            assert!(seen.insert(accelerator.clone()), "{}", accelerator);
+        }
+    }
+
+    #[test]
+    fn test_set_rejects_duplicates() {
+        let mut overrides = BTreeMap::new();
+        assert_eq!(
+            set(&mut overrides, "zoom-out", "ctrl+d"),
+            Err(KeyBindingError::Duplicate {
+                accelerator: "Ctrl+D".to_string(),
+                action: "toggle-bookmark",
+            })
+        );
+        assert!(overrides.is_empty());
+
+        // Flytt bokmerke-snarveien, så blir Ctrl+D ledig
+        set(&mut overrides, "toggle-bookmark", "Ctrl+Shift+D").unwrap();
+        set(&mut overrides, "zoom-out", "Ctrl+D").unwrap();
+        let bindings = effective(&overrides);
+        assert_eq!(bindings[&Action::ZoomOut], "Ctrl+D");
+        assert_eq!(bindings[&Action::ToggleBookmark], "Ctrl+Shift+D");
+
+        // Tilbakestilling kolliderer nå med zoom-out
+        assert!(matches!(
+            reset(&mut overrides, "toggle-bookmark"),
+            Err(KeyBindingError::Duplicate { .. })
+        ));
+        assert!(matches!(
+            set(&mut overrides, "fly", "Ctrl+Y"),
+            Err(KeyBindingError::UnknownAction(_))
+        ));
+    }
+
+    #[test]
+    fn test_defaults_merge_under_overrides() {
+        let mut overrides = BTreeMap::new();
+        overrides.insert("zoom-in".to_string(), "Ctrl+=".to_string());
+        overrides.insert("ukjent".to_string(), "Ctrl+U".to_string());
+        overrides.insert("back".to_string(), "ikke en snarvei".to_string());
+
+        let bindings = effective(&overrides);
+        assert_eq!(bindings.len(), Action::ALL.len());
+        assert_eq!(bindings[&Action::ZoomIn], "Ctrl+=");
+        assert_eq!(bindings[&Action::Back], "Alt+ArrowLeft");
+        assert_eq!(bindings[&Action::FocusAddress], "Ctrl+L");
+        assert_eq!(bindings[&Action::HardReload], "Ctrl+Shift+R");
+
+        // Å sette standardsnarveien fjerner endringen
+        reset(&mut overrides, "zoom-in").unwrap();
+        assert!(!overrides.contains_key("zoom-in"));
+        assert!(list(&overrides).iter().all(|b| b.is_default));
+    }
+}
@@ -2,22 +2,70 @@
 //!
 //! Hovedmodul som starter Tauri-applikasjonen og registrerer commands.
 
+// En lås holdt over en `.await` blokkerer alle andre commands som trenger
+// den så lenge hentingen pågår
+#![deny(clippy::await_holding_lock)]
+
+mod address;
+mod app_state;
+mod autocomplete;
+mod bookmark_io;
 mod bookmarks;
+mod breadcrumbs;
+mod broadcast;
+mod browsing_data;
+mod command_error;
 mod commands;
 mod converter;
+mod deep_link;
+mod downloads;
+mod error_page;
+mod export;
 mod fetcher;
+mod find;
 mod gemini;
+mod gemini_identity;
 mod gemtext;
 mod gopher;
 mod gophermap;
+mod history;
+mod keybindings;
+mod linkcheck;
+mod local_files;
+mod locale;
+mod localsite;
+mod logging;
 mod markdown;
+mod page_security;
+mod page_source;
+mod pages;
+mod prefetch;
+mod private_mode;
+mod progress;
+mod recent_files;
+mod render_cache;
+mod search;
 mod settings;
+mod snapshots;
+mod startup;
+mod storage;
+#[cfg(test)]
+mod test_support;
+mod timing;
+mod url_safety;
+mod watcher;
+mod window_state;
 
+use app_state::{AppState, ConfigPaths};
 use log::info;
+use parking_lot::Mutex;
+use tauri::Manager;
+use tauri_plugin_deep_link::DeepLinkExt;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    env_logger::init();
+    let paths = ConfigPaths::from_config_dir();
+    logging::init(&paths.log_file());
 
     // Installer rustls crypto-provider (ring) før noe annet bruker TLS
     rustls::crypto::ring::default_provider()
@@ -26,38 +74,236 @@ pub fn run() {
 
     info!("Starting Bare browser");
 
+    let state = AppState::load(paths);
+    logging::set_level(state.settings.read().log_level);
+
+    // Filer og adresser fra kommandolinjen, åpnes når frontend har startet
+    let startup_targets = {
+        let settings = state.settings.read();
+        let cwd = std::env::current_dir().unwrap_or_default();
+        startup::classify_all(std::env::args().skip(1), &cwd, |host| {
+            settings.site_settings(Some(host)).default_scheme
+        })
+    };
+
     tauri::Builder::default()
+        // Må registreres først: en ny instans sender argumentene hit og avslutter
+        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+            commands::open_deep_links(app, argv.into_iter().skip(1));
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .manage(Mutex::new(window_state::WindowStates::default()))
+        .manage(deep_link::DeepLinks::default())
+        .manage(Mutex::new(startup::StartupQueue::new(startup_targets)))
+        .manage(state)
+        .setup(|app| {
+            commands::init_locale(&app.state::<AppState>());
+            commands::start_bookmarks_watcher(app.handle().clone());
+            commands::start_settings_watcher(app.handle().clone());
+
+            // gemini:// og gopher:// fra andre programmer
+            #[cfg(any(windows, target_os = "linux"))]
+            if let Err(e) = app.deep_link().register_all() {
+                log::warn!("Kunne ikke registrere gemini:// og gopher://: {}", e);
+            }
+            if let Ok(Some(urls)) = app.deep_link().get_current() {
+                commands::accept_deep_links(app.handle(), urls.iter().map(|u| u.to_string()));
+            }
+            let handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| {
+                commands::open_deep_links(&handle, event.urls().iter().map(|u| u.to_string()));
+            });
+            Ok(())
+        })
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::Destroyed = event {
+                commands::forget_window(window);
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             commands::get_app_version,
+            commands::get_render_cache_stats,
+            commands::get_recent_logs,
+            commands::get_log_file_path,
             commands::render_markdown,
+            commands::render_content,
+            commands::get_plain_text,
+            commands::find_in_page,
             commands::open_file,
+            commands::watch_file,
+            commands::unwatch_file,
+            commands::get_recent_files,
+            commands::clear_recent_files,
             commands::get_welcome_content,
+            commands::get_onboarding_page,
+            commands::get_home_page,
+            commands::get_bookmarks_page,
+            commands::navigate,
             commands::fetch_url,
+            commands::get_page_security,
+            commands::check_url_safety,
+            commands::trust_url_host,
             commands::convert_url,
+            commands::prefetch,
             commands::resolve_url,
+            commands::get_breadcrumbs,
+            commands::export_page,
+            commands::save_page_source,
+            commands::copy_page_as_markdown,
+            commands::copy_link,
+            // Nedlastinger
+            commands::start_download,
+            commands::save_resource,
+            commands::cancel_download,
+            commands::list_downloads,
+            commands::resolve_address_input,
+            commands::autocomplete,
+            commands::remember_address_scheme,
+            // Vinduer
+            commands::get_window_state,
+            commands::navigate_back,
+            commands::navigate_forward,
+            commands::can_go_back,
+            commands::can_go_forward,
+            commands::take_initial_url,
+            commands::take_deep_links,
+            commands::get_startup_targets,
+            commands::open_url_in_new_window,
             // Bokmerker
             commands::get_bookmarks,
             commands::add_bookmark,
             commands::remove_bookmark,
+            commands::toggle_bookmark,
+            commands::set_bookmark_pinned,
+            commands::get_pinned_bookmarks,
+            commands::reorder_pinned_bookmarks,
+            commands::snapshot_bookmark,
+            commands::open_bookmark_snapshot,
             commands::is_bookmarked,
+            commands::update_bookmark,
+            commands::resolve_keyword,
+            commands::get_bookmarks_by_tag,
+            commands::search_bookmarks,
+            commands::check_bookmarks,
+            commands::list_tags,
+            commands::get_bookmark_folders,
+            commands::create_bookmark_folder,
+            commands::rename_bookmark_folder,
+            commands::move_bookmark,
+            commands::remove_bookmark_folder,
+            commands::import_bookmarks,
+            commands::export_bookmarks,
+            commands::merge_bookmarks,
+            commands::list_bookmark_backups,
+            commands::restore_bookmarks_backup,
             // Innstillinger
             commands::get_settings,
             commands::update_settings,
+            commands::set_private_mode,
+            commands::reset_colors,
+            commands::reset_settings,
+            commands::get_download_target,
+            commands::list_domain_conversion_rules,
+            commands::set_domain_conversion_rule,
+            commands::remove_domain_conversion_rule,
+            commands::get_custom_css,
+            commands::list_system_fonts,
+            commands::get_key_bindings,
+            commands::set_key_binding,
+            commands::reset_key_binding,
+            commands::get_site_settings,
+            commands::set_site_override,
+            commands::clear_site_override,
             commands::zoom_in,
             commands::zoom_out,
             commands::zoom_reset,
+            commands::set_site_zoom,
+            commands::get_effective_zoom,
+            // Historikk
+            commands::get_history,
+            commands::add_history_entry,
+            commands::delete_history_entry,
+            commands::clear_history,
+            commands::search_history,
+            commands::clear_browsing_data,
+            commands::get_top_sites,
+            commands::dismiss_top_site,
             // Gemini
             commands::fetch_gemini,
             commands::submit_gemini_input,
             commands::resolve_gemini_url,
+            commands::list_gemini_identities,
+            commands::create_gemini_identity,
+            commands::assign_identity_to_host,
+            commands::delete_gemini_identity,
             // Gopher
             commands::fetch_gopher,
             commands::gopher_search,
             commands::resolve_gopher_url,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|_app, _event| {
+            // Filer som åpnes fra Finder mens Bare starter eller kjører
+            #[cfg(target_os = "macos")]
+            if let tauri::RunEvent::Opened { urls } = _event {
+                let paths = urls.into_iter().filter_map(|url| url.to_file_path().ok());
+                commands::open_startup_files(_app, paths);
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+    use std::collections::BTreeSet;
+
+    /// Navnene på alle `#[tauri::command]`-funksjoner i kilden
+    fn defined_commands(source: &str) -> BTreeSet<String> {
+        let pattern = Regex::new(r"#\[tauri::command\]\s*pub\s+(?:async\s+)?fn\s+(\w+)").unwrap();
+        pattern
+            .captures_iter(source)
+            .map(|c| c[1].to_string())
+            .collect()
+    }
+
+    /// Navnene i `generate_handler!`, i rekkefølge
+    fn registered_commands(source: &str) -> Vec<String> {
+        let start = source.find("generate_handler![").unwrap();
+        let end = start + source[start..].find("])").unwrap();
+        let pattern = Regex::new(r"commands::(\w+)").unwrap();
+        pattern
+            .captures_iter(&source[start..end])
+            .map(|c| c[1].to_string())
+            .collect()
+    }
+
+    /// En command som ikke er registrert gir "command not found" først når
+    /// frontend kaller den, så alle må stå i `generate_handler!`
+    #[test]
+    fn test_every_command_is_registered() {
+        let defined = defined_commands(include_str!("commands.rs"));
+        let registered = registered_commands(include_str!("lib.rs"));
+        let registered_set: BTreeSet<String> = registered.iter().cloned().collect();
+
+        assert!(defined.len() > 50, "fant bare {} commands", defined.len());
+        let missing: Vec<_> = defined.difference(&registered_set).collect();
+        assert!(missing.is_empty(), "ikke registrert: {:?}", missing);
+        assert_eq!(
+            registered.len(),
+            registered_set.len(),
+            "registrert flere ganger"
+        );
+    }
+
+    #[test]
+    fn test_command_scanner() {
+        let source = "#[tauri::command]\npub fn a() {}\n\n#[tauri::command]\npub async fn b(\n) {}\n\npub fn c() {}\n";
+        let names: Vec<String> = defined_commands(source).into_iter().collect();
+        assert_eq!(names, vec!["a", "b"]);
+    }
 }
@@ -0,0 +1,472 @@
+//! Sjekk av døde lenker i bokmerker
+//!
+//! Gjør en lett forespørsel per bokmerke og klassifiserer svaret: HTTP(S)
+//! bruker HEAD (med GET av første byte som reserve), Gemini leser kun
+//! header-linjen og Gopher leser de første bytene av responsen. Redirects
+//! følges ikke, slik at frontend kan foreslå den nye adressen.
+
+use crate::gemini::{GeminiClient, GeminiError};
+use crate::gopher::{self, GopherError};
+use futures::stream::{self, StreamExt};
+use log::debug;
+use reqwest::header::{LOCATION, RANGE, USER_AGENT};
+use reqwest::{redirect, Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::Duration;
+use url::Url;
+
+/// Maksimalt antall samtidige sjekker
+const MAX_CONCURRENT_CHECKS: usize = 4;
+
+/// Tidsfrist per bokmerke i sekunder
+const CHECK_TIMEOUT_SECONDS: u64 = 10;
+
+/// Status for én sjekket lenke
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkStatus {
+    /// Ressursen svarte som forventet
+    Ok,
+    /// Serveren sendte videre til en annen adresse
+    Redirect,
+    /// Ressursen finnes ikke (lenger)
+    NotFound,
+    /// Serveren svarte ikke innen tidsfristen
+    Timeout,
+    /// Gemini-serverens sertifikat er ikke det samme som før (TOFU)
+    CertChanged,
+    /// Andre feil (tilkobling, serverfeil, ustøttet protokoll)
+    Error,
+}
+
+/// Resultat av sjekken for ett bokmerke
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinkCheckResult {
+    /// Bokmerke-ID
+    pub id: String,
+    /// URL-en som ble sjekket
+    pub url: String,
+    /// Klassifisert status
+    pub status: LinkStatus,
+    /// Ny adresse ved redirect
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect_url: Option<String>,
+    /// Foreslått erstatning (kun ved permanente redirects)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggested_url: Option<String>,
+    /// Feilmelding eller statuskode for visning
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Klassifisert svar, før det knyttes til et bokmerke
+#[derive(Debug, Clone, PartialEq)]
+pub struct Classification {
+    /// Klassifisert status
+    pub status: LinkStatus,
+    /// Absolutt mål for redirect
+    pub redirect_url: Option<String>,
+    /// Om redirecten er permanent (HTTP 301/308, Gemini 31)
+    pub permanent: bool,
+    /// Feilmelding eller statuskode for visning
+    pub message: Option<String>,
+}
+
+impl Classification {
+    fn status(status: LinkStatus) -> Self {
+        Self {
+            status,
+            redirect_url: None,
+            permanent: false,
+            message: None,
+        }
+    }
+
+    fn error(message: impl Into<String>) -> Self {
+        Self {
+            message: Some(message.into()),
+            ..Self::status(LinkStatus::Error)
+        }
+    }
+
+    fn redirect(base: &Url, location: Option<&str>, permanent: bool) -> Self {
+        match location.and_then(|l| base.join(l.trim()).ok()) {
+            Some(target) => Self {
+                status: LinkStatus::Redirect,
+                redirect_url: Some(target.to_string()),
+                permanent,
+                message: None,
+            },
+            None => Self::error("Redirect uten gyldig mål"),
+        }
+    }
+}
+
+/// Klassifiser et HTTP-svar
+///
+/// # Arguments
+/// * `status` - HTTP-statuskode
+/// * `location` - Location-header, hvis satt
+/// * `base` - URL-en som ble forespurt (for relative redirects)
+pub fn classify_http(status: u16, location: Option<&str>, base: &Url) -> Classification {
+    match status {
+        200..=299 => Classification::status(LinkStatus::Ok),
+        301 | 308 => Classification::redirect(base, location, true),
+        300..=399 => Classification::redirect(base, location, false),
+        404 | 410 => Classification::status(LinkStatus::NotFound),
+        408 | 504 => Classification::status(LinkStatus::Timeout),
+        _ => Classification::error(format!("HTTP {}", status)),
+    }
+}
+
+/// Klassifiser en Gemini respons-header
+///
+/// Input (1x) og krav om klientsertifikat (6x) betyr at siden finnes.
+pub fn classify_gemini(status: u8, meta: &str, base: &Url) -> Classification {
+    match status {
+        10..=29 | 60..=69 => Classification::status(LinkStatus::Ok),
+        31 => Classification::redirect(base, Some(meta), true),
+        30..=39 => Classification::redirect(base, Some(meta), false),
+        51 | 52 => Classification::status(LinkStatus::NotFound),
+        _ => Classification::error(format!("Gemini {}: {}", status, meta)),
+    }
+}
+
+/// Klassifiser starten av et Gopher-svar
+///
+/// Gopher har ingen statuskoder; en tom respons eller en meny som starter
+/// med en feillinje (type 3) tolkes som at selektoren ikke finnes.
+pub fn classify_gopher(response: &str) -> Classification {
+    if response.is_empty() || response.starts_with('3') {
+        Classification::status(LinkStatus::NotFound)
+    } else {
+        Classification::status(LinkStatus::Ok)
+    }
+}
+
+/// Klassifiser en feil fra Gemini-klienten
+pub fn classify_gemini_error(error: &GeminiError) -> Classification {
+    match error {
+        GeminiError::Timeout(_) => Classification::status(LinkStatus::Timeout),
+        GeminiError::CertificateChanged { .. } => Classification {
+            message: Some(error.to_string()),
+            ..Classification::status(LinkStatus::CertChanged)
+        },
+        _ => Classification::error(error.to_string()),
+    }
+}
+
+/// Klassifiser en feil fra Gopher-klienten
+pub fn classify_gopher_error(error: &GopherError) -> Classification {
+    match error {
+        GopherError::Timeout(_) => Classification::status(LinkStatus::Timeout),
+        _ => Classification::error(error.to_string()),
+    }
+}
+
+/// Sjekker bokmerker mot serverne de peker til
+pub struct LinkChecker {
+    client: reqwest::Client,
+}
+
+impl Default for LinkChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LinkChecker {
+    /// Opprett en LinkChecker med egen HTTP-klient som ikke følger redirects
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .redirect(redirect::Policy::none())
+            .timeout(Duration::from_secs(CHECK_TIMEOUT_SECONDS))
+            .build()
+            .unwrap_or_default();
+        Self { client }
+    }
+
+    /// Sjekk en liste med bokmerker, noen av gangen
+    ///
+    /// # Arguments
+    /// * `gemini` - Gemini-klient (deler TOFU-lager med vanlig surfing)
+    /// * `targets` - Par av bokmerke-ID og URL
+    /// * `on_result` - Kalles for hvert ferdige resultat, i fullføringsrekkefølge
+    ///
+    /// # Returns
+    /// Resultatene i samme rekkefølge som `targets`
+    pub async fn check_all<F>(
+        &self,
+        gemini: &GeminiClient,
+        targets: Vec<(String, String)>,
+        mut on_result: F,
+    ) -> Vec<LinkCheckResult>
+    where
+        F: FnMut(&LinkCheckResult),
+    {
+        let mut results: Vec<(usize, LinkCheckResult)> =
+            stream::iter(targets.into_iter().enumerate())
+                .map(|(index, (id, url))| async move {
+                    let classification = self.check(gemini, &url).await;
+                    (index, to_result(id, url, classification))
+                })
+                .buffer_unordered(MAX_CONCURRENT_CHECKS)
+                .inspect(|(_, result)| on_result(result))
+                .collect()
+                .await;
+
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+
+    /// Sjekk én URL
+    pub async fn check(&self, gemini: &GeminiClient, url: &str) -> Classification {
+        let parsed = match Url::parse(url) {
+            Ok(parsed) => parsed,
+            Err(e) => return Classification::error(format!("Ugyldig URL: {}", e)),
+        };
+        debug!("Sjekker bokmerke: {}", url);
+
+        match parsed.scheme() {
+            "http" | "https" => with_timeout(self.check_http(&parsed)).await,
+            "gemini" => {
+                with_timeout(async {
+                    match gemini.fetch_header(url).await {
+                        Ok((status, meta)) => classify_gemini(status, &meta, &parsed),
+                        Err(e) => classify_gemini_error(&e),
+                    }
+                })
+                .await
+            }
+            "gopher" => {
+                with_timeout(async {
+                    match gopher::GopherClient::default().probe(url).await {
+                        Ok(response) => classify_gopher(&response),
+                        Err(e) => classify_gopher_error(&e),
+                    }
+                })
+                .await
+            }
+            "file" => match parsed.to_file_path() {
+                Ok(path) if path.exists() => Classification::status(LinkStatus::Ok),
+                _ => Classification::status(LinkStatus::NotFound),
+            },
+            scheme => Classification::error(format!("Ustøttet protokoll: {}", scheme)),
+        }
+    }
+
+    /// HEAD-forespørsel, med GET av første byte for servere som avviser HEAD
+    async fn check_http(&self, url: &Url) -> Classification {
+        let user_agent = format!("Bare/{} (Markdown Browser)", env!("CARGO_PKG_VERSION"));
+        let mut response = self.request(Method::HEAD, url, &user_agent).await;
+        if let Ok(ref head) = response {
+            let status = head.status();
+            if status == StatusCode::METHOD_NOT_ALLOWED || status == StatusCode::NOT_IMPLEMENTED {
+                response = self.request(Method::GET, url, &user_agent).await;
+            }
+        }
+
+        match response {
+            Ok(response) => {
+                let location = response
+                    .headers()
+                    .get(LOCATION)
+                    .and_then(|v| v.to_str().ok());
+                classify_http(response.status().as_u16(), location, url)
+            }
+            Err(e) if e.is_timeout() => Classification::status(LinkStatus::Timeout),
+            Err(e) => Classification::error(e.to_string()),
+        }
+    }
+
+    async fn request(
+        &self,
+        method: Method,
+        url: &Url,
+        user_agent: &str,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self
+            .client
+            .request(method.clone(), url.as_str())
+            .header(USER_AGENT, user_agent);
+        if method == Method::GET {
+            request = request.header(RANGE, "bytes=0-0");
+        }
+        request.send().await
+    }
+}
+
+/// Kjør en sjekk med felles tidsfrist
+async fn with_timeout(check: impl Future<Output = Classification>) -> Classification {
+    tokio::time::timeout(Duration::from_secs(CHECK_TIMEOUT_SECONDS), check)
+        .await
+        .unwrap_or_else(|_| Classification::status(LinkStatus::Timeout))
+}
+
+/// Knytt en klassifisering til bokmerket den gjelder
+fn to_result(id: String, url: String, classification: Classification) -> LinkCheckResult {
+    let suggested_url = classification
+        .permanent
+        .then(|| classification.redirect_url.clone())
+        .flatten()
+        .filter(|target| *target != url);
+    LinkCheckResult {
+        id,
+        url,
+        status: classification.status,
+        redirect_url: classification.redirect_url,
+        suggested_url,
+        message: classification.message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> Url {
+        Url::parse("https://example.com/docs/side").unwrap()
+    }
+
+    #[test]
+    fn test_classify_http_statuses() {
+        assert_eq!(classify_http(200, None, &base()).status, LinkStatus::Ok);
+        assert_eq!(classify_http(206, None, &base()).status, LinkStatus::Ok);
+        assert_eq!(
+            classify_http(404, None, &base()).status,
+            LinkStatus::NotFound
+        );
+        assert_eq!(
+            classify_http(410, None, &base()).status,
+            LinkStatus::NotFound
+        );
+        assert_eq!(
+            classify_http(504, None, &base()).status,
+            LinkStatus::Timeout
+        );
+
+        let error = classify_http(500, None, &base());
+        assert_eq!(error.status, LinkStatus::Error);
+        assert_eq!(error.message.as_deref(), Some("HTTP 500"));
+    }
+
+    #[test]
+    fn test_classify_http_redirects() {
+        let permanent = classify_http(301, Some("/ny"), &base());
+        assert_eq!(permanent.status, LinkStatus::Redirect);
+        assert_eq!(
+            permanent.redirect_url.as_deref(),
+            Some("https://example.com/ny")
+        );
+        assert!(permanent.permanent);
+        assert!(classify_http(308, Some("https://a.example/"), &base()).permanent);
+
+        let temporary = classify_http(302, Some("annen"), &base());
+        assert_eq!(
+            temporary.redirect_url.as_deref(),
+            Some("https://example.com/docs/annen")
+        );
+        assert!(!temporary.permanent);
+
+        assert_eq!(classify_http(301, None, &base()).status, LinkStatus::Error);
+    }
+
+    #[test]
+    fn test_classify_gemini_headers() {
+        let base = Url::parse("gemini://example.org/gammel").unwrap();
+        assert_eq!(
+            classify_gemini(20, "text/gemini", &base).status,
+            LinkStatus::Ok
+        );
+        assert_eq!(classify_gemini(10, "Søk", &base).status, LinkStatus::Ok);
+        assert_eq!(classify_gemini(60, "", &base).status, LinkStatus::Ok);
+        assert_eq!(classify_gemini(51, "", &base).status, LinkStatus::NotFound);
+        assert_eq!(classify_gemini(52, "", &base).status, LinkStatus::NotFound);
+        assert_eq!(classify_gemini(40, "", &base).status, LinkStatus::Error);
+
+        let moved = classify_gemini(31, "/ny", &base);
+        assert_eq!(
+            moved.redirect_url.as_deref(),
+            Some("gemini://example.org/ny")
+        );
+        assert!(moved.permanent);
+        assert!(!classify_gemini(30, "/midlertidig", &base).permanent);
+    }
+
+    #[test]
+    fn test_classify_gopher_responses() {
+        assert_eq!(
+            classify_gopher("1Meny\t/\texample.org\t70\r\n").status,
+            LinkStatus::Ok
+        );
+        assert_eq!(
+            classify_gopher("3'/mangler' does not exist\t\terror.host\t1\r\n").status,
+            LinkStatus::NotFound
+        );
+        assert_eq!(classify_gopher("").status, LinkStatus::NotFound);
+    }
+
+    #[test]
+    fn test_classify_client_errors() {
+        let changed = GeminiError::CertificateChanged {
+            host: "example.org:1965".to_string(),
+            old_fp: "aa".to_string(),
+            new_fp: "bb".to_string(),
+        };
+        assert_eq!(
+            classify_gemini_error(&changed).status,
+            LinkStatus::CertChanged
+        );
+        assert_eq!(
+            classify_gemini_error(&GeminiError::Timeout(10)).status,
+            LinkStatus::Timeout
+        );
+        assert_eq!(
+            classify_gopher_error(&GopherError::Timeout(10)).status,
+            LinkStatus::Timeout
+        );
+        assert_eq!(
+            classify_gopher_error(&GopherError::ConnectionError("nei".to_string())).status,
+            LinkStatus::Error
+        );
+    }
+
+    #[test]
+    fn test_suggested_url_only_for_permanent_redirects() {
+        let url = "https://example.com/docs/side".to_string();
+
+        let result = to_result(
+            "a".to_string(),
+            url.clone(),
+            classify_http(301, Some("/ny"), &base()),
+        );
+        assert_eq!(
+            result.suggested_url.as_deref(),
+            Some("https://example.com/ny")
+        );
+
+        let result = to_result(
+            "a".to_string(),
+            url.clone(),
+            classify_http(302, Some("/ny"), &base()),
+        );
+        assert_eq!(
+            result.redirect_url.as_deref(),
+            Some("https://example.com/ny")
+        );
+        assert_eq!(result.suggested_url, None);
+    }
+
+    #[test]
+    fn test_serializes_snake_case_status() {
+        let result = to_result(
+            "a".to_string(),
+            "gemini://example.org/".to_string(),
+            Classification::status(LinkStatus::CertChanged),
+        );
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["status"], "cert_changed");
+        assert!(json.get("suggested_url").is_none());
+    }
+}
@@ -0,0 +1,545 @@
+//! Lokale filer og mapper
+//!
+//! Avgjør hvilke filer Bare kan vise fra disk, og lager en innholdsfortegnelse
+//! når brukeren åpner en mappe. Fortegnelsen viser bare mappen selv; undermapper
+//! åpnes ved å klikke på dem, slik at store mappetrær aldri gås gjennom.
+//!
+//! Før en fil leses gjøres stien kanonisk, og filen sjekkes mot
+//! `OpenPolicy`: spesialfiler (FIFO-er, enheter) og filer over
+//! størrelsesgrensen avvises, og symbolske lenker følges bare når brukeren
+//! tillater det.
+
+use crate::locale::{Locale, Localize};
+use crate::markdown;
+use chrono::{DateTime, Local};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Filer som vises under fortegnelsen, i prioritert rekkefølge
+const INDEX_FILES: [&str; 2] = ["index.md", "README.md"];
+
+/// Feil som kan oppstå når en lokal fil åpnes
+#[derive(Debug, Error)]
+pub enum LocalFileError {
+    #[error("Filen finnes ikke: {0}")]
+    NotFound(String),
+
+    #[error("Ingen tilgang til {0}")]
+    PermissionDenied(String),
+
+    #[error("Filen er for stor ({size} bytes, maks {max} bytes)")]
+    TooLarge { size: u64, max: u64 },
+
+    #[error("{0} er ikke en vanlig fil")]
+    SpecialFile(String),
+
+    #[error("{0} er en symbolsk lenke, og symbolske lenker følges ikke")]
+    Symlink(String),
+
+    #[error("Kunne ikke lese fil: {0}")]
+    Read(String),
+}
+
+impl Localize for LocalFileError {
+    fn localize_in(&self, locale: Locale) -> String {
+        match self {
+            Self::NotFound(path) => locale.format("error.file_not_found", &[path]),
+            Self::PermissionDenied(path) => locale.format("error.file_permission_denied", &[path]),
+            Self::TooLarge { size, max } => {
+                let (size, max) = (format_size(*size), format_size(*max));
+                locale.format("error.file_too_large", &[&size, &max])
+            }
+            Self::SpecialFile(path) => locale.format("error.file_special", &[path]),
+            Self::Symlink(path) => locale.format("error.file_symlink", &[path]),
+            Self::Read(e) => locale.format("error.file_read", &[e]),
+        }
+    }
+}
+
+impl LocalFileError {
+    /// Feilen fra filsystemet, med egne varianter for manglende filer og tilgang
+    fn from_io(path: &Path, e: io::Error) -> Self {
+        let shown = path.display().to_string();
+        match e.kind() {
+            io::ErrorKind::NotFound => Self::NotFound(shown),
+            io::ErrorKind::PermissionDenied => Self::PermissionDenied(shown),
+            _ => Self::Read(e.to_string()),
+        }
+    }
+}
+
+/// Reglene for hvilke lokale filer som kan åpnes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpenPolicy {
+    /// Største fil som leses, i bytes
+    pub max_size: u64,
+    /// Følg symbolske lenker; målet er det som sjekkes og vises
+    pub follow_symlinks: bool,
+}
+
+/// Finn filen eller mappen en sti peker på, og sjekk at den kan åpnes
+///
+/// # Arguments
+/// * `path` - Stien brukeren åpnet
+/// * `policy` - Størrelsesgrense og om symbolske lenker følges
+///
+/// # Returns
+/// Den kanoniske stien og metadataene til det den peker på
+pub fn resolve(
+    path: &Path,
+    policy: &OpenPolicy,
+) -> Result<(PathBuf, fs::Metadata), LocalFileError> {
+    let link = fs::symlink_metadata(path).map_err(|e| LocalFileError::from_io(path, e))?;
+    if link.file_type().is_symlink() && !policy.follow_symlinks {
+        return Err(LocalFileError::Symlink(path.display().to_string()));
+    }
+    let canonical = fs::canonicalize(path).map_err(|e| LocalFileError::from_io(path, e))?;
+    let metadata = fs::metadata(&canonical).map_err(|e| LocalFileError::from_io(&canonical, e))?;
+    if metadata.is_dir() {
+        return Ok((canonical, metadata));
+    }
+    if !metadata.is_file() {
+        return Err(LocalFileError::SpecialFile(canonical.display().to_string()));
+    }
+    if metadata.len() > policy.max_size {
+        return Err(LocalFileError::TooLarge {
+            size: metadata.len(),
+            max: policy.max_size,
+        });
+    }
+    Ok((canonical, metadata))
+}
+
+/// Les en fil som allerede er sjekket med `resolve`
+///
+/// Lesingen stopper ved størrelsesgrensen, så en fil som vokser etter
+/// sjekken aldri leses inn i sin helhet.
+pub fn read(path: &Path, policy: &OpenPolicy) -> Result<String, LocalFileError> {
+    let file = File::open(path).map_err(|e| LocalFileError::from_io(path, e))?;
+    let mut content = String::new();
+    file.take(policy.max_size.saturating_add(1))
+        .read_to_string(&mut content)
+        .map_err(|e| LocalFileError::from_io(path, e))?;
+    let size = content.len() as u64;
+    if size > policy.max_size {
+        return Err(LocalFileError::TooLarge {
+            size,
+            max: policy.max_size,
+        });
+    }
+    Ok(content)
+}
+
+/// Filformatene Bare kan åpne fra disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocalFormat {
+    Markdown,
+    Gemtext,
+    Text,
+}
+
+impl LocalFormat {
+    /// Formatet til en fil ut fra filendelsen, uavhengig av store og små bokstaver
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "md" | "markdown" => Some(Self::Markdown),
+            "gmi" | "gemini" => Some(Self::Gemtext),
+            "txt" => Some(Self::Text),
+            _ => None,
+        }
+    }
+}
+
+/// Én oppføring i en mappe
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectoryEntry {
+    /// Filnavnet
+    pub name: String,
+    /// Tittelen fra et lokalt nettsted, se `localsite`
+    pub title: Option<String>,
+    /// file://-adressen oppføringen åpnes med
+    pub url: String,
+    /// Om oppføringen er en mappe
+    pub is_dir: bool,
+    /// Størrelse i bytes; 0 for mapper
+    pub size: u64,
+    /// Sist endret, hvis filsystemet oppgir det
+    pub modified: Option<DateTime<Local>>,
+}
+
+/// Innholdet i en mappe Bare kan vise
+///
+/// Mapper kommer først, deretter filer i formater Bare kan åpne, begge
+/// sortert alfabetisk. Andre filer tas ikke med.
+///
+/// # Arguments
+/// * `dir` - Mappen som skal listes
+/// * `show_hidden` - Ta med filer og mapper som starter med punktum
+///
+/// # Returns
+/// Oppføringene, eller feilen fra filsystemet hvis mappen ikke kan leses
+pub fn list_directory(dir: &Path, show_hidden: bool) -> io::Result<Vec<DirectoryEntry>> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.starts_with('.') && !show_hidden {
+            continue;
+        }
+        // Følger symlenker; brutte lenker hoppes over
+        let path = entry.path();
+        let Ok(metadata) = fs::metadata(&path) else {
+            continue;
+        };
+        let is_dir = metadata.is_dir();
+        if !is_dir && LocalFormat::from_path(&path).is_none() {
+            continue;
+        }
+        let url = if is_dir {
+            url::Url::from_directory_path(&path)
+        } else {
+            url::Url::from_file_path(&path)
+        };
+        let Ok(url) = url else {
+            continue;
+        };
+        entries.push(DirectoryEntry {
+            name,
+            title: None,
+            url: url.into(),
+            is_dir,
+            size: if is_dir { 0 } else { metadata.len() },
+            modified: metadata.modified().ok().map(DateTime::from),
+        });
+    }
+    entries.sort_by_cached_key(|e| (!e.is_dir, e.name.to_lowercase()));
+    Ok(entries)
+}
+
+/// Filen som skal vises under fortegnelsen, f.eks. index.md eller README.md
+///
+/// Navnene sammenlignes uten hensyn til store og små bokstaver.
+pub fn index_file(entries: &[DirectoryEntry]) -> Option<PathBuf> {
+    INDEX_FILES.iter().find_map(|wanted| {
+        entries
+            .iter()
+            .find(|e| !e.is_dir && e.name.eq_ignore_ascii_case(wanted))
+            .and_then(|e| url::Url::parse(&e.url).ok()?.to_file_path().ok())
+    })
+}
+
+/// Lag markdown for en mappefortegnelse
+///
+/// # Arguments
+/// * `dir` - Mappen som vises; gir tittel og lenke til foreldermappen
+/// * `entries` - Fra `list_directory`
+/// * `empty_text` - Teksten som vises når mappen ikke har noe å vise
+/// * `index` - Innholdet i index.md/README.md, som legges under listen
+pub fn directory_markdown(
+    dir: &Path,
+    entries: &[DirectoryEntry],
+    empty_text: &str,
+    index: Option<&str>,
+) -> String {
+    let title = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| dir.display().to_string());
+    let mut md = format!("# {}\n\n", markdown::escape_markdown(&title));
+
+    if let Some(parent) = dir
+        .parent()
+        .and_then(|p| url::Url::from_directory_path(p).ok())
+    {
+        md.push_str(&format!("- [..](<{}>)\n", parent));
+    }
+    for entry in entries {
+        let name = markdown::escape_markdown(&entry.name);
+        if entry.is_dir {
+            md.push_str(&format!("- 📁 [{}/](<{}>)", name, entry.url));
+        } else if let Some(title) = &entry.title {
+            md.push_str(&format!(
+                "- [{}](<{}>) · {} · {}",
+                markdown::escape_markdown(title),
+                entry.url,
+                name,
+                format_size(entry.size)
+            ));
+        } else {
+            md.push_str(&format!(
+                "- [{}](<{}>) · {}",
+                name,
+                entry.url,
+                format_size(entry.size)
+            ));
+        }
+        if let Some(modified) = entry.modified {
+            md.push_str(&format!(" · {}", modified.format("%Y-%m-%d %H:%M")));
+        }
+        md.push('\n');
+    }
+    if entries.is_empty() {
+        md.push_str(&format!("\n*{}*\n", empty_text));
+    }
+
+    if let Some(index) = index {
+        md.push_str("\n---\n\n");
+        md.push_str(index);
+        if !index.ends_with('\n') {
+            md.push('\n');
+        }
+    }
+    md
+}
+
+/// Filstørrelse i lesbar form, f.eks. "1.5 KB"
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Mappe med undermapper, skjulte filer og filer Bare ikke kan vise
+    fn fixture() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("prosjekt")).unwrap();
+        fs::create_dir(dir.path().join("Arkiv")).unwrap();
+        fs::create_dir(dir.path().join(".git")).unwrap();
+        fs::write(dir.path().join("prosjekt").join("dypt.md"), "# Dypt").unwrap();
+        fs::write(dir.path().join("notater.md"), "# Notater").unwrap();
+        fs::write(dir.path().join("Kapsel.gmi"), "# Kapsel").unwrap();
+        fs::write(dir.path().join("les meg.txt"), "x".repeat(2048)).unwrap();
+        fs::write(dir.path().join(".skjult.md"), "# Skjult").unwrap();
+        fs::write(dir.path().join("bilde.png"), [0u8; 4]).unwrap();
+        dir
+    }
+
+    fn names(entries: &[DirectoryEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.name.as_str()).collect()
+    }
+
+    const POLICY: OpenPolicy = OpenPolicy {
+        max_size: 1024,
+        follow_symlinks: true,
+    };
+
+    #[test]
+    fn test_resolve_canonicalizes_and_reads() {
+        let dir = fixture();
+        let path = dir.path().join("prosjekt").join("..").join("notater.md");
+        let (canonical, metadata) = resolve(&path, &POLICY).unwrap();
+        assert_eq!(
+            canonical,
+            dir.path().canonicalize().unwrap().join("notater.md")
+        );
+        assert!(metadata.is_file());
+        assert_eq!(read(&canonical, &POLICY).unwrap(), "# Notater");
+
+        let (folder, metadata) = resolve(&dir.path().join("prosjekt"), &POLICY).unwrap();
+        assert!(metadata.is_dir());
+        assert!(folder.ends_with("prosjekt"));
+    }
+
+    #[test]
+    fn test_resolve_errors() {
+        let dir = fixture();
+        assert!(matches!(
+            resolve(&dir.path().join("borte.md"), &POLICY),
+            Err(LocalFileError::NotFound(_))
+        ));
+
+        // "les meg.txt" er 2 KB
+        let large = dir.path().join("les meg.txt");
+        assert!(matches!(
+            resolve(&large, &POLICY),
+            Err(LocalFileError::TooLarge {
+                size: 2048,
+                max: 1024
+            })
+        ));
+        assert!(matches!(
+            read(&large, &POLICY),
+            Err(LocalFileError::TooLarge { size: 1025, .. })
+        ));
+        let error = resolve(&large, &POLICY).unwrap_err();
+        assert_eq!(
+            error.localize_in(Locale::En),
+            "File too large (2.0 KB, max 1.0 KB)"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_refuses_special_files_and_unreadable_files() {
+        use std::os::unix::fs::PermissionsExt;
+
+        assert!(matches!(
+            resolve(Path::new("/dev/null"), &POLICY),
+            Err(LocalFileError::SpecialFile(_))
+        ));
+
+        let dir = fixture();
+        let socket = dir.path().join("kanal.md");
+        let _listener = std::os::unix::net::UnixListener::bind(&socket).unwrap();
+        assert!(matches!(
+            resolve(&socket, &POLICY),
+            Err(LocalFileError::SpecialFile(_))
+        ));
+
+        let locked = dir.path().join("notater.md");
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+        // root kan lese filen uansett
+        if File::open(&locked).is_err() {
+            assert!(matches!(
+                read(&locked, &POLICY),
+                Err(LocalFileError::PermissionDenied(_))
+            ));
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_symlink_policy() {
+        let dir = fixture();
+        let link = dir.path().join("snarvei.md");
+        std::os::unix::fs::symlink(dir.path().join("Kapsel.gmi"), &link).unwrap();
+
+        let (target, _) = resolve(&link, &POLICY).unwrap();
+        assert_eq!(
+            target,
+            dir.path().canonicalize().unwrap().join("Kapsel.gmi")
+        );
+        assert_eq!(LocalFormat::from_path(&target), Some(LocalFormat::Gemtext));
+
+        let strict = OpenPolicy {
+            follow_symlinks: false,
+            ..POLICY
+        };
+        assert!(matches!(
+            resolve(&link, &strict),
+            Err(LocalFileError::Symlink(_))
+        ));
+        assert!(resolve(&dir.path().join("notater.md"), &strict).is_ok());
+    }
+
+    #[test]
+    fn test_local_format_from_extension() {
+        let cases = [
+            ("notater.md", Some(LocalFormat::Markdown)),
+            ("NOTATER.MD", Some(LocalFormat::Markdown)),
+            ("notater.markdown", Some(LocalFormat::Markdown)),
+            ("kapsel.gmi", Some(LocalFormat::Gemtext)),
+            ("kapsel.Gemini", Some(LocalFormat::Gemtext)),
+            ("les-meg.txt", Some(LocalFormat::Text)),
+            ("bilde.png", None),
+            ("uten-endelse", None),
+        ];
+        for (name, expected) in cases {
+            assert_eq!(
+                LocalFormat::from_path(Path::new(name)),
+                expected,
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_list_directory_folders_first_and_skips_hidden() {
+        let dir = fixture();
+        let entries = list_directory(dir.path(), false).unwrap();
+        assert_eq!(
+            names(&entries),
+            vec![
+                "Arkiv",
+                "prosjekt",
+                "Kapsel.gmi",
+                "les meg.txt",
+                "notater.md"
+            ]
+        );
+
+        let folder = &entries[1];
+        assert!(folder.is_dir);
+        assert!(folder.url.starts_with("file://") && folder.url.ends_with("/prosjekt/"));
+        let text = &entries[3];
+        assert_eq!(text.size, 2048);
+        assert!(text.url.ends_with("/les%20meg.txt"));
+        assert!(text.modified.is_some());
+
+        let all = list_directory(dir.path(), true).unwrap();
+        assert_eq!(
+            names(&all),
+            vec![
+                ".git",
+                "Arkiv",
+                "prosjekt",
+                ".skjult.md",
+                "Kapsel.gmi",
+                "les meg.txt",
+                "notater.md"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_directory_markdown_lists_entries_and_index() {
+        let dir = fixture();
+        fs::write(dir.path().join("README.md"), "Les dette først.").unwrap();
+        let entries = list_directory(dir.path(), false).unwrap();
+
+        let index = index_file(&entries).unwrap();
+        assert_eq!(index, dir.path().join("README.md"));
+        fs::write(dir.path().join("index.md"), "Forsiden.").unwrap();
+        let entries = list_directory(dir.path(), false).unwrap();
+        assert_eq!(index_file(&entries).unwrap(), dir.path().join("index.md"));
+
+        let md = directory_markdown(dir.path(), &entries, "Tom", Some("Forsiden."));
+        let lines: Vec<&str> = md.lines().collect();
+        assert!(lines[0].starts_with("# "));
+        assert!(lines[2].starts_with("- [..](<file://"));
+        assert!(lines[3].starts_with("- 📁 [Arkiv/](<file://"));
+        assert!(md.contains("- [les meg\\.txt](<file://"));
+        assert!(md.contains(") · 2.0 KB · "));
+        assert!(!md.contains("dypt.md"));
+        assert!(md.ends_with("\n---\n\nForsiden.\n"));
+        assert!(!md.contains("Tom"));
+
+        let html = markdown::render_with_options(&md, &markdown::RenderOptions::default()).html;
+        assert!(html.contains("les%20meg.txt\">les meg.txt</a>"));
+    }
+
+    #[test]
+    fn test_empty_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(".skjult.md"), "").unwrap();
+        let entries = list_directory(dir.path(), false).unwrap();
+        assert!(entries.is_empty());
+        assert!(index_file(&entries).is_none());
+        let md = directory_markdown(dir.path(), &entries, "Mappen er tom", None);
+        assert!(md.ends_with("\n*Mappen er tom*\n"));
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MB");
+    }
+}
@@ -0,0 +1,599 @@
+//! Oversettelse av tekster som sendes til frontend
+//!
+//! Feilmeldinger, statusmeldinger og innebygde sider slås opp med en nøkkel
+//! i en tabell per språk. Backend har norsk (bokmål) og engelsk; andre
+//! språk i frontend får engelsk. `{0}`, `{1}` osv. byttes ut med argumenter.
+//!
+//! Feiltypenes `Display` (thiserror) er fortsatt norsk og brukes i loggen;
+//! command-laget oversetter med [`Localize`] før feilen returneres.
+
+use serde::Serialize;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Språk for tekster fra backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum Locale {
+    /// Norsk bokmål (også brukt for nynorsk)
+    #[default]
+    Nb,
+    /// Engelsk
+    En,
+}
+
+impl Locale {
+    /// Finn språket for en språkinnstilling
+    ///
+    /// # Arguments
+    /// * `language` - ISO 639-1-kode (f.eks. "nb", "en") eller "system"
+    pub fn from_language(language: &str) -> Self {
+        if language == "system" {
+            return Self::from_system();
+        }
+        Self::from_code(language)
+    }
+
+    /// Språket fra miljøvariablene (LC_ALL, LC_MESSAGES, LANG)
+    fn from_system() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty() && value != "C" && value != "POSIX")
+            .map(|value| Self::from_code(&value))
+            .unwrap_or_default()
+    }
+
+    /// Språket for en kode som "nb", "nn_NO.UTF-8" eller "en-US"
+    fn from_code(code: &str) -> Self {
+        let primary = code
+            .split(['_', '-', '.'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        match primary.as_str() {
+            "nb" | "nn" | "no" => Self::Nb,
+            _ => Self::En,
+        }
+    }
+
+    /// Tabellen med tekster for språket
+    fn messages(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Nb => NB,
+            Self::En => EN,
+        }
+    }
+
+    /// Slå opp en tekst
+    ///
+    /// Mangler nøkkelen brukes den norske teksten, og deretter nøkkelen selv.
+    pub fn text(self, key: &'static str) -> &'static str {
+        lookup(self.messages(), key)
+            .or_else(|| lookup(NB, key))
+            .unwrap_or_else(|| {
+                log::warn!("Mangler oversettelse for {}", key);
+                key
+            })
+    }
+
+    /// Slå opp en tekst og sett inn argumenter for `{0}`, `{1}` osv.
+    pub fn format(self, key: &'static str, args: &[&dyn Display]) -> String {
+        args.iter()
+            .enumerate()
+            .fold(self.text(key).to_string(), |text, (i, arg)| {
+                text.replace(&format!("{{{}}}", i), &arg.to_string())
+            })
+    }
+}
+
+fn lookup(messages: &[(&'static str, &'static str)], key: &str) -> Option<&'static str> {
+    messages.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+}
+
+/// Språket brukeren har valgt (0 = nb, 1 = en)
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+/// Språket som brukes for tekster til frontend
+pub fn current() -> Locale {
+    match CURRENT.load(Ordering::Relaxed) {
+        1 => Locale::En,
+        _ => Locale::Nb,
+    }
+}
+
+/// Bytt språk, f.eks. når språkinnstillingen endres
+pub fn set_current(locale: Locale) {
+    let value = match locale {
+        Locale::Nb => 0,
+        Locale::En => 1,
+    };
+    CURRENT.store(value, Ordering::Relaxed);
+}
+
+/// Slå opp en tekst på brukerens språk
+pub fn t(key: &'static str) -> &'static str {
+    current().text(key)
+}
+
+/// Slå opp en tekst med argumenter på brukerens språk
+pub fn tf(key: &'static str, args: &[&dyn Display]) -> String {
+    current().format(key, args)
+}
+
+/// Feil som kan vises for brukeren på flere språk
+pub trait Localize {
+    /// Feilmeldingen på et gitt språk
+    fn localize_in(&self, locale: Locale) -> String;
+
+    /// Feilmeldingen på brukerens språk
+    fn localize(&self) -> String {
+        self.localize_in(current())
+    }
+}
+
+/// Norsk (bokmål)
+const NB: &[(&str, &str)] = &[
+    // Statusmeldinger under lasting
+    ("status.done", "Dokument: Ferdig"),
+    ("status.superseded", "Avbrutt av en nyere navigering"),
+    ("status.fetch_failed", "Feil under henting"),
+    ("status.transferring", "Overfører data... ({0} bytes)"),
+    ("status.rendering_markdown", "Rendrer markdown..."),
+    ("status.rendering_text", "Rendrer tekst..."),
+    ("status.converting_html", "Konverterer HTML til markdown..."),
+    ("status.converting_gemtext", "Konverterer gemtext..."),
+    ("status.converting_gophermap", "Konverterer gophermap..."),
+    ("status.converting_search_results", "Konverterer søkeresultater..."),
+    ("status.converting_error", "Konverterer feilmelding..."),
+    ("status.resolving", "{0} {1}: Slår opp {2}..."),
+    ("status.connecting", "{0} {1}: Kobler til {2}..."),
+    ("status.file_opening", "{0} Lokal fil: Åpner {1}..."),
+    ("status.file_rendering", "{0} Lokal fil: Rendrer markdown..."),
+    ("status.gemini_handshake", "{0} Gemini TLS-handshake (port 1965) med {1}..."),
+    ("status.gopher_connecting", "{0} Gopher: Kobler til {1} (port 70)..."),
+    ("status.gopher_searching", "{0} Gopher: Søker på {1}..."),
+    ("status.markdown_only", "Stoppet: Kun markdown"),
+    ("status.awaiting_choice", "Venter på brukervalg..."),
+    ("status.awaiting_input", "Venter på brukerinput..."),
+    ("status.awaiting_search", "Venter på søkeinput..."),
+    ("status.certificate_error", "Sertifikat-feil"),
+    ("status.client_cert_required", "Klientsertifikat påkrevd"),
+    // Feil fra commands
+    ("error.file_not_found", "Filen finnes ikke: {0}"),
+    ("error.file_unsupported", "Bare kan åpne .md, .markdown, .gmi, .gemini og .txt"),
+    ("error.directory_read", "Kunne ikke lese mappen: {}"),
+    ("error.watch_file", "Kunne ikke overvåke filen: {}"),
+    ("error.file_read", "Kunne ikke lese fil: {0}"),
+    ("error.file_permission_denied", "Ingen tilgang til {0}"),
+    ("error.file_too_large", "Filen er for stor ({0}, maks {1})"),
+    ("error.file_special", "{0} er ikke en vanlig fil"),
+    ("error.file_symlink", "{0} er en symbolsk lenke, og symbolske lenker følges ikke"),
+    ("error.not_markdown", "Innholdet er ikke markdown (Content-Type: {0}). Konvertering er deaktivert i innstillingene."),
+    ("error.certificate_warning", "⚠️ Sertifikatadvarsel for {0}!\n\nSertifikatet har endret seg siden forrige besøk.\nDette kan indikere et sikkerhetsbrudd.\n\nGammelt fingerprint: {1}\nNytt fingerprint: {2}"),
+    ("error.no_snapshot", "Bokmerket har ingen arkivert kopi: {0}"),
+    ("prompt.convert_html", "Innholdet er HTML. Vil du konvertere det til markdown?"),
+    ("prompt.scheme_redirect", "Siden sender deg videre til {0}, som bruker en annen protokoll. Vil du åpne adressen?"),
+    ("prompt.client_cert", "{0} ber om et klientsertifikat: {1}\n\nVil du lage en ny identitet for {0}? Kapselen vil kjenne deg igjen på den."),
+    ("prompt.confirm_navigation", "Adressen til {0} ser mistenkelig ut:"),
+    ("prompt.confirm_navigation_question", "Vil du åpne den likevel?"),
+    // Advarsler om adresser (url_safety.rs)
+    ("url_safety.mixed_script", "{0} blander bokstaver fra flere alfabeter."),
+    ("url_safety.confusable", "{0} kan forveksles med {1}."),
+    ("url_safety.userinfo", "Adressen går til {1}, ikke til {0}. Det som står foran @ er et brukernavn."),
+    ("url_safety.link_text", "Lenketeksten viser {0}, men lenken går til {1}."),
+    // Felles feil for nettverksprotokollene
+    ("error.invalid_url", "Ugyldig URL: {0}"),
+    ("error.open_window", "Kunne ikke åpne nytt vindu: {0}"),
+    ("error.export_no_destination", "Velg hvor filen skal lagres"),
+    ("error.export_write", "Kunne ikke skrive eksportert fil: {0}"),
+    ("error.download_destination", "Velg en fil å lagre nedlastingen i"),
+    ("error.download_write", "Kunne ikke lagre nedlastingen: {0}"),
+    ("error.download_cancelled", "Nedlastingen ble avbrutt"),
+    ("error.download_scheme", "Kan ikke laste ned fra {0}-adresser"),
+    ("error.not_displayable", "Innholdet kan ikke vises, men kan lastes ned: {0}"),
+    ("error.internal", "Noe gikk galt i Bare da siden skulle vises. Se loggen for detaljer."),
+    ("error.log_read", "Kunne ikke lese loggfilen: {0}"),
+    ("error.onboarding_step", "Onboarding har ikke steg {0} (1–{1})"),
+    ("render.truncated", "Dokumentet er for stort til å vises i sin helhet. Resten er utelatt."),
+    ("render.diagram_source", "Diagramkilde ({0})"),
+    ("render.admonition_note", "Merk"),
+    ("render.admonition_tip", "Tips"),
+    ("render.admonition_important", "Viktig"),
+    ("render.admonition_warning", "Advarsel"),
+    ("render.admonition_caution", "Forsiktig"),
+    ("error.save_source", "Kunne ikke lagre kilden: {0}"),
+    ("error.clipboard", "Kunne ikke kopiere til utklippstavlen: {0}"),
+    ("error.timeout", "Timeout: Serveren svarte ikke innen {0} sekunder"),
+    ("error.too_large", "Responsen er for stor (maks {0} bytes)"),
+    ("error.connection", "Tilkoblingsfeil: {0}"),
+    ("error.invalid_response", "Ugyldig respons fra serveren: {0}"),
+    ("error.network", "Nettverksfeil: {0}"),
+    ("error.io", "I/O-feil: {0}"),
+    ("error.unsupported_scheme", "Ustøttet protokoll: {0}. Kun http og https støttes."),
+    ("error.not_found", "Ressurs ikke funnet (404): {0}"),
+    ("error.server", "Server-feil ({0}): {1}"),
+    ("error.tls", "TLS-feil: {0}"),
+    ("error.gemini_input", "Serveren ber om input: {0}"),
+    ("error.gemini_sensitive_input", "Sensitiv input forespurt: {0}"),
+    ("error.redirect_loop", "For mange redirects (maks {0})"),
+    ("error.gemini_cross_scheme_redirect", "Serveren sender videre til en annen protokoll: {0}"),
+    ("error.unknown_scheme", "Bare kan ikke åpne {0}://-adresser."),
+    ("error.gemini_certificate_changed", "Sertifikatet for {0} har endret seg!\nGammelt fingerprint: {1}\nNytt fingerprint: {2}\nDette kan indikere et man-in-the-middle angrep."),
+    ("error.gemini_client_cert", "Serveren krever klientsertifikat: {0}"),
+    ("error.gemini_client_cert_rejected", "Serveren godtok ikke klientsertifikatet ({0}): {1}"),
+    ("error.identity_empty_name", "Identiteten må ha et navn"),
+    ("error.identity_not_found", "Fant ingen identitet med fingerprint {0}"),
+    ("error.identity_invalid_host", "Ugyldig vert: {0}"),
+    ("error.identity_generate", "Kunne ikke lage sertifikat: {0}"),
+    ("error.identity_save", "Kunne ikke lagre identitetene: {0}"),
+    ("error.gemini_server", "Gemini-feil ({0}): {1}"),
+    ("error.gopher_search_input", "Serveren ber om søkeinput"),
+    // Bokmerker
+    ("error.bookmarks_read", "Kunne ikke lese bokmerker: {0}"),
+    ("error.bookmarks_write", "Kunne ikke lagre bokmerker: {0}"),
+    ("error.bookmark_exists", "Bokmerke finnes allerede: {0}"),
+    ("error.bookmark_not_found", "Bokmerke ikke funnet: {0}"),
+    ("error.invalid_folder", "Ugyldig mappenavn: {0}"),
+    ("error.folder_not_found", "Mappe ikke funnet: {0}"),
+    ("error.folder_exists", "Mappe finnes allerede: {0}"),
+    ("error.invalid_keyword", "Ugyldig nøkkelord: {0} (kun små bokstaver, tall, - og _)"),
+    ("error.keyword_in_use", "Nøkkelordet er allerede i bruk: {0}"),
+    // Arkiverte kopier
+    ("error.snapshot_too_large", "Siden er for stor til å arkiveres ({0} bytes, maks {1})"),
+    ("error.snapshot_invalid_id", "Ugyldig bokmerke-ID for arkivering: {0}"),
+    ("error.snapshot_not_loaded", "Siden er ikke åpen, last den inn før den arkiveres: {0}"),
+    ("error.snapshot_read", "Kunne ikke lese arkivert kopi: {0}"),
+    ("error.snapshot_write", "Kunne ikke lagre arkivert kopi: {0}"),
+    ("error.history_read", "Kunne ikke lese historikken: {0}"),
+    ("error.history_write", "Kunne ikke lagre historikken: {0}"),
+    ("error.recent_files_write", "Kunne ikke lagre nylige filer: {0}"),
+    // Søk i siden
+    ("error.find_empty", "Søket er tomt"),
+    ("error.find_too_long", "Søket er for langt ({0} tegn, maks {1})"),
+    ("error.find_invalid_pattern", "Ugyldig søkemønster: {0}"),
+    // Innstillinger
+    ("error.settings_read", "Kunne ikke lese innstillinger: {0}"),
+    ("error.settings_write", "Kunne ikke lagre innstillinger: {0}"),
+    ("error.invalid_homepage", "Ugyldig startside: {0}. Bruk en URL, en filsti, \"welcome\" eller \"bookmarks\"."),
+    ("error.invalid_site_pattern", "Ugyldig vertsnavn for nettstedsinnstillinger: {0}"),
+    ("error.invalid_font_name", "Ugyldig skriftnavn: {0}. Bruk bokstaver, tall, mellomrom, punktum, - og _."),
+    ("error.invalid_search_engine", "Ugyldig søkemotor: {0}. Bruk en http(s)-adresse med %s der søkeordene skal stå."),
+    ("error.invalid_color", "Ugyldig farge: {0}. Bruk #rgb eller #rrggbb."),
+    ("error.invalid_downloads_dir", "Ugyldig nedlastingsmappe: {0}. Mappen må finnes eller kunne opprettes, og være skrivbar."),
+    ("error.settings_not_object", "Innstillingene må sendes som et objekt med felter"),
+    ("error.settings_field", "Ugyldig verdi for {0}: {1}"),
+    ("error.css_too_large", "Egendefinert CSS er for stor ({0} bytes, maks {1})"),
+    ("error.unknown_action", "Ukjent handling: {0}"),
+    ("error.invalid_accelerator", "Ugyldig snarvei: {0}"),
+    ("error.duplicate_accelerator", "Snarveien {0} brukes allerede av {1}"),
+    // Gopher-menyer
+    ("gopher.telnet_unsupported", "Telnet — ikke støttet"),
+    ("gopher.cso_phonebook", "CSO-telefonbok"),
+    ("gopher.error_title", "Gopher-feil"),
+    ("gopher.search_results", "Søkeresultater: {0}"),
+    ("gopher.type.text", "Tekstfil"),
+    ("gopher.type.directory", "Mappe"),
+    ("gopher.type.error", "Feil"),
+    ("gopher.type.binhex", "BinHex-fil"),
+    ("gopher.type.dos_binary", "DOS-binærfil"),
+    ("gopher.type.uuencoded", "UUencodet fil"),
+    ("gopher.type.search", "Søk"),
+    ("gopher.type.binary", "Binærfil"),
+    ("gopher.type.gif", "GIF-bilde"),
+    ("gopher.type.image", "Bilde"),
+    ("gopher.type.unknown", "Ukjent"),
+    // Feilsider
+    ("error_page.address", "Adresse"),
+    ("error_page.retry", "Prøv igjen"),
+    ("error_page.open_externally", "Åpne i standardnettleseren"),
+    ("error_page.back", "Gå tilbake"),
+    ("error_page.dns.title", "Fant ikke serveren"),
+    ("error_page.dns.body", "Navnet {0} kunne ikke slås opp. Sjekk at adressen er skrevet riktig og at du er koblet til nettet."),
+    ("error_page.connection.title", "Kunne ikke koble til"),
+    ("error_page.connection.body", "{0} tok ikke imot tilkoblingen. Serveren kan være nede, eller en brannmur kan stoppe den."),
+    ("error_page.timeout.title", "Serveren brukte for lang tid"),
+    ("error_page.timeout.body", "{0} svarte ikke i tide. Prøv igjen om litt, eller øk tidsavbruddet i innstillingene."),
+    ("error_page.not_found.title", "Siden finnes ikke"),
+    ("error_page.not_found.body", "Serveren har ingen side på denne adressen. Den kan være flyttet eller slettet."),
+    ("error_page.server.title", "Feil hos serveren"),
+    ("error_page.server.body", "{0} klarte ikke å svare på forespørselen. Feilen er hos nettstedet, ikke hos deg."),
+    ("error_page.certificate_changed.title", "Sertifikatet er endret"),
+    ("error_page.certificate_changed.body", "Sertifikatet til {0} er et annet enn sist. Det kan være fornyet, men det kan også være noen som utgir seg for å være serveren."),
+    ("error_page.too_large.title", "Siden er for stor"),
+    ("error_page.too_large.body", "Siden er større enn grensen i innstillingene og ble ikke lastet ned."),
+    ("error_page.unsupported.title", "Kan ikke vises"),
+    ("error_page.unsupported.body", "Bare kan ikke vise innhold fra {0}."),
+    ("error_page.invalid_argument.title", "Fant ikke filen eller adressen"),
+    ("error_page.invalid_argument.body", "«{0}» fra kommandolinjen er verken en fil som finnes eller en adresse Bare kan åpne."),
+    ("error_page.other.title", "Siden kunne ikke lastes"),
+    ("error_page.other.body", "Noe gikk galt da siden fra {0} skulle hentes."),
+    // Bokmerkesiden
+    ("bookmarks_page.title", "Bokmerker"),
+    ("bookmarks_page.summary", "{0} i {1}"),
+    ("bookmarks_page.empty", "Du har ingen bokmerker ennå."),
+    ("bookmarks_page.pinned", "Festet"),
+    ("bookmarks_page.no_folder", "Uten mappe"),
+    ("bookmarks_page.added", "lagt til {0}"),
+    ("directory_page.empty", "Mappen har ingen filer Bare kan vise."),
+    ("count.bookmark_one", "{0} bokmerke"),
+    ("count.bookmark_many", "{0} bokmerker"),
+    ("count.folder_one", "{0} mappe"),
+    ("count.folder_many", "{0} mapper"),
+    ("age.today", "i dag"),
+    ("age.yesterday", "i går"),
+    ("age.days", "for {0} dager siden"),
+    ("age.weeks", "for {0} uker siden"),
+    ("age.months", "for {0} måneder siden"),
+    ("age.one_year", "for ett år siden"),
+    ("age.years", "for {0} år siden"),
+    // Velkomstsiden
+    ("welcome.pinned", "Festede bokmerker"),
+    ("top_sites.most_visited", "Ofte besøkt"),
+    ("top_sites.recently_visited", "Nylig besøkt"),
+];
+
+/// Engelsk
+const EN: &[(&str, &str)] = &[
+    // Statusmeldinger under lasting
+    ("status.done", "Document: Done"),
+    ("status.superseded", "Cancelled by a newer navigation"),
+    ("status.fetch_failed", "Error while fetching"),
+    ("status.transferring", "Transferring data... ({0} bytes)"),
+    ("status.rendering_markdown", "Rendering markdown..."),
+    ("status.rendering_text", "Rendering text..."),
+    ("status.converting_html", "Converting HTML to markdown..."),
+    ("status.converting_gemtext", "Converting gemtext..."),
+    ("status.converting_gophermap", "Converting gophermap..."),
+    ("status.converting_search_results", "Converting search results..."),
+    ("status.converting_error", "Converting error message..."),
+    ("status.resolving", "{0} {1}: Looking up {2}..."),
+    ("status.connecting", "{0} {1}: Connecting to {2}..."),
+    ("status.file_opening", "{0} Local file: Opening {1}..."),
+    ("status.file_rendering", "{0} Local file: Rendering markdown..."),
+    ("status.gemini_handshake", "{0} Gemini TLS handshake (port 1965) with {1}..."),
+    ("status.gopher_connecting", "{0} Gopher: Connecting to {1} (port 70)..."),
+    ("status.gopher_searching", "{0} Gopher: Searching {1}..."),
+    ("status.markdown_only", "Stopped: Markdown only"),
+    ("status.awaiting_choice", "Waiting for your choice..."),
+    ("status.awaiting_input", "Waiting for input..."),
+    ("status.awaiting_search", "Waiting for search input..."),
+    ("status.certificate_error", "Certificate error"),
+    ("status.client_cert_required", "Client certificate required"),
+    // Feil fra commands
+    ("error.file_not_found", "File not found: {0}"),
+    ("error.file_unsupported", "Bare can open .md, .markdown, .gmi, .gemini and .txt"),
+    ("error.directory_read", "Could not read the folder: {}"),
+    ("error.watch_file", "Could not watch the file: {}"),
+    ("error.file_read", "Could not read file: {0}"),
+    ("error.file_permission_denied", "Permission denied: {0}"),
+    ("error.file_too_large", "File too large ({0}, max {1})"),
+    ("error.file_special", "{0} is not a regular file"),
+    ("error.file_symlink", "{0} is a symbolic link, and symbolic links are not followed"),
+    ("error.not_markdown", "The content is not markdown (Content-Type: {0}). Conversion is disabled in the settings."),
+    ("error.certificate_warning", "⚠️ Certificate warning for {0}!\n\nThe certificate has changed since your last visit.\nThis may indicate a security breach.\n\nOld fingerprint: {1}\nNew fingerprint: {2}"),
+    ("error.no_snapshot", "The bookmark has no archived copy: {0}"),
+    ("prompt.convert_html", "The content is HTML. Do you want to convert it to markdown?"),
+    ("prompt.scheme_redirect", "The page redirects you to {0}, which uses another protocol. Do you want to open it?"),
+    ("prompt.client_cert", "{0} asks for a client certificate: {1}\n\nDo you want to create a new identity for {0}? The capsule will recognise you by it."),
+    ("prompt.confirm_navigation", "The address of {0} looks suspicious:"),
+    ("prompt.confirm_navigation_question", "Do you want to open it anyway?"),
+    // Advarsler om adresser (url_safety.rs)
+    ("url_safety.mixed_script", "{0} mixes letters from different alphabets."),
+    ("url_safety.confusable", "{0} can be mistaken for {1}."),
+    ("url_safety.userinfo", "The address goes to {1}, not to {0}. The part before @ is a user name."),
+    ("url_safety.link_text", "The link text shows {0}, but the link goes to {1}."),
+    // Felles feil for nettverksprotokollene
+    ("error.invalid_url", "Invalid URL: {0}"),
+    ("error.open_window", "Could not open new window: {0}"),
+    ("error.export_no_destination", "Choose where to save the file"),
+    ("error.export_write", "Could not write exported file: {0}"),
+    ("error.download_destination", "Choose a file to save the download to"),
+    ("error.download_write", "Could not save the download: {0}"),
+    ("error.download_cancelled", "The download was cancelled"),
+    ("error.download_scheme", "Cannot download from {0} addresses"),
+    ("error.not_displayable", "The content cannot be displayed, but can be downloaded: {0}"),
+    ("error.internal", "Something went wrong in Bare while showing the page. See the log for details."),
+    ("error.log_read", "Could not read the log file: {0}"),
+    ("error.onboarding_step", "Onboarding has no step {0} (1–{1})"),
+    ("render.truncated", "The document is too large to show in full. The rest has been left out."),
+    ("render.diagram_source", "Diagram source ({0})"),
+    ("render.admonition_note", "Note"),
+    ("render.admonition_tip", "Tip"),
+    ("render.admonition_important", "Important"),
+    ("render.admonition_warning", "Warning"),
+    ("render.admonition_caution", "Caution"),
+    ("error.save_source", "Could not save the source: {0}"),
+    ("error.clipboard", "Could not copy to the clipboard: {0}"),
+    ("error.timeout", "Timeout: The server did not respond within {0} seconds"),
+    ("error.too_large", "The response is too large (max {0} bytes)"),
+    ("error.connection", "Connection error: {0}"),
+    ("error.invalid_response", "Invalid response from the server: {0}"),
+    ("error.network", "Network error: {0}"),
+    ("error.io", "I/O error: {0}"),
+    ("error.unsupported_scheme", "Unsupported protocol: {0}. Only http and https are supported."),
+    ("error.not_found", "Resource not found (404): {0}"),
+    ("error.server", "Server error ({0}): {1}"),
+    ("error.tls", "TLS error: {0}"),
+    ("error.gemini_input", "The server asks for input: {0}"),
+    ("error.gemini_sensitive_input", "Sensitive input requested: {0}"),
+    ("error.redirect_loop", "Too many redirects (max {0})"),
+    ("error.gemini_cross_scheme_redirect", "The server redirects to another protocol: {0}"),
+    ("error.unknown_scheme", "Bare can't open {0}:// addresses."),
+    ("error.gemini_certificate_changed", "The certificate for {0} has changed!\nOld fingerprint: {1}\nNew fingerprint: {2}\nThis may indicate a man-in-the-middle attack."),
+    ("error.gemini_client_cert", "The server requires a client certificate: {0}"),
+    ("error.gemini_client_cert_rejected", "The server did not accept the client certificate ({0}): {1}"),
+    ("error.identity_empty_name", "The identity needs a name"),
+    ("error.identity_not_found", "No identity with fingerprint {0}"),
+    ("error.identity_invalid_host", "Invalid host: {0}"),
+    ("error.identity_generate", "Could not create certificate: {0}"),
+    ("error.identity_save", "Could not save the identities: {0}"),
+    ("error.gemini_server", "Gemini error ({0}): {1}"),
+    ("error.gopher_search_input", "The server asks for search input"),
+    // Bokmerker
+    ("error.bookmarks_read", "Could not read bookmarks: {0}"),
+    ("error.bookmarks_write", "Could not save bookmarks: {0}"),
+    ("error.bookmark_exists", "Bookmark already exists: {0}"),
+    ("error.bookmark_not_found", "Bookmark not found: {0}"),
+    ("error.invalid_folder", "Invalid folder name: {0}"),
+    ("error.folder_not_found", "Folder not found: {0}"),
+    ("error.folder_exists", "Folder already exists: {0}"),
+    ("error.invalid_keyword", "Invalid keyword: {0} (only lowercase letters, digits, - and _)"),
+    ("error.keyword_in_use", "The keyword is already in use: {0}"),
+    // Arkiverte kopier
+    ("error.snapshot_too_large", "The page is too large to archive ({0} bytes, max {1})"),
+    ("error.snapshot_invalid_id", "Invalid bookmark ID for archiving: {0}"),
+    ("error.snapshot_not_loaded", "The page is not open, load it before archiving: {0}"),
+    ("error.snapshot_read", "Could not read archived copy: {0}"),
+    ("error.snapshot_write", "Could not save archived copy: {0}"),
+    ("error.history_read", "Could not read history: {0}"),
+    ("error.history_write", "Could not save history: {0}"),
+    ("error.recent_files_write", "Could not save recent files: {0}"),
+    // Søk i siden
+    ("error.find_empty", "The search is empty"),
+    ("error.find_too_long", "The search is too long ({0} characters, max {1})"),
+    ("error.find_invalid_pattern", "Invalid search pattern: {0}"),
+    // Innstillinger
+    ("error.settings_read", "Could not read settings: {0}"),
+    ("error.settings_write", "Could not save settings: {0}"),
+    ("error.invalid_homepage", "Invalid home page: {0}. Use a URL, a file path, \"welcome\" or \"bookmarks\"."),
+    ("error.invalid_site_pattern", "Invalid host name for site settings: {0}"),
+    ("error.invalid_font_name", "Invalid font name: {0}. Use letters, digits, spaces, periods, - and _."),
+    ("error.invalid_search_engine", "Invalid search engine: {0}. Use an http(s) address with %s where the search terms go."),
+    ("error.invalid_color", "Invalid color: {0}. Use #rgb or #rrggbb."),
+    ("error.invalid_downloads_dir", "Invalid downloads folder: {0}. The folder must exist or be possible to create, and be writable."),
+    ("error.settings_not_object", "Settings must be sent as an object with fields"),
+    ("error.settings_field", "Invalid value for {0}: {1}"),
+    ("error.css_too_large", "Custom CSS is too large ({0} bytes, max {1})"),
+    ("error.unknown_action", "Unknown action: {0}"),
+    ("error.invalid_accelerator", "Invalid shortcut: {0}"),
+    ("error.duplicate_accelerator", "The shortcut {0} is already used by {1}"),
+    // Gopher-menyer
+    ("gopher.telnet_unsupported", "Telnet — not supported"),
+    ("gopher.cso_phonebook", "CSO phone book"),
+    ("gopher.error_title", "Gopher error"),
+    ("gopher.search_results", "Search results: {0}"),
+    ("gopher.type.text", "Text file"),
+    ("gopher.type.directory", "Directory"),
+    ("gopher.type.error", "Error"),
+    ("gopher.type.binhex", "BinHex file"),
+    ("gopher.type.dos_binary", "DOS binary"),
+    ("gopher.type.uuencoded", "UUencoded file"),
+    ("gopher.type.search", "Search"),
+    ("gopher.type.binary", "Binary file"),
+    ("gopher.type.gif", "GIF image"),
+    ("gopher.type.image", "Image"),
+    ("gopher.type.unknown", "Unknown"),
+    // Feilsider
+    ("error_page.address", "Address"),
+    ("error_page.retry", "Try again"),
+    ("error_page.open_externally", "Open in your default browser"),
+    ("error_page.back", "Go back"),
+    ("error_page.dns.title", "Server not found"),
+    ("error_page.dns.body", "The name {0} could not be looked up. Check that the address is spelled correctly and that you are online."),
+    ("error_page.connection.title", "Unable to connect"),
+    ("error_page.connection.body", "{0} did not accept the connection. The server may be down, or a firewall may be blocking it."),
+    ("error_page.timeout.title", "The server took too long"),
+    ("error_page.timeout.body", "{0} did not answer in time. Try again in a moment, or raise the timeout in the settings."),
+    ("error_page.not_found.title", "Page not found"),
+    ("error_page.not_found.body", "The server has no page at this address. It may have been moved or deleted."),
+    ("error_page.server.title", "Server error"),
+    ("error_page.server.body", "{0} could not answer the request. The problem is with the site, not with you."),
+    ("error_page.certificate_changed.title", "Certificate changed"),
+    ("error_page.certificate_changed.body", "The certificate for {0} differs from last time. It may have been renewed, but someone may also be pretending to be the server."),
+    ("error_page.too_large.title", "Page too large"),
+    ("error_page.too_large.body", "The page is larger than the limit in the settings and was not downloaded."),
+    ("error_page.unsupported.title", "Can't be shown"),
+    ("error_page.unsupported.body", "Bare can't show content from {0}."),
+    ("error_page.invalid_argument.title", "File or address not found"),
+    ("error_page.invalid_argument.body", "“{0}” from the command line is neither an existing file nor an address Bare can open."),
+    ("error_page.other.title", "The page could not be loaded"),
+    ("error_page.other.body", "Something went wrong while fetching the page from {0}."),
+    // Bokmerkesiden
+    ("bookmarks_page.title", "Bookmarks"),
+    ("bookmarks_page.summary", "{0} in {1}"),
+    ("bookmarks_page.empty", "You have no bookmarks yet."),
+    ("bookmarks_page.pinned", "Pinned"),
+    ("bookmarks_page.no_folder", "No folder"),
+    ("bookmarks_page.added", "added {0}"),
+    ("directory_page.empty", "This folder has no files Bare can show."),
+    ("count.bookmark_one", "{0} bookmark"),
+    ("count.bookmark_many", "{0} bookmarks"),
+    ("count.folder_one", "{0} folder"),
+    ("count.folder_many", "{0} folders"),
+    ("age.today", "today"),
+    ("age.yesterday", "yesterday"),
+    ("age.days", "{0} days ago"),
+    ("age.weeks", "{0} weeks ago"),
+    ("age.months", "{0} months ago"),
+    ("age.one_year", "a year ago"),
+    ("age.years", "{0} years ago"),
+    // Velkomstsiden
+    ("welcome.pinned", "Pinned bookmarks"),
+    ("top_sites.most_visited", "Most visited"),
+    ("top_sites.recently_visited", "Recently visited"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    fn keys(messages: &[(&'static str, &'static str)]) -> BTreeSet<&'static str> {
+        messages.iter().map(|(k, _)| *k).collect()
+    }
+
+    fn placeholders(text: &str) -> BTreeSet<usize> {
+        (0..10)
+            .filter(|i| text.contains(&format!("{{{}}}", i)))
+            .collect()
+    }
+
+    #[test]
+    fn test_every_locale_has_every_key() {
+        let locales = [Locale::Nb, Locale::En];
+        let all: BTreeSet<_> = locales.iter().flat_map(|l| keys(l.messages())).collect();
+
+        for locale in locales {
+            let own = keys(locale.messages());
+            let missing: Vec<_> = all.difference(&own).collect();
+            assert!(missing.is_empty(), "{:?} mangler {:?}", locale, missing);
+            // Ingen nøkkel skal stå to ganger
+            assert_eq!(keys(locale.messages()).len(), locale.messages().len());
+        }
+    }
+
+    #[test]
+    fn test_translations_use_the_same_placeholders() {
+        for (key, nb) in NB {
+            let en = Locale::En.text(key);
+            assert_eq!(placeholders(nb), placeholders(en), "{}", key);
+        }
+    }
+
+    #[test]
+    fn test_format_replaces_arguments() {
+        assert_eq!(
+            Locale::En.format("error.server", &[&500, &"Internal"]),
+            "Server error (500): Internal"
+        );
+        assert_eq!(
+            Locale::Nb.format("status.transferring", &[&42]),
+            "Overfører data... (42 bytes)"
+        );
+    }
+
+    #[test]
+    fn test_missing_key_falls_back_to_key() {
+        assert_eq!(Locale::En.text("finnes.ikke"), "finnes.ikke");
+    }
+
+    #[test]
+    fn test_locale_from_language() {
+        assert_eq!(Locale::from_language("nb"), Locale::Nb);
+        assert_eq!(Locale::from_language("nn"), Locale::Nb);
+        assert_eq!(Locale::from_language("en"), Locale::En);
+        assert_eq!(Locale::from_language("de"), Locale::En);
+        assert_eq!(Locale::from_code("nb_NO.UTF-8"), Locale::Nb);
+        assert_eq!(Locale::from_code("en-US"), Locale::En);
+    }
+}
@@ -0,0 +1,460 @@
+//! Lokale mapper som små nettsteder
+//!
+//! En mappe med `bare.toml`, eller en `index.md` med `nav:` i front matter,
+//! er roten i et lite nettsted. Sidene i `nav` får en navigasjonslinje med
+//! forrige, innhold og neste over innholdet, og mappefortegnelsen viser dem
+//! først, i oppgitt rekkefølge og med titlene deres.
+//!
+//! `bare.toml`:
+//!
+//! ```toml
+//! title = "Hagenotater"
+//! nav = ["index.md", { path = "planting.md", title = "Planting" }, "stell.md"]
+//! ```
+//!
+//! Front matter i `index.md` støtter `title:` og `nav:` som liste, enten på
+//! én linje (`nav: [index.md, planting.md]`) eller med `- ` på hver linje.
+
+use crate::local_files::DirectoryEntry;
+use crate::markdown;
+use log::warn;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Manifestet som gjør en mappe til et nettsted
+pub const MANIFEST_FILE: &str = "bare.toml";
+
+/// Forsiden, som også kan bære manifestet i front matter
+const INDEX_FILE: &str = "index.md";
+
+/// Feil i et manifest
+#[derive(Debug, Error)]
+pub enum LocalSiteError {
+    #[error("Ugyldig {MANIFEST_FILE}: {0}")]
+    Manifest(#[from] toml::de::Error),
+}
+
+/// En side i `nav`, med eller uten egen tittel
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum NavEntry {
+    Path(String),
+    Titled { path: String, title: String },
+}
+
+/// Manifestet slik det står i filen
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct SiteManifest {
+    pub title: Option<String>,
+    #[serde(default)]
+    pub nav: Vec<NavEntry>,
+}
+
+impl SiteManifest {
+    /// Les `bare.toml`
+    pub fn from_toml(content: &str) -> Result<Self, LocalSiteError> {
+        Ok(toml::from_str(content)?)
+    }
+
+    /// Les front matter i `index.md`
+    ///
+    /// # Returns
+    /// None hvis filen ikke har front matter med `nav:`
+    pub fn from_front_matter(content: &str) -> Option<Self> {
+        let block = front_matter(content)?;
+        let mut manifest = Self::default();
+        let mut in_nav_list = false;
+        for line in block.lines() {
+            if in_nav_list {
+                if let Some(item) = line.trim_start().strip_prefix("- ") {
+                    manifest.nav.push(NavEntry::Path(unquote(item).to_string()));
+                    continue;
+                }
+                in_nav_list = false;
+            }
+            if let Some(value) = line.strip_prefix("title:") {
+                manifest.title = Some(unquote(value).to_string()).filter(|t| !t.is_empty());
+            } else if let Some(value) = line.strip_prefix("nav:") {
+                let value = value.trim();
+                match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+                    Some(items) => manifest.nav.extend(
+                        items
+                            .split(',')
+                            .map(unquote)
+                            .filter(|item| !item.is_empty())
+                            .map(|item| NavEntry::Path(item.to_string())),
+                    ),
+                    None => in_nav_list = value.is_empty(),
+                }
+            }
+        }
+        (!manifest.nav.is_empty()).then_some(manifest)
+    }
+}
+
+/// En side i nettstedet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SitePage {
+    /// Kanonisk sti til filen
+    pub path: PathBuf,
+    /// Tittelen fra manifestet, sidens første overskrift eller filnavnet
+    pub title: String,
+}
+
+/// Et lokalt nettsted med sidene i `nav`-rekkefølge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalSite {
+    /// Mappen manifestet ligger i
+    pub root: PathBuf,
+    pub title: String,
+    pub pages: Vec<SitePage>,
+}
+
+impl LocalSite {
+    /// Finn nettstedet en fil eller mappe hører til
+    ///
+    /// Går oppover fra stien til nærmeste mappe med `bare.toml` eller en
+    /// `index.md` med `nav:`. Et ugyldig `bare.toml` logges og hoppes over.
+    pub fn find(path: &Path) -> Option<Self> {
+        let path = path.canonicalize().ok()?;
+        let start = if path.is_dir() {
+            path.as_path()
+        } else {
+            path.parent()?
+        };
+        start.ancestors().find_map(Self::load)
+    }
+
+    /// Les nettstedet i en mappe, hvis mappen har et manifest
+    fn load(dir: &Path) -> Option<Self> {
+        let manifest = match fs::read_to_string(dir.join(MANIFEST_FILE)) {
+            Ok(content) => match SiteManifest::from_toml(&content) {
+                Ok(manifest) => manifest,
+                Err(e) => {
+                    warn!("{} i {}", e, dir.display());
+                    return None;
+                }
+            },
+            Err(_) => {
+                let index = fs::read_to_string(dir.join(INDEX_FILE)).ok()?;
+                SiteManifest::from_front_matter(&index)?
+            }
+        };
+        Some(Self::from_manifest(dir, manifest))
+    }
+
+    /// Bygg nettstedet fra et manifest
+    ///
+    /// Sider som ikke finnes, ikke er filer eller ligger utenfor roten tas
+    /// ikke med.
+    pub fn from_manifest(root: &Path, manifest: SiteManifest) -> Self {
+        let pages = manifest
+            .nav
+            .into_iter()
+            .filter_map(|entry| {
+                let (path, title) = match entry {
+                    NavEntry::Path(path) => (path, None),
+                    NavEntry::Titled { path, title } => (path, Some(title)),
+                };
+                let path = root.join(path).canonicalize().ok()?;
+                if !path.starts_with(root) || !path.is_file() {
+                    return None;
+                }
+                let title = title.or_else(|| page_title(&path))?;
+                Some(SitePage { path, title })
+            })
+            .collect();
+        let title = manifest.title.unwrap_or_else(|| {
+            root.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| root.display().to_string())
+        });
+        Self {
+            root: root.to_path_buf(),
+            title,
+            pages,
+        }
+    }
+
+    /// Navigasjonslinjen over en side, som markdown
+    ///
+    /// Sider i `nav` får lenker til forrige og neste side; alle sider får
+    /// lenke til forsiden, unntatt forsiden selv.
+    ///
+    /// # Arguments
+    /// * `path` - Kanonisk sti til siden som vises
+    pub fn nav_markdown(&self, path: &Path) -> Option<String> {
+        let position = self.pages.iter().position(|page| page.path == path);
+        let mut links = Vec::new();
+        if let Some(previous) = position
+            .and_then(|i| i.checked_sub(1))
+            .and_then(|i| self.pages.get(i))
+        {
+            links.push(page_link("← ", &previous.title, "", &previous.path)?);
+        }
+        let index = self.root.join(INDEX_FILE);
+        if path != index {
+            let home = if index.is_file() {
+                page_link("⌂ ", &self.title, "", &index)?
+            } else {
+                let url = url::Url::from_directory_path(&self.root).ok()?;
+                format!("[⌂ {}](<{}>)", markdown::escape_markdown(&self.title), url)
+            };
+            links.push(home);
+        }
+        if let Some(next) = position.and_then(|i| self.pages.get(i + 1)) {
+            links.push(page_link("", &next.title, " →", &next.path)?);
+        }
+        (!links.is_empty()).then(|| links.join(" · "))
+    }
+
+    /// Sorter en mappefortegnelse etter `nav` og gi sidene titler
+    ///
+    /// Sidene i `nav` kommer først i oppgitt rekkefølge; resten beholder
+    /// rekkefølgen de hadde.
+    ///
+    /// # Arguments
+    /// * `dir` - Kanonisk sti til mappen som listes
+    /// * `entries` - Fra `local_files::list_directory`
+    pub fn order_entries(&self, dir: &Path, entries: &mut [DirectoryEntry]) {
+        for entry in entries.iter_mut() {
+            let path = dir.join(&entry.name);
+            if let Some(page) = self.pages.iter().find(|page| page.path == path) {
+                entry.title = Some(page.title.clone());
+            }
+        }
+        entries.sort_by_key(|entry| {
+            let path = dir.join(&entry.name);
+            self.pages
+                .iter()
+                .position(|page| page.path == path)
+                .unwrap_or(usize::MAX)
+        });
+    }
+}
+
+/// Innholdet uten front matter, f.eks. for `index.md` under en fortegnelse
+pub fn strip_front_matter(content: &str) -> &str {
+    front_matter_bounds(content).map_or(content, |(_, end)| &content[end..])
+}
+
+/// Teksten mellom `---`-linjene øverst i filen
+fn front_matter(content: &str) -> Option<&str> {
+    front_matter_bounds(content).map(|(block, _)| block)
+}
+
+/// Blokken og hvor innholdet etter den begynner
+fn front_matter_bounds(content: &str) -> Option<(&str, usize)> {
+    let rest = content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))?;
+    let start = content.len() - rest.len();
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            let block = &rest[..offset];
+            return Some((block, start + offset + line.len()));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Fjern mellomrom og anførselstegn rundt en verdi
+fn unquote(value: &str) -> &str {
+    value.trim().trim_matches(|c| c == '"' || c == '\'')
+}
+
+/// Sidens første overskrift, ellers filnavnet uten endelse
+fn page_title(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| markdown::extract_title(&content))
+        .or_else(|| Some(path.file_stem()?.to_string_lossy().into_owned()))
+}
+
+/// Markdown-lenke til en side i nettstedet
+fn page_link(before: &str, title: &str, after: &str, path: &Path) -> Option<String> {
+    let url = url::Url::from_file_path(path).ok()?;
+    Some(format!(
+        "[{}{}{}](<{}>)",
+        before,
+        markdown::escape_markdown(title),
+        after,
+        url
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::local_files;
+
+    /// Et nettsted med tre sider: forside, planting og stell
+    fn fixture() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let files = [
+            (
+                MANIFEST_FILE,
+                include_str!("../fixtures/localsite/bare.toml"),
+            ),
+            (INDEX_FILE, include_str!("../fixtures/localsite/index.md")),
+            (
+                "planting.md",
+                include_str!("../fixtures/localsite/planting.md"),
+            ),
+            ("stell.md", include_str!("../fixtures/localsite/stell.md")),
+        ];
+        for (name, content) in files {
+            fs::write(dir.path().join(name), content).unwrap();
+        }
+        dir
+    }
+
+    fn url(path: &Path) -> String {
+        url::Url::from_file_path(path).unwrap().to_string()
+    }
+
+    #[test]
+    fn test_find_walks_up_to_manifest() {
+        let dir = fixture();
+        let root = dir.path().canonicalize().unwrap();
+        fs::create_dir(root.join("utkast")).unwrap();
+        fs::write(root.join("utkast/ide.md"), "# Ide").unwrap();
+
+        let site = LocalSite::find(&root.join("utkast/ide.md")).unwrap();
+        assert_eq!(site.root, root);
+        assert_eq!(site.title, "Hagenotater");
+        let titles: Vec<&str> = site.pages.iter().map(|p| p.title.as_str()).collect();
+        // Tittel fra manifestet, så fra overskriften; den manglende siden
+        // og siden utenfor roten er borte
+        assert_eq!(
+            titles,
+            vec!["Hagenotater", "Når og hva vi planter", "Stell av bedene"]
+        );
+
+        let outside = tempfile::tempdir().unwrap();
+        fs::write(outside.path().join("løs.md"), "# Løs").unwrap();
+        assert!(LocalSite::find(&outside.path().join("løs.md")).is_none());
+    }
+
+    #[test]
+    fn test_nav_markdown_links_previous_index_and_next() {
+        let dir = fixture();
+        let root = dir.path().canonicalize().unwrap();
+        let site = LocalSite::find(&root).unwrap();
+        let (index, planting, stell) = (
+            root.join(INDEX_FILE),
+            root.join("planting.md"),
+            root.join("stell.md"),
+        );
+
+        assert_eq!(
+            site.nav_markdown(&index).unwrap(),
+            format!("[Når og hva vi planter →](<{}>)", url(&planting))
+        );
+        assert_eq!(
+            site.nav_markdown(&planting).unwrap(),
+            format!(
+                "[← Hagenotater](<{}>) · [⌂ Hagenotater](<{}>) · [Stell av bedene →](<{}>)",
+                url(&index),
+                url(&index),
+                url(&stell)
+            )
+        );
+        assert_eq!(
+            site.nav_markdown(&stell).unwrap(),
+            format!(
+                "[← Når og hva vi planter](<{}>) · [⌂ Hagenotater](<{}>)",
+                url(&planting),
+                url(&index)
+            )
+        );
+
+        // En side utenfor nav får bare lenke hjem
+        fs::write(root.join("annet.md"), "# Annet").unwrap();
+        assert_eq!(
+            site.nav_markdown(&root.join("annet.md")).unwrap(),
+            format!("[⌂ Hagenotater](<{}>)", url(&index))
+        );
+    }
+
+    #[test]
+    fn test_order_entries_follows_nav() {
+        let dir = fixture();
+        let root = dir.path().canonicalize().unwrap();
+        fs::write(root.join("arkiv.md"), "# Arkiv").unwrap();
+        fs::create_dir(root.join("bilder")).unwrap();
+        let site = LocalSite::find(&root).unwrap();
+
+        let mut entries = local_files::list_directory(&root, false).unwrap();
+        site.order_entries(&root, &mut entries);
+        let listed: Vec<(&str, Option<&str>)> = entries
+            .iter()
+            .map(|e| (e.name.as_str(), e.title.as_deref()))
+            .collect();
+        assert_eq!(
+            listed,
+            vec![
+                ("index.md", Some("Hagenotater")),
+                ("planting.md", Some("Når og hva vi planter")),
+                ("stell.md", Some("Stell av bedene")),
+                ("bilder", None),
+                ("arkiv.md", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_front_matter_manifest() {
+        let flow = "---\ntitle: \"Notater\"\nnav: [index.md, 'b.md', c.md]\n---\n# Forside\n";
+        let manifest = SiteManifest::from_front_matter(flow).unwrap();
+        assert_eq!(manifest.title.as_deref(), Some("Notater"));
+        assert_eq!(
+            manifest.nav,
+            vec![
+                NavEntry::Path("index.md".into()),
+                NavEntry::Path("b.md".into()),
+                NavEntry::Path("c.md".into()),
+            ]
+        );
+        assert_eq!(strip_front_matter(flow), "# Forside\n");
+
+        let block = "---\nnav:\n  - a.md\n  - \"b.md\"\ntitle: X\n---\n";
+        let manifest = SiteManifest::from_front_matter(block).unwrap();
+        assert_eq!(manifest.nav.len(), 2);
+        assert_eq!(manifest.title.as_deref(), Some("X"));
+
+        // Front matter uten nav, eller ingen front matter, er ikke et nettsted
+        assert!(SiteManifest::from_front_matter("---\ntitle: X\n---\n").is_none());
+        assert!(SiteManifest::from_front_matter("# Bare tekst\n").is_none());
+        assert_eq!(strip_front_matter("# Bare tekst\n"), "# Bare tekst\n");
+    }
+
+    #[test]
+    fn test_index_front_matter_makes_a_site() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        fs::write(
+            root.join(INDEX_FILE),
+            "---\nnav: [index.md, b.md, a.md]\n---\n# Start\n",
+        )
+        .unwrap();
+        fs::write(root.join("a.md"), "# A").unwrap();
+        fs::write(root.join("b.md"), "Ingen overskrift").unwrap();
+
+        let site = LocalSite::find(&root.join("a.md")).unwrap();
+        let titles: Vec<&str> = site.pages.iter().map(|p| p.title.as_str()).collect();
+        assert_eq!(titles, vec!["Start", "b", "A"]);
+    }
+
+    #[test]
+    fn test_invalid_manifest_is_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(MANIFEST_FILE), "nav = [").unwrap();
+        assert!(SiteManifest::from_toml("nav = [").is_err());
+        assert!(LocalSite::find(dir.path()).is_none());
+    }
+}
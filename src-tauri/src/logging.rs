@@ -0,0 +1,301 @@
+//! Logging til stderr og til en fil i config-mappen
+//!
+//! En installert app har ingen terminal, så uten filen forsvinner alt som
+//! logges. Filen roteres når den blir for stor, og bare noen få eldre
+//! generasjoner beholdes. Spørrestrenger i adresser fjernes før noe skrives,
+//! siden de ofte inneholder søkeord og tokens.
+//!
+//! Nivået følger innstillingen `log_level` og kan endres mens appen kjører.
+//! `RUST_LOG` kan i tillegg begrense hva som skrives til stderr.
+
+use crate::settings::LogLevel;
+use log::{LevelFilter, Log, Metadata, Record};
+use parking_lot::Mutex;
+use regex::Regex;
+use std::borrow::Cow;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+/// Største loggfil før den roteres
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Antall eldre loggfiler som beholdes (`bare.log.1`, `bare.log.2`, ...)
+const GENERATIONS: usize = 3;
+
+/// Adresse med spørrestreng; første gruppe er alt før `?`
+static URL_QUERY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\b([a-zA-Z][a-zA-Z0-9+.-]*://[^\s?#"'<>]*)\?[^\s#"'<>]*"#).unwrap()
+});
+
+/// Fjern spørrestrenger fra adresser i en loggmelding
+///
+/// `https://a.example/søk?q=hemmelig#treff` blir `https://a.example/søk?…#treff`.
+pub fn redact_urls(message: &str) -> Cow<'_, str> {
+    URL_QUERY.replace_all(message, "$1?…")
+}
+
+/// Loggfil som roteres når den blir for stor
+#[derive(Debug)]
+pub struct RotatingFile {
+    path: PathBuf,
+    max_bytes: u64,
+    generations: usize,
+    file: File,
+    written: u64,
+}
+
+impl RotatingFile {
+    /// Åpne loggfilen for å skrive på slutten av den
+    ///
+    /// # Arguments
+    /// * `path` - Loggfilen; mappen opprettes hvis den mangler
+    /// * `max_bytes` - Størrelsen filen roteres ved
+    /// * `generations` - Antall eldre filer som beholdes
+    pub fn open(path: &Path, max_bytes: u64, generations: usize) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            path: path.to_path_buf(),
+            max_bytes,
+            generations,
+            file,
+            written,
+        })
+    }
+
+    /// Skriv en linje, og roter først hvis den ikke får plass
+    pub fn write_line(&mut self, line: &str) -> io::Result<()> {
+        let size = line.len() as u64 + 1;
+        if self.written > 0 && self.written + size > self.max_bytes {
+            self.rotate()?;
+        }
+        writeln!(self.file, "{}", line)?;
+        self.written += size;
+        Ok(())
+    }
+
+    /// Flytt hver generasjon ett hakk ned og begynn på en tom fil
+    fn rotate(&mut self) -> io::Result<()> {
+        if self.generations == 0 {
+            self.file = File::create(&self.path)?;
+            self.written = 0;
+            return Ok(());
+        }
+        for generation in (1..self.generations).rev() {
+            let from = generation_path(&self.path, generation);
+            if from.exists() {
+                fs::rename(&from, generation_path(&self.path, generation + 1))?;
+            }
+        }
+        fs::rename(&self.path, generation_path(&self.path, 1))?;
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+/// Stien til en eldre generasjon av loggfilen
+fn generation_path(path: &Path, generation: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".{}", generation));
+    PathBuf::from(name)
+}
+
+/// De siste linjene som er logget, eldste først
+///
+/// Leser fra forrige generasjon også hvis filen nettopp er rotert.
+///
+/// # Arguments
+/// * `path` - Loggfilen
+/// * `lines` - Maksimalt antall linjer
+pub fn recent_lines(path: &Path, lines: usize) -> io::Result<Vec<String>> {
+    let mut recent = Vec::new();
+    for file in [path.to_path_buf(), generation_path(path, 1)] {
+        let content = match fs::read_to_string(&file) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        let missing = lines - recent.len();
+        let mut older: Vec<String> = content
+            .lines()
+            .rev()
+            .take(missing)
+            .map(String::from)
+            .collect();
+        older.reverse();
+        older.append(&mut recent);
+        recent = older;
+        if recent.len() == lines {
+            break;
+        }
+    }
+    Ok(recent)
+}
+
+/// Skriver hver melding til både stderr og loggfilen
+struct TeeLogger {
+    stderr: env_logger::Logger,
+    file: Mutex<Option<RotatingFile>>,
+}
+
+impl Log for TeeLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let message = record.args().to_string();
+        let message = redact_urls(&message);
+        self.stderr.log(
+            &Record::builder()
+                .args(format_args!("{}", message))
+                .metadata(record.metadata().clone())
+                .module_path(record.module_path())
+                .file(record.file())
+                .line(record.line())
+                .build(),
+        );
+
+        let mut file = self.file.lock();
+        if let Some(log_file) = file.as_mut() {
+            let line = format!(
+                "{} {:<5} [{}] {}",
+                chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+                record.level(),
+                record.target(),
+                message
+            );
+            if let Err(e) = log_file.write_line(&line) {
+                // Kan ikke logges, så skriv til stderr og slutt å prøve
+                eprintln!("Kunne ikke skrive til loggfilen: {}", e);
+                *file = None;
+            }
+        }
+    }
+
+    fn flush(&self) {
+        self.stderr.flush();
+        if let Some(log_file) = self.file.lock().as_mut() {
+            let _ = log_file.file.flush();
+        }
+    }
+}
+
+/// Start loggingen
+///
+/// Kan ikke loggfilen åpnes, logges det bare til stderr.
+///
+/// # Arguments
+/// * `path` - Loggfilen
+pub fn init(path: &Path) {
+    let stderr = env_logger::Builder::new()
+        .filter_level(LevelFilter::Trace)
+        .parse_env("RUST_LOG")
+        .build();
+    let file = RotatingFile::open(path, MAX_FILE_BYTES, GENERATIONS)
+        .map_err(|e| eprintln!("Kunne ikke åpne loggfilen {}: {}", path.display(), e))
+        .ok();
+    let logger = TeeLogger {
+        stderr,
+        file: Mutex::new(file),
+    };
+    if log::set_boxed_logger(Box::new(logger)).is_ok() {
+        set_level(LogLevel::default());
+    }
+}
+
+/// Endre hvor mye som logges
+pub fn set_level(level: LogLevel) {
+    log::set_max_level(level.filter());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_urls() {
+        assert_eq!(
+            redact_urls("Henter https://a.example/søk?q=hemmelig&token=abc#treff"),
+            "Henter https://a.example/søk?…#treff"
+        );
+        assert_eq!(
+            redact_urls("gemini://b.example/input?svar (status 10)"),
+            "gemini://b.example/input?… (status 10)"
+        );
+        assert_eq!(
+            redact_urls("\"http://c.example/?a=1\" og http://d.example/?b=2"),
+            "\"http://c.example/?…\" og http://d.example/?…"
+        );
+        // Uten spørrestreng er meldingen uendret
+        assert!(matches!(
+            redact_urls("Lastet https://a.example/side"),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn test_rotation_keeps_generations() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs").join("bare.log");
+        let mut file = RotatingFile::open(&path, 20, 2).unwrap();
+
+        // Hver linje er 10 bytes med linjeskift, så to får plass per fil
+        for i in 0..7 {
+            file.write_line(&format!("linje {:03}", i)).unwrap();
+        }
+
+        let read = |path: &Path| fs::read_to_string(path).unwrap();
+        assert_eq!(read(&path), "linje 006\n");
+        assert_eq!(read(&generation_path(&path, 1)), "linje 004\nlinje 005\n");
+        assert_eq!(read(&generation_path(&path, 2)), "linje 002\nlinje 003\n");
+        // Den eldste generasjonen er slettet
+        assert!(!generation_path(&path, 3).exists());
+    }
+
+    #[test]
+    fn test_reopen_continues_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bare.log");
+        RotatingFile::open(&path, 20, 1)
+            .unwrap()
+            .write_line("linje 000")
+            .unwrap();
+
+        let mut file = RotatingFile::open(&path, 20, 1).unwrap();
+        file.write_line("linje 001").unwrap();
+        file.write_line("linje 002").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "linje 002\n");
+    }
+
+    #[test]
+    fn test_recent_lines_spans_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bare.log");
+        let mut file = RotatingFile::open(&path, 30, 2).unwrap();
+        for i in 0..5 {
+            file.write_line(&format!("linje {:03}", i)).unwrap();
+        }
+
+        assert_eq!(recent_lines(&path, 2).unwrap(), ["linje 003", "linje 004"]);
+        assert_eq!(
+            recent_lines(&path, 4).unwrap(),
+            ["linje 001", "linje 002", "linje 003", "linje 004"]
+        );
+        assert!(recent_lines(&dir.path().join("mangler.log"), 5)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -2,30 +2,590 @@
 //!
 //! Bruker pulldown-cmark for å konvertere markdown til HTML.
 
-use pulldown_cmark::{html, Options, Parser};
+use crate::locale::Locale;
+use crate::render_cache::{self, RenderCache};
+use crate::settings::PlainText;
+use base64::Engine;
+use parking_lot::Mutex;
+use pulldown_cmark::{
+    html, BlockQuoteKind, CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-/// Rendrer markdown-innhold til HTML
+/// Maksimal størrelse på lokale bilder som bygges inn som data-URI
+const MAX_INLINE_IMAGE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Starten på en lokal lenke som peker ut av `RenderOptions::local_root`
+const OUTSIDE_ROOT_LINK: &str = "<a class=\"outside-root\">";
+
+/// Utvidelsespunkt for innebygd rendering av diagrammer
 ///
-/// # Arguments
-/// * `content` - Markdown-tekst som skal konverteres
+/// Bare kjører ikke JavaScript-baserte renderere. En native renderer kan
+/// implementere denne og settes i `RenderOptions::diagram_renderer`.
+pub trait DiagramRenderer: std::fmt::Debug + Send + Sync {
+    /// Rendrer diagramkilden til HTML (f.eks. inline SVG)
+    ///
+    /// Returnerer None for å falle tilbake til sammenfoldet kildevisning.
+    fn render(&self, language: &str, source: &str) -> Option<String>;
+}
+
+/// Innstillinger for rendering av markdown
 ///
-/// # Returns
-/// HTML-representasjon av markdown-innholdet
-pub fn render(content: &str) -> String {
+/// Serialiseres som en del av nøkkelen i `render_cache`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RenderOptions {
+    /// Adressen dokumentet ble lastet fra (http(s)-URL eller lokal filsti),
+    /// brukt til å løse relative bildeadresser
+    pub base_url: Option<String>,
+    /// Kodeblokk-språk som behandles som diagrammer (f.eks. "mermaid")
+    pub diagram_languages: Vec<String>,
+    /// Valgfri renderer for diagramblokker
+    #[serde(skip)]
+    pub diagram_renderer: Option<Arc<dyn DiagramRenderer>>,
+    /// Vis bilder som alternativ tekst i stedet for å laste dem
+    pub strip_images: bool,
+    /// Vis bare bilder fra nettet som alternativ tekst; lokale bilder vises
+    pub block_remote_images: bool,
+    /// Mappen lenker og bilder i lokale dokumenter må holde seg innenfor
+    ///
+    /// Lenker som peker ut av mappen vises som tekst uten adresse, og bilder
+    /// utenfor bygges ikke inn.
+    pub local_root: Option<PathBuf>,
+    /// Største kilde som rendres; lengre kilder kuttes, se `truncate_source`
+    pub max_input_bytes: Option<usize>,
+    /// Språket for tekster Bare legger inn i dokumentet, f.eks. varseltitler
+    pub locale: Locale,
+    /// Lageret for rendrede sider; None rendrer alltid på nytt
+    #[serde(skip)]
+    pub cache: Option<Arc<Mutex<RenderCache>>>,
+}
+
+impl RenderOptions {
+    /// Opsjoner for et dokument lastet fra gitt adresse
+    pub fn with_base(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: Some(base_url.into()),
+            ..Default::default()
+        }
+    }
+}
+
+/// Fremdrift i dokumentets oppgavelister
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskSummary {
+    /// Antall oppgaver totalt
+    pub total: usize,
+    /// Antall avkryssede oppgaver
+    pub done: usize,
+}
+
+/// Resultat fra rendering av markdown
+#[derive(Debug, Clone)]
+pub struct RenderOutput {
+    /// HTML-representasjon av dokumentet
+    pub html: String,
+    /// Oppgavestatus, None hvis dokumentet ikke har oppgavelister
+    pub tasks: Option<TaskSummary>,
+    /// Antall diagramblokker som ble vist som kilde i stedet for rendret
+    pub skipped_diagrams: usize,
+    /// Om kilden var for stor og bare begynnelsen ble rendret
+    pub truncated: bool,
+}
+
+/// Opphavet til et dokument, for å løse relative bildeadresser
+enum DocumentBase {
+    /// Dokument hentet over http(s)
+    Remote(url::Url),
+    /// Lokal fil; inneholder mappen filen ligger i
+    Local(PathBuf),
+}
+
+/// En overskrift i dokumentets disposisjon
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Heading {
+    /// Overskriftsnivå (1–6)
+    pub level: u8,
+    /// Overskriftsteksten uten inline-formatering
+    pub text: String,
+    /// Unik fragment-id (eksplisitt `{#id}` eller generert slug)
+    pub id: String,
+}
+
+/// En del av dokumentet, fra en overskrift frem til neste
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section<'a> {
+    /// Indeks i `extract_headings` for overskriften som starter seksjonen
+    pub heading_index: Option<usize>,
+    /// Markdown-kilden for seksjonen, inkludert overskriften
+    pub source: &'a str,
+}
+
+/// Felles parser-opsjoner for rendering og analyse av markdown
+fn parser_options() -> Options {
     let mut options = Options::empty();
     options.insert(Options::ENABLE_TABLES);
     options.insert(Options::ENABLE_STRIKETHROUGH);
     options.insert(Options::ENABLE_TASKLISTS);
     options.insert(Options::ENABLE_HEADING_ATTRIBUTES);
+    options.insert(Options::ENABLE_YAML_STYLE_METADATA_BLOCKS);
+    options.insert(Options::ENABLE_GFM);
+    options
+}
+
+/// Rendrer markdown-innhold til HTML med gitte opsjoner
+///
+/// Alle overskrifter får en unik id (se `extract_headings`). Teller
+/// samtidig avkryssede og åpne oppgaver (`- [ ]`/`- [x]`), også i
+/// nestede lister og sitater. Kodeblokker telles ikke. Kodeblokker med et
+/// språk fra `diagram_languages` vises sammenfoldet, med mindre en
+/// `diagram_renderer` kan rendre dem.
+///
+/// # Arguments
+/// * `content` - Markdown-tekst som skal konverteres
+/// * `options` - Renderingsopsjoner (f.eks. dokumentets adresse)
+///
+/// Sider som er rendret med de samme opsjonene før hentes fra `cache`,
+/// hvis opsjonene har et lager. Lokale dokumenter rendres alltid på nytt, siden bildene
+/// deres leses fra disk. Kilder over `max_input_bytes` kuttes, og en
+/// merknad legges til etter det som ble rendret.
+///
+/// # Returns
+/// RenderOutput med HTML og oppgavestatus
+pub fn render_with_options(content: &str, options: &RenderOptions) -> RenderOutput {
+    let truncated = options
+        .max_input_bytes
+        .and_then(|max| truncate_source(content, max));
+    let content = truncated.unwrap_or(content);
+    let base = options.base_url.as_deref().and_then(document_base);
+    let mut output = match (&options.cache, &base) {
+        (Some(cache), Some(DocumentBase::Remote(_)) | None) => {
+            let key = render_cache::cache_key(content, options);
+            render_cache::get_or_render(cache, key, || render_uncached(content, options, base))
+        }
+        _ => render_uncached(content, options, base),
+    };
+    if truncated.is_some() {
+        output.truncated = true;
+        output.html.push_str(&format!(
+            "<p class=\"truncated-notice\" role=\"note\">{}</p>\n",
+            options.locale.text("render.truncated")
+        ));
+    }
+    output
+}
+
+/// Kutt en kilde som er større enn `max_bytes`
+///
+/// Kuttet legges etter en hel linje utenfor kodeblokker, slik at en
+/// kodeblokk aldri deles og resten av dokumentet ikke blir kode. Finnes
+/// ingen slik linje innenfor grensen, kuttes det etter siste hele linje,
+/// eller til slutt ved siste tegn som får plass.
+///
+/// # Returns
+/// Begynnelsen av kilden, eller None hvis hele kilden får plass
+pub fn truncate_source(content: &str, max_bytes: usize) -> Option<&str> {
+    if content.len() <= max_bytes {
+        return None;
+    }
+    let mut fence: Option<(char, usize)> = None;
+    let mut safe_end = 0;
+    let mut line_end = 0;
+    for line in content.split_inclusive('\n') {
+        if line_end + line.len() > max_bytes {
+            break;
+        }
+        line_end += line.len();
+        fence = match fence {
+            Some(open) if closes_fence(line, open) => None,
+            Some(open) => Some(open),
+            None => code_fence(line),
+        };
+        if fence.is_none() {
+            safe_end = line_end;
+        }
+    }
+    let end = match (safe_end, line_end) {
+        (0, 0) => (0..=max_bytes)
+            .rev()
+            .find(|&i| content.is_char_boundary(i))
+            .unwrap_or(0),
+        (0, line_end) => line_end,
+        (safe_end, _) => safe_end,
+    };
+    Some(&content[..end])
+}
+
+/// Tegnet og lengden på gjerdet hvis linjen er en kodeblokk-gjerde
+///
+/// Innrykk og sitatmerker foran gjerdet hoppes over, så kodeblokker i
+/// lister og sitater gjenkjennes også.
+fn code_fence(line: &str) -> Option<(char, usize)> {
+    let line = line.trim_start_matches([' ', '\t', '>']);
+    let marker = line.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let length = line.chars().take_while(|c| *c == marker).count();
+    (length >= 3).then_some((marker, length))
+}
+
+/// Om linjen avslutter en kodeblokk åpnet med gitt gjerde
+fn closes_fence(line: &str, (marker, length): (char, usize)) -> bool {
+    code_fence(line).is_some_and(|(m, l)| m == marker && l >= length)
+        && line
+            .trim_start_matches([' ', '\t', '>'])
+            .trim_start_matches(marker)
+            .trim()
+            .is_empty()
+}
 
-    let parser = Parser::new_ext(content, options);
+/// Selve renderingen bak `render_with_options`
+fn render_uncached(
+    content: &str,
+    options: &RenderOptions,
+    base: Option<DocumentBase>,
+) -> RenderOutput {
+    let mut tasks = TaskSummary { total: 0, done: 0 };
+    let mut diagram: Option<(String, String)> = None;
+    let mut skipped_diagrams = 0;
+    let mut heading_ids = extract_headings(content).into_iter().map(|h| h.id);
+    // Om hvert åpne bilde er blokkert, slik at slutt-taggen kan fjernes også
+    let mut open_images: Vec<bool> = Vec::new();
+    // Om hver åpne lenke peker ut av `local_root`
+    let mut open_links: Vec<bool> = Vec::new();
+    let parser = Parser::new_ext(content, parser_options())
+        .map(|event| match event {
+            Event::Start(Tag::Heading {
+                level,
+                id,
+                classes,
+                attrs,
+            }) => Event::Start(Tag::Heading {
+                level,
+                id: heading_ids.next().map(Into::into).or(id),
+                classes,
+                attrs,
+            }),
+            other => other,
+        })
+        .flat_map(|event| match event {
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(ref info))) => {
+                match diagram_language(info, &options.diagram_languages) {
+                    Some(language) => {
+                        diagram = Some((language, String::new()));
+                        Vec::new()
+                    }
+                    None => vec![event],
+                }
+            }
+            Event::Start(Tag::Image { .. }) | Event::End(TagEnd::Image) if options.strip_images => {
+                Vec::new()
+            }
+            Event::Text(text) if diagram.is_some() => {
+                if let Some((_, source)) = diagram.as_mut() {
+                    source.push_str(&text);
+                }
+                Vec::new()
+            }
+            Event::End(TagEnd::CodeBlock) if diagram.is_some() => {
+                let (language, source) = diagram.take().unwrap_or_default();
+                let rendered = options
+                    .diagram_renderer
+                    .as_ref()
+                    .and_then(|renderer| renderer.render(&language, &source));
+                match rendered {
+                    Some(html) => vec![Event::Html(html.into())],
+                    None => {
+                        skipped_diagrams += 1;
+                        collapsed_diagram(language, source, options.locale)
+                    }
+                }
+            }
+            other => vec![other],
+        })
+        .inspect(|event| {
+            if let Event::TaskListMarker(checked) = event {
+                tasks.total += 1;
+                if *checked {
+                    tasks.done += 1;
+                }
+            }
+        })
+        .map(|event| rewrite_admonition(event, options.locale))
+        .map(|event| match &base {
+            Some(base) => rewrite_image_source(event, base, options.local_root.as_deref()),
+            None => event,
+        })
+        .map(|event| match &base {
+            Some(DocumentBase::Local(dir)) => {
+                rewrite_local_link(event, dir, options.local_root.as_deref(), &mut open_links)
+            }
+            _ => event,
+        })
+        .filter(|event| match event {
+            Event::Start(Tag::Image { dest_url, .. }) => {
+                let blocked = options.block_remote_images && is_remote_image(dest_url);
+                open_images.push(blocked);
+                !blocked
+            }
+            Event::End(TagEnd::Image) => !open_images.pop().unwrap_or(false),
+            _ => true,
+        });
     let mut html_output = String::new();
     html::push_html(&mut html_output, parser);
 
-    html_output
+    RenderOutput {
+        html: html_output,
+        tasks: (tasks.total > 0).then_some(tasks),
+        skipped_diagrams,
+        truncated: false,
+    }
+}
+
+/// Finn diagramspråket for en kodeblokk, hvis det er konfigurert
+fn diagram_language(info: &str, languages: &[String]) -> Option<String> {
+    let language = info.split_whitespace().next()?.to_lowercase();
+    languages
+        .iter()
+        .any(|l| l.eq_ignore_ascii_case(&language))
+        .then_some(language)
+}
+
+/// Diagramkilde som kodeblokk inne i et sammenfoldet `<details>`-element
+fn collapsed_diagram(language: String, source: String, locale: Locale) -> Vec<Event<'static>> {
+    vec![
+        Event::Html(
+            format!(
+                "<details class=\"diagram\">\n<summary>{}</summary>\n",
+                locale.format("render.diagram_source", &[&language])
+            )
+            .into(),
+        ),
+        Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(language.into()))),
+        Event::Text(source.into()),
+        Event::End(TagEnd::CodeBlock),
+        Event::Html("</details>\n".into()),
+    ]
+}
+
+/// Tolker dokumentadressen som http(s)-URL eller lokal filsti
+///
+/// Andre protokoller (gemini, gopher) gir None, siden webview ikke kan
+/// laste bilder derfra uansett.
+fn document_base(base_url: &str) -> Option<DocumentBase> {
+    match url::Url::parse(base_url) {
+        Ok(url) if url.scheme() == "http" || url.scheme() == "https" => {
+            Some(DocumentBase::Remote(url))
+        }
+        Ok(url) if url.scheme() == "file" => url
+            .to_file_path()
+            .ok()
+            .and_then(|path| path.parent().map(Path::to_path_buf))
+            .map(DocumentBase::Local),
+        // Windows-stier som "C:\docs" tolkes som en URL med ett-bokstavs skjema
+        Ok(url) if url.scheme().len() > 1 => None,
+        _ => Path::new(base_url)
+            .parent()
+            .map(|dir| DocumentBase::Local(dir.to_path_buf())),
+    }
+}
+
+/// Løser relative bildeadresser mot dokumentets adresse
+fn rewrite_image_source<'a>(
+    event: Event<'a>,
+    base: &DocumentBase,
+    root: Option<&Path>,
+) -> Event<'a> {
+    match event {
+        Event::Start(Tag::Image {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => {
+            let dest_url = resolve_image_source(&dest_url, base, root)
+                .map(Into::into)
+                .unwrap_or(dest_url);
+            Event::Start(Tag::Image {
+                link_type,
+                dest_url,
+                title,
+                id,
+            })
+        }
+        other => other,
+    }
+}
+
+/// Løser relative lenker i et lokalt dokument til file://-adresser
+///
+/// Søskenfiler åpnes da direkte av frontend. Lenker som havner utenfor
+/// `root` byttes ut med et `<a class="outside-root">` uten adresse, slik
+/// at teksten vises men lenken ikke kan følges.
+fn rewrite_local_link<'a>(
+    event: Event<'a>,
+    dir: &Path,
+    root: Option<&Path>,
+    open_links: &mut Vec<bool>,
+) -> Event<'a> {
+    match event {
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => {
+            let resolved = resolve_local_link(&dest_url, dir);
+            let outside = match (&resolved, root) {
+                (Some(url), Some(root)) => !url.to_file_path().is_ok_and(|p| p.starts_with(root)),
+                _ => false,
+            };
+            open_links.push(outside);
+            if outside {
+                return Event::InlineHtml(OUTSIDE_ROOT_LINK.into());
+            }
+            let dest_url = resolved
+                .map(|url| String::from(url).into())
+                .unwrap_or(dest_url);
+            Event::Start(Tag::Link {
+                link_type,
+                dest_url,
+                title,
+                id,
+            })
+        }
+        Event::End(TagEnd::Link) if open_links.pop().unwrap_or(false) => {
+            Event::InlineHtml("</a>".into())
+        }
+        other => other,
+    }
+}
+
+/// Løser én lenke i et lokalt dokument
+///
+/// # Returns
+/// file://-adressen lenken peker på, eller None for fragmenter og lenker
+/// til andre protokoller, som beholdes uendret
+fn resolve_local_link(dest: &str, dir: &Path) -> Option<url::Url> {
+    if dest.is_empty() || dest.starts_with('#') {
+        return None;
+    }
+    match url::Url::parse(dest) {
+        Ok(url) => (url.scheme() == "file").then_some(url),
+        Err(_) => url::Url::from_directory_path(dir).ok()?.join(dest).ok(),
+    }
+}
+
+/// Sjekk om en (allerede løst) bildeadresse hentes fra nettet
+fn is_remote_image(src: &str) -> bool {
+    src.starts_with("//")
+        || url::Url::parse(src).is_ok_and(|url| url.scheme() == "http" || url.scheme() == "https")
+}
+
+/// Løser én bildeadresse
+///
+/// Absolutte adresser (med skjema) og tomme adresser returnerer None og
+/// beholdes uendret. For http(s)-dokumenter blir resultatet en absolutt URL;
+/// lokale bilder bygges inn som data-URI siden webview ikke kan laste filer
+/// direkte fra disk. Bilder utenfor `root` bygges ikke inn.
+fn resolve_image_source(src: &str, base: &DocumentBase, root: Option<&Path>) -> Option<String> {
+    if src.is_empty() || url::Url::parse(src).is_ok() {
+        return None;
+    }
+
+    match base {
+        DocumentBase::Remote(url) => url.join(src).ok().map(String::from),
+        DocumentBase::Local(dir) => {
+            let path = url::Url::from_directory_path(dir)
+                .ok()?
+                .join(src)
+                .ok()?
+                .to_file_path()
+                .ok()?;
+            if root.is_some_and(|root| !is_within(&path, root)) {
+                return None;
+            }
+            inline_local_image(&path)
+        }
+    }
+}
+
+/// Om en fil ligger i en mappe, etter at symbolske lenker er fulgt
+fn is_within(path: &Path, root: &Path) -> bool {
+    match (path.canonicalize(), root.canonicalize()) {
+        (Ok(path), Ok(root)) => path.starts_with(root),
+        _ => false,
+    }
+}
+
+/// Leser et lokalt bilde og returnerer det som data-URI
+fn inline_local_image(path: &Path) -> Option<String> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    let mime = match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "svg" => "image/svg+xml",
+        "bmp" => "image/bmp",
+        "ico" => "image/x-icon",
+        _ => return None,
+    };
+
+    let metadata = fs::metadata(path).ok()?;
+    if !metadata.is_file() || metadata.len() > MAX_INLINE_IMAGE_SIZE {
+        return None;
+    }
+    let bytes = fs::read(path).ok()?;
+
+    Some(format!(
+        "data:{};base64,{}",
+        mime,
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    ))
+}
+
+/// Erstatter GFM-varselblokker (`> [!NOTE]` osv.) med en admonition-wrapper
+///
+/// pulldown-cmark gjenkjenner markøren kun på første linje i sitatet og
+/// fjerner den selv, så vanlige sitater som nevner `[!NOTE]` berøres ikke.
+fn rewrite_admonition(event: Event<'_>, locale: Locale) -> Event<'_> {
+    match event {
+        Event::Start(Tag::BlockQuote(Some(kind))) => {
+            let (class, label) = admonition_style(kind);
+            Event::Html(
+                format!(
+                    "<div class=\"admonition {}\">\n<p class=\"admonition-title\">{}</p>\n",
+                    class,
+                    locale.text(label)
+                )
+                .into(),
+            )
+        }
+        Event::End(TagEnd::BlockQuote(Some(_))) => Event::Html("</div>\n".into()),
+        other => other,
+    }
+}
+
+/// CSS-klasse og nøkkelen til visningsnavnet for en varseltype
+fn admonition_style(kind: BlockQuoteKind) -> (&'static str, &'static str) {
+    match kind {
+        BlockQuoteKind::Note => ("note", "render.admonition_note"),
+        BlockQuoteKind::Tip => ("tip", "render.admonition_tip"),
+        BlockQuoteKind::Important => ("important", "render.admonition_important"),
+        BlockQuoteKind::Warning => ("warning", "render.admonition_warning"),
+        BlockQuoteKind::Caution => ("caution", "render.admonition_caution"),
+    }
 }
 
-/// Ekstraherer tittelen fra markdown-innhold (første H1)
+/// Ekstraherer tittelen fra markdown-innhold
+///
+/// Rekkefølge: `title:` i front matter, deretter første H1, og til slutt
+/// første overskrift på et hvilket som helst nivå. Overskrifter i sitater
+/// ignoreres, og inline-formatering flates ut til ren tekst.
 ///
 /// # Arguments
 /// * `content` - Markdown-tekst
@@ -33,45 +593,471 @@ pub fn render(content: &str) -> String {
 /// # Returns
 /// Tittel hvis funnet, ellers None
 pub fn extract_title(content: &str) -> Option<String> {
-    for line in content.lines() {
-        let trimmed = line.trim();
-        if let Some(title) = trimmed.strip_prefix("# ") {
-            return Some(title.trim().to_string());
+    let mut first_heading: Option<String> = None;
+    let mut current: Option<(HeadingLevel, String)> = None;
+    let mut metadata: Option<String> = None;
+    let mut quote_depth = 0usize;
+
+    for event in Parser::new_ext(content, parser_options()) {
+        match event {
+            Event::Start(Tag::MetadataBlock(_)) => metadata = Some(String::new()),
+            Event::End(TagEnd::MetadataBlock(_)) => {
+                if let Some(title) = metadata.take().as_deref().and_then(front_matter_title) {
+                    return Some(title);
+                }
+            }
+            Event::Start(Tag::BlockQuote(_)) => quote_depth += 1,
+            Event::End(TagEnd::BlockQuote(_)) => quote_depth = quote_depth.saturating_sub(1),
+            Event::Start(Tag::Heading { level, .. }) if quote_depth == 0 => {
+                current = Some((level, String::new()));
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                if let Some((level, text)) = current.take() {
+                    let text = collapse_whitespace(&text);
+                    if text.is_empty() {
+                        continue;
+                    }
+                    if level == HeadingLevel::H1 {
+                        return Some(text);
+                    }
+                    first_heading.get_or_insert(text);
+                }
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(buffer) = metadata.as_mut() {
+                    buffer.push_str(&text);
+                } else if let Some((_, buffer)) = current.as_mut() {
+                    buffer.push_str(&text);
+                }
+            }
+            Event::SoftBreak | Event::HardBreak => {
+                if let Some((_, buffer)) = current.as_mut() {
+                    buffer.push(' ');
+                }
+            }
+            _ => {}
+        }
+    }
+
+    first_heading
+}
+
+/// Ekstraherer alle overskrifter i dokumentrekkefølge
+///
+/// Id-ene er de samme som `render_with_options` skriver ut, etter at
+/// duplikater har fått suffiks.
+///
+/// # Arguments
+/// * `content` - Markdown-tekst
+///
+/// # Returns
+/// Overskrifter med nivå, tekst og fragment-id
+pub fn extract_headings(content: &str) -> Vec<Heading> {
+    heading_spans(content)
+        .into_iter()
+        .map(|(heading, _, _)| heading)
+        .collect()
+}
+
+/// Deler dokumentet i seksjoner ved hver overskrift på toppnivå
+///
+/// Overskrifter inne i sitater og lister starter ingen ny seksjon, siden
+/// kilden da ikke kan deles uten å bryte blokken.
+///
+/// # Arguments
+/// * `content` - Markdown-tekst
+///
+/// # Returns
+/// Seksjoner i dokumentrekkefølge; innhold før første overskrift får
+/// `heading_index: None`
+pub fn split_sections(content: &str) -> Vec<Section<'_>> {
+    let mut sections = Vec::new();
+    let mut start = 0;
+    let mut heading_index = None;
+
+    for (index, (_, offset, top_level)) in heading_spans(content).into_iter().enumerate() {
+        if !top_level {
+            continue;
+        }
+        if offset > start || heading_index.is_some() {
+            sections.push(Section {
+                heading_index,
+                source: &content[start..offset],
+            });
+        }
+        start = offset;
+        heading_index = Some(index);
+    }
+
+    if start < content.len() || heading_index.is_some() {
+        sections.push(Section {
+            heading_index,
+            source: &content[start..],
+        });
+    }
+
+    sections
+}
+
+/// Finn overskrifter med startposisjon i kilden og om de står på toppnivå
+fn heading_spans(content: &str) -> Vec<(Heading, usize, bool)> {
+    let mut spans = Vec::new();
+    let mut current: Option<(u8, Option<String>, usize, bool, String)> = None;
+    let mut container_depth = 0usize;
+
+    for (event, range) in Parser::new_ext(content, parser_options()).into_offset_iter() {
+        match event {
+            Event::Start(Tag::BlockQuote(_)) | Event::Start(Tag::Item) => container_depth += 1,
+            Event::End(TagEnd::BlockQuote(_)) | Event::End(TagEnd::Item) => {
+                container_depth = container_depth.saturating_sub(1);
+            }
+            Event::Start(Tag::Heading { level, id, .. }) => {
+                current = Some((
+                    level as u8,
+                    id.map(|id| id.to_string()),
+                    range.start,
+                    container_depth == 0,
+                    String::new(),
+                ));
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                if let Some((level, explicit, offset, top_level, text)) = current.take() {
+                    let text = collapse_whitespace(&text);
+                    let id = explicit.as_deref().map(sanitize_id).unwrap_or_default();
+                    spans.push((Heading { level, text, id }, offset, top_level));
+                }
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some((.., buffer)) = current.as_mut() {
+                    buffer.push_str(&text);
+                }
+            }
+            Event::SoftBreak | Event::HardBreak => {
+                if let Some((.., buffer)) = current.as_mut() {
+                    buffer.push(' ');
+                }
+            }
+            _ => {}
+        }
+    }
+
+    assign_heading_ids(spans.iter_mut().map(|(heading, ..)| heading));
+    spans
+}
+
+/// Gjør overskrifts-id-ene unike
+///
+/// Eksplisitte id-er (`{#id}`) reserveres først, slik at en generert slug
+/// aldri tar navnet fra en eksplisitt id senere i dokumentet. Duplikater
+/// får suffiks `-1`, `-2` osv. Forventer at `id` er tom for overskrifter
+/// uten eksplisitt id.
+fn assign_heading_ids<'a>(headings: impl Iterator<Item = &'a mut Heading>) {
+    let mut headings: Vec<&mut Heading> = headings.collect();
+    let reserved: HashSet<String> = headings
+        .iter()
+        .filter(|heading| !heading.id.is_empty())
+        .map(|heading| heading.id.clone())
+        .collect();
+    let mut used: HashSet<String> = HashSet::new();
+
+    for heading in headings.iter_mut() {
+        let explicit = !heading.id.is_empty();
+        let base = if explicit {
+            heading.id.clone()
+        } else {
+            let slug = slugify(&heading.text);
+            if slug.is_empty() {
+                "overskrift".to_string()
+            } else {
+                slug
+            }
+        };
+
+        let is_free = |id: &str| !used.contains(id) && (explicit || !reserved.contains(id));
+        let mut id = base.clone();
+        let mut suffix = 0;
+        while !is_free(&id) {
+            suffix += 1;
+            id = format!("{}-{}", base, suffix);
         }
+
+        used.insert(id.clone());
+        heading.id = id;
     }
-    None
+}
+
+/// Fjerner tegn som ikke hører hjemme i en HTML-id
+///
+/// Beholder bokstaver, tall, `-`, `_`, `.` og `:`; mellomrom og annet fjernes.
+fn sanitize_id(id: &str) -> String {
+    id.chars()
+        .filter(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+        .collect()
+}
+
+/// Lager en URL-vennlig slug av overskriftstekst
+///
+/// Små bokstaver, bokstaver og tall beholdes (også æøå), mellomrom og
+/// bindestreker blir én bindestrek, alt annet fjernes.
+fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars() {
+        if c.is_alphanumeric() || c == '_' {
+            slug.extend(c.to_lowercase());
+        } else if (c.is_whitespace() || c == '-') && !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// Gjør ren tekst om til markdown
+///
+/// # Arguments
+/// * `text` - Teksten, f.eks. fra en .txt-fil
+/// * `style` - Kodeblokk med teksten ordrett, eller avsnitt der alle
+///   markdown-tegn er escapet og linjeskift beholdes
+///
+/// # Returns
+/// Markdown som rendres til teksten slik den står
+pub fn plain_text_to_markdown(text: &str, style: PlainText) -> String {
+    match style {
+        PlainText::Preformatted => {
+            // Gjerdet må være lengre enn alle rekker av ` i teksten
+            let longest = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+            let fence = "`".repeat(longest.max(2) + 1);
+            format!("{}\n{}\n{}\n", fence, text.trim_end_matches('\n'), fence)
+        }
+        PlainText::Paragraphs => text
+            .split("\n\n")
+            .map(|paragraph| {
+                paragraph
+                    .lines()
+                    .map(|line| escape_markdown(line.trim()))
+                    .filter(|line| !line.is_empty())
+                    .collect::<Vec<_>>()
+                    .join("\\\n")
+            })
+            .filter(|paragraph| !paragraph.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+    }
+}
+
+/// Escape alle ASCII-tegn som kan ha betydning i markdown
+pub fn escape_markdown(line: &str) -> String {
+    let mut escaped = String::with_capacity(line.len());
+    for c in line.chars() {
+        if c.is_ascii_punctuation() {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Ekstraherer lesbar ren tekst fra markdown-innhold
+///
+/// Brukes til "kopier som ren tekst" og opplesning. Overskrifter og avsnitt
+/// skilles med tomme linjer, listepunkter får "• ", tabellrader blir
+/// "celle — celle" og kodeblokker bevares ordrett.
+///
+/// # Arguments
+/// * `content` - Markdown-tekst
+/// * `include_link_urls` - Legg til URL i parentes etter lenketekst
+///
+/// # Returns
+/// Ren tekst uten markdown- eller HTML-syntaks
+pub fn to_plain_text(content: &str, include_link_urls: bool) -> String {
+    let mut output = String::new();
+    let mut lists: Vec<Option<u64>> = Vec::new();
+    let mut links: Vec<(String, usize)> = Vec::new();
+    let mut row: Vec<String> = Vec::new();
+    let mut cell: Option<String> = None;
+    let mut in_metadata = false;
+    // Hvor i `output` kodeblokkene står, slik at de ikke ryddes som prosa
+    let mut code: Vec<Range<usize>> = Vec::new();
+    let mut code_start: Option<usize> = None;
+
+    for event in Parser::new_ext(content, parser_options()) {
+        match event {
+            Event::Start(Tag::MetadataBlock(_)) => in_metadata = true,
+            Event::End(TagEnd::MetadataBlock(_)) => in_metadata = false,
+            _ if in_metadata => {}
+            Event::End(TagEnd::Paragraph) | Event::End(TagEnd::Heading(_)) => {
+                output.push_str("\n\n");
+            }
+            Event::Start(Tag::CodeBlock(_)) => {
+                end_block(&mut output);
+                code_start = Some(output.len());
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some(start) = code_start.take() {
+                    code.push(start..output.len());
+                }
+                ensure_newline(&mut output);
+                output.push('\n');
+            }
+            Event::Start(Tag::List(start)) => {
+                ensure_newline(&mut output);
+                lists.push(start);
+            }
+            Event::End(TagEnd::List(_)) => {
+                lists.pop();
+                if lists.is_empty() {
+                    end_block(&mut output);
+                }
+            }
+            Event::Start(Tag::Item) => {
+                ensure_newline(&mut output);
+                output.push_str(&"  ".repeat(lists.len().saturating_sub(1)));
+                match lists.last_mut() {
+                    Some(Some(number)) => {
+                        output.push_str(&format!("{}. ", number));
+                        *number += 1;
+                    }
+                    _ => output.push_str("• "),
+                }
+            }
+            Event::End(TagEnd::Item) => ensure_newline(&mut output),
+            Event::TaskListMarker(checked) => {
+                output.push_str(if checked { "[x] " } else { "[ ] " });
+            }
+            Event::Start(Tag::TableHead) | Event::Start(Tag::TableRow) => row.clear(),
+            Event::Start(Tag::TableCell) => cell = Some(String::new()),
+            Event::End(TagEnd::TableCell) => {
+                if let Some(text) = cell.take() {
+                    row.push(collapse_whitespace(&text));
+                }
+            }
+            Event::End(TagEnd::TableHead) | Event::End(TagEnd::TableRow) => {
+                output.push_str(&row.join(" — "));
+                output.push('\n');
+            }
+            Event::End(TagEnd::Table) => end_block(&mut output),
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                let target = cell.as_ref().map_or(output.len(), String::len);
+                links.push((dest_url.to_string(), target));
+            }
+            Event::End(TagEnd::Link) => {
+                if let Some((url, start)) = links.pop() {
+                    let target = cell.as_mut().unwrap_or(&mut output);
+                    let text = target.get(start..).unwrap_or_default();
+                    if include_link_urls && !url.is_empty() && text != url {
+                        target.push_str(&format!(" ({})", url));
+                    }
+                }
+            }
+            Event::Rule => end_block(&mut output),
+            Event::Text(text) | Event::Code(text) => match cell.as_mut() {
+                Some(buffer) => buffer.push_str(&text),
+                None => output.push_str(&text),
+            },
+            Event::SoftBreak => match cell.as_mut() {
+                Some(buffer) => buffer.push(' '),
+                None => output.push(' '),
+            },
+            Event::HardBreak => match cell.as_mut() {
+                Some(buffer) => buffer.push(' '),
+                None => output.push('\n'),
+            },
+            _ => {}
+        }
+    }
+
+    let mut text = String::new();
+    let mut blank_lines = 0;
+    let mut position = 0;
+    for line in output.split_inclusive('\n') {
+        let start = position;
+        position += line.len();
+        let line = line.trim_end_matches('\n');
+        if code.iter().any(|range| range.contains(&start)) {
+            blank_lines = 0;
+            text.push_str(line);
+            text.push('\n');
+            continue;
+        }
+        if line.trim().is_empty() {
+            blank_lines += 1;
+            if blank_lines > 1 {
+                continue;
+            }
+        } else {
+            blank_lines = 0;
+        }
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+
+    text.trim_matches('\n').to_string()
+}
+
+/// Sørg for at teksten slutter med linjeskift
+fn ensure_newline(output: &mut String) {
+    if !output.is_empty() && !output.ends_with('\n') {
+        output.push('\n');
+    }
+}
+
+/// Avslutt en blokk med en tom linje
+fn end_block(output: &mut String) {
+    ensure_newline(output);
+    output.push('\n');
+}
+
+/// Finn `title:` i en YAML front matter-blokk
+fn front_matter_title(metadata: &str) -> Option<String> {
+    metadata.lines().find_map(|line| {
+        let value = line.strip_prefix("title:")?.trim();
+        let value = value.trim_matches(|c| c == '"' || c == '\'');
+        if value.is_empty() {
+            None
+        } else {
+            Some(value.to_string())
+        }
+    })
+}
+
+/// Slå sammen påfølgende whitespace til ett mellomrom
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn render(content: &str) -> RenderOutput {
+        render_with_options(content, &RenderOptions::default())
+    }
+
     #[test]
     fn test_render_heading() {
         let input = "# Hello World";
-        let output = render(input);
-        assert!(output.contains("<h1>"));
+        let output = render(input).html;
+        assert!(output.contains("<h1 id=\"hello-world\">"));
         assert!(output.contains("Hello World"));
     }
 
     #[test]
     fn test_render_bold() {
         let input = "This is **bold** text";
-        let output = render(input);
+        let output = render(input).html;
         assert!(output.contains("<strong>bold</strong>"));
     }
 
     #[test]
     fn test_render_italic() {
         let input = "This is *italic* text";
-        let output = render(input);
+        let output = render(input).html;
         assert!(output.contains("<em>italic</em>"));
     }
 
     #[test]
     fn test_render_link() {
         let input = "[Link](https://example.com)";
-        let output = render(input);
+        let output = render(input).html;
         assert!(output.contains("<a href=\"https://example.com\">"));
         assert!(output.contains("Link</a>"));
     }
@@ -79,7 +1065,7 @@ mod tests {
     #[test]
     fn test_render_list() {
         let input = "- Item 1\n- Item 2\n- Item 3";
-        let output = render(input);
+        let output = render(input).html;
         assert!(output.contains("<ul>"));
         assert!(output.contains("<li>Item 1</li>"));
     }
@@ -87,7 +1073,7 @@ mod tests {
     #[test]
     fn test_render_code_block() {
         let input = "```rust\nfn main() {}\n```";
-        let output = render(input);
+        let output = render(input).html;
         assert!(output.contains("<pre>"));
         assert!(output.contains("<code"));
     }
@@ -95,7 +1081,7 @@ mod tests {
     #[test]
     fn test_render_table() {
         let input = "| A | B |\n|---|---|\n| 1 | 2 |";
-        let output = render(input);
+        let output = render(input).html;
         assert!(output.contains("<table>"));
         assert!(output.contains("<th>"));
     }
@@ -103,7 +1089,7 @@ mod tests {
     #[test]
     fn test_render_task_list() {
         let input = "- [ ] Todo\n- [x] Done";
-        let output = render(input);
+        let output = render(input).html;
         assert!(output.contains("type=\"checkbox\""));
     }
 
@@ -127,4 +1113,516 @@ mod tests {
         let title = extract_title(input);
         assert_eq!(title, Some("Spaced Title".to_string()));
     }
+
+    #[test]
+    fn test_extract_title_skips_code_fence() {
+        let input = "```markdown\n# Hello\n```\n\n# Real Title";
+        assert_eq!(extract_title(input), Some("Real Title".to_string()));
+    }
+
+    #[test]
+    fn test_extract_title_setext() {
+        let input = "Setext Title\n============\n\nContent";
+        assert_eq!(extract_title(input), Some("Setext Title".to_string()));
+    }
+
+    #[test]
+    fn test_extract_title_strips_inline_formatting() {
+        let input = "# **Bold** title with `code` and [a link](https://example.com)";
+        assert_eq!(
+            extract_title(input),
+            Some("Bold title with code and a link".to_string())
+        );
+    }
+
+    #[test]
+    fn test_extract_title_ignores_blockquote() {
+        let input = "> # Quoted\n\n# Actual";
+        assert_eq!(extract_title(input), Some("Actual".to_string()));
+    }
+
+    #[test]
+    fn test_extract_title_falls_back_to_first_heading() {
+        let input = "Intro\n\n## Section\n\n### Subsection";
+        assert_eq!(extract_title(input), Some("Section".to_string()));
+    }
+
+    #[test]
+    fn test_extract_title_prefers_h1_over_earlier_h2() {
+        let input = "## Section\n\n# Main";
+        assert_eq!(extract_title(input), Some("Main".to_string()));
+    }
+
+    #[test]
+    fn test_extract_title_front_matter_wins() {
+        let input = "---\ntitle: \"Fra front matter\"\n---\n\n# Overskrift";
+        assert_eq!(extract_title(input), Some("Fra front matter".to_string()));
+    }
+
+    #[test]
+    fn test_render_hides_front_matter() {
+        let output = render("---\ntitle: Skjult\n---\n\nTekst").html;
+        assert!(!output.contains("Skjult"));
+        assert!(output.contains("Tekst"));
+    }
+
+    #[test]
+    fn test_plain_text_headings_and_paragraphs() {
+        let text = to_plain_text("# Tittel\n\nFørste **avsnitt**.\n\nAndre avsnitt.", false);
+        assert_eq!(text, "Tittel\n\nFørste avsnitt.\n\nAndre avsnitt.");
+    }
+
+    #[test]
+    fn test_plain_text_lists() {
+        let text = to_plain_text("- En\n- To\n  - Nøstet\n\n1. Første\n2. Andre", false);
+        assert_eq!(text, "• En\n• To\n  • Nøstet\n\n1. Første\n2. Andre");
+    }
+
+    #[test]
+    fn test_plain_text_links() {
+        let input = "Se [dokumentasjonen](https://example.com/docs).";
+        assert_eq!(to_plain_text(input, false), "Se dokumentasjonen.");
+        assert_eq!(
+            to_plain_text(input, true),
+            "Se dokumentasjonen (https://example.com/docs)."
+        );
+    }
+
+    #[test]
+    fn test_plain_text_autolink_not_duplicated() {
+        let input = "<https://example.com>";
+        assert_eq!(to_plain_text(input, true), "https://example.com");
+    }
+
+    #[test]
+    fn test_plain_text_code_block_verbatim() {
+        let input = "Før\n\n```rust\nfn main() {\n    println!(\"*hei*\");\n}\n```\n\nEtter";
+        assert_eq!(
+            to_plain_text(input, false),
+            "Før\n\nfn main() {\n    println!(\"*hei*\");\n}\n\nEtter"
+        );
+    }
+
+    #[test]
+    fn test_plain_text_code_block_keeps_blank_lines_and_indentation() {
+        let input = "```\n    innrykk\n\n\n\nslutt\n```\n\nEtter";
+        assert_eq!(
+            to_plain_text(input, false),
+            "    innrykk\n\n\n\nslutt\n\nEtter"
+        );
+    }
+
+    #[test]
+    fn test_plain_text_table() {
+        let input = "| Funksjon | Status |\n|---|---|\n| Rendering | Ferdig |";
+        assert_eq!(
+            to_plain_text(input, false),
+            "Funksjon — Status\nRendering — Ferdig"
+        );
+    }
+
+    #[test]
+    fn test_plain_text_task_list() {
+        let text = to_plain_text("- [x] Ferdig\n- [ ] Gjenstår", false);
+        assert_eq!(text, "• [x] Ferdig\n• [ ] Gjenstår");
+    }
+
+    #[test]
+    fn test_render_labels_follow_locale() {
+        let options = RenderOptions {
+            locale: Locale::En,
+            diagram_languages: vec!["mermaid".to_string()],
+            ..Default::default()
+        };
+        let html = render_with_options(
+            "> [!WARNING]\n> Innhold\n\n```mermaid\ngraph TD\n```",
+            &options,
+        )
+        .html;
+        assert!(html.contains("<p class=\"admonition-title\">Warning</p>"));
+        assert!(html.contains("<summary>Diagram source (mermaid)</summary>"));
+        assert!(!html.contains("Advarsel"));
+        assert!(!html.contains("Diagramkilde"));
+    }
+
+    #[test]
+    fn test_render_admonition_types() {
+        let cases = [
+            ("NOTE", "note", "Merk"),
+            ("TIP", "tip", "Tips"),
+            ("IMPORTANT", "important", "Viktig"),
+            ("WARNING", "warning", "Advarsel"),
+            ("CAUTION", "caution", "Forsiktig"),
+        ];
+        for (marker, class, label) in cases {
+            let output = render(&format!("> [!{}]\n> Innhold", marker)).html;
+            assert!(
+                output.contains(&format!("<div class=\"admonition {}\">", class)),
+                "{}: {}",
+                marker,
+                output
+            );
+            assert!(output.contains(&format!("<p class=\"admonition-title\">{}</p>", label)));
+            assert!(output.contains("<p>Innhold</p>"));
+            assert!(!output.contains("[!"));
+            assert!(!output.contains("<blockquote"));
+        }
+    }
+
+    #[test]
+    fn test_render_admonition_multi_paragraph() {
+        let output = render("> [!WARNING]\n> Første avsnitt.\n>\n> Andre avsnitt.\n\nEtter").html;
+        let start = output.find("<div class=\"admonition warning\">").unwrap();
+        let end = output.find("</div>").unwrap();
+        let inner = &output[start..end];
+        assert!(inner.contains("<p>Første avsnitt.</p>"));
+        assert!(inner.contains("<p>Andre avsnitt.</p>"));
+        assert!(output[end..].contains("<p>Etter</p>"));
+    }
+
+    #[test]
+    fn test_render_blockquote_mentioning_marker_untouched() {
+        let output = render("> Bruk [!NOTE] for å lage en merknad.").html;
+        assert!(output.contains("<blockquote>"));
+        assert!(output.contains("[!NOTE]"));
+        assert!(!output.contains("admonition"));
+    }
+
+    #[test]
+    fn test_extract_headings_slugs_and_explicit_ids() {
+        let headings =
+            extract_headings("# Første kapittel\n\n## Oppsett `cargo` & mer {#oppsett}\n");
+        assert_eq!(headings.len(), 2);
+        assert_eq!(headings[0].level, 1);
+        assert_eq!(headings[0].text, "Første kapittel");
+        assert_eq!(headings[0].id, "første-kapittel");
+        assert_eq!(headings[1].level, 2);
+        assert_eq!(headings[1].id, "oppsett");
+    }
+
+    #[test]
+    fn test_split_sections() {
+        let input = "Innledning\n\n# En\n\nTekst\n\n> ## Sitert\n\n## To\n\nMer";
+        let sections = split_sections(input);
+        assert_eq!(sections.len(), 3);
+        assert_eq!(sections[0].heading_index, None);
+        assert_eq!(sections[0].source, "Innledning\n\n");
+        assert_eq!(sections[1].heading_index, Some(0));
+        assert!(sections[1].source.contains("> ## Sitert"));
+        // Overskriften i sitatet telles i disposisjonen, men deler ikke kilden
+        assert_eq!(sections[2].heading_index, Some(2));
+        assert!(sections[2].source.starts_with("## To"));
+    }
+
+    #[test]
+    fn test_render_relative_image_against_page_url() {
+        let options = RenderOptions::with_base("https://example.com/docs/guide.md");
+        let output = render_with_options("![d](images/arch.png)", &options).html;
+        assert!(output.contains("src=\"https://example.com/docs/images/arch.png\""));
+
+        let output = render_with_options("![d](../logo.svg)", &options).html;
+        assert!(output.contains("src=\"https://example.com/logo.svg\""));
+    }
+
+    #[test]
+    fn test_render_root_relative_image() {
+        let options = RenderOptions::with_base("https://example.com/docs/guide.md");
+        let output = render_with_options("![d](/static/a.png)", &options).html;
+        assert!(output.contains("src=\"https://example.com/static/a.png\""));
+    }
+
+    #[test]
+    fn test_render_absolute_image_untouched() {
+        let options = RenderOptions::with_base("https://example.com/docs/guide.md");
+        let output = render_with_options("![d](https://cdn.example.org/x.png)", &options).html;
+        assert!(output.contains("src=\"https://cdn.example.org/x.png\""));
+
+        let output = render_with_options("![d](data:image/png;base64,AAAA)", &options).html;
+        assert!(output.contains("src=\"data:image/png;base64,AAAA\""));
+    }
+
+    #[test]
+    fn test_render_without_base_leaves_images_alone() {
+        let output = render("![d](images/arch.png)").html;
+        assert!(output.contains("src=\"images/arch.png\""));
+
+        let options = RenderOptions::with_base("gemini://example.com/index.gmi");
+        let output = render_with_options("![d](images/arch.png)", &options).html;
+        assert!(output.contains("src=\"images/arch.png\""));
+    }
+
+    #[test]
+    fn test_render_strip_images_keeps_alt_text() {
+        let options = RenderOptions {
+            strip_images: true,
+            ..RenderOptions::with_base("https://example.com/docs/guide.md")
+        };
+        let output = render_with_options("Se ![arkitektur](arch.png) her", &options).html;
+        assert!(!output.contains("<img"));
+        assert!(output.contains("Se arkitektur her"));
+    }
+
+    #[test]
+    fn test_plain_text_to_markdown() {
+        let text = "# Ikke en overskrift\n*ikke kursiv* ```\n\nAndre avsnitt <b>";
+
+        let preformatted = plain_text_to_markdown(text, PlainText::Preformatted);
+        assert!(preformatted.starts_with("````\n"));
+        let html = render(&preformatted).html;
+        assert!(html.contains("# Ikke en overskrift\n*ikke kursiv* ```"));
+        assert!(html.contains("&lt;b&gt;"));
+
+        let html = render(&plain_text_to_markdown(text, PlainText::Paragraphs)).html;
+        assert_eq!(
+            html,
+            "<p># Ikke en overskrift<br />\n*ikke kursiv* ```</p>\n<p>Andre avsnitt &lt;b&gt;</p>\n"
+        );
+    }
+
+    #[test]
+    fn test_render_block_remote_images() {
+        let markdown = "![nett](https://cdn.example.com/a.png) ![relativ](b.png) ![data](data:image/png;base64,cG5n)";
+        let options = RenderOptions::with_base("https://example.com/docs/guide.md");
+        let output = render_with_options(markdown, &options).html;
+        assert_eq!(output.matches("<img").count(), 3);
+
+        let options = RenderOptions {
+            block_remote_images: true,
+            ..options
+        };
+        let output = render_with_options(markdown, &options).html;
+        // Relative bilder løses til nettadresser og blokkeres også
+        assert_eq!(output.matches("<img").count(), 1);
+        assert!(output.contains("src=\"data:image/png;base64,cG5n\""));
+        assert!(output.contains("nett relativ"));
+    }
+
+    #[test]
+    fn test_render_local_image_as_data_uri() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("bilder")).unwrap();
+        fs::write(dir.path().join("bilder").join("mitt bilde.png"), b"png").unwrap();
+        let document = dir.path().join("notat.md");
+
+        let options = RenderOptions::with_base(document.to_string_lossy());
+        let output = render_with_options("![d](bilder/mitt%20bilde.png)", &options).html;
+        assert!(output.contains("src=\"data:image/png;base64,cG5n\""));
+
+        // Ukjente filer og ikke-bilder beholdes uendret
+        let output = render_with_options("![d](mangler.png)", &options).html;
+        assert!(output.contains("src=\"mangler.png\""));
+        fs::write(dir.path().join("hemmelig.txt"), b"x").unwrap();
+        let output = render_with_options("![d](hemmelig.txt)", &options).html;
+        assert!(output.contains("src=\"hemmelig.txt\""));
+    }
+
+    #[test]
+    fn test_render_local_links_resolve_to_file_urls() {
+        let root = tempfile::tempdir().unwrap();
+        let project = root.path().join("prosjekt");
+        fs::create_dir_all(project.join("img")).unwrap();
+        fs::write(project.join("img").join("diagram.png"), b"png").unwrap();
+        fs::write(project.join("design.md"), "[Tilbake](readme.md)").unwrap();
+        fs::write(root.path().join("hemmelig.md"), "# Hemmelig").unwrap();
+        let readme = project.join("readme.md");
+        let project_url = url::Url::from_directory_path(&project).unwrap();
+
+        let input = "[Design](./design.md#mål) [Bilde](img/) [Utenfor](../hemmelig.md) \
+                     [Absolutt](file:///etc/passwd) [Anker](#mål) [Nett](https://example.com/)\n\n\
+                     ![Diagram](./img/diagram.png)";
+        let mut options = RenderOptions::with_base(readme.to_string_lossy());
+        let output = render_with_options(input, &options).html;
+        assert!(output.contains(&format!("href=\"{}design.md#m%C3%A5l\"", project_url)));
+        assert!(output.contains(&format!("href=\"{}img/\"", project_url)));
+        assert!(output.contains("href=\"#m%C3%A5l\""));
+        assert!(output.contains("href=\"https://example.com/\""));
+        assert!(output.contains("src=\"data:image/png;base64,cG5n\""));
+        // Uten rot kan lenkene peke hvor som helst
+        assert!(output.contains("hemmelig.md\">Utenfor</a>"));
+        assert!(!output.contains("outside-root"));
+
+        options.local_root = Some(project.clone());
+        let output = render_with_options(input, &options).html;
+        assert!(output.contains("<a class=\"outside-root\">Utenfor</a>"));
+        assert!(output.contains("<a class=\"outside-root\">Absolutt</a>"));
+        assert!(!output.contains("hemmelig.md"));
+        assert!(!output.contains("/etc/passwd"));
+        assert!(output.contains(&format!("href=\"{}design.md#m%C3%A5l\"", project_url)));
+        assert!(output.contains("href=\"https://example.com/\""));
+
+        // Lenker i dokumenter fra nettet løses fortsatt av frontend
+        let remote = RenderOptions::with_base("https://example.com/docs/side.md");
+        let output = render_with_options("[Design](design.md)", &remote).html;
+        assert!(output.contains("href=\"design.md\""));
+    }
+
+    #[test]
+    fn test_render_task_summary() {
+        let input = "- [x] Ferdig\n- [ ] Gjenstår\n  - [x] Nestet ferdig\n  - [ ] Nestet åpen\n\n> - [x] I sitat\n";
+        let output = render(input);
+        assert_eq!(output.tasks, Some(TaskSummary { total: 5, done: 3 }));
+    }
+
+    #[test]
+    fn test_render_task_summary_ignores_code_blocks() {
+        let input =
+            "```markdown\n- [ ] Ikke en oppgave\n- [x] Heller ikke\n```\n\n- [ ] Ekte oppgave";
+        let output = render(input);
+        assert_eq!(output.tasks, Some(TaskSummary { total: 1, done: 0 }));
+
+        let output = render("Ingen oppgaver her");
+        assert_eq!(output.tasks, None);
+    }
+
+    fn diagram_options() -> RenderOptions {
+        RenderOptions {
+            diagram_languages: vec!["mermaid".to_string(), "plantuml".to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_render_diagram_fence_collapsed() {
+        let input = "```mermaid\ngraph TD\n  A --> B\n```";
+        let output = render_with_options(input, &diagram_options());
+        assert_eq!(output.skipped_diagrams, 1);
+        assert!(output.html.contains("<details class=\"diagram\">"));
+        assert!(output
+            .html
+            .contains("<summary>Diagramkilde (mermaid)</summary>"));
+        assert!(output.html.contains("A --&gt; B"));
+        assert!(output.html.trim_end().ends_with("</details>"));
+    }
+
+    #[test]
+    fn test_render_regular_fence_unaffected() {
+        let input = "```rust\nfn main() {}\n```";
+        let output = render_with_options(input, &diagram_options());
+        assert_eq!(output.skipped_diagrams, 0);
+        assert!(!output.html.contains("<details"));
+        assert!(output.html.contains("<code class=\"language-rust\">"));
+
+        // Uten konfigurerte diagramspråk er mermaid en vanlig kodeblokk
+        let output = render("```mermaid\ngraph TD\n```");
+        assert!(!output.html.contains("<details"));
+    }
+
+    #[test]
+    fn test_render_diagram_with_renderer() {
+        #[derive(Debug)]
+        struct Fake;
+        impl DiagramRenderer for Fake {
+            fn render(&self, language: &str, source: &str) -> Option<String> {
+                (language == "mermaid").then(|| format!("<svg>{}</svg>", source.len()))
+            }
+        }
+
+        let options = RenderOptions {
+            diagram_renderer: Some(Arc::new(Fake)),
+            ..diagram_options()
+        };
+        let output = render_with_options("```mermaid\nabc\n```\n\n```plantuml\nx\n```", &options);
+        assert!(output.html.contains("<svg>4</svg>"));
+        assert!(output.html.contains("Diagramkilde (plantuml)"));
+        assert_eq!(output.skipped_diagrams, 1);
+    }
+
+    #[test]
+    fn test_heading_ids_deduplicated() {
+        let headings = extract_headings("# Intro\n\n# Intro\n\n## Intro\n");
+        let ids: Vec<_> = headings.iter().map(|h| h.id.as_str()).collect();
+        assert_eq!(ids, ["intro", "intro-1", "intro-2"]);
+    }
+
+    #[test]
+    fn test_explicit_id_wins_over_earlier_auto_slug() {
+        let input = "# Oppsett\n\n## Noe annet {#oppsett}\n\n## Oppsett\n";
+        let ids: Vec<_> = extract_headings(input).into_iter().map(|h| h.id).collect();
+        assert_eq!(ids, ["oppsett-1", "oppsett", "oppsett-2"]);
+
+        let output = render(input).html;
+        assert!(output.contains("<h1 id=\"oppsett-1\">"));
+        assert!(output.contains("<h2 id=\"oppsett\">"));
+        assert!(output.contains("<h2 id=\"oppsett-2\">"));
+    }
+
+    #[test]
+    fn test_duplicate_explicit_ids() {
+        let input = "# A {#same}\n\n# B {#same}\n";
+        let ids: Vec<_> = extract_headings(input).into_iter().map(|h| h.id).collect();
+        assert_eq!(ids, ["same", "same-1"]);
+    }
+
+    #[test]
+    fn test_explicit_id_invalid_characters_stripped() {
+        let input = "# Tittel {#mitt/anker!}\n";
+        let output = render(input).html;
+        assert_eq!(extract_headings(input)[0].id, "mittanker");
+        assert!(output.contains("<h1 id=\"mittanker\">"));
+    }
+
+    #[test]
+    fn test_heading_without_sluggable_text() {
+        let ids: Vec<_> = extract_headings("# !!!\n\n# ???\n")
+            .into_iter()
+            .map(|h| h.id)
+            .collect();
+        assert_eq!(ids, ["overskrift", "overskrift-1"]);
+    }
+
+    #[test]
+    fn test_truncate_source_keeps_short_content() {
+        assert_eq!(truncate_source("# Kort\n", 100), None);
+    }
+
+    #[test]
+    fn test_truncate_source_at_line_boundary() {
+        let content = "# Tittel\n\nFørste avsnitt.\n\nAndre avsnitt som ikke får plass.\n";
+        let truncated = truncate_source(content, 30).unwrap();
+        assert_eq!(truncated, "# Tittel\n\nFørste avsnitt.\n\n");
+    }
+
+    #[test]
+    fn test_truncate_source_not_inside_code_fence() {
+        let content = "Før\n\n```rust\nfn main() {\n    println!(\"hei\");\n}\n```\n\nEtter\n";
+        // Grensen faller midt i kodeblokken
+        let truncated = truncate_source(content, 30).unwrap();
+        assert_eq!(truncated, "Før\n\n");
+
+        // Et lengre gjerde lukkes ikke av et kortere
+        let content = "Tekst\n````\n```\nkode\n```\n````\nEtter\n";
+        assert_eq!(
+            truncate_source(content, 29).unwrap(),
+            "Tekst\n````\n```\nkode\n```\n````\n"
+        );
+        assert_eq!(truncate_source(content, 28).unwrap(), "Tekst\n");
+    }
+
+    #[test]
+    fn test_truncate_source_single_long_line() {
+        let content = "æøå".repeat(10);
+        let truncated = truncate_source(&content, 5).unwrap();
+        assert_eq!(truncated, "æø");
+    }
+
+    #[test]
+    fn test_render_truncated_sets_flag_and_notice() {
+        let content = format!("# Stor\n\n{}", "Et avsnitt.\n\n".repeat(100));
+        let options = RenderOptions {
+            max_input_bytes: Some(100),
+            ..Default::default()
+        };
+        let output = render_with_options(&content, &options);
+        assert!(output.truncated);
+        assert!(output.html.contains("class=\"truncated-notice\""));
+        assert!(output.html.matches("<p>Et avsnitt.</p>").count() < 100);
+
+        let options = RenderOptions {
+            max_input_bytes: Some(content.len()),
+            ..Default::default()
+        };
+        let output = render_with_options(&content, &options);
+        assert!(!output.truncated);
+        assert!(!output.html.contains("truncated-notice"));
+    }
 }
@@ -0,0 +1,177 @@
+//! Sikkerheten til forbindelsen en side ble hentet over
+//!
+//! Klientene fyller ut `SecurityInfo` mens siden hentes: HTTP-klienten fra
+//! TLS-informasjonen reqwest legger ved responsen, Gemini-klienten fra
+//! rustls-forbindelsen. Informasjonen følger med `RenderedPage` og huskes for
+//! de sist viste sidene, så frontend kan hente den når brukeren klikker på
+//! indikatoren i adresselinjen.
+//!
+//! reqwest forteller ikke hvilket chiffer som ble forhandlet frem, så
+//! `cipher_suite` er bare kjent for Gemini.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use url::Url;
+
+/// Protokollen siden ble hentet med
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Protocol {
+    Http,
+    Https,
+    Gemini,
+    Gopher,
+}
+
+/// Hvorfor serverens sertifikat ble godtatt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CertificateTrust {
+    /// Forbindelsen er ikke kryptert
+    None,
+    /// Sertifikatet er utstedt av en kjent sertifiseringsinstans (HTTPS)
+    CaValidated,
+    /// Sertifikatet er det samme som ved første besøk (Gemini, TOFU)
+    Pinned,
+}
+
+/// Detaljer om forbindelsen til en side
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecurityInfo {
+    pub protocol: Protocol,
+    /// Om forbindelsen var kryptert med TLS
+    pub encrypted: bool,
+    /// TLS-versjonen, f.eks. "TLS 1.3"
+    pub tls_version: Option<String>,
+    /// Chifferet som ble forhandlet frem, f.eks. "TLS13_AES_256_GCM_SHA384"
+    pub cipher_suite: Option<String>,
+    pub certificate: CertificateTrust,
+    /// SHA-256 av serverens sertifikat, heksadesimalt
+    pub fingerprint: Option<String>,
+    /// Om en redirect førte fra en kryptert til en ukryptert adresse
+    pub downgraded: bool,
+}
+
+impl SecurityInfo {
+    /// En ukryptert forbindelse
+    pub fn plain(protocol: Protocol) -> Self {
+        Self {
+            protocol,
+            encrypted: false,
+            tls_version: None,
+            cipher_suite: None,
+            certificate: CertificateTrust::None,
+            fingerprint: None,
+            downgraded: false,
+        }
+    }
+
+    /// Forbindelsen bak en HTTP-respons
+    ///
+    /// Klienten må være bygget med `tls_info(true)`.
+    ///
+    /// # Arguments
+    /// * `requested` - Adressen som ble bedt om, før redirects
+    /// * `response` - Responsen fra den endelige adressen
+    pub fn from_http(requested: &Url, response: &reqwest::Response) -> Self {
+        let final_scheme = response.url().scheme();
+        let downgraded = requested.scheme() == "https" && final_scheme != "https";
+        if final_scheme != "https" {
+            return Self {
+                downgraded,
+                ..Self::plain(Protocol::Http)
+            };
+        }
+
+        let tls = response.extensions().get::<reqwest::tls::TlsInfo>();
+        Self {
+            protocol: Protocol::Https,
+            encrypted: true,
+            tls_version: tls.and_then(|tls| tls.version()).map(reqwest_version_name),
+            cipher_suite: None,
+            certificate: CertificateTrust::CaValidated,
+            fingerprint: tls.and_then(|tls| tls.peer_certificate()).map(fingerprint),
+            downgraded,
+        }
+    }
+
+    /// Forbindelsen til en Gemini-server, etter at sertifikatet er festet
+    ///
+    /// # Arguments
+    /// * `connection` - rustls-forbindelsen etter handshake
+    /// * `fingerprint` - Fingerprint til sertifikatet som ble festet
+    pub fn from_gemini(connection: &rustls::CommonState, fingerprint: Option<String>) -> Self {
+        Self {
+            protocol: Protocol::Gemini,
+            encrypted: true,
+            tls_version: connection.protocol_version().map(rustls_version_name),
+            cipher_suite: connection
+                .negotiated_cipher_suite()
+                .map(|suite| format!("{:?}", suite.suite())),
+            certificate: CertificateTrust::Pinned,
+            fingerprint,
+            downgraded: false,
+        }
+    }
+}
+
+/// SHA-256 av et sertifikat i DER-format, heksadesimalt
+pub fn fingerprint(der: &[u8]) -> String {
+    hex::encode(Sha256::digest(der))
+}
+
+fn rustls_version_name(version: rustls::ProtocolVersion) -> String {
+    match version {
+        rustls::ProtocolVersion::TLSv1_2 => "TLS 1.2".to_string(),
+        rustls::ProtocolVersion::TLSv1_3 => "TLS 1.3".to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+fn reqwest_version_name(version: reqwest::tls::Version) -> String {
+    use reqwest::tls::Version;
+    let name = if version == Version::TLS_1_3 {
+        "TLS 1.3"
+    } else if version == Version::TLS_1_2 {
+        "TLS 1.2"
+    } else if version == Version::TLS_1_1 {
+        "TLS 1.1"
+    } else {
+        "TLS 1.0"
+    };
+    name.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serde_names() {
+        let info = SecurityInfo {
+            protocol: Protocol::Https,
+            encrypted: true,
+            tls_version: Some("TLS 1.3".into()),
+            cipher_suite: None,
+            certificate: CertificateTrust::CaValidated,
+            fingerprint: Some("ab".into()),
+            downgraded: false,
+        };
+        assert_eq!(
+            serde_json::to_value(&info).unwrap(),
+            serde_json::json!({
+                "protocol": "https",
+                "encrypted": true,
+                "tls_version": "TLS 1.3",
+                "cipher_suite": null,
+                "certificate": "ca_validated",
+                "fingerprint": "ab",
+                "downgraded": false,
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(SecurityInfo::plain(Protocol::Gopher)).unwrap()["certificate"],
+            "none"
+        );
+    }
+}
@@ -0,0 +1,152 @@
+//! Kilden til viste sider, byte for byte
+//!
+//! Gemtext, HTML og gophermenyer gjøres om til markdown før de vises. Her
+//! tas vare på bytene slik serveren sendte dem, uten dekoding eller
+//! konvertering, slik at kilden kan lagres til disk for arkivering eller
+//! sammenligning og blir identisk med originalen.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Formatet til kilden, som avgjør filendelsen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceFormat {
+    Gemtext,
+    Html,
+    Markdown,
+    Text,
+}
+
+impl SourceFormat {
+    /// Filendelsen kilden lagres med
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Gemtext => "gmi",
+            Self::Html => "html",
+            Self::Markdown => "md",
+            Self::Text => "txt",
+        }
+    }
+
+    /// Formatet ut fra Content-Type eller Gemini-meta, og ellers adressen
+    ///
+    /// # Arguments
+    /// * `content_type` - MIME-typen fra serveren, hvis den oppga en
+    /// * `url` - Adressen kilden ble hentet fra
+    pub fn detect(content_type: Option<&str>, url: &str) -> Self {
+        let mime = content_type
+            .and_then(|ct| ct.split(';').next())
+            .map(|ct| ct.trim().to_ascii_lowercase())
+            .unwrap_or_default();
+        match mime.as_str() {
+            "text/gemini" => return Self::Gemtext,
+            "text/html" | "application/xhtml+xml" => return Self::Html,
+            "text/markdown" | "text/x-markdown" => return Self::Markdown,
+            _ => {}
+        }
+
+        let path = url::Url::parse(url)
+            .map(|u| u.path().to_ascii_lowercase())
+            .unwrap_or_default();
+        let extension = path.rsplit_once('.').map_or("", |(_, ext)| ext);
+        match extension {
+            "md" | "markdown" => Self::Markdown,
+            "gmi" | "gemini" if mime.is_empty() => Self::Gemtext,
+            "html" | "htm" if mime.is_empty() => Self::Html,
+            _ => Self::Text,
+        }
+    }
+}
+
+/// Kilden til én side
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageSource {
+    /// Bytene slik serveren sendte dem
+    pub bytes: Vec<u8>,
+    pub format: SourceFormat,
+}
+
+/// Målfilen med formatets endelse, hvis brukeren ikke har valgt en selv
+pub fn with_extension(destination: &Path, format: SourceFormat) -> PathBuf {
+    if destination.extension().is_some() {
+        destination.to_path_buf()
+    } else {
+        destination.with_extension(format.extension())
+    }
+}
+
+/// Skriv kilden til disk uten å endre noen bytes
+///
+/// # Returns
+/// Stien filen ble skrevet til, se `with_extension`
+pub fn save(destination: &Path, source: &PageSource) -> io::Result<PathBuf> {
+    let path = with_extension(destination, source.format);
+    fs::write(&path, &source.bytes)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_format() {
+        let cases = [
+            (
+                Some("text/gemini; lang=no"),
+                "gemini://a.example/",
+                SourceFormat::Gemtext,
+            ),
+            (
+                Some("text/html; charset=iso-8859-1"),
+                "https://a.example/",
+                SourceFormat::Html,
+            ),
+            (
+                Some("text/plain"),
+                "https://a.example/LES.MD",
+                SourceFormat::Markdown,
+            ),
+            (
+                Some("text/plain"),
+                "https://a.example/side.html",
+                SourceFormat::Text,
+            ),
+            (None, "gopher://a.example/0/side.htm", SourceFormat::Html),
+            (None, "gopher://a.example/1/", SourceFormat::Text),
+            (
+                Some("text/markdown"),
+                "https://a.example/",
+                SourceFormat::Markdown,
+            ),
+        ];
+        for (content_type, url, expected) in cases {
+            assert_eq!(SourceFormat::detect(content_type, url), expected, "{}", url);
+        }
+    }
+
+    #[test]
+    fn test_latin1_html_round_trips_byte_for_byte() {
+        let dir = tempfile::tempdir().unwrap();
+        // "Blåbærsyltetøy" i ISO-8859-1, som ikke er gyldig UTF-8
+        let bytes = b"<html><meta charset=\"iso-8859-1\"><p>Bl\xe5b\xe6rsyltet\xf8y</p>\r\n</html>"
+            .to_vec();
+        let source = PageSource {
+            bytes: bytes.clone(),
+            format: SourceFormat::detect(
+                Some("text/html; charset=iso-8859-1"),
+                "https://a.example/",
+            ),
+        };
+
+        let path = save(&dir.path().join("side"), &source).unwrap();
+        assert_eq!(path, dir.path().join("side.html"));
+        assert_eq!(fs::read(&path).unwrap(), bytes);
+
+        // En endelse brukeren har valgt beholdes
+        let path = save(&dir.path().join("arkiv.htm"), &source).unwrap();
+        assert_eq!(path, dir.path().join("arkiv.htm"));
+        assert_eq!(fs::read(&path).unwrap(), bytes);
+    }
+}
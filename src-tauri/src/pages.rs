@@ -0,0 +1,143 @@
+//! Innebygde sider: velkomstsiden og onboarding
+//!
+//! Sidene er markdown-filer i `pages/`, én per språk, som bygges inn i
+//! programmet med `include_str!`. Versjonsnummeret settes inn der
+//! `{{version}}` står. Mangler en side på brukerens språk, vises den norske.
+
+use crate::locale::Locale;
+
+/// Stedet i sidene der versjonsnummeret settes inn
+const VERSION_TOKEN: &str = "{{version}}";
+
+/// Antall steg i onboarding
+pub const ONBOARDING_STEPS: usize = 3;
+
+/// En innebygd side
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Page {
+    Welcome,
+    /// Et steg i onboarding, fra 1
+    Onboarding(usize),
+}
+
+/// Alle sidene, med språket de er skrevet på
+const PAGES: &[(Page, Locale, &str)] = &[
+    (
+        Page::Welcome,
+        Locale::Nb,
+        include_str!("../pages/welcome.nb.md"),
+    ),
+    (
+        Page::Welcome,
+        Locale::En,
+        include_str!("../pages/welcome.en.md"),
+    ),
+    (
+        Page::Onboarding(1),
+        Locale::Nb,
+        include_str!("../pages/onboarding-1.nb.md"),
+    ),
+    (
+        Page::Onboarding(1),
+        Locale::En,
+        include_str!("../pages/onboarding-1.en.md"),
+    ),
+    (
+        Page::Onboarding(2),
+        Locale::Nb,
+        include_str!("../pages/onboarding-2.nb.md"),
+    ),
+    (
+        Page::Onboarding(2),
+        Locale::En,
+        include_str!("../pages/onboarding-2.en.md"),
+    ),
+    (
+        Page::Onboarding(3),
+        Locale::Nb,
+        include_str!("../pages/onboarding-3.nb.md"),
+    ),
+    (
+        Page::Onboarding(3),
+        Locale::En,
+        include_str!("../pages/onboarding-3.en.md"),
+    ),
+];
+
+/// Markdown-kilden til en side på et gitt språk
+///
+/// # Returns
+/// None hvis siden ikke finnes, f.eks. et steg etter det siste
+pub fn source(page: Page, locale: Locale) -> Option<String> {
+    let find = |locale: Locale| {
+        PAGES
+            .iter()
+            .find(|(p, l, _)| *p == page && *l == locale)
+            .map(|(_, _, source)| *source)
+    };
+    find(locale)
+        .or_else(|| find(Locale::Nb))
+        .map(|source| source.replace(VERSION_TOKEN, env!("CARGO_PKG_VERSION")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markdown;
+
+    #[test]
+    fn test_every_page_has_version_and_title() {
+        for (page, locale, source) in PAGES {
+            assert_eq!(
+                source.matches(VERSION_TOKEN).count(),
+                1,
+                "{:?} på {:?}",
+                page,
+                locale
+            );
+
+            let source = super::source(*page, *locale).unwrap();
+            assert!(!source.contains(VERSION_TOKEN));
+            assert!(source.contains(env!("CARGO_PKG_VERSION")));
+            let rendered = markdown::render_with_options(&source, &Default::default());
+            assert!(
+                rendered.html.starts_with("<h1"),
+                "{:?} på {:?}",
+                page,
+                locale
+            );
+            assert!(markdown::extract_title(&source).is_some());
+        }
+    }
+
+    #[test]
+    fn test_every_page_in_every_locale() {
+        let mut pages = vec![Page::Welcome];
+        pages.extend((1..=ONBOARDING_STEPS).map(Page::Onboarding));
+        for page in pages {
+            for locale in [Locale::Nb, Locale::En] {
+                assert!(
+                    PAGES.iter().any(|(p, l, _)| *p == page && *l == locale),
+                    "{:?} mangler på {:?}",
+                    page,
+                    locale
+                );
+            }
+        }
+        assert_eq!(
+            source(Page::Onboarding(ONBOARDING_STEPS + 1), Locale::Nb),
+            None
+        );
+        assert_eq!(source(Page::Onboarding(0), Locale::En), None);
+    }
+
+    #[test]
+    fn test_localized_welcome() {
+        assert!(source(Page::Welcome, Locale::Nb)
+            .unwrap()
+            .starts_with("# Velkommen til Bare"));
+        assert!(source(Page::Welcome, Locale::En)
+            .unwrap()
+            .starts_with("# Welcome to Bare"));
+    }
+}
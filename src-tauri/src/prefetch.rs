@@ -0,0 +1,249 @@
+//! Forhåndshenting av lenker brukeren holder musen over
+//!
+//! Siden hentes gjennom samme klient som ved vanlig navigering og legges i et
+//! lite mellomlager i minnet. Klikker brukeren på lenken kort tid etter,
+//! brukes den hentede kopien én gang i stedet for en ny forespørsel. Ingenting
+//! rendres og ingenting skrives til disk.
+
+use crate::fetcher::FetchResult;
+use crate::gemini::GeminiResponse;
+use crate::gopher::GopherResponse;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// Hvor lenge en forhåndshentet side kan brukes
+pub const MAX_AGE: Duration = Duration::from_secs(60);
+
+/// Maks antall forhåndshentede sider i minnet
+pub const MAX_ENTRIES: usize = 16;
+
+/// Maks antall forhåndshentinger som pågår samtidig
+pub const MAX_CONCURRENT: usize = 2;
+
+/// Protokollene som kan forhåndshentes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrefetchScheme {
+    Http,
+    Gemini,
+    Gopher,
+}
+
+/// Hvorfor en lenke ikke forhåndshentes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrefetchSkip {
+    /// Slått av i innstillingene
+    Disabled,
+    /// Privat surfing er på
+    Private,
+    /// Ugyldig adresse eller protokoll som ikke hentes over nettet
+    UnsupportedUrl,
+}
+
+/// Avgjør om en lenke kan forhåndshentes
+///
+/// # Arguments
+/// * `url` - Absolutt adresse
+/// * `enabled` - Innstillingen for forhåndshenting
+/// * `private` - Om privat surfing er på
+///
+/// # Returns
+/// Protokollen som skal brukes, eller grunnen til at lenken hoppes over
+pub fn check(url: &str, enabled: bool, private: bool) -> Result<PrefetchScheme, PrefetchSkip> {
+    if !enabled {
+        return Err(PrefetchSkip::Disabled);
+    }
+    if private {
+        return Err(PrefetchSkip::Private);
+    }
+    let parsed = url::Url::parse(url).map_err(|_| PrefetchSkip::UnsupportedUrl)?;
+    match parsed.scheme() {
+        "http" | "https" => Ok(PrefetchScheme::Http),
+        "gemini" => Ok(PrefetchScheme::Gemini),
+        "gopher" => Ok(PrefetchScheme::Gopher),
+        _ => Err(PrefetchSkip::UnsupportedUrl),
+    }
+}
+
+/// En forhåndshentet respons, slik klienten leverte den
+#[derive(Debug)]
+pub enum Prefetched {
+    Http(FetchResult),
+    Gemini(GeminiResponse),
+    Gopher(GopherResponse),
+}
+
+/// Tillatelse til én forhåndshenting; gis tilbake når den slippes
+#[derive(Debug)]
+pub struct PrefetchPermit<'a> {
+    in_flight: &'a AtomicUsize,
+}
+
+impl Drop for PrefetchPermit<'_> {
+    fn drop(&mut self) {
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Grense for hvor mange forhåndshentinger som pågår samtidig
+#[derive(Debug)]
+pub struct PrefetchBudget {
+    in_flight: AtomicUsize,
+    max: usize,
+}
+
+impl PrefetchBudget {
+    pub const fn new(max: usize) -> Self {
+        Self {
+            in_flight: AtomicUsize::new(0),
+            max,
+        }
+    }
+
+    /// Be om plass til én forhåndshenting
+    ///
+    /// # Returns
+    /// En tillatelse, eller None hvis budsjettet er brukt opp
+    pub fn try_acquire(&self) -> Option<PrefetchPermit<'_>> {
+        self.in_flight
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                (n < self.max).then_some(n + 1)
+            })
+            .ok()?;
+        Some(PrefetchPermit {
+            in_flight: &self.in_flight,
+        })
+    }
+}
+
+/// Forhåndshentede sider som venter på å bli åpnet
+#[derive(Debug)]
+pub struct PrefetchCache<T> {
+    entries: HashMap<String, (Instant, T)>,
+}
+
+impl<T> Default for PrefetchCache<T> {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+}
+
+impl<T> PrefetchCache<T> {
+    /// Legg til en forhåndshentet side
+    ///
+    /// Utgåtte sider ryddes bort, og den eldste fjernes hvis lageret er fullt.
+    pub fn insert(&mut self, url: &str, value: T, now: Instant) {
+        self.entries
+            .retain(|_, (fetched, _)| now.duration_since(*fetched) < MAX_AGE);
+        if self.entries.len() >= MAX_ENTRIES && !self.entries.contains_key(url) {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (fetched, _))| *fetched)
+                .map(|(url, _)| url.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert(url.to_string(), (now, value));
+    }
+
+    /// Hent ut en forhåndshentet side til navigering
+    ///
+    /// Siden fjernes fra lageret, så den brukes høyst én gang.
+    ///
+    /// # Returns
+    /// Siden, eller None hvis den ikke finnes eller er for gammel
+    pub fn take(&mut self, url: &str, now: Instant) -> Option<T> {
+        let (fetched, value) = self.entries.remove(url)?;
+        (now.duration_since(fetched) < MAX_AGE).then_some(value)
+    }
+
+    /// Om en side venter i lageret
+    pub fn contains(&self, url: &str) -> bool {
+        self.entries.contains_key(url)
+    }
+
+    /// Glem alle forhåndshentede sider
+    ///
+    /// # Returns
+    /// Antall sider som ble glemt
+    pub fn clear(&mut self) -> usize {
+        let count = self.entries.len();
+        self.entries.clear();
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_gates() {
+        let url = "https://example.com/side.md";
+        assert_eq!(check(url, true, false), Ok(PrefetchScheme::Http));
+        assert_eq!(check(url, false, false), Err(PrefetchSkip::Disabled));
+        assert_eq!(check(url, true, true), Err(PrefetchSkip::Private));
+
+        let cases = [
+            ("gemini://example.org/", Ok(PrefetchScheme::Gemini)),
+            ("gopher://example.org/1/", Ok(PrefetchScheme::Gopher)),
+            ("file:///tmp/a.md", Err(PrefetchSkip::UnsupportedUrl)),
+            ("bare://bookmarks", Err(PrefetchSkip::UnsupportedUrl)),
+            ("side.md", Err(PrefetchSkip::UnsupportedUrl)),
+        ];
+        for (url, expected) in cases {
+            assert_eq!(check(url, true, false), expected, "{}", url);
+        }
+    }
+
+    #[test]
+    fn test_budget_limits_concurrent_prefetches() {
+        let budget = PrefetchBudget::new(2);
+        let first = budget.try_acquire().unwrap();
+        let second = budget.try_acquire().unwrap();
+        assert!(budget.try_acquire().is_none());
+
+        drop(first);
+        let third = budget.try_acquire();
+        assert!(third.is_some());
+        assert!(budget.try_acquire().is_none());
+
+        drop(second);
+        drop(third);
+        assert_eq!(budget.in_flight.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_cache_is_reused_once() {
+        let now = Instant::now();
+        let mut cache = PrefetchCache::default();
+        cache.insert("https://example.com/", "# Eksempel", now);
+
+        assert!(cache.contains("https://example.com/"));
+        assert_eq!(cache.take("https://example.com/", now), Some("# Eksempel"));
+        assert_eq!(cache.take("https://example.com/", now), None);
+        assert_eq!(cache.take("https://example.org/", now), None);
+    }
+
+    #[test]
+    fn test_cache_expires_and_evicts_oldest() {
+        let start = Instant::now();
+        let mut cache = PrefetchCache::default();
+        cache.insert("https://gammel.example/", 0, start);
+        assert_eq!(cache.take("https://gammel.example/", start + MAX_AGE), None);
+
+        for i in 0..MAX_ENTRIES {
+            let at = start + Duration::from_millis(i as u64);
+            cache.insert(&format!("https://{}.example/", i), i, at);
+        }
+        let later = start + Duration::from_secs(1);
+        cache.insert("https://ny.example/", 99, later);
+        assert!(!cache.contains("https://0.example/"));
+        assert!(cache.contains("https://1.example/"));
+        assert_eq!(cache.take("https://ny.example/", later), Some(99));
+    }
+}
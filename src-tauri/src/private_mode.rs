@@ -0,0 +1,50 @@
+//! Privat surfing
+//!
+//! Mens privat modus er på, skrives ingenting om surfingen til disk:
+//! historikken tar ikke imot besøk, nye Gemini-sertifikater festes bare i
+//! minnet, og adresser holdes utenfor loggen. Flagget hører til økten i én
+//! `AppState`, gjelder bare mens appen kjører og kan slås av igjen uten
+//! omstart. Nettverksklientene får sin egen kopi av flagget, siden de logger
+//! adresser uten tilgang til økten.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Det som står i loggen i stedet for en adresse
+const REDACTED: &str = "[privat]";
+
+/// Om privat modus er på, i `Session`
+#[derive(Debug, Default)]
+pub struct PrivateMode(AtomicBool);
+
+impl PrivateMode {
+    /// Om privat modus er på
+    pub fn is_active(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Slå privat modus av eller på
+    pub fn set_active(&self, active: bool) {
+        self.0.store(active, Ordering::Relaxed);
+    }
+
+    /// Adressen slik den skal stå i loggen; se `log_url`
+    pub fn log_url<'a>(&self, url: &'a str) -> &'a str {
+        log_url(self.is_active(), url)
+    }
+}
+
+/// Adressen slik den skal stå i loggen
+///
+/// # Arguments
+/// * `private` - Om privat modus er på
+/// * `url` - Adressen, eller vert og port
+///
+/// # Returns
+/// Adressen, eller en plassholder når privat modus er på
+pub fn log_url(private: bool, url: &str) -> &str {
+    if private {
+        REDACTED
+    } else {
+        url
+    }
+}
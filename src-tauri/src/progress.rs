@@ -0,0 +1,292 @@
+//! Fremdrift mens en side lastes
+//!
+//! Hentingene sender `page-load-progress` til vinduet som laster siden, med
+//! steget hentingen er kommet til. Frontend lager teksten selv på valgt språk
+//! og kan vise en fremdriftslinje ut fra `bytes` og `total`. `done`-steget har
+//! med hvor lang tid hver fase tok (se `timing`), og det samme logges på én
+//! linje.
+//!
+//! Den gamle `loading-status`-hendelsen med ferdige tekster sendes i tillegg
+//! så lenge featuren `legacy-loading-status` er på. Den fjernes i neste
+//! versjon.
+
+use crate::private_mode;
+use crate::timing::{NetworkTiming, TimingInfo};
+use log::info;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+/// Hendelsen med strukturert fremdrift
+pub const PAGE_LOAD_PROGRESS_EVENT: &str = "page-load-progress";
+
+/// Den gamle hendelsen med en ferdig statustekst
+pub const LOADING_STATUS_EVENT: &str = "loading-status";
+
+/// Stegene en henting går gjennom, i rekkefølge
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoadStage {
+    Resolving,
+    Connecting,
+    TlsHandshake,
+    Transferring,
+    Converting,
+    Rendering,
+    Done,
+    Error,
+}
+
+/// Innholdet i `page-load-progress`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PageLoadProgress {
+    pub stage: LoadStage,
+    /// Bytes mottatt så langt
+    pub bytes: Option<u64>,
+    /// Forventet størrelse, hvis den er kjent
+    pub total: Option<u64>,
+    /// Verten siden hentes fra
+    pub host: String,
+    /// Hvor lang tid fasene tok, bare i `done`-steget
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<TimingInfo>,
+}
+
+/// Mottaker av fremdriften; i appen vinduet som laster siden
+pub trait ProgressSink {
+    /// Send strukturert fremdrift
+    fn send_progress(&self, progress: PageLoadProgress);
+
+    /// Send en ferdig statustekst (`loading-status`)
+    fn send_status(&self, status: &str);
+}
+
+/// `emit` på et vindu når alle vinduer, så hendelsene adresseres med etiketten
+impl ProgressSink for tauri::Window {
+    fn send_progress(&self, progress: PageLoadProgress) {
+        let _ = self.emit_to(self.label(), PAGE_LOAD_PROGRESS_EVENT, progress);
+    }
+
+    fn send_status(&self, status: &str) {
+        let _ = self.emit_to(self.label(), LOADING_STATUS_EVENT, status);
+    }
+}
+
+/// Fremdriften til én henting
+pub struct LoadProgress<'a, S: ?Sized> {
+    sink: &'a S,
+    host: String,
+    /// Privat modus: verten holdes utenfor loggen
+    private: bool,
+    started: Instant,
+    clock: Mutex<PhaseClock>,
+}
+
+/// Tidene en henting har samlet så langt
+#[derive(Debug, Default)]
+struct PhaseClock {
+    network: NetworkTiming,
+    /// Konvertering eller rendring som pågår, og når den startet
+    current: Option<(LoadStage, Instant)>,
+    convert: Option<Duration>,
+    render: Option<Duration>,
+}
+
+impl PhaseClock {
+    /// Avslutt fasen som pågår, og start en ny hvis steget måles
+    fn enter(&mut self, stage: LoadStage) {
+        let now = Instant::now();
+        if let Some((previous, since)) = self.current.take() {
+            let phase = match previous {
+                LoadStage::Converting => &mut self.convert,
+                _ => &mut self.render,
+            };
+            *phase = Some(phase.unwrap_or_default() + (now - since));
+        }
+        if matches!(stage, LoadStage::Converting | LoadStage::Rendering) {
+            self.current = Some((stage, now));
+        }
+    }
+}
+
+impl<'a, S: ProgressSink + ?Sized> LoadProgress<'a, S> {
+    /// # Arguments
+    /// * `sink` - Mottakeren, vanligvis vinduet
+    /// * `host` - Verten siden hentes fra
+    /// * `private` - Om privat modus er på
+    pub fn new(sink: &'a S, host: impl Into<String>, private: bool) -> Self {
+        Self {
+            sink,
+            host: host.into(),
+            private,
+            started: Instant::now(),
+            clock: Mutex::default(),
+        }
+    }
+
+    /// Registrer tiden klienten brukte på nettverket
+    ///
+    /// Kalles ikke for forhåndshentede sider, som ikke gikk over nettet nå.
+    pub fn network(&self, timing: NetworkTiming) {
+        self.clock.lock().network += timing;
+    }
+
+    /// Meld at siden er ferdig, med tiden hver fase tok
+    ///
+    /// # Arguments
+    /// * `status` - Teksten til `loading-status`
+    ///
+    /// # Returns
+    /// Tidene, til `RenderedPage`
+    pub fn done(&self, status: impl AsRef<str>) -> TimingInfo {
+        let timing = {
+            let mut clock = self.clock.lock();
+            clock.enter(LoadStage::Done);
+            TimingInfo::new(
+                clock.network,
+                clock.convert,
+                clock.render,
+                self.started.elapsed(),
+            )
+        };
+        info!(
+            "Lastet {}: {}",
+            private_mode::log_url(self.private, &self.host),
+            timing.summary()
+        );
+        self.status(status);
+        self.sink.send_progress(PageLoadProgress {
+            timing: Some(timing),
+            ..self.payload(LoadStage::Done, None, None)
+        });
+        timing
+    }
+
+    /// Meld at hentingen er kommet til et nytt steg
+    ///
+    /// # Arguments
+    /// * `stage` - Steget
+    /// * `status` - Teksten til `loading-status`
+    pub fn stage(&self, stage: LoadStage, status: impl AsRef<str>) {
+        self.send(stage, None, None, status.as_ref());
+    }
+
+    /// Meld et nytt steg uten egen statustekst, f.eks. TLS-handshaken som
+    /// den gamle teksten for tilkoblingen allerede nevner
+    pub fn advance(&self, stage: LoadStage) {
+        self.sink.send_progress(self.payload(stage, None, None));
+    }
+
+    /// Meld hvor mye som er overført
+    ///
+    /// # Arguments
+    /// * `bytes` - Bytes mottatt så langt
+    /// * `total` - Forventet størrelse, hvis den er kjent
+    /// * `status` - Teksten til `loading-status`
+    pub fn transferring(&self, bytes: u64, total: Option<u64>, status: impl AsRef<str>) {
+        self.send(LoadStage::Transferring, Some(bytes), total, status.as_ref());
+    }
+
+    /// Send bare en statustekst, for tilstander som ikke er et steg
+    /// (f.eks. at hentingen venter på et valg fra brukeren)
+    pub fn status(&self, status: impl AsRef<str>) {
+        if cfg!(feature = "legacy-loading-status") {
+            self.sink.send_status(status.as_ref());
+        }
+    }
+
+    fn send(&self, stage: LoadStage, bytes: Option<u64>, total: Option<u64>, status: &str) {
+        self.clock.lock().enter(stage);
+        self.status(status);
+        self.sink.send_progress(self.payload(stage, bytes, total));
+    }
+
+    fn payload(
+        &self,
+        stage: LoadStage,
+        bytes: Option<u64>,
+        total: Option<u64>,
+    ) -> PageLoadProgress {
+        PageLoadProgress {
+            stage,
+            bytes,
+            total,
+            host: self.host.clone(),
+            timing: None,
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    /// Mottaker som husker alt den får
+    #[derive(Default)]
+    pub(crate) struct Recorder {
+        pub progress: Mutex<Vec<PageLoadProgress>>,
+        pub statuses: Mutex<Vec<String>>,
+    }
+
+    impl Recorder {
+        pub fn stages(&self) -> Vec<LoadStage> {
+            self.progress
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|p| p.stage)
+                .collect()
+        }
+    }
+
+    impl ProgressSink for Recorder {
+        fn send_progress(&self, progress: PageLoadProgress) {
+            self.progress.lock().unwrap().push(progress);
+        }
+
+        fn send_status(&self, status: &str) {
+            self.statuses.lock().unwrap().push(status.to_string());
+        }
+    }
+
+    #[test]
+    fn test_progress_payload_shape() {
+        let progress = PageLoadProgress {
+            stage: LoadStage::TlsHandshake,
+            bytes: None,
+            total: None,
+            host: "a.example".into(),
+            timing: None,
+        };
+        assert_eq!(
+            serde_json::to_value(progress).unwrap(),
+            json!({ "stage": "tls_handshake", "bytes": null, "total": null, "host": "a.example" })
+        );
+
+        let recorder = Recorder::default();
+        LoadProgress::new(&recorder, "a.example", false).transferring(512, Some(2048), "Overfører");
+        assert_eq!(
+            serde_json::to_value(&recorder.progress.lock().unwrap()[0]).unwrap(),
+            json!({ "stage": "transferring", "bytes": 512, "total": 2048, "host": "a.example" })
+        );
+    }
+
+    #[test]
+    fn test_legacy_status_follows_feature() {
+        let recorder = Recorder::default();
+        let progress = LoadProgress::new(&recorder, "a.example", false);
+        progress.stage(LoadStage::Resolving, "Slår opp");
+        progress.status("Venter");
+
+        assert_eq!(recorder.stages(), vec![LoadStage::Resolving]);
+        let statuses = recorder.statuses.lock().unwrap();
+        if cfg!(feature = "legacy-loading-status") {
+            assert_eq!(*statuses, vec!["Slår opp", "Venter"]);
+        } else {
+            assert!(statuses.is_empty());
+        }
+    }
+}
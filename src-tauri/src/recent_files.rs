@@ -0,0 +1,144 @@
+//! Nylig åpnede lokale filer
+//!
+//! Listen vises i menyen slik at de samme filene kan åpnes igjen uten
+//! fildialogen. Filer som ikke finnes lenger, skjules når listen leses, men
+//! beholdes i lageret: en minnepinne eller nettverksdisk som er frakoblet en
+//! stund skal ikke tømme listen.
+
+use crate::storage;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Maks antall filer som huskes
+pub const MAX_RECENT_FILES: usize = 10;
+
+/// En fil i listen, slik frontend får den
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentFile {
+    /// Full sti til filen
+    pub path: String,
+    /// Filnavnet, til visning
+    pub name: String,
+}
+
+/// Nylig åpnede filer, nyeste først
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentFiles {
+    paths: Vec<PathBuf>,
+}
+
+impl RecentFiles {
+    /// Last listen fra fil; en fil som mangler eller er ødelagt gir tom liste
+    pub fn load(path: &Path) -> Self {
+        storage::read_json(path)
+            .unwrap_or_else(|e| {
+                warn!("Kunne ikke lese nylige filer: {}", e);
+                None
+            })
+            .unwrap_or_default()
+    }
+
+    /// Lagre listen til fil
+    pub fn save(&self, path: &Path) -> Result<(), storage::StorageError> {
+        storage::write_json(path, self)
+    }
+
+    /// Legg en fil først i listen
+    ///
+    /// En fil som allerede står i listen flyttes først, og de eldste
+    /// filene faller ut når listen er full.
+    pub fn add(&mut self, path: &Path) {
+        self.paths.retain(|p| p != path);
+        self.paths.insert(0, path.to_path_buf());
+        self.paths.truncate(MAX_RECENT_FILES);
+    }
+
+    /// Filene som finnes, nyeste først
+    pub fn existing(&self) -> Vec<RecentFile> {
+        self.paths
+            .iter()
+            .filter(|p| p.is_file())
+            .map(|p| RecentFile {
+                path: p.display().to_string(),
+                name: p
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| p.display().to_string()),
+            })
+            .collect()
+    }
+
+    /// Tøm listen
+    ///
+    /// # Returns
+    /// Antall filer som ble fjernet
+    pub fn clear(&mut self) -> usize {
+        std::mem::take(&mut self.paths).len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn names(recent: &RecentFiles) -> Vec<String> {
+        recent.existing().into_iter().map(|f| f.name).collect()
+    }
+
+    #[test]
+    fn test_add_dedupes_most_recent_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut recent = RecentFiles::default();
+        for name in ["a.md", "b.md", "c.md", "a.md"] {
+            let path = dir.path().join(name);
+            fs::write(&path, "").unwrap();
+            recent.add(&path);
+        }
+        assert_eq!(names(&recent), vec!["a.md", "c.md", "b.md"]);
+
+        for i in 0..MAX_RECENT_FILES {
+            let path = dir.path().join(format!("{}.md", i));
+            fs::write(&path, "").unwrap();
+            recent.add(&path);
+        }
+        let names = names(&recent);
+        assert_eq!(names.len(), MAX_RECENT_FILES);
+        assert_eq!(names[0], format!("{}.md", MAX_RECENT_FILES - 1));
+        assert!(!names.contains(&"a.md".to_string()));
+    }
+
+    #[test]
+    fn test_missing_files_are_hidden_but_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let kept = dir.path().join("notater.md");
+        let gone = dir.path().join("borte.md");
+        fs::write(&kept, "").unwrap();
+        fs::write(&gone, "").unwrap();
+
+        let mut recent = RecentFiles::default();
+        recent.add(&kept);
+        recent.add(&gone);
+        fs::remove_file(&gone).unwrap();
+        assert_eq!(names(&recent), vec!["notater.md"]);
+
+        // Filen kommer tilbake, f.eks. når disken kobles til igjen
+        let store = dir.path().join("recent_files.json");
+        recent.save(&store).unwrap();
+        fs::write(&gone, "").unwrap();
+        let loaded = RecentFiles::load(&store);
+        assert_eq!(names(&loaded), vec!["borte.md", "notater.md"]);
+        assert_eq!(loaded.existing()[1].path, kept.display().to_string());
+    }
+
+    #[test]
+    fn test_clear() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut recent = RecentFiles::default();
+        recent.add(&dir.path().join("a.md"));
+        recent.add(&dir.path().join("b.md"));
+        assert_eq!(recent.clear(), 2);
+        assert_eq!(recent, RecentFiles::default());
+    }
+}
@@ -0,0 +1,260 @@
+//! Mellomlager for rendret markdown
+//!
+//! Frem og tilbake mellom de samme sidene rendres den samme kilden med de
+//! samme opsjonene om og om igjen. For sider fra nettet avhenger
+//! renderingen bare av kilden og `RenderOptions`, så nøkkelen er en hash av
+//! begge: endrede innstillinger gir en ny nøkkel, og ingenting må tømmes.
+//! HTML-en har ingen stiler, så bytte av tema treffer fortsatt. Lokale
+//! dokumenter legger inn bilder fra disk og går utenom lageret.
+//!
+//! Lageret er begrenset både i antall sider og i samlet størrelse, og den
+//! som er brukt minst nylig fjernes først. Hver `AppState` har sitt eget
+//! lager, som renderingen får gjennom `RenderOptions::cache`.
+
+use crate::markdown::{RenderOptions, RenderOutput};
+use parking_lot::Mutex;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::VecDeque;
+
+/// Maksimalt antall rendrede sider
+const MAX_ENTRIES: usize = 32;
+
+/// Maksimal samlet størrelse på HTML-en i lageret
+const MAX_BYTES: usize = 16 * 1024 * 1024;
+
+/// Hash av kilden og opsjonene
+pub type CacheKey = [u8; 32];
+
+/// Tall for å justere grensene
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct RenderCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+    pub bytes: usize,
+}
+
+/// Rendrede sider, den sist brukte bakerst
+#[derive(Debug)]
+pub struct RenderCache {
+    entries: VecDeque<(CacheKey, RenderOutput)>,
+    bytes: usize,
+    max_entries: usize,
+    max_bytes: usize,
+    hits: u64,
+    misses: u64,
+}
+
+impl Default for RenderCache {
+    fn default() -> Self {
+        Self::new(MAX_ENTRIES, MAX_BYTES)
+    }
+}
+
+impl RenderCache {
+    /// # Arguments
+    /// * `max_entries` - Maksimalt antall sider
+    /// * `max_bytes` - Maksimal samlet størrelse på HTML-en
+    pub const fn new(max_entries: usize, max_bytes: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            bytes: 0,
+            max_entries,
+            max_bytes,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Hent en rendret side og marker den som nylig brukt
+    pub fn get(&mut self, key: &CacheKey) -> Option<RenderOutput> {
+        let Some(index) = self.entries.iter().position(|(k, _)| k == key) else {
+            self.misses += 1;
+            return None;
+        };
+        self.hits += 1;
+        let entry = self.entries.remove(index)?;
+        let output = entry.1.clone();
+        self.entries.push_back(entry);
+        Some(output)
+    }
+
+    /// Legg til en rendret side
+    ///
+    /// De minst nylig brukte fjernes til sidene får plass. En side som alene
+    /// er større enn grensen lagres ikke.
+    pub fn insert(&mut self, key: CacheKey, output: RenderOutput) {
+        let size = output.html.len();
+        if size > self.max_bytes || self.max_entries == 0 {
+            return;
+        }
+        if let Some(index) = self.entries.iter().position(|(k, _)| *k == key) {
+            if let Some((_, old)) = self.entries.remove(index) {
+                self.bytes -= old.html.len();
+            }
+        }
+        while self.entries.len() >= self.max_entries || self.bytes + size > self.max_bytes {
+            let Some((_, evicted)) = self.entries.pop_front() else {
+                break;
+            };
+            self.bytes -= evicted.html.len();
+        }
+        self.bytes += size;
+        self.entries.push_back((key, output));
+    }
+
+    pub fn stats(&self) -> RenderCacheStats {
+        RenderCacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.entries.len(),
+            bytes: self.bytes,
+        }
+    }
+
+    /// Glem alle rendrede sider; tallene for treff beholdes
+    ///
+    /// # Returns
+    /// Antall sider som ble glemt
+    pub fn clear(&mut self) -> usize {
+        self.bytes = 0;
+        std::mem::take(&mut self.entries).len()
+    }
+}
+
+/// Nøkkelen for en kilde rendret med gitte opsjoner
+///
+/// # Returns
+/// None hvis opsjonene har en diagramrenderer, som ikke kan sammenlignes
+pub fn cache_key(content: &str, options: &RenderOptions) -> Option<CacheKey> {
+    if options.diagram_renderer.is_some() {
+        return None;
+    }
+    let options = serde_json::to_vec(options).ok()?;
+    let mut hasher = Sha256::new();
+    hasher.update((options.len() as u64).to_le_bytes());
+    hasher.update(&options);
+    hasher.update(content.as_bytes());
+    Some(hasher.finalize().into())
+}
+
+/// Hent en rendret side fra lageret, eller rendre den og lagre resultatet
+///
+/// # Arguments
+/// * `cache` - Lageret
+/// * `key` - Nøkkelen fra `cache_key`; None rendrer uten lageret
+/// * `render` - Selve renderingen
+pub fn get_or_render(
+    cache: &Mutex<RenderCache>,
+    key: Option<CacheKey>,
+    render: impl FnOnce() -> RenderOutput,
+) -> RenderOutput {
+    let Some(key) = key else {
+        return render();
+    };
+    if let Some(output) = cache.lock().get(&key) {
+        return output;
+    }
+    // Låsen holdes ikke mens siden rendres
+    let output = render();
+    cache.lock().insert(key, output.clone());
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(html: &str) -> RenderOutput {
+        RenderOutput {
+            html: html.to_string(),
+            tasks: None,
+            skipped_diagrams: 0,
+            truncated: false,
+        }
+    }
+
+    fn key(n: u8) -> CacheKey {
+        [n; 32]
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let mut cache = RenderCache::new(3, 1024);
+        cache.insert(key(1), output("en"));
+        cache.insert(key(2), output("to"));
+        cache.insert(key(3), output("tre"));
+
+        // Bruk av 1 gjør 2 til den eldste
+        assert_eq!(cache.get(&key(1)).unwrap().html, "en");
+        cache.insert(key(4), output("fire"));
+
+        assert!(cache.get(&key(2)).is_none());
+        assert!(cache.get(&key(1)).is_some());
+        assert!(cache.get(&key(3)).is_some());
+        assert!(cache.get(&key(4)).is_some());
+        assert_eq!(
+            cache.stats(),
+            RenderCacheStats {
+                hits: 4,
+                misses: 1,
+                entries: 3,
+                bytes: 2 + 2 + 3 + 4 - 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_bounded_by_bytes() {
+        let mut cache = RenderCache::new(10, 10);
+        cache.insert(key(1), output("aaaa"));
+        cache.insert(key(2), output("bbbb"));
+        cache.insert(key(3), output("cccc"));
+        assert!(cache.get(&key(1)).is_none());
+        assert_eq!(cache.stats().bytes, 8);
+
+        // For stor til å lagres i det hele tatt
+        cache.insert(key(4), output("ddddddddddd"));
+        assert!(cache.get(&key(4)).is_none());
+        assert_eq!(cache.stats().entries, 2);
+
+        // Samme nøkkel erstatter uten å telle dobbelt
+        cache.insert(key(2), output("bb"));
+        assert_eq!(cache.stats().bytes, 6);
+    }
+
+    #[test]
+    fn test_differing_options_miss() {
+        let plain = RenderOptions::default();
+        let stripped = RenderOptions {
+            strip_images: true,
+            ..Default::default()
+        };
+        let remote = RenderOptions::with_base("https://a.example/side.md");
+        let english = RenderOptions {
+            locale: crate::locale::Locale::En,
+            ..Default::default()
+        };
+
+        let content = "# Side\n\n![Bilde](bilde.png)";
+        let keys = [
+            cache_key(content, &plain).unwrap(),
+            cache_key(content, &stripped).unwrap(),
+            cache_key(content, &remote).unwrap(),
+            cache_key(content, &english).unwrap(),
+            cache_key("# Annen side", &plain).unwrap(),
+        ];
+        for (i, a) in keys.iter().enumerate() {
+            for b in &keys[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+        assert_eq!(cache_key(content, &plain), cache_key(content, &plain));
+
+        let mut cache = RenderCache::new(4, 1024);
+        cache.insert(keys[0], output("<h1>Side</h1>"));
+        assert!(cache.get(&keys[1]).is_none());
+        assert!(cache.get(&keys[0]).is_some());
+    }
+}
@@ -0,0 +1,271 @@
+//! Enkel uskarp søking i titler og adresser
+//!
+//! Felles for bokmerke- og historikksøk. Sammenligningen skiller ikke mellom
+//! store og små bokstaver. Et sammenhengende treff rangeres alltid foran et
+//! treff der tegnene bare står i riktig rekkefølge med mellomrom mellom.
+
+use serde::{Deserialize, Serialize};
+
+/// Hvor mye et uskarpt treff kan spre seg, målt i lengden på søket
+///
+/// Uten en grense ville nesten ethvert kort søk treffe lange adresser.
+const MAX_SPREAD: usize = 3;
+
+/// Hvordan søket traff teksten, det beste først
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchKind {
+    /// Teksten begynner med søket
+    Prefix,
+    /// Søket står sammenhengende et sted i teksten
+    Substring,
+    /// Tegnene i søket står i riktig rekkefølge, men ikke sammenhengende
+    Subsequence,
+}
+
+impl MatchKind {
+    /// Poeng for treffet; forskjellen mellom to typer er alltid minst 1
+    pub fn score(self) -> f64 {
+        match self {
+            Self::Prefix => 3.0,
+            Self::Substring => 2.0,
+            Self::Subsequence => 1.0,
+        }
+    }
+}
+
+/// Et treff i teksten, som tegnposisjoner `[start, end)`
+///
+/// Posisjonene teller tegn, ikke bytes, slik at frontend kan utheve treffet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Resultatet av et søk i én tekst
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextMatch {
+    pub kind: MatchKind,
+    pub ranges: Vec<MatchRange>,
+}
+
+/// Små bokstaver tegn for tegn, slik at posisjonene beholdes
+///
+/// Brukes til å forberede tekst som søkes i mange ganger, se [`find_folded`].
+pub fn folded(text: &str) -> Vec<char> {
+    text.chars()
+        .map(|c| c.to_lowercase().next().unwrap_or(c))
+        .collect()
+}
+
+/// Søk etter `query` i `text`
+///
+/// # Returns
+/// Det beste treffet, eller None hvis søket er tomt eller ikke treffer
+pub fn find(text: &str, query: &str) -> Option<TextMatch> {
+    find_folded(&folded(text), &folded(query.trim()))
+}
+
+/// Som [`find`], men for tekst og søk som allerede er gjort om med [`folded`]
+///
+/// # Returns
+/// Det beste treffet, eller None hvis søket er tomt eller ikke treffer
+pub fn find_folded(text: &[char], query: &[char]) -> Option<TextMatch> {
+    if query.is_empty() {
+        return None;
+    }
+
+    if let Some(start) = text.windows(query.len()).position(|w| w == query) {
+        let kind = if start == 0 {
+            MatchKind::Prefix
+        } else {
+            MatchKind::Substring
+        };
+        return Some(TextMatch {
+            kind,
+            ranges: vec![MatchRange {
+                start,
+                end: start + query.len(),
+            }],
+        });
+    }
+
+    let mut ranges: Vec<MatchRange> = Vec::new();
+    let mut remaining = query.iter().peekable();
+    for (i, c) in text.iter().enumerate() {
+        let Some(&&wanted) = remaining.peek() else {
+            break;
+        };
+        if *c != wanted {
+            continue;
+        }
+        remaining.next();
+        match ranges.last_mut() {
+            Some(range) if range.end == i => range.end = i + 1,
+            _ => ranges.push(MatchRange {
+                start: i,
+                end: i + 1,
+            }),
+        }
+    }
+    if remaining.peek().is_some() {
+        return None;
+    }
+
+    let spread = ranges.last()?.end - ranges.first()?.start;
+    (spread <= query.len() * MAX_SPREAD).then_some(TextMatch {
+        kind: MatchKind::Subsequence,
+        ranges,
+    })
+}
+
+/// Søk etter `query` i en adresse
+///
+/// Protokollen og "www." hoppes over når treffet vurderes, slik at
+/// "example" er et prefiks-treff i "https://www.example.com". Posisjonene
+/// gjelder hele adressen.
+pub fn find_in_url(url: &str, query: &str) -> Option<TextMatch> {
+    find_in_url_folded(&folded(url), address_offset(url), &folded(query.trim()))
+}
+
+/// Hvor adressen begynner etter protokollen og "www.", i tegn
+pub fn address_offset(url: &str) -> usize {
+    let mut address_start = url.find("://").map_or(0, |i| i + 3);
+    if url[address_start..]
+        .get(..4)
+        .is_some_and(|www| www.eq_ignore_ascii_case("www."))
+    {
+        address_start += 4;
+    }
+    url[..address_start].chars().count()
+}
+
+/// Som [`find_in_url`], men for en adresse som allerede er gjort om med
+/// [`folded`]
+///
+/// # Arguments
+/// * `url` - Adressen
+/// * `offset` - Starten på adressen fra [`address_offset`]
+/// * `query` - Søket
+pub fn find_in_url_folded(url: &[char], offset: usize, query: &[char]) -> Option<TextMatch> {
+    if let Some(mut found) = find_folded(&url[offset..], query) {
+        for range in &mut found.ranges {
+            range.start += offset;
+            range.end += offset;
+        }
+        return Some(found);
+    }
+
+    // Treff i protokollen, f.eks. "gemini"
+    find_folded(url, query).map(|found| TextMatch {
+        kind: found.kind.max(MatchKind::Substring),
+        ..found
+    })
+}
+
+/// Ekstra poeng for nylige besøk, alltid under 0,5
+///
+/// Holdes lavere enn forskjellen mellom to treff-typer, slik at et ferskt
+/// uskarpt treff aldri går foran et gammelt sammenhengende.
+///
+/// # Arguments
+/// * `age_secs` - Hvor lenge siden besøket var
+pub fn recency_boost(age_secs: u64) -> f64 {
+    let days = age_secs as f64 / (24.0 * 60.0 * 60.0);
+    0.5 / (1.0 + days / 7.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Expected = Option<(MatchKind, Vec<MatchRange>)>;
+
+    fn range(start: usize, end: usize) -> MatchRange {
+        MatchRange { start, end }
+    }
+
+    #[test]
+    fn test_find_table() {
+        let cases: [(&str, &str, Expected); 9] = [
+            (
+                "Rust-boka",
+                "rust",
+                Some((MatchKind::Prefix, vec![range(0, 4)])),
+            ),
+            (
+                "Lær Rust",
+                "RUST",
+                Some((MatchKind::Substring, vec![range(4, 8)])),
+            ),
+            (
+                "Ærlig talt",
+                "ærlig",
+                Some((MatchKind::Prefix, vec![range(0, 5)])),
+            ),
+            (
+                "Σοφία",
+                "ΣΟΦΊΑ",
+                Some((MatchKind::Prefix, vec![range(0, 5)])),
+            ),
+            (
+                "Rust Book",
+                "rbook",
+                Some((MatchKind::Subsequence, vec![range(0, 1), range(5, 9)])),
+            ),
+            (
+                "Nyheter",
+                "nyt",
+                Some((MatchKind::Subsequence, vec![range(0, 2), range(4, 5)])),
+            ),
+            ("Nyheter", "tyn", None),
+            // For spredt til å telle som treff
+            ("a-----------------b", "ab", None),
+            ("Nyheter", "  ", None),
+        ];
+
+        for (text, query, expected) in cases {
+            let found = find(text, query).map(|m| (m.kind, m.ranges));
+            assert_eq!(found, expected, "{:?} i {:?}", query, text);
+        }
+    }
+
+    #[test]
+    fn test_find_in_url_table() {
+        let cases = [
+            (
+                "https://www.example.com/",
+                "example",
+                Some((MatchKind::Prefix, 12)),
+            ),
+            (
+                "https://example.com/rust",
+                "rust",
+                Some((MatchKind::Substring, 20)),
+            ),
+            (
+                "gemini://example.org/",
+                "gemini",
+                Some((MatchKind::Substring, 0)),
+            ),
+            ("https://example.com/", "gemini", None),
+        ];
+
+        for (url, query, expected) in cases {
+            let found = find_in_url(url, query).map(|m| (m.kind, m.ranges[0].start));
+            assert_eq!(found, expected, "{:?} i {:?}", query, url);
+        }
+    }
+
+    #[test]
+    fn test_kind_order_beats_recency() {
+        assert!(MatchKind::Prefix < MatchKind::Substring);
+        assert!(MatchKind::Substring < MatchKind::Subsequence);
+
+        let fresh_fuzzy = MatchKind::Subsequence.score() + recency_boost(0);
+        let old_substring = MatchKind::Substring.score() + recency_boost(10_000 * 86_400);
+        assert!(old_substring > fresh_fuzzy);
+        assert!(recency_boost(0) > recency_boost(86_400));
+    }
+}
@@ -2,11 +2,24 @@
 //!
 //! Håndterer lagring og lasting av brukerpreferanser.
 
+use crate::address;
+use crate::bookmarks;
+use crate::history::HistoryLimits;
+use crate::local_files::OpenPolicy;
+use crate::locale::{Locale, Localize};
+use crate::storage;
+use parking_lot::Mutex;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::fs;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::SystemTime;
 use thiserror::Error;
 
+/// Maksimal størrelse på egendefinert CSS (50 KB)
+pub const MAX_CUSTOM_CSS_SIZE: usize = 50 * 1024;
+
 /// Feil som kan oppstå ved innstillingsoperasjoner
 #[derive(Debug, Error)]
 pub enum SettingsError {
@@ -15,9 +28,59 @@ pub enum SettingsError {
 
     #[error("Kunne ikke lagre innstillinger: {0}")]
     Write(String),
+
+    #[error("Ugyldig startside: {0}. Bruk en URL, en filsti, \"welcome\" eller \"bookmarks\".")]
+    InvalidHomepage(String),
+
+    #[error("Ugyldig vertsnavn for nettstedsinnstillinger: {0}")]
+    InvalidSitePattern(String),
+
+    #[error("Ugyldig skriftnavn: {0}. Bruk bokstaver, tall, mellomrom, punktum, - og _.")]
+    InvalidFontName(String),
+
+    #[error("Ugyldig søkemotor: {0}. Bruk en http(s)-adresse med %s der søkeordene skal stå.")]
+    InvalidSearchEngine(String),
+
+    #[error("Ugyldig farge: {0}. Bruk #rgb eller #rrggbb.")]
+    InvalidColor(String),
+
+    #[error(
+        "Ugyldig nedlastingsmappe: {0}. Mappen må finnes eller kunne opprettes, og være skrivbar."
+    )]
+    InvalidDownloadsDir(String),
+
+    #[error("Egendefinert CSS er for stor ({0} bytes, maks {MAX_CUSTOM_CSS_SIZE})")]
+    CssTooLarge(usize),
+}
+
+impl Localize for SettingsError {
+    fn localize_in(&self, locale: Locale) -> String {
+        match self {
+            Self::Read(e) => locale.format("error.settings_read", &[e]),
+            Self::Write(e) => locale.format("error.settings_write", &[e]),
+            Self::InvalidHomepage(value) => locale.format("error.invalid_homepage", &[value]),
+            Self::InvalidSitePattern(pattern) => {
+                locale.format("error.invalid_site_pattern", &[pattern])
+            }
+            Self::InvalidFontName(name) => locale.format("error.invalid_font_name", &[name]),
+            Self::InvalidSearchEngine(value) => {
+                locale.format("error.invalid_search_engine", &[value])
+            }
+            Self::InvalidColor(value) => locale.format("error.invalid_color", &[value]),
+            Self::InvalidDownloadsDir(path) => {
+                locale.format("error.invalid_downloads_dir", &[path])
+            }
+            Self::CssTooLarge(size) => {
+                locale.format("error.css_too_large", &[size, &MAX_CUSTOM_CSS_SIZE])
+            }
+        }
+    }
 }
 
 /// Tema-valg
+///
+/// Ukjente verdier i filen (f.eks. fra en nyere versjon) gir standardtemaet,
+/// se `Settings::from_json`.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum Theme {
@@ -25,9 +88,153 @@ pub enum Theme {
     Light,
     Dark,
     System,
+    /// Varm, papirlignende lesebakgrunn
+    Sepia,
+}
+
+impl Theme {
+    /// Tolk et temanavn som "dark" eller "sepia"
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "light" => Some(Self::Light),
+            "dark" => Some(Self::Dark),
+            "system" => Some(Self::System),
+            "sepia" => Some(Self::Sepia),
+            _ => None,
+        }
+    }
+}
+
+/// Hvor mye som logges, se `logging`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    /// Tolk et nivå som "warn" eller "debug"
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "error" => Some(Self::Error),
+            "warn" => Some(Self::Warn),
+            "info" => Some(Self::Info),
+            "debug" => Some(Self::Debug),
+            "trace" => Some(Self::Trace),
+            _ => None,
+        }
+    }
+
+    /// Det tilsvarende filteret i `log`
+    pub fn filter(self) -> log::LevelFilter {
+        match self {
+            Self::Error => log::LevelFilter::Error,
+            Self::Warn => log::LevelFilter::Warn,
+            Self::Info => log::LevelFilter::Info,
+            Self::Debug => log::LevelFilter::Debug,
+            Self::Trace => log::LevelFilter::Trace,
+        }
+    }
+}
+
+/// Egne farger som legges over temaet
+///
+/// Hver farge er `#rgb` eller `#rrggbb`; felter som mangler tar fargen
+/// fra temaet.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct ThemeColors {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accent: Option<String>,
+}
+
+impl ThemeColors {
+    fn fields(&self) -> [&Option<String>; 4] {
+        [&self.background, &self.text, &self.link, &self.accent]
+    }
+
+    /// Om ingen farger er satt
+    pub fn is_empty(&self) -> bool {
+        self.fields().iter().all(|color| color.is_none())
+    }
+
+    /// Sjekk at alle fargene er gyldige hex-farger
+    ///
+    /// # Returns
+    /// SettingsError::InvalidColor med den første ugyldige fargen
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        match self
+            .fields()
+            .into_iter()
+            .flatten()
+            .find(|color| !is_valid_hex_color(color))
+        {
+            Some(color) => Err(SettingsError::InvalidColor(color.clone())),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Sjekk at en farge er `#rgb` eller `#rrggbb`
+///
+/// Bare slike verdier slipper gjennom, så en farge aldri kan bære med seg
+/// annen CSS.
+pub fn is_valid_hex_color(color: &str) -> bool {
+    let Some(hex) = color.strip_prefix('#') else {
+        return false;
+    };
+    matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Ikoner foran lenker i generert innhold (f.eks. gophermenyer)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Decorations {
+    /// Emoji som 📁 og 📄
+    #[default]
+    Emoji,
+    /// Tekst i klammer som [DIR], greit for skjermlesere
+    Text,
+    /// Ingen ikoner, bare lenkene
+    None,
+}
+
+/// Hvordan ren tekst (.txt, text/plain) vises
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PlainText {
+    /// Som den er, i en kodeblokk med fast bredde
+    #[default]
+    Preformatted,
+    /// Som avsnitt med vanlig skrift; markdown-tegn vises som tekst
+    Paragraphs,
+}
+
+/// Protokollen adresser uten protokoll åpnes med, f.eks. `example.com`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DefaultScheme {
+    #[default]
+    Https,
+    Gemini,
+    /// La brukeren velge mellom https:// og gemini://
+    Ask,
 }
 
 /// Skrifttype-valg
+///
+/// Forhåndsvalgene lagres som tekst ("serif"), en egen skrift som
+/// `{"custom": "Navn"}`.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum FontFamily {
@@ -36,6 +243,60 @@ pub enum FontFamily {
     Serif,
     SansSerif,
     Mono,
+    /// En installert skrift, f.eks. "Literata"
+    Custom(String),
+}
+
+/// Maks lengde på et egendefinert skriftnavn
+const MAX_FONT_NAME_LENGTH: usize = 64;
+
+impl FontFamily {
+    /// Lag et egendefinert skriftvalg
+    ///
+    /// Navnet settes inn i CSS, så bare bokstaver, tall, mellomrom, punktum,
+    /// bindestrek og understrek er tillatt.
+    ///
+    /// # Returns
+    /// SettingsError::InvalidFontName hvis navnet er tomt, for langt eller
+    /// inneholder andre tegn
+    pub fn custom(name: &str) -> Result<Self, SettingsError> {
+        let name = name.trim();
+        if is_valid_font_name(name) {
+            Ok(Self::Custom(name.to_string()))
+        } else {
+            Err(SettingsError::InvalidFontName(name.to_string()))
+        }
+    }
+
+    /// Navnet på den egendefinerte skriften, hvis valgt
+    pub fn custom_name(&self) -> Option<&str> {
+        match self {
+            Self::Custom(name) => Some(name),
+            _ => None,
+        }
+    }
+}
+
+/// Sjekk at et skriftnavn er trygt å sette inn i CSS
+fn is_valid_font_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.chars().count() <= MAX_FONT_NAME_LENGTH
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, ' ' | '.' | '-' | '_'))
+}
+
+/// Hent navnene på skriftfamiliene som er installert, sortert og uten duplikater
+pub fn system_font_families() -> Vec<String> {
+    let mut db = fontdb::Database::new();
+    db.load_system_fonts();
+
+    let families: std::collections::BTreeSet<String> = db
+        .faces()
+        .filter_map(|face| face.families.first().map(|(name, _)| name.clone()))
+        .filter(|name| is_valid_font_name(name))
+        .collect();
+    families.into_iter().collect()
 }
 
 /// Konverteringsmodus for nettsider
@@ -51,6 +312,411 @@ pub enum ConversionMode {
     AskEverytime,
 }
 
+/// Konverteringsmodus for et domene, f.eks. aldri konvertere intranettet
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DomainRule {
+    /// Vertsnavn eller `*.vert` for alle underdomener
+    pub pattern: String,
+    pub mode: ConversionMode,
+}
+
+/// Konverteringsvalg per protokoll
+///
+/// Gemini og Gopher vises som de er; bare HTTP-sider konverteres.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct ConversionSettings {
+    /// Standardmodus for HTTP-sider
+    #[serde(default)]
+    pub http: ConversionMode,
+
+    /// Regler for enkelte domener; går foran standardmodusen
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<DomainRule>,
+}
+
+impl ConversionSettings {
+    /// Konverteringsmodusen for en vert
+    ///
+    /// Et eksakt vertsnavn går foran jokertegn-mønstre, og lengre
+    /// jokertegn-mønstre går foran kortere. Uten treff gjelder `http`.
+    ///
+    /// # Arguments
+    /// * `host` - Vertsnavnet, eller None for lokale filer
+    pub fn mode_for(&self, host: Option<&str>) -> ConversionMode {
+        let Some(host) = host else {
+            return self.http.clone();
+        };
+        let host = host.trim_end_matches('.').to_lowercase();
+        self.rules
+            .iter()
+            .filter_map(|rule| site_pattern_rank(&rule.pattern, &host).map(|rank| (rank, rule)))
+            .max_by_key(|(rank, _)| *rank)
+            .map(|(_, rule)| rule.mode.clone())
+            .unwrap_or_else(|| self.http.clone())
+    }
+
+    /// Legg til en regel for et domene, eller erstatt regelen som finnes
+    ///
+    /// # Arguments
+    /// * `pattern` - Vertsnavn eller `*.vert` for alle underdomener
+    /// * `mode` - Konverteringsmodusen for domenet
+    ///
+    /// # Returns
+    /// Det normaliserte mønsteret, eller SettingsError::InvalidSitePattern
+    pub fn set_rule(
+        &mut self,
+        pattern: &str,
+        mode: ConversionMode,
+    ) -> Result<String, SettingsError> {
+        let pattern = normalize_site_pattern(pattern)
+            .ok_or_else(|| SettingsError::InvalidSitePattern(pattern.to_string()))?;
+        match self.rules.iter_mut().find(|rule| rule.pattern == pattern) {
+            Some(rule) => rule.mode = mode,
+            None => self.rules.push(DomainRule {
+                pattern: pattern.clone(),
+                mode,
+            }),
+        }
+        Ok(pattern)
+    }
+
+    /// Fjern regelen for et domene
+    ///
+    /// # Returns
+    /// `true` hvis det fantes en regel
+    pub fn remove_rule(&mut self, pattern: &str) -> bool {
+        let Some(pattern) = normalize_site_pattern(pattern) else {
+            return false;
+        };
+        let before = self.rules.len();
+        self.rules.retain(|rule| rule.pattern != pattern);
+        self.rules.len() != before
+    }
+
+    /// Normaliser mønstrene, fjern ugyldige og behold siste regel per mønster
+    fn normalized(self) -> Self {
+        let mut rules: Vec<DomainRule> = Vec::new();
+        for rule in self.rules {
+            let Some(pattern) = normalize_site_pattern(&rule.pattern) else {
+                log::warn!("Ignorerer ugyldig domeneregel: {}", rule.pattern);
+                continue;
+            };
+            rules.retain(|existing| existing.pattern != pattern);
+            rules.push(DomainRule {
+                pattern,
+                mode: rule.mode,
+            });
+        }
+        Self {
+            http: self.http,
+            rules,
+        }
+    }
+}
+
+/// Hvilke innstillinger som tilbakestilles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ResetScope {
+    /// Alle innstillinger
+    All,
+    /// Tema, skrift, typografi, innholdsbredde, zoom og egne farger
+    Appearance,
+    /// Tidsavbrudd og størrelsesgrenser
+    Network,
+    /// Personvern, inkludert historikk-bryteren
+    Privacy,
+    /// Overstyringer per nettsted
+    SiteOverrides,
+}
+
+/// Hvor en nedlasting skal lagres
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum DownloadTarget {
+    /// Lagre direkte til denne filen
+    Direct { path: PathBuf },
+    /// Spør brukeren med en lagringsdialog som starter i `default_dir`
+    Ask { default_dir: Option<PathBuf> },
+}
+
+/// Hva som vises når appen starter eller brukeren går hjem
+#[derive(Debug, Clone, PartialEq)]
+pub enum HomePage {
+    /// Den innebygde velkomstsiden
+    Welcome,
+    /// Bokmerkesiden
+    Bookmarks,
+    /// En side på nett (http, https, gemini eller gopher)
+    Url(String),
+    /// En lokal fil
+    File(PathBuf),
+}
+
+impl HomePage {
+    /// Tolk en startside fra innstillingene
+    ///
+    /// Godtar `welcome`, `bookmarks`, URL-er med støttet protokoll,
+    /// `file://`-URL-er og absolutte stier (også `~/...`).
+    ///
+    /// # Returns
+    /// None hvis verdien ikke er en gyldig startside
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        match value {
+            "" | "welcome" => return Some(Self::Welcome),
+            "bookmarks" | "bare://bookmarks" => return Some(Self::Bookmarks),
+            _ => {}
+        }
+
+        if let Ok(url) = url::Url::parse(value) {
+            match url.scheme() {
+                "http" | "https" | "gemini" | "gopher" if url.host_str().is_some() => {
+                    return Some(Self::Url(value.to_string()))
+                }
+                "file" => return url.to_file_path().ok().map(Self::File),
+                _ => {}
+            }
+        }
+
+        let path = match value.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir()?.join(rest),
+            None => PathBuf::from(value),
+        };
+        path.is_absolute().then_some(Self::File(path))
+    }
+
+    /// Verdien som lagres i innstillingene (None for velkomstsiden)
+    pub fn to_setting(&self) -> Option<String> {
+        match self {
+            Self::Welcome => None,
+            Self::Bookmarks => Some("bookmarks".to_string()),
+            Self::Url(url) => Some(url.clone()),
+            Self::File(path) => Some(path.to_string_lossy().into_owned()),
+        }
+    }
+}
+
+/// Grenser for nettverksforespørsler (HTTP, Gemini og Gopher)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NetworkSettings {
+    /// Hvor lenge vi venter på svar fra en server
+    #[serde(default = "default_timeout_seconds")]
+    pub timeout_seconds: u64,
+
+    /// Største respons som lastes ned, i megabytes
+    #[serde(default = "default_max_response_mb")]
+    pub max_response_mb: u32,
+
+    /// Maksimalt antall redirects som følges
+    #[serde(default = "default_max_redirects")]
+    pub max_redirects: u8,
+}
+
+fn default_timeout_seconds() -> u64 {
+    30
+}
+
+fn default_max_response_mb() -> u32 {
+    5
+}
+
+fn default_max_redirects() -> u8 {
+    5
+}
+
+impl Default for NetworkSettings {
+    fn default() -> Self {
+        Self {
+            timeout_seconds: default_timeout_seconds(),
+            max_response_mb: default_max_response_mb(),
+            max_redirects: default_max_redirects(),
+        }
+    }
+}
+
+impl NetworkSettings {
+    /// Juster verdiene til gyldige områder
+    pub fn clamped(self) -> Self {
+        Self {
+            timeout_seconds: self.timeout_seconds.clamp(1, 300),
+            max_response_mb: self.max_response_mb.clamp(1, 100),
+            max_redirects: self.max_redirects.min(20),
+        }
+    }
+
+    /// Største respons i bytes
+    pub fn max_response_bytes(&self) -> usize {
+        self.max_response_mb as usize * 1024 * 1024
+    }
+}
+
+/// Typografi for lesing av lengre tekster
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Typography {
+    /// Linjehøyde i prosent av skriftstørrelsen
+    #[serde(default = "default_line_height")]
+    pub line_height: u32,
+
+    /// Avstand etter avsnitt i prosent av skriftstørrelsen
+    #[serde(default = "default_paragraph_spacing")]
+    pub paragraph_spacing: u32,
+
+    /// Blokkjuster brødteksten
+    #[serde(default)]
+    pub justify_text: bool,
+}
+
+fn default_line_height() -> u32 {
+    160
+}
+
+fn default_paragraph_spacing() -> u32 {
+    100
+}
+
+impl Default for Typography {
+    fn default() -> Self {
+        Self {
+            line_height: default_line_height(),
+            paragraph_spacing: default_paragraph_spacing(),
+            justify_text: false,
+        }
+    }
+}
+
+impl Typography {
+    /// Feltene som ligger i `typography`
+    const FIELDS: [&'static str; 3] = ["line_height", "paragraph_spacing", "justify_text"];
+
+    /// Juster verdiene til gyldige områder
+    pub fn clamped(self) -> Self {
+        Self {
+            line_height: self.line_height.clamp(120, 220),
+            paragraph_spacing: self.paragraph_spacing.clamp(0, 300),
+            justify_text: self.justify_text,
+        }
+    }
+}
+
+/// Innstillinger som påvirker hva andre får vite om brukeren
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PrivacySettings {
+    /// Last bilder fra nettet; ellers vises alternativ tekst
+    #[serde(default = "default_load_remote_images")]
+    pub load_remote_images: bool,
+
+    /// Fjern sporingsparametere (utm_*, fbclid, ...) fra adresser før de hentes
+    #[serde(default)]
+    pub strip_query_params: bool,
+
+    /// Send opprinnelsen til siden en lenke ble fulgt fra som Referer
+    #[serde(default)]
+    pub send_referrer: bool,
+
+    /// Be om å ikke bli sporet med `Sec-GPC: 1` og `DNT: 1`
+    #[serde(default)]
+    pub send_gpc: bool,
+
+    /// Hent lenker brukeren holder musen over, før de klikkes
+    ///
+    /// Av som standard, siden serveren da får vite om lenker brukeren
+    /// aldri åpner.
+    #[serde(default)]
+    pub prefetch: bool,
+}
+
+fn default_load_remote_images() -> bool {
+    true
+}
+
+impl Default for PrivacySettings {
+    fn default() -> Self {
+        Self {
+            load_remote_images: default_load_remote_images(),
+            strip_query_params: false,
+            send_referrer: false,
+            send_gpc: false,
+            prefetch: false,
+        }
+    }
+}
+
+impl PrivacySettings {
+    /// Om endringen fra `old` krever en ny HTTP-klient
+    pub fn needs_new_client(&self, old: &Self) -> bool {
+        self.send_referrer != old.send_referrer || self.send_gpc != old.send_gpc
+    }
+}
+
+/// Innstillinger som kan overstyres for et enkelt nettsted
+///
+/// Felter som er `None` arver fra de globale innstillingene.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct SiteOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversion_mode: Option<ConversionMode>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub readability_enabled: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font_size: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zoom: Option<u32>,
+
+    /// Vis bilder som alternativ tekst
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strip_images: Option<bool>,
+
+    /// Protokollen brukeren har valgt for verten i adresselinjen
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_scheme: Option<DefaultScheme>,
+}
+
+impl SiteOverride {
+    /// Om overstyringen ikke endrer noe
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Legg overstyringene over `site`
+    fn apply(&self, site: &mut SiteSettings) {
+        if let Some(mode) = &self.conversion_mode {
+            site.conversion_mode = mode.clone();
+        }
+        if let Some(readability) = self.readability_enabled {
+            site.readability_enabled = readability;
+        }
+        if let Some(size) = self.font_size {
+            site.font_size = size;
+        }
+        if let Some(zoom) = self.zoom {
+            site.zoom = zoom;
+        }
+        if let Some(strip) = self.strip_images {
+            site.strip_images = strip;
+        }
+        if let Some(scheme) = self.default_scheme {
+            site.default_scheme = scheme;
+        }
+    }
+}
+
+/// Innstillingene som gjelder for et bestemt nettsted
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SiteSettings {
+    pub conversion_mode: ConversionMode,
+    pub readability_enabled: bool,
+    pub font_size: u32,
+    pub zoom: u32,
+    pub strip_images: bool,
+    pub default_scheme: DefaultScheme,
+    /// Overstyringene som ble brukt, fra minst til mest spesifikk
+    pub matched: Vec<String>,
+}
+
 /// Brukerinnstillinger
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
@@ -66,10 +732,18 @@ pub struct Settings {
     #[serde(default = "default_zoom")]
     pub zoom: u32,
 
+    /// Prosentpoeng per zoom-steg (5–50)
+    #[serde(default = "default_zoom_step")]
+    pub zoom_step: u32,
+
     /// Skrifttype
     #[serde(default)]
     pub font_family: FontFamily,
 
+    /// Egne farger over temaet; None bruker temaets farger
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_colors: Option<ThemeColors>,
+
     /// Maks innholdsbredde i piksler
     #[serde(default = "default_content_width")]
     pub content_width: u32,
@@ -78,14 +752,38 @@ pub struct Settings {
     #[serde(default)]
     pub show_line_numbers: bool,
 
-    /// Konverteringsmodus for HTML-sider
+    /// Vis filer og mapper som starter med punktum i mappefortegnelser
     #[serde(default)]
-    pub conversion_mode: ConversionMode,
+    pub show_hidden_files: bool,
+
+    /// Største lokale fil som åpnes, i megabytes
+    #[serde(default = "default_max_file_mb")]
+    pub max_file_mb: u32,
+
+    /// Største dokument som rendres, i megabytes; resten kuttes bort
+    #[serde(default = "default_max_render_mb")]
+    pub max_render_mb: u32,
+
+    /// Følg symbolske lenker når lokale filer åpnes
+    #[serde(default = "default_follow_symlinks")]
+    pub follow_symlinks: bool,
+
+    /// Konverteringsmodus for HTML-sider, med regler per domene
+    #[serde(default)]
+    pub conversion: ConversionSettings,
 
     /// Aktiver readability-modus for å ekstrahere hovedinnhold
     #[serde(default = "default_readability")]
     pub readability_enabled: bool,
 
+    /// Ikoner foran lenker i gophermenyer
+    #[serde(default)]
+    pub decorations: Decorations,
+
+    /// Visning av ren tekst
+    #[serde(default)]
+    pub plain_text: PlainText,
+
     /// Om brukeren har fullført onboarding
     #[serde(default)]
     pub onboarding_completed: bool,
@@ -93,6 +791,108 @@ pub struct Settings {
     /// Språkpreferanse (ISO 639-1 kode, f.eks. "nb", "en", "system")
     #[serde(default = "default_language")]
     pub language: String,
+
+    /// Hvor mye som logges til stderr og loggfilen
+    #[serde(default)]
+    pub log_level: LogLevel,
+
+    /// Kodeblokk-språk som vises som sammenfoldet diagramkilde
+    #[serde(default = "default_diagram_languages")]
+    pub diagram_languages: Vec<String>,
+
+    /// Antall roterte sikkerhetskopier av bokmerker og innstillinger
+    #[serde(default = "default_backup_generations")]
+    pub backup_generations: u32,
+
+    /// Startside: "bookmarks", en URL eller en filsti; None er velkomstsiden
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub homepage: Option<String>,
+
+    /// Søkemotor-mal med `%s` for søkeordene; None er standardmotoren
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search_engine: Option<String>,
+
+    /// Protokoll for adresser som skrives uten protokoll
+    #[serde(default)]
+    pub default_scheme: DefaultScheme,
+
+    /// Mappe for nedlastinger; None er systemets nedlastingsmappe
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub downloads_dir: Option<PathBuf>,
+
+    /// Lagre nedlastinger rett i mappen i stedet for å spørre hvor
+    #[serde(default)]
+    pub use_downloads_dir_without_asking: bool,
+
+    /// Egendefinert CSS som legges over sidens stiler
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_css: Option<String>,
+
+    /// Snarveier brukeren har endret, handling -> snarvei
+    ///
+    /// Handlinger som mangler bruker standardsnarveien, se `keybindings`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub key_bindings: BTreeMap<String, String>,
+
+    /// Linjehøyde, avsnittsavstand og justering
+    #[serde(default)]
+    pub typography: Typography,
+
+    /// Tidsavbrudd og størrelsesgrenser for nettverket
+    #[serde(default)]
+    pub network: NetworkSettings,
+
+    /// Bilder fra nettet, sporingsparametere og Referer
+    #[serde(default)]
+    pub privacy: PrivacySettings,
+
+    /// Husk besøkte sider: historikk, besøkstall, adresseforslag og økter
+    ///
+    /// Av som standard. Når den er av skrives ingenting, og historikk som
+    /// finnes fra før ligger urørt til brukeren sletter den.
+    #[serde(default)]
+    pub history_enabled: bool,
+
+    /// Maks antall sider i historikken; de eldste ryddes bort
+    #[serde(default = "default_history_max_entries")]
+    pub history_max_entries: u32,
+
+    /// Hvor mange døgn historikken beholdes
+    #[serde(default = "default_history_max_age_days")]
+    pub history_max_age_days: u32,
+
+    /// Sider brukeren har skjult fra "ofte besøkt" (normaliserte adresser)
+    ///
+    /// Et valg, ikke historikk: listen blir stående når historikken slettes.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub dismissed_top_sites: BTreeSet<String>,
+
+    /// Overstyringer per vert, f.eks. "docs.example.com" eller "*.example.com"
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub site_overrides: BTreeMap<String, SiteOverride>,
+
+    #[serde(skip)]
+    sync: SyncCell,
+}
+
+/// `SyncState` bak en lås, så `save` kan oppdatere den gjennom `&self`
+/// mens innstillingene deles mellom tråder
+#[derive(Debug, Default)]
+struct SyncCell(Mutex<SyncState>);
+
+impl Clone for SyncCell {
+    fn clone(&self) -> Self {
+        Self(Mutex::new(self.0.lock().clone()))
+    }
+}
+
+/// Forholdet mellom innstillingene i minnet og filen
+#[derive(Debug, Clone, Default)]
+struct SyncState {
+    /// Innstillingene slik de sist ble lest fra eller skrevet til filen
+    base: Option<serde_json::Value>,
+    /// Endringstid og størrelse på filen slik vi sist leste eller skrev den
+    written: Option<(SystemTime, u64)>,
 }
 
 fn default_font_size() -> u32 {
@@ -103,10 +903,26 @@ fn default_zoom() -> u32 {
     100
 }
 
+fn default_zoom_step() -> u32 {
+    10
+}
+
 fn default_content_width() -> u32 {
     800
 }
 
+fn default_max_file_mb() -> u32 {
+    10
+}
+
+fn default_max_render_mb() -> u32 {
+    8
+}
+
+fn default_follow_symlinks() -> bool {
+    true
+}
+
 fn default_readability() -> bool {
     true
 }
@@ -115,77 +931,823 @@ fn default_language() -> String {
     "system".to_string()
 }
 
+fn default_backup_generations() -> u32 {
+    3
+}
+
+fn default_history_max_entries() -> u32 {
+    10_000
+}
+
+fn default_history_max_age_days() -> u32 {
+    90
+}
+
+fn default_diagram_languages() -> Vec<String> {
+    ["mermaid", "plantuml", "graphviz", "dot"]
+        .iter()
+        .map(|l| l.to_string())
+        .collect()
+}
+
 impl Default for Settings {
     fn default() -> Self {
         Self {
             theme: Theme::default(),
             font_size: default_font_size(),
             zoom: default_zoom(),
+            zoom_step: default_zoom_step(),
             font_family: FontFamily::default(),
+            custom_colors: None,
             content_width: default_content_width(),
             show_line_numbers: false,
-            conversion_mode: ConversionMode::default(),
+            show_hidden_files: false,
+            max_file_mb: default_max_file_mb(),
+            max_render_mb: default_max_render_mb(),
+            follow_symlinks: default_follow_symlinks(),
+            conversion: ConversionSettings::default(),
             readability_enabled: default_readability(),
+            decorations: Decorations::default(),
+            plain_text: PlainText::default(),
             onboarding_completed: false,
             language: default_language(),
+            log_level: LogLevel::default(),
+            diagram_languages: default_diagram_languages(),
+            backup_generations: default_backup_generations(),
+            homepage: None,
+            search_engine: None,
+            default_scheme: DefaultScheme::default(),
+            downloads_dir: None,
+            use_downloads_dir_without_asking: false,
+            custom_css: None,
+            key_bindings: BTreeMap::new(),
+            typography: Typography::default(),
+            network: NetworkSettings::default(),
+            privacy: PrivacySettings::default(),
+            history_enabled: false,
+            history_max_entries: default_history_max_entries(),
+            history_max_age_days: default_history_max_age_days(),
+            dismissed_top_sites: BTreeSet::new(),
+            site_overrides: BTreeMap::new(),
+            sync: SyncCell::default(),
         }
     }
 }
 
 impl Settings {
     /// Last innstillinger fra fil
-    pub fn load(path: &PathBuf) -> Result<Self, SettingsError> {
-        if !path.exists() {
-            return Ok(Self::default());
+    pub fn load(path: &Path) -> Result<Self, SettingsError> {
+        match storage::read_json(path).map_err(|e| SettingsError::Read(e.to_string()))? {
+            Some(value) => {
+                let settings = Self::from_json(value)?;
+                settings.mark_synced(path);
+                Ok(settings)
+            }
+            None => Ok(Self::default()),
         }
+    }
 
-        let content = fs::read_to_string(path).map_err(|e| SettingsError::Read(e.to_string()))?;
+    /// Les filen slik den ligger på disk, uten sikkerhetskopi som reserve
+    ///
+    /// Brukes når filen er endret utenfra: en ugyldig fil skal gi en feil,
+    /// ikke erstattes av sikkerhetskopien.
+    pub fn load_external(path: &Path) -> Result<Self, SettingsError> {
+        let value: serde_json::Value =
+            storage::parse_file(path).map_err(|e| SettingsError::Read(e.to_string()))?;
+        let settings = Self::from_json(value)?;
+        settings.mark_synced(path);
+        Ok(settings)
+    }
+
+    /// Husk at innstillingene i minnet nå er de samme som i filen
+    fn mark_synced(&self, path: &Path) {
+        *self.sync.0.lock() = SyncState {
+            base: serde_json::to_value(self).ok(),
+            written: storage::file_stamp(path),
+        };
+    }
+
+    /// Sjekk om filen er slik vi sist leste eller skrev den
+    ///
+    /// Brukes av filovervåkingen for å se bort fra våre egne lagringer.
+    pub fn is_own_write(&self, path: &Path) -> bool {
+        let written = self.sync.0.lock().written;
+        written.is_some() && written == storage::file_stamp(path)
+    }
+
+    /// Legg endringene i minnet oppå en nyere versjon fra fil
+    ///
+    /// Felter som bare er endret i filen hentes derfra, felter som bare er
+    /// endret i minnet beholdes. Er samme felt endret begge steder, vinner
+    /// endringen i minnet. Objekter som `typography` slås sammen felt for felt.
+    ///
+    /// # Arguments
+    /// * `disk` - Innstillingene slik de ligger i filen nå
+    /// * `path` - Filen de ble lest fra
+    ///
+    /// # Returns
+    /// `true` hvis endringer i minnet ble tatt med, slik at innstillingene
+    /// bør lagres igjen
+    pub fn rebase(&mut self, disk: Settings, path: &Path) -> bool {
+        let theirs = serde_json::to_value(&disk).unwrap_or_default();
+        let mine = serde_json::to_value(&*self).unwrap_or_default();
+        let base = self
+            .sync
+            .0
+            .get_mut()
+            .base
+            .take()
+            .unwrap_or_else(|| mine.clone());
+
+        let merged = merge_json(&base, &mine, &theirs);
+        let has_unsaved = merged != theirs;
+        let mut rebased = match Self::from_json(merged) {
+            Ok(settings) => settings,
+            Err(e) => {
+                log::warn!("Kunne ikke slå sammen innstillinger: {}", e);
+                disk
+            }
+        };
+        *rebased.sync.0.get_mut() = SyncState {
+            base: Some(theirs),
+            written: storage::file_stamp(path),
+        };
+        *self = rebased;
+        has_unsaved
+    }
+
+    /// Les innstillinger fra JSON, også fra filer med eldre oppsett
+    ///
+    /// Typografifelter som ligger flatt på toppnivå flyttes inn i
+    /// `typography`; verdier som allerede ligger der går foran.
+    pub fn from_json(mut value: serde_json::Value) -> Result<Self, SettingsError> {
+        if let Some(root) = value.as_object_mut() {
+            // Et tema fra en nyere versjon skal ikke gjøre hele filen ugyldig
+            if let Some(theme) = root.get("theme").and_then(|t| t.as_str()) {
+                if Theme::parse(theme).is_none() {
+                    log::warn!("Ukjent tema {}, bruker standardtemaet", theme);
+                    root.remove("theme");
+                }
+            }
+            if let Some(level) = root.get("log_level").and_then(|l| l.as_str()) {
+                if LogLevel::parse(level).is_none() {
+                    log::warn!("Ukjent loggnivå {}, bruker standardnivået", level);
+                    root.remove("log_level");
+                }
+            }
+
+            // Eldre filer har én konverteringsmodus for alle sider
+            if let Some(mode) = root.remove("conversion_mode") {
+                root.entry("conversion")
+                    .or_insert_with(|| serde_json::json!({ "http": mode }));
+            }
+
+            let flat: serde_json::Map<_, _> = Typography::FIELDS
+                .iter()
+                .filter_map(|field| root.remove(*field).map(|v| (field.to_string(), v)))
+                .collect();
+
+            if !flat.is_empty() {
+                let typography = root
+                    .entry("typography")
+                    .or_insert_with(|| serde_json::Value::Object(Default::default()));
+                if let Some(typography) = typography.as_object_mut() {
+                    for (field, v) in flat {
+                        typography.entry(field).or_insert(v);
+                    }
+                }
+            }
+        }
+
+        let mut settings: Self =
+            serde_json::from_value(value).map_err(|e| SettingsError::Read(e.to_string()))?;
+        settings.typography = settings.typography.clamped();
+        settings.conversion = settings.conversion.normalized();
+
+        // Et skriftnavn redigert for hånd må ikke slippe gjennom til CSS
+        if let Some(name) = settings.font_family.custom_name() {
+            if !is_valid_font_name(name) {
+                log::warn!("Ignorerer ugyldig skriftnavn: {}", name);
+                settings.font_family = FontFamily::System;
+            }
+        }
 
-        serde_json::from_str(&content).map_err(|e| SettingsError::Read(e.to_string()))
+        // Det samme gjelder farger
+        if let Some(colors) = &settings.custom_colors {
+            if let Err(e) = colors.validate() {
+                log::warn!("Ignorerer egne farger: {}", e);
+                settings.custom_colors = None;
+            }
+        }
+        Ok(settings)
     }
 
     /// Lagre innstillinger til fil
-    pub fn save(&self, path: &PathBuf) -> Result<(), SettingsError> {
-        // Opprett overordnede mapper hvis de ikke finnes
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent).map_err(|e| SettingsError::Write(e.to_string()))?;
+    ///
+    /// Skrives atomisk, så en avbrutt lagring etterlater den forrige filen.
+    /// Forrige versjon beholdes som rotert sikkerhetskopi.
+    pub fn save(&self, path: &Path) -> Result<(), SettingsError> {
+        storage::rotate_backups(path, self.backup_generations)
+            .map_err(|e| SettingsError::Write(e.to_string()))?;
+        storage::write_json(path, self).map_err(|e| SettingsError::Write(e.to_string()))?;
+        self.mark_synced(path);
+        Ok(())
+    }
+
+    /// Sett egne farger over temaet
+    ///
+    /// None eller farger uten noen verdier tilbakestiller til temaets farger.
+    ///
+    /// # Returns
+    /// SettingsError::InvalidColor hvis en farge ikke er `#rgb` eller `#rrggbb`
+    pub fn set_custom_colors(&mut self, colors: Option<ThemeColors>) -> Result<(), SettingsError> {
+        let colors = colors.map(|colors| ThemeColors {
+            background: normalize_color(colors.background),
+            text: normalize_color(colors.text),
+            link: normalize_color(colors.link),
+            accent: normalize_color(colors.accent),
+        });
+        if let Some(colors) = &colors {
+            colors.validate()?;
+        }
+        self.custom_colors = colors.filter(|colors| !colors.is_empty());
+        Ok(())
+    }
+
+    /// Sett egendefinert CSS
+    ///
+    /// Tom tekst fjerner innstillingen. Eksterne ressurser fjernes, se
+    /// [`sanitize_css`].
+    ///
+    /// # Returns
+    /// SettingsError::CssTooLarge hvis teksten er over [`MAX_CUSTOM_CSS_SIZE`]
+    pub fn set_custom_css(&mut self, css: &str) -> Result<(), SettingsError> {
+        if css.len() > MAX_CUSTOM_CSS_SIZE {
+            return Err(SettingsError::CssTooLarge(css.len()));
+        }
+        let css = sanitize_css(css);
+        self.custom_css = (!css.trim().is_empty()).then_some(css);
+        Ok(())
+    }
+
+    /// Hent CSS-en som skal brukes på sidene
+    ///
+    /// `user.css` ved siden av settings.json går foran innstillingen.
+    ///
+    /// # Arguments
+    /// * `user_css` - Stien til `user.css`
+    pub fn effective_css(&self, user_css: &Path) -> Option<String> {
+        match std::fs::read_to_string(user_css) {
+            Ok(css) if css.len() <= MAX_CUSTOM_CSS_SIZE => Some(sanitize_css(&css)),
+            Ok(css) => {
+                log::warn!(
+                    "Ignorerer {}: {}",
+                    user_css.display(),
+                    SettingsError::CssTooLarge(css.len())
+                );
+                self.custom_css.clone()
+            }
+            Err(_) => self.custom_css.clone(),
+        }
+    }
+
+    /// Startsiden; en ugyldig verdi i filen gir velkomstsiden
+    pub fn home_page(&self) -> HomePage {
+        self.homepage
+            .as_deref()
+            .and_then(HomePage::parse)
+            .unwrap_or(HomePage::Welcome)
+    }
+
+    /// Sett startsiden
+    ///
+    /// # Returns
+    /// SettingsError::InvalidHomepage hvis verdien ikke kan tolkes
+    pub fn set_homepage(&mut self, value: &str) -> Result<(), SettingsError> {
+        let home = HomePage::parse(value)
+            .ok_or_else(|| SettingsError::InvalidHomepage(value.to_string()))?;
+        self.homepage = home.to_setting();
+        Ok(())
+    }
+
+    /// Største markdown-kilde som rendres, i bytes
+    pub fn max_render_bytes(&self) -> usize {
+        self.max_render_mb as usize * 1024 * 1024
+    }
+
+    /// Reglene for hvilke lokale filer som kan åpnes
+    pub fn open_policy(&self) -> OpenPolicy {
+        OpenPolicy {
+            max_size: u64::from(self.max_file_mb) * 1024 * 1024,
+            follow_symlinks: self.follow_symlinks,
+        }
+    }
+
+    /// Grensene for hvor mye historikk som beholdes
+    pub fn history_limits(&self) -> HistoryLimits {
+        HistoryLimits {
+            max_entries: self.history_max_entries as usize,
+            max_age_days: self.history_max_age_days,
+        }
+    }
+
+    /// Skjul en side fra "ofte besøkt" og "nylig besøkt"
+    ///
+    /// # Returns
+    /// true hvis siden ikke var skjult fra før
+    pub fn dismiss_top_site(&mut self, url: &str) -> bool {
+        self.dismissed_top_sites
+            .insert(bookmarks::normalize_url(url))
+    }
+
+    /// Tilbakestill en gruppe innstillinger til standardverdiene
+    ///
+    /// Alt utenfor gruppen beholdes.
+    ///
+    /// # Arguments
+    /// * `scope` - Gruppen som skal tilbakestilles
+    /// * `keep_onboarding` - Ved `All`: behold at onboarding er fullført
+    pub fn reset(&mut self, scope: ResetScope, keep_onboarding: bool) {
+        let defaults = Settings::default();
+        match scope {
+            ResetScope::All => {
+                let onboarding_completed = keep_onboarding && self.onboarding_completed;
+                // Synkroniseringen med filen gjelder fortsatt
+                let sync = std::mem::take(&mut self.sync);
+                *self = Settings {
+                    onboarding_completed,
+                    sync,
+                    ..defaults
+                };
+            }
+            ResetScope::Appearance => {
+                self.theme = defaults.theme;
+                self.font_size = defaults.font_size;
+                self.font_family = defaults.font_family;
+                self.typography = defaults.typography;
+                self.content_width = defaults.content_width;
+                self.zoom = defaults.zoom;
+                self.zoom_step = defaults.zoom_step;
+                self.custom_colors = defaults.custom_colors;
+            }
+            ResetScope::Network => self.network = defaults.network,
+            ResetScope::Privacy => {
+                self.privacy = defaults.privacy;
+                self.history_enabled = defaults.history_enabled;
+                self.history_max_entries = defaults.history_max_entries;
+                self.history_max_age_days = defaults.history_max_age_days;
+            }
+            ResetScope::SiteOverrides => self.site_overrides = defaults.site_overrides,
+        }
+    }
+
+    /// Nedlastingsmappen som gjelder: den valgte, ellers systemets
+    pub fn effective_downloads_dir(&self) -> Option<PathBuf> {
+        self.downloads_dir.clone().or_else(dirs::download_dir)
+    }
+
+    /// Sett nedlastingsmappen
+    ///
+    /// Mappen opprettes hvis foreldermappen finnes. Tom verdi gir systemets
+    /// nedlastingsmappe.
+    ///
+    /// # Returns
+    /// SettingsError::InvalidDownloadsDir hvis stien ikke er absolutt, ikke
+    /// kan opprettes eller ikke er skrivbar
+    pub fn set_downloads_dir(&mut self, value: &str) -> Result<(), SettingsError> {
+        let value = value.trim();
+        if value.is_empty() {
+            self.downloads_dir = None;
+            return Ok(());
         }
+        let path = PathBuf::from(value);
+        prepare_downloads_dir(&path)?;
+        self.downloads_dir = Some(path);
+        Ok(())
+    }
+
+    /// Hvor en nedlasting skal lagres
+    ///
+    /// Filen lagres direkte bare når brukeren har bedt om det og mappen er
+    /// skrivbar. Ellers skal brukeren spørres, med mappen som utgangspunkt
+    /// hvis den kan brukes.
+    ///
+    /// # Arguments
+    /// * `file_name` - Foreslått filnavn; bare selve navnet brukes
+    pub fn download_target(&self, file_name: &str) -> DownloadTarget {
+        let dir = self
+            .effective_downloads_dir()
+            .filter(|dir| is_writable_dir(dir));
+        match dir {
+            Some(dir) if self.use_downloads_dir_without_asking => DownloadTarget::Direct {
+                path: dir.join(safe_file_name(file_name)),
+            },
+            default_dir => DownloadTarget::Ask { default_dir },
+        }
+    }
+
+    /// Søkemotoren som brukes fra adresselinjen
+    pub fn search_engine(&self) -> &str {
+        self.search_engine
+            .as_deref()
+            .unwrap_or(address::DEFAULT_SEARCH_ENGINE)
+    }
+
+    /// Sett søkemotoren; tom verdi gir standardmotoren
+    ///
+    /// # Returns
+    /// SettingsError::InvalidSearchEngine hvis malen ikke er en http(s)-adresse med `%s`
+    pub fn set_search_engine(&mut self, value: &str) -> Result<(), SettingsError> {
+        let value = value.trim();
+        if value.is_empty() || value == address::DEFAULT_SEARCH_ENGINE {
+            self.search_engine = None;
+            return Ok(());
+        }
+        if !address::is_valid_search_engine(value) {
+            return Err(SettingsError::InvalidSearchEngine(value.to_string()));
+        }
+        self.search_engine = Some(value.to_string());
+        Ok(())
+    }
+
+    /// Hent innstillingene som gjelder for en vert
+    ///
+    /// Globale innstillinger overstyres først av jokertegn-mønstre, fra det
+    /// minst til det mest spesifikke, og til slutt av et eksakt vertsnavn.
+    /// Konverteringsmodusen starter fra domenereglene før overstyringene.
+    ///
+    /// # Arguments
+    /// * `host` - Vertsnavnet, eller None for lokale filer
+    pub fn site_settings(&self, host: Option<&str>) -> SiteSettings {
+        let mut site = SiteSettings {
+            conversion_mode: self.conversion.mode_for(host),
+            readability_enabled: self.readability_enabled,
+            font_size: self.font_size,
+            zoom: self.zoom,
+            strip_images: false,
+            default_scheme: self.default_scheme,
+            matched: Vec::new(),
+        };
+        let Some(host) = host else {
+            return site;
+        };
+        let host = host.trim_end_matches('.').to_lowercase();
+
+        let mut matches: Vec<(usize, &String, &SiteOverride)> = self
+            .site_overrides
+            .iter()
+            .filter_map(|(pattern, site_override)| {
+                site_pattern_rank(pattern, &host).map(|rank| (rank, pattern, site_override))
+            })
+            .collect();
+        matches.sort_by_key(|(rank, _, _)| *rank);
+
+        for (_, pattern, site_override) in matches {
+            site_override.apply(&mut site);
+            site.matched.push(pattern.clone());
+        }
+        site
+    }
+
+    /// Lagre en overstyring for en vert
+    ///
+    /// Verdier utenfor gyldig område justeres som for de globale
+    /// innstillingene. En tom overstyring fjernes.
+    ///
+    /// # Arguments
+    /// * `pattern` - Vertsnavn eller `*.vert` for alle underdomener
+    /// * `site_override` - Innstillingene som skal overstyres
+    ///
+    /// # Returns
+    /// Det normaliserte mønsteret, eller SettingsError::InvalidSitePattern
+    pub fn set_site_override(
+        &mut self,
+        pattern: &str,
+        mut site_override: SiteOverride,
+    ) -> Result<String, SettingsError> {
+        let pattern = normalize_site_pattern(pattern)
+            .ok_or_else(|| SettingsError::InvalidSitePattern(pattern.to_string()))?;
+
+        site_override.font_size = site_override.font_size.map(|s| s.clamp(70, 150));
+        site_override.zoom = site_override.zoom.map(|z| z.clamp(50, 200));
+
+        if site_override.is_empty() {
+            self.site_overrides.remove(&pattern);
+        } else {
+            self.site_overrides.insert(pattern.clone(), site_override);
+        }
+        Ok(pattern)
+    }
+
+    /// Fjern overstyringen for en vert
+    ///
+    /// # Returns
+    /// `true` hvis det fantes en overstyring
+    pub fn clear_site_override(&mut self, pattern: &str) -> bool {
+        normalize_site_pattern(pattern)
+            .and_then(|pattern| self.site_overrides.remove(&pattern))
+            .is_some()
+    }
+
+    /// Husk zoom-nivået for en vert
+    ///
+    /// # Arguments
+    /// * `pattern` - Vertsnavn eller `*.vert` for alle underdomener
+    /// * `zoom` - Zoom i prosent, eller None for å bruke den globale zoomen
+    ///
+    /// # Returns
+    /// Det normaliserte mønsteret, eller SettingsError::InvalidSitePattern
+    pub fn set_site_zoom(
+        &mut self,
+        pattern: &str,
+        zoom: Option<u32>,
+    ) -> Result<String, SettingsError> {
+        let pattern = normalize_site_pattern(pattern)
+            .ok_or_else(|| SettingsError::InvalidSitePattern(pattern.to_string()))?;
+        self.store_site_zoom(&pattern, zoom);
+        Ok(pattern)
+    }
+
+    /// Husk hvilken protokoll adresser til en vert skal åpnes med
+    ///
+    /// # Arguments
+    /// * `pattern` - Vertsnavn eller `*.vert` for alle underdomener
+    /// * `scheme` - Protokollen, eller None for å bruke den globale innstillingen
+    ///
+    /// # Returns
+    /// Det normaliserte mønsteret, eller SettingsError::InvalidSitePattern
+    pub fn set_site_scheme(
+        &mut self,
+        pattern: &str,
+        scheme: Option<DefaultScheme>,
+    ) -> Result<String, SettingsError> {
+        let pattern = normalize_site_pattern(pattern)
+            .ok_or_else(|| SettingsError::InvalidSitePattern(pattern.to_string()))?;
+        self.edit_site_override(&pattern, |site_override| {
+            site_override.default_scheme = scheme
+        });
+        Ok(pattern)
+    }
+
+    /// Sett zoomen i overstyringen for et allerede normalisert mønster
+    fn store_site_zoom(&mut self, pattern: &str, zoom: Option<u32>) {
+        self.edit_site_override(pattern, |site_override| {
+            site_override.zoom = zoom.map(|z| z.clamp(50, 200))
+        });
+    }
+
+    /// Endre overstyringen for et normalisert mønster; en tom overstyring fjernes
+    fn edit_site_override(&mut self, pattern: &str, edit: impl FnOnce(&mut SiteOverride)) {
+        let site_override = self.site_overrides.entry(pattern.to_string()).or_default();
+        edit(site_override);
+        if site_override.is_empty() {
+            self.site_overrides.remove(pattern);
+        }
+    }
+
+    /// Zoom-steget, begrenset til 5–50 prosentpoeng
+    pub fn zoom_step(&self) -> u32 {
+        self.zoom_step.clamp(5, 50)
+    }
+
+    /// Endre zoom-steget; verdier utenfor 5–50 justeres
+    pub fn set_zoom_step(&mut self, step: u32) {
+        self.zoom_step = step.clamp(5, 50);
+    }
+
+    /// Øk zoom-nivå med ett steg
+    ///
+    /// # Arguments
+    /// * `host` - Vertsnavnet til siden som vises; zoomen huskes da for
+    ///   verten. None endrer den globale zoomen.
+    ///
+    /// # Returns
+    /// Zoom-nivået som gjelder etter endringen
+    pub fn zoom_in(&mut self, host: Option<&str>) -> u32 {
+        let zoom = self.site_settings(host).zoom + self.zoom_step();
+        self.store_zoom(host, zoom)
+    }
+
+    /// Senk zoom-nivå med ett steg
+    ///
+    /// # Arguments
+    /// * `host` - Som for `zoom_in`
+    ///
+    /// # Returns
+    /// Zoom-nivået som gjelder etter endringen
+    pub fn zoom_out(&mut self, host: Option<&str>) -> u32 {
+        let zoom = self
+            .site_settings(host)
+            .zoom
+            .saturating_sub(self.zoom_step());
+        self.store_zoom(host, zoom)
+    }
+
+    /// Tilbakestill zoom
+    ///
+    /// Fjerner zoomen som er husket for verten. Finnes ingen, settes den
+    /// globale zoomen til 100%.
+    ///
+    /// # Returns
+    /// Zoom-nivået som gjelder etter endringen
+    pub fn zoom_reset(&mut self, host: Option<&str>) -> u32 {
+        let remembered = host.and_then(normalize_site_pattern).filter(|pattern| {
+            self.site_overrides
+                .get(pattern)
+                .is_some_and(|site_override| site_override.zoom.is_some())
+        });
+        match remembered {
+            Some(pattern) => self.store_site_zoom(&pattern, None),
+            None => self.zoom = 100,
+        }
+        self.site_settings(host).zoom
+    }
+
+    /// Lagre et nytt zoom-nivå for verten, eller globalt uten gyldig vert
+    fn store_zoom(&mut self, host: Option<&str>, zoom: u32) -> u32 {
+        let zoom = zoom.clamp(50, 200);
+        match host.and_then(normalize_site_pattern) {
+            Some(pattern) => self.store_site_zoom(&pattern, Some(zoom)),
+            None => self.zoom = zoom,
+        }
+        zoom
+    }
+}
+
+/// Fjern mellomrom rundt en farge og gjør tom tekst om til None
+fn normalize_color(color: Option<String>) -> Option<String> {
+    color
+        .map(|c| c.trim().to_lowercase())
+        .filter(|c| !c.is_empty())
+}
 
-        let content =
-            serde_json::to_string_pretty(self).map_err(|e| SettingsError::Write(e.to_string()))?;
+/// Treveis sammenslåing av JSON-verdier
+///
+/// Der bare én side har endret seg fra `base` brukes den siden; ellers
+/// vinner `mine`. Objekter slås sammen nøkkel for nøkkel.
+fn merge_json(
+    base: &serde_json::Value,
+    mine: &serde_json::Value,
+    theirs: &serde_json::Value,
+) -> serde_json::Value {
+    use serde_json::Value;
 
-        fs::write(path, content).map_err(|e| SettingsError::Write(e.to_string()))
+    if mine == base {
+        return theirs.clone();
+    }
+    if theirs == base {
+        return mine.clone();
     }
+    let (Value::Object(mine_map), Value::Object(theirs_map)) = (mine, theirs) else {
+        return mine.clone();
+    };
+
+    let empty = serde_json::Map::new();
+    let base_map = base.as_object().unwrap_or(&empty);
+    let mut keys: Vec<&String> = mine_map.keys().chain(theirs_map.keys()).collect();
+    keys.sort();
+    keys.dedup();
 
-    /// Øk zoom-nivå
-    pub fn zoom_in(&mut self) {
-        if self.zoom < 200 {
-            self.zoom = (self.zoom + 10).min(200);
+    let mut merged = serde_json::Map::new();
+    for key in keys {
+        let side =
+            |map: &serde_json::Map<String, Value>| map.get(key).cloned().unwrap_or(Value::Null);
+        let value = merge_json(&side(base_map), &side(mine_map), &side(theirs_map));
+        if !value.is_null() {
+            merged.insert(key.clone(), value);
         }
     }
+    Value::Object(merged)
+}
+
+/// Normaliser et vertsmønster for nettstedsinnstillinger
+///
+/// Godtar et vertsnavn (`example.com`) eller et jokertegn for alle
+/// underdomener (`*.example.com`).
+pub fn normalize_site_pattern(pattern: &str) -> Option<String> {
+    let pattern = pattern.trim().trim_end_matches('.').to_lowercase();
+    let host = pattern.strip_prefix("*.").unwrap_or(&pattern);
+    let valid = !host.is_empty()
+        && host.split('.').all(|label| {
+            !label.is_empty()
+                && label
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        });
+    valid.then_some(pattern)
+}
 
-    /// Senk zoom-nivå
-    pub fn zoom_out(&mut self) {
-        if self.zoom > 50 {
-            self.zoom = (self.zoom - 10).max(50);
+/// Sjekk at nedlastingsmappen kan brukes, og opprett den om nødvendig
+///
+/// Bare den siste mappen opprettes; foreldermappen må finnes fra før.
+fn prepare_downloads_dir(path: &Path) -> Result<(), SettingsError> {
+    let invalid = || SettingsError::InvalidDownloadsDir(path.display().to_string());
+    if !path.is_absolute() {
+        return Err(invalid());
+    }
+    if !path.exists() {
+        let parent_exists = path.parent().is_some_and(Path::is_dir);
+        if !parent_exists || std::fs::create_dir(path).is_err() {
+            return Err(invalid());
         }
     }
+    if is_writable_dir(path) {
+        Ok(())
+    } else {
+        Err(invalid())
+    }
+}
+
+/// Om stien er en mappe vi kan lage filer i
+fn is_writable_dir(path: &Path) -> bool {
+    if !path.is_dir() {
+        return false;
+    }
+    let probe = path.join(format!(".bare-skrivetest-{}", std::process::id()));
+    let writable = std::fs::File::create(&probe).is_ok();
+    let _ = std::fs::remove_file(&probe);
+    writable
+}
 
-    /// Tilbakestill zoom til 100%
-    pub fn zoom_reset(&mut self) {
-        self.zoom = 100;
+/// Filnavnet uten mapper, slik at en nedlasting ikke havner utenfor mappen
+fn safe_file_name(file_name: &str) -> String {
+    Path::new(file_name.trim())
+        .file_name()
+        .and_then(|name| name.to_str())
+        .filter(|name| !name.is_empty())
+        .unwrap_or("nedlasting")
+        .to_string()
+}
+
+/// Hvor spesifikt et mønster treffer en vert, eller None hvis det ikke treffer
+///
+/// Lengre jokertegn-mønstre er mer spesifikke; et eksakt vertsnavn slår alle.
+fn site_pattern_rank(pattern: &str, host: &str) -> Option<usize> {
+    match pattern.strip_prefix("*.") {
+        Some(domain) => host
+            .strip_suffix(domain)
+            .filter(|prefix| prefix.ends_with('.') && prefix.len() > 1)
+            .map(|_| domain.len()),
+        None => (pattern == host).then_some(usize::MAX),
     }
 }
 
-/// Hent stien til innstillings-filen
-pub fn get_settings_path() -> PathBuf {
-    let config_dir = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
-    config_dir.join("bare").join("settings.json")
+/// Kommentarer, som kan skjule regler for filtrene under
+static CSS_COMMENT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)/\*.*?\*/").unwrap());
+
+/// `@import`-regler, med eller uten avsluttende semikolon
+static CSS_IMPORT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?im)@import\b[^;]*?(;|$)").unwrap());
+
+/// `url(...)`, med eller uten anførselstegn
+static CSS_URL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)url\(\s*(?:"([^"]*)"|'([^']*)'|([^)]*?))\s*\)"#).unwrap());
+
+/// Fjern det som kan laste ressurser fra andre maskiner
+///
+/// Egendefinert CSS skal ikke kunne brukes til sporing: alle `@import` fjernes,
+/// og `url()` som peker på en annen opprinnelse enn selve dokumentet byttes
+/// med `none`. `data:`-URL-er og relative stier beholdes.
+pub fn sanitize_css(css: &str) -> String {
+    let css = CSS_COMMENT.replace_all(css, "");
+    let css = CSS_IMPORT.replace_all(&css, "");
+    CSS_URL
+        .replace_all(&css, |caps: &regex::Captures| {
+            let target = caps
+                .get(1)
+                .or(caps.get(2))
+                .or(caps.get(3))
+                .map_or("", |m| m.as_str());
+            if is_remote_css_url(target) {
+                "none".to_string()
+            } else {
+                caps[0].to_string()
+            }
+        })
+        .into_owned()
+}
+
+/// Sjekk om en URL i CSS peker ut av dokumentet
+fn is_remote_css_url(target: &str) -> bool {
+    let target = target.trim().to_ascii_lowercase();
+    if target.starts_with("//") || target.contains('\\') {
+        return true;
+    }
+    match target.split_once(':') {
+        Some((scheme, _)) => {
+            let is_scheme = scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+            is_scheme && scheme != "data"
+        }
+        None => false,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs;
     use tempfile::tempdir;
 
     #[test]
@@ -199,37 +1761,278 @@ mod tests {
     #[test]
     fn test_zoom_in() {
         let mut settings = Settings::default();
-        settings.zoom_in();
+        settings.zoom_in(None);
         assert_eq!(settings.zoom, 110);
     }
 
     #[test]
     fn test_zoom_out() {
         let mut settings = Settings::default();
-        settings.zoom_out();
+        settings.zoom_out(None);
         assert_eq!(settings.zoom, 90);
     }
 
     #[test]
     fn test_zoom_limits() {
-        let mut settings = Settings::default();
-        settings.zoom = 200;
-        settings.zoom_in();
+        let mut settings = Settings {
+            zoom: 200,
+            ..Default::default()
+        };
+        settings.zoom_in(None);
         assert_eq!(settings.zoom, 200); // Skal ikke gå over 200
 
         settings.zoom = 50;
-        settings.zoom_out();
+        settings.zoom_out(None);
         assert_eq!(settings.zoom, 50); // Skal ikke gå under 50
     }
 
+    #[test]
+    fn test_site_scheme_remembered() {
+        let mut settings = Settings {
+            default_scheme: DefaultScheme::Ask,
+            ..Default::default()
+        };
+        settings
+            .set_site_scheme("Kapsel.Example", Some(DefaultScheme::Gemini))
+            .unwrap();
+        assert_eq!(
+            settings
+                .site_settings(Some("kapsel.example"))
+                .default_scheme,
+            DefaultScheme::Gemini
+        );
+        assert_eq!(
+            settings.site_settings(Some("annen.example")).default_scheme,
+            DefaultScheme::Ask
+        );
+
+        // Uten valg fjernes overstyringen igjen
+        settings.set_site_scheme("kapsel.example", None).unwrap();
+        assert!(settings.site_overrides.is_empty());
+    }
+
+    /// Innstillinger der alle grupper er endret fra standard
+    fn customized() -> Settings {
+        let mut settings = Settings {
+            theme: Theme::Dark,
+            font_size: 130,
+            zoom: 150,
+            onboarding_completed: true,
+            language: "en".to_string(),
+            ..Default::default()
+        };
+        settings.network.timeout_seconds = 90;
+        settings.privacy.send_gpc = true;
+        settings.history_enabled = true;
+        settings.typography.line_height = 200;
+        settings.set_site_zoom("example.com", Some(120)).unwrap();
+        settings
+    }
+
+    #[test]
+    fn test_reset_appearance_keeps_other_groups() {
+        let mut settings = customized();
+        settings.reset(ResetScope::Appearance, false);
+        assert_eq!(settings.theme, Theme::Light);
+        assert_eq!(settings.font_size, 100);
+        assert_eq!(settings.zoom, 100);
+        assert_eq!(settings.typography, Typography::default());
+
+        assert_eq!(settings.network.timeout_seconds, 90);
+        assert!(settings.privacy.send_gpc);
+        assert!(settings.history_enabled);
+        assert!(settings.site_overrides.contains_key("example.com"));
+        assert_eq!(settings.language, "en");
+    }
+
+    #[test]
+    fn test_reset_single_groups() {
+        let mut settings = customized();
+        settings.reset(ResetScope::Network, false);
+        assert_eq!(settings.network, NetworkSettings::default());
+        assert!(settings.privacy.send_gpc);
+
+        settings.reset(ResetScope::Privacy, false);
+        assert_eq!(settings.privacy, PrivacySettings::default());
+        assert!(!settings.history_enabled);
+        assert_eq!(settings.theme, Theme::Dark);
+
+        settings.reset(ResetScope::SiteOverrides, false);
+        assert!(settings.site_overrides.is_empty());
+        assert_eq!(settings.zoom, 150);
+    }
+
+    #[test]
+    fn test_reset_all_can_keep_onboarding() {
+        let mut settings = customized();
+        settings.reset(ResetScope::All, true);
+        assert!(settings.onboarding_completed);
+        assert_eq!(settings.theme, Theme::Light);
+        assert_eq!(settings.language, "system");
+        assert!(settings.site_overrides.is_empty());
+
+        let mut settings = customized();
+        settings.reset(ResetScope::All, false);
+        assert!(!settings.onboarding_completed);
+
+        let scope: ResetScope = serde_json::from_str("\"site-overrides\"").unwrap();
+        assert_eq!(scope, ResetScope::SiteOverrides);
+    }
+
+    #[test]
+    fn test_dismissed_top_sites_are_normalized() {
+        let mut settings = Settings::default();
+        assert!(settings.dismiss_top_site("https://Example.com/#topp"));
+        assert!(!settings.dismiss_top_site("https://example.com"));
+        assert!(settings
+            .dismissed_top_sites
+            .contains(&bookmarks::normalize_url("https://example.com/")));
+
+        // Listen lagres sammen med innstillingene
+        let json = serde_json::to_value(&settings).unwrap();
+        let restored = Settings::from_json(json).unwrap();
+        assert_eq!(restored.dismissed_top_sites, settings.dismissed_top_sites);
+    }
+
+    #[test]
+    fn test_set_downloads_dir() {
+        let dir = tempdir().unwrap();
+        let mut settings = Settings::default();
+
+        // Mappen opprettes når foreldermappen finnes
+        let new_dir = dir.path().join("Nedlastinger");
+        settings
+            .set_downloads_dir(new_dir.to_str().unwrap())
+            .unwrap();
+        assert!(new_dir.is_dir());
+        assert_eq!(settings.downloads_dir, Some(new_dir.clone()));
+
+        // Men ikke en hel kjede av mapper
+        let deep = dir.path().join("a").join("b");
+        assert!(matches!(
+            settings.set_downloads_dir(deep.to_str().unwrap()),
+            Err(SettingsError::InvalidDownloadsDir(_))
+        ));
+        assert!(!deep.exists());
+
+        // En fil er ikke en mappe, og relative stier godtas ikke
+        let file = dir.path().join("fil.txt");
+        std::fs::write(&file, "x").unwrap();
+        assert!(settings.set_downloads_dir(file.to_str().unwrap()).is_err());
+        assert!(settings.set_downloads_dir("Nedlastinger").is_err());
+        assert_eq!(settings.downloads_dir, Some(new_dir));
+
+        // Tom verdi gir systemets mappe
+        settings.set_downloads_dir("  ").unwrap();
+        assert_eq!(settings.downloads_dir, None);
+        assert_eq!(settings.effective_downloads_dir(), dirs::download_dir());
+    }
+
+    #[test]
+    fn test_download_target_fallback_order() {
+        let dir = tempdir().unwrap();
+        let mut settings = Settings {
+            downloads_dir: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        };
+
+        // Uten valget spørres det, med mappen som utgangspunkt
+        assert_eq!(
+            settings.download_target("side.md"),
+            DownloadTarget::Ask {
+                default_dir: Some(dir.path().to_path_buf())
+            }
+        );
+
+        // Med valget lagres det direkte, uten mapper fra filnavnet
+        settings.use_downloads_dir_without_asking = true;
+        assert_eq!(
+            settings.download_target("../../etc/side.md"),
+            DownloadTarget::Direct {
+                path: dir.path().join("side.md")
+            }
+        );
+        assert_eq!(
+            settings.download_target(".."),
+            DownloadTarget::Direct {
+                path: dir.path().join("nedlasting")
+            }
+        );
+
+        // En mappe som er borte gir lagringsdialog uten utgangspunkt
+        settings.downloads_dir = Some(dir.path().join("borte"));
+        assert_eq!(
+            settings.download_target("side.md"),
+            DownloadTarget::Ask { default_dir: None }
+        );
+    }
+
+    #[test]
+    fn test_zoom_step_clamped() {
+        let mut settings = Settings::default();
+        settings.set_zoom_step(2);
+        assert_eq!(settings.zoom_step, 5);
+        settings.set_zoom_step(80);
+        assert_eq!(settings.zoom_step, 50);
+
+        settings.set_zoom_step(25);
+        assert_eq!(settings.zoom_in(None), 125);
+        assert_eq!(settings.zoom_out(None), 100);
+
+        // Ugyldig verdi fra filen begrenses når den brukes
+        let mut settings =
+            Settings::from_json(serde_json::json!({ "zoom_step": 0, "zoom": 100 })).unwrap();
+        assert_eq!(settings.zoom_in(None), 105);
+    }
+
+    #[test]
+    fn test_site_zoom_overrides_global() {
+        let mut settings = Settings {
+            zoom: 120,
+            ..Default::default()
+        };
+
+        // Zoom på en side huskes for verten og endrer ikke den globale
+        assert_eq!(settings.zoom_in(Some("Gopher.Floodgap.com")), 130);
+        assert_eq!(settings.zoom, 120);
+        assert_eq!(
+            settings.site_overrides["gopher.floodgap.com"].zoom,
+            Some(130)
+        );
+        assert_eq!(
+            settings.site_settings(Some("gopher.floodgap.com")).zoom,
+            130
+        );
+        assert_eq!(settings.site_settings(Some("example.com")).zoom, 120);
+
+        // Jokertegn gjelder til verten får sin egen zoom
+        settings.set_site_zoom("*.example.com", Some(80)).unwrap();
+        assert_eq!(settings.site_settings(Some("docs.example.com")).zoom, 80);
+        assert_eq!(settings.zoom_out(Some("docs.example.com")), 70);
+        assert_eq!(settings.site_settings(Some("www.example.com")).zoom, 80);
+
+        // Tilbakestilling fjerner vertens zoom først, deretter den globale
+        assert_eq!(settings.zoom_reset(Some("gopher.floodgap.com")), 120);
+        assert!(!settings.site_overrides.contains_key("gopher.floodgap.com"));
+        assert_eq!(settings.zoom_reset(Some("gopher.floodgap.com")), 100);
+        assert_eq!(settings.zoom, 100);
+
+        // Uten vert endres den globale zoomen
+        settings.zoom_in(None);
+        assert_eq!(settings.zoom, 110);
+        assert!(settings.set_site_zoom("ikke gyldig", Some(120)).is_err());
+    }
+
     #[test]
     fn test_save_and_load() {
         let dir = tempdir().unwrap();
         let path = dir.path().join("settings.json");
 
-        let mut settings = Settings::default();
-        settings.theme = Theme::Dark;
-        settings.zoom = 120;
+        let settings = Settings {
+            theme: Theme::Dark,
+            zoom: 120,
+            ..Default::default()
+        };
 
         settings.save(&path).unwrap();
 
@@ -237,4 +2040,831 @@ mod tests {
         assert_eq!(loaded.theme, Theme::Dark);
         assert_eq!(loaded.zoom, 120);
     }
+
+    #[test]
+    fn test_save_rotates_backups() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+
+        for zoom in [100, 110, 120] {
+            let settings = Settings {
+                zoom,
+                backup_generations: 2,
+                ..Default::default()
+            };
+            settings.save(&path).unwrap();
+        }
+
+        let backup: Settings = storage::parse_file(&storage::generation_path(&path, 1)).unwrap();
+        assert_eq!(backup.zoom, 110);
+        let oldest: Settings = storage::parse_file(&storage::generation_path(&path, 2)).unwrap();
+        assert_eq!(oldest.zoom, 100);
+        assert_eq!(Settings::load(&path).unwrap().zoom, 120);
+    }
+
+    #[test]
+    fn test_font_family_round_trip() {
+        for family in [
+            FontFamily::System,
+            FontFamily::Serif,
+            FontFamily::SansSerif,
+            FontFamily::Mono,
+            FontFamily::Custom("Literata".to_string()),
+        ] {
+            let json = serde_json::to_string(&family).unwrap();
+            let back: FontFamily = serde_json::from_str(&json).unwrap();
+            assert_eq!(back, family);
+        }
+
+        // Gamle innstillinger lagret bare forhåndsvalgene som tekst
+        let old: FontFamily = serde_json::from_str("\"serif\"").unwrap();
+        assert_eq!(old, FontFamily::Serif);
+        assert_eq!(
+            serde_json::to_string(&FontFamily::Custom("Literata".to_string())).unwrap(),
+            r#"{"custom":"Literata"}"#
+        );
+    }
+
+    #[test]
+    fn test_custom_font_name_filter() {
+        assert_eq!(
+            FontFamily::custom("  Source Serif 4 ").unwrap(),
+            FontFamily::Custom("Source Serif 4".to_string())
+        );
+        assert!(FontFamily::custom("Noto_Sans-JP.Bold").is_ok());
+        assert!(FontFamily::custom("Åsne Grotesk").is_ok());
+
+        for name in [
+            "",
+            "   ",
+            "Arial; } body { display: none",
+            "Arial\", url(https://evil.example/x)",
+            "Arial'",
+            "a/**/b",
+            "Font\\65",
+            &"x".repeat(65),
+        ] {
+            assert!(
+                matches!(
+                    FontFamily::custom(name),
+                    Err(SettingsError::InvalidFontName(_))
+                ),
+                "{:?}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_custom_font_in_file_falls_back() {
+        let settings = Settings::from_json(serde_json::json!({
+            "font_family": { "custom": "x; } * { color: red" }
+        }))
+        .unwrap();
+        assert_eq!(settings.font_family, FontFamily::System);
+
+        let settings =
+            Settings::from_json(serde_json::json!({ "font_family": { "custom": "Literata" } }))
+                .unwrap();
+        assert_eq!(settings.font_family.custom_name(), Some("Literata"));
+    }
+
+    #[test]
+    fn test_typography_clamped() {
+        let typography = Typography {
+            line_height: 400,
+            paragraph_spacing: 1000,
+            justify_text: true,
+        }
+        .clamped();
+        assert_eq!(typography.line_height, 220);
+        assert_eq!(typography.paragraph_spacing, 300);
+        assert!(typography.justify_text);
+
+        let typography = Typography {
+            line_height: 50,
+            ..Default::default()
+        }
+        .clamped();
+        assert_eq!(typography.line_height, 120);
+    }
+
+    #[test]
+    fn test_typography_defaults_for_old_files() {
+        let settings = Settings::from_json(serde_json::json!({ "theme": "dark" })).unwrap();
+        assert_eq!(settings.typography, Typography::default());
+
+        // Delvis typografi fyller inn resten med standardverdier
+        let settings =
+            Settings::from_json(serde_json::json!({ "typography": { "justify_text": true } }))
+                .unwrap();
+        assert_eq!(settings.typography.line_height, 160);
+        assert!(settings.typography.justify_text);
+    }
+
+    #[test]
+    fn test_flat_typography_fields_are_nested() {
+        let settings = Settings::from_json(serde_json::json!({
+            "zoom": 120,
+            "line_height": 180,
+            "paragraph_spacing": 500,
+            "justify_text": true,
+        }))
+        .unwrap();
+        assert_eq!(settings.zoom, 120);
+        assert_eq!(settings.typography.line_height, 180);
+        assert_eq!(settings.typography.paragraph_spacing, 300);
+        assert!(settings.typography.justify_text);
+
+        // Nestede verdier går foran flate
+        let settings = Settings::from_json(serde_json::json!({
+            "line_height": 180,
+            "typography": { "line_height": 200 },
+        }))
+        .unwrap();
+        assert_eq!(settings.typography.line_height, 200);
+
+        // Lagres bare nestet
+        let saved = serde_json::to_value(&settings).unwrap();
+        assert!(saved.get("line_height").is_none());
+        assert_eq!(saved["typography"]["line_height"], 200);
+    }
+
+    /// Skriv innstillinger til fil slik en teksteditor ville gjort
+    fn edit_file(path: &Path, edit: impl FnOnce(&mut serde_json::Value)) {
+        let mut value: serde_json::Value = storage::parse_file(path).unwrap();
+        edit(&mut value);
+        // Sørg for at endringstiden blir en annen enn ved forrige lagring
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        std::fs::write(path, serde_json::to_string_pretty(&value).unwrap() + "\n").unwrap();
+    }
+
+    #[test]
+    fn test_rebase_keeps_changes_from_both_sides() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        Settings::default().save(&path).unwrap();
+        let mut memory = Settings::load(&path).unwrap();
+        assert!(memory.is_own_write(&path));
+
+        // Endret for hånd i filen
+        edit_file(&path, |value| {
+            value["font_size"] = 130.into();
+            value["typography"]["justify_text"] = true.into();
+            value["homepage"] = "bookmarks".into();
+        });
+        assert!(!memory.is_own_write(&path));
+
+        // Endret i appen før filen ble lest inn på nytt
+        memory.zoom_in(None);
+        memory.typography.line_height = 180;
+
+        let disk = Settings::load_external(&path).unwrap();
+        assert!(memory.rebase(disk, &path));
+        assert_eq!(memory.font_size, 130);
+        assert_eq!(memory.zoom, 110);
+        assert!(memory.typography.justify_text);
+        assert_eq!(memory.typography.line_height, 180);
+        assert_eq!(memory.home_page(), HomePage::Bookmarks);
+        assert!(memory.is_own_write(&path));
+
+        // Lagringen etterpå overskriver ikke endringene i filen
+        memory.save(&path).unwrap();
+        let saved = Settings::load(&path).unwrap();
+        assert_eq!(saved.font_size, 130);
+        assert_eq!(saved.zoom, 110);
+    }
+
+    #[test]
+    fn test_rebase_conflict_prefers_memory() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        Settings::default().save(&path).unwrap();
+        let mut memory = Settings::load(&path).unwrap();
+
+        edit_file(&path, |value| value["zoom"] = 150.into());
+        memory.zoom_out(None);
+
+        let disk = Settings::load_external(&path).unwrap();
+        assert!(memory.rebase(disk, &path));
+        assert_eq!(memory.zoom, 90);
+    }
+
+    #[test]
+    fn test_rebase_without_local_changes_takes_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        Settings::default().save(&path).unwrap();
+        let mut memory = Settings::load(&path).unwrap();
+
+        edit_file(&path, |value| {
+            value["theme"] = "dark".into();
+            // Utenfor gyldig område; justeres ved innlesing
+            value["typography"]["line_height"] = 999.into();
+        });
+        let disk = Settings::load_external(&path).unwrap();
+        assert!(!memory.rebase(disk, &path));
+        assert_eq!(memory.theme, Theme::Dark);
+        assert_eq!(memory.typography.line_height, 220);
+    }
+
+    #[test]
+    fn test_load_external_rejects_invalid_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        Settings::default().save(&path).unwrap();
+        Settings::load(&path).unwrap();
+        std::fs::write(&path, "{ \"zoom\": ").unwrap();
+
+        // Sikkerhetskopien brukes ikke når filen er endret utenfra
+        assert!(matches!(
+            Settings::load_external(&path),
+            Err(SettingsError::Read(_))
+        ));
+    }
+
+    #[test]
+    fn test_hex_color_validation() {
+        for valid in ["#fff", "#FFF", "#f4ecd8", "#0000EE"] {
+            assert!(is_valid_hex_color(valid), "{}", valid);
+        }
+        for invalid in [
+            "",
+            "fff",
+            "#ff",
+            "#ffff",
+            "#fffffff",
+            "#ggg",
+            "red",
+            "#fff; background: url(https://evil.example/)",
+            "#fff}body{display:none",
+            "rgb(0,0,0)",
+        ] {
+            assert!(!is_valid_hex_color(invalid), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_set_custom_colors() {
+        let mut settings = Settings::default();
+        settings
+            .set_custom_colors(Some(ThemeColors {
+                background: Some(" #F4ECD8 ".to_string()),
+                link: Some("#8b4513".to_string()),
+                text: Some(String::new()),
+                ..Default::default()
+            }))
+            .unwrap();
+        let colors = settings.custom_colors.clone().unwrap();
+        assert_eq!(colors.background.as_deref(), Some("#f4ecd8"));
+        assert_eq!(colors.link.as_deref(), Some("#8b4513"));
+        assert_eq!(colors.text, None);
+
+        let injected = ThemeColors {
+            accent: Some("#000;}body{display:none".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            settings.set_custom_colors(Some(injected)),
+            Err(SettingsError::InvalidColor(_))
+        ));
+        assert_eq!(settings.custom_colors, Some(colors));
+
+        // Tomme farger tilbakestiller
+        settings
+            .set_custom_colors(Some(ThemeColors::default()))
+            .unwrap();
+        assert_eq!(settings.custom_colors, None);
+    }
+
+    #[test]
+    fn test_invalid_colors_in_file_are_ignored() {
+        let settings = Settings::from_json(serde_json::json!({
+            "custom_colors": { "background": "url(x)" },
+            "zoom": 120,
+        }))
+        .unwrap();
+        assert_eq!(settings.custom_colors, None);
+        assert_eq!(settings.zoom, 120);
+    }
+
+    #[test]
+    fn test_sepia_theme_round_trip() {
+        let settings = Settings {
+            theme: Theme::Sepia,
+            ..Settings::default()
+        };
+        let value = serde_json::to_value(&settings).unwrap();
+        assert_eq!(value["theme"], "sepia");
+        let loaded = Settings::from_json(value).unwrap();
+        assert_eq!(loaded.theme, Theme::Sepia);
+        assert_eq!(Theme::parse("sepia"), Some(Theme::Sepia));
+    }
+
+    #[test]
+    fn test_unknown_theme_falls_back_to_default() {
+        let settings =
+            Settings::from_json(serde_json::json!({ "theme": "solarized", "font_size": 120 }))
+                .unwrap();
+        assert_eq!(settings.theme, Theme::Light);
+        assert_eq!(settings.font_size, 120);
+    }
+
+    #[test]
+    fn test_search_engine() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.search_engine(), address::DEFAULT_SEARCH_ENGINE);
+
+        settings
+            .set_search_engine(" https://search.example/?q=%s ")
+            .unwrap();
+        assert_eq!(settings.search_engine(), "https://search.example/?q=%s");
+
+        assert!(matches!(
+            settings.set_search_engine("https://search.example/"),
+            Err(SettingsError::InvalidSearchEngine(_))
+        ));
+        assert_eq!(settings.search_engine(), "https://search.example/?q=%s");
+
+        settings.set_search_engine("").unwrap();
+        assert_eq!(settings.search_engine, None);
+    }
+
+    #[test]
+    fn test_privacy_defaults_for_old_files() {
+        let settings = Settings::from_json(serde_json::json!({ "theme": "dark" })).unwrap();
+        assert!(settings.privacy.load_remote_images);
+        assert!(!settings.privacy.strip_query_params);
+        assert!(!settings.privacy.send_referrer);
+        assert!(!settings.privacy.prefetch);
+        assert!(!settings.history_enabled);
+    }
+
+    #[test]
+    fn test_open_policy() {
+        let settings = Settings::from_json(serde_json::json!({ "theme": "dark" })).unwrap();
+        assert_eq!(settings.open_policy().max_size, 10 * 1024 * 1024);
+        assert!(settings.open_policy().follow_symlinks);
+
+        let settings = Settings::from_json(serde_json::json!({
+            "max_file_mb": 2,
+            "follow_symlinks": false,
+        }))
+        .unwrap();
+        assert_eq!(settings.open_policy().max_size, 2 * 1024 * 1024);
+        assert!(!settings.open_policy().follow_symlinks);
+    }
+
+    #[test]
+    fn test_max_render_bytes() {
+        let settings = Settings::from_json(serde_json::json!({ "theme": "dark" })).unwrap();
+        assert_eq!(settings.max_render_bytes(), 8 * 1024 * 1024);
+
+        let settings = Settings::from_json(serde_json::json!({ "max_render_mb": 1 })).unwrap();
+        assert_eq!(settings.max_render_bytes(), 1024 * 1024);
+    }
+
+    #[test]
+    fn test_missing_diagram_languages_uses_default() {
+        let settings: Settings = serde_json::from_str(r#"{"theme": "dark"}"#).unwrap();
+        assert!(settings.diagram_languages.contains(&"mermaid".to_string()));
+    }
+
+    #[test]
+    fn test_load_recovers_from_backup_after_truncated_save() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+
+        let settings = Settings {
+            font_size: 120,
+            ..Settings::default()
+        };
+        settings.save(&path).unwrap();
+        Settings::load(&path).unwrap();
+
+        fs::write(&path, "{\"theme\": \"dark\", \"font_si").unwrap();
+
+        let recovered = Settings::load(&path).unwrap();
+        assert_eq!(recovered.font_size, 120);
+        assert_eq!(recovered.theme, Theme::Light);
+    }
+
+    #[test]
+    fn test_sanitize_css_strips_imports() {
+        let css = "@import url(https://fonts.example.com/a.css);\n\
+                   @IMPORT \"https://example.com/b.css\" screen;\n\
+                   @import 'lokal.css'\n\
+                   blockquote { margin: 0 1em; }";
+        let sanitized = sanitize_css(css);
+        assert!(!sanitized.to_lowercase().contains("@import"));
+        assert!(sanitized.contains("blockquote { margin: 0 1em; }"));
+    }
+
+    #[test]
+    fn test_sanitize_css_replaces_remote_urls() {
+        let css = "body { background: url(https://tracker.example/p.gif); }\n\
+                   h1 { background: url( '//cdn.example/h.png' ); }\n\
+                   h2 { background: URL(\"http:\\2f\\2f x.example\"); }\n\
+                   .ikon { background: url(\"data:image/png;base64,AAAA\"); }\n\
+                   .lokal { background: url(bilder/ikon.png); }";
+        let sanitized = sanitize_css(css);
+        assert!(!sanitized.contains("tracker.example"));
+        assert!(!sanitized.contains("cdn.example"));
+        assert!(!sanitized.contains("x.example"));
+        assert!(sanitized.contains("url(\"data:image/png;base64,AAAA\")"));
+        assert!(sanitized.contains("url(bilder/ikon.png)"));
+        assert_eq!(sanitized.matches("none").count(), 3);
+    }
+
+    #[test]
+    fn test_sanitize_css_ignores_comments_hiding_rules() {
+        let sanitized = sanitize_css("/* */@import/**/ url(https://x.example/a.css);");
+        assert!(!sanitized.contains("x.example"));
+    }
+
+    #[test]
+    fn test_set_custom_css_limits_size() {
+        let mut settings = Settings::default();
+        settings
+            .set_custom_css("code { font-family: 'Iosevka'; }")
+            .unwrap();
+        assert_eq!(
+            settings.custom_css.as_deref(),
+            Some("code { font-family: 'Iosevka'; }")
+        );
+
+        let large = "a".repeat(MAX_CUSTOM_CSS_SIZE + 1);
+        assert!(matches!(
+            settings.set_custom_css(&large),
+            Err(SettingsError::CssTooLarge(_))
+        ));
+
+        settings.set_custom_css("  \n").unwrap();
+        assert_eq!(settings.custom_css, None);
+    }
+
+    #[test]
+    fn test_user_css_file_wins_over_setting() {
+        let dir = tempdir().unwrap();
+        let user_css = dir.path().join("user.css");
+        let mut settings = Settings::default();
+        settings.set_custom_css("p { color: red; }").unwrap();
+
+        assert_eq!(
+            settings.effective_css(&user_css).as_deref(),
+            Some("p { color: red; }")
+        );
+
+        fs::write(&user_css, "p { color: blue; } @import 'x.css';").unwrap();
+        assert_eq!(
+            settings.effective_css(&user_css).as_deref(),
+            Some("p { color: blue; } ")
+        );
+    }
+
+    #[test]
+    fn test_site_pattern_matching() {
+        assert!(normalize_site_pattern("*.Example.com.").is_some());
+        assert_eq!(
+            normalize_site_pattern("*.Example.com.").unwrap(),
+            "*.example.com"
+        );
+        for invalid in [
+            "",
+            "*",
+            "*.",
+            "a..b",
+            "exa mple.com",
+            "*.*.example.com",
+            "http://x",
+        ] {
+            assert_eq!(normalize_site_pattern(invalid), None, "{}", invalid);
+        }
+
+        assert_eq!(
+            site_pattern_rank("example.com", "example.com"),
+            Some(usize::MAX)
+        );
+        assert!(site_pattern_rank("*.example.com", "docs.example.com").is_some());
+        assert!(site_pattern_rank("*.example.com", "a.b.example.com").is_some());
+        assert_eq!(site_pattern_rank("*.example.com", "example.com"), None);
+        assert_eq!(site_pattern_rank("*.example.com", "badexample.com"), None);
+        assert!(
+            site_pattern_rank("*.docs.example.com", "v1.docs.example.com")
+                > site_pattern_rank("*.example.com", "v1.docs.example.com")
+        );
+    }
+
+    fn rule(pattern: &str, mode: ConversionMode) -> DomainRule {
+        DomainRule {
+            pattern: pattern.to_string(),
+            mode,
+        }
+    }
+
+    #[test]
+    fn test_conversion_rule_precedence() {
+        let conversion = ConversionSettings {
+            http: ConversionMode::AskEverytime,
+            rules: vec![
+                rule("wiki.intra.example", ConversionMode::MarkdownOnly),
+                rule("*.example", ConversionMode::ConvertAll),
+                rule("*.intra.example", ConversionMode::AskEverytime),
+            ],
+        };
+        // Eksakt vert > lengste jokertegn > kortere jokertegn > global
+        assert_eq!(
+            conversion.mode_for(Some("Wiki.Intra.Example.")),
+            ConversionMode::MarkdownOnly
+        );
+        assert_eq!(
+            conversion.mode_for(Some("mail.intra.example")),
+            ConversionMode::AskEverytime
+        );
+        assert_eq!(
+            conversion.mode_for(Some("www.example")),
+            ConversionMode::ConvertAll
+        );
+        assert_eq!(
+            conversion.mode_for(Some("example.org")),
+            ConversionMode::AskEverytime
+        );
+        assert_eq!(conversion.mode_for(None), ConversionMode::AskEverytime);
+
+        // Nettstedets overstyring går foran domeneregelen
+        let mut settings = Settings {
+            conversion,
+            ..Default::default()
+        };
+        assert_eq!(
+            settings.site_settings(Some("www.example")).conversion_mode,
+            ConversionMode::ConvertAll
+        );
+        settings
+            .set_site_override(
+                "www.example",
+                SiteOverride {
+                    conversion_mode: Some(ConversionMode::MarkdownOnly),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            settings.site_settings(Some("www.example")).conversion_mode,
+            ConversionMode::MarkdownOnly
+        );
+    }
+
+    #[test]
+    fn test_conversion_rule_add_replace_remove() {
+        let mut conversion = ConversionSettings::default();
+        assert_eq!(
+            conversion
+                .set_rule(" Wiki.Intra.Example ", ConversionMode::MarkdownOnly)
+                .unwrap(),
+            "wiki.intra.example"
+        );
+        conversion
+            .set_rule("*.example", ConversionMode::ConvertAll)
+            .unwrap();
+
+        // Samme mønster erstatter regelen i stedet for å legge til en ny
+        conversion
+            .set_rule("wiki.intra.example.", ConversionMode::AskEverytime)
+            .unwrap();
+        assert_eq!(
+            conversion.rules,
+            vec![
+                rule("wiki.intra.example", ConversionMode::AskEverytime),
+                rule("*.example", ConversionMode::ConvertAll),
+            ]
+        );
+
+        assert!(matches!(
+            conversion.set_rule("ikke gyldig", ConversionMode::ConvertAll),
+            Err(SettingsError::InvalidSitePattern(_))
+        ));
+        assert!(conversion.remove_rule("WIKI.intra.example"));
+        assert!(!conversion.remove_rule("wiki.intra.example"));
+        assert!(!conversion.remove_rule("ikke gyldig"));
+        assert_eq!(
+            conversion.rules,
+            vec![rule("*.example", ConversionMode::ConvertAll)]
+        );
+    }
+
+    #[test]
+    fn test_conversion_mode_migrated() {
+        let settings =
+            Settings::from_json(serde_json::json!({ "conversion_mode": "markdown-only" })).unwrap();
+        assert_eq!(settings.conversion.http, ConversionMode::MarkdownOnly);
+        assert!(settings.conversion.rules.is_empty());
+
+        let saved = serde_json::to_value(&settings).unwrap();
+        assert!(saved.get("conversion_mode").is_none());
+        assert_eq!(saved["conversion"]["http"], "markdown-only");
+
+        // Den nye strukturen går foran et gammelt felt som ligger igjen
+        let settings = Settings::from_json(serde_json::json!({
+            "conversion_mode": "markdown-only",
+            "conversion": { "http": "ask-everytime" },
+        }))
+        .unwrap();
+        assert_eq!(settings.conversion.http, ConversionMode::AskEverytime);
+    }
+
+    #[test]
+    fn test_conversion_rules_normalized_on_load() {
+        let settings = Settings::from_json(serde_json::json!({
+            "conversion": {
+                "rules": [
+                    { "pattern": "Wiki.Example.", "mode": "markdown-only" },
+                    { "pattern": "ikke gyldig", "mode": "convert-all" },
+                    { "pattern": "wiki.example", "mode": "convert-all" },
+                ],
+            },
+        }))
+        .unwrap();
+        assert_eq!(settings.conversion.http, ConversionMode::ConvertAll);
+        assert_eq!(
+            settings.conversion.rules,
+            vec![rule("wiki.example", ConversionMode::ConvertAll)]
+        );
+    }
+
+    #[test]
+    fn test_site_settings_merge_order() {
+        let mut settings = Settings {
+            font_size: 110,
+            ..Default::default()
+        };
+        settings
+            .set_site_override(
+                "*.example.com",
+                SiteOverride {
+                    readability_enabled: Some(false),
+                    font_size: Some(120),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        settings
+            .set_site_override(
+                "*.docs.example.com",
+                SiteOverride {
+                    font_size: Some(130),
+                    strip_images: Some(true),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        settings
+            .set_site_override(
+                "v1.docs.example.com",
+                SiteOverride {
+                    conversion_mode: Some(ConversionMode::MarkdownOnly),
+                    font_size: Some(999),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let site = settings.site_settings(Some("V1.docs.example.com"));
+        assert_eq!(
+            site.matched,
+            vec!["*.example.com", "*.docs.example.com", "v1.docs.example.com"]
+        );
+        assert_eq!(site.conversion_mode, ConversionMode::MarkdownOnly);
+        assert!(!site.readability_enabled);
+        assert!(site.strip_images);
+        assert_eq!(site.font_size, 150); // Justert til maks
+        assert_eq!(site.zoom, 100); // Arvet fra globale innstillinger
+
+        let site = settings.site_settings(Some("blog.example.com"));
+        assert_eq!(site.font_size, 120);
+        assert!(!site.strip_images);
+
+        let site = settings.site_settings(Some("example.com"));
+        assert!(site.matched.is_empty());
+        assert_eq!(site.font_size, 110);
+        assert_eq!(settings.site_settings(None).font_size, 110);
+    }
+
+    #[test]
+    fn test_clear_site_override() {
+        let mut settings = Settings::default();
+        let zoom = SiteOverride {
+            zoom: Some(130),
+            ..Default::default()
+        };
+        settings.set_site_override("Capsule.example", zoom).unwrap();
+        assert!(settings.site_overrides.contains_key("capsule.example"));
+
+        // En tom overstyring fjerner oppføringen
+        settings
+            .set_site_override("capsule.example", SiteOverride::default())
+            .unwrap();
+        assert!(settings.site_overrides.is_empty());
+
+        settings
+            .set_site_override(
+                "capsule.example",
+                SiteOverride {
+                    zoom: Some(130),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert!(settings.clear_site_override("CAPSULE.example"));
+        assert!(!settings.clear_site_override("capsule.example"));
+        assert!(matches!(
+            settings.set_site_override("*", SiteOverride::default()),
+            Err(SettingsError::InvalidSitePattern(_))
+        ));
+    }
+
+    #[test]
+    fn test_network_settings_clamped() {
+        let network = NetworkSettings {
+            timeout_seconds: 0,
+            max_response_mb: 1000,
+            max_redirects: 200,
+        }
+        .clamped();
+        assert_eq!(network.timeout_seconds, 1);
+        assert_eq!(network.max_response_mb, 100);
+        assert_eq!(network.max_redirects, 20);
+        assert_eq!(network.max_response_bytes(), 100 * 1024 * 1024);
+
+        // Gamle filer uten nettverksseksjon, eller med bare noen felter
+        let settings: Settings = serde_json::from_str(r#"{"theme": "dark"}"#).unwrap();
+        assert_eq!(settings.network, NetworkSettings::default());
+        let settings: Settings =
+            serde_json::from_str(r#"{"network": {"timeout_seconds": 5}}"#).unwrap();
+        assert_eq!(settings.network.timeout_seconds, 5);
+        assert_eq!(settings.network.max_redirects, 5);
+    }
+
+    #[test]
+    fn test_homepage_kinds() {
+        assert_eq!(HomePage::parse("welcome"), Some(HomePage::Welcome));
+        assert_eq!(HomePage::parse(""), Some(HomePage::Welcome));
+        assert_eq!(HomePage::parse("bookmarks"), Some(HomePage::Bookmarks));
+        assert_eq!(
+            HomePage::parse(" gemini://geminiprotocol.net/ "),
+            Some(HomePage::Url("gemini://geminiprotocol.net/".to_string()))
+        );
+        assert_eq!(
+            HomePage::parse("https://example.com/README.md"),
+            Some(HomePage::Url("https://example.com/README.md".to_string()))
+        );
+        assert!(matches!(
+            HomePage::parse("gopher://gopher.floodgap.com/"),
+            Some(HomePage::Url(_))
+        ));
+
+        let dir = tempdir().unwrap();
+        let notes = dir.path().join("notater.md");
+        assert_eq!(
+            HomePage::parse(&notes.to_string_lossy()),
+            Some(HomePage::File(notes.clone()))
+        );
+        let file_url = url::Url::from_file_path(&notes).unwrap();
+        assert_eq!(
+            HomePage::parse(file_url.as_str()),
+            Some(HomePage::File(notes))
+        );
+
+        for invalid in [
+            "notater.md",
+            "ftp://example.com/",
+            "https://",
+            "javascript:alert(1)",
+        ] {
+            assert_eq!(HomePage::parse(invalid), None, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_set_homepage() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.home_page(), HomePage::Welcome);
+
+        settings.set_homepage("bookmarks").unwrap();
+        assert_eq!(settings.homepage.as_deref(), Some("bookmarks"));
+        assert_eq!(settings.home_page(), HomePage::Bookmarks);
+
+        assert!(matches!(
+            settings.set_homepage("ikke en side"),
+            Err(SettingsError::InvalidHomepage(_))
+        ));
+        assert_eq!(settings.home_page(), HomePage::Bookmarks);
+
+        settings.set_homepage("welcome").unwrap();
+        assert_eq!(settings.homepage, None);
+
+        // En ugyldig verdi i filen faller tilbake til velkomstsiden
+        settings.homepage = Some("relativ/sti.md".to_string());
+        assert_eq!(settings.home_page(), HomePage::Welcome);
+    }
 }
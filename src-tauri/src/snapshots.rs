@@ -0,0 +1,260 @@
+//! Arkiverte kopier av bokmerkede sider
+//!
+//! Lagrer markdown-kilden til en side (etter eventuell konvertering) i
+//! `snapshots/` under config-mappen, med bokmerke-ID som filnavn, slik at
+//! siden kan leses selv om den forsvinner fra nettet.
+
+use crate::bookmarks;
+use crate::locale::{Locale, Localize};
+use crate::page_security::SecurityInfo;
+use crate::page_source::PageSource;
+use crate::storage;
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Maksimal størrelse på en arkivert kopi (5 MB)
+pub const MAX_SNAPSHOT_SIZE: usize = 5 * 1024 * 1024;
+
+/// Antall nylig viste sider som huskes for arkivering
+const RECENT_PAGES: usize = 8;
+
+/// Feil som kan oppstå ved arkivering
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    #[error("Siden er for stor til å arkiveres ({0} bytes, maks {MAX_SNAPSHOT_SIZE})")]
+    TooLarge(usize),
+
+    #[error("Ugyldig bokmerke-ID for arkivering: {0}")]
+    InvalidId(String),
+
+    #[error("Siden er ikke åpen, last den inn før den arkiveres: {0}")]
+    NotLoaded(String),
+
+    #[error("Kunne ikke lese arkivert kopi: {0}")]
+    Read(String),
+
+    #[error("Kunne ikke lagre arkivert kopi: {0}")]
+    Write(String),
+}
+
+impl Localize for SnapshotError {
+    fn localize_in(&self, locale: Locale) -> String {
+        match self {
+            Self::TooLarge(size) => {
+                locale.format("error.snapshot_too_large", &[size, &MAX_SNAPSHOT_SIZE])
+            }
+            Self::InvalidId(id) => locale.format("error.snapshot_invalid_id", &[id]),
+            Self::NotLoaded(url) => locale.format("error.snapshot_not_loaded", &[url]),
+            Self::Read(e) => locale.format("error.snapshot_read", &[e]),
+            Self::Write(e) => locale.format("error.snapshot_write", &[e]),
+        }
+    }
+}
+
+/// Markdown-kilden til de sist viste sidene
+///
+/// Fylles når en side rendres, slik at en arkivert kopi blir det brukeren
+/// faktisk så, uten en ny forespørsel til serveren. Bytene serveren sendte
+/// huskes ved siden av, for `save_page_source`, og forbindelsen de kom over,
+/// for `get_page_security`.
+#[derive(Debug, Default)]
+pub struct RecentPages {
+    pages: VecDeque<(String, String)>,
+    sources: VecDeque<(String, PageSource)>,
+    security: VecDeque<(String, SecurityInfo)>,
+}
+
+impl RecentPages {
+    /// Husk kilden til en side som nettopp ble vist
+    pub fn remember(&mut self, url: &str, markdown: &str) {
+        let key = bookmarks::normalize_url(url);
+        self.pages.retain(|(existing, _)| *existing != key);
+        self.pages.push_front((key, markdown.to_string()));
+        self.pages.truncate(RECENT_PAGES);
+    }
+
+    /// Husk bytene serveren sendte for en side som nettopp ble vist
+    pub fn remember_raw(&mut self, url: &str, source: PageSource) {
+        let key = bookmarks::normalize_url(url);
+        self.sources.retain(|(existing, _)| *existing != key);
+        self.sources.push_front((key, source));
+        self.sources.truncate(RECENT_PAGES);
+    }
+
+    /// Husk forbindelsen en side som nettopp ble vist, ble hentet over
+    pub fn remember_security(&mut self, url: &str, security: SecurityInfo) {
+        let key = bookmarks::normalize_url(url);
+        self.security.retain(|(existing, _)| *existing != key);
+        self.security.push_front((key, security));
+        self.security.truncate(RECENT_PAGES);
+    }
+
+    /// Glem alle sidene
+    ///
+    /// # Returns
+    /// Antall sider som ble glemt
+    pub fn clear(&mut self) -> usize {
+        let count = self.pages.len();
+        self.pages.clear();
+        self.sources.clear();
+        self.security.clear();
+        count
+    }
+
+    /// Hent kilden til en nylig vist side
+    pub fn get(&self, url: &str) -> Option<&str> {
+        let key = bookmarks::normalize_url(url);
+        self.pages
+            .iter()
+            .find(|(existing, _)| *existing == key)
+            .map(|(_, markdown)| markdown.as_str())
+    }
+
+    /// Hent bytene til en nylig vist side
+    pub fn raw(&self, url: &str) -> Option<&PageSource> {
+        let key = bookmarks::normalize_url(url);
+        self.sources
+            .iter()
+            .find(|(existing, _)| *existing == key)
+            .map(|(_, source)| source)
+    }
+
+    /// Hent forbindelsen til en nylig vist side
+    pub fn security(&self, url: &str) -> Option<&SecurityInfo> {
+        let key = bookmarks::normalize_url(url);
+        self.security
+            .iter()
+            .find(|(existing, _)| *existing == key)
+            .map(|(_, security)| security)
+    }
+}
+
+/// Lagre en arkivert kopi for et bokmerke
+///
+/// # Arguments
+/// * `dir` - Mappen kopier lagres i
+/// * `id` - Bokmerke-ID (blir filnavnet)
+/// * `markdown` - Sidens markdown-kilde
+///
+/// # Returns
+/// Stien til filen, eller SnapshotError
+pub fn save(dir: &Path, id: &str, markdown: &str) -> Result<PathBuf, SnapshotError> {
+    let valid_id = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid_id {
+        return Err(SnapshotError::InvalidId(id.to_string()));
+    }
+    if markdown.len() > MAX_SNAPSHOT_SIZE {
+        return Err(SnapshotError::TooLarge(markdown.len()));
+    }
+
+    let path = dir.join(format!("{}.md", id));
+    storage::write_atomic(&path, markdown).map_err(|e| SnapshotError::Write(e.to_string()))?;
+    Ok(path)
+}
+
+/// Les en arkivert kopi
+pub fn load(path: &Path) -> Result<String, SnapshotError> {
+    fs::read_to_string(path).map_err(|e| SnapshotError::Read(e.to_string()))
+}
+
+/// Slett en arkivert kopi; en fil som allerede mangler er ikke en feil
+pub fn delete(path: &Path) -> Result<(), SnapshotError> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(SnapshotError::Write(e.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_snapshot_lifecycle() {
+        let dir = tempdir().unwrap();
+
+        let path = save(dir.path(), "bm_1", "# Arkivert\n\nTekst").unwrap();
+        assert_eq!(path, dir.path().join("bm_1.md"));
+        assert_eq!(load(&path).unwrap(), "# Arkivert\n\nTekst");
+
+        // Ny kopi erstatter den gamle
+        save(dir.path(), "bm_1", "# Oppdatert").unwrap();
+        assert_eq!(load(&path).unwrap(), "# Oppdatert");
+
+        delete(&path).unwrap();
+        assert!(!path.exists());
+        assert!(delete(&path).is_ok());
+        assert!(load(&path).is_err());
+    }
+
+    #[test]
+    fn test_snapshot_size_cap() {
+        let dir = tempdir().unwrap();
+        let large = "a".repeat(MAX_SNAPSHOT_SIZE + 1);
+        assert!(matches!(
+            save(dir.path(), "bm_1", &large),
+            Err(SnapshotError::TooLarge(_))
+        ));
+        assert!(!dir.path().join("bm_1.md").exists());
+    }
+
+    #[test]
+    fn test_snapshot_rejects_unsafe_ids() {
+        let dir = tempdir().unwrap();
+        for id in ["", "../bm_1", "bm/1", "bm 1"] {
+            assert!(matches!(
+                save(dir.path(), id, "x"),
+                Err(SnapshotError::InvalidId(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_recent_pages_match_normalized_urls() {
+        let mut recent = RecentPages::default();
+        recent.remember("https://example.com/artikkel/", "# Første");
+        recent.remember("gemini://example.org/", "# Gemini");
+        recent.remember("https://example.com/artikkel", "# Andre");
+
+        assert_eq!(
+            recent.get("https://EXAMPLE.com/artikkel#del"),
+            Some("# Andre")
+        );
+        assert_eq!(recent.get("https://example.com/annen"), None);
+
+        for i in 0..RECENT_PAGES {
+            recent.remember(&format!("https://example.com/{}", i), "x");
+        }
+        assert_eq!(recent.get("gemini://example.org/"), None);
+    }
+
+    #[test]
+    fn test_recent_pages_keep_raw_source() {
+        use crate::page_source::SourceFormat;
+
+        let mut recent = RecentPages::default();
+        let source = PageSource {
+            bytes: b"# Kapsel\n=> /a Lenke\n".to_vec(),
+            format: SourceFormat::Gemtext,
+        };
+        recent.remember("gemini://example.org/", "# Kapsel");
+        recent.remember_raw("gemini://example.org/", source.clone());
+        assert_eq!(recent.raw("gemini://EXAMPLE.org/"), Some(&source));
+        assert_eq!(recent.raw("gemini://example.org/annen"), None);
+
+        let security = SecurityInfo::plain(crate::page_security::Protocol::Gopher);
+        recent.remember_security("gemini://example.org/", security.clone());
+        assert_eq!(recent.security("gemini://EXAMPLE.org/"), Some(&security));
+
+        assert_eq!(recent.clear(), 1);
+        assert_eq!(recent.raw("gemini://example.org/"), None);
+        assert_eq!(recent.security("gemini://example.org/"), None);
+    }
+}
@@ -0,0 +1,249 @@
+//! Filer og adresser fra kommandolinjen
+//!
+//! `bare notater.md` eller `bare gemini://geminiprotocol.net/` åpner filen
+//! eller adressen i stedet for startsiden. Argumentene tolkes i `run()` før
+//! vinduet finnes, og legges i en kø som frontend i hovedvinduet henter med
+//! `get_startup_targets` når den har startet. På macOS kommer filer som åpnes
+//! fra Finder som hendelser i stedet for argumenter, og legges i samme kø.
+//!
+//! Argumenter som verken er en fil som finnes eller en adresse, vises som en
+//! feilside i stedet for å stoppe oppstarten.
+
+use crate::address::{self, AddressAction};
+use crate::settings::DefaultScheme;
+use parking_lot::Mutex;
+use std::path::Path;
+
+/// Filendelser som tyder på at argumentet er en fil, ikke et vertsnavn
+const DOCUMENT_EXTENSIONS: [&str; 5] = ["md", "markdown", "txt", "gmi", "gemini"];
+
+/// Et argument fra kommandolinjen, tolket
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StartupArgument {
+    /// Filstien eller adressen åpnes som om den ble skrevet i adresselinjen
+    Open(String),
+    /// Argumentet kan ikke åpnes; feilsiden viser det som det ble skrevet
+    Invalid(String),
+}
+
+/// Tolk ett argument
+///
+/// En fil som finnes, relativt til `cwd` eller absolutt, åpnes med full sti.
+/// Ellers tolkes argumentet som i adresselinjen, men det som ville blitt et
+/// søk, eller som ser ut som en fil som mangler, er ugyldig.
+///
+/// # Arguments
+/// * `argument` - Argumentet slik det ble gitt
+/// * `cwd` - Mappen programmet ble startet fra
+/// * `scheme_for` - Protokollen som gjelder for et vertsnavn (med små bokstaver)
+///
+/// # Returns
+/// None for tomme argumenter og flagg (`-…`), som ignoreres
+pub fn classify(
+    argument: &str,
+    cwd: &Path,
+    scheme_for: impl Fn(&str) -> DefaultScheme,
+) -> Option<StartupArgument> {
+    let trimmed = argument.trim();
+    if trimmed.is_empty() || trimmed.starts_with('-') {
+        return None;
+    }
+    if !trimmed.contains("://") {
+        let path = cwd.join(trimmed);
+        if path.exists() {
+            return Some(StartupArgument::Open(path.to_string_lossy().into_owned()));
+        }
+        if looks_like_path(trimmed) {
+            return Some(StartupArgument::Invalid(trimmed.to_string()));
+        }
+    }
+
+    let resolution = address::resolve(trimmed, None, address::DEFAULT_SEARCH_ENGINE, scheme_for);
+    Some(match resolution.action {
+        AddressAction::Navigate | AddressAction::Keyword => StartupArgument::Open(resolution.url),
+        // Frontend spør brukeren om protokollen
+        AddressAction::Ask => StartupArgument::Open(trimmed.to_string()),
+        AddressAction::Search => StartupArgument::Invalid(trimmed.to_string()),
+    })
+}
+
+/// Tolk argumentene til programmet
+///
+/// # Arguments
+/// * `args` - Argumentene, uten programnavnet
+/// * `cwd` - Mappen programmet ble startet fra
+/// * `scheme_for` - Protokollen som gjelder for et vertsnavn
+///
+/// # Returns
+/// Målene i samme rekkefølge som argumentene
+pub fn classify_all(
+    args: impl IntoIterator<Item = String>,
+    cwd: &Path,
+    scheme_for: impl Fn(&str) -> DefaultScheme,
+) -> Vec<StartupArgument> {
+    args.into_iter()
+        .filter_map(|arg| classify(&arg, cwd, &scheme_for))
+        .collect()
+}
+
+/// Stier som begynner med `./`, `../` eller `~`, eller har en filendelse
+/// for dokumenter, skal ikke gjettes til å være et vertsnavn
+fn looks_like_path(argument: &str) -> bool {
+    if ["./", "../", ".\\", "..\\", "~"]
+        .iter()
+        .any(|prefix| argument.starts_with(prefix))
+        || address::is_local_path(argument)
+    {
+        return true;
+    }
+    Path::new(argument)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| DOCUMENT_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+/// Køen, registrert med `.manage()`
+pub type StartupTargets = Mutex<StartupQueue>;
+
+/// Mål som venter på at frontend i hovedvinduet skal starte
+#[derive(Debug, Default)]
+pub struct StartupQueue {
+    /// Om frontend har hentet målene; senere mål sendes som `navigate-request`
+    taken: bool,
+    pending: Vec<StartupArgument>,
+}
+
+impl StartupQueue {
+    /// Køen med argumentene programmet ble startet med
+    pub fn new(targets: Vec<StartupArgument>) -> Self {
+        Self {
+            taken: false,
+            pending: targets,
+        }
+    }
+
+    /// Ta imot et mål som kom etter oppstart, f.eks. en fil fra Finder
+    ///
+    /// # Returns
+    /// Målet hvis frontend allerede har hentet køen, None hvis det ble lagt i kø
+    pub fn push(&mut self, target: StartupArgument) -> Option<StartupArgument> {
+        if self.taken {
+            return Some(target);
+        }
+        self.pending.push(target);
+        None
+    }
+
+    /// Frontend har startet og henter målene
+    ///
+    /// # Returns
+    /// Målene i rekkefølge; tom etter første gang, så en omlasting ikke
+    /// åpner dem på nytt
+    pub fn take(&mut self) -> Vec<StartupArgument> {
+        self.taken = true;
+        std::mem::take(&mut self.pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn classify_in(argument: &str, cwd: &Path) -> Option<StartupArgument> {
+        classify(argument, cwd, |_| DefaultScheme::Https)
+    }
+
+    #[test]
+    fn test_existing_files_get_full_path() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("notater")).unwrap();
+        fs::write(dir.path().join("notater").join("todo.md"), "# Todo").unwrap();
+        let expected = dir.path().join("notater").join("todo.md");
+        let expected = expected.to_string_lossy();
+
+        assert_eq!(
+            classify_in("notater/todo.md", dir.path()),
+            Some(StartupArgument::Open(expected.to_string()))
+        );
+        assert_eq!(
+            classify_in(&expected, Path::new("/")),
+            Some(StartupArgument::Open(expected.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_addresses_are_resolved() {
+        let cwd = tempfile::tempdir().unwrap();
+        assert_eq!(
+            classify_in("gemini://geminiprotocol.net/", cwd.path()),
+            Some(StartupArgument::Open("gemini://geminiprotocol.net/".into()))
+        );
+        assert_eq!(
+            classify_in("Example.com/side", cwd.path()),
+            Some(StartupArgument::Open("https://example.com/side".into()))
+        );
+        assert_eq!(
+            classify(" example.org ", cwd.path(), |_| DefaultScheme::Ask),
+            Some(StartupArgument::Open("example.org".into()))
+        );
+    }
+
+    #[test]
+    fn test_missing_files_and_words_are_invalid() {
+        let cwd = tempfile::tempdir().unwrap();
+        for argument in [
+            "mangler.md",
+            "./mangler",
+            "../mangler/README.txt",
+            "~/notater.gmi",
+            "/finnes/ikke.md",
+            "bare ord",
+            "ord",
+        ] {
+            assert_eq!(
+                classify_in(argument, cwd.path()),
+                Some(StartupArgument::Invalid(argument.to_string())),
+                "{:?}",
+                argument
+            );
+        }
+    }
+
+    #[test]
+    fn test_flags_are_ignored() {
+        let cwd = tempfile::tempdir().unwrap();
+        let targets = classify_all(
+            ["--verbose", "", "a.example", "-psn_0_12345", "b.md"].map(String::from),
+            cwd.path(),
+            |_| DefaultScheme::Https,
+        );
+        assert_eq!(
+            targets,
+            [
+                StartupArgument::Open("https://a.example".into()),
+                StartupArgument::Invalid("b.md".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_queue_is_handed_over_once() {
+        let mut queue = StartupQueue::new(vec![StartupArgument::Open("/a.md".into())]);
+        assert_eq!(queue.push(StartupArgument::Open("/b.md".into())), None);
+        assert_eq!(
+            queue.take(),
+            [
+                StartupArgument::Open("/a.md".into()),
+                StartupArgument::Open("/b.md".into()),
+            ]
+        );
+
+        // En omlasting åpner ikke målene på nytt, og nye mål sendes videre
+        assert!(queue.take().is_empty());
+        assert_eq!(
+            queue.push(StartupArgument::Open("/c.md".into())),
+            Some(StartupArgument::Open("/c.md".into()))
+        );
+    }
+}
@@ -0,0 +1,326 @@
+//! Trygg lagring av JSON-filer
+//!
+//! Felles hjelpefunksjoner for bokmerker, innstillinger og TOFU-lageret.
+//! Filer skrives først til en midlertidig fil som deretter døpes om, slik at
+//! et krasj eller full disk midt i skrivingen aldri etterlater en avkuttet
+//! fil. Ved innlasting tas det vare på en `.bak`-kopi av siste gyldige fil,
+//! som brukes hvis hovedfilen senere ikke kan leses. I tillegg kan tidligere
+//! versjoner roteres til nummererte kopier (`.1` er den nyeste).
+
+use log::warn;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use thiserror::Error;
+
+/// Feil som kan oppstå ved lesing og skriving av lagrede filer
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("{0}")]
+    Io(#[from] io::Error),
+
+    #[error("Ugyldig JSON: {0}")]
+    Parse(String),
+}
+
+/// Sti til en søskenfil med ekstra endelse (f.eks. "bookmarks.json.bak")
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name: OsString = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Sti til sikkerhetskopien av en fil
+pub fn backup_path(path: &Path) -> PathBuf {
+    sibling(path, ".bak")
+}
+
+/// En rotert sikkerhetskopi
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Backup {
+    /// Generasjon (1 er den nyeste)
+    pub generation: u32,
+    /// Når kopien ble skrevet (Unix timestamp)
+    pub modified: u64,
+}
+
+/// Sti til en rotert sikkerhetskopi (f.eks. "bookmarks.json.1")
+pub fn generation_path(path: &Path, generation: u32) -> PathBuf {
+    sibling(path, &format!(".{}", generation))
+}
+
+/// Roter sikkerhetskopier før en ny lagring
+///
+/// `.1` blir `.2` og så videre, kopier eldre enn `generations` slettes, og
+/// den nåværende filen kopieres til `.1`. Gjør ingenting hvis filen ikke
+/// finnes ennå eller `generations` er 0.
+pub fn rotate_backups(path: &Path, generations: u32) -> io::Result<()> {
+    // Fjern kopier utover grensen (også etter at grensen er senket)
+    let mut generation = generations.max(1);
+    while generation_path(path, generation).exists() {
+        fs::remove_file(generation_path(path, generation))?;
+        generation += 1;
+    }
+
+    if generations == 0 || !path.exists() {
+        return Ok(());
+    }
+
+    for generation in (1..generations).rev() {
+        let from = generation_path(path, generation);
+        if from.exists() {
+            fs::rename(&from, generation_path(path, generation + 1))?;
+        }
+    }
+    fs::copy(path, generation_path(path, 1))?;
+    Ok(())
+}
+
+/// List tilgjengelige roterte sikkerhetskopier, nyeste først
+pub fn list_backups(path: &Path, generations: u32) -> Vec<Backup> {
+    (1..=generations)
+        .filter_map(|generation| {
+            let modified = fs::metadata(generation_path(path, generation))
+                .and_then(|m| m.modified())
+                .ok()?;
+            let modified = modified
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            Some(Backup {
+                generation,
+                modified,
+            })
+        })
+        .collect()
+}
+
+/// Endringstid og størrelse på en fil, hvis den finnes
+///
+/// Brukes for å kjenne igjen våre egne lagringer når filen overvåkes.
+pub fn file_stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Skriv en fil atomisk: først til en midlertidig fil, deretter rename
+///
+/// Overordnede mapper opprettes ved behov.
+pub fn write_atomic(path: &Path, content: &str) -> io::Result<()> {
+    write_file(path, content, false)
+}
+
+/// Skriv en fil atomisk som bare eieren kan lese (0600 på unix)
+///
+/// For filer med hemmeligheter, f.eks. private nøkler.
+pub fn write_private(path: &Path, content: &str) -> io::Result<()> {
+    write_file(path, content, true)
+}
+
+fn write_file(path: &Path, content: &str, private: bool) -> io::Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+
+    let temp_path = sibling(path, ".tmp");
+    let result = create_file(&temp_path, private)
+        .and_then(|mut file| {
+            file.write_all(content.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&temp_path, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+/// Opprett (eller tøm) en fil, eventuelt med tilgang bare for eieren
+fn create_file(path: &Path, private: bool) -> io::Result<fs::File> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        let file = options.open(path)?;
+        // Modusen over gjelder bare nye filer; en gammel .tmp kan ha en annen
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        return Ok(file);
+    }
+    #[cfg(not(unix))]
+    let _ = private;
+    options.open(path)
+}
+
+/// Serialiser en verdi som formatert JSON og skriv den atomisk
+pub fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), StorageError> {
+    let content =
+        serde_json::to_string_pretty(value).map_err(|e| StorageError::Parse(e.to_string()))?;
+    write_atomic(path, &content)?;
+    Ok(())
+}
+
+/// Som [`write_json`], men bare eieren kan lese filen
+pub fn write_private_json<T: Serialize>(path: &Path, value: &T) -> Result<(), StorageError> {
+    let content =
+        serde_json::to_string_pretty(value).map_err(|e| StorageError::Parse(e.to_string()))?;
+    write_private(path, &content)?;
+    Ok(())
+}
+
+/// Les en JSON-fil, med sikkerhetskopi som reserve
+///
+/// Når hovedfilen kan leses, kopieres den til `.bak`. Hvis hovedfilen finnes
+/// men ikke kan tolkes, logges en advarsel og sikkerhetskopien brukes i
+/// stedet. Hovedfilen blir da liggende urørt til neste lagring.
+///
+/// # Returns
+/// `Ok(None)` hvis filen ikke finnes, ellers innholdet eller feilen fra
+/// hovedfilen når heller ikke sikkerhetskopien kan brukes
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, StorageError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let error = match parse_file(path) {
+        Ok(value) => {
+            if let Err(e) = fs::copy(path, backup_path(path)) {
+                warn!("Kunne ikke ta sikkerhetskopi av {}: {}", path.display(), e);
+            }
+            return Ok(Some(value));
+        }
+        Err(e) => e,
+    };
+
+    let backup = backup_path(path);
+    match parse_file(&backup) {
+        Ok(value) => {
+            warn!(
+                "Kunne ikke lese {} ({}), bruker sikkerhetskopi {}",
+                path.display(),
+                error,
+                backup.display()
+            );
+            Ok(Some(value))
+        }
+        Err(_) => Err(error),
+    }
+}
+
+/// Les og tolk én JSON-fil, uten sikkerhetskopi som reserve
+pub fn parse_file<T: DeserializeOwned>(path: &Path) -> Result<T, StorageError> {
+    let content = fs::read_to_string(path)?;
+    serde_json::from_str(&content).map_err(|e| StorageError::Parse(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tempfile::tempdir;
+
+    type Data = HashMap<String, u32>;
+
+    fn data(value: u32) -> Data {
+        HashMap::from([("verdi".to_string(), value)])
+    }
+
+    #[test]
+    fn test_write_atomic_leaves_no_temp_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("undermappe").join("fil.json");
+
+        write_json(&path, &data(1)).unwrap();
+        write_json(&path, &data(2)).unwrap();
+
+        assert_eq!(read_json::<Data>(&path).unwrap(), Some(data(2)));
+        assert!(!sibling(&path, ".tmp").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_private_json_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("hemmelig.json");
+        fs::write(&path, "{}").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        write_private_json(&path, &data(1)).unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(parse_file::<Data>(&path).unwrap(), data(1));
+    }
+
+    #[test]
+    fn test_missing_file_is_none() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("finnes-ikke.json");
+        assert!(read_json::<Data>(&path).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_truncated_file_recovers_from_backup() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("fil.json");
+
+        write_json(&path, &data(7)).unwrap();
+        // Innlasting tar sikkerhetskopi av siste gyldige fil
+        assert_eq!(read_json::<Data>(&path).unwrap(), Some(data(7)));
+
+        fs::write(&path, "{\"verdi\": ").unwrap();
+        assert_eq!(read_json::<Data>(&path).unwrap(), Some(data(7)));
+        // Sikkerhetskopien skal ikke overskrives av den ødelagte filen
+        assert_eq!(parse_file::<Data>(&backup_path(&path)).unwrap(), data(7));
+    }
+
+    #[test]
+    fn test_corrupt_without_backup_is_error() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("fil.json");
+        fs::write(&path, "ikke json").unwrap();
+
+        assert!(matches!(
+            read_json::<Data>(&path),
+            Err(StorageError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_rotate_backups_order() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("fil.json");
+
+        // Ingen fil ennå: ingenting å rotere
+        rotate_backups(&path, 3).unwrap();
+        assert!(list_backups(&path, 3).is_empty());
+
+        for value in 1..=5 {
+            rotate_backups(&path, 3).unwrap();
+            write_json(&path, &data(value)).unwrap();
+        }
+
+        let generation = |g| parse_file::<Data>(&generation_path(&path, g)).unwrap();
+        assert_eq!(generation(1), data(4));
+        assert_eq!(generation(2), data(3));
+        assert_eq!(generation(3), data(2));
+        assert!(!generation_path(&path, 4).exists());
+        let generations: Vec<u32> = list_backups(&path, 3)
+            .iter()
+            .map(|b| b.generation)
+            .collect();
+        assert_eq!(generations, vec![1, 2, 3]);
+
+        // Senket grense rydder bort eldre kopier
+        rotate_backups(&path, 1).unwrap();
+        assert_eq!(generation(1), data(5));
+        assert!(!generation_path(&path, 2).exists());
+        assert!(!generation_path(&path, 3).exists());
+    }
+}
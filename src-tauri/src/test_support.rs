@@ -0,0 +1,297 @@
+//! Servere for tester av nettverksklientene
+//!
+//! Hver server lytter på en tilfeldig port på 127.0.0.1 og svarer etter et
+//! manus: en funksjon som får forespørselen og gir tilbake svaret. Alle
+//! forespørsler tas vare på, så testene kan sjekke hva klienten sendte.
+//!
+//! Gemini-serveren bruker de selvsignerte sertifikatene i `fixtures/tls/`,
+//! laget med
+//!
+//! ```text
+//! openssl req -x509 -newkey ec -pkeyopt ec_paramgen_curve:prime256v1 -nodes \
+//!     -keyout first.key -out first.crt -days 36500 -subj /CN=localhost \
+//!     -addext subjectAltName=DNS:localhost,IP:127.0.0.1
+//! ```
+//!
+//! Den ber om klientsertifikat uten å kreve det, og godtar alle.
+
+use crate::page_security;
+use parking_lot::Mutex;
+use rustls::client::danger::HandshakeSignatureValid;
+use rustls::crypto::WebPkiSupportedAlgorithms;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, UnixTime};
+use rustls::server::danger::{ClientCertVerified, ClientCertVerifier};
+use rustls::{DigitallySignedStruct, DistinguishedName, ServerConfig, SignatureScheme};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tokio_rustls::TlsAcceptor;
+
+/// Svaret på én forespørsel
+#[derive(Debug, Clone)]
+pub enum Reply {
+    /// Send bytene og lukk tilkoblingen
+    Close(Vec<u8>),
+    /// Send bytene og hold tilkoblingen åpen uten å sende mer
+    Stall(Vec<u8>),
+}
+
+impl Reply {
+    /// Send teksten og lukk tilkoblingen
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::Close(text.into().into_bytes())
+    }
+}
+
+/// Forespørslene serveren har fått, i rekkefølge
+type Requests = Arc<Mutex<Vec<String>>>;
+
+/// Manuset til en server
+type Script = Arc<dyn Fn(&str) -> Reply + Send + Sync>;
+
+/// Manuset til Gemini-serveren; får også fingerprint for klientsertifikatet
+type IdentifiedScript = Arc<dyn Fn(&str, Option<&str>) -> Reply + Send + Sync>;
+
+/// Les forespørselslinjen, svar etter manuset og lukk eller heng
+async fn serve<S>(stream: S, script: Script, requests: Requests)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut stream = BufReader::new(stream);
+    let mut line = String::new();
+    if stream.read_line(&mut line).await.is_err() {
+        return;
+    }
+    let request = line.trim_end_matches(['\r', '\n']).to_string();
+    let reply = script(&request);
+    requests.lock().push(request);
+
+    match reply {
+        Reply::Close(bytes) => {
+            let _ = stream.write_all(&bytes).await;
+            let _ = stream.shutdown().await;
+        }
+        Reply::Stall(bytes) => {
+            let _ = stream.write_all(&bytes).await;
+            let _ = stream.flush().await;
+            std::future::pending::<()>().await;
+        }
+    }
+}
+
+/// Gopher-server som svarer på selektorer
+pub struct GopherServer {
+    pub addr: SocketAddr,
+    requests: Requests,
+}
+
+impl GopherServer {
+    /// Start serveren
+    ///
+    /// # Arguments
+    /// * `script` - Får selektoren (med søket etter tab) og gir svaret
+    pub async fn start(script: impl Fn(&str) -> Reply + Send + Sync + 'static) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Requests::default();
+        let script: Script = Arc::new(script);
+
+        let received = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                tokio::spawn(serve(socket, script.clone(), received.clone()));
+            }
+        });
+        Self { addr, requests }
+    }
+
+    /// Adressen til en side på serveren, f.eks. `/0/tekst`
+    pub fn url(&self, path: &str) -> String {
+        format!("gopher://{}{}", self.addr, path)
+    }
+
+    /// Selektorene serveren har fått
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().clone()
+    }
+}
+
+/// Sertifikatet Gemini-serveren viser frem
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Identity {
+    First,
+    Second,
+}
+
+impl Identity {
+    fn server_config(self) -> Arc<ServerConfig> {
+        let (cert, key): (&[u8], &[u8]) = match self {
+            Self::First => (
+                include_bytes!("../fixtures/tls/first.crt"),
+                include_bytes!("../fixtures/tls/first.key"),
+            ),
+            Self::Second => (
+                include_bytes!("../fixtures/tls/second.crt"),
+                include_bytes!("../fixtures/tls/second.key"),
+            ),
+        };
+        let certs = CertificateDer::pem_slice_iter(cert)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let key = PrivateKeyDer::from_pem_slice(key).unwrap();
+
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let verifier = Arc::new(AnyClientCert(provider.signature_verification_algorithms));
+        let config = ServerConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_client_cert_verifier(verifier)
+            .with_single_cert(certs, key)
+            .unwrap();
+        Arc::new(config)
+    }
+}
+
+/// Ber om klientsertifikat, men godtar alle og ingen
+#[derive(Debug)]
+struct AnyClientCert(WebPkiSupportedAlgorithms);
+
+impl ClientCertVerifier for AnyClientCert {
+    fn client_auth_mandatory(&self) -> bool {
+        false
+    }
+
+    fn root_hint_subjects(&self) -> &[DistinguishedName] {
+        &[]
+    }
+
+    fn verify_client_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _now: UnixTime,
+    ) -> Result<ClientCertVerified, rustls::Error> {
+        Ok(ClientCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, &self.0)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, &self.0)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.supported_schemes()
+    }
+}
+
+/// Gemini-server over TLS som svarer på adresser
+pub struct GeminiServer {
+    pub addr: SocketAddr,
+    requests: Requests,
+    config: Arc<Mutex<Arc<ServerConfig>>>,
+}
+
+impl GeminiServer {
+    /// Start serveren med det første sertifikatet
+    ///
+    /// # Arguments
+    /// * `script` - Får adressen klienten ba om og gir hele svaret, med header
+    pub async fn start(script: impl Fn(&str) -> Reply + Send + Sync + 'static) -> Self {
+        Self::start_identified(move |request, _| script(request)).await
+    }
+
+    /// Start serveren med et manus som ser klientsertifikatet
+    ///
+    /// # Arguments
+    /// * `script` - Får adressen og fingerprint for klientsertifikatet, hvis
+    ///   klienten sendte et
+    pub async fn start_identified(
+        script: impl Fn(&str, Option<&str>) -> Reply + Send + Sync + 'static,
+    ) -> Self {
+        // GeminiClient bygger sin TLS-konfigurasjon med standardleverandøren
+        let _ = rustls::crypto::ring::default_provider().install_default();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Requests::default();
+        let config = Arc::new(Mutex::new(Identity::First.server_config()));
+        let script: IdentifiedScript = Arc::new(script);
+
+        let received = requests.clone();
+        let current = config.clone();
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                let acceptor = TlsAcceptor::from(current.lock().clone());
+                let script = script.clone();
+                let received = received.clone();
+                tokio::spawn(async move {
+                    if let Ok(stream) = acceptor.accept(socket).await {
+                        let fingerprint = stream
+                            .get_ref()
+                            .1
+                            .peer_certificates()
+                            .and_then(|certs| certs.first())
+                            .map(|cert| page_security::fingerprint(cert.as_ref()));
+                        let script: Script =
+                            Arc::new(move |request| script(request, fingerprint.as_deref()));
+                        serve(stream, script, received).await;
+                    }
+                });
+            }
+        });
+        Self {
+            addr,
+            requests,
+            config,
+        }
+    }
+
+    /// Bytt sertifikatet for nye tilkoblinger
+    pub fn set_identity(&self, identity: Identity) {
+        *self.config.lock() = identity.server_config();
+    }
+
+    /// Adressen til en side på serveren, f.eks. `/side.gmi`
+    pub fn url(&self, path: &str) -> String {
+        format!("gemini://{}{}", self.addr, path)
+    }
+
+    /// Adressene serveren har fått forespørsler om
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().clone()
+    }
+}
+
+/// HTTP-server som svarer én gang med en HTML-side
+pub async fn http_server(body: impl Into<String>) -> SocketAddr {
+    let body = body.into();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = [0u8; 4096];
+        let _ = socket.read(&mut request).await;
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        socket.write_all(response.as_bytes()).await.unwrap();
+    });
+    addr
+}
@@ -0,0 +1,155 @@
+//! Hvor tiden går når en side lastes
+//!
+//! Klientene måler nettverksdelen selv (`NetworkTiming`): tilkobling,
+//! TLS-handshake og overføring. Konvertering og rendring måles av
+//! `LoadProgress` mellom stegene. Til sammen blir det `TimingInfo`, som følger
+//! med `RenderedPage` og `done`-steget i `page-load-progress`.
+//!
+//! reqwest skiller ikke oppslag, tilkobling og TLS fra hverandre, så for
+//! HTTP(S) er `connect_ms` hele ventetiden frem til responsheaderne kom, og
+//! `tls_ms` mangler.
+
+use serde::{Deserialize, Serialize};
+use std::ops::AddAssign;
+use std::time::Duration;
+
+/// Tiden nettverksdelen av en henting tok
+///
+/// None betyr at fasen ikke fant sted, f.eks. TLS for Gopher.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NetworkTiming {
+    /// Oppslag og TCP-tilkobling
+    pub connect: Option<Duration>,
+    /// TLS-handshake
+    pub tls: Option<Duration>,
+    /// Forespørsel, header og body
+    pub transfer: Option<Duration>,
+}
+
+/// Summerer fasene, f.eks. over en kjede av redirects
+impl AddAssign for NetworkTiming {
+    fn add_assign(&mut self, other: Self) {
+        fn add(a: Option<Duration>, b: Option<Duration>) -> Option<Duration> {
+            match (a, b) {
+                (Some(a), Some(b)) => Some(a + b),
+                (a, b) => a.or(b),
+            }
+        }
+        self.connect = add(self.connect, other.connect);
+        self.tls = add(self.tls, other.tls);
+        self.transfer = add(self.transfer, other.transfer);
+    }
+}
+
+/// Hvor lang tid hver fase av en navigering tok, i millisekunder
+///
+/// Faser som ikke fant sted, mangler i JSON.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimingInfo {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transfer_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub convert_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub render_ms: Option<u64>,
+    /// Fra navigeringen startet til siden var klar
+    pub total_ms: u64,
+}
+
+impl TimingInfo {
+    /// Sett sammen fasene
+    ///
+    /// # Arguments
+    /// * `network` - Nettverksfasene, målt av klienten
+    /// * `convert` - Konvertering til markdown, hvis siden ble konvertert
+    /// * `render` - Rendring av markdown
+    /// * `total` - Hele navigeringen
+    pub fn new(
+        network: NetworkTiming,
+        convert: Option<Duration>,
+        render: Option<Duration>,
+        total: Duration,
+    ) -> Self {
+        let ms = |duration: Option<Duration>| duration.map(|d| d.as_millis() as u64);
+        Self {
+            connect_ms: ms(network.connect),
+            tls_ms: ms(network.tls),
+            transfer_ms: ms(network.transfer),
+            convert_ms: ms(convert),
+            render_ms: ms(render),
+            total_ms: total.as_millis() as u64,
+        }
+    }
+
+    /// Fasene på én linje for loggen, f.eks.
+    /// `connect 12 ms, transfer 80 ms, render 3 ms, total 97 ms`
+    pub fn summary(&self) -> String {
+        let phases = [
+            ("connect", self.connect_ms),
+            ("tls", self.tls_ms),
+            ("transfer", self.transfer_ms),
+            ("convert", self.convert_ms),
+            ("render", self.render_ms),
+            ("total", Some(self.total_ms)),
+        ];
+        phases
+            .iter()
+            .filter_map(|(name, ms)| ms.map(|ms| format!("{} {} ms", name, ms)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_timing_sums_redirects() {
+        let mut timing = NetworkTiming {
+            connect: Some(Duration::from_millis(10)),
+            tls: None,
+            transfer: Some(Duration::from_millis(5)),
+        };
+        timing += NetworkTiming {
+            connect: Some(Duration::from_millis(20)),
+            tls: Some(Duration::from_millis(7)),
+            transfer: None,
+        };
+        assert_eq!(timing.connect, Some(Duration::from_millis(30)));
+        assert_eq!(timing.tls, Some(Duration::from_millis(7)));
+        assert_eq!(timing.transfer, Some(Duration::from_millis(5)));
+    }
+
+    #[test]
+    fn test_missing_phases_are_left_out() {
+        let network = NetworkTiming {
+            connect: Some(Duration::from_millis(12)),
+            tls: None,
+            transfer: Some(Duration::from_millis(80)),
+        };
+        let timing = TimingInfo::new(
+            network,
+            None,
+            Some(Duration::from_millis(3)),
+            Duration::from_millis(97),
+        );
+        assert_eq!(
+            serde_json::to_value(timing).unwrap(),
+            serde_json::json!({
+                "connect_ms": 12,
+                "transfer_ms": 80,
+                "render_ms": 3,
+                "total_ms": 97,
+            })
+        );
+        assert_eq!(
+            timing.summary(),
+            "connect 12 ms, transfer 80 ms, render 3 ms, total 97 ms"
+        );
+    }
+}
@@ -0,0 +1,137 @@
+//! Overvåking av filer som kan endres utenfra
+//!
+//! Brukes for å lese inn bokmerker på nytt når filen endres av en annen
+//! prosess, f.eks. et synkroniseringsverktøy, og for å vise lokale filer på
+//! nytt når de lagres i en editor.
+
+use log::warn;
+use notify_debouncer_mini::notify::{self, RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, DebouncedEventKind, Debouncer};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// En aktiv overvåking; stopper når den droppes
+pub type Watcher = Debouncer<RecommendedWatcher>;
+
+/// Hvor lenge vi venter på at en serie endringer skal roe seg
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Overvåk en fil og kall `on_change` når den endres
+///
+/// Mappen filen ligger i overvåkes, siden atomiske lagringer erstatter
+/// filen med en ny i stedet for å skrive til den.
+///
+/// # Arguments
+/// * `path` - Filen som skal overvåkes
+/// * `on_change` - Kalles fra en egen tråd etter hver serie endringer
+///
+/// # Returns
+/// Overvåkeren, som må holdes i live så lenge filen skal overvåkes
+pub fn watch_file<F>(path: &Path, on_change: F) -> Result<Watcher, notify::Error>
+where
+    F: Fn() + Send + 'static,
+{
+    let target: PathBuf = path.to_path_buf();
+    let dir = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."))
+        .to_path_buf();
+    std::fs::create_dir_all(&dir)?;
+
+    let file_name = target.file_name().map(|n| n.to_os_string());
+    let mut debouncer = new_debouncer(DEBOUNCE, move |result: DebounceEventResult| match result {
+        Ok(events) => {
+            // AnyContinuous kommer midt i en lang serie skrivinger; vent til
+            // det har roet seg, så en lagring gir én melding
+            if events.iter().any(|event| {
+                event.kind == DebouncedEventKind::Any
+                    && event.path.file_name().map(|n| n.to_os_string()) == file_name
+            }) {
+                on_change();
+            }
+        }
+        Err(e) => warn!("Feil ved overvåking av {}: {}", target.display(), e),
+    })?;
+
+    debouncer
+        .watcher()
+        .watch(&dir, RecursiveMode::NonRecursive)?;
+    Ok(debouncer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_watch_file_ignores_other_files() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("bookmarks.json");
+        std::fs::write(&path, "{}").unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        let _watcher = watch_file(&path, move || {
+            let _ = tx.send(());
+        })
+        .unwrap();
+
+        std::fs::write(dir.path().join("annet.json"), "{}").unwrap();
+        assert!(rx.recv_timeout(DEBOUNCE * 3).is_err());
+
+        std::fs::write(&path, "{\"bookmarks\": []}").unwrap();
+        assert!(rx.recv_timeout(Duration::from_secs(5)).is_ok());
+    }
+
+    #[test]
+    fn test_watch_file_debounces_save_storm() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("notater.md");
+        std::fs::write(&path, "# Notater").unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        let _watcher = watch_file(&path, move || {
+            let _ = tx.send(());
+        })
+        .unwrap();
+
+        // Mange skrivinger tett etter hverandre gir én melding
+        for i in 0..5 {
+            std::fs::write(&path, format!("# Notater {}", i)).unwrap();
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert!(rx.recv_timeout(Duration::from_secs(5)).is_ok());
+        assert!(rx.recv_timeout(DEBOUNCE * 3).is_err());
+    }
+
+    #[test]
+    fn test_watch_file_survives_delete_and_recreate() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("notater.md");
+        std::fs::write(&path, "# Notater").unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        let _watcher = watch_file(&path, move || {
+            let _ = tx.send(());
+        })
+        .unwrap();
+
+        // Atomisk lagring: skriv til en midlertidig fil og døp den om
+        let tmp = dir.path().join(".notater.md.tmp");
+        std::fs::write(&tmp, "# Ny versjon").unwrap();
+        std::fs::rename(&tmp, &path).unwrap();
+        assert!(rx.recv_timeout(Duration::from_secs(5)).is_ok());
+
+        // Slettet og laget på nytt
+        std::fs::remove_file(&path).unwrap();
+        assert!(rx.recv_timeout(Duration::from_secs(5)).is_ok());
+        std::fs::write(&path, "# Tilbake").unwrap();
+        assert!(rx.recv_timeout(Duration::from_secs(5)).is_ok());
+
+        // Overvåkingen følger fortsatt den nye filen
+        std::fs::write(&path, "# Endret igjen").unwrap();
+        assert!(rx.recv_timeout(Duration::from_secs(5)).is_ok());
+    }
+}
@@ -0,0 +1,507 @@
+//! Navigeringstilstand per vindu
+//!
+//! Hvert vindu har sin egen adresse, sin egen navigeringsstabel og sin egen
+//! henting som pågår. Tilstanden holdes i Tauri sin managed state og slås opp
+//! med vinduets etikett, slik at to vinduer aldri ser hverandres sider.
+//!
+//! Hentinger legger den endelige adressen, etter omdirigeringer, på
+//! navigeringsstabelen. De siste hentede sidene holdes i minnet, slik at
+//! tilbake og frem kan vise dem uten å hente dem på nytt.
+
+use crate::commands::RenderedPage;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+
+/// Prefiks for etikettene til nye vinduer, f.eks. "window-2"
+pub const WINDOW_LABEL_PREFIX: &str = "window-";
+
+/// Største antall sider på tilbake-stabelen; de eldste glemmes
+pub const MAX_BACK_ENTRIES: usize = 50;
+
+/// Antall hentede sider hvert vindu holder i minnet
+pub const PAGE_CACHE_SIZE: usize = 10;
+
+/// Tilstanden til alle vinduer, registrert med `.manage()`
+pub type WindowRegistry = Mutex<WindowStates>;
+
+/// Navigeringstilstand for ett vindu
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct WindowState {
+    /// Adressen til siden som vises
+    pub current_url: Option<String>,
+    /// Tidligere sider, eldste først
+    pub back: Vec<String>,
+    /// Sider foran den som vises, nærmeste sist
+    pub forward: Vec<String>,
+    /// Adressen et nytt vindu skal åpne, til frontend har hentet den
+    #[serde(skip)]
+    initial_url: Option<String>,
+    /// Øker for hver henting som startes; eldre hentinger er utdatert
+    #[serde(skip)]
+    fetch_generation: u64,
+    /// Hentingen som laster siden et steg tilbake eller frem landet på;
+    /// den erstatter adressen i stedet for å legge den på stabelen
+    #[serde(skip)]
+    restore_generation: Option<u64>,
+    /// De sist hentede sidene, eldste først
+    #[serde(skip)]
+    pages: VecDeque<RenderedPage>,
+}
+
+impl WindowState {
+    /// Registrer at vinduet har navigert til en side
+    ///
+    /// Siden som vistes legges på tilbake-stabelen og fremover-stabelen
+    /// tømmes. Å laste samme side på nytt endrer ikke stablene.
+    pub fn visit(&mut self, url: &str) {
+        if self.current_url.as_deref() == Some(url) {
+            return;
+        }
+        if let Some(previous) = self.current_url.replace(url.to_string()) {
+            self.back.push(previous);
+        }
+        if self.back.len() > MAX_BACK_ENTRIES {
+            self.back.remove(0);
+        }
+        self.forward.clear();
+    }
+
+    /// Gå ett steg tilbake
+    ///
+    /// # Returns
+    /// Adressen vinduet nå står på, eller None hvis tilbake-stabelen er tom
+    pub fn go_back(&mut self) -> Option<String> {
+        let url = self.back.pop()?;
+        if let Some(current) = self.current_url.replace(url.clone()) {
+            self.forward.push(current);
+        }
+        Some(url)
+    }
+
+    /// Gå ett steg frem
+    ///
+    /// # Returns
+    /// Adressen vinduet nå står på, eller None hvis fremover-stabelen er tom
+    pub fn go_forward(&mut self) -> Option<String> {
+        let url = self.forward.pop()?;
+        if let Some(current) = self.current_url.replace(url.clone()) {
+            self.back.push(current);
+        }
+        Some(url)
+    }
+
+    /// Om det finnes en side å gå tilbake til
+    pub fn can_go_back(&self) -> bool {
+        !self.back.is_empty()
+    }
+
+    /// Om det finnes en side å gå frem til
+    pub fn can_go_forward(&self) -> bool {
+        !self.forward.is_empty()
+    }
+
+    /// Siden med adressen, hvis den fortsatt er i minnet
+    /// Siden med adressen, hvis vinduet holder den i minnet
+    pub fn cached_page(&self, url: &str) -> Option<&RenderedPage> {
+        self.pages
+            .iter()
+            .find(|page| page.url.as_deref() == Some(url))
+    }
+}
+
+/// Et steg tilbake eller frem i et vindu
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HistoryStep {
+    /// Adressen vinduet står på etter steget
+    pub url: String,
+    /// Siden fra minnet, eller None hvis frontend må hente den på nytt
+    pub page: Option<RenderedPage>,
+}
+
+/// Bevis på at en henting er startet i et vindu
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchTicket {
+    label: String,
+    generation: u64,
+}
+
+/// Tilstanden til alle åpne vinduer, med etiketten som nøkkel
+#[derive(Debug, Default)]
+pub struct WindowStates {
+    windows: HashMap<String, WindowState>,
+    opened: u64,
+}
+
+impl WindowStates {
+    /// Tilstanden til et vindu, hvis det har navigert
+    pub fn get(&self, label: &str) -> Option<&WindowState> {
+        self.windows.get(label)
+    }
+
+    /// Tilstanden til et vindu, opprettet ved første bruk
+    fn entry(&mut self, label: &str) -> &mut WindowState {
+        self.windows.entry(label.to_string()).or_default()
+    }
+
+    /// Reserver en etikett for et nytt vindu som skal åpne en adresse
+    ///
+    /// # Arguments
+    /// * `url` - Adressen vinduet skal navigere til
+    ///
+    /// # Returns
+    /// Etiketten vinduet må opprettes med
+    pub fn open(&mut self, url: &str) -> String {
+        let label = loop {
+            self.opened += 1;
+            // Hovedvinduet er nummer 1
+            let label = format!("{}{}", WINDOW_LABEL_PREFIX, self.opened + 1);
+            if !self.windows.contains_key(&label) {
+                break label;
+            }
+        };
+        self.entry(&label).initial_url = Some(url.to_string());
+        label
+    }
+
+    /// Hent adressen et nytt vindu skal åpne
+    ///
+    /// # Returns
+    /// Adressen første gang den spørres etter, ellers None
+    pub fn take_initial_url(&mut self, label: &str) -> Option<String> {
+        self.windows.get_mut(label)?.initial_url.take()
+    }
+
+    /// Start en henting i et vindu
+    ///
+    /// Hentinger som allerede pågår i samme vindu blir utdatert. Hentinger i
+    /// andre vinduer påvirkes ikke.
+    pub fn begin_fetch(&mut self, label: &str) -> FetchTicket {
+        let state = self.entry(label);
+        state.fetch_generation += 1;
+        FetchTicket {
+            label: label.to_string(),
+            generation: state.fetch_generation,
+        }
+    }
+
+    /// Om hentingen fortsatt er den siste som er startet i vinduet
+    pub fn is_current(&self, ticket: &FetchTicket) -> bool {
+        self.windows
+            .get(&ticket.label)
+            .is_some_and(|state| state.fetch_generation == ticket.generation)
+    }
+
+    /// Fullfør en henting og registrer siden vinduet endte opp på
+    ///
+    /// Henter frontend siden et steg tilbake eller frem landet på, erstatter
+    /// den endelige adressen den på stabelen, så en omdirigering ikke gir et
+    /// nytt steg.
+    ///
+    /// # Arguments
+    /// * `ticket` - Fra `begin_fetch`
+    /// * `url` - Den endelige adressen, etter eventuelle omdirigeringer
+    ///
+    /// # Returns
+    /// false hvis en nyere henting har tatt over; da endres ingenting
+    pub fn finish_fetch(&mut self, ticket: &FetchTicket, url: Option<&str>) -> bool {
+        if !self.is_current(ticket) {
+            return false;
+        }
+        let state = self.entry(&ticket.label);
+        let restoring = state.restore_generation.take() == Some(ticket.generation);
+        match url {
+            Some(url) if restoring => state.current_url = Some(url.to_string()),
+            Some(url) => state.visit(url),
+            None => {}
+        }
+        true
+    }
+
+    /// Hold en hentet side i minnet, så tilbake og frem kan vise den igjen
+    ///
+    /// Sider uten adresse huskes ikke. Er minnet fullt, glemmes den eldste.
+    pub fn remember_page(&mut self, label: &str, page: RenderedPage) {
+        let Some(url) = page.url.clone() else {
+            return;
+        };
+        let pages = &mut self.entry(label).pages;
+        pages.retain(|cached| cached.url.as_deref() != Some(url.as_str()));
+        pages.push_back(page);
+        if pages.len() > PAGE_CACHE_SIZE {
+            pages.pop_front();
+        }
+    }
+
+    /// Gå ett steg tilbake i et vindu
+    ///
+    /// Hentinger som pågår blir utdatert. Er siden ikke i minnet, skal
+    /// frontend hente den på nytt; den hentingen erstatter adressen i stedet
+    /// for å legge den på stabelen.
+    ///
+    /// # Returns
+    /// Steget, eller None hvis vinduet ikke kan gå tilbake
+    pub fn go_back(&mut self, label: &str) -> Option<HistoryStep> {
+        let url = self.windows.get_mut(label)?.go_back()?;
+        Some(self.step_to(label, url))
+    }
+
+    /// Gå ett steg frem i et vindu; se `go_back`
+    ///
+    /// # Returns
+    /// Steget, eller None hvis vinduet ikke kan gå frem
+    pub fn go_forward(&mut self, label: &str) -> Option<HistoryStep> {
+        let url = self.windows.get_mut(label)?.go_forward()?;
+        Some(self.step_to(label, url))
+    }
+
+    fn step_to(&mut self, label: &str, url: String) -> HistoryStep {
+        let state = self.entry(label);
+        state.fetch_generation += 1;
+        let page = state.cached_page(&url).cloned();
+        state.restore_generation = page.is_none().then_some(state.fetch_generation + 1);
+        HistoryStep { url, page }
+    }
+
+    /// Glem et vindu som er lukket
+    pub fn remove(&mut self, label: &str) {
+        self.windows.remove(label);
+    }
+
+    /// Glem sidene alle vinduer holder i minnet
+    ///
+    /// # Returns
+    /// Antall sider som ble glemt
+    pub fn clear_pages(&mut self) -> usize {
+        self.windows
+            .values_mut()
+            .map(|state| std::mem::take(&mut state.pages).len())
+            .sum()
+    }
+
+    /// Tøm tilbake- og fremover-stabelen i alle vinduer
+    ///
+    /// Sidene som vises beholdes.
+    ///
+    /// # Returns
+    /// Antall adresser som ble fjernet fra stablene
+    pub fn clear_navigation(&mut self) -> usize {
+        self.windows
+            .values_mut()
+            .map(|state| {
+                state.restore_generation = None;
+                std::mem::take(&mut state.back).len() + std::mem::take(&mut state.forward).len()
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAIN_WINDOW: &str = "main";
+    const OTHER: &str = "window-2";
+
+    #[test]
+    fn test_navigation_is_isolated_per_window() {
+        let mut states = WindowStates::default();
+        for url in ["https://a.example/", "https://b.example/"] {
+            let ticket = states.begin_fetch(MAIN_WINDOW);
+            assert!(states.finish_fetch(&ticket, Some(url)));
+        }
+        let ticket = states.begin_fetch(OTHER);
+        assert!(states.finish_fetch(&ticket, Some("gemini://c.example/")));
+
+        let main = states.get(MAIN_WINDOW).unwrap();
+        assert_eq!(main.current_url.as_deref(), Some("https://b.example/"));
+        assert_eq!(main.back, vec!["https://a.example/"]);
+
+        let other = states.get(OTHER).unwrap();
+        assert_eq!(other.current_url.as_deref(), Some("gemini://c.example/"));
+        assert!(other.back.is_empty());
+
+        states.remove(OTHER);
+        assert!(states.get(OTHER).is_none());
+        assert!(states.get(MAIN_WINDOW).is_some());
+    }
+
+    #[test]
+    fn test_new_fetch_supersedes_only_same_window() {
+        let mut states = WindowStates::default();
+        let slow = states.begin_fetch(MAIN_WINDOW);
+        let other = states.begin_fetch(OTHER);
+        let fast = states.begin_fetch(MAIN_WINDOW);
+
+        assert!(!states.is_current(&slow));
+        assert!(states.is_current(&fast));
+        assert!(states.is_current(&other));
+
+        assert!(states.finish_fetch(&fast, Some("https://ny.example/")));
+        assert!(!states.finish_fetch(&slow, Some("https://gammel.example/")));
+        assert!(states.finish_fetch(&other, None));
+
+        let main = states.get(MAIN_WINDOW).unwrap();
+        assert_eq!(main.current_url.as_deref(), Some("https://ny.example/"));
+        assert!(main.back.is_empty());
+        assert_eq!(states.get(OTHER).unwrap().current_url, None);
+    }
+
+    #[test]
+    fn test_visit_clears_forward_and_ignores_reload() {
+        let mut state = WindowState {
+            current_url: Some("https://b.example/".into()),
+            back: vec!["https://a.example/".into()],
+            forward: vec!["https://c.example/".into()],
+            ..Default::default()
+        };
+        state.visit("https://b.example/");
+        assert_eq!(state.forward, vec!["https://c.example/"]);
+
+        state.visit("https://d.example/");
+        assert_eq!(state.back, vec!["https://a.example/", "https://b.example/"]);
+        assert!(state.forward.is_empty());
+    }
+
+    fn page(url: &str) -> RenderedPage {
+        RenderedPage {
+            html: format!("<p>{}</p>", url),
+            title: None,
+            url: Some(url.to_string()),
+            is_remote: true,
+            was_converted: false,
+            tasks: None,
+            skipped_diagrams: 0,
+            is_archived: false,
+            used_readability: false,
+            truncated: false,
+            security: None,
+            timing: None,
+        }
+    }
+
+    fn visit_all(states: &mut WindowStates, urls: &[&str]) {
+        for url in urls {
+            let ticket = states.begin_fetch(MAIN_WINDOW);
+            assert!(states.finish_fetch(&ticket, Some(url)));
+        }
+    }
+
+    #[test]
+    fn test_navigating_after_going_back_truncates_forward() {
+        let mut states = WindowStates::default();
+        visit_all(
+            &mut states,
+            &[
+                "https://a.example/",
+                "https://b.example/",
+                "https://c.example/",
+            ],
+        );
+
+        assert_eq!(
+            states.go_back(MAIN_WINDOW).unwrap().url,
+            "https://b.example/"
+        );
+        assert_eq!(
+            states.go_back(MAIN_WINDOW).unwrap().url,
+            "https://a.example/"
+        );
+        assert!(states.go_back(MAIN_WINDOW).is_none());
+        assert_eq!(
+            states.go_forward(MAIN_WINDOW).unwrap().url,
+            "https://b.example/"
+        );
+        let main = states.get(MAIN_WINDOW).unwrap();
+        assert!(main.can_go_back() && main.can_go_forward());
+
+        // Steget ble hentet på nytt, så neste henting er en vanlig navigering
+        visit_all(&mut states, &["https://b.example/", "https://d.example/"]);
+        let main = states.get(MAIN_WINDOW).unwrap();
+        assert_eq!(main.current_url.as_deref(), Some("https://d.example/"));
+        assert_eq!(main.back, vec!["https://a.example/", "https://b.example/"]);
+        assert!(!main.can_go_forward());
+        assert!(states.go_forward(MAIN_WINDOW).is_none());
+    }
+
+    #[test]
+    fn test_redirects_are_recorded_by_final_url() {
+        let mut states = WindowStates::default();
+        // http://a.example/ omdirigerte til https://a.example/
+        visit_all(&mut states, &["https://a.example/", "https://b.example/"]);
+        assert_eq!(
+            states.get(MAIN_WINDOW).unwrap().back,
+            vec!["https://a.example/"]
+        );
+
+        // a.example omdirigerer et annet sted når den hentes på nytt
+        states.go_back(MAIN_WINDOW).unwrap();
+        let ticket = states.begin_fetch(MAIN_WINDOW);
+        assert!(states.finish_fetch(&ticket, Some("https://a.example/ny")));
+
+        let main = states.get(MAIN_WINDOW).unwrap();
+        assert_eq!(main.current_url.as_deref(), Some("https://a.example/ny"));
+        assert!(main.back.is_empty());
+        assert_eq!(main.forward, vec!["https://b.example/"]);
+    }
+
+    #[test]
+    fn test_steps_are_served_from_page_cache() {
+        let mut states = WindowStates::default();
+        visit_all(&mut states, &["https://a.example/", "https://b.example/"]);
+        states.remember_page(MAIN_WINDOW, page("https://a.example/"));
+
+        // En treg henting blir utdatert av steget
+        let slow = states.begin_fetch(MAIN_WINDOW);
+        let step = states.go_back(MAIN_WINDOW).unwrap();
+        assert_eq!(step.page, Some(page("https://a.example/")));
+        assert!(!states.finish_fetch(&slow, Some("https://treg.example/")));
+
+        // Siden er ikke i minnet og må hentes på nytt
+        assert_eq!(states.go_forward(MAIN_WINDOW).unwrap().page, None);
+        assert_eq!(
+            states.get(MAIN_WINDOW).unwrap().current_url.as_deref(),
+            Some("https://b.example/")
+        );
+    }
+
+    #[test]
+    fn test_page_cache_and_back_stack_are_bounded() {
+        let mut states = WindowStates::default();
+        let urls: Vec<String> = (0..=MAX_BACK_ENTRIES + 1)
+            .map(|i| format!("https://{}.example/", i))
+            .collect();
+        for url in &urls {
+            visit_all(&mut states, &[url]);
+            states.remember_page(MAIN_WINDOW, page(url));
+        }
+        states.remember_page(MAIN_WINDOW, page(&urls[0]));
+
+        let main = states.get(MAIN_WINDOW).unwrap();
+        assert_eq!(main.back.len(), MAX_BACK_ENTRIES);
+        assert_eq!(main.back[0], urls[1]);
+        assert_eq!(main.pages.len(), PAGE_CACHE_SIZE);
+        assert!(main.cached_page(&urls[0]).is_some());
+        assert!(main.cached_page(&urls[1]).is_none());
+    }
+
+    #[test]
+    fn test_opened_windows_get_unique_labels_and_initial_url() {
+        let mut states = WindowStates::default();
+        let first = states.open("https://a.example/");
+        let second = states.open("https://b.example/");
+        assert_eq!(first, OTHER);
+        assert_ne!(first, second);
+
+        assert_eq!(
+            states.take_initial_url(&second).as_deref(),
+            Some("https://b.example/")
+        );
+        assert_eq!(states.take_initial_url(&second), None);
+        assert_eq!(states.take_initial_url(MAIN_WINDOW), None);
+        assert_eq!(
+            states.take_initial_url(&first).as_deref(),
+            Some("https://a.example/")
+        );
+    }
+}
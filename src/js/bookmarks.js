@@ -4,20 +4,30 @@
  * Håndterer bokmerker: CRUD-operasjoner og UI-oppdateringer.
  */
 
-const { invoke: invokeBookmarks } = window.__TAURI__.core;
+const invokeBookmarks = invokeCommand;
 
 /**
  * Laster bokmerker fra backend og oppdaterer UI
  */
 async function loadBookmarks() {
     try {
-        const bookmarks = await invokeBookmarks('get_bookmarks');
-        renderBookmarksList(bookmarks);
+        const { items } = await invokeBookmarks('get_bookmarks');
+        renderBookmarksList(items);
     } catch (error) {
         console.error(t('status.loadSettingsError') + ':', error);
     }
 }
 
+/**
+ * Oppdaterer bokmerker når bokmerkefilen er endret utenfra
+ */
+async function initBookmarksChangedListener() {
+    await listen('bookmarks-changed', async () => {
+        await loadBookmarks();
+        await updateBookmarkButton();
+    });
+}
+
 /**
  * Rendrer bokmerkelisten
  * @param {Array} bookmarks - Array av bokmerke-objekter
@@ -50,23 +60,10 @@ async function toggleBookmark() {
     }
     
     try {
-        const isBookmarked = await invokeBookmarks('is_bookmarked', { url });
-        
-        if (isBookmarked) {
-            // Finn og fjern bokmerket
-            const bookmarks = await invokeBookmarks('get_bookmarks');
-            const bookmark = bookmarks.find(b => b.url === url);
-            if (bookmark) {
-                await invokeBookmarks('remove_bookmark', { id: bookmark.id });
-                updateBookmarkButtonUI(false);
-                showStatus(t('bookmarks.removed'));
-            }
-        } else {
-            const title = state.currentTitle || url;
-            await invokeBookmarks('add_bookmark', { title, url });
-            updateBookmarkButtonUI(true);
-            showStatus(t('bookmarks.added'));
-        }
+        const title = state.currentTitle || '';
+        const { bookmarked } = await invokeBookmarks('toggle_bookmark', { title, url });
+        updateBookmarkButtonUI(bookmarked);
+        showStatus(t(bookmarked ? 'bookmarks.added' : 'bookmarks.removed'));
         
         await loadBookmarks();
     } catch (error) {
@@ -134,9 +131,7 @@ async function navigateToBookmark(url) {
     closeBookmarksPanel();
     
     if (url.startsWith('file://')) {
-        await loadPath(url.replace('file://', ''));
-    } else if (url.startsWith('http://') || url.startsWith('https://')) {
-        await loadUrl(url);
+        await loadPath(fileUrlToPath(url));
     } else {
         await loadPath(url);
     }
@@ -23,9 +23,6 @@ function getAppVersion() {
     return APP_VERSION;
 }
 
-// Historikk
-const MAX_HISTORY_SIZE = 50;
-
 // Zoom-grenser
 const ZOOM_MIN = 50;
 const ZOOM_MAX = 200;
@@ -43,28 +40,55 @@ const CONTENT_WIDTH_DEFAULT = 800;
 
 // Spesielle verdier
 const HOME_PATH = '__home__';
-const CONVERSION_PROMPT_PREFIX = 'CONVERSION_PROMPT:';
+const BOOKMARKS_PAGE_URL = 'bare://bookmarks';
+// Velkomstsiden på navigeringsstabelen i backend
+const HOME_PAGE_URL = 'bare://home';
+// Handlingene på feilsider (se error_page.rs)
+const ERROR_PAGE_BACK_URL = 'bare://back';
+const ERROR_PAGE_OPEN_EXTERNALLY_URL = 'bare://open-externally';
 const GEMINI_SCHEME = 'gemini://';
 const GOPHER_SCHEME = 'gopher://';
-const GEMINI_INPUT_PROMPT_PREFIX = 'GEMINI_INPUT_PROMPT:';
-const GEMINI_SENSITIVE_INPUT_PROMPT_PREFIX = 'GEMINI_SENSITIVE_INPUT_PROMPT:';
-const GOPHER_SEARCH_PROMPT_PREFIX = 'GOPHER_SEARCH_PROMPT:';
 
 // Status bar timeout (ms)
 const STATUS_TIMEOUT = 3000;
 
+// Hvor lenge musen må holdes over en lenke før den forhåndshentes (ms)
+const PREFETCH_HOVER_DELAY = 200;
+
 // Tema-alternativer
-const THEMES = ['light', 'dark', 'system'];
+const THEMES = ['light', 'dark', 'sepia', 'system'];
 
 // Standard innstillinger
 const DEFAULT_SETTINGS = {
     theme: 'light',
     font_size: FONT_SIZE_DEFAULT,
     zoom: ZOOM_DEFAULT,
+    zoom_step: 10,
     font_family: 'system',
     content_width: CONTENT_WIDTH_DEFAULT,
+    typography: { line_height: 160, paragraph_spacing: 100, justify_text: false },
     conversion_mode: 'convert-all',
+    conversion_rules: [],
+    decorations: 'emoji',
+    plain_text: 'preformatted',
+    show_hidden_files: false,
+    max_file_mb: 10,
+    max_render_mb: 8,
+    log_level: 'info',
+    follow_symlinks: true,
+    default_scheme: 'https',
+    downloads_dir: null,
+    history_enabled: false,
+    history_max_entries: 10000,
+    history_max_age_days: 90,
+    private_mode: false,
+    use_downloads_dir_without_asking: false,
+    custom_colors: null,
     readability_enabled: true,
     onboarding_completed: false,
     language: 'system',
+    homepage: 'welcome',
+    search_engine: 'https://lite.duckduckgo.com/lite/?q=%s',
+    network: { timeout_seconds: 30, max_response_mb: 5, max_redirects: 5 },
+    privacy: { load_remote_images: true, strip_query_params: false, send_referrer: false, send_gpc: false, prefetch: false },
 };
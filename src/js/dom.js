@@ -8,6 +8,7 @@
 const elements = {
     // Toolbar
     urlBar: document.getElementById('url-bar'),
+    breadcrumbs: document.getElementById('breadcrumbs'),
     btnBack: document.getElementById('btn-back'),
     btnForward: document.getElementById('btn-forward'),
     btnHome: document.getElementById('btn-home'),
@@ -24,6 +25,22 @@ const elements = {
     dropdownMenu: document.getElementById('dropdown-menu'),
     menuZoomLevel: document.getElementById('menu-zoom-level'),
     btnAbout: document.getElementById('btn-about'),
+    btnExportHtml: document.getElementById('btn-export-html'),
+    btnExportPdf: document.getElementById('btn-export-pdf'),
+    btnSaveSource: document.getElementById('btn-save-source'),
+    btnCopyMarkdown: document.getElementById('btn-copy-markdown'),
+    btnCopyText: document.getElementById('btn-copy-text'),
+    btnCopyLink: document.getElementById('btn-copy-link'),
+    menuRecentFiles: document.getElementById('menu-recent-files'),
+    recentFilesList: document.getElementById('recent-files-list'),
+    btnClearRecentFiles: document.getElementById('btn-clear-recent-files'),
+    
+    // Sikkerhet for siden
+    btnSecurity: document.getElementById('btn-security'),
+    securityOverlay: document.getElementById('security-overlay'),
+    securityWarning: document.getElementById('security-warning'),
+    securityDetails: document.getElementById('security-details'),
+    btnCloseSecurity: document.getElementById('btn-close-security'),
     
     // Om-dialog
     aboutOverlay: document.getElementById('about-overlay'),
@@ -65,14 +82,50 @@ const elements = {
     
     // Innstillinger-kontroller
     settingTheme: document.getElementById('setting-theme'),
+    settingColors: document.querySelectorAll('.setting-color'),
+    btnResetColors: document.getElementById('btn-reset-colors'),
     settingFontFamily: document.getElementById('setting-font-family'),
+    settingCustomFont: document.getElementById('setting-custom-font'),
+    systemFonts: document.getElementById('system-fonts'),
     settingFontSize: document.getElementById('setting-font-size'),
     settingFontSizeValue: document.getElementById('setting-font-size-value'),
     settingContentWidth: document.getElementById('setting-content-width'),
+    settingZoomStep: document.getElementById('setting-zoom-step'),
     settingContentWidthValue: document.getElementById('setting-content-width-value'),
+    settingLineHeight: document.getElementById('setting-line-height'),
+    settingLineHeightValue: document.getElementById('setting-line-height-value'),
+    settingParagraphSpacing: document.getElementById('setting-paragraph-spacing'),
+    settingParagraphSpacingValue: document.getElementById('setting-paragraph-spacing-value'),
+    settingJustifyText: document.getElementById('setting-justify-text'),
+    settingHomepage: document.getElementById('setting-homepage'),
+    settingSearchEngine: document.getElementById('setting-search-engine'),
+    settingDefaultScheme: document.getElementById('setting-default-scheme'),
+    settingDownloadsDir: document.getElementById('setting-downloads-dir'),
+    settingDownloadsWithoutAsking: document.getElementById('setting-downloads-without-asking'),
+    settingCustomCss: document.getElementById('setting-custom-css'),
     settingConversionMode: document.getElementById('setting-conversion-mode'),
+    settingDecorations: document.getElementById('setting-decorations'),
+    settingPlainText: document.getElementById('setting-plain-text'),
+    settingShowHiddenFiles: document.getElementById('setting-show-hidden-files'),
+    settingFollowSymlinks: document.getElementById('setting-follow-symlinks'),
+    settingMaxFile: document.getElementById('setting-max-file'),
+    settingMaxRender: document.getElementById('setting-max-render'),
+    settingLogLevel: document.getElementById('setting-log-level'),
     settingReadability: document.getElementById('setting-readability'),
     settingLanguage: document.getElementById('setting-language'),
+    settingTimeout: document.getElementById('setting-timeout'),
+    settingMaxResponse: document.getElementById('setting-max-response'),
+    settingMaxRedirects: document.getElementById('setting-max-redirects'),
+    settingLoadRemoteImages: document.getElementById('setting-load-remote-images'),
+    settingStripQueryParams: document.getElementById('setting-strip-query-params'),
+    settingSendReferrer: document.getElementById('setting-send-referrer'),
+    settingSendGpc: document.getElementById('setting-send-gpc'),
+    settingPrefetch: document.getElementById('setting-prefetch'),
+    settingHistoryEnabled: document.getElementById('setting-history-enabled'),
+    settingPrivateMode: document.getElementById('setting-private-mode'),
+    settingResetScope: document.getElementById('setting-reset-scope'),
+    btnResetSettings: document.getElementById('btn-reset-settings'),
+    btnClearBrowsingData: document.getElementById('btn-clear-browsing-data'),
     
     // Gemini input-dialog
     geminiInputOverlay: document.getElementById('gemini-input-overlay'),
@@ -81,6 +134,14 @@ const elements = {
     btnGeminiInputSend: document.getElementById('btn-gemini-input-send'),
     btnGeminiInputCancel: document.getElementById('btn-gemini-input-cancel'),
     btnCloseGeminiInput: document.getElementById('btn-close-gemini-input'),
+    
+    // Valg av protokoll
+    schemeChoiceOverlay: document.getElementById('scheme-choice-overlay'),
+    schemeChoicePrompt: document.getElementById('scheme-choice-prompt'),
+    schemeChoiceRemember: document.getElementById('scheme-choice-remember'),
+    btnSchemeHttps: document.getElementById('btn-scheme-https'),
+    btnSchemeGemini: document.getElementById('btn-scheme-gemini'),
+    btnCloseSchemeChoice: document.getElementById('btn-close-scheme-choice'),
 };
 
 // ===== Utility Functions =====
@@ -4,6 +4,8 @@
  * Sentralisert registrering av alle event listeners.
  */
 
+const invokeEvents = invokeCommand;
+
 /**
  * Initialiserer alle event listeners
  */
@@ -44,6 +46,44 @@ function initToolbarEvents() {
         toggleDropdownMenu();
     });
     
+    // Nylige filer
+    elements.btnClearRecentFiles.addEventListener('click', clearRecentFiles);
+    
+    // Eksport
+    elements.btnExportHtml.addEventListener('click', () => {
+        closeDropdownMenu();
+        exportCurrentPage('html');
+    });
+    elements.btnExportPdf.addEventListener('click', () => {
+        closeDropdownMenu();
+        exportCurrentPage('pdf');
+    });
+    elements.btnSaveSource.addEventListener('click', () => {
+        closeDropdownMenu();
+        saveCurrentSource();
+    });
+    elements.btnCopyMarkdown.addEventListener('click', () => {
+        closeDropdownMenu();
+        copyCurrentPage(false);
+    });
+    elements.btnCopyText.addEventListener('click', () => {
+        closeDropdownMenu();
+        copyCurrentPage(true);
+    });
+    elements.btnCopyLink.addEventListener('click', () => {
+        closeDropdownMenu();
+        copyCurrentLink();
+    });
+    
+    // Sikkerhet for siden
+    elements.btnSecurity.addEventListener('click', showSecurityDialog);
+    elements.btnCloseSecurity.addEventListener('click', closeSecurityDialog);
+    elements.securityOverlay.addEventListener('click', (e) => {
+        if (e.target === elements.securityOverlay) {
+            closeSecurityDialog();
+        }
+    });
+    
     // Om-dialog
     elements.btnAbout.addEventListener('click', showAboutDialog);
     elements.btnCloseAbout.addEventListener('click', closeAboutDialog);
@@ -95,14 +135,38 @@ function initSettingsEvents() {
     elements.btnCloseSettings.addEventListener('click', closeSettingsPanel);
     
     // Tema
+    // Egne farger; hvert felt sendes sammen med de andre som er satt
+    elements.settingColors.forEach((input) => {
+        input.addEventListener('change', (e) => {
+            const colors = { ...(getSettings().custom_colors || {}) };
+            colors[e.target.dataset.color] = e.target.value;
+            updateSetting('custom_colors', colors);
+        });
+    });
+    elements.btnResetColors.addEventListener('click', resetColors);
+    elements.btnClearBrowsingData.addEventListener('click', clearBrowsingData);
+    elements.btnResetSettings.addEventListener('click', () => resetSettings(elements.settingResetScope.value));
+    
     elements.settingTheme.addEventListener('change', (e) => {
         updateSetting('theme', e.target.value);
     });
     
     // Skrifttype
     elements.settingFontFamily.addEventListener('change', (e) => {
+        // Egen skrift lagres først når brukeren har skrevet et navn
+        if (e.target.value === 'custom' && !getSettings().custom_font_name) {
+            elements.settingCustomFont.classList.remove('hidden');
+            elements.settingCustomFont.focus();
+            return;
+        }
         updateSetting('font_family', e.target.value);
     });
+    elements.settingCustomFont.addEventListener('change', (e) => {
+        const name = e.target.value.trim();
+        if (name) {
+            updateSetting('custom_font_name', name);
+        }
+    });
     
     // Skriftstørrelse
     elements.settingFontSize.addEventListener('input', (e) => {
@@ -120,6 +184,76 @@ function initSettingsEvents() {
         updateSetting('content_width', parseInt(e.target.value));
     });
     
+    // Typografi
+    elements.settingLineHeight.addEventListener('input', (e) => {
+        elements.settingLineHeightValue.textContent = `${e.target.value}%`;
+    });
+    elements.settingLineHeight.addEventListener('change', (e) => {
+        updateSetting('typography', { line_height: parseInt(e.target.value) });
+    });
+    elements.settingParagraphSpacing.addEventListener('input', (e) => {
+        elements.settingParagraphSpacingValue.textContent = `${e.target.value}%`;
+    });
+    elements.settingParagraphSpacing.addEventListener('change', (e) => {
+        updateSetting('typography', { paragraph_spacing: parseInt(e.target.value) });
+    });
+    elements.settingJustifyText.addEventListener('change', (e) => {
+        updateSetting('typography', { justify_text: e.target.checked });
+    });
+    
+    // Ikoner foran lenker
+    elements.settingDecorations.addEventListener('change', (e) => {
+        updateSetting('decorations', e.target.value);
+    });
+    
+    // Visning av ren tekst
+    elements.settingPlainText.addEventListener('change', (e) => {
+        updateSetting('plain_text', e.target.value);
+    });
+    
+    // Skjulte filer i mappefortegnelser
+    elements.settingShowHiddenFiles.addEventListener('change', (e) => {
+        updateSetting('show_hidden_files', e.target.checked);
+    });
+    
+    // Lokale filer som åpnes
+    elements.settingFollowSymlinks.addEventListener('change', (e) => {
+        updateSetting('follow_symlinks', e.target.checked);
+    });
+    elements.settingMaxFile.addEventListener('change', (e) => {
+        updateSetting('max_file_mb', parseInt(e.target.value));
+    });
+    elements.settingMaxRender.addEventListener('change', (e) => {
+        updateSetting('max_render_mb', parseInt(e.target.value));
+    });
+    elements.settingLogLevel.addEventListener('change', (e) => {
+        updateSetting('log_level', e.target.value);
+    });
+    
+    // Startside
+    elements.settingHomepage.addEventListener('change', (e) => {
+        updateSetting('homepage', e.target.value.trim() || 'welcome');
+    });
+    
+    // Søkemotor (tom verdi gir standardmotoren)
+    elements.settingSearchEngine.addEventListener('change', (e) => {
+        updateSetting('search_engine', e.target.value.trim());
+    });
+    elements.settingDefaultScheme.addEventListener('change', (e) => {
+        updateSetting('default_scheme', e.target.value);
+    });
+    elements.settingDownloadsDir.addEventListener('change', (e) => {
+        updateSetting('downloads_dir', e.target.value.trim());
+    });
+    elements.settingDownloadsWithoutAsking.addEventListener('change', (e) => {
+        updateSetting('use_downloads_dir_without_asking', e.target.checked);
+    });
+    
+    // Egen CSS
+    elements.settingCustomCss.addEventListener('change', (e) => {
+        updateSetting('custom_css', e.target.value);
+    });
+    
     // Konverteringsinnstillinger
     elements.settingConversionMode.addEventListener('change', (e) => {
         updateSetting('conversion_mode', e.target.value);
@@ -128,6 +262,43 @@ function initSettingsEvents() {
         updateSetting('readability_enabled', e.target.checked);
     });
     
+    // Nettverk
+    elements.settingZoomStep.addEventListener('change', (e) => {
+        updateSetting('zoom_step', parseInt(e.target.value));
+    });
+    elements.settingTimeout.addEventListener('change', (e) => {
+        updateSetting('network', { timeout_seconds: parseInt(e.target.value) });
+    });
+    elements.settingMaxResponse.addEventListener('change', (e) => {
+        updateSetting('network', { max_response_mb: parseInt(e.target.value) });
+    });
+    elements.settingMaxRedirects.addEventListener('change', (e) => {
+        updateSetting('network', { max_redirects: parseInt(e.target.value) });
+    });
+    
+    // Personvern
+    elements.settingLoadRemoteImages.addEventListener('change', (e) => {
+        updateSetting('privacy', { load_remote_images: e.target.checked });
+    });
+    elements.settingStripQueryParams.addEventListener('change', (e) => {
+        updateSetting('privacy', { strip_query_params: e.target.checked });
+    });
+    elements.settingSendReferrer.addEventListener('change', (e) => {
+        updateSetting('privacy', { send_referrer: e.target.checked });
+    });
+    elements.settingSendGpc.addEventListener('change', (e) => {
+        updateSetting('privacy', { send_gpc: e.target.checked });
+    });
+    elements.settingPrefetch.addEventListener('change', (e) => {
+        updateSetting('privacy', { prefetch: e.target.checked });
+    });
+    elements.settingHistoryEnabled.addEventListener('change', (e) => {
+        updateSetting('history_enabled', e.target.checked);
+    });
+    elements.settingPrivateMode.addEventListener('change', (e) => {
+        setPrivateMode(e.target.checked);
+    });
+    
     // Språk
     if (elements.settingLanguage) {
         elements.settingLanguage.addEventListener('change', (e) => {
@@ -190,6 +361,81 @@ function initGeminiInputEvents() {
 
 // ===== Keyboard Shortcuts =====
 
+/**
+ * Handlinger som kan ha egendefinerte snarveier (navn fra backend)
+ */
+const KEY_BINDING_ACTIONS = {
+    'reload': () => reloadPage(),
+    'hard-reload': () => reloadPage(true),
+    'open-file': () => openFileDialog(),
+    'find': () => openSearch(),
+    'toggle-bookmark': () => toggleBookmark(),
+    'show-bookmarks': () => toggleBookmarksPanel(),
+    'zoom-in': () => zoomIn(),
+    'zoom-out': () => zoomOut(),
+    'zoom-reset': () => zoomReset(),
+    'back': () => goBack(),
+    'forward': () => goForward(),
+    'focus-address': () => {
+        elements.urlBar.focus();
+        elements.urlBar.select();
+    },
+};
+
+/**
+ * Henter snarveiene fra backend
+ */
+async function loadKeyBindings() {
+    try {
+        const bindings = await invokeEvents('get_key_bindings');
+        setKeyBindings(bindings);
+    } catch (error) {
+        console.error(t('status.loadSettingsError') + ':', error);
+    }
+}
+
+/**
+ * Lager en snarvei på samme form som backend ("Ctrl+Shift+D") fra et tastetrykk
+ * @param {KeyboardEvent} e - Tastetrykket
+ * @param {boolean} withShift - Ta med Shift
+ * @returns {string} Snarveien
+ */
+function eventToAccelerator(e, withShift = true) {
+    const namedKeys = { '+': 'Plus', '-': 'Minus', ' ': 'Space' };
+    const key = namedKeys[e.key] || (e.key.length === 1 ? e.key.toUpperCase() : e.key);
+    const parts = [];
+    if (e.ctrlKey) parts.push('Ctrl');
+    if (e.altKey) parts.push('Alt');
+    if (e.shiftKey && withShift) parts.push('Shift');
+    if (e.metaKey) parts.push('Meta');
+    parts.push(key);
+    return parts.join('+');
+}
+
+/**
+ * Finner handlingen et tastetrykk er bundet til
+ *
+ * Tegn som krever Shift på tastaturet (f.eks. + på engelsk oppsett) matcher
+ * også snarveien uten Shift, og = regnes som plusstasten.
+ * @param {KeyboardEvent} e - Tastetrykket
+ * @returns {string|null} Handlingen, eller null
+ */
+function keyBindingAction(e) {
+    const bindings = getKeyBindings();
+    const candidates = [eventToAccelerator(e)];
+    if (e.shiftKey && e.key.length === 1 && !/[a-z]/i.test(e.key)) {
+        candidates.push(eventToAccelerator(e, false));
+    }
+    if (e.key === '=') {
+        candidates.push(...candidates.map(c => c.replace(/=$/, 'Plus')));
+    }
+    for (const accelerator of candidates) {
+        const binding = bindings.find(b => b.accelerator === accelerator);
+        if (binding) return binding.action;
+    }
+    return null;
+}
+
 function initKeyboardShortcuts() {
     document.addEventListener('keydown', (e) => {
         // F5: Last siden på nytt
@@ -198,71 +444,12 @@ function initKeyboardShortcuts() {
             reloadPage();
         }
         
-        // Ctrl+R: Last siden på nytt
-        if (e.ctrlKey && e.key === 'r') {
-            e.preventDefault();
-            reloadPage();
-        }
-        
-        // Ctrl+O: Åpne fil
-        if (e.ctrlKey && e.key === 'o') {
-            e.preventDefault();
-            openFileDialog();
-        }
-        
-        // Ctrl+F: Søk
-        if (e.ctrlKey && e.key === 'f') {
-            e.preventDefault();
-            openSearch();
-        }
-        
-        // Ctrl+D: Bokmerke
-        if (e.ctrlKey && e.key === 'd') {
-            e.preventDefault();
-            toggleBookmark();
-        }
-        
-        // Ctrl+B: Vis bokmerker
-        if (e.ctrlKey && e.key === 'b') {
-            e.preventDefault();
-            toggleBookmarksPanel();
-        }
-        
-        // Ctrl++: Zoom inn
-        if (e.ctrlKey && (e.key === '+' || e.key === '=')) {
-            e.preventDefault();
-            zoomIn();
-        }
-        
-        // Ctrl+-: Zoom ut
-        if (e.ctrlKey && e.key === '-') {
-            e.preventDefault();
-            zoomOut();
-        }
-        
-        // Ctrl+0: Tilbakestill zoom
-        if (e.ctrlKey && e.key === '0') {
-            e.preventDefault();
-            zoomReset();
-        }
-        
-        // Alt+Left: Tilbake
-        if (e.altKey && e.key === 'ArrowLeft') {
-            e.preventDefault();
-            goBack();
-        }
-        
-        // Alt+Right: Fremover
-        if (e.altKey && e.key === 'ArrowRight') {
-            e.preventDefault();
-            goForward();
-        }
-        
-        // Ctrl+L: Fokuser URL-bar
-        if (e.ctrlKey && e.key === 'l') {
+        // Snarveier som kan endres i innstillingene
+        const action = keyBindingAction(e);
+        if (action && KEY_BINDING_ACTIONS[action]) {
             e.preventDefault();
-            elements.urlBar.focus();
-            elements.urlBar.select();
+            KEY_BINDING_ACTIONS[action]();
+            return;
         }
         
         // Vim-lignende navigasjon (kun når ikke i input)
@@ -301,6 +488,7 @@ function initKeyboardShortcuts() {
             closeSearch();
             closeDropdownMenu();
             closeAboutDialog();
+            closeSecurityDialog();
             closeGeminiInputDialog();
         }
     });
@@ -315,13 +503,101 @@ function initContentEvents() {
         if (link) {
             const href = link.getAttribute('href');
             
+            // Lokale lenker som peker ut av mappen som er åpnet
+            if (link.classList.contains('outside-root')) {
+                showStatus(t('status.linkOutsideRoot'), true);
+                return;
+            }
+            
             // Ignorer fragment-lenker
-            if (href.startsWith('#')) {
+            if (!href || href.startsWith('#')) {
                 return;
             }
             
             e.preventDefault();
-            await resolveAndNavigate(href);
+            // Alt-klikk lagrer det lenken peker på
+            if (e.altKey && state.currentUrl) {
+                await saveResource(new URL(href, state.currentUrl).href);
+                return;
+            }
+            // Ctrl/Cmd-klikk åpner lenken i et nytt vindu
+            if (e.ctrlKey || e.metaKey) {
+                await openLinkInNewWindow(href);
+                return;
+            }
+            await resolveAndNavigate(href, link.textContent);
+        }
+    });
+    
+    // Innhold som limes inn utenfor tekstfelt vises som en side
+    document.addEventListener('paste', async (e) => {
+        if (e.target.closest('input, textarea, [contenteditable]')) return;
+        const html = e.clipboardData?.getData('text/html');
+        const text = e.clipboardData?.getData('text/plain');
+        if (!html && !text) return;
+        e.preventDefault();
+        await renderPastedContent(html || text, html ? 'html' : 'markdown');
+    });
+    
+    // Midtklikk åpner lenken i et nytt vindu
+    elements.content.addEventListener('auxclick', async (e) => {
+        const link = e.target.closest('a');
+        if (!link || e.button !== 1) return;
+        const href = link.getAttribute('href');
+        if (!href || href.startsWith('#')) return;
+        e.preventDefault();
+        await openLinkInNewWindow(href);
+    });
+    
+    // Forhåndshent lenker musen holdes over
+    let prefetchTimer = null;
+    elements.content.addEventListener('mouseover', (e) => {
+        const link = e.target.closest('a[href]');
+        if (!link || !getSettings()?.privacy?.prefetch) return;
+        clearTimeout(prefetchTimer);
+        prefetchTimer = setTimeout(() => prefetchLink(link.getAttribute('href')), PREFETCH_HOVER_DELAY);
+    });
+    elements.content.addEventListener('mouseout', (e) => {
+        if (e.target.closest('a')) {
+            clearTimeout(prefetchTimer);
         }
     });
 }
+
+/**
+ * Åpner en lenke i et nytt vindu
+ * @param {string} href - Lenken slik den står på siden
+ */
+async function openLinkInNewWindow(href) {
+    let url = href;
+    try {
+        if (state.currentUrl) {
+            url = new URL(href, state.currentUrl).href;
+        } else if (state.currentPath && !/^[a-z][a-z0-9+.-]*:/i.test(href)) {
+            const basePath = state.currentPath.substring(0, state.currentPath.lastIndexOf('/') + 1);
+            url = basePath + href;
+        }
+    } catch {
+        // Vinduet får lenken som den står
+    }
+    try {
+        await invokeEvents('open_url_in_new_window', { url });
+    } catch (error) {
+        showStatus(`${t('status.openWindowError')}: ${error}`, true);
+    }
+}
+
+/**
+ * Ber backend forhåndshente en lenke; feil ignoreres
+ * @param {string|null} href - Lenken slik den står på siden
+ */
+function prefetchLink(href) {
+    if (!href || href.startsWith('#') || !state.currentUrl) return;
+    let url;
+    try {
+        url = new URL(href, state.currentUrl).href;
+    } catch {
+        return;
+    }
+    invokeEvents('prefetch', { url }).catch(() => {});
+}
@@ -48,6 +48,14 @@ const TRANSLATIONS = {
         'menu.toggleTheme': 'Bytt tema',
         'menu.settings': 'Innstillinger...',
         'menu.about': 'Om Bare...',
+        'menu.exportHtml': 'Eksporter som HTML...',
+        'menu.exportPdf': 'Eksporter som PDF...',
+        'menu.saveSource': 'Lagre kilden...',
+        'menu.copyMarkdown': 'Kopier som markdown',
+        'menu.copyText': 'Kopier som tekst',
+        'menu.copyLink': 'Kopier lenken',
+        'menu.recentFiles': 'Nylige filer',
+        'menu.clearRecentFiles': 'Tøm listen',
         
         // Søk
         'search.placeholder': 'Søk i siden...',
@@ -69,13 +77,53 @@ const TRANSLATIONS = {
         'settings.themeLight': 'Lys',
         'settings.themeDark': 'Mørk',
         'settings.themeSystem': 'System',
+        'settings.themeSepia': 'Sepia',
+        'settings.colors': 'Egne farger',
+        'settings.colorBackground': 'Bakgrunn',
+        'settings.colorText': 'Tekst',
+        'settings.colorLink': 'Lenker',
+        'settings.colorAccent': 'Aksent',
+        'settings.resetColors': 'Tilbakestill farger',
         'settings.fontFamily': 'Skrifttype',
         'settings.fontSystem': 'System',
         'settings.fontSerif': 'Serif',
         'settings.fontSansSerif': 'Sans-serif',
         'settings.fontMono': 'Monospace',
+        'settings.fontCustom': 'Egen skrift',
+        'settings.customFontName': 'Navn på installert skrift',
         'settings.fontSize': 'Skriftstørrelse',
         'settings.contentWidth': 'Innholdsbredde',
+        'settings.zoomStep': 'Zoom-steg (%)',
+        'settings.lineHeight': 'Linjehøyde',
+        'settings.paragraphSpacing': 'Avsnittsavstand',
+        'settings.justifyText': 'Blokkjuster tekst',
+        'settings.decorations': 'Ikoner foran lenker',
+        'settings.decorationsEmoji': 'Emoji',
+        'settings.decorationsText': 'Tekst ([DIR])',
+        'settings.decorationsNone': 'Ingen',
+        'settings.plainText': 'Ren tekst',
+        'settings.plainTextPreformatted': 'Fast bredde',
+        'settings.plainTextParagraphs': 'Avsnitt',
+        'settings.showHiddenFiles': 'Vis skjulte filer i mapper',
+        'settings.followSymlinks': 'Følg symbolske lenker',
+        'settings.maxFileMb': 'Største lokale fil (MB)',
+        'settings.maxRenderMb': 'Største dokument som vises (MB)',
+        'settings.logLevel': 'Logging',
+        'settings.logLevelError': 'Bare feil',
+        'settings.logLevelWarn': 'Feil og advarsler',
+        'settings.logLevelInfo': 'Vanlig',
+        'settings.logLevelDebug': 'Detaljert',
+        'settings.logLevelTrace': 'Alt',
+        'settings.customCss': 'Egen CSS',
+        'settings.homepage': 'Startside',
+        'settings.searchEngine': 'Søkemotor',
+        'settings.defaultScheme': 'Adresser uten protokoll',
+        'settings.defaultSchemeAsk': 'Spør hver gang',
+        'settings.downloadsDir': 'Nedlastingsmappe',
+        'settings.downloadsWithoutAsking': 'Lagre uten å spørre',
+        'schemeChoice.title': 'Velg protokoll',
+        'schemeChoice.remember': 'Husk for dette nettstedet',
+        'schemeChoice.prompt': 'Hvordan vil du åpne {address}?',
         'settings.language': 'Språk',
         'settings.languageSystem': 'System',
         
@@ -86,6 +134,47 @@ const TRANSLATIONS = {
         'settings.markdownOnly': 'Kun markdown',
         'settings.askEverytime': 'Spør hver gang',
         'settings.readability': 'Readability-modus (ekstraher hovedinnhold)',
+        'settings.network': 'Nettverk',
+        'settings.timeoutSeconds': 'Tidsavbrudd (sekunder)',
+        'settings.maxResponseMb': 'Maks størrelse (MB)',
+        'settings.maxRedirects': 'Maks antall omdirigeringer',
+        'settings.privacy': 'Personvern',
+        'settings.loadRemoteImages': 'Last bilder fra nettet',
+        'settings.stripQueryParams': 'Fjern sporingsparametere fra lenker',
+        'settings.sendReferrer': 'Send henvisende side (Referer)',
+        'settings.sendGpc': 'Be om å ikke bli sporet (GPC/DNT)',
+        'settings.prefetch': 'Hent lenker når musen holdes over dem',
+        'settings.historyEnabled': 'Husk besøkte sider',
+        'settings.privateMode': 'Privat surfing (skriver ingenting til disk)',
+        'status.privateModeOn': 'Privat surfing er på',
+        'status.privateModeOff': 'Privat surfing er av',
+        'status.openWindowError': 'Kunne ikke åpne nytt vindu',
+        'status.exported': 'Lagret {path}',
+        'status.exportError': 'Kunne ikke eksportere siden',
+        'status.saveSourceError': 'Kunne ikke lagre kilden',
+        'status.copied': 'Kopiert til utklippstavlen',
+        'status.copyError': 'Kunne ikke kopiere',
+        'status.linkCopied': 'Kopierte {url}',
+        'prompt.download': 'Bare kan ikke vise {name}. Vil du laste ned filen?',
+        'status.notDisplayable': '{name} kan ikke vises i Bare',
+        'status.downloadStarted': 'Starter nedlasting av {name}',
+        'status.downloading': 'Laster ned {name}: {progress}',
+        'status.downloaded': 'Lastet ned {path}',
+        'status.downloadError': 'Nedlastingen mislyktes',
+        'status.linkOutsideRoot': 'Lenken peker ut av mappen som er åpnet',
+        'status.exportNoPage': 'Åpne en side før du eksporterer',
+        'status.didYouMean': 'Mente du {url}?',
+        'settings.reset': 'Tilbakestill innstillinger',
+        'settings.resetAppearance': 'Utseende',
+        'settings.resetNetwork': 'Nettverk',
+        'settings.resetPrivacy': 'Personvern',
+        'settings.resetSiteOverrides': 'Innstillinger per nettsted',
+        'settings.resetAll': 'Alle innstillinger',
+        'settings.resetButton': 'Tilbakestill',
+        'settings.resetConfirm': 'Tilbakestille valgte innstillinger til standard?',
+        'settings.clearBrowsingData': 'Slett surfedata',
+        'settings.clearBrowsingDataConfirm': 'Slette historikk, tilbake og frem i vinduene, mellomlagrede sider, kjente Gemini-verter, nylige filer og innstillinger per nettsted? Bokmerker og andre innstillinger beholdes.',
+        'status.browsingDataCleared': 'Surfedata er slettet',
         
         // Om-dialog
         'about.title': 'Om Bare',
@@ -123,9 +212,18 @@ const TRANSLATIONS = {
         // Footer
         'footer.ready': 'Klar',
         'footer.connecting': 'Kobler til...',
+        'progress.resolving': 'Slår opp {host}...',
+        'progress.connecting': 'Kobler til {host}...',
+        'progress.tls_handshake': 'TLS-handshake med {host}...',
+        'progress.transferring': 'Overfører data... ({bytes} bytes)',
+        'progress.converting': 'Konverterer...',
+        'progress.rendering': 'Rendrer...',
+        'progress.done': 'Dokument: Ferdig',
+        'progress.error': 'Feil under lasting',
         'footer.loadingHome': 'Laster startside...',
         'footer.reloading': 'Laster på nytt...',
         'footer.converted': 'konvertert',
+        'footer.readerMode': 'lesevisning',
         
         // Loading/Error
         'loading.text': '[ LASTER... ]',
@@ -134,8 +232,32 @@ const TRANSLATIONS = {
         
         // Status-meldinger
         'status.settingsError': 'Kunne ikke oppdatere innstilling',
+        'status.settingsAdjusted': 'Innstillingen ble endret',
+        'settings.warning.clamped': 'justert',
+        'settings.warning.ignored': 'ignorert',
+        'settings.warning.invalid': 'ugyldig',
         'status.loadSettingsError': 'Kunne ikke laste innstillinger',
         'status.conversionCancelled': 'Konvertering avbrutt av brukeren',
+        'status.redirectCancelled': 'Videresendingen ble avbrutt',
+        'status.identityCancelled': 'Ingen identitet ble laget, så siden kan ikke vises',
+        'status.navigationCancelled': 'Navigering avbrutt',
+        'toolbar.security': 'Sikkerhet for siden',
+        'security.title': 'Tilkobling',
+        'security.protocol': 'Protokoll',
+        'security.encryption': 'Kryptering',
+        'security.encrypted': 'Kryptert',
+        'security.notEncrypted': 'Ikke kryptert',
+        'security.tlsVersion': 'TLS-versjon',
+        'security.cipher': 'Chiffer',
+        'security.certificate': 'Sertifikat',
+        'security.cert.ca_validated': 'Utstedt av en kjent sertifiseringsinstans',
+        'security.cert.pinned': 'Samme som ved første besøk',
+        'security.cert.none': 'Ingen',
+        'security.fingerprint': 'Fingeravtrykk (SHA-256)',
+        'security.downgraded': 'En omdirigering førte fra en kryptert til en ukryptert adresse',
+        'security.local': 'Lokal side, ingen tilkobling',
+        'security.unknown': 'Ukjent',
+        'conversion.alwaysForSite': 'Konverter alltid sider fra {host}?',
         'status.zoomInError': 'Kunne ikke zoome inn',
         'status.zoomOutError': 'Kunne ikke zoome ut',
         'status.zoomResetError': 'Kunne ikke tilbakestille zoom',
@@ -144,6 +266,7 @@ const TRANSLATIONS = {
         'status.urlResolveError': 'Kunne ikke løse URL',
         'status.noBaseUrl': 'Kan ikke navigere til relativ lenke uten en base-URL',
         'status.openFileError': 'Kunne ikke åpne fil',
+        'dialog.supportedFiles': 'Dokumenter',
         
         // Shortcuts (tooltips)
         'shortcut.back': 'Alt+←',
@@ -174,6 +297,14 @@ const TRANSLATIONS = {
         'menu.toggleTheme': 'Byt tema',
         'menu.settings': 'Innstillingar...',
         'menu.about': 'Om Bare...',
+        'menu.exportHtml': 'Eksporter som HTML...',
+        'menu.exportPdf': 'Eksporter som PDF...',
+        'menu.saveSource': 'Lagre kjelda...',
+        'menu.copyMarkdown': 'Kopier som markdown',
+        'menu.copyText': 'Kopier som tekst',
+        'menu.copyLink': 'Kopier lenkja',
+        'menu.recentFiles': 'Nylege filer',
+        'menu.clearRecentFiles': 'Tøm lista',
         'search.placeholder': 'Søk i sida...',
         'search.prev': 'Førre treff',
         'search.next': 'Neste treff',
@@ -189,13 +320,53 @@ const TRANSLATIONS = {
         'settings.themeLight': 'Lys',
         'settings.themeDark': 'Mørk',
         'settings.themeSystem': 'System',
+        'settings.themeSepia': 'Sepia',
+        'settings.colors': 'Eigne fargar',
+        'settings.colorBackground': 'Bakgrunn',
+        'settings.colorText': 'Tekst',
+        'settings.colorLink': 'Lenkjer',
+        'settings.colorAccent': 'Aksent',
+        'settings.resetColors': 'Tilbakestill fargar',
         'settings.fontFamily': 'Skrifttype',
         'settings.fontSystem': 'System',
         'settings.fontSerif': 'Serif',
         'settings.fontSansSerif': 'Sans-serif',
         'settings.fontMono': 'Monospace',
+        'settings.fontCustom': 'Eiga skrift',
+        'settings.customFontName': 'Namn på installert skrift',
         'settings.fontSize': 'Skriftstorleik',
         'settings.contentWidth': 'Innhaldsbreidd',
+        'settings.zoomStep': 'Zoom-steg (%)',
+        'settings.lineHeight': 'Linjehøgd',
+        'settings.paragraphSpacing': 'Avsnittsavstand',
+        'settings.justifyText': 'Blokkjuster tekst',
+        'settings.decorations': 'Ikon framfor lenkjer',
+        'settings.decorationsEmoji': 'Emoji',
+        'settings.decorationsText': 'Tekst ([DIR])',
+        'settings.decorationsNone': 'Ingen',
+        'settings.plainText': 'Rein tekst',
+        'settings.plainTextPreformatted': 'Fast breidd',
+        'settings.plainTextParagraphs': 'Avsnitt',
+        'settings.showHiddenFiles': 'Vis skjulte filer i mapper',
+        'settings.followSymlinks': 'Følg symbolske lenkjer',
+        'settings.maxFileMb': 'Største lokale fil (MB)',
+        'settings.maxRenderMb': 'Største dokument som blir vist (MB)',
+        'settings.logLevel': 'Logging',
+        'settings.logLevelError': 'Berre feil',
+        'settings.logLevelWarn': 'Feil og åtvaringar',
+        'settings.logLevelInfo': 'Vanleg',
+        'settings.logLevelDebug': 'Detaljert',
+        'settings.logLevelTrace': 'Alt',
+        'settings.customCss': 'Eigen CSS',
+        'settings.homepage': 'Startside',
+        'settings.searchEngine': 'Søkjemotor',
+        'settings.defaultScheme': 'Adresser utan protokoll',
+        'settings.defaultSchemeAsk': 'Spør kvar gong',
+        'settings.downloadsDir': 'Nedlastingsmappe',
+        'settings.downloadsWithoutAsking': 'Lagre utan å spørje',
+        'schemeChoice.title': 'Vel protokoll',
+        'schemeChoice.remember': 'Hugs for denne nettstaden',
+        'schemeChoice.prompt': 'Korleis vil du opne {address}?',
         'settings.language': 'Språk',
         'settings.languageSystem': 'System',
         'settings.htmlConversion': 'HTML-konvertering',
@@ -204,6 +375,47 @@ const TRANSLATIONS = {
         'settings.markdownOnly': 'Berre markdown',
         'settings.askEverytime': 'Spør kvar gong',
         'settings.readability': 'Readability-modus (hent ut hovudinnhald)',
+        'settings.network': 'Nettverk',
+        'settings.timeoutSeconds': 'Tidsavbrot (sekund)',
+        'settings.maxResponseMb': 'Maks storleik (MB)',
+        'settings.maxRedirects': 'Maks tal på omdirigeringar',
+        'settings.privacy': 'Personvern',
+        'settings.loadRemoteImages': 'Last bilete frå nettet',
+        'settings.stripQueryParams': 'Fjern sporingsparametrar frå lenkjer',
+        'settings.sendReferrer': 'Send tilvisande side (Referer)',
+        'settings.sendGpc': 'Be om å ikkje bli spora (GPC/DNT)',
+        'settings.prefetch': 'Hent lenkjer når musa blir halden over dei',
+        'settings.historyEnabled': 'Hugs besøkte sider',
+        'settings.privateMode': 'Privat surfing (skriv ingenting til disk)',
+        'status.privateModeOn': 'Privat surfing er på',
+        'status.privateModeOff': 'Privat surfing er av',
+        'status.openWindowError': 'Kunne ikkje opne nytt vindauge',
+        'status.exported': 'Lagra {path}',
+        'status.exportError': 'Kunne ikkje eksportere sida',
+        'status.saveSourceError': 'Kunne ikkje lagre kjelda',
+        'status.copied': 'Kopiert til utklippstavla',
+        'status.copyError': 'Kunne ikkje kopiere',
+        'status.linkCopied': 'Kopierte {url}',
+        'prompt.download': 'Bare kan ikkje vise {name}. Vil du laste ned fila?',
+        'status.notDisplayable': '{name} kan ikkje visast i Bare',
+        'status.downloadStarted': 'Startar nedlasting av {name}',
+        'status.downloading': 'Lastar ned {name}: {progress}',
+        'status.downloaded': 'Lasta ned {path}',
+        'status.downloadError': 'Nedlastinga mislukkast',
+        'status.linkOutsideRoot': 'Lenkja peikar ut av mappa som er opna',
+        'status.exportNoPage': 'Opne ei side før du eksporterer',
+        'status.didYouMean': 'Meinte du {url}?',
+        'settings.reset': 'Tilbakestill innstillingar',
+        'settings.resetAppearance': 'Utsjånad',
+        'settings.resetNetwork': 'Nettverk',
+        'settings.resetPrivacy': 'Personvern',
+        'settings.resetSiteOverrides': 'Innstillingar per nettstad',
+        'settings.resetAll': 'Alle innstillingar',
+        'settings.resetButton': 'Tilbakestill',
+        'settings.resetConfirm': 'Tilbakestille valde innstillingar til standard?',
+        'settings.clearBrowsingData': 'Slett surfedata',
+        'settings.clearBrowsingDataConfirm': 'Slette historikk, tilbake og fram i vindauga, mellomlagra sider, kjende Gemini-vertar, nylege filer og innstillingar per nettstad? Bokmerke og andre innstillingar vert behaldne.',
+        'status.browsingDataCleared': 'Surfedata er sletta',
         'about.title': 'Om Bare',
         'about.description': 'Ein eksperimentell markdown-nettlesar med fokus på personvern, fart og reint innhald.',
         'about.feature1': '> Rein markdown-vising',
@@ -233,15 +445,48 @@ const TRANSLATIONS = {
         'input.searchPrompt': 'Skriv inn søkeord:',
         'footer.ready': 'Klar',
         'footer.connecting': 'Koplar til...',
+        'progress.resolving': 'Slår opp {host}...',
+        'progress.connecting': 'Koplar til {host}...',
+        'progress.tls_handshake': 'TLS-handshake med {host}...',
+        'progress.transferring': 'Overfører data... ({bytes} bytes)',
+        'progress.converting': 'Konverterer...',
+        'progress.rendering': 'Rendrar...',
+        'progress.done': 'Dokument: Ferdig',
+        'progress.error': 'Feil under lasting',
         'footer.loadingHome': 'Lastar startside...',
         'footer.reloading': 'Lastar på nytt...',
         'footer.converted': 'konvertert',
+        'footer.readerMode': 'lesevising',
         'loading.text': '[ LASTAR... ]',
         'error.title': '⚠️ Feil',
         'error.goHome': 'Gå til startsida',
         'status.settingsError': 'Kunne ikkje oppdatere innstilling',
+        'status.settingsAdjusted': 'Innstillinga vart endra',
+        'settings.warning.clamped': 'justert',
+        'settings.warning.ignored': 'ignorert',
+        'settings.warning.invalid': 'ugyldig',
         'status.loadSettingsError': 'Kunne ikkje laste innstillingar',
         'status.conversionCancelled': 'Konvertering avbroten av brukaren',
+        'status.redirectCancelled': 'Vidaresendinga vart avbroten',
+        'status.identityCancelled': 'Ingen identitet vart laga, så sida kan ikkje visast',
+        'status.navigationCancelled': 'Navigering avbroten',
+        'toolbar.security': 'Tryggleik for sida',
+        'security.title': 'Tilkopling',
+        'security.protocol': 'Protokoll',
+        'security.encryption': 'Kryptering',
+        'security.encrypted': 'Kryptert',
+        'security.notEncrypted': 'Ikkje kryptert',
+        'security.tlsVersion': 'TLS-versjon',
+        'security.cipher': 'Chiffer',
+        'security.certificate': 'Sertifikat',
+        'security.cert.ca_validated': 'Utferda av ein kjend sertifiseringsinstans',
+        'security.cert.pinned': 'Same som ved første vitjing',
+        'security.cert.none': 'Ingen',
+        'security.fingerprint': 'Fingeravtrykk (SHA-256)',
+        'security.downgraded': 'Ei omdirigering førte frå ei kryptert til ei ukryptert adresse',
+        'security.local': 'Lokal side, inga tilkopling',
+        'security.unknown': 'Ukjend',
+        'conversion.alwaysForSite': 'Konverter alltid sider frå {host}?',
         'status.zoomInError': 'Kunne ikkje zoome inn',
         'status.zoomOutError': 'Kunne ikkje zoome ut',
         'status.zoomResetError': 'Kunne ikkje tilbakestille zoom',
@@ -250,6 +495,7 @@ const TRANSLATIONS = {
         'status.urlResolveError': 'Kunne ikkje løyse URL',
         'status.noBaseUrl': 'Kan ikkje navigere til relativ lenkje utan ein base-URL',
         'status.openFileError': 'Kunne ikkje opne fil',
+        'dialog.supportedFiles': 'Dokument',
         'shortcut.back': 'Alt+←',
         'shortcut.forward': 'Alt+→',
         'shortcut.home': 'G',
@@ -278,6 +524,14 @@ const TRANSLATIONS = {
         'menu.toggleTheme': 'Byt tema',
         'menu.settings': 'Inställningar...',
         'menu.about': 'Om Bare...',
+        'menu.exportHtml': 'Exportera som HTML...',
+        'menu.exportPdf': 'Exportera som PDF...',
+        'menu.saveSource': 'Spara källan...',
+        'menu.copyMarkdown': 'Kopiera som markdown',
+        'menu.copyText': 'Kopiera som text',
+        'menu.copyLink': 'Kopiera länken',
+        'menu.recentFiles': 'Senaste filer',
+        'menu.clearRecentFiles': 'Töm listan',
         'search.placeholder': 'Sök på sidan...',
         'search.prev': 'Föregående träff',
         'search.next': 'Nästa träff',
@@ -293,13 +547,53 @@ const TRANSLATIONS = {
         'settings.themeLight': 'Ljust',
         'settings.themeDark': 'Mörkt',
         'settings.themeSystem': 'System',
+        'settings.themeSepia': 'Sepia',
+        'settings.colors': 'Egna färger',
+        'settings.colorBackground': 'Bakgrund',
+        'settings.colorText': 'Text',
+        'settings.colorLink': 'Länkar',
+        'settings.colorAccent': 'Accent',
+        'settings.resetColors': 'Återställ färger',
         'settings.fontFamily': 'Typsnitt',
         'settings.fontSystem': 'System',
         'settings.fontSerif': 'Serif',
         'settings.fontSansSerif': 'Sans-serif',
         'settings.fontMono': 'Monospace',
+        'settings.fontCustom': 'Eget typsnitt',
+        'settings.customFontName': 'Namn på installerat typsnitt',
         'settings.fontSize': 'Teckenstorlek',
         'settings.contentWidth': 'Innehållsbredd',
+        'settings.zoomStep': 'Zoomsteg (%)',
+        'settings.lineHeight': 'Radhöjd',
+        'settings.paragraphSpacing': 'Styckeavstånd',
+        'settings.justifyText': 'Marginaljustera text',
+        'settings.decorations': 'Ikoner före länkar',
+        'settings.decorationsEmoji': 'Emoji',
+        'settings.decorationsText': 'Text ([DIR])',
+        'settings.decorationsNone': 'Inga',
+        'settings.plainText': 'Ren text',
+        'settings.plainTextPreformatted': 'Fast bredd',
+        'settings.plainTextParagraphs': 'Stycken',
+        'settings.showHiddenFiles': 'Visa dolda filer i mappar',
+        'settings.followSymlinks': 'Följ symboliska länkar',
+        'settings.maxFileMb': 'Största lokala fil (MB)',
+        'settings.maxRenderMb': 'Största dokument som visas (MB)',
+        'settings.logLevel': 'Loggning',
+        'settings.logLevelError': 'Endast fel',
+        'settings.logLevelWarn': 'Fel och varningar',
+        'settings.logLevelInfo': 'Normal',
+        'settings.logLevelDebug': 'Detaljerad',
+        'settings.logLevelTrace': 'Allt',
+        'settings.customCss': 'Egen CSS',
+        'settings.homepage': 'Startsida',
+        'settings.searchEngine': 'Sökmotor',
+        'settings.defaultScheme': 'Adresser utan protokoll',
+        'settings.defaultSchemeAsk': 'Fråga varje gång',
+        'settings.downloadsDir': 'Mapp för nedladdningar',
+        'settings.downloadsWithoutAsking': 'Spara utan att fråga',
+        'schemeChoice.title': 'Välj protokoll',
+        'schemeChoice.remember': 'Kom ihåg för den här webbplatsen',
+        'schemeChoice.prompt': 'Hur vill du öppna {address}?',
         'settings.language': 'Språk',
         'settings.languageSystem': 'System',
         'settings.htmlConversion': 'HTML-konvertering',
@@ -308,6 +602,47 @@ const TRANSLATIONS = {
         'settings.markdownOnly': 'Endast markdown',
         'settings.askEverytime': 'Fråga varje gång',
         'settings.readability': 'Readability-läge (extrahera huvudinnehåll)',
+        'settings.network': 'Nätverk',
+        'settings.timeoutSeconds': 'Tidsgräns (sekunder)',
+        'settings.maxResponseMb': 'Maxstorlek (MB)',
+        'settings.maxRedirects': 'Max antal omdirigeringar',
+        'settings.privacy': 'Integritet',
+        'settings.loadRemoteImages': 'Läs in bilder från nätet',
+        'settings.stripQueryParams': 'Ta bort spårningsparametrar från länkar',
+        'settings.sendReferrer': 'Skicka hänvisande sida (Referer)',
+        'settings.sendGpc': 'Be om att inte spåras (GPC/DNT)',
+        'settings.prefetch': 'Hämta länkar när musen hålls över dem',
+        'settings.historyEnabled': 'Kom ihåg besökta sidor',
+        'settings.privateMode': 'Privat surfning (skriver inget till disk)',
+        'status.privateModeOn': 'Privat surfning är på',
+        'status.privateModeOff': 'Privat surfning är av',
+        'status.openWindowError': 'Kunde inte öppna nytt fönster',
+        'status.exported': 'Sparade {path}',
+        'status.exportError': 'Kunde inte exportera sidan',
+        'status.saveSourceError': 'Kunde inte spara källan',
+        'status.copied': 'Kopierat till urklipp',
+        'status.copyError': 'Kunde inte kopiera',
+        'status.linkCopied': 'Kopierade {url}',
+        'prompt.download': 'Bare kan inte visa {name}. Vill du ladda ned filen?',
+        'status.notDisplayable': '{name} kan inte visas i Bare',
+        'status.downloadStarted': 'Startar nedladdning av {name}',
+        'status.downloading': 'Laddar ned {name}: {progress}',
+        'status.downloaded': 'Laddade ned {path}',
+        'status.downloadError': 'Nedladdningen misslyckades',
+        'status.linkOutsideRoot': 'Länken pekar utanför den öppnade mappen',
+        'status.exportNoPage': 'Öppna en sida innan du exporterar',
+        'status.didYouMean': 'Menade du {url}?',
+        'settings.reset': 'Återställ inställningar',
+        'settings.resetAppearance': 'Utseende',
+        'settings.resetNetwork': 'Nätverk',
+        'settings.resetPrivacy': 'Integritet',
+        'settings.resetSiteOverrides': 'Inställningar per webbplats',
+        'settings.resetAll': 'Alla inställningar',
+        'settings.resetButton': 'Återställ',
+        'settings.resetConfirm': 'Återställa valda inställningar till standard?',
+        'settings.clearBrowsingData': 'Rensa webbdata',
+        'settings.clearBrowsingDataConfirm': 'Radera historik, bakåt och framåt i fönstren, cachade sidor, kända Gemini-värdar, senaste filer och inställningar per webbplats? Bokmärken och andra inställningar behålls.',
+        'status.browsingDataCleared': 'Webbdata har rensats',
         'about.title': 'Om Bare',
         'about.description': 'En experimentell markdown-webbläsare med fokus på integritet, hastighet och rent innehåll.',
         'about.feature1': '> Ren markdown-visning',
@@ -337,15 +672,48 @@ const TRANSLATIONS = {
         'input.searchPrompt': 'Ange sökord:',
         'footer.ready': 'Klar',
         'footer.connecting': 'Ansluter...',
+        'progress.resolving': 'Slår upp {host}...',
+        'progress.connecting': 'Ansluter till {host}...',
+        'progress.tls_handshake': 'TLS-handskakning med {host}...',
+        'progress.transferring': 'Överför data... ({bytes} byte)',
+        'progress.converting': 'Konverterar...',
+        'progress.rendering': 'Renderar...',
+        'progress.done': 'Dokument: Klart',
+        'progress.error': 'Fel vid inläsning',
         'footer.loadingHome': 'Laddar startsida...',
         'footer.reloading': 'Laddar om...',
         'footer.converted': 'konverterad',
+        'footer.readerMode': 'läsläge',
         'loading.text': '[ LADDAR... ]',
         'error.title': '⚠️ Fel',
         'error.goHome': 'Gå till startsidan',
         'status.settingsError': 'Kunde inte uppdatera inställning',
+        'status.settingsAdjusted': 'Inställningen ändrades',
+        'settings.warning.clamped': 'justerad',
+        'settings.warning.ignored': 'ignorerad',
+        'settings.warning.invalid': 'ogiltig',
         'status.loadSettingsError': 'Kunde inte ladda inställningar',
         'status.conversionCancelled': 'Konvertering avbruten av användaren',
+        'status.redirectCancelled': 'Omdirigeringen avbröts',
+        'status.identityCancelled': 'Ingen identitet skapades, så sidan kan inte visas',
+        'status.navigationCancelled': 'Navigering avbruten',
+        'toolbar.security': 'Sidans säkerhet',
+        'security.title': 'Anslutning',
+        'security.protocol': 'Protokoll',
+        'security.encryption': 'Kryptering',
+        'security.encrypted': 'Krypterad',
+        'security.notEncrypted': 'Inte krypterad',
+        'security.tlsVersion': 'TLS-version',
+        'security.cipher': 'Chiffer',
+        'security.certificate': 'Certifikat',
+        'security.cert.ca_validated': 'Utfärdat av en känd certifikatutfärdare',
+        'security.cert.pinned': 'Samma som vid första besöket',
+        'security.cert.none': 'Inget',
+        'security.fingerprint': 'Fingeravtryck (SHA-256)',
+        'security.downgraded': 'En omdirigering ledde från en krypterad till en okrypterad adress',
+        'security.local': 'Lokal sida, ingen anslutning',
+        'security.unknown': 'Okänd',
+        'conversion.alwaysForSite': 'Konvertera alltid sidor från {host}?',
         'status.zoomInError': 'Kunde inte zooma in',
         'status.zoomOutError': 'Kunde inte zooma ut',
         'status.zoomResetError': 'Kunde inte återställa zoom',
@@ -354,6 +722,7 @@ const TRANSLATIONS = {
         'status.urlResolveError': 'Kunde inte lösa URL',
         'status.noBaseUrl': 'Kan inte navigera till relativ länk utan en bas-URL',
         'status.openFileError': 'Kunde inte öppna fil',
+        'dialog.supportedFiles': 'Dokument',
         'shortcut.back': 'Alt+←',
         'shortcut.forward': 'Alt+→',
         'shortcut.home': 'G',
@@ -382,6 +751,14 @@ const TRANSLATIONS = {
         'menu.toggleTheme': 'Skift tema',
         'menu.settings': 'Indstillinger...',
         'menu.about': 'Om Bare...',
+        'menu.exportHtml': 'Eksportér som HTML...',
+        'menu.exportPdf': 'Eksportér som PDF...',
+        'menu.saveSource': 'Gem kilden...',
+        'menu.copyMarkdown': 'Kopiér som markdown',
+        'menu.copyText': 'Kopiér som tekst',
+        'menu.copyLink': 'Kopiér linket',
+        'menu.recentFiles': 'Seneste filer',
+        'menu.clearRecentFiles': 'Ryd listen',
         'search.placeholder': 'Søg på siden...',
         'search.prev': 'Forrige match',
         'search.next': 'Næste match',
@@ -397,13 +774,53 @@ const TRANSLATIONS = {
         'settings.themeLight': 'Lyst',
         'settings.themeDark': 'Mørkt',
         'settings.themeSystem': 'System',
+        'settings.themeSepia': 'Sepia',
+        'settings.colors': 'Egne farver',
+        'settings.colorBackground': 'Baggrund',
+        'settings.colorText': 'Tekst',
+        'settings.colorLink': 'Links',
+        'settings.colorAccent': 'Accent',
+        'settings.resetColors': 'Nulstil farver',
         'settings.fontFamily': 'Skrifttype',
         'settings.fontSystem': 'System',
         'settings.fontSerif': 'Serif',
         'settings.fontSansSerif': 'Sans-serif',
         'settings.fontMono': 'Monospace',
+        'settings.fontCustom': 'Egen skrifttype',
+        'settings.customFontName': 'Navn på installeret skrifttype',
         'settings.fontSize': 'Skriftstørrelse',
         'settings.contentWidth': 'Indholdsbredde',
+        'settings.zoomStep': 'Zoomtrin (%)',
+        'settings.lineHeight': 'Linjehøjde',
+        'settings.paragraphSpacing': 'Afsnitsafstand',
+        'settings.justifyText': 'Lige margener',
+        'settings.decorations': 'Ikoner foran links',
+        'settings.decorationsEmoji': 'Emoji',
+        'settings.decorationsText': 'Tekst ([DIR])',
+        'settings.decorationsNone': 'Ingen',
+        'settings.plainText': 'Ren tekst',
+        'settings.plainTextPreformatted': 'Fast bredde',
+        'settings.plainTextParagraphs': 'Afsnit',
+        'settings.showHiddenFiles': 'Vis skjulte filer i mapper',
+        'settings.followSymlinks': 'Følg symbolske links',
+        'settings.maxFileMb': 'Største lokale fil (MB)',
+        'settings.maxRenderMb': 'Største dokument der vises (MB)',
+        'settings.logLevel': 'Logning',
+        'settings.logLevelError': 'Kun fejl',
+        'settings.logLevelWarn': 'Fejl og advarsler',
+        'settings.logLevelInfo': 'Normal',
+        'settings.logLevelDebug': 'Detaljeret',
+        'settings.logLevelTrace': 'Alt',
+        'settings.customCss': 'Egen CSS',
+        'settings.homepage': 'Startside',
+        'settings.searchEngine': 'Søgemaskine',
+        'settings.defaultScheme': 'Adresser uden protokol',
+        'settings.defaultSchemeAsk': 'Spørg hver gang',
+        'settings.downloadsDir': 'Mappe til overførsler',
+        'settings.downloadsWithoutAsking': 'Gem uden at spørge',
+        'schemeChoice.title': 'Vælg protokol',
+        'schemeChoice.remember': 'Husk for dette websted',
+        'schemeChoice.prompt': 'Hvordan vil du åbne {address}?',
         'settings.language': 'Sprog',
         'settings.languageSystem': 'System',
         'settings.htmlConversion': 'HTML-konvertering',
@@ -412,6 +829,47 @@ const TRANSLATIONS = {
         'settings.markdownOnly': 'Kun markdown',
         'settings.askEverytime': 'Spørg hver gang',
         'settings.readability': 'Readability-tilstand (udtræk hovedindhold)',
+        'settings.network': 'Netværk',
+        'settings.timeoutSeconds': 'Tidsgrænse (sekunder)',
+        'settings.maxResponseMb': 'Maks. størrelse (MB)',
+        'settings.maxRedirects': 'Maks. antal omdirigeringer',
+        'settings.privacy': 'Privatliv',
+        'settings.loadRemoteImages': 'Indlæs billeder fra nettet',
+        'settings.stripQueryParams': 'Fjern sporingsparametre fra links',
+        'settings.sendReferrer': 'Send henvisende side (Referer)',
+        'settings.sendGpc': 'Bed om ikke at blive sporet (GPC/DNT)',
+        'settings.prefetch': 'Hent links, når musen holdes over dem',
+        'settings.historyEnabled': 'Husk besøgte sider',
+        'settings.privateMode': 'Privat browsing (skriver intet til disk)',
+        'status.privateModeOn': 'Privat browsing er slået til',
+        'status.privateModeOff': 'Privat browsing er slået fra',
+        'status.openWindowError': 'Kunne ikke åbne nyt vindue',
+        'status.exported': 'Gemte {path}',
+        'status.exportError': 'Kunne ikke eksportere siden',
+        'status.saveSourceError': 'Kunne ikke gemme kilden',
+        'status.copied': 'Kopieret til udklipsholderen',
+        'status.copyError': 'Kunne ikke kopiere',
+        'status.linkCopied': 'Kopierede {url}',
+        'prompt.download': 'Bare kan ikke vise {name}. Vil du hente filen?',
+        'status.notDisplayable': '{name} kan ikke vises i Bare',
+        'status.downloadStarted': 'Starter overførsel af {name}',
+        'status.downloading': 'Henter {name}: {progress}',
+        'status.downloaded': 'Hentede {path}',
+        'status.downloadError': 'Overførslen mislykkedes',
+        'status.linkOutsideRoot': 'Linket peger uden for den åbnede mappe',
+        'status.exportNoPage': 'Åbn en side før du eksporterer',
+        'status.didYouMean': 'Mente du {url}?',
+        'settings.reset': 'Nulstil indstillinger',
+        'settings.resetAppearance': 'Udseende',
+        'settings.resetNetwork': 'Netværk',
+        'settings.resetPrivacy': 'Privatliv',
+        'settings.resetSiteOverrides': 'Indstillinger pr. websted',
+        'settings.resetAll': 'Alle indstillinger',
+        'settings.resetButton': 'Nulstil',
+        'settings.resetConfirm': 'Nulstil valgte indstillinger til standard?',
+        'settings.clearBrowsingData': 'Slet browserdata',
+        'settings.clearBrowsingDataConfirm': 'Slet historik, tilbage og frem i vinduerne, cachelagrede sider, kendte Gemini-værter, seneste filer og indstillinger pr. websted? Bogmærker og andre indstillinger beholdes.',
+        'status.browsingDataCleared': 'Browserdata er slettet',
         'about.title': 'Om Bare',
         'about.description': 'En eksperimentel markdown-browser med fokus på privatliv, hastighed og rent indhold.',
         'about.feature1': '> Ren markdown-visning',
@@ -441,15 +899,48 @@ const TRANSLATIONS = {
         'input.searchPrompt': 'Indtast søgeord:',
         'footer.ready': 'Klar',
         'footer.connecting': 'Forbinder...',
+        'progress.resolving': 'Slår {host} op...',
+        'progress.connecting': 'Forbinder til {host}...',
+        'progress.tls_handshake': 'TLS-handshake med {host}...',
+        'progress.transferring': 'Overfører data... ({bytes} bytes)',
+        'progress.converting': 'Konverterer...',
+        'progress.rendering': 'Renderer...',
+        'progress.done': 'Dokument: Færdig',
+        'progress.error': 'Fejl under indlæsning',
         'footer.loadingHome': 'Indlæser startside...',
         'footer.reloading': 'Genindlæser...',
         'footer.converted': 'konverteret',
+        'footer.readerMode': 'læsetilstand',
         'loading.text': '[ INDLÆSER... ]',
         'error.title': '⚠️ Fejl',
         'error.goHome': 'Gå til startsiden',
         'status.settingsError': 'Kunne ikke opdatere indstilling',
+        'status.settingsAdjusted': 'Indstillingen blev ændret',
+        'settings.warning.clamped': 'justeret',
+        'settings.warning.ignored': 'ignoreret',
+        'settings.warning.invalid': 'ugyldig',
         'status.loadSettingsError': 'Kunne ikke indlæse indstillinger',
         'status.conversionCancelled': 'Konvertering annulleret af brugeren',
+        'status.redirectCancelled': 'Omdirigeringen blev afbrudt',
+        'status.identityCancelled': 'Der blev ikke oprettet nogen identitet, så siden kan ikke vises',
+        'status.navigationCancelled': 'Navigation afbrudt',
+        'toolbar.security': 'Sidens sikkerhed',
+        'security.title': 'Forbindelse',
+        'security.protocol': 'Protokol',
+        'security.encryption': 'Kryptering',
+        'security.encrypted': 'Krypteret',
+        'security.notEncrypted': 'Ikke krypteret',
+        'security.tlsVersion': 'TLS-version',
+        'security.cipher': 'Cipher',
+        'security.certificate': 'Certifikat',
+        'security.cert.ca_validated': 'Udstedt af en kendt certificeringsmyndighed',
+        'security.cert.pinned': 'Samme som ved første besøg',
+        'security.cert.none': 'Intet',
+        'security.fingerprint': 'Fingeraftryk (SHA-256)',
+        'security.downgraded': 'En omdirigering førte fra en krypteret til en ukrypteret adresse',
+        'security.local': 'Lokal side, ingen forbindelse',
+        'security.unknown': 'Ukendt',
+        'conversion.alwaysForSite': 'Konvertér altid sider fra {host}?',
         'status.zoomInError': 'Kunne ikke zoome ind',
         'status.zoomOutError': 'Kunne ikke zoome ud',
         'status.zoomResetError': 'Kunne ikke nulstille zoom',
@@ -458,6 +949,7 @@ const TRANSLATIONS = {
         'status.urlResolveError': 'Kunne ikke løse URL',
         'status.noBaseUrl': 'Kan ikke navigere til relativt link uden en base-URL',
         'status.openFileError': 'Kunne ikke åbne fil',
+        'dialog.supportedFiles': 'Dokumenter',
         'shortcut.back': 'Alt+←',
         'shortcut.forward': 'Alt+→',
         'shortcut.home': 'G',
@@ -486,6 +978,14 @@ const TRANSLATIONS = {
         'menu.toggleTheme': 'Vaihda teema',
         'menu.settings': 'Asetukset...',
         'menu.about': 'Tietoja Bare...',
+        'menu.exportHtml': 'Vie HTML-tiedostona...',
+        'menu.exportPdf': 'Vie PDF-tiedostona...',
+        'menu.saveSource': 'Tallenna lähde...',
+        'menu.copyMarkdown': 'Kopioi markdownina',
+        'menu.copyText': 'Kopioi tekstinä',
+        'menu.copyLink': 'Kopioi linkki',
+        'menu.recentFiles': 'Viimeisimmät tiedostot',
+        'menu.clearRecentFiles': 'Tyhjennä luettelo',
         'search.placeholder': 'Hae sivulta...',
         'search.prev': 'Edellinen osuma',
         'search.next': 'Seuraava osuma',
@@ -501,13 +1001,53 @@ const TRANSLATIONS = {
         'settings.themeLight': 'Vaalea',
         'settings.themeDark': 'Tumma',
         'settings.themeSystem': 'Järjestelmä',
+        'settings.themeSepia': 'Seepia',
+        'settings.colors': 'Omat värit',
+        'settings.colorBackground': 'Tausta',
+        'settings.colorText': 'Teksti',
+        'settings.colorLink': 'Linkit',
+        'settings.colorAccent': 'Korostus',
+        'settings.resetColors': 'Palauta värit',
         'settings.fontFamily': 'Kirjasintyyppi',
         'settings.fontSystem': 'Järjestelmä',
         'settings.fontSerif': 'Serif',
         'settings.fontSansSerif': 'Sans-serif',
         'settings.fontMono': 'Monospace',
+        'settings.fontCustom': 'Oma fontti',
+        'settings.customFontName': 'Asennetun fontin nimi',
         'settings.fontSize': 'Kirjasinkoko',
         'settings.contentWidth': 'Sisällön leveys',
+        'settings.zoomStep': 'Zoomausaskel (%)',
+        'settings.lineHeight': 'Rivikorkeus',
+        'settings.paragraphSpacing': 'Kappaleväli',
+        'settings.justifyText': 'Tasaa molemmat reunat',
+        'settings.decorations': 'Kuvakkeet linkkien edessä',
+        'settings.decorationsEmoji': 'Emoji',
+        'settings.decorationsText': 'Teksti ([DIR])',
+        'settings.decorationsNone': 'Ei mitään',
+        'settings.plainText': 'Pelkkä teksti',
+        'settings.plainTextPreformatted': 'Tasalevyinen',
+        'settings.plainTextParagraphs': 'Kappaleet',
+        'settings.showHiddenFiles': 'Näytä piilotiedostot kansioissa',
+        'settings.followSymlinks': 'Seuraa symbolisia linkkejä',
+        'settings.maxFileMb': 'Suurin paikallinen tiedosto (Mt)',
+        'settings.maxRenderMb': 'Suurin näytettävä asiakirja (Mt)',
+        'settings.logLevel': 'Lokitus',
+        'settings.logLevelError': 'Vain virheet',
+        'settings.logLevelWarn': 'Virheet ja varoitukset',
+        'settings.logLevelInfo': 'Tavallinen',
+        'settings.logLevelDebug': 'Yksityiskohtainen',
+        'settings.logLevelTrace': 'Kaikki',
+        'settings.customCss': 'Oma CSS',
+        'settings.homepage': 'Aloitussivu',
+        'settings.searchEngine': 'Hakukone',
+        'settings.defaultScheme': 'Osoitteet ilman protokollaa',
+        'settings.defaultSchemeAsk': 'Kysy joka kerta',
+        'settings.downloadsDir': 'Latauskansio',
+        'settings.downloadsWithoutAsking': 'Tallenna kysymättä',
+        'schemeChoice.title': 'Valitse protokolla',
+        'schemeChoice.remember': 'Muista tälle sivustolle',
+        'schemeChoice.prompt': 'Miten haluat avata osoitteen {address}?',
         'settings.language': 'Kieli',
         'settings.languageSystem': 'Järjestelmä',
         'settings.htmlConversion': 'HTML-muunnos',
@@ -516,6 +1056,47 @@ const TRANSLATIONS = {
         'settings.markdownOnly': 'Vain markdown',
         'settings.askEverytime': 'Kysy joka kerta',
         'settings.readability': 'Readability-tila (poimi pääsisältö)',
+        'settings.network': 'Verkko',
+        'settings.timeoutSeconds': 'Aikakatkaisu (sekuntia)',
+        'settings.maxResponseMb': 'Enimmäiskoko (Mt)',
+        'settings.maxRedirects': 'Uudelleenohjausten enimmäismäärä',
+        'settings.privacy': 'Yksityisyys',
+        'settings.loadRemoteImages': 'Lataa kuvat verkosta',
+        'settings.stripQueryParams': 'Poista seurantaparametrit linkeistä',
+        'settings.sendReferrer': 'Lähetä viittaava sivu (Referer)',
+        'settings.sendGpc': 'Pyydä, ettei minua seurata (GPC/DNT)',
+        'settings.prefetch': 'Nouda linkit, kun hiiri on niiden päällä',
+        'settings.historyEnabled': 'Muista vieraillut sivut',
+        'settings.privateMode': 'Yksityinen selaus (ei tallenna levylle)',
+        'status.privateModeOn': 'Yksityinen selaus on päällä',
+        'status.privateModeOff': 'Yksityinen selaus on pois',
+        'status.openWindowError': 'Uuden ikkunan avaaminen epäonnistui',
+        'status.exported': 'Tallennettu {path}',
+        'status.exportError': 'Sivun vienti epäonnistui',
+        'status.saveSourceError': 'Lähdettä ei voitu tallentaa',
+        'status.copied': 'Kopioitu leikepöydälle',
+        'status.copyError': 'Kopiointi epäonnistui',
+        'status.linkCopied': 'Kopioitu {url}',
+        'prompt.download': 'Bare ei voi näyttää tiedostoa {name}. Haluatko ladata sen?',
+        'status.notDisplayable': 'Tiedostoa {name} ei voi näyttää Baressa',
+        'status.downloadStarted': 'Aloitetaan tiedoston {name} lataus',
+        'status.downloading': 'Ladataan {name}: {progress}',
+        'status.downloaded': 'Ladattu {path}',
+        'status.downloadError': 'Lataus epäonnistui',
+        'status.linkOutsideRoot': 'Linkki osoittaa avatun kansion ulkopuolelle',
+        'status.exportNoPage': 'Avaa sivu ennen vientiä',
+        'status.didYouMean': 'Tarkoititko {url}?',
+        'settings.reset': 'Palauta asetukset',
+        'settings.resetAppearance': 'Ulkoasu',
+        'settings.resetNetwork': 'Verkko',
+        'settings.resetPrivacy': 'Yksityisyys',
+        'settings.resetSiteOverrides': 'Sivustokohtaiset asetukset',
+        'settings.resetAll': 'Kaikki asetukset',
+        'settings.resetButton': 'Palauta',
+        'settings.resetConfirm': 'Palautetaanko valitut asetukset oletuksiin?',
+        'settings.clearBrowsingData': 'Tyhjennä selaustiedot',
+        'settings.clearBrowsingDataConfirm': 'Poistetaanko historia, ikkunoiden edellinen- ja seuraava-sivut, välimuistissa olevat sivut, tunnetut Gemini-palvelimet, viimeisimmät tiedostot ja sivustokohtaiset asetukset? Kirjanmerkit ja muut asetukset säilyvät.',
+        'status.browsingDataCleared': 'Selaustiedot tyhjennetty',
         'about.title': 'Tietoja Bare',
         'about.description': 'Kokeellinen markdown-selain, joka keskittyy yksityisyyteen, nopeuteen ja puhtaaseen sisältöön.',
         'about.feature1': '> Puhdas markdown-näkymä',
@@ -545,15 +1126,48 @@ const TRANSLATIONS = {
         'input.searchPrompt': 'Kirjoita hakusana:',
         'footer.ready': 'Valmis',
         'footer.connecting': 'Yhdistetään...',
+        'progress.resolving': 'Haetaan {host}...',
+        'progress.connecting': 'Yhdistetään {host}...',
+        'progress.tls_handshake': 'TLS-kättely: {host}...',
+        'progress.transferring': 'Siirretään dataa... ({bytes} tavua)',
+        'progress.converting': 'Muunnetaan...',
+        'progress.rendering': 'Piirretään...',
+        'progress.done': 'Asiakirja: Valmis',
+        'progress.error': 'Virhe latauksessa',
         'footer.loadingHome': 'Ladataan aloitussivua...',
         'footer.reloading': 'Ladataan uudelleen...',
         'footer.converted': 'muunnettu',
+        'footer.readerMode': 'lukutila',
         'loading.text': '[ LADATAAN... ]',
         'error.title': '⚠️ Virhe',
         'error.goHome': 'Siirry aloitussivulle',
         'status.settingsError': 'Asetuksen päivitys epäonnistui',
+        'status.settingsAdjusted': 'Asetusta muutettiin',
+        'settings.warning.clamped': 'rajattu',
+        'settings.warning.ignored': 'ohitettu',
+        'settings.warning.invalid': 'virheellinen',
         'status.loadSettingsError': 'Asetusten lataus epäonnistui',
         'status.conversionCancelled': 'Käyttäjä keskeytti muunnoksen',
+        'status.redirectCancelled': 'Uudelleenohjaus peruttiin',
+        'status.identityCancelled': 'Identiteettiä ei luotu, joten sivua ei voi näyttää',
+        'status.navigationCancelled': 'Siirtyminen peruttu',
+        'toolbar.security': 'Sivun suojaus',
+        'security.title': 'Yhteys',
+        'security.protocol': 'Protokolla',
+        'security.encryption': 'Salaus',
+        'security.encrypted': 'Salattu',
+        'security.notEncrypted': 'Ei salattu',
+        'security.tlsVersion': 'TLS-versio',
+        'security.cipher': 'Salausalgoritmi',
+        'security.certificate': 'Varmenne',
+        'security.cert.ca_validated': 'Tunnetun varmentajan myöntämä',
+        'security.cert.pinned': 'Sama kuin ensimmäisellä käynnillä',
+        'security.cert.none': 'Ei mitään',
+        'security.fingerprint': 'Sormenjälki (SHA-256)',
+        'security.downgraded': 'Uudelleenohjaus vei salatusta osoitteesta salaamattomaan',
+        'security.local': 'Paikallinen sivu, ei yhteyttä',
+        'security.unknown': 'Tuntematon',
+        'conversion.alwaysForSite': 'Muunnetaanko sivut aina sivustolta {host}?',
         'status.zoomInError': 'Lähennys epäonnistui',
         'status.zoomOutError': 'Loitonnus epäonnistui',
         'status.zoomResetError': 'Zoomin nollaus epäonnistui',
@@ -562,6 +1176,7 @@ const TRANSLATIONS = {
         'status.urlResolveError': 'URL:n ratkaisu epäonnistui',
         'status.noBaseUrl': 'Suhteelliseen linkkiin ei voi navigoida ilman perus-URL:ää',
         'status.openFileError': 'Tiedoston avaus epäonnistui',
+        'dialog.supportedFiles': 'Asiakirjat',
         'shortcut.back': 'Alt+←',
         'shortcut.forward': 'Alt+→',
         'shortcut.home': 'G',
@@ -590,6 +1205,14 @@ const TRANSLATIONS = {
         'menu.toggleTheme': 'Toggle theme',
         'menu.settings': 'Settings...',
         'menu.about': 'About Bare...',
+        'menu.exportHtml': 'Export as HTML...',
+        'menu.exportPdf': 'Export as PDF...',
+        'menu.saveSource': 'Save source...',
+        'menu.copyMarkdown': 'Copy as markdown',
+        'menu.copyText': 'Copy as text',
+        'menu.copyLink': 'Copy link',
+        'menu.recentFiles': 'Recent files',
+        'menu.clearRecentFiles': 'Clear list',
         'search.placeholder': 'Search in page...',
         'search.prev': 'Previous match',
         'search.next': 'Next match',
@@ -605,13 +1228,53 @@ const TRANSLATIONS = {
         'settings.themeLight': 'Light',
         'settings.themeDark': 'Dark',
         'settings.themeSystem': 'System',
+        'settings.themeSepia': 'Sepia',
+        'settings.colors': 'Custom colors',
+        'settings.colorBackground': 'Background',
+        'settings.colorText': 'Text',
+        'settings.colorLink': 'Links',
+        'settings.colorAccent': 'Accent',
+        'settings.resetColors': 'Reset colors',
         'settings.fontFamily': 'Font',
         'settings.fontSystem': 'System',
         'settings.fontSerif': 'Serif',
         'settings.fontSansSerif': 'Sans-serif',
         'settings.fontMono': 'Monospace',
+        'settings.fontCustom': 'Custom font',
+        'settings.customFontName': 'Name of an installed font',
         'settings.fontSize': 'Font size',
         'settings.contentWidth': 'Content width',
+        'settings.zoomStep': 'Zoom step (%)',
+        'settings.lineHeight': 'Line height',
+        'settings.paragraphSpacing': 'Paragraph spacing',
+        'settings.justifyText': 'Justify text',
+        'settings.decorations': 'Icons before links',
+        'settings.decorationsEmoji': 'Emoji',
+        'settings.decorationsText': 'Text ([DIR])',
+        'settings.decorationsNone': 'None',
+        'settings.plainText': 'Plain text',
+        'settings.plainTextPreformatted': 'Monospaced',
+        'settings.plainTextParagraphs': 'Paragraphs',
+        'settings.showHiddenFiles': 'Show hidden files in folders',
+        'settings.followSymlinks': 'Follow symbolic links',
+        'settings.maxFileMb': 'Largest local file (MB)',
+        'settings.maxRenderMb': 'Largest document shown (MB)',
+        'settings.logLevel': 'Logging',
+        'settings.logLevelError': 'Errors only',
+        'settings.logLevelWarn': 'Errors and warnings',
+        'settings.logLevelInfo': 'Normal',
+        'settings.logLevelDebug': 'Detailed',
+        'settings.logLevelTrace': 'Everything',
+        'settings.customCss': 'Custom CSS',
+        'settings.homepage': 'Home page',
+        'settings.searchEngine': 'Search engine',
+        'settings.defaultScheme': 'Addresses without protocol',
+        'settings.defaultSchemeAsk': 'Ask every time',
+        'settings.downloadsDir': 'Downloads folder',
+        'settings.downloadsWithoutAsking': 'Save without asking',
+        'schemeChoice.title': 'Choose protocol',
+        'schemeChoice.remember': 'Remember for this site',
+        'schemeChoice.prompt': 'How do you want to open {address}?',
         'settings.language': 'Language',
         'settings.languageSystem': 'System',
         'settings.htmlConversion': 'HTML conversion',
@@ -620,6 +1283,47 @@ const TRANSLATIONS = {
         'settings.markdownOnly': 'Markdown only',
         'settings.askEverytime': 'Ask every time',
         'settings.readability': 'Readability mode (extract main content)',
+        'settings.network': 'Network',
+        'settings.timeoutSeconds': 'Timeout (seconds)',
+        'settings.maxResponseMb': 'Max size (MB)',
+        'settings.maxRedirects': 'Max redirects',
+        'settings.privacy': 'Privacy',
+        'settings.loadRemoteImages': 'Load remote images',
+        'settings.stripQueryParams': 'Strip tracking parameters from links',
+        'settings.sendReferrer': 'Send referring page (Referer)',
+        'settings.sendGpc': 'Ask not to be tracked (GPC/DNT)',
+        'settings.prefetch': 'Fetch links when hovering over them',
+        'settings.historyEnabled': 'Remember visited pages',
+        'settings.privateMode': 'Private browsing (writes nothing to disk)',
+        'status.privateModeOn': 'Private browsing is on',
+        'status.privateModeOff': 'Private browsing is off',
+        'status.openWindowError': 'Could not open new window',
+        'status.exported': 'Saved {path}',
+        'status.exportError': 'Could not export page',
+        'status.saveSourceError': 'Could not save the source',
+        'status.copied': 'Copied to the clipboard',
+        'status.copyError': 'Could not copy',
+        'status.linkCopied': 'Copied {url}',
+        'prompt.download': 'Bare cannot show {name}. Download the file?',
+        'status.notDisplayable': '{name} cannot be shown in Bare',
+        'status.downloadStarted': 'Downloading {name}',
+        'status.downloading': 'Downloading {name}: {progress}',
+        'status.downloaded': 'Downloaded {path}',
+        'status.downloadError': 'Download failed',
+        'status.linkOutsideRoot': 'This link points outside the opened folder',
+        'status.exportNoPage': 'Open a page before exporting',
+        'status.didYouMean': 'Did you mean {url}?',
+        'settings.reset': 'Reset settings',
+        'settings.resetAppearance': 'Appearance',
+        'settings.resetNetwork': 'Network',
+        'settings.resetPrivacy': 'Privacy',
+        'settings.resetSiteOverrides': 'Per-site settings',
+        'settings.resetAll': 'All settings',
+        'settings.resetButton': 'Reset',
+        'settings.resetConfirm': 'Reset the selected settings to their defaults?',
+        'settings.clearBrowsingData': 'Clear browsing data',
+        'settings.clearBrowsingDataConfirm': 'Delete history, back and forward in each window, cached pages, known Gemini hosts, recent files and per-site settings? Bookmarks and other settings are kept.',
+        'status.browsingDataCleared': 'Browsing data cleared',
         'about.title': 'About Bare',
         'about.description': 'An experimental markdown browser focused on privacy, speed and clean content.',
         'about.feature1': '> Clean markdown rendering',
@@ -649,15 +1353,48 @@ const TRANSLATIONS = {
         'input.searchPrompt': 'Enter search term:',
         'footer.ready': 'Ready',
         'footer.connecting': 'Connecting...',
+        'progress.resolving': 'Looking up {host}...',
+        'progress.connecting': 'Connecting to {host}...',
+        'progress.tls_handshake': 'TLS handshake with {host}...',
+        'progress.transferring': 'Transferring data... ({bytes} bytes)',
+        'progress.converting': 'Converting...',
+        'progress.rendering': 'Rendering...',
+        'progress.done': 'Document: Done',
+        'progress.error': 'Error while loading',
         'footer.loadingHome': 'Loading home page...',
         'footer.reloading': 'Reloading...',
         'footer.converted': 'converted',
+        'footer.readerMode': 'reader mode',
         'loading.text': '[ LOADING... ]',
         'error.title': '⚠️ Error',
         'error.goHome': 'Go to home page',
         'status.settingsError': 'Could not update setting',
+        'status.settingsAdjusted': 'Setting was changed',
+        'settings.warning.clamped': 'clamped',
+        'settings.warning.ignored': 'ignored',
+        'settings.warning.invalid': 'invalid',
         'status.loadSettingsError': 'Could not load settings',
         'status.conversionCancelled': 'Conversion cancelled by user',
+        'status.redirectCancelled': 'Redirect cancelled',
+        'status.identityCancelled': 'No identity was created, so the page cannot be shown',
+        'status.navigationCancelled': 'Navigation cancelled',
+        'toolbar.security': 'Page security',
+        'security.title': 'Connection',
+        'security.protocol': 'Protocol',
+        'security.encryption': 'Encryption',
+        'security.encrypted': 'Encrypted',
+        'security.notEncrypted': 'Not encrypted',
+        'security.tlsVersion': 'TLS version',
+        'security.cipher': 'Cipher suite',
+        'security.certificate': 'Certificate',
+        'security.cert.ca_validated': 'Issued by a known certificate authority',
+        'security.cert.pinned': 'Same as on first visit',
+        'security.cert.none': 'None',
+        'security.fingerprint': 'Fingerprint (SHA-256)',
+        'security.downgraded': 'A redirect led from an encrypted to an unencrypted address',
+        'security.local': 'Local page, no connection',
+        'security.unknown': 'Unknown',
+        'conversion.alwaysForSite': 'Always convert pages from {host}?',
         'status.zoomInError': 'Could not zoom in',
         'status.zoomOutError': 'Could not zoom out',
         'status.zoomResetError': 'Could not reset zoom',
@@ -666,6 +1403,7 @@ const TRANSLATIONS = {
         'status.urlResolveError': 'Could not resolve URL',
         'status.noBaseUrl': 'Cannot navigate to relative link without a base URL',
         'status.openFileError': 'Could not open file',
+        'dialog.supportedFiles': 'Documents',
         'shortcut.back': 'Alt+←',
         'shortcut.forward': 'Alt+→',
         'shortcut.home': 'G',
@@ -694,6 +1432,14 @@ const TRANSLATIONS = {
         'menu.toggleTheme': 'Design wechseln',
         'menu.settings': 'Einstellungen...',
         'menu.about': 'Über Bare...',
+        'menu.exportHtml': 'Als HTML exportieren...',
+        'menu.exportPdf': 'Als PDF exportieren...',
+        'menu.saveSource': 'Quelle speichern...',
+        'menu.copyMarkdown': 'Als Markdown kopieren',
+        'menu.copyText': 'Als Text kopieren',
+        'menu.copyLink': 'Link kopieren',
+        'menu.recentFiles': 'Zuletzt geöffnet',
+        'menu.clearRecentFiles': 'Liste leeren',
         'search.placeholder': 'Auf Seite suchen...',
         'search.prev': 'Vorheriger Treffer',
         'search.next': 'Nächster Treffer',
@@ -709,13 +1455,53 @@ const TRANSLATIONS = {
         'settings.themeLight': 'Hell',
         'settings.themeDark': 'Dunkel',
         'settings.themeSystem': 'System',
+        'settings.themeSepia': 'Sepia',
+        'settings.colors': 'Eigene Farben',
+        'settings.colorBackground': 'Hintergrund',
+        'settings.colorText': 'Text',
+        'settings.colorLink': 'Links',
+        'settings.colorAccent': 'Akzent',
+        'settings.resetColors': 'Farben zurücksetzen',
         'settings.fontFamily': 'Schriftart',
         'settings.fontSystem': 'System',
         'settings.fontSerif': 'Serif',
         'settings.fontSansSerif': 'Sans-Serif',
         'settings.fontMono': 'Monospace',
+        'settings.fontCustom': 'Eigene Schrift',
+        'settings.customFontName': 'Name einer installierten Schrift',
         'settings.fontSize': 'Schriftgröße',
         'settings.contentWidth': 'Inhaltsbreite',
+        'settings.zoomStep': 'Zoomschritt (%)',
+        'settings.lineHeight': 'Zeilenhöhe',
+        'settings.paragraphSpacing': 'Absatzabstand',
+        'settings.justifyText': 'Blocksatz',
+        'settings.decorations': 'Symbole vor Links',
+        'settings.decorationsEmoji': 'Emoji',
+        'settings.decorationsText': 'Text ([DIR])',
+        'settings.decorationsNone': 'Keine',
+        'settings.plainText': 'Reiner Text',
+        'settings.plainTextPreformatted': 'Feste Breite',
+        'settings.plainTextParagraphs': 'Absätze',
+        'settings.showHiddenFiles': 'Versteckte Dateien in Ordnern anzeigen',
+        'settings.followSymlinks': 'Symbolischen Links folgen',
+        'settings.maxFileMb': 'Größte lokale Datei (MB)',
+        'settings.maxRenderMb': 'Größtes angezeigtes Dokument (MB)',
+        'settings.logLevel': 'Protokollierung',
+        'settings.logLevelError': 'Nur Fehler',
+        'settings.logLevelWarn': 'Fehler und Warnungen',
+        'settings.logLevelInfo': 'Normal',
+        'settings.logLevelDebug': 'Ausführlich',
+        'settings.logLevelTrace': 'Alles',
+        'settings.customCss': 'Eigenes CSS',
+        'settings.homepage': 'Startseite',
+        'settings.searchEngine': 'Suchmaschine',
+        'settings.defaultScheme': 'Adressen ohne Protokoll',
+        'settings.defaultSchemeAsk': 'Jedes Mal fragen',
+        'settings.downloadsDir': 'Download-Ordner',
+        'settings.downloadsWithoutAsking': 'Ohne Nachfrage speichern',
+        'schemeChoice.title': 'Protokoll wählen',
+        'schemeChoice.remember': 'Für diese Website merken',
+        'schemeChoice.prompt': 'Wie möchten Sie {address} öffnen?',
         'settings.language': 'Sprache',
         'settings.languageSystem': 'System',
         'settings.htmlConversion': 'HTML-Konvertierung',
@@ -724,6 +1510,47 @@ const TRANSLATIONS = {
         'settings.markdownOnly': 'Nur Markdown',
         'settings.askEverytime': 'Jedes Mal fragen',
         'settings.readability': 'Readability-Modus (Hauptinhalt extrahieren)',
+        'settings.network': 'Netzwerk',
+        'settings.timeoutSeconds': 'Zeitlimit (Sekunden)',
+        'settings.maxResponseMb': 'Max. Größe (MB)',
+        'settings.maxRedirects': 'Max. Weiterleitungen',
+        'settings.privacy': 'Datenschutz',
+        'settings.loadRemoteImages': 'Bilder aus dem Netz laden',
+        'settings.stripQueryParams': 'Tracking-Parameter aus Links entfernen',
+        'settings.sendReferrer': 'Verweisende Seite senden (Referer)',
+        'settings.sendGpc': 'Bitten, nicht verfolgt zu werden (GPC/DNT)',
+        'settings.prefetch': 'Links beim Überfahren mit der Maus laden',
+        'settings.historyEnabled': 'Besuchte Seiten merken',
+        'settings.privateMode': 'Privates Surfen (schreibt nichts auf die Festplatte)',
+        'status.privateModeOn': 'Privates Surfen ist an',
+        'status.privateModeOff': 'Privates Surfen ist aus',
+        'status.openWindowError': 'Neues Fenster konnte nicht geöffnet werden',
+        'status.exported': '{path} gespeichert',
+        'status.exportError': 'Seite konnte nicht exportiert werden',
+        'status.saveSourceError': 'Quelle konnte nicht gespeichert werden',
+        'status.copied': 'In die Zwischenablage kopiert',
+        'status.copyError': 'Kopieren fehlgeschlagen',
+        'status.linkCopied': '{url} kopiert',
+        'prompt.download': 'Bare kann {name} nicht anzeigen. Datei herunterladen?',
+        'status.notDisplayable': '{name} kann in Bare nicht angezeigt werden',
+        'status.downloadStarted': 'Download von {name} gestartet',
+        'status.downloading': 'Lade {name} herunter: {progress}',
+        'status.downloaded': 'Heruntergeladen: {path}',
+        'status.downloadError': 'Download fehlgeschlagen',
+        'status.linkOutsideRoot': 'Der Link zeigt aus dem geöffneten Ordner heraus',
+        'status.exportNoPage': 'Öffnen Sie vor dem Export eine Seite',
+        'status.didYouMean': 'Meinten Sie {url}?',
+        'settings.reset': 'Einstellungen zurücksetzen',
+        'settings.resetAppearance': 'Darstellung',
+        'settings.resetNetwork': 'Netzwerk',
+        'settings.resetPrivacy': 'Datenschutz',
+        'settings.resetSiteOverrides': 'Einstellungen pro Website',
+        'settings.resetAll': 'Alle Einstellungen',
+        'settings.resetButton': 'Zurücksetzen',
+        'settings.resetConfirm': 'Ausgewählte Einstellungen auf Standard zurücksetzen?',
+        'settings.clearBrowsingData': 'Browserdaten löschen',
+        'settings.clearBrowsingDataConfirm': 'Verlauf, Zurück und Vor in den Fenstern, zwischengespeicherte Seiten, bekannte Gemini-Hosts, zuletzt geöffnete Dateien und Website-Einstellungen löschen? Lesezeichen und andere Einstellungen bleiben erhalten.',
+        'status.browsingDataCleared': 'Browserdaten gelöscht',
         'about.title': 'Über Bare',
         'about.description': 'Ein experimenteller Markdown-Browser mit Fokus auf Datenschutz, Geschwindigkeit und sauberen Inhalt.',
         'about.feature1': '> Saubere Markdown-Anzeige',
@@ -753,15 +1580,48 @@ const TRANSLATIONS = {
         'input.searchPrompt': 'Suchbegriff eingeben:',
         'footer.ready': 'Bereit',
         'footer.connecting': 'Verbinde...',
+        'progress.resolving': 'Suche {host}...',
+        'progress.connecting': 'Verbinde mit {host}...',
+        'progress.tls_handshake': 'TLS-Handshake mit {host}...',
+        'progress.transferring': 'Übertrage Daten... ({bytes} Bytes)',
+        'progress.converting': 'Konvertiere...',
+        'progress.rendering': 'Rendere...',
+        'progress.done': 'Dokument: Fertig',
+        'progress.error': 'Fehler beim Laden',
         'footer.loadingHome': 'Startseite wird geladen...',
         'footer.reloading': 'Neu laden...',
         'footer.converted': 'konvertiert',
+        'footer.readerMode': 'Lesemodus',
         'loading.text': '[ LADEN... ]',
         'error.title': '⚠️ Fehler',
         'error.goHome': 'Zur Startseite',
         'status.settingsError': 'Einstellung konnte nicht aktualisiert werden',
+        'status.settingsAdjusted': 'Einstellung wurde geändert',
+        'settings.warning.clamped': 'angepasst',
+        'settings.warning.ignored': 'ignoriert',
+        'settings.warning.invalid': 'ungültig',
         'status.loadSettingsError': 'Einstellungen konnten nicht geladen werden',
         'status.conversionCancelled': 'Konvertierung vom Benutzer abgebrochen',
+        'status.redirectCancelled': 'Weiterleitung abgebrochen',
+        'status.identityCancelled': 'Es wurde keine Identität erstellt, daher kann die Seite nicht angezeigt werden',
+        'status.navigationCancelled': 'Navigation abgebrochen',
+        'toolbar.security': 'Seitensicherheit',
+        'security.title': 'Verbindung',
+        'security.protocol': 'Protokoll',
+        'security.encryption': 'Verschlüsselung',
+        'security.encrypted': 'Verschlüsselt',
+        'security.notEncrypted': 'Nicht verschlüsselt',
+        'security.tlsVersion': 'TLS-Version',
+        'security.cipher': 'Cipher-Suite',
+        'security.certificate': 'Zertifikat',
+        'security.cert.ca_validated': 'Von einer bekannten Zertifizierungsstelle ausgestellt',
+        'security.cert.pinned': 'Wie beim ersten Besuch',
+        'security.cert.none': 'Keins',
+        'security.fingerprint': 'Fingerabdruck (SHA-256)',
+        'security.downgraded': 'Eine Weiterleitung führte von einer verschlüsselten zu einer unverschlüsselten Adresse',
+        'security.local': 'Lokale Seite, keine Verbindung',
+        'security.unknown': 'Unbekannt',
+        'conversion.alwaysForSite': 'Seiten von {host} immer konvertieren?',
         'status.zoomInError': 'Vergrößern fehlgeschlagen',
         'status.zoomOutError': 'Verkleinern fehlgeschlagen',
         'status.zoomResetError': 'Zoom konnte nicht zurückgesetzt werden',
@@ -770,6 +1630,7 @@ const TRANSLATIONS = {
         'status.urlResolveError': 'URL konnte nicht aufgelöst werden',
         'status.noBaseUrl': 'Kann nicht zu relativem Link ohne Basis-URL navigieren',
         'status.openFileError': 'Datei konnte nicht geöffnet werden',
+        'dialog.supportedFiles': 'Dokumente',
         'shortcut.back': 'Alt+←',
         'shortcut.forward': 'Alt+→',
         'shortcut.home': 'G',
@@ -798,6 +1659,14 @@ const TRANSLATIONS = {
         'menu.toggleTheme': 'Changer de thème',
         'menu.settings': 'Paramètres...',
         'menu.about': 'À propos de Bare...',
+        'menu.exportHtml': 'Exporter en HTML...',
+        'menu.exportPdf': 'Exporter en PDF...',
+        'menu.saveSource': 'Enregistrer la source...',
+        'menu.copyMarkdown': 'Copier en markdown',
+        'menu.copyText': 'Copier en texte',
+        'menu.copyLink': 'Copier le lien',
+        'menu.recentFiles': 'Fichiers récents',
+        'menu.clearRecentFiles': 'Vider la liste',
         'search.placeholder': 'Rechercher dans la page...',
         'search.prev': 'Résultat précédent',
         'search.next': 'Résultat suivant',
@@ -813,13 +1682,53 @@ const TRANSLATIONS = {
         'settings.themeLight': 'Clair',
         'settings.themeDark': 'Sombre',
         'settings.themeSystem': 'Système',
+        'settings.themeSepia': 'Sépia',
+        'settings.colors': 'Couleurs personnalisées',
+        'settings.colorBackground': 'Arrière-plan',
+        'settings.colorText': 'Texte',
+        'settings.colorLink': 'Liens',
+        'settings.colorAccent': 'Accent',
+        'settings.resetColors': 'Réinitialiser les couleurs',
         'settings.fontFamily': 'Police',
         'settings.fontSystem': 'Système',
         'settings.fontSerif': 'Serif',
         'settings.fontSansSerif': 'Sans-serif',
         'settings.fontMono': 'Monospace',
+        'settings.fontCustom': 'Police personnalisée',
+        'settings.customFontName': 'Nom d\'une police installée',
         'settings.fontSize': 'Taille de police',
         'settings.contentWidth': 'Largeur du contenu',
+        'settings.zoomStep': 'Pas du zoom (%)',
+        'settings.lineHeight': 'Hauteur de ligne',
+        'settings.paragraphSpacing': 'Espacement des paragraphes',
+        'settings.justifyText': 'Justifier le texte',
+        'settings.decorations': 'Icônes devant les liens',
+        'settings.decorationsEmoji': 'Emoji',
+        'settings.decorationsText': 'Texte ([DIR])',
+        'settings.decorationsNone': 'Aucune',
+        'settings.plainText': 'Texte brut',
+        'settings.plainTextPreformatted': 'Chasse fixe',
+        'settings.plainTextParagraphs': 'Paragraphes',
+        'settings.showHiddenFiles': 'Afficher les fichiers cachés dans les dossiers',
+        'settings.followSymlinks': 'Suivre les liens symboliques',
+        'settings.maxFileMb': 'Taille max. des fichiers locaux (Mo)',
+        'settings.maxRenderMb': 'Taille max. des documents affichés (Mo)',
+        'settings.logLevel': 'Journalisation',
+        'settings.logLevelError': 'Erreurs uniquement',
+        'settings.logLevelWarn': 'Erreurs et avertissements',
+        'settings.logLevelInfo': 'Normal',
+        'settings.logLevelDebug': 'Détaillé',
+        'settings.logLevelTrace': 'Tout',
+        'settings.customCss': 'CSS personnalisé',
+        'settings.homepage': 'Page d\'accueil',
+        'settings.searchEngine': 'Moteur de recherche',
+        'settings.defaultScheme': 'Adresses sans protocole',
+        'settings.defaultSchemeAsk': 'Demander à chaque fois',
+        'settings.downloadsDir': 'Dossier de téléchargement',
+        'settings.downloadsWithoutAsking': 'Enregistrer sans demander',
+        'schemeChoice.title': 'Choisir le protocole',
+        'schemeChoice.remember': 'Retenir pour ce site',
+        'schemeChoice.prompt': 'Comment voulez-vous ouvrir {address} ?',
         'settings.language': 'Langue',
         'settings.languageSystem': 'Système',
         'settings.htmlConversion': 'Conversion HTML',
@@ -828,6 +1737,47 @@ const TRANSLATIONS = {
         'settings.markdownOnly': 'Markdown uniquement',
         'settings.askEverytime': 'Demander à chaque fois',
         'settings.readability': 'Mode lisibilité (extraire le contenu principal)',
+        'settings.network': 'Réseau',
+        'settings.timeoutSeconds': 'Délai d\'attente (secondes)',
+        'settings.maxResponseMb': 'Taille max. (Mo)',
+        'settings.maxRedirects': 'Redirections max.',
+        'settings.privacy': 'Confidentialité',
+        'settings.loadRemoteImages': 'Charger les images distantes',
+        'settings.stripQueryParams': 'Supprimer les paramètres de suivi des liens',
+        'settings.sendReferrer': 'Envoyer la page de provenance (Referer)',
+        'settings.sendGpc': 'Demander à ne pas être pisté (GPC/DNT)',
+        'settings.prefetch': 'Charger les liens au survol',
+        'settings.historyEnabled': 'Mémoriser les pages visitées',
+        'settings.privateMode': 'Navigation privée (rien n\'est écrit sur le disque)',
+        'status.privateModeOn': 'Navigation privée activée',
+        'status.privateModeOff': 'Navigation privée désactivée',
+        'status.openWindowError': 'Impossible d\'ouvrir une nouvelle fenêtre',
+        'status.exported': '{path} enregistré',
+        'status.exportError': 'Impossible d\'exporter la page',
+        'status.saveSourceError': 'Impossible d’enregistrer la source',
+        'status.copied': 'Copié dans le presse-papiers',
+        'status.copyError': 'Impossible de copier',
+        'status.linkCopied': '{url} copié',
+        'prompt.download': 'Bare ne peut pas afficher {name}. Télécharger le fichier ?',
+        'status.notDisplayable': '{name} ne peut pas être affiché dans Bare',
+        'status.downloadStarted': 'Téléchargement de {name} lancé',
+        'status.downloading': 'Téléchargement de {name} : {progress}',
+        'status.downloaded': 'Téléchargé : {path}',
+        'status.downloadError': 'Échec du téléchargement',
+        'status.linkOutsideRoot': 'Ce lien pointe hors du dossier ouvert',
+        'status.exportNoPage': 'Ouvrez une page avant d\'exporter',
+        'status.didYouMean': 'Vouliez-vous dire {url} ?',
+        'settings.reset': 'Réinitialiser les paramètres',
+        'settings.resetAppearance': 'Apparence',
+        'settings.resetNetwork': 'Réseau',
+        'settings.resetPrivacy': 'Confidentialité',
+        'settings.resetSiteOverrides': 'Paramètres par site',
+        'settings.resetAll': 'Tous les paramètres',
+        'settings.resetButton': 'Réinitialiser',
+        'settings.resetConfirm': 'Réinitialiser les paramètres sélectionnés ?',
+        'settings.clearBrowsingData': 'Effacer les données de navigation',
+        'settings.clearBrowsingDataConfirm': 'Supprimer l\'historique, le retour et l\'avance des fenêtres, les pages en cache, les hôtes Gemini connus, les fichiers récents et les paramètres par site ? Les favoris et les autres paramètres sont conservés.',
+        'status.browsingDataCleared': 'Données de navigation effacées',
         'about.title': 'À propos de Bare',
         'about.description': 'Un navigateur markdown expérimental axé sur la confidentialité, la vitesse et un contenu épuré.',
         'about.feature1': '> Rendu markdown propre',
@@ -857,15 +1807,48 @@ const TRANSLATIONS = {
         'input.searchPrompt': 'Entrer un terme de recherche :',
         'footer.ready': 'Prêt',
         'footer.connecting': 'Connexion...',
+        'progress.resolving': 'Résolution de {host}...',
+        'progress.connecting': 'Connexion à {host}...',
+        'progress.tls_handshake': 'Négociation TLS avec {host}...',
+        'progress.transferring': 'Transfert des données... ({bytes} octets)',
+        'progress.converting': 'Conversion...',
+        'progress.rendering': 'Rendu...',
+        'progress.done': 'Document : terminé',
+        'progress.error': 'Erreur de chargement',
         'footer.loadingHome': 'Chargement de la page d\'accueil...',
         'footer.reloading': 'Rechargement...',
         'footer.converted': 'converti',
+        'footer.readerMode': 'mode lecture',
         'loading.text': '[ CHARGEMENT... ]',
         'error.title': '⚠️ Erreur',
         'error.goHome': 'Retour à l\'accueil',
         'status.settingsError': 'Impossible de mettre à jour le paramètre',
+        'status.settingsAdjusted': 'Le paramètre a été modifié',
+        'settings.warning.clamped': 'ajusté',
+        'settings.warning.ignored': 'ignoré',
+        'settings.warning.invalid': 'invalide',
         'status.loadSettingsError': 'Impossible de charger les paramètres',
         'status.conversionCancelled': 'Conversion annulée par l\'utilisateur',
+        'status.redirectCancelled': 'Redirection annulée',
+        'status.identityCancelled': 'Aucune identité n\'a été créée, la page ne peut donc pas être affichée',
+        'status.navigationCancelled': 'Navigation annulée',
+        'toolbar.security': 'Sécurité de la page',
+        'security.title': 'Connexion',
+        'security.protocol': 'Protocole',
+        'security.encryption': 'Chiffrement',
+        'security.encrypted': 'Chiffrée',
+        'security.notEncrypted': 'Non chiffrée',
+        'security.tlsVersion': 'Version TLS',
+        'security.cipher': 'Suite de chiffrement',
+        'security.certificate': 'Certificat',
+        'security.cert.ca_validated': 'Émis par une autorité de certification connue',
+        'security.cert.pinned': 'Identique à la première visite',
+        'security.cert.none': 'Aucun',
+        'security.fingerprint': 'Empreinte (SHA-256)',
+        'security.downgraded': 'Une redirection a mené d’une adresse chiffrée à une adresse non chiffrée',
+        'security.local': 'Page locale, aucune connexion',
+        'security.unknown': 'Inconnu',
+        'conversion.alwaysForSite': 'Toujours convertir les pages de {host} ?',
         'status.zoomInError': 'Impossible de zoomer',
         'status.zoomOutError': 'Impossible de dézoomer',
         'status.zoomResetError': 'Impossible de réinitialiser le zoom',
@@ -874,6 +1857,7 @@ const TRANSLATIONS = {
         'status.urlResolveError': 'Impossible de résoudre l\'URL',
         'status.noBaseUrl': 'Impossible de naviguer vers un lien relatif sans URL de base',
         'status.openFileError': 'Impossible d\'ouvrir le fichier',
+        'dialog.supportedFiles': 'Documents',
         'shortcut.back': 'Alt+←',
         'shortcut.forward': 'Alt+→',
         'shortcut.home': 'G',
@@ -902,6 +1886,14 @@ const TRANSLATIONS = {
         'menu.toggleTheme': 'Cambiar tema',
         'menu.settings': 'Ajustes...',
         'menu.about': 'Acerca de Bare...',
+        'menu.exportHtml': 'Exportar como HTML...',
+        'menu.exportPdf': 'Exportar como PDF...',
+        'menu.saveSource': 'Guardar código fuente...',
+        'menu.copyMarkdown': 'Copiar como markdown',
+        'menu.copyText': 'Copiar como texto',
+        'menu.copyLink': 'Copiar enlace',
+        'menu.recentFiles': 'Archivos recientes',
+        'menu.clearRecentFiles': 'Vaciar la lista',
         'search.placeholder': 'Buscar en la página...',
         'search.prev': 'Coincidencia anterior',
         'search.next': 'Siguiente coincidencia',
@@ -917,13 +1909,53 @@ const TRANSLATIONS = {
         'settings.themeLight': 'Claro',
         'settings.themeDark': 'Oscuro',
         'settings.themeSystem': 'Sistema',
+        'settings.themeSepia': 'Sepia',
+        'settings.colors': 'Colores personalizados',
+        'settings.colorBackground': 'Fondo',
+        'settings.colorText': 'Texto',
+        'settings.colorLink': 'Enlaces',
+        'settings.colorAccent': 'Acento',
+        'settings.resetColors': 'Restablecer colores',
         'settings.fontFamily': 'Fuente',
         'settings.fontSystem': 'Sistema',
         'settings.fontSerif': 'Serif',
         'settings.fontSansSerif': 'Sans-serif',
         'settings.fontMono': 'Monospace',
+        'settings.fontCustom': 'Fuente personalizada',
+        'settings.customFontName': 'Nombre de una fuente instalada',
         'settings.fontSize': 'Tamaño de fuente',
         'settings.contentWidth': 'Ancho del contenido',
+        'settings.zoomStep': 'Paso de zoom (%)',
+        'settings.lineHeight': 'Altura de línea',
+        'settings.paragraphSpacing': 'Espacio entre párrafos',
+        'settings.justifyText': 'Justificar texto',
+        'settings.decorations': 'Iconos delante de los enlaces',
+        'settings.decorationsEmoji': 'Emoji',
+        'settings.decorationsText': 'Texto ([DIR])',
+        'settings.decorationsNone': 'Ninguno',
+        'settings.plainText': 'Texto plano',
+        'settings.plainTextPreformatted': 'Ancho fijo',
+        'settings.plainTextParagraphs': 'Párrafos',
+        'settings.showHiddenFiles': 'Mostrar archivos ocultos en carpetas',
+        'settings.followSymlinks': 'Seguir enlaces simbólicos',
+        'settings.maxFileMb': 'Tamaño máx. de archivo local (MB)',
+        'settings.maxRenderMb': 'Tamaño máx. de documento mostrado (MB)',
+        'settings.logLevel': 'Registro',
+        'settings.logLevelError': 'Solo errores',
+        'settings.logLevelWarn': 'Errores y advertencias',
+        'settings.logLevelInfo': 'Normal',
+        'settings.logLevelDebug': 'Detallado',
+        'settings.logLevelTrace': 'Todo',
+        'settings.customCss': 'CSS personalizado',
+        'settings.homepage': 'Página de inicio',
+        'settings.searchEngine': 'Motor de búsqueda',
+        'settings.defaultScheme': 'Direcciones sin protocolo',
+        'settings.defaultSchemeAsk': 'Preguntar siempre',
+        'settings.downloadsDir': 'Carpeta de descargas',
+        'settings.downloadsWithoutAsking': 'Guardar sin preguntar',
+        'schemeChoice.title': 'Elegir protocolo',
+        'schemeChoice.remember': 'Recordar para este sitio',
+        'schemeChoice.prompt': '¿Cómo quieres abrir {address}?',
         'settings.language': 'Idioma',
         'settings.languageSystem': 'Sistema',
         'settings.htmlConversion': 'Conversión HTML',
@@ -932,6 +1964,47 @@ const TRANSLATIONS = {
         'settings.markdownOnly': 'Solo markdown',
         'settings.askEverytime': 'Preguntar cada vez',
         'settings.readability': 'Modo legibilidad (extraer contenido principal)',
+        'settings.network': 'Red',
+        'settings.timeoutSeconds': 'Tiempo de espera (segundos)',
+        'settings.maxResponseMb': 'Tamaño máx. (MB)',
+        'settings.maxRedirects': 'Redirecciones máx.',
+        'settings.privacy': 'Privacidad',
+        'settings.loadRemoteImages': 'Cargar imágenes remotas',
+        'settings.stripQueryParams': 'Quitar parámetros de seguimiento de los enlaces',
+        'settings.sendReferrer': 'Enviar la página de origen (Referer)',
+        'settings.sendGpc': 'Pedir no ser rastreado (GPC/DNT)',
+        'settings.prefetch': 'Cargar enlaces al pasar el ratón',
+        'settings.historyEnabled': 'Recordar páginas visitadas',
+        'settings.privateMode': 'Navegación privada (no escribe nada en disco)',
+        'status.privateModeOn': 'Navegación privada activada',
+        'status.privateModeOff': 'Navegación privada desactivada',
+        'status.openWindowError': 'No se pudo abrir una nueva ventana',
+        'status.exported': 'Guardado {path}',
+        'status.exportError': 'No se pudo exportar la página',
+        'status.saveSourceError': 'No se pudo guardar el código fuente',
+        'status.copied': 'Copiado al portapapeles',
+        'status.copyError': 'No se pudo copiar',
+        'status.linkCopied': '{url} copiado',
+        'prompt.download': 'Bare no puede mostrar {name}. ¿Descargar el archivo?',
+        'status.notDisplayable': '{name} no se puede mostrar en Bare',
+        'status.downloadStarted': 'Iniciando la descarga de {name}',
+        'status.downloading': 'Descargando {name}: {progress}',
+        'status.downloaded': 'Descargado {path}',
+        'status.downloadError': 'La descarga falló',
+        'status.linkOutsideRoot': 'El enlace apunta fuera de la carpeta abierta',
+        'status.exportNoPage': 'Abre una página antes de exportar',
+        'status.didYouMean': '¿Quisiste decir {url}?',
+        'settings.reset': 'Restablecer ajustes',
+        'settings.resetAppearance': 'Apariencia',
+        'settings.resetNetwork': 'Red',
+        'settings.resetPrivacy': 'Privacidad',
+        'settings.resetSiteOverrides': 'Ajustes por sitio',
+        'settings.resetAll': 'Todos los ajustes',
+        'settings.resetButton': 'Restablecer',
+        'settings.resetConfirm': '¿Restablecer los ajustes seleccionados?',
+        'settings.clearBrowsingData': 'Borrar datos de navegación',
+        'settings.clearBrowsingDataConfirm': '¿Borrar el historial, atrás y adelante en las ventanas, las páginas en caché, los hosts Gemini conocidos, los archivos recientes y los ajustes por sitio? Los marcadores y demás ajustes se conservan.',
+        'status.browsingDataCleared': 'Datos de navegación borrados',
         'about.title': 'Acerca de Bare',
         'about.description': 'Un navegador markdown experimental centrado en la privacidad, la velocidad y el contenido limpio.',
         'about.feature1': '> Renderizado markdown limpio',
@@ -961,15 +2034,48 @@ const TRANSLATIONS = {
         'input.searchPrompt': 'Introducir término de búsqueda:',
         'footer.ready': 'Listo',
         'footer.connecting': 'Conectando...',
+        'progress.resolving': 'Resolviendo {host}...',
+        'progress.connecting': 'Conectando con {host}...',
+        'progress.tls_handshake': 'Negociación TLS con {host}...',
+        'progress.transferring': 'Transfiriendo datos... ({bytes} bytes)',
+        'progress.converting': 'Convirtiendo...',
+        'progress.rendering': 'Renderizando...',
+        'progress.done': 'Documento: listo',
+        'progress.error': 'Error al cargar',
         'footer.loadingHome': 'Cargando página de inicio...',
         'footer.reloading': 'Recargando...',
         'footer.converted': 'convertido',
+        'footer.readerMode': 'modo lectura',
         'loading.text': '[ CARGANDO... ]',
         'error.title': '⚠️ Error',
         'error.goHome': 'Ir a la página de inicio',
         'status.settingsError': 'No se pudo actualizar el ajuste',
+        'status.settingsAdjusted': 'El ajuste se modificó',
+        'settings.warning.clamped': 'ajustado',
+        'settings.warning.ignored': 'ignorado',
+        'settings.warning.invalid': 'no válido',
         'status.loadSettingsError': 'No se pudieron cargar los ajustes',
         'status.conversionCancelled': 'Conversión cancelada por el usuario',
+        'status.redirectCancelled': 'Redirección cancelada',
+        'status.identityCancelled': 'No se creó ninguna identidad, así que la página no se puede mostrar',
+        'status.navigationCancelled': 'Navegación cancelada',
+        'toolbar.security': 'Seguridad de la página',
+        'security.title': 'Conexión',
+        'security.protocol': 'Protocolo',
+        'security.encryption': 'Cifrado',
+        'security.encrypted': 'Cifrada',
+        'security.notEncrypted': 'Sin cifrar',
+        'security.tlsVersion': 'Versión de TLS',
+        'security.cipher': 'Suite de cifrado',
+        'security.certificate': 'Certificado',
+        'security.cert.ca_validated': 'Emitido por una autoridad de certificación conocida',
+        'security.cert.pinned': 'El mismo que en la primera visita',
+        'security.cert.none': 'Ninguno',
+        'security.fingerprint': 'Huella (SHA-256)',
+        'security.downgraded': 'Una redirección llevó de una dirección cifrada a una sin cifrar',
+        'security.local': 'Página local, sin conexión',
+        'security.unknown': 'Desconocido',
+        'conversion.alwaysForSite': '¿Convertir siempre las páginas de {host}?',
         'status.zoomInError': 'No se pudo acercar',
         'status.zoomOutError': 'No se pudo alejar',
         'status.zoomResetError': 'No se pudo restablecer el zoom',
@@ -978,6 +2084,7 @@ const TRANSLATIONS = {
         'status.urlResolveError': 'No se pudo resolver la URL',
         'status.noBaseUrl': 'No se puede navegar a un enlace relativo sin una URL base',
         'status.openFileError': 'No se pudo abrir el archivo',
+        'dialog.supportedFiles': 'Documentos',
         'shortcut.back': 'Alt+←',
         'shortcut.forward': 'Alt+→',
         'shortcut.home': 'G',
@@ -1006,6 +2113,14 @@ const TRANSLATIONS = {
         'menu.toggleTheme': 'Cambia tema',
         'menu.settings': 'Impostazioni...',
         'menu.about': 'Informazioni su Bare...',
+        'menu.exportHtml': 'Esporta come HTML...',
+        'menu.exportPdf': 'Esporta come PDF...',
+        'menu.saveSource': 'Salva sorgente...',
+        'menu.copyMarkdown': 'Copia come markdown',
+        'menu.copyText': 'Copia come testo',
+        'menu.copyLink': 'Copia link',
+        'menu.recentFiles': 'File recenti',
+        'menu.clearRecentFiles': 'Svuota elenco',
         'search.placeholder': 'Cerca nella pagina...',
         'search.prev': 'Risultato precedente',
         'search.next': 'Risultato successivo',
@@ -1021,13 +2136,53 @@ const TRANSLATIONS = {
         'settings.themeLight': 'Chiaro',
         'settings.themeDark': 'Scuro',
         'settings.themeSystem': 'Sistema',
+        'settings.themeSepia': 'Seppia',
+        'settings.colors': 'Colori personalizzati',
+        'settings.colorBackground': 'Sfondo',
+        'settings.colorText': 'Testo',
+        'settings.colorLink': 'Link',
+        'settings.colorAccent': 'Accento',
+        'settings.resetColors': 'Ripristina colori',
         'settings.fontFamily': 'Carattere',
         'settings.fontSystem': 'Sistema',
         'settings.fontSerif': 'Serif',
         'settings.fontSansSerif': 'Sans-serif',
         'settings.fontMono': 'Monospace',
+        'settings.fontCustom': 'Carattere personalizzato',
+        'settings.customFontName': 'Nome di un carattere installato',
         'settings.fontSize': 'Dimensione carattere',
         'settings.contentWidth': 'Larghezza contenuto',
+        'settings.zoomStep': 'Passo zoom (%)',
+        'settings.lineHeight': 'Altezza riga',
+        'settings.paragraphSpacing': 'Spaziatura paragrafi',
+        'settings.justifyText': 'Giustifica testo',
+        'settings.decorations': 'Icone davanti ai link',
+        'settings.decorationsEmoji': 'Emoji',
+        'settings.decorationsText': 'Testo ([DIR])',
+        'settings.decorationsNone': 'Nessuna',
+        'settings.plainText': 'Testo semplice',
+        'settings.plainTextPreformatted': 'Larghezza fissa',
+        'settings.plainTextParagraphs': 'Paragrafi',
+        'settings.showHiddenFiles': 'Mostra file nascosti nelle cartelle',
+        'settings.followSymlinks': 'Segui i collegamenti simbolici',
+        'settings.maxFileMb': 'Dimensione max file locale (MB)',
+        'settings.maxRenderMb': 'Dimensione max documento mostrato (MB)',
+        'settings.logLevel': 'Registro',
+        'settings.logLevelError': 'Solo errori',
+        'settings.logLevelWarn': 'Errori e avvisi',
+        'settings.logLevelInfo': 'Normale',
+        'settings.logLevelDebug': 'Dettagliato',
+        'settings.logLevelTrace': 'Tutto',
+        'settings.customCss': 'CSS personalizzato',
+        'settings.homepage': 'Pagina iniziale',
+        'settings.searchEngine': 'Motore di ricerca',
+        'settings.defaultScheme': 'Indirizzi senza protocollo',
+        'settings.defaultSchemeAsk': 'Chiedi ogni volta',
+        'settings.downloadsDir': 'Cartella download',
+        'settings.downloadsWithoutAsking': 'Salva senza chiedere',
+        'schemeChoice.title': 'Scegli protocollo',
+        'schemeChoice.remember': 'Ricorda per questo sito',
+        'schemeChoice.prompt': 'Come vuoi aprire {address}?',
         'settings.language': 'Lingua',
         'settings.languageSystem': 'Sistema',
         'settings.htmlConversion': 'Conversione HTML',
@@ -1036,6 +2191,47 @@ const TRANSLATIONS = {
         'settings.markdownOnly': 'Solo markdown',
         'settings.askEverytime': 'Chiedi ogni volta',
         'settings.readability': 'Modalità leggibilità (estrai contenuto principale)',
+        'settings.network': 'Rete',
+        'settings.timeoutSeconds': 'Timeout (secondi)',
+        'settings.maxResponseMb': 'Dimensione max (MB)',
+        'settings.maxRedirects': 'Reindirizzamenti max',
+        'settings.privacy': 'Privacy',
+        'settings.loadRemoteImages': 'Carica immagini remote',
+        'settings.stripQueryParams': 'Rimuovi i parametri di tracciamento dai link',
+        'settings.sendReferrer': 'Invia la pagina di provenienza (Referer)',
+        'settings.sendGpc': 'Chiedi di non essere tracciato (GPC/DNT)',
+        'settings.prefetch': 'Carica i link al passaggio del mouse',
+        'settings.historyEnabled': 'Ricorda le pagine visitate',
+        'settings.privateMode': 'Navigazione privata (nulla viene scritto su disco)',
+        'status.privateModeOn': 'Navigazione privata attiva',
+        'status.privateModeOff': 'Navigazione privata disattivata',
+        'status.openWindowError': 'Impossibile aprire una nuova finestra',
+        'status.exported': 'Salvato {path}',
+        'status.exportError': 'Impossibile esportare la pagina',
+        'status.saveSourceError': 'Impossibile salvare il sorgente',
+        'status.copied': 'Copiato negli appunti',
+        'status.copyError': 'Impossibile copiare',
+        'status.linkCopied': '{url} copiato',
+        'prompt.download': 'Bare non può mostrare {name}. Scaricare il file?',
+        'status.notDisplayable': '{name} non può essere visualizzato in Bare',
+        'status.downloadStarted': 'Download di {name} avviato',
+        'status.downloading': 'Download di {name}: {progress}',
+        'status.downloaded': 'Scaricato {path}',
+        'status.downloadError': 'Download non riuscito',
+        'status.linkOutsideRoot': 'Il link punta fuori dalla cartella aperta',
+        'status.exportNoPage': 'Apri una pagina prima di esportare',
+        'status.didYouMean': 'Forse cercavi {url}?',
+        'settings.reset': 'Ripristina impostazioni',
+        'settings.resetAppearance': 'Aspetto',
+        'settings.resetNetwork': 'Rete',
+        'settings.resetPrivacy': 'Privacy',
+        'settings.resetSiteOverrides': 'Impostazioni per sito',
+        'settings.resetAll': 'Tutte le impostazioni',
+        'settings.resetButton': 'Ripristina',
+        'settings.resetConfirm': 'Ripristinare le impostazioni selezionate?',
+        'settings.clearBrowsingData': 'Cancella dati di navigazione',
+        'settings.clearBrowsingDataConfirm': 'Eliminare cronologia, indietro e avanti nelle finestre, pagine in cache, host Gemini noti, file recenti e impostazioni per sito? Segnalibri e altre impostazioni vengono mantenuti.',
+        'status.browsingDataCleared': 'Dati di navigazione cancellati',
         'about.title': 'Informazioni su Bare',
         'about.description': 'Un browser markdown sperimentale focalizzato su privacy, velocità e contenuti puliti.',
         'about.feature1': '> Rendering markdown pulito',
@@ -1065,15 +2261,48 @@ const TRANSLATIONS = {
         'input.searchPrompt': 'Inserisci termine di ricerca:',
         'footer.ready': 'Pronto',
         'footer.connecting': 'Connessione...',
+        'progress.resolving': 'Risoluzione di {host}...',
+        'progress.connecting': 'Connessione a {host}...',
+        'progress.tls_handshake': 'Handshake TLS con {host}...',
+        'progress.transferring': 'Trasferimento dati... ({bytes} byte)',
+        'progress.converting': 'Conversione...',
+        'progress.rendering': 'Rendering...',
+        'progress.done': 'Documento: completato',
+        'progress.error': 'Errore durante il caricamento',
         'footer.loadingHome': 'Caricamento pagina iniziale...',
         'footer.reloading': 'Ricaricamento...',
         'footer.converted': 'convertito',
+        'footer.readerMode': 'modalità lettura',
         'loading.text': '[ CARICAMENTO... ]',
         'error.title': '⚠️ Errore',
         'error.goHome': 'Vai alla pagina iniziale',
         'status.settingsError': 'Impossibile aggiornare l\'impostazione',
+        'status.settingsAdjusted': 'Impostazione modificata',
+        'settings.warning.clamped': 'adattato',
+        'settings.warning.ignored': 'ignorato',
+        'settings.warning.invalid': 'non valido',
         'status.loadSettingsError': 'Impossibile caricare le impostazioni',
         'status.conversionCancelled': 'Conversione annullata dall\'utente',
+        'status.redirectCancelled': 'Reindirizzamento annullato',
+        'status.identityCancelled': 'Nessuna identità è stata creata, quindi la pagina non può essere mostrata',
+        'status.navigationCancelled': 'Navigazione annullata',
+        'toolbar.security': 'Sicurezza della pagina',
+        'security.title': 'Connessione',
+        'security.protocol': 'Protocollo',
+        'security.encryption': 'Crittografia',
+        'security.encrypted': 'Crittografata',
+        'security.notEncrypted': 'Non crittografata',
+        'security.tlsVersion': 'Versione TLS',
+        'security.cipher': 'Suite di cifratura',
+        'security.certificate': 'Certificato',
+        'security.cert.ca_validated': 'Emesso da una autorità di certificazione nota',
+        'security.cert.pinned': 'Lo stesso della prima visita',
+        'security.cert.none': 'Nessuno',
+        'security.fingerprint': 'Impronta (SHA-256)',
+        'security.downgraded': 'Un reindirizzamento ha portato da un indirizzo crittografato a uno non crittografato',
+        'security.local': 'Pagina locale, nessuna connessione',
+        'security.unknown': 'Sconosciuto',
+        'conversion.alwaysForSite': 'Convertire sempre le pagine di {host}?',
         'status.zoomInError': 'Impossibile ingrandire',
         'status.zoomOutError': 'Impossibile rimpicciolire',
         'status.zoomResetError': 'Impossibile reimpostare lo zoom',
@@ -1082,6 +2311,7 @@ const TRANSLATIONS = {
         'status.urlResolveError': 'Impossibile risolvere l\'URL',
         'status.noBaseUrl': 'Impossibile navigare verso un link relativo senza un URL di base',
         'status.openFileError': 'Impossibile aprire il file',
+        'dialog.supportedFiles': 'Documenti',
         'shortcut.back': 'Alt+←',
         'shortcut.forward': 'Alt+→',
         'shortcut.home': 'G',
@@ -1110,6 +2340,14 @@ const TRANSLATIONS = {
         'menu.toggleTheme': 'Mudar tema',
         'menu.settings': 'Definições...',
         'menu.about': 'Sobre o Bare...',
+        'menu.exportHtml': 'Exportar como HTML...',
+        'menu.exportPdf': 'Exportar como PDF...',
+        'menu.saveSource': 'Guardar fonte...',
+        'menu.copyMarkdown': 'Copiar como markdown',
+        'menu.copyText': 'Copiar como texto',
+        'menu.copyLink': 'Copiar link',
+        'menu.recentFiles': 'Ficheiros recentes',
+        'menu.clearRecentFiles': 'Limpar lista',
         'search.placeholder': 'Pesquisar na página...',
         'search.prev': 'Resultado anterior',
         'search.next': 'Próximo resultado',
@@ -1125,13 +2363,53 @@ const TRANSLATIONS = {
         'settings.themeLight': 'Claro',
         'settings.themeDark': 'Escuro',
         'settings.themeSystem': 'Sistema',
+        'settings.themeSepia': 'Sépia',
+        'settings.colors': 'Cores personalizadas',
+        'settings.colorBackground': 'Fundo',
+        'settings.colorText': 'Texto',
+        'settings.colorLink': 'Ligações',
+        'settings.colorAccent': 'Destaque',
+        'settings.resetColors': 'Repor cores',
         'settings.fontFamily': 'Tipo de letra',
         'settings.fontSystem': 'Sistema',
         'settings.fontSerif': 'Serif',
         'settings.fontSansSerif': 'Sans-serif',
         'settings.fontMono': 'Monospace',
+        'settings.fontCustom': 'Fonte personalizada',
+        'settings.customFontName': 'Nome de uma fonte instalada',
         'settings.fontSize': 'Tamanho da letra',
         'settings.contentWidth': 'Largura do conteúdo',
+        'settings.zoomStep': 'Passo de zoom (%)',
+        'settings.lineHeight': 'Altura da linha',
+        'settings.paragraphSpacing': 'Espaçamento entre parágrafos',
+        'settings.justifyText': 'Justificar texto',
+        'settings.decorations': 'Ícones antes das ligações',
+        'settings.decorationsEmoji': 'Emoji',
+        'settings.decorationsText': 'Texto ([DIR])',
+        'settings.decorationsNone': 'Nenhum',
+        'settings.plainText': 'Texto simples',
+        'settings.plainTextPreformatted': 'Largura fixa',
+        'settings.plainTextParagraphs': 'Parágrafos',
+        'settings.showHiddenFiles': 'Mostrar ficheiros ocultos nas pastas',
+        'settings.followSymlinks': 'Seguir ligações simbólicas',
+        'settings.maxFileMb': 'Tamanho máx. de ficheiro local (MB)',
+        'settings.maxRenderMb': 'Tamanho máx. de documento mostrado (MB)',
+        'settings.logLevel': 'Registo',
+        'settings.logLevelError': 'Apenas erros',
+        'settings.logLevelWarn': 'Erros e avisos',
+        'settings.logLevelInfo': 'Normal',
+        'settings.logLevelDebug': 'Detalhado',
+        'settings.logLevelTrace': 'Tudo',
+        'settings.customCss': 'CSS personalizado',
+        'settings.homepage': 'Página inicial',
+        'settings.searchEngine': 'Motor de pesquisa',
+        'settings.defaultScheme': 'Endereços sem protocolo',
+        'settings.defaultSchemeAsk': 'Perguntar sempre',
+        'settings.downloadsDir': 'Pasta de transferências',
+        'settings.downloadsWithoutAsking': 'Guardar sem perguntar',
+        'schemeChoice.title': 'Escolher protocolo',
+        'schemeChoice.remember': 'Lembrar para este site',
+        'schemeChoice.prompt': 'Como quer abrir {address}?',
         'settings.language': 'Idioma',
         'settings.languageSystem': 'Sistema',
         'settings.htmlConversion': 'Conversão HTML',
@@ -1140,6 +2418,47 @@ const TRANSLATIONS = {
         'settings.markdownOnly': 'Apenas markdown',
         'settings.askEverytime': 'Perguntar sempre',
         'settings.readability': 'Modo legibilidade (extrair conteúdo principal)',
+        'settings.network': 'Rede',
+        'settings.timeoutSeconds': 'Tempo limite (segundos)',
+        'settings.maxResponseMb': 'Tamanho máx. (MB)',
+        'settings.maxRedirects': 'Redirecionamentos máx.',
+        'settings.privacy': 'Privacidade',
+        'settings.loadRemoteImages': 'Carregar imagens remotas',
+        'settings.stripQueryParams': 'Remover parâmetros de rastreamento dos links',
+        'settings.sendReferrer': 'Enviar a página de origem (Referer)',
+        'settings.sendGpc': 'Pedir para não ser rastreado (GPC/DNT)',
+        'settings.prefetch': 'Carregar links ao passar o rato',
+        'settings.historyEnabled': 'Lembrar páginas visitadas',
+        'settings.privateMode': 'Navegação privada (nada é gravado no disco)',
+        'status.privateModeOn': 'Navegação privada ativada',
+        'status.privateModeOff': 'Navegação privada desativada',
+        'status.openWindowError': 'Não foi possível abrir uma nova janela',
+        'status.exported': 'Guardado {path}',
+        'status.exportError': 'Não foi possível exportar a página',
+        'status.saveSourceError': 'Não foi possível guardar a fonte',
+        'status.copied': 'Copiado para a área de transferência',
+        'status.copyError': 'Não foi possível copiar',
+        'status.linkCopied': '{url} copiado',
+        'prompt.download': 'O Bare não pode apresentar {name}. Transferir o ficheiro?',
+        'status.notDisplayable': '{name} não pode ser apresentado no Bare',
+        'status.downloadStarted': 'A iniciar a transferência de {name}',
+        'status.downloading': 'A transferir {name}: {progress}',
+        'status.downloaded': 'Transferido {path}',
+        'status.downloadError': 'A transferência falhou',
+        'status.linkOutsideRoot': 'A ligação aponta para fora da pasta aberta',
+        'status.exportNoPage': 'Abra uma página antes de exportar',
+        'status.didYouMean': 'Você quis dizer {url}?',
+        'settings.reset': 'Repor definições',
+        'settings.resetAppearance': 'Aparência',
+        'settings.resetNetwork': 'Rede',
+        'settings.resetPrivacy': 'Privacidade',
+        'settings.resetSiteOverrides': 'Definições por site',
+        'settings.resetAll': 'Todas as definições',
+        'settings.resetButton': 'Repor',
+        'settings.resetConfirm': 'Repor as definições selecionadas?',
+        'settings.clearBrowsingData': 'Limpar dados de navegação',
+        'settings.clearBrowsingDataConfirm': 'Eliminar histórico, retroceder e avançar nas janelas, páginas em cache, anfitriões Gemini conhecidos, ficheiros recentes e definições por site? Os marcadores e outras definições são mantidos.',
+        'status.browsingDataCleared': 'Dados de navegação limpos',
         'about.title': 'Sobre o Bare',
         'about.description': 'Um navegador markdown experimental focado na privacidade, velocidade e conteúdo limpo.',
         'about.feature1': '> Renderização markdown limpa',
@@ -1169,15 +2488,48 @@ const TRANSLATIONS = {
         'input.searchPrompt': 'Introduzir termo de pesquisa:',
         'footer.ready': 'Pronto',
         'footer.connecting': 'A ligar...',
+        'progress.resolving': 'Resolvendo {host}...',
+        'progress.connecting': 'Conectando a {host}...',
+        'progress.tls_handshake': 'Handshake TLS com {host}...',
+        'progress.transferring': 'Transferindo dados... ({bytes} bytes)',
+        'progress.converting': 'Convertendo...',
+        'progress.rendering': 'Renderizando...',
+        'progress.done': 'Documento: concluído',
+        'progress.error': 'Erro ao carregar',
         'footer.loadingHome': 'A carregar página inicial...',
         'footer.reloading': 'A recarregar...',
         'footer.converted': 'convertido',
+        'footer.readerMode': 'modo leitura',
         'loading.text': '[ A CARREGAR... ]',
         'error.title': '⚠️ Erro',
         'error.goHome': 'Ir para a página inicial',
         'status.settingsError': 'Não foi possível atualizar a definição',
+        'status.settingsAdjusted': 'A definição foi alterada',
+        'settings.warning.clamped': 'ajustado',
+        'settings.warning.ignored': 'ignorado',
+        'settings.warning.invalid': 'inválido',
         'status.loadSettingsError': 'Não foi possível carregar as definições',
         'status.conversionCancelled': 'Conversão cancelada pelo utilizador',
+        'status.redirectCancelled': 'Redirecionamento cancelado',
+        'status.identityCancelled': 'Nenhuma identidade foi criada, por isso a página não pode ser mostrada',
+        'status.navigationCancelled': 'Navegação cancelada',
+        'toolbar.security': 'Segurança da página',
+        'security.title': 'Ligação',
+        'security.protocol': 'Protocolo',
+        'security.encryption': 'Encriptação',
+        'security.encrypted': 'Encriptada',
+        'security.notEncrypted': 'Não encriptada',
+        'security.tlsVersion': 'Versão TLS',
+        'security.cipher': 'Conjunto de cifras',
+        'security.certificate': 'Certificado',
+        'security.cert.ca_validated': 'Emitido por uma autoridade de certificação conhecida',
+        'security.cert.pinned': 'O mesmo da primeira visita',
+        'security.cert.none': 'Nenhum',
+        'security.fingerprint': 'Impressão digital (SHA-256)',
+        'security.downgraded': 'Um redirecionamento levou de um endereço encriptado para um não encriptado',
+        'security.local': 'Página local, sem ligação',
+        'security.unknown': 'Desconhecido',
+        'conversion.alwaysForSite': 'Converter sempre as páginas de {host}?',
         'status.zoomInError': 'Não foi possível aumentar o zoom',
         'status.zoomOutError': 'Não foi possível diminuir o zoom',
         'status.zoomResetError': 'Não foi possível repor o zoom',
@@ -1186,6 +2538,7 @@ const TRANSLATIONS = {
         'status.urlResolveError': 'Não foi possível resolver o URL',
         'status.noBaseUrl': 'Não é possível navegar para link relativo sem um URL base',
         'status.openFileError': 'Não foi possível abrir o ficheiro',
+        'dialog.supportedFiles': 'Documentos',
         'shortcut.back': 'Alt+←',
         'shortcut.forward': 'Alt+→',
         'shortcut.home': 'G',
@@ -1214,6 +2567,14 @@ const TRANSLATIONS = {
         'menu.toggleTheme': 'Thema wisselen',
         'menu.settings': 'Instellingen...',
         'menu.about': 'Over Bare...',
+        'menu.exportHtml': 'Exporteren als HTML...',
+        'menu.exportPdf': 'Exporteren als PDF...',
+        'menu.saveSource': 'Bron opslaan...',
+        'menu.copyMarkdown': 'Kopiëren als markdown',
+        'menu.copyText': 'Kopiëren als tekst',
+        'menu.copyLink': 'Link kopiëren',
+        'menu.recentFiles': 'Recente bestanden',
+        'menu.clearRecentFiles': 'Lijst wissen',
         'search.placeholder': 'Zoeken op pagina...',
         'search.prev': 'Vorige overeenkomst',
         'search.next': 'Volgende overeenkomst',
@@ -1229,13 +2590,53 @@ const TRANSLATIONS = {
         'settings.themeLight': 'Licht',
         'settings.themeDark': 'Donker',
         'settings.themeSystem': 'Systeem',
+        'settings.themeSepia': 'Sepia',
+        'settings.colors': 'Eigen kleuren',
+        'settings.colorBackground': 'Achtergrond',
+        'settings.colorText': 'Tekst',
+        'settings.colorLink': 'Links',
+        'settings.colorAccent': 'Accent',
+        'settings.resetColors': 'Kleuren herstellen',
         'settings.fontFamily': 'Lettertype',
         'settings.fontSystem': 'Systeem',
         'settings.fontSerif': 'Serif',
         'settings.fontSansSerif': 'Sans-serif',
         'settings.fontMono': 'Monospace',
+        'settings.fontCustom': 'Eigen lettertype',
+        'settings.customFontName': 'Naam van een geïnstalleerd lettertype',
         'settings.fontSize': 'Lettergrootte',
         'settings.contentWidth': 'Inhoudsbreedte',
+        'settings.zoomStep': 'Zoomstap (%)',
+        'settings.lineHeight': 'Regelhoogte',
+        'settings.paragraphSpacing': 'Alinea-afstand',
+        'settings.justifyText': 'Tekst uitvullen',
+        'settings.decorations': 'Pictogrammen voor links',
+        'settings.decorationsEmoji': 'Emoji',
+        'settings.decorationsText': 'Tekst ([DIR])',
+        'settings.decorationsNone': 'Geen',
+        'settings.plainText': 'Platte tekst',
+        'settings.plainTextPreformatted': 'Vaste breedte',
+        'settings.plainTextParagraphs': 'Alinea\'s',
+        'settings.showHiddenFiles': 'Verborgen bestanden in mappen tonen',
+        'settings.followSymlinks': 'Symbolische koppelingen volgen',
+        'settings.maxFileMb': 'Grootste lokale bestand (MB)',
+        'settings.maxRenderMb': 'Grootste getoonde document (MB)',
+        'settings.logLevel': 'Logboek',
+        'settings.logLevelError': 'Alleen fouten',
+        'settings.logLevelWarn': 'Fouten en waarschuwingen',
+        'settings.logLevelInfo': 'Normaal',
+        'settings.logLevelDebug': 'Gedetailleerd',
+        'settings.logLevelTrace': 'Alles',
+        'settings.customCss': 'Eigen CSS',
+        'settings.homepage': 'Startpagina',
+        'settings.searchEngine': 'Zoekmachine',
+        'settings.defaultScheme': 'Adressen zonder protocol',
+        'settings.defaultSchemeAsk': 'Elke keer vragen',
+        'settings.downloadsDir': 'Downloadmap',
+        'settings.downloadsWithoutAsking': 'Opslaan zonder te vragen',
+        'schemeChoice.title': 'Protocol kiezen',
+        'schemeChoice.remember': 'Onthouden voor deze site',
+        'schemeChoice.prompt': 'Hoe wil je {address} openen?',
         'settings.language': 'Taal',
         'settings.languageSystem': 'Systeem',
         'settings.htmlConversion': 'HTML-conversie',
@@ -1244,6 +2645,47 @@ const TRANSLATIONS = {
         'settings.markdownOnly': 'Alleen markdown',
         'settings.askEverytime': 'Elke keer vragen',
         'settings.readability': 'Leesbaarheidsmodus (hoofdinhoud extraheren)',
+        'settings.network': 'Netwerk',
+        'settings.timeoutSeconds': 'Time-out (seconden)',
+        'settings.maxResponseMb': 'Max. grootte (MB)',
+        'settings.maxRedirects': 'Max. omleidingen',
+        'settings.privacy': 'Privacy',
+        'settings.loadRemoteImages': 'Externe afbeeldingen laden',
+        'settings.stripQueryParams': 'Trackingparameters uit links verwijderen',
+        'settings.sendReferrer': 'Verwijzende pagina meesturen (Referer)',
+        'settings.sendGpc': 'Vragen om niet gevolgd te worden (GPC/DNT)',
+        'settings.prefetch': 'Links laden bij aanwijzen met de muis',
+        'settings.historyEnabled': 'Bezochte pagina\'s onthouden',
+        'settings.privateMode': 'Privé browsen (schrijft niets naar schijf)',
+        'status.privateModeOn': 'Privé browsen staat aan',
+        'status.privateModeOff': 'Privé browsen staat uit',
+        'status.openWindowError': 'Kon geen nieuw venster openen',
+        'status.exported': '{path} opgeslagen',
+        'status.exportError': 'Kon pagina niet exporteren',
+        'status.saveSourceError': 'Kan de bron niet opslaan',
+        'status.copied': 'Gekopieerd naar het klembord',
+        'status.copyError': 'Kopiëren mislukt',
+        'status.linkCopied': '{url} gekopieerd',
+        'prompt.download': 'Bare kan {name} niet weergeven. Het bestand downloaden?',
+        'status.notDisplayable': '{name} kan niet in Bare worden weergegeven',
+        'status.downloadStarted': 'Download van {name} gestart',
+        'status.downloading': '{name} downloaden: {progress}',
+        'status.downloaded': 'Gedownload: {path}',
+        'status.downloadError': 'Download mislukt',
+        'status.linkOutsideRoot': 'De link verwijst buiten de geopende map',
+        'status.exportNoPage': 'Open een pagina voordat u exporteert',
+        'status.didYouMean': 'Bedoelde je {url}?',
+        'settings.reset': 'Instellingen herstellen',
+        'settings.resetAppearance': 'Weergave',
+        'settings.resetNetwork': 'Netwerk',
+        'settings.resetPrivacy': 'Privacy',
+        'settings.resetSiteOverrides': 'Instellingen per website',
+        'settings.resetAll': 'Alle instellingen',
+        'settings.resetButton': 'Herstellen',
+        'settings.resetConfirm': 'Geselecteerde instellingen herstellen naar standaard?',
+        'settings.clearBrowsingData': 'Browsegegevens wissen',
+        'settings.clearBrowsingDataConfirm': 'Geschiedenis, terug en vooruit in de vensters, gecachte pagina\'s, bekende Gemini-hosts, recente bestanden en instellingen per website verwijderen? Bladwijzers en andere instellingen blijven behouden.',
+        'status.browsingDataCleared': 'Browsegegevens gewist',
         'about.title': 'Over Bare',
         'about.description': 'Een experimentele markdown-browser gericht op privacy, snelheid en schone inhoud.',
         'about.feature1': '> Schone markdown-weergave',
@@ -1273,15 +2715,48 @@ const TRANSLATIONS = {
         'input.searchPrompt': 'Zoekterm invoeren:',
         'footer.ready': 'Gereed',
         'footer.connecting': 'Verbinden...',
+        'progress.resolving': '{host} opzoeken...',
+        'progress.connecting': 'Verbinden met {host}...',
+        'progress.tls_handshake': 'TLS-handshake met {host}...',
+        'progress.transferring': 'Gegevens overdragen... ({bytes} bytes)',
+        'progress.converting': 'Converteren...',
+        'progress.rendering': 'Renderen...',
+        'progress.done': 'Document: klaar',
+        'progress.error': 'Fout bij laden',
         'footer.loadingHome': 'Startpagina laden...',
         'footer.reloading': 'Herladen...',
         'footer.converted': 'geconverteerd',
+        'footer.readerMode': 'leesmodus',
         'loading.text': '[ LADEN... ]',
         'error.title': '⚠️ Fout',
         'error.goHome': 'Naar startpagina',
         'status.settingsError': 'Kon instelling niet bijwerken',
+        'status.settingsAdjusted': 'Instelling is aangepast',
+        'settings.warning.clamped': 'begrensd',
+        'settings.warning.ignored': 'genegeerd',
+        'settings.warning.invalid': 'ongeldig',
         'status.loadSettingsError': 'Kon instellingen niet laden',
         'status.conversionCancelled': 'Conversie geannuleerd door gebruiker',
+        'status.redirectCancelled': 'Doorverwijzing geannuleerd',
+        'status.identityCancelled': 'Er is geen identiteit aangemaakt, dus de pagina kan niet worden getoond',
+        'status.navigationCancelled': 'Navigatie geannuleerd',
+        'toolbar.security': 'Paginabeveiliging',
+        'security.title': 'Verbinding',
+        'security.protocol': 'Protocol',
+        'security.encryption': 'Versleuteling',
+        'security.encrypted': 'Versleuteld',
+        'security.notEncrypted': 'Niet versleuteld',
+        'security.tlsVersion': 'TLS-versie',
+        'security.cipher': 'Cipher suite',
+        'security.certificate': 'Certificaat',
+        'security.cert.ca_validated': 'Uitgegeven door een bekende certificaatautoriteit',
+        'security.cert.pinned': 'Hetzelfde als bij het eerste bezoek',
+        'security.cert.none': 'Geen',
+        'security.fingerprint': 'Vingerafdruk (SHA-256)',
+        'security.downgraded': 'Een doorverwijzing leidde van een versleuteld naar een onversleuteld adres',
+        'security.local': 'Lokale pagina, geen verbinding',
+        'security.unknown': 'Onbekend',
+        'conversion.alwaysForSite': 'Pagina\'s van {host} altijd omzetten?',
         'status.zoomInError': 'Kan niet inzoomen',
         'status.zoomOutError': 'Kan niet uitzoomen',
         'status.zoomResetError': 'Kan zoom niet herstellen',
@@ -1290,6 +2765,7 @@ const TRANSLATIONS = {
         'status.urlResolveError': 'Kon URL niet oplossen',
         'status.noBaseUrl': 'Kan niet navigeren naar relatieve link zonder basis-URL',
         'status.openFileError': 'Kon bestand niet openen',
+        'dialog.supportedFiles': 'Documenten',
         'shortcut.back': 'Alt+←',
         'shortcut.forward': 'Alt+→',
         'shortcut.home': 'G',
@@ -1318,6 +2794,14 @@ const TRANSLATIONS = {
         'menu.toggleTheme': 'Zmień motyw',
         'menu.settings': 'Ustawienia...',
         'menu.about': 'O programie Bare...',
+        'menu.exportHtml': 'Eksportuj jako HTML...',
+        'menu.exportPdf': 'Eksportuj jako PDF...',
+        'menu.saveSource': 'Zapisz źródło...',
+        'menu.copyMarkdown': 'Kopiuj jako markdown',
+        'menu.copyText': 'Kopiuj jako tekst',
+        'menu.copyLink': 'Kopiuj link',
+        'menu.recentFiles': 'Ostatnie pliki',
+        'menu.clearRecentFiles': 'Wyczyść listę',
         'search.placeholder': 'Szukaj na stronie...',
         'search.prev': 'Poprzedni wynik',
         'search.next': 'Następny wynik',
@@ -1333,13 +2817,53 @@ const TRANSLATIONS = {
         'settings.themeLight': 'Jasny',
         'settings.themeDark': 'Ciemny',
         'settings.themeSystem': 'Systemowy',
+        'settings.themeSepia': 'Sepia',
+        'settings.colors': 'Własne kolory',
+        'settings.colorBackground': 'Tło',
+        'settings.colorText': 'Tekst',
+        'settings.colorLink': 'Linki',
+        'settings.colorAccent': 'Akcent',
+        'settings.resetColors': 'Przywróć kolory',
         'settings.fontFamily': 'Czcionka',
         'settings.fontSystem': 'Systemowa',
         'settings.fontSerif': 'Serif',
         'settings.fontSansSerif': 'Sans-serif',
         'settings.fontMono': 'Monospace',
+        'settings.fontCustom': 'Własna czcionka',
+        'settings.customFontName': 'Nazwa zainstalowanej czcionki',
         'settings.fontSize': 'Rozmiar czcionki',
         'settings.contentWidth': 'Szerokość treści',
+        'settings.zoomStep': 'Krok powiększenia (%)',
+        'settings.lineHeight': 'Wysokość linii',
+        'settings.paragraphSpacing': 'Odstęp między akapitami',
+        'settings.justifyText': 'Wyjustuj tekst',
+        'settings.decorations': 'Ikony przed linkami',
+        'settings.decorationsEmoji': 'Emoji',
+        'settings.decorationsText': 'Tekst ([DIR])',
+        'settings.decorationsNone': 'Brak',
+        'settings.plainText': 'Zwykły tekst',
+        'settings.plainTextPreformatted': 'Stała szerokość',
+        'settings.plainTextParagraphs': 'Akapity',
+        'settings.showHiddenFiles': 'Pokaż ukryte pliki w folderach',
+        'settings.followSymlinks': 'Podążaj za dowiązaniami symbolicznymi',
+        'settings.maxFileMb': 'Największy plik lokalny (MB)',
+        'settings.maxRenderMb': 'Największy wyświetlany dokument (MB)',
+        'settings.logLevel': 'Logowanie',
+        'settings.logLevelError': 'Tylko błędy',
+        'settings.logLevelWarn': 'Błędy i ostrzeżenia',
+        'settings.logLevelInfo': 'Normalne',
+        'settings.logLevelDebug': 'Szczegółowe',
+        'settings.logLevelTrace': 'Wszystko',
+        'settings.customCss': 'Własny CSS',
+        'settings.homepage': 'Strona startowa',
+        'settings.searchEngine': 'Wyszukiwarka',
+        'settings.defaultScheme': 'Adresy bez protokołu',
+        'settings.defaultSchemeAsk': 'Pytaj za każdym razem',
+        'settings.downloadsDir': 'Folder pobierania',
+        'settings.downloadsWithoutAsking': 'Zapisuj bez pytania',
+        'schemeChoice.title': 'Wybierz protokół',
+        'schemeChoice.remember': 'Zapamiętaj dla tej witryny',
+        'schemeChoice.prompt': 'Jak chcesz otworzyć {address}?',
         'settings.language': 'Język',
         'settings.languageSystem': 'Systemowy',
         'settings.htmlConversion': 'Konwersja HTML',
@@ -1348,6 +2872,47 @@ const TRANSLATIONS = {
         'settings.markdownOnly': 'Tylko markdown',
         'settings.askEverytime': 'Pytaj za każdym razem',
         'settings.readability': 'Tryb czytelności (wyodrębnij główną treść)',
+        'settings.network': 'Sieć',
+        'settings.timeoutSeconds': 'Limit czasu (sekundy)',
+        'settings.maxResponseMb': 'Maks. rozmiar (MB)',
+        'settings.maxRedirects': 'Maks. przekierowań',
+        'settings.privacy': 'Prywatność',
+        'settings.loadRemoteImages': 'Wczytuj zdalne obrazy',
+        'settings.stripQueryParams': 'Usuwaj parametry śledzące z linków',
+        'settings.sendReferrer': 'Wysyłaj stronę odsyłającą (Referer)',
+        'settings.sendGpc': 'Proś o brak śledzenia (GPC/DNT)',
+        'settings.prefetch': 'Wczytuj linki po najechaniu myszą',
+        'settings.historyEnabled': 'Zapamiętuj odwiedzone strony',
+        'settings.privateMode': 'Przeglądanie prywatne (nic nie jest zapisywane na dysku)',
+        'status.privateModeOn': 'Przeglądanie prywatne włączone',
+        'status.privateModeOff': 'Przeglądanie prywatne wyłączone',
+        'status.openWindowError': 'Nie udało się otworzyć nowego okna',
+        'status.exported': 'Zapisano {path}',
+        'status.exportError': 'Nie udało się wyeksportować strony',
+        'status.saveSourceError': 'Nie można zapisać źródła',
+        'status.copied': 'Skopiowano do schowka',
+        'status.copyError': 'Nie udało się skopiować',
+        'status.linkCopied': 'Skopiowano {url}',
+        'prompt.download': 'Bare nie może wyświetlić {name}. Pobrać plik?',
+        'status.notDisplayable': 'Nie można wyświetlić {name} w Bare',
+        'status.downloadStarted': 'Rozpoczęto pobieranie {name}',
+        'status.downloading': 'Pobieranie {name}: {progress}',
+        'status.downloaded': 'Pobrano {path}',
+        'status.downloadError': 'Pobieranie nie powiodło się',
+        'status.linkOutsideRoot': 'Link wskazuje poza otwarty folder',
+        'status.exportNoPage': 'Otwórz stronę przed eksportem',
+        'status.didYouMean': 'Czy chodziło ci o {url}?',
+        'settings.reset': 'Przywróć ustawienia',
+        'settings.resetAppearance': 'Wygląd',
+        'settings.resetNetwork': 'Sieć',
+        'settings.resetPrivacy': 'Prywatność',
+        'settings.resetSiteOverrides': 'Ustawienia dla witryn',
+        'settings.resetAll': 'Wszystkie ustawienia',
+        'settings.resetButton': 'Przywróć',
+        'settings.resetConfirm': 'Przywrócić domyślne wybrane ustawienia?',
+        'settings.clearBrowsingData': 'Wyczyść dane przeglądania',
+        'settings.clearBrowsingDataConfirm': 'Usunąć historię, wstecz i dalej w oknach, strony w pamięci podręcznej, znane hosty Gemini, ostatnie pliki i ustawienia witryn? Zakładki i inne ustawienia zostaną zachowane.',
+        'status.browsingDataCleared': 'Dane przeglądania wyczyszczone',
         'about.title': 'O programie Bare',
         'about.description': 'Eksperymentalna przeglądarka markdown skupiona na prywatności, szybkości i czystej treści.',
         'about.feature1': '> Czyste renderowanie markdown',
@@ -1377,15 +2942,48 @@ const TRANSLATIONS = {
         'input.searchPrompt': 'Wprowadź szukaną frazę:',
         'footer.ready': 'Gotowe',
         'footer.connecting': 'Łączenie...',
+        'progress.resolving': 'Wyszukiwanie {host}...',
+        'progress.connecting': 'Łączenie z {host}...',
+        'progress.tls_handshake': 'Uzgadnianie TLS z {host}...',
+        'progress.transferring': 'Przesyłanie danych... ({bytes} bajtów)',
+        'progress.converting': 'Konwertowanie...',
+        'progress.rendering': 'Renderowanie...',
+        'progress.done': 'Dokument: gotowe',
+        'progress.error': 'Błąd ładowania',
         'footer.loadingHome': 'Ładowanie strony głównej...',
         'footer.reloading': 'Odświeżanie...',
         'footer.converted': 'przekonwertowano',
+        'footer.readerMode': 'tryb czytania',
         'loading.text': '[ ŁADOWANIE... ]',
         'error.title': '⚠️ Błąd',
         'error.goHome': 'Przejdź do strony głównej',
         'status.settingsError': 'Nie udało się zaktualizować ustawienia',
+        'status.settingsAdjusted': 'Ustawienie zostało zmienione',
+        'settings.warning.clamped': 'dopasowano',
+        'settings.warning.ignored': 'zignorowano',
+        'settings.warning.invalid': 'nieprawidłowe',
         'status.loadSettingsError': 'Nie udało się załadować ustawień',
         'status.conversionCancelled': 'Konwersja anulowana przez użytkownika',
+        'status.redirectCancelled': 'Przekierowanie anulowane',
+        'status.identityCancelled': 'Nie utworzono tożsamości, więc strony nie można wyświetlić',
+        'status.navigationCancelled': 'Nawigacja anulowana',
+        'toolbar.security': 'Bezpieczeństwo strony',
+        'security.title': 'Połączenie',
+        'security.protocol': 'Protokół',
+        'security.encryption': 'Szyfrowanie',
+        'security.encrypted': 'Szyfrowane',
+        'security.notEncrypted': 'Nieszyfrowane',
+        'security.tlsVersion': 'Wersja TLS',
+        'security.cipher': 'Zestaw szyfrów',
+        'security.certificate': 'Certyfikat',
+        'security.cert.ca_validated': 'Wydany przez znany urząd certyfikacji',
+        'security.cert.pinned': 'Taki sam jak przy pierwszej wizycie',
+        'security.cert.none': 'Brak',
+        'security.fingerprint': 'Odcisk (SHA-256)',
+        'security.downgraded': 'Przekierowanie prowadziło z adresu szyfrowanego do nieszyfrowanego',
+        'security.local': 'Strona lokalna, brak połączenia',
+        'security.unknown': 'Nieznane',
+        'conversion.alwaysForSite': 'Zawsze konwertować strony z {host}?',
         'status.zoomInError': 'Nie udało się powiększyć',
         'status.zoomOutError': 'Nie udało się pomniejszyć',
         'status.zoomResetError': 'Nie udało się zresetować powiększenia',
@@ -1394,6 +2992,7 @@ const TRANSLATIONS = {
         'status.urlResolveError': 'Nie udało się rozwiązać URL',
         'status.noBaseUrl': 'Nie można nawigować do względnego linku bez bazowego URL',
         'status.openFileError': 'Nie udało się otworzyć pliku',
+        'dialog.supportedFiles': 'Dokumenty',
         'shortcut.back': 'Alt+←',
         'shortcut.forward': 'Alt+→',
         'shortcut.home': 'G',
@@ -0,0 +1,42 @@
+/**
+ * Bare Browser - Kall til backend
+ *
+ * Commands avviser med et objekt { kind, message, ... } (se command_error.rs).
+ * Feilene gjøres om til CommandError, slik at de kan sjekkes med `kind` og
+ * fortsatt vises som tekst der de settes rett inn i en melding.
+ */
+
+class CommandError extends Error {
+    /**
+     * @param {{kind: string, message: string}} payload - Feilen fra backend
+     */
+    constructor(payload) {
+        super(payload.message);
+        Object.assign(this, payload);
+    }
+
+    toString() {
+        return this.message;
+    }
+}
+
+/**
+ * Kaller en Tauri-command
+ * @param {string} command - Navnet på commanden
+ * @param {Object} [args] - Argumentene
+ * @returns {Promise<*>} Svaret fra commanden
+ * @throws {CommandError} Hvis commanden feiler
+ */
+async function invokeCommand(command, args) {
+    try {
+        return await window.__TAURI__.core.invoke(command, args);
+    } catch (error) {
+        if (typeof error === 'string') {
+            throw new CommandError({ kind: 'failed', message: error });
+        }
+        if (error && typeof error.kind === 'string') {
+            throw new CommandError(error);
+        }
+        throw error;
+    }
+}
@@ -4,7 +4,7 @@
  * Initialiserer applikasjonen og orkestrerer alle moduler.
  */
 
-const { invoke: invokeMain } = window.__TAURI__.core;
+const invokeMain = invokeCommand;
 
 /**
  * Initialiserer applikasjonen
@@ -25,17 +25,71 @@ async function init() {
     // Vis onboarding ved første oppstart
     await checkAndShowOnboarding();
     
-    // Registrer event listeners
+    // Hent tastatursnarveier og registrer event listeners
+    await loadKeyBindings();
     initEventListeners();
     
     // Start lytting på loading-status events fra backend
     await initLoadingStatusListener();
     
+    // Vis lokale filer på nytt når de lagres
+    await initFileChangeListener();
+    
+    // Vis fremdriften til nedlastinger
+    await initDownloadListeners();
+    
+    // Oppdater bokmerker når filen endres av en annen prosess
+    await initBookmarksChangedListener();
+    
+    // Oppdater innstillinger som endres i andre vinduer
+    await initSettingsChangedListener();
+    
+    // Åpne gemini://- og gopher://-lenker fra andre programmer
+    await initNavigateRequestListener();
+    
     // Oppdater UI
     updateNavigationButtons();
     
-    // Naviger til startsiden
-    await goHome();
+    // Nye vinduer åpner adressen de ble opprettet for, hovedvinduet filene
+    // og adressene fra kommandolinjen, ellers vises startsiden
+    let initialUrl = null;
+    let startupTargets = [];
+    try {
+        initialUrl = await invokeMain('take_initial_url');
+        startupTargets = await invokeMain('get_startup_targets');
+        // Lenker fra andre programmer som kom før vinduet var klart; den nyeste vises
+        const deepLinks = await invokeMain('take_deep_links');
+        initialUrl = initialUrl || deepLinks.at(-1) || null;
+    } catch (error) {
+        console.error('Kunne ikke hente adressen til vinduet:', error);
+    }
+    if (startupTargets.length > 0) {
+        await openStartupTargets(startupTargets);
+    }
+    if (initialUrl) {
+        elements.urlBar.value = initialUrl;
+        await handleUrlSubmit();
+    } else if (startupTargets.length === 0) {
+        await goHome();
+    }
+}
+
+/**
+ * Åpner filene og adressene fra kommandolinjen etter hverandre
+ *
+ * Den siste blir stående; de andre ligger i historikken bak den.
+ * @param {Array<{kind: string, url?: string, argument?: string, page?: Object}>} targets - Målene fra get_startup_targets
+ */
+async function openStartupTargets(targets) {
+    for (const target of targets) {
+        if (target.kind === 'open') {
+            elements.urlBar.value = target.url;
+            await handleUrlSubmit();
+        } else {
+            elements.urlBar.value = target.argument;
+            showError({ message: target.page.title, page: target.page });
+        }
+    }
 }
 
 // Start applikasjonen når DOM er klar
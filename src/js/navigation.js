@@ -4,8 +4,8 @@
  * Håndterer navigasjon, URL-lasting og filåpning.
  */
 
-const { invoke: invokeNav } = window.__TAURI__.core;
-const { open } = window.__TAURI__.dialog;
+const invokeNav = invokeCommand;
+const { open, save } = window.__TAURI__.dialog;
 
 // ===== PDF Helpers =====
 
@@ -21,6 +21,13 @@ function isPdfUrl(url) {
     }
 }
 
+/**
+ * Sjekker om en HTTP(S)-URL peker til en PDF-fil, som åpnes eksternt
+ */
+function isHttpPdf(url) {
+    return (url.startsWith('http://') || url.startsWith('https://')) && isPdfUrl(url);
+}
+
 /**
  * Åpner en URL i systemets standard program
  */
@@ -28,6 +35,13 @@ async function openExternally(url) {
     await window.__TAURI__.opener.openUrl(url);
 }
 
+/**
+ * Sjekker om en henting ble forkastet fordi en nyere startet i samme vindu
+ */
+function isSuperseded(error) {
+    return error?.kind === 'superseded';
+}
+
 // ===== Home =====
 
 /**
@@ -38,17 +52,20 @@ async function goHome() {
     startFooterLoading();
     updateFooterStatus(t('footer.loadingHome'));
     try {
-        const result = await invokeNav('get_welcome_content');
-        renderContent(result.html, result.title);
-        elements.urlBar.value = '';
-        setCurrentUrl(null);
+        // Backend faller tilbake til velkomstsiden hvis startsiden feiler
+        const result = await invokeNav('get_home_page');
+        renderContent(result.html, result.title, result.tasks, result.security);
+        elements.urlBar.value = result.is_remote ? result.url : '';
+        setCurrentUrl(result.is_remote ? result.url : null);
         setCurrentPath(HOME_PATH);
-        addToHistory(HOME_PATH);
+        followLocalFile(null);
         updateNavigationButtons();
         updateFooter(HOME_PATH);
         updateBookmarkButton();
+        applySiteSettings();
         stopFooterLoading();
     } catch (error) {
+        if (isSuperseded(error)) return;
         showError(`${t('status.loadHomeError')}: ${error}`);
         stopFooterLoading();
     }
@@ -58,9 +75,10 @@ async function goHome() {
 
 /**
  * Laster gjeldende side på nytt
+ * @param {boolean} bypassCache - Hent en fersk kopi forbi alle mellomlagre
  */
-async function reloadPage() {
-    const currentPath = getCurrentPath();
+async function reloadPage(bypassCache = false) {
+    const currentPath = getCurrentLocation();
     if (!currentPath || currentPath === HOME_PATH) {
         await goHome();
         return;
@@ -72,7 +90,11 @@ async function reloadPage() {
     updateFooterStatus(t('footer.reloading'));
     
     try {
-        await loadPath(currentPath, false);
+        if (bypassCache && (currentPath.startsWith('http://') || currentPath.startsWith('https://'))) {
+            await navigateTo(currentPath, null, null, true);
+        } else {
+            await loadPath(currentPath);
+        }
     } finally {
         elements.btnReload.classList.remove('loading');
     }
@@ -84,54 +106,142 @@ async function reloadPage() {
  * Navigerer tilbake i historikken
  */
 async function goBack() {
-    const path = historyBack();
-    if (path) {
-        await loadPath(path, false);
-    }
+    await showHistoryStep(await invokeNav('navigate_back'));
 }
 
 /**
  * Navigerer fremover i historikken
  */
 async function goForward() {
-    const path = historyForward();
-    if (path) {
-        await loadPath(path, false);
+    await showHistoryStep(await invokeNav('navigate_forward'));
+}
+
+/**
+ * Viser siden et steg tilbake eller frem landet på
+ *
+ * Backend har flyttet seg på navigeringsstabelen. Sider fra minnet vises
+ * direkte; andre hentes på nytt uten å bli et nytt steg.
+ * @param {?Object} step - { url, page } fra backend, eller null
+ */
+async function showHistoryStep(step) {
+    if (!step) return;
+    const page = step.page;
+    if (!page) {
+        await loadLocation(step.url);
+        return;
+    }
+    renderContent(page.html, page.title, page.tasks, page.security);
+    setCurrentPath(null);
+    followLocalFile(null);
+    setCurrentUrl(step.url);
+    elements.urlBar.value = step.url;
+    updateNavigationButtons();
+    updateFooter(step.url, page.was_converted, page.used_readability);
+    updateBookmarkButton();
+    applySiteSettings();
+}
+
+/**
+ * Laster en adresse fra navigeringsstabelen i backend
+ * @param {string} url - Adressen; lokale filer er file://-adresser
+ */
+async function loadLocation(url) {
+    await loadPath(url.startsWith('file://') ? fileUrlToPath(url) : url);
+}
+
+/**
+ * Viser siden brukeren var på før en navigering feilet
+ *
+ * Den feilede adressen er aldri lagt på navigeringsstabelen, så siden står
+ * fortsatt der stabelen peker.
+ */
+async function returnFromErrorPage() {
+    const { current_url: url } = await invokeNav('get_window_state');
+    if (url) {
+        await loadLocation(url);
+    } else {
+        await goHome();
     }
 }
 
 // ===== File Loading =====
 
+/**
+ * Følg endringer i den lokale filen som vises, eller slutt å følge
+ * @param {string|null} path - Filen som vises, eller null for andre sider
+ */
+function followLocalFile(path) {
+    const request = path
+        ? invokeNav('watch_file', { path })
+        : invokeNav('unwatch_file');
+    request.catch((error) => console.error('Kunne ikke følge filen:', error));
+}
+
+/**
+ * Lytter etter endringer i den lokale filen som vises
+ *
+ * Siden byttes ut på stedet, og rulleposisjonen beholdes.
+ */
+async function initFileChangeListener() {
+    const currentWindow = window.__TAURI__.webviewWindow.getCurrentWebviewWindow();
+    await currentWindow.listen('file-changed', (event) => {
+        const page = event.payload;
+        if (page.url !== state.currentUrl) return;
+        const scrollTop = elements.content.scrollTop;
+        renderContent(page.html, page.title, page.tasks);
+        elements.content.scrollTop = scrollTop;
+    });
+}
+
+/**
+ * Lytter etter adresser fra andre programmer (gemini:// og gopher://-lenker)
+ *
+ * Backend sender dem bare til hovedvinduet.
+ */
+async function initNavigateRequestListener() {
+    const currentWindow = window.__TAURI__.webviewWindow.getCurrentWebviewWindow();
+    await currentWindow.listen('navigate-request', async (event) => {
+        elements.urlBar.value = event.payload;
+        await handleUrlSubmit();
+    });
+}
+
+/**
+ * Gjør en file://-adresse om til en filsti
+ * @param {string} url - file://-adresse, eventuelt prosentkodet
+ * @returns {string} Stien filen åpnes med
+ */
+function fileUrlToPath(url) {
+    const path = url.replace('file://', '');
+    try {
+        return decodeURIComponent(path);
+    } catch {
+        return path;
+    }
+}
+
 /**
  * Laster en fil eller URL basert på sti
  * @param {string} path - Sti eller URL å laste
- * @param {boolean} addHistory - Om stien skal legges til historikken
  */
-async function loadPath(path, addHistory = true) {
-    if (path === HOME_PATH) {
+async function loadPath(path) {
+    if (path === HOME_PATH || path === HOME_PAGE_URL) {
         await goHome();
         return;
     }
     
-    // Sjekk om dette er en URL eller lokal fil
-    if (path.startsWith('http://') || path.startsWith('https://')) {
-        if (isPdfUrl(path)) {
-            await openExternally(path);
-            return;
-        }
-        await loadUrl(path, addHistory);
-        return;
-    }
-    
-    // Gemini-URLer
-    if (path.startsWith(GEMINI_SCHEME)) {
-        await loadGeminiUrl(path, addHistory);
+    if (path === BOOKMARKS_PAGE_URL) {
+        await loadBookmarksPage();
         return;
     }
     
-    // Gopher-URLer
-    if (path.startsWith(GOPHER_SCHEME)) {
-        await loadGopherUrl(path, addHistory);
+    // Adresser hentes av backend med klienten for protokollen
+    if (path.includes('://') && !path.startsWith('file://')) {
+        if (isHttpPdf(path)) {
+            await openExternally(path);
+            return;
+        }
+        await navigateTo(path);
         return;
     }
     
@@ -146,17 +256,62 @@ async function loadPath(path, addHistory = true) {
     
     try {
         const result = await invokeNav('open_file', { path });
-        renderContent(result.html, result.title);
+        renderContent(result.html, result.title, result.tasks, result.security);
         setCurrentPath(path);
         setCurrentUrl(result.url || null);
-        
-        if (addHistory) {
-            addToHistory(path);
-        }
-        
+        // Mapper vises som en fortegnelse og følges ikke
+        followLocalFile(result.url?.endsWith('/') ? null : path);
         updateNavigationButtons();
         updateFooter(path);
         updateBookmarkButton();
+        applySiteSettings();
+    } catch (error) {
+        // En nyere navigering i samme vindu har tatt over
+        if (isSuperseded(error)) return;
+        showError(error);
+    }
+}
+
+/**
+ * Viser innhold som er limt inn, uten å gå via en fil
+ * @param {string} content - Innholdet
+ * @param {string} format - "markdown", "gemtext" eller "html"
+ * @param {?string} base - Filsti eller adresse relative lenker løses mot
+ */
+async function renderPastedContent(content, format, base = null) {
+    try {
+        const result = await invokeNav('render_content', { content, format, base });
+        renderContent(result.html, result.title, result.tasks, result.security);
+        setCurrentPath(null);
+        setCurrentUrl(result.url || null);
+        followLocalFile(null);
+        elements.urlBar.value = result.url || '';
+        updateNavigationButtons();
+        updateBookmarkButton();
+    } catch (error) {
+        showError(error);
+    }
+}
+
+// ===== Bookmarks Page =====
+
+/**
+ * Viser alle bokmerker som en side
+ */
+async function loadBookmarksPage() {
+    showLoading();
+    elements.urlBar.value = BOOKMARKS_PAGE_URL;
+    
+    try {
+        const result = await invokeNav('get_bookmarks_page');
+        renderContent(result.html, result.title, result.tasks, result.security);
+        setCurrentPath(BOOKMARKS_PAGE_URL);
+        followLocalFile(null);
+        setCurrentUrl(null);
+        updateNavigationButtons();
+        updateFooter(BOOKMARKS_PAGE_URL);
+        updateBookmarkButton();
+        applySiteSettings();
     } catch (error) {
         showError(error);
     }
@@ -165,93 +320,151 @@ async function loadPath(path, addHistory = true) {
 // ===== URL Loading =====
 
 /**
- * Laster innhold fra en URL
- * @param {string} url - URL å laste
- * @param {boolean} addHistory - Om URL skal legges til historikken
+ * Åpner en adresse; backend velger klient etter protokollen
+ *
+ * Adresser uten protokoll får protokollen fra innstillingene. Lokale filer
+ * åpnes med loadPath, som også følger dem når de endres.
+ * @param {string} url - Adressen som skal åpnes
+ * @param {string|null} linkText - Teksten på lenken som ble klikket, hvis noen
+ * @param {string|null} referrer - Siden lenken ble fulgt fra, hvis noen
+ * @param {boolean} bypassCache - Hent en fersk kopi forbi alle mellomlagre (HTTP)
  */
-async function loadUrl(url, addHistory = true) {
+async function navigateTo(url, linkText = null, referrer = null, bypassCache = false) {
     showLoading();
     startFooterLoading();
     elements.urlBar.value = url;
     
     try {
-        const result = await invokeNav('fetch_url', { url });
-        renderContent(result.html, result.title);
+        const result = await invokeNav('navigate', { url, referrer, bypassCache, linkText });
+        renderContent(result.html, result.title, result.tasks, result.security);
         setCurrentPath(null);
+        followLocalFile(null);
         setCurrentUrl(result.url || url);
         
         if (result.url) {
             elements.urlBar.value = result.url;
         }
         
-        if (addHistory) {
-            addToHistory(result.url || url);
-        }
-        
         updateNavigationButtons();
-        updateFooter(result.url || url, result.was_converted);
+        updateFooter(result.url || url, result.was_converted, result.used_readability);
         updateBookmarkButton();
+        applySiteSettings();
     } catch (error) {
+        // En nyere navigering i samme vindu har tatt over
+        if (isSuperseded(error)) return;
         stopFooterLoading();
-        // Sjekk om dette er en konverteringsprompt
-        if (typeof error === 'string' && error.startsWith(CONVERSION_PROMPT_PREFIX)) {
-            // Bruk indexOf for å unngå splitting av URL-er som inneholder ':'
-            const withoutPrefix = error.substring(CONVERSION_PROMPT_PREFIX.length);
-            const lastColon = withoutPrefix.lastIndexOf(':http');
-            let message, promptUrl;
-            if (lastColon !== -1) {
-                message = withoutPrefix.substring(0, lastColon);
-                promptUrl = withoutPrefix.substring(lastColon + 1);
-            } else {
-                // Fallback: bruk opprinnelig URL
-                message = withoutPrefix;
-                promptUrl = url;
-            }
-            
-            // Gjenopprett URL i adressefeltet mens brukeren velger
+        if (await confirmNavigation(error, () => navigateTo(url, linkText, referrer, bypassCache))) {
+            return;
+        }
+        if (await offerDownload(error)) {
             elements.urlBar.value = url;
-            
-            if (confirm(message)) {
-                await convertAndLoad(promptUrl, addHistory);
+        } else if (error?.kind === 'conversion_prompt') {
+            elements.urlBar.value = url;
+            if (confirm(error.message)) {
+                await rememberConversionChoice(error.url);
+                await convertAndLoad(error.url);
             } else {
                 showError(t('status.conversionCancelled'));
             }
+        } else if (error?.kind === 'gemini_input_prompt') {
+            showGeminiInputDialog(error.prompt, error.url, error.sensitive);
+        } else if (error?.kind === 'gopher_search_prompt') {
+            showGopherSearchDialog(error.url);
+        } else if (error?.kind === 'scheme_redirect') {
+            await followSchemeRedirect(error, url);
+        } else if (error?.kind === 'client_cert_prompt') {
+            await offerGeminiIdentity(error, url);
         } else {
             showError(error);
         }
     }
 }
 
+/**
+ * Spør om en redirect til en annen protokoll skal følges, f.eks. fra
+ * gemini:// til https://
+ * @param {CommandError} error - Feilen med adressen redirecten peker til
+ * @param {string} url - Adressen som ble forsøkt
+ */
+async function followSchemeRedirect(error, url) {
+    elements.urlBar.value = url;
+    if (confirm(error.message)) {
+        await navigateTo(error.url);
+    } else {
+        showError(t('status.redirectCancelled'));
+    }
+}
+
+/**
+ * Spør om en identitet skal lages for en Gemini-kapsel som ber om
+ * klientsertifikat, og åpner siden på nytt med den
+ * @param {CommandError} error - Feilen med verten og adressen
+ * @param {string} url - Adressen som ble forsøkt
+ */
+async function offerGeminiIdentity(error, url) {
+    elements.urlBar.value = url;
+    if (!confirm(error.message)) {
+        showError(t('status.identityCancelled'));
+        return;
+    }
+    try {
+        await invokeNav('create_gemini_identity', { name: error.host, host: error.host });
+    } catch (createError) {
+        showError(createError);
+        return;
+    }
+    await navigateTo(error.url);
+}
+
 // ===== URL Conversion =====
 
+/**
+ * Spør om nettstedet alltid skal konverteres, og lagrer i så fall en domeneregel
+ * @param {string} url - Siden som skal konverteres
+ */
+async function rememberConversionChoice(url) {
+    let host;
+    try {
+        host = new URL(url).hostname;
+    } catch {
+        return;
+    }
+    if (!host || !confirm(t('conversion.alwaysForSite', { host }))) return;
+    
+    try {
+        await invokeNav('set_domain_conversion_rule', { hostPattern: host, mode: 'convert-all' });
+    } catch (error) {
+        showStatus(`${t('status.settingsError')}: ${error}`, true);
+    }
+}
+
 /**
  * Konverterer og laster en URL
  * @param {string} url - URL å konvertere
- * @param {boolean} addHistory - Om URL skal legges til historikken
  */
-async function convertAndLoad(url, addHistory = true) {
+async function convertAndLoad(url) {
     showLoading();
     startFooterLoading();
     elements.urlBar.value = url;
     
     try {
         const result = await invokeNav('convert_url', { url });
-        renderContent(result.html, result.title);
+        renderContent(result.html, result.title, result.tasks, result.security);
         setCurrentPath(null);
+        followLocalFile(null);
         setCurrentUrl(result.url || url);
         
         if (result.url) {
             elements.urlBar.value = result.url;
         }
         
-        if (addHistory) {
-            addToHistory(result.url || url);
-        }
-        
         updateNavigationButtons();
-        updateFooter(result.url || url, true);
+        updateFooter(result.url || url, true, result.used_readability);
         updateBookmarkButton();
+        applySiteSettings();
     } catch (error) {
+        // En nyere navigering i samme vindu har tatt over
+        if (isSuperseded(error)) return;
         stopFooterLoading();
         showError(error);
     }
@@ -262,37 +475,39 @@ async function convertAndLoad(url, addHistory = true) {
 /**
  * Løser og navigerer til en lenke
  * @param {string} href - Lenke å følge
+ * @param {string|null} linkText - Teksten på lenken, sjekkes mot adressen
  */
-async function resolveAndNavigate(href) {
-    // Absolutte URLer
-    if (href.startsWith('http://') || href.startsWith('https://')) {
-        if (isPdfUrl(href)) {
-            await openExternally(href);
-            return;
-        }
-        await loadUrl(href);
+async function resolveAndNavigate(href, linkText = null) {
+    // Handlinger på feilsider
+    if (href === ERROR_PAGE_BACK_URL) {
+        await returnFromErrorPage();
         return;
     }
-    
-    // Gemini-URLer
-    if (href.startsWith(GEMINI_SCHEME)) {
-        await loadGeminiUrl(href);
-        return;
-    }
-    
-    // Gopher-URLer
-    if (href.startsWith(GOPHER_SCHEME)) {
-        await loadGopherUrl(href);
+    if (href.startsWith(ERROR_PAGE_OPEN_EXTERNALLY_URL)) {
+        const target = new URL(href).searchParams.get('url');
+        if (target) {
+            await openExternally(target);
+        }
         return;
     }
     
     // File URLs
     if (href.startsWith('file://')) {
-        const path = href.replace('file://', '');
+        const path = fileUrlToPath(href);
         await loadPath(path);
         return;
     }
     
+    // Absolutte URLer; backend velger klient etter protokollen
+    if (href.includes('://')) {
+        if (isHttpPdf(href)) {
+            await openExternally(href);
+            return;
+        }
+        await navigateTo(href, linkText, state.currentUrl);
+        return;
+    }
+    
     // Relativ URL - løs basert på nåværende lokasjon
     const currentUrl = state.currentUrl;
     const currentPath = state.currentPath;
@@ -319,18 +534,12 @@ async function resolveAndNavigate(href) {
             }
             
             if (resolvedUrl.startsWith('file://')) {
-                const path = resolvedUrl.replace('file://', '');
+                const path = fileUrlToPath(resolvedUrl);
                 await loadPath(path);
-            } else if (resolvedUrl.startsWith(GEMINI_SCHEME)) {
-                await loadGeminiUrl(resolvedUrl);
-            } else if (resolvedUrl.startsWith(GOPHER_SCHEME)) {
-                await loadGopherUrl(resolvedUrl);
+            } else if (isHttpPdf(resolvedUrl)) {
+                await openExternally(resolvedUrl);
             } else {
-                if (isPdfUrl(resolvedUrl)) {
-                    await openExternally(resolvedUrl);
-                    return;
-                }
-                await loadUrl(resolvedUrl);
+                await navigateTo(resolvedUrl, linkText, currentUrl);
             }
         } catch (error) {
             showError(`${t('status.urlResolveError')}: ${error}`);
@@ -347,49 +556,6 @@ async function resolveAndNavigate(href) {
 
 // ===== Gemini Loading =====
 
-/**
- * Laster innhold fra en Gemini-URL
- * @param {string} url - Gemini-URL å laste (gemini://...)
- * @param {boolean} addHistory - Om URL skal legges til historikken
- */
-async function loadGeminiUrl(url, addHistory = true) {
-    showLoading();
-    startFooterLoading();
-    elements.urlBar.value = url;
-    
-    try {
-        const result = await invokeNav('fetch_gemini', { url });
-        renderContent(result.html, result.title);
-        setCurrentPath(null);
-        setCurrentUrl(result.url || url);
-        
-        if (result.url) {
-            elements.urlBar.value = result.url;
-        }
-        
-        if (addHistory) {
-            addToHistory(result.url || url);
-        }
-        
-        updateNavigationButtons();
-        updateFooter(result.url || url, true);
-        updateBookmarkButton();
-    } catch (error) {
-        stopFooterLoading();
-        
-        // Sjekk om dette er en input-prompt
-        if (typeof error === 'string' && error.startsWith(GEMINI_INPUT_PROMPT_PREFIX)) {
-            const prompt = error.substring(GEMINI_INPUT_PROMPT_PREFIX.length);
-            showGeminiInputDialog(prompt, url, false);
-        } else if (typeof error === 'string' && error.startsWith(GEMINI_SENSITIVE_INPUT_PROMPT_PREFIX)) {
-            const prompt = error.substring(GEMINI_SENSITIVE_INPUT_PROMPT_PREFIX.length);
-            showGeminiInputDialog(prompt, url, true);
-        } else {
-            showError(error);
-        }
-    }
-}
-
 /**
  * Sender brukerinput til en Gemini-server og laster resultatet
  * @param {string} url - Original Gemini-URL som ba om input
@@ -401,28 +567,29 @@ async function submitGeminiInput(url, input) {
     
     try {
         const result = await invokeNav('submit_gemini_input', { url, input });
-        renderContent(result.html, result.title);
+        renderContent(result.html, result.title, result.tasks, result.security);
         setCurrentPath(null);
+        followLocalFile(null);
         setCurrentUrl(result.url || url);
         
         if (result.url) {
             elements.urlBar.value = result.url;
         }
         
-        addToHistory(result.url || url);
         updateNavigationButtons();
         updateFooter(result.url || url, true);
         updateBookmarkButton();
+        applySiteSettings();
     } catch (error) {
+        // En nyere navigering i samme vindu har tatt over
+        if (isSuperseded(error)) return;
         stopFooterLoading();
         
         // Sjekk om dette er enda en input-prompt
-        if (typeof error === 'string' && error.startsWith(GEMINI_INPUT_PROMPT_PREFIX)) {
-            const prompt = error.substring(GEMINI_INPUT_PROMPT_PREFIX.length);
-            showGeminiInputDialog(prompt, url, false);
-        } else if (typeof error === 'string' && error.startsWith(GEMINI_SENSITIVE_INPUT_PROMPT_PREFIX)) {
-            const prompt = error.substring(GEMINI_SENSITIVE_INPUT_PROMPT_PREFIX.length);
-            showGeminiInputDialog(prompt, url, true);
+        if (await offerDownload(error)) {
+            elements.urlBar.value = url;
+        } else if (error?.kind === 'gemini_input_prompt') {
+            showGeminiInputDialog(error.prompt, error.url, error.sensitive);
         } else {
             showError(error);
         }
@@ -431,46 +598,6 @@ async function submitGeminiInput(url, input) {
 
 // ===== Gopher Loading =====
 
-/**
- * Laster innhold fra en Gopher-URL
- * @param {string} url - Gopher-URL å laste (gopher://...)
- * @param {boolean} addHistory - Om URL skal legges til historikken
- */
-async function loadGopherUrl(url, addHistory = true) {
-    showLoading();
-    startFooterLoading();
-    elements.urlBar.value = url;
-    
-    try {
-        const result = await invokeNav('fetch_gopher', { url });
-        renderContent(result.html, result.title);
-        setCurrentPath(null);
-        setCurrentUrl(result.url || url);
-        
-        if (result.url) {
-            elements.urlBar.value = result.url;
-        }
-        
-        if (addHistory) {
-            addToHistory(result.url || url);
-        }
-        
-        updateNavigationButtons();
-        updateFooter(result.url || url, true);
-        updateBookmarkButton();
-    } catch (error) {
-        stopFooterLoading();
-        
-        // Sjekk om dette er en søke-prompt
-        if (typeof error === 'string' && error.startsWith(GOPHER_SEARCH_PROMPT_PREFIX)) {
-            const searchUrl = error.substring(GOPHER_SEARCH_PROMPT_PREFIX.length);
-            showGopherSearchDialog(searchUrl);
-        } else {
-            showError(error);
-        }
-    }
-}
-
 /**
  * Sender et Gopher-søk og laster resultatet
  * @param {string} url - Gopher-søke-URL
@@ -482,19 +609,22 @@ async function submitGopherSearch(url, query) {
     
     try {
         const result = await invokeNav('gopher_search', { url, query });
-        renderContent(result.html, result.title);
+        renderContent(result.html, result.title, result.tasks, result.security);
         setCurrentPath(null);
+        followLocalFile(null);
         setCurrentUrl(result.url || url);
         
         if (result.url) {
             elements.urlBar.value = result.url;
         }
         
-        addToHistory(result.url || url);
         updateNavigationButtons();
         updateFooter(result.url || url, true);
         updateBookmarkButton();
+        applySiteSettings();
     } catch (error) {
+        // En nyere navigering i samme vindu har tatt over
+        if (isSuperseded(error)) return;
         stopFooterLoading();
         showError(error);
     }
@@ -503,15 +633,15 @@ async function submitGopherSearch(url, query) {
 // ===== File Dialog =====
 
 /**
- * Åpner fil-dialog for å velge en markdown-fil
+ * Åpner fil-dialog for å velge en fil Bare kan vise
  */
 async function openFileDialog() {
     try {
         const selected = await open({
             multiple: false,
             filters: [{
-                name: 'Markdown',
-                extensions: ['md', 'markdown']
+                name: t('dialog.supportedFiles'),
+                extensions: ['md', 'markdown', 'gmi', 'gemini', 'txt']
             }]
         });
         
@@ -523,38 +653,351 @@ async function openFileDialog() {
     }
 }
 
-// ===== URL Bar Handling =====
+/**
+ * Fyller listen over nylige filer i menyen
+ */
+async function updateRecentFiles() {
+    let files = [];
+    try {
+        files = await invokeNav('get_recent_files');
+    } catch (error) {
+        console.error('Kunne ikke hente nylige filer:', error);
+    }
+    
+    elements.recentFilesList.replaceChildren(...files.map((file) => {
+        const item = document.createElement('button');
+        item.className = 'menu-item';
+        item.title = file.path;
+        const icon = document.createElement('span');
+        icon.className = 'menu-icon';
+        icon.textContent = '▤';
+        const name = document.createElement('span');
+        name.textContent = file.name;
+        item.append(icon, name);
+        item.addEventListener('click', () => {
+            closeDropdownMenu();
+            loadPath(file.path);
+        });
+        return item;
+    }));
+    elements.menuRecentFiles.classList.toggle('hidden', files.length === 0);
+}
 
 /**
- * Håndterer submit fra URL-bar
+ * Tømmer listen over nylige filer
  */
-async function handleUrlSubmit() {
-    const input = elements.urlBar.value.trim();
+async function clearRecentFiles() {
+    try {
+        await invokeNav('clear_recent_files');
+        await updateRecentFiles();
+    } catch (error) {
+        showStatus(String(error), true);
+    }
+}
+
+// ===== Export =====
+
+/**
+ * Filnavn for en eksportert side, fra siste ledd i adressen
+ * @param {string} url - Adressen til siden
+ * @param {?string} extension - Endelsen; null lar backend velge
+ */
+function exportFileName(url, extension = 'html') {
+    let name = '';
+    try {
+        name = decodeURIComponent(new URL(url).pathname.split('/').filter(Boolean).pop() || '');
+    } catch {
+        // Bruk standardnavnet
+    }
+    name = name.replace(/\.[^.]*$/, '').replace(/[\\/:*?"<>|]/g, '_');
+    name = name || 'side';
+    return extension ? `${name}.${extension}` : name;
+}
+
+/**
+ * Eksporterer siden som vises som HTML-fil eller PDF
+ * @param {'html'|'pdf'} format - Formatet
+ */
+async function exportCurrentPage(format) {
+    const url = state.currentUrl;
+    if (!url) {
+        showStatus(t('status.exportNoPage'), true);
+        return;
+    }
     
-    if (!input) {
-        await goHome();
+    try {
+        let destination = null;
+        if (format === 'html') {
+            const fileName = exportFileName(url);
+            const target = await invokeNav('get_download_target', { fileName });
+            if (target.kind === 'direct') {
+                destination = target.path;
+            } else {
+                destination = await save({
+                    defaultPath: target.default_dir ? `${target.default_dir}/${fileName}` : fileName,
+                    filters: [{ name: 'HTML', extensions: ['html'] }]
+                });
+                if (!destination) return;
+            }
+        }
+        
+        const result = await invokeNav('export_page', { url, format, destination });
+        if (result.kind === 'print') {
+            // Webviewens utskriftsdialog lagrer som PDF
+            window.print();
+        } else {
+            showStatus(t('status.exported', { path: result.path }));
+        }
+    } catch (error) {
+        showStatus(`${t('status.exportError')}: ${error}`, true);
+    }
+}
+
+/**
+ * Lagrer kilden til siden som vises, slik serveren sendte den
+ */
+async function saveCurrentSource() {
+    const url = state.currentUrl;
+    if (!url) {
+        showStatus(t('status.exportNoPage'), true);
         return;
     }
     
-    // Absolutte URLer
-    if (input.startsWith('http://') || input.startsWith('https://')) {
-        if (isPdfUrl(input)) {
-            await openExternally(input);
-            return;
+    try {
+        // Backend legger til .gmi, .html, .md eller .txt etter formatet
+        const fileName = exportFileName(url, null);
+        let destination;
+        const target = await invokeNav('get_download_target', { fileName });
+        if (target.kind === 'direct') {
+            destination = target.path;
+        } else {
+            destination = await save({
+                defaultPath: target.default_dir ? `${target.default_dir}/${fileName}` : fileName
+            });
+            if (!destination) return;
         }
-        await loadUrl(input);
+        const path = await invokeNav('save_page_source', { url, destination });
+        showStatus(t('status.exported', { path }));
+    } catch (error) {
+        showStatus(`${t('status.saveSourceError')}: ${error}`, true);
+    }
+}
+
+/**
+ * Kopier den viste siden til utklippstavlen
+ * @param {boolean} plainText - Ren tekst i stedet for markdown
+ */
+async function copyCurrentPage(plainText) {
+    const url = state.currentUrl;
+    if (!url) {
+        showStatus(t('status.exportNoPage'), true);
         return;
     }
     
-    // Gemini-URLer
-    if (input.startsWith(GEMINI_SCHEME)) {
-        await loadGeminiUrl(input);
+    try {
+        await invokeNav('copy_page_as_markdown', { url, plainText });
+        showStatus(t('status.copied'));
+    } catch (error) {
+        showStatus(`${t('status.copyError')}: ${error}`, true);
+    }
+}
+
+/**
+ * Kopier adressen til den viste siden uten sporingsparametere
+ */
+async function copyCurrentLink() {
+    const url = state.currentUrl;
+    if (!url) {
+        showStatus(t('status.exportNoPage'), true);
+        return;
+    }
+    
+    try {
+        const copied = await invokeNav('copy_link', { url });
+        showStatus(t('status.linkCopied', { url: copied }));
+    } catch (error) {
+        showStatus(`${t('status.copyError')}: ${error}`, true);
+    }
+}
+
+// ===== Downloads =====
+
+/** Nedlastinger startet fra dette vinduet, som får fremdriften i statuslinjen */
+const ownDownloads = new Set();
+
+/**
+ * Filnavn for en nedlasting, fra siste ledd i adressen
+ * @param {string} url - Adressen til filen
+ */
+function downloadFileName(url) {
+    let name = '';
+    try {
+        name = decodeURIComponent(new URL(url).pathname.split('/').filter(Boolean).pop() || '');
+    } catch {
+        // Bruk standardnavnet
+    }
+    name = name.replace(/[\\/:*?"<>|]/g, '_');
+    return name || 'nedlasting';
+}
+
+/**
+ * Spør om en adresse som ser mistenkelig ut skal åpnes likevel
+ *
+ * Godtar brukeren, huskes verten resten av økten og navigeringen prøves igjen.
+ * @param {*} error - Feilen fra en henting
+ * @param {Function} retry - Navigeringen som prøves igjen
+ * @returns {Promise<boolean>} true hvis feilen var et slikt spørsmål
+ */
+async function confirmNavigation(error, retry) {
+    if (error?.kind !== 'confirm_navigation') {
+        return false;
+    }
+    if (!confirm(error.message)) {
+        showError(t('status.navigationCancelled'));
+        return true;
+    }
+    try {
+        await invokeNav('trust_url_host', { url: error.url });
+    } catch (trustError) {
+        showError(trustError);
+        return true;
+    }
+    await retry();
+    return true;
+}
+
+/**
+ * Tilbyr å laste ned en fil backend ikke kan vise
+ * @param {*} error - Feilen fra en henting
+ * @returns {Promise<boolean>} true hvis feilen var et tilbud om nedlasting
+ */
+async function offerDownload(error) {
+    if (error?.kind !== 'download_prompt') {
+        return false;
+    }
+    const url = error.url;
+    const name = downloadFileName(url);
+    showError(t('status.notDisplayable', { name }));
+    if (confirm(t('prompt.download', { name }))) {
+        await downloadUrl(url);
+    }
+    return true;
+}
+
+/**
+ * Laster ned en adresse til nedlastingsmappen eller et valgt sted
+ * @param {string} url - Adressen til filen
+ */
+async function downloadUrl(url) {
+    const fileName = downloadFileName(url);
+    try {
+        let destination;
+        const target = await invokeNav('get_download_target', { fileName });
+        if (target.kind === 'direct') {
+            destination = target.path;
+        } else {
+            destination = await save({
+                defaultPath: target.default_dir ? `${target.default_dir}/${fileName}` : fileName
+            });
+            if (!destination) return;
+        }
+        const id = await invokeNav('start_download', { url, destination });
+        ownDownloads.add(id);
+        showStatus(t('status.downloadStarted', { name: fileName }));
+    } catch (error) {
+        showStatus(`${t('status.downloadError')}: ${error}`, true);
+    }
+}
+
+/**
+ * Lagrer en ressurs (bilde, binærfil, PDF) fra en hvilken som helst protokoll
+ * @param {string} url - Adressen til ressursen
+ * @param {?string} suggestedName - Foreslått filnavn; ellers velger backend
+ */
+async function saveResource(url, suggestedName = null) {
+    try {
+        showStatus(t('status.downloadStarted', { name: downloadFileName(url) }));
+        const path = await invokeNav('save_resource', { url, suggestedName });
+        if (path) {
+            showStatus(t('status.downloaded', { path }));
+        } else {
+            hideStatus();
+        }
+    } catch (error) {
+        showStatus(`${t('status.downloadError')}: ${error}`, true);
+    }
+}
+
+/**
+ * Fremdrift i lesbar form, i prosent hvis størrelsen er kjent
+ * @param {{received: number, total: ?number}} download - Nedlastingen
+ */
+function downloadProgress(download) {
+    if (download.total) {
+        return `${Math.floor(download.received * 100 / download.total)} %`;
+    }
+    return `${(download.received / (1024 * 1024)).toFixed(1)} MB`;
+}
+
+/**
+ * Viser fremdriften til nedlastinger fra dette vinduet i statuslinjen
+ */
+async function initDownloadListeners() {
+    await listen('download-progress', (event) => {
+        const download = event.payload;
+        if (!ownDownloads.has(download.id)) return;
+        showStatus(t('status.downloading', {
+            name: downloadFileName(download.url),
+            progress: downloadProgress(download)
+        }));
+    });
+    await listen('download-complete', (event) => {
+        const download = event.payload;
+        if (!ownDownloads.delete(download.id)) return;
+        showStatus(t('status.downloaded', { path: download.destination }));
+    });
+    await listen('download-failed', (event) => {
+        const download = event.payload;
+        if (!ownDownloads.delete(download.id)) return;
+        showStatus(`${t('status.downloadError')}: ${download.error}`, true);
+    });
+}
+
+// ===== URL Bar Handling =====
+
+/**
+ * Håndterer submit fra URL-bar
+ */
+async function handleUrlSubmit() {
+    const typed = elements.urlBar.value.trim();
+    
+    if (!typed) {
+        await goHome();
         return;
     }
     
-    // Gopher-URLer
-    if (input.startsWith(GOPHER_SCHEME)) {
-        await loadGopherUrl(input);
+    // Nøkkelord fra bokmerker ("gp", "rs serde") og søkeord ("rust lang")
+    // gjøres om til URL; adresser uten protokoll får https:// eller gemini://
+    let input = typed;
+    try {
+        const resolved = await invokeNav('resolve_address_input', { input: typed });
+        if (resolved.action === 'ask') {
+            const chosen = await chooseScheme(resolved.candidates);
+            if (!chosen) return;
+            input = chosen;
+        } else {
+            input = resolved.url;
+        }
+        // Skrivefeil som er rettet, f.eks. komma i stedet for punktum
+        if (resolved.did_you_mean) {
+            showStatus(t('status.didYouMean', { url: input }));
+        }
+    } catch (error) {
+        console.error('Kunne ikke tolke adresselinjen:', error);
+    }
+    
+    if (input === BOOKMARKS_PAGE_URL) {
+        await loadBookmarksPage();
         return;
     }
     
@@ -562,15 +1005,12 @@ async function handleUrlSubmit() {
     if (input.startsWith('/') || input.match(/^[a-zA-Z]:\\/)) {
         await loadPath(input);
     } else if (input.startsWith('file://')) {
-        const path = input.replace('file://', '');
+        const path = fileUrlToPath(input);
         await loadPath(path);
+    } else if (isHttpPdf(input)) {
+        await openExternally(input);
     } else {
-        // Anta HTTPS for alt annet
-        const urlWithScheme = 'https://' + input;
-        if (isPdfUrl(urlWithScheme)) {
-            await openExternally(urlWithScheme);
-            return;
-        }
-        await loadUrl(urlWithScheme);
+        // Backend velger klient, og protokoll for adresser uten
+        await navigateTo(input);
     }
 }
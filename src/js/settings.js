@@ -4,7 +4,7 @@
  * Håndterer brukerinnstillinger og synkronisering med backend.
  */
 
-const { invoke } = window.__TAURI__.core;
+const invoke = invokeCommand;
 
 /**
  * Laster innstillinger fra backend
@@ -29,6 +29,26 @@ async function loadSettings() {
     }
 }
 
+/**
+ * Lytter etter innstillinger som er endret i et annet vindu
+ */
+async function initSettingsChangedListener() {
+    await listen('settings-changed', async (event) => {
+        const settings = event.payload;
+        if (settings.language !== getSettings()?.language) {
+            if (settings.language && settings.language !== 'system') {
+                setLanguage(settings.language);
+            } else {
+                initI18n();
+            }
+        }
+        setSettings(settings);
+        
+        // Nettstedets overstyringer kan også ha endret seg
+        await applySiteSettings();
+    });
+}
+
 /**
  * Anvender innstillinger på UI
  */
@@ -42,26 +62,80 @@ function applySettings() {
         effectiveTheme = window.matchMedia('(prefers-color-scheme: dark)').matches ? 'dark' : 'light';
     }
     document.documentElement.setAttribute('data-theme', effectiveTheme);
+    applyCustomColors(settings.custom_colors);
+    document.body.classList.toggle('private-mode', settings.private_mode);
+    
+    // Nettstedets egne innstillinger går foran de globale
+    const site = getSiteSettings();
+    const fontSize = site ? site.font_size : settings.font_size;
+    const zoom = site ? site.zoom : settings.zoom;
     
     // Skriftstørrelse
-    document.documentElement.style.setProperty('--base-font-size', `${settings.font_size}%`);
-    document.body.style.fontSize = `${settings.font_size}%`;
+    document.documentElement.style.setProperty('--base-font-size', `${fontSize}%`);
+    document.body.style.fontSize = `${fontSize}%`;
     
     // Zoom
-    elements.content.style.transform = `scale(${settings.zoom / 100})`;
+    elements.content.style.transform = `scale(${zoom / 100})`;
     elements.content.style.transformOrigin = 'top center';
-    updateZoomDisplay(settings.zoom);
+    updateZoomDisplay(zoom);
     
     // Skrifttype
     document.body.className = `font-${settings.font_family}`;
+    if (settings.custom_font_name) {
+        document.documentElement.style.setProperty('--font-custom', `"${settings.custom_font_name}"`);
+    }
     
     // Innholdsbredde
     document.documentElement.style.setProperty('--content-max-width', `${settings.content_width}px`);
     
+    // Typografi
+    const typography = settings.typography || DEFAULT_SETTINGS.typography;
+    document.documentElement.style.setProperty('--line-height', typography.line_height / 100);
+    document.documentElement.style.setProperty('--paragraph-spacing', `${typography.paragraph_spacing / 100}em`);
+    document.documentElement.style.setProperty('--text-align', typography.justify_text ? 'justify' : 'start');
+    
+    // Egen CSS (user.css går foran innstillingen)
+    applyCustomCss();
+    
     // Oppdater innstillingspanel-kontroller
     updateSettingsPanel(settings);
 }
 
+/**
+ * Henter innstillingene for siden som vises og anvender dem
+ */
+async function applySiteSettings() {
+    const url = getState().currentUrl;
+    let site = null;
+    if (url) {
+        try {
+            site = await invoke('get_site_settings', { url });
+        } catch (error) {
+            console.error(t('status.loadSettingsError') + ':', error);
+        }
+    }
+    setSiteSettings(site);
+    applySettings();
+}
+
+/**
+ * Legger brukerens egen CSS i en egen <style>-blokk etter appens stiler
+ */
+async function applyCustomCss() {
+    let style = document.getElementById('custom-css');
+    try {
+        const css = await invoke('get_custom_css');
+        if (!style) {
+            style = document.createElement('style');
+            style.id = 'custom-css';
+            document.head.appendChild(style);
+        }
+        style.textContent = css || '';
+    } catch (error) {
+        console.error(t('status.settingsError') + ':', error);
+    }
+}
+
 /**
  * Oppdaterer innstillingspanel-kontrollene
  * @param {Object} settings - Innstillinger
@@ -70,9 +144,14 @@ function updateSettingsPanel(settings) {
     if (elements.settingTheme) {
         elements.settingTheme.value = settings.theme;
     }
+    updateColorInputs();
     if (elements.settingFontFamily) {
         elements.settingFontFamily.value = settings.font_family;
     }
+    if (elements.settingCustomFont && document.activeElement !== elements.settingCustomFont) {
+        elements.settingCustomFont.value = settings.custom_font_name || '';
+        elements.settingCustomFont.classList.toggle('hidden', settings.font_family !== 'custom');
+    }
     if (elements.settingFontSize) {
         elements.settingFontSize.value = settings.font_size;
         elements.settingFontSizeValue.textContent = `${settings.font_size}%`;
@@ -81,6 +160,50 @@ function updateSettingsPanel(settings) {
         elements.settingContentWidth.value = settings.content_width;
         elements.settingContentWidthValue.textContent = `${settings.content_width}px`;
     }
+    if (elements.settingLineHeight && settings.typography) {
+        elements.settingLineHeight.value = settings.typography.line_height;
+        elements.settingLineHeightValue.textContent = `${settings.typography.line_height}%`;
+        elements.settingParagraphSpacing.value = settings.typography.paragraph_spacing;
+        elements.settingParagraphSpacingValue.textContent = `${settings.typography.paragraph_spacing}%`;
+        elements.settingJustifyText.checked = settings.typography.justify_text;
+    }
+    if (elements.settingDecorations) {
+        elements.settingDecorations.value = settings.decorations;
+    }
+    if (elements.settingPlainText) {
+        elements.settingPlainText.value = settings.plain_text;
+    }
+    if (elements.settingShowHiddenFiles) {
+        elements.settingShowHiddenFiles.checked = settings.show_hidden_files;
+    }
+    if (elements.settingFollowSymlinks) {
+        elements.settingFollowSymlinks.checked = settings.follow_symlinks;
+    }
+    if (elements.settingMaxFile) {
+        elements.settingMaxFile.value = settings.max_file_mb;
+    }
+    if (elements.settingMaxRender) {
+        elements.settingMaxRender.value = settings.max_render_mb;
+    }
+    if (elements.settingLogLevel) {
+        elements.settingLogLevel.value = settings.log_level;
+    }
+    if (elements.settingHomepage && document.activeElement !== elements.settingHomepage) {
+        elements.settingHomepage.value = settings.homepage;
+    }
+    if (elements.settingSearchEngine && document.activeElement !== elements.settingSearchEngine) {
+        elements.settingSearchEngine.value = settings.search_engine;
+    }
+    if (elements.settingDefaultScheme) {
+        elements.settingDefaultScheme.value = settings.default_scheme;
+    }
+    if (elements.settingDownloadsDir && document.activeElement !== elements.settingDownloadsDir) {
+        elements.settingDownloadsDir.value = settings.downloads_dir || '';
+        elements.settingDownloadsWithoutAsking.checked = settings.use_downloads_dir_without_asking;
+    }
+    if (elements.settingCustomCss && document.activeElement !== elements.settingCustomCss) {
+        elements.settingCustomCss.value = settings.custom_css || '';
+    }
     if (elements.settingConversionMode) {
         elements.settingConversionMode.value = settings.conversion_mode;
     }
@@ -90,6 +213,27 @@ function updateSettingsPanel(settings) {
     if (elements.settingLanguage) {
         elements.settingLanguage.value = getSavedLanguagePreference();
     }
+    if (elements.settingZoomStep) {
+        elements.settingZoomStep.value = settings.zoom_step;
+    }
+    if (settings.network && elements.settingTimeout) {
+        elements.settingTimeout.value = settings.network.timeout_seconds;
+        elements.settingMaxResponse.value = settings.network.max_response_mb;
+        elements.settingMaxRedirects.value = settings.network.max_redirects;
+    }
+    if (settings.privacy && elements.settingLoadRemoteImages) {
+        elements.settingLoadRemoteImages.checked = settings.privacy.load_remote_images;
+        elements.settingStripQueryParams.checked = settings.privacy.strip_query_params;
+        elements.settingSendReferrer.checked = settings.privacy.send_referrer;
+        elements.settingSendGpc.checked = settings.privacy.send_gpc;
+        elements.settingPrefetch.checked = settings.privacy.prefetch;
+    }
+    if (elements.settingHistoryEnabled) {
+        elements.settingHistoryEnabled.checked = settings.history_enabled;
+    }
+    if (elements.settingPrivateMode) {
+        elements.settingPrivateMode.checked = settings.private_mode;
+    }
 }
 
 /**
@@ -101,9 +245,132 @@ async function updateSetting(key, value) {
     try {
         const params = {};
         params[key] = value;
-        const newSettings = await invoke('update_settings', { params });
+        const { settings: newSettings, warnings } = await invoke('update_settings', { params });
+        setSettings(newSettings);
+        applySettings();
+        if (warnings.length > 0) {
+            const fields = warnings.map((w) => `${w.field} (${t(`settings.warning.${w.action}`)})`);
+            showStatus(`${t('status.settingsAdjusted')}: ${fields.join(', ')}`, true);
+        }
+    } catch (error) {
+        showStatus(`${t('status.settingsError')}: ${error.message ?? error}`, true);
+    }
+}
+
+/** CSS-variabler som styres av hver egen farge */
+const CUSTOM_COLOR_VARS = {
+    background: ['--bg-primary', '--panel-bg'],
+    text: ['--text-primary'],
+    link: ['--link-color'],
+    accent: ['--accent-color', '--accent-bg'],
+};
+
+/**
+ * Legger brukerens egne farger over temaet
+ * @param {Object|null} colors - Farger som #rrggbb; felter som mangler bruker temaet
+ */
+function applyCustomColors(colors) {
+    const style = document.documentElement.style;
+    for (const [key, vars] of Object.entries(CUSTOM_COLOR_VARS)) {
+        const color = colors && colors[key];
+        vars.forEach((cssVar) => {
+            if (color) {
+                style.setProperty(cssVar, color);
+            } else {
+                style.removeProperty(cssVar);
+            }
+        });
+    }
+}
+
+/**
+ * Viser gjeldende farger i fargevelgerne
+ *
+ * Farger som ikke er satt vises med temaets verdi.
+ */
+function updateColorInputs() {
+    const computed = getComputedStyle(document.documentElement);
+    elements.settingColors.forEach((input) => {
+        const value = computed.getPropertyValue(input.dataset.cssVar).trim();
+        if (/^#[0-9a-f]{6}$/i.test(value)) {
+            input.value = value;
+        } else if (/^#[0-9a-f]{3}$/i.test(value)) {
+            input.value = '#' + [...value.slice(1)].map((c) => c + c).join('');
+        }
+    });
+}
+
+/**
+ * Fjerner egne farger og går tilbake til temaets farger
+ */
+async function resetColors() {
+    try {
+        const newSettings = await invoke('reset_colors');
+        setSettings(newSettings);
+        applySettings();
+    } catch (error) {
+        showStatus(`${t('status.settingsError')}: ${error}`, true);
+    }
+}
+
+/**
+ * Slår privat surfing av eller på for resten av økten
+ * @param {boolean} enabled - Om privat modus skal være på
+ */
+async function setPrivateMode(enabled) {
+    try {
+        const newSettings = await invoke('set_private_mode', { enabled });
+        setSettings(newSettings);
+        applySettings();
+        showStatus(t(enabled ? 'status.privateModeOn' : 'status.privateModeOff'));
+    } catch (error) {
+        showStatus(`${t('status.settingsError')}: ${error}`, true);
+    }
+}
+
+/**
+ * Sletter historikk, tilbake og frem i vinduene, sidebuffer, kjente
+ * Gemini-verter, nylige filer og innstillinger per nettsted etter
+ * bekreftelse. Bokmerker og andre innstillinger beholdes.
+ */
+async function clearBrowsingData() {
+    if (!confirm(t('settings.clearBrowsingDataConfirm'))) {
+        return;
+    }
+    const results = await invoke('clear_browsing_data', {
+        options: {
+            history: true,
+            session_state: true,
+            page_cache: true,
+            known_hosts: true,
+            recent_files: true,
+            site_overrides: true,
+        },
+    });
+    updateNavigationButtons();
+    updateRecentFiles();
+    const failed = results.filter(result => result.error);
+    if (failed.length > 0) {
+        showStatus(failed.map(result => result.error).join('; '), true);
+    } else {
+        showStatus(t('status.browsingDataCleared'));
+    }
+}
+
+/**
+ * Tilbakestiller en gruppe innstillinger etter bekreftelse
+ * @param {string} scope - "all", "appearance", "network", "privacy" eller "site-overrides"
+ */
+async function resetSettings(scope) {
+    if (!confirm(t('settings.resetConfirm'))) {
+        return;
+    }
+    try {
+        const newSettings = await invoke('reset_settings', { scope, keepOnboarding: true });
         setSettings(newSettings);
         applySettings();
+        updateSettingsPanel();
+        await applySiteSettings();
     } catch (error) {
         showStatus(`${t('status.settingsError')}: ${error}`, true);
     }
@@ -120,39 +387,39 @@ async function toggleTheme() {
 }
 
 /**
- * Zoomer inn
+ * Zoomer inn; zoomen huskes for nettstedet som vises
  */
 async function zoomIn() {
     try {
-        const newSettings = await invoke('zoom_in');
+        const newSettings = await invoke('zoom_in', { url: getState().currentUrl });
         setSettings(newSettings);
-        applySettings();
+        await applySiteSettings();
     } catch (error) {
         showStatus(`${t('status.zoomInError')}: ${error}`, true);
     }
 }
 
 /**
- * Zoomer ut
+ * Zoomer ut; zoomen huskes for nettstedet som vises
  */
 async function zoomOut() {
     try {
-        const newSettings = await invoke('zoom_out');
+        const newSettings = await invoke('zoom_out', { url: getState().currentUrl });
         setSettings(newSettings);
-        applySettings();
+        await applySiteSettings();
     } catch (error) {
         showStatus(`${t('status.zoomOutError')}: ${error}`, true);
     }
 }
 
 /**
- * Tilbakestiller zoom til 100%
+ * Tilbakestiller zoom; sidens egen zoom fjernes før den globale
  */
 async function zoomReset() {
     try {
-        const newSettings = await invoke('zoom_reset');
+        const newSettings = await invoke('zoom_reset', { url: getState().currentUrl });
         setSettings(newSettings);
-        applySettings();
+        await applySiteSettings();
     } catch (error) {
         showStatus(`${t('status.zoomResetError')}: ${error}`, true);
     }
@@ -162,7 +429,24 @@ async function zoomReset() {
  * Toggles innstillingspanelet og håndterer state
  */
 function toggleSettingsPanel() {
-    toggleSettingsPanelUI();
+    if (toggleSettingsPanelUI()) {
+        loadSystemFonts();
+    }
+}
+
+/**
+ * Fyller forslagslisten for egen skrift med installerte skrifter (én gang)
+ */
+async function loadSystemFonts() {
+    if (!elements.systemFonts || elements.systemFonts.childElementCount > 0) return;
+    try {
+        const fonts = await invoke('list_system_fonts');
+        elements.systemFonts.innerHTML = fonts
+            .map(name => `<option value="${escapeHtml(name)}">`)
+            .join('');
+    } catch (error) {
+        console.error(t('status.settingsError') + ':', error);
+    }
 }
 
 // ===== Onboarding =====
@@ -5,10 +5,6 @@
  */
 
 const state = {
-    // Navigasjonshistorikk
-    history: [],
-    historyIndex: -1,
-    
     // Nåværende lokasjon
     currentPath: null,
     currentUrl: null,
@@ -17,6 +13,12 @@ const state = {
     // Brukerinnstillinger
     settings: null,
     
+    // Innstillinger for nettstedet som vises (med overstyringer)
+    siteSettings: null,
+    
+    // Tastatursnarveier fra backend ({ action, accelerator, is_default })
+    keyBindings: [],
+    
     // Søk
     searchMatches: [],
     currentMatchIndex: -1,
@@ -83,82 +85,43 @@ function setSettings(settings) {
 }
 
 /**
- * Henter brukerinnstillinger
- * @returns {Object|null} Innstillinger
- */
-function getSettings() {
-    return state.settings;
-}
-
-// ===== History Management =====
-
-/**
- * Legger til en sti i historikken
- * @param {string} path - Sti å legge til
- */
-function addToHistory(path) {
-    // Fjern fremtidig historikk hvis vi har navigert tilbake
-    if (state.historyIndex < state.history.length - 1) {
-        state.history = state.history.slice(0, state.historyIndex + 1);
-    }
-    
-    state.history.push(path);
-    state.historyIndex = state.history.length - 1;
-    state.currentPath = path;
-    
-    // Begrens historikk-størrelse
-    if (state.history.length > MAX_HISTORY_SIZE) {
-        state.history.shift();
-        state.historyIndex--;
-    }
-}
-
-/**
- * Kan vi gå tilbake i historikken?
- * @returns {boolean}
+ * Setter innstillingene for nettstedet som vises
+ * @param {Object|null} siteSettings - Effektive innstillinger, eller null for globale
  */
-function canGoBack() {
-    return state.historyIndex > 0;
+function setSiteSettings(siteSettings) {
+    state.siteSettings = siteSettings;
 }
 
 /**
- * Kan vi gå fremover i historikken?
- * @returns {boolean}
+ * Henter innstillingene for nettstedet som vises
+ * @returns {Object|null} Effektive innstillinger
  */
-function canGoForward() {
-    return state.historyIndex < state.history.length - 1;
+function getSiteSettings() {
+    return state.siteSettings;
 }
 
 /**
- * Gå tilbake i historikken
- * @returns {string|null} Forrige sti eller null
+ * Setter tastatursnarveiene
+ * @param {Array} keyBindings - Snarveier fra backend
  */
-function historyBack() {
-    if (canGoBack()) {
-        state.historyIndex--;
-        return state.history[state.historyIndex];
-    }
-    return null;
+function setKeyBindings(keyBindings) {
+    state.keyBindings = keyBindings;
 }
 
 /**
- * Gå fremover i historikken
- * @returns {string|null} Neste sti eller null
+ * Henter tastatursnarveiene
+ * @returns {Array} Snarveier
  */
-function historyForward() {
-    if (canGoForward()) {
-        state.historyIndex++;
-        return state.history[state.historyIndex];
-    }
-    return null;
+function getKeyBindings() {
+    return state.keyBindings;
 }
 
 /**
- * Henter historikk-indeks
- * @returns {number}
+ * Henter brukerinnstillinger
+ * @returns {Object|null} Innstillinger
  */
-function getHistoryIndex() {
-    return state.historyIndex;
+function getSettings() {
+    return state.settings;
 }
 
 // ===== Search State =====
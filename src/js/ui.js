@@ -66,6 +66,18 @@ const LOADING_STEP_PROGRESS = {
     'stopped': 0,
 };
 
+/** Steg fra page-load-progress mapping til progress-prosent */
+const LOAD_STAGE_PROGRESS = {
+    'resolving': 15,
+    'connecting': 25,
+    'tls_handshake': 35,
+    'transferring': 60,
+    'converting': 75,
+    'rendering': 90,
+    'done': 100,
+    'error': 0,
+};
+
 /**
  * Parser en loading-status melding og gir tilbake progress-steg
  * @param {string} msg - Status-melding fra backend
@@ -128,6 +140,31 @@ function updateLoadingStatus(message) {
     }
 }
 
+/**
+ * Oppdaterer footer fra en page-load-progress hendelse
+ * @param {{stage: string, bytes: ?number, total: ?number, host: string}} progress - Steget fra backend
+ */
+function updateLoadProgress(progress) {
+    const { stage, bytes, total, host } = progress;
+    elements.footerStatus.textContent = t(`progress.${stage}`, { host, bytes });
+
+    let target = LOAD_STAGE_PROGRESS[stage] ?? 50;
+    // Med kjent størrelse fylles overføringen gradvis
+    if (stage === 'transferring' && bytes != null && total) {
+        const start = LOAD_STAGE_PROGRESS.tls_handshake;
+        target = start + (LOAD_STAGE_PROGRESS.transferring - start) * Math.min(1, bytes / total);
+    }
+    progressTarget = target;
+    animateProgress();
+
+    if (stage === 'done') {
+        clearTimeout(footerResetTimeout);
+        footerResetTimeout = setTimeout(() => {
+            stopFooterLoading();
+        }, 1500);
+    }
+}
+
 /**
  * Animerer progress-bar mot target
  */
@@ -166,12 +203,20 @@ function stopFooterLoading() {
 }
 
 /**
- * Initialiserer lytting på loading-status events fra Tauri-backend
+ * Initialiserer lytting på fremdrift fra Tauri-backend
+ *
+ * Backend sender status bare til vinduet som startet hentingen. Nettsider
+ * sender page-load-progress; loading-status med ferdig tekst brukes ellers
+ * (lokale filer, venting på brukeren) og forsvinner for nettsider senere.
  */
 async function initLoadingStatusListener() {
-    await listen('loading-status', (event) => {
+    const currentWindow = window.__TAURI__.webviewWindow.getCurrentWebviewWindow();
+    await currentWindow.listen('loading-status', (event) => {
         updateLoadingStatus(event.payload);
     });
+    await currentWindow.listen('page-load-progress', (event) => {
+        updateLoadProgress(event.payload);
+    });
 }
 
 // ===== Content States =====
@@ -185,9 +230,17 @@ function showLoading() {
 
 /**
  * Viser en feilmelding i content-området
- * @param {string} message - Feilmelding
+ *
+ * Feil fra hentinger har ofte en ferdig feilside fra backend, som vises i
+ * stedet for den enkle meldingen.
+ * @param {string|CommandError} message - Feilmelding
  */
 function showError(message) {
+    if (message?.page) {
+        elements.content.innerHTML = `<div class="markdown-body">${message.page.html}</div>`;
+        showStatus(message.message, true);
+        return;
+    }
     elements.content.innerHTML = `
         <div class="markdown-body">
             <h1>${t('error.title')}</h1>
@@ -202,31 +255,79 @@ function showError(message) {
  * Rendrer innhold i content-området
  * @param {string} html - HTML-innhold
  * @param {string|null} title - Sidetittel
+ * @param {{total: number, done: number}|undefined} tasks - Oppgavestatus
  */
-function renderContent(html, title) {
+function renderContent(html, title, tasks, security = null) {
     elements.content.innerHTML = `<div class="markdown-body">${html}</div>`;
     
     setCurrentTitle(title);
+    updateSecurityIndicator(security);
     
+    const progress = tasks ? ` (${tasks.done}/${tasks.total})` : '';
     if (title) {
-        document.title = `${title} - Bare`;
+        document.title = `${title}${progress} - Bare`;
     } else {
-        document.title = 'Bare';
+        document.title = `Bare${progress}`;
     }
 }
 
+// ===== Brødsmulesti =====
+
+/**
+ * Viser brødsmulestien for siden som vises
+ * @param {string|null} path - Nåværende sti/URL
+ */
+async function updateBreadcrumbs(path) {
+    let crumbs = [];
+    if (path && path !== HOME_PATH) {
+        try {
+            crumbs = await invoke('get_breadcrumbs', { url: path });
+        } catch (error) {
+            console.error('Kunne ikke hente brødsmulesti:', error);
+        }
+    }
+    
+    elements.breadcrumbs.replaceChildren();
+    // Bare verten gir ingen sti å vise
+    elements.breadcrumbs.classList.toggle('hidden', crumbs.length < 2);
+    crumbs.forEach((crumb, index) => {
+        if (index > 0) {
+            const separator = document.createElement('span');
+            separator.className = 'breadcrumb-separator';
+            separator.textContent = '›';
+            elements.breadcrumbs.appendChild(separator);
+        }
+        const button = document.createElement('button');
+        button.className = 'breadcrumb';
+        button.textContent = crumb.label;
+        button.title = crumb.url;
+        if (index === crumbs.length - 1) {
+            button.setAttribute('aria-current', 'page');
+        } else {
+            button.addEventListener('click', () => {
+                elements.urlBar.value = crumb.url;
+                handleUrlSubmit();
+            });
+        }
+        elements.breadcrumbs.appendChild(button);
+    });
+}
+
 // ===== Footer =====
 
 /**
  * Oppdaterer footer-informasjon
  * @param {string} path - Nåværende sti/URL
  * @param {boolean} wasConverted - Om innholdet ble konvertert
+ * @param {boolean} usedReadability - Om leservisningen hentet ut hovedinnholdet
  */
-function updateFooter(path, wasConverted = false) {
+function updateFooter(path, wasConverted = false, usedReadability = false) {
+    updateBreadcrumbs(path);
     if (path && path !== HOME_PATH) {
         const filename = path.split(/[\\/]/).pop();
         const conversionIndicator = wasConverted ? ` (${t('footer.converted')})` : '';
-        elements.footerInfo.textContent = `Bare ${getAppVersion()} — ${filename}${conversionIndicator}`;
+        const readerIndicator = usedReadability ? ` · ${t('footer.readerMode')}` : '';
+        elements.footerInfo.textContent = `Bare ${getAppVersion()} — ${filename}${conversionIndicator}${readerIndicator}`;
     } else {
         elements.footerInfo.textContent = `Bare ${getAppVersion()}`;
     }
@@ -255,10 +356,20 @@ function updateZoomDisplay(zoomLevel) {
 
 /**
  * Oppdaterer navigasjonsknappenes tilstand
+ *
+ * Navigeringsstabelen holdes av backend, per vindu.
  */
-function updateNavigationButtons() {
-    elements.btnBack.disabled = !canGoBack();
-    elements.btnForward.disabled = !canGoForward();
+async function updateNavigationButtons() {
+    try {
+        const [back, forward] = await Promise.all([
+            invokeCommand('can_go_back'),
+            invokeCommand('can_go_forward'),
+        ]);
+        elements.btnBack.disabled = !back;
+        elements.btnForward.disabled = !forward;
+    } catch (error) {
+        console.error('Kunne ikke hente navigeringstilstanden:', error);
+    }
 }
 
 // ===== Panel Management =====
@@ -316,6 +427,9 @@ function closeSettingsPanel() {
 function toggleDropdownMenu() {
     const isVisible = !elements.dropdownMenu.classList.contains('hidden');
     elements.dropdownMenu.classList.toggle('hidden', isVisible);
+    if (!isVisible) {
+        updateRecentFiles();
+    }
 }
 
 /**
@@ -354,6 +468,70 @@ function closeAboutDialog() {
     elements.aboutOverlay.classList.add('hidden');
 }
 
+// ===== Sikkerhet for siden =====
+
+/**
+ * Viser hvor sikker forbindelsen til siden var, i adresselinjen
+ * @param {Object|null} security - SecurityInfo fra backend, null for lokale sider
+ */
+function updateSecurityIndicator(security) {
+    let level = 'local';
+    let icon = '→';
+    if (security?.downgraded || (security && !security.encrypted)) {
+        level = 'insecure';
+        icon = '⚠';
+    } else if (security) {
+        level = 'secure';
+        icon = '🔒';
+    }
+    elements.btnSecurity.dataset.security = level;
+    elements.btnSecurity.textContent = icon;
+}
+
+/**
+ * Viser detaljene om forbindelsen til siden som vises
+ */
+async function showSecurityDialog() {
+    let security = null;
+    if (state.currentUrl) {
+        try {
+            security = await invoke('get_page_security', { url: state.currentUrl });
+        } catch (error) {
+            console.error('Kunne ikke hente sikkerhetsinformasjon:', error);
+        }
+    }
+
+    const rows = security ? [
+        ['security.protocol', security.protocol.toUpperCase()],
+        ['security.encryption', t(security.encrypted ? 'security.encrypted' : 'security.notEncrypted')],
+        ['security.tlsVersion', security.tls_version],
+        ['security.cipher', security.cipher_suite],
+        ['security.certificate', t(`security.cert.${security.certificate}`)],
+        ['security.fingerprint', security.fingerprint],
+    ] : [['security.protocol', t('security.local')]];
+
+    elements.securityDetails.replaceChildren();
+    rows.forEach(([label, value]) => {
+        if (security && !security.encrypted && value == null) {
+            return;
+        }
+        const term = document.createElement('dt');
+        term.textContent = t(label);
+        const detail = document.createElement('dd');
+        detail.textContent = value ?? t('security.unknown');
+        elements.securityDetails.append(term, detail);
+    });
+    elements.securityWarning.classList.toggle('hidden', !security?.downgraded);
+    elements.securityOverlay.classList.remove('hidden');
+}
+
+/**
+ * Lukker sikkerhetsdialogen
+ */
+function closeSecurityDialog() {
+    elements.securityOverlay.classList.add('hidden');
+}
+
 // ===== Gemini Input Dialog =====
 
 /** Lagrer nåværende Gemini input URL */
@@ -378,6 +556,43 @@ function showGeminiInputDialog(prompt, url, sensitive = false) {
     setTimeout(() => elements.geminiInputField.focus(), 100);
 }
 
+/**
+ * Lar brukeren velge mellom https:// og gemini:// for en adresse
+ * @param {string[]} candidates - Adressene med https:// og gemini://
+ * @returns {Promise<string|null>} Valgt adresse, eller null hvis dialogen lukkes
+ */
+function chooseScheme(candidates) {
+    const [https, gemini] = candidates;
+    elements.schemeChoicePrompt.textContent = t('schemeChoice.prompt', { address: https.replace(/^https:\/\//, '') });
+    elements.schemeChoiceRemember.checked = false;
+    elements.schemeChoiceOverlay.classList.remove('hidden');
+    setTimeout(() => elements.btnSchemeHttps.focus(), 100);
+    
+    return new Promise((resolve) => {
+        const finish = async (url) => {
+            elements.schemeChoiceOverlay.classList.add('hidden');
+            elements.btnSchemeHttps.onclick = null;
+            elements.btnSchemeGemini.onclick = null;
+            elements.btnCloseSchemeChoice.onclick = null;
+            if (url && elements.schemeChoiceRemember.checked) {
+                try {
+                    const parsed = new URL(url);
+                    await invoke('remember_address_scheme', {
+                        host: parsed.hostname,
+                        scheme: parsed.protocol.replace(':', ''),
+                    });
+                } catch (error) {
+                    showStatus(`${t('status.settingsError')}: ${error}`, true);
+                }
+            }
+            resolve(url);
+        };
+        elements.btnSchemeHttps.onclick = () => finish(https);
+        elements.btnSchemeGemini.onclick = () => finish(gemini);
+        elements.btnCloseSchemeChoice.onclick = () => finish(null);
+    });
+}
+
 /**
  * Lukker Gemini input-dialog
  */